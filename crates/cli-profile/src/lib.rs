@@ -0,0 +1,185 @@
+//! Named RPC profiles.
+//!
+//! Profiles live in `~/.config/doublezero/cli.toml` (or `$XDG_CONFIG_HOME/doublezero/cli.toml`):
+//!
+//! ```toml
+//! [profiles.mainnet-ops]
+//! url = "https://my-rpc.example.com"
+//! ws_url = "wss://my-rpc.example.com"
+//! commitment = "confirmed"
+//! with_compute_unit_price = 5000
+//! dz_ledger_url = "https://doublezero-ledger.example.com"
+//! ```
+//!
+//! Selecting a profile with `--profile mainnet-ops` fills in the matching connection and payer
+//! options for the invoked command. Options given explicitly on the command line always win,
+//! and profile values are only applied to commands that accept the corresponding option.
+
+use std::{collections::BTreeMap, ffi::OsString, fs, path::PathBuf};
+
+use anyhow::{Context, Result, bail};
+use clap::{Arg, Command};
+use serde::Deserialize;
+
+pub const PROFILE_ARG: &str = "profile";
+
+#[derive(Debug, Default, Deserialize)]
+pub struct CliConfig {
+    #[serde(default)]
+    pub profiles: BTreeMap<String, RpcProfile>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RpcProfile {
+    /// Solana JSON RPC URL or moniker.
+    pub url: Option<String>,
+
+    /// Solana WebSocket URL.
+    pub ws_url: Option<String>,
+
+    /// Commitment level.
+    pub commitment: Option<String>,
+
+    /// Default compute unit price (micro-lamports) for transactions.
+    pub with_compute_unit_price: Option<u64>,
+
+    /// DoubleZero Ledger JSON RPC URL.
+    pub dz_ledger_url: Option<String>,
+}
+
+impl RpcProfile {
+    /// Profile values keyed by the long option names they may fill in, most specific first.
+    pub fn option_values(&self) -> Vec<(&'static [&'static str], String)> {
+        [
+            (&["url"][..], self.url.clone()),
+            (&["ws", "ws-url"][..], self.ws_url.clone()),
+            (&["commitment"][..], self.commitment.clone()),
+            (
+                &["with-compute-unit-price"][..],
+                self.with_compute_unit_price.map(|price| price.to_string()),
+            ),
+            (&["dz-ledger-url"][..], self.dz_ledger_url.clone()),
+        ]
+        .into_iter()
+        .filter_map(|(names, value)| value.map(|value| (names, value)))
+        .collect()
+    }
+}
+
+/// `$XDG_CONFIG_HOME/doublezero`, falling back to `~/.config/doublezero`.
+pub fn config_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|dir| dir.join("doublezero"))
+}
+
+pub fn config_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("cli.toml"))
+}
+
+pub fn load_profile(name: &str) -> Result<RpcProfile> {
+    let path = config_path().context("Cannot locate config directory for RPC profiles")?;
+    let contents =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let config = toml::from_str::<CliConfig>(&contents)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+
+    match config.profiles.get(name) {
+        Some(profile) => Ok(profile.clone()),
+        None => bail!(
+            "Profile {name} not found in {}. Available: {}",
+            path.display(),
+            config
+                .profiles
+                .keys()
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// Expand `--profile NAME` into the options the selected profile provides.
+///
+/// Returns the arguments unchanged when no profile is selected.
+pub fn apply_profile(command: &Command, args: Vec<OsString>) -> Result<Vec<OsString>> {
+    let Some(name) = find_profile_name(&args) else {
+        return Ok(args);
+    };
+    let profile = load_profile(&name)?;
+
+    let (subcommand, _) = resolve_subcommand(command, &args);
+    let mut expanded = args;
+
+    for (names, value) in profile.option_values() {
+        let Some(arg) = subcommand
+            .get_arguments()
+            .find(|arg| arg.get_long().is_some_and(|long| names.contains(&long)))
+        else {
+            continue;
+        };
+        if !is_option_set(&expanded, arg) {
+            expanded.push(format!("--{}", arg.get_long().unwrap()).into());
+            expanded.push(value.into());
+        }
+    }
+
+    Ok(expanded)
+}
+
+/// Whether `arg` was given on the command line, by its long or short name.
+pub fn is_option_set(args: &[OsString], arg: &Arg) -> bool {
+    args.iter().any(|token| {
+        let token = token.to_string_lossy();
+        arg.get_long().is_some_and(|long| {
+            token == format!("--{long}") || token.starts_with(&format!("--{long}="))
+        }) || arg
+            .get_short()
+            .is_some_and(|short| token.starts_with(&format!("-{short}")))
+    })
+}
+
+pub fn find_profile_name(args: &[OsString]) -> Option<String> {
+    let flag = format!("--{PROFILE_ARG}");
+    let mut tokens = args.iter().map(|token| token.to_string_lossy());
+
+    while let Some(token) = tokens.next() {
+        if token == flag {
+            return tokens.next().map(|name| name.into_owned());
+        }
+        if let Some(name) = token.strip_prefix(&format!("{flag}=")) {
+            return Some(name.to_string());
+        }
+    }
+
+    None
+}
+
+/// Find the deepest subcommand named on the command line, along with the names of the
+/// subcommands leading to it.
+pub fn resolve_subcommand<'a>(
+    command: &'a Command,
+    args: &[OsString],
+) -> (&'a Command, Vec<String>) {
+    let mut current = command;
+    let mut path = Vec::new();
+
+    for token in args.iter().skip(1) {
+        let token = token.to_string_lossy();
+        if token.starts_with('-') {
+            continue;
+        }
+        match current.find_subcommand(token.as_ref()) {
+            Some(subcommand) => {
+                current = subcommand;
+                path.push(subcommand.get_name().to_string());
+            }
+            None if current.has_subcommands() => continue,
+            None => break,
+        }
+    }
+
+    (current, path)
+}
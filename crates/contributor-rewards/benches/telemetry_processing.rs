@@ -0,0 +1,137 @@
+//! Telemetry processing on a synthetic epoch, single-threaded versus the default thread pool
+//!
+//! Run with `cargo bench --bench telemetry_processing`. Sizes default to a large mainnet epoch
+//! and can be overridden with BENCH_CIRCUITS, BENCH_ROUTES and BENCH_SAMPLES.
+
+use anyhow::Result;
+use doublezero_contributor_rewards::{
+    ingestor::types::{DZDeviceLatencySamples, DZInternetLatencySamples},
+    processor::process::{DeviceExclusions, process_device_samples, process_internet_samples},
+};
+use solana_sdk::pubkey::Pubkey;
+use std::time::{Duration, Instant};
+
+const SAMPLING_INTERVAL_US: u64 = 10_000_000;
+const START_US: u64 = 1_700_000_000_000_000;
+const ITERATIONS: u32 = 3;
+
+fn env_or(name: &str, default: usize) -> usize {
+    std::env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+/// Deterministic RTTs around `base_us`, with roughly 1% of samples lost
+fn synthetic_rtts(seed: u64, base_us: u32, count: usize) -> Vec<u32> {
+    let mut state = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+    (0..count)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let noise = (state >> 33) as u32;
+            if noise % 100 == 0 {
+                0
+            } else {
+                base_us + noise % (base_us / 4 + 1)
+            }
+        })
+        .collect()
+}
+
+fn device_samples(circuits: usize, samples: usize) -> Vec<DZDeviceLatencySamples> {
+    (0..circuits)
+        .map(|circuit| DZDeviceLatencySamples {
+            pubkey: Pubkey::new_unique(),
+            epoch: 1,
+            origin_device_pk: Pubkey::new_unique(),
+            target_device_pk: Pubkey::new_unique(),
+            link_pk: Pubkey::new_unique(),
+            origin_device_location_pk: Pubkey::new_unique(),
+            target_device_location_pk: Pubkey::new_unique(),
+            origin_device_agent_pk: Pubkey::new_unique(),
+            sampling_interval_us: SAMPLING_INTERVAL_US,
+            start_timestamp_us: START_US,
+            samples: synthetic_rtts(circuit as u64, 20_000 + circuit as u32 * 100, samples),
+            sample_count: samples as u32,
+        })
+        .collect()
+}
+
+fn internet_samples(routes: usize, samples: usize) -> Vec<DZInternetLatencySamples> {
+    (0..routes)
+        .map(|route| DZInternetLatencySamples {
+            pubkey: Pubkey::new_unique(),
+            epoch: 1,
+            data_provider_name: if route % 2 == 0 {
+                "ripeatlas"
+            } else {
+                "wheresitup"
+            }
+            .to_string(),
+            oracle_agent_pk: Pubkey::new_unique(),
+            origin_exchange_pk: Pubkey::new_unique(),
+            target_exchange_pk: Pubkey::new_unique(),
+            sampling_interval_us: SAMPLING_INTERVAL_US,
+            start_timestamp_us: START_US,
+            samples: synthetic_rtts(route as u64 + 1_000, 40_000 + route as u32 * 100, samples),
+            sample_count: samples as u32,
+        })
+        .collect()
+}
+
+/// Best of `ITERATIONS` runs of `run` on a pool of `threads` threads (0 for rayon's default)
+fn time<T>(threads: usize, run: impl Fn() -> Result<T> + Send + Sync) -> Result<(Duration, T)>
+where
+    T: Send,
+{
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()?;
+    let mut best = Duration::MAX;
+    let mut output = None;
+    for _ in 0..ITERATIONS {
+        let start = Instant::now();
+        let result = pool.install(&run)?;
+        best = best.min(start.elapsed());
+        output = Some(result);
+    }
+    Ok((best, output.expect("at least one iteration")))
+}
+
+fn report(name: &str, sequential: Duration, parallel: Duration) {
+    println!(
+        "{name:<10} 1 thread: {:>9.1?}  {} threads: {:>9.1?}  speedup: {:.2}x",
+        sequential,
+        rayon::current_num_threads(),
+        parallel,
+        sequential.as_secs_f64() / parallel.as_secs_f64()
+    );
+}
+
+fn main() -> Result<()> {
+    let circuits = env_or("BENCH_CIRCUITS", 400);
+    let routes = env_or("BENCH_ROUTES", 600);
+    let samples = env_or("BENCH_SAMPLES", 17_280);
+    let end_us = START_US + samples as u64 * SAMPLING_INTERVAL_US;
+
+    println!("{circuits} circuits and {routes} routes of {samples} samples each");
+
+    let device = device_samples(circuits, samples);
+    let exclusions = DeviceExclusions::new();
+    let run_device = || process_device_samples(&device, START_US, end_us, &exclusions);
+    let (sequential, expected) = time(1, run_device)?;
+    let (parallel, actual) = time(0, run_device)?;
+    assert_eq!(format!("{expected:?}"), format!("{actual:?}"));
+    report("device", sequential, parallel);
+
+    let internet = internet_samples(routes, samples);
+    let run_internet = || process_internet_samples(&internet, START_US, end_us);
+    let (sequential, expected) = time(1, run_internet)?;
+    let (parallel, actual) = time(0, run_internet)?;
+    assert_eq!(format!("{expected:?}"), format!("{actual:?}"));
+    report("internet", sequential, parallel);
+
+    Ok(())
+}
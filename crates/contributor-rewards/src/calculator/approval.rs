@@ -0,0 +1,204 @@
+//! Governance approval of the parameters rewards are computed with
+//!
+//! Governance posts an [`ApprovedParameters`] record for each epoch from the configured approval
+//! authority. Record addresses are derived from the key that created them, so a record at the
+//! authority's address can only have been signed by that authority. Before publishing, the
+//! Shapley settings and reward pools about to be used are compared against it.
+
+use crate::{
+    calculator::recorder::compute_record_address,
+    ingestor::fetcher::Fetcher,
+    settings::{RewardPoolSettings, Settings, ShapleySettings},
+};
+use anyhow::{Context, Result, bail};
+use borsh::{BorshDeserialize, BorshSerialize};
+use doublezero_record::state::RecordData;
+use serde::{Deserialize, Serialize};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use std::mem::size_of;
+use tracing::{info, warn};
+
+/// Reward parameters governance approved for an epoch
+#[derive(Debug, Clone, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+pub struct ApprovedParameters {
+    pub epoch: u64,
+    pub shapley_settings: ShapleySettings,
+    pub reward_pools: Vec<RewardPoolSettings>,
+}
+
+impl ApprovedParameters {
+    /// Differences between these parameters and the ones about to be used, one line each
+    pub fn diff(&self, shapley: &ShapleySettings, pools: &[RewardPoolSettings]) -> Vec<String> {
+        let mut differences = Vec::new();
+
+        let approved = &self.shapley_settings;
+        for (name, approved, actual) in [
+            (
+                "operator_uptime",
+                approved.operator_uptime,
+                shapley.operator_uptime,
+            ),
+            (
+                "contiguity_bonus",
+                approved.contiguity_bonus,
+                shapley.contiguity_bonus,
+            ),
+            (
+                "demand_multiplier",
+                approved.demand_multiplier,
+                shapley.demand_multiplier,
+            ),
+        ] {
+            if approved != actual {
+                differences.push(format!(
+                    "shapley.{name}: approved {approved}, configured {actual}"
+                ));
+            }
+        }
+
+        for pool in &self.reward_pools {
+            match pools.iter().find(|actual| actual.name == pool.name) {
+                None => {
+                    differences.push(format!("pool {}: approved but not configured", pool.name))
+                }
+                Some(actual) if actual != pool => differences.push(format!(
+                    "pool {}: approved {} ({:?}), configured {} ({:?})",
+                    pool.name, pool.share, pool.allocation, actual.share, actual.allocation
+                )),
+                Some(_) => {}
+            }
+        }
+        for pool in pools {
+            if !self
+                .reward_pools
+                .iter()
+                .any(|approved| approved.name == pool.name)
+            {
+                differences.push(format!("pool {}: configured but not approved", pool.name));
+            }
+        }
+
+        differences
+    }
+}
+
+/// Check the configured parameters against governance's approval for `epoch`
+///
+/// Returns the approval record's address, or `None` when no approval authority is configured.
+/// A missing or mismatched approval is an error unless `force` is set, in which case it is
+/// logged and the run continues without an approval.
+pub async fn check_approved_parameters(
+    settings: &Settings,
+    fetcher: &Fetcher,
+    epoch: u64,
+    force: bool,
+) -> Result<Option<Pubkey>> {
+    let Some(authority) = &settings.governance.approval_authority else {
+        return Ok(None);
+    };
+    let authority: Pubkey = authority
+        .parse()
+        .context("Invalid governance approval authority")?;
+
+    let prefix = settings.governance.approved_parameters_prefix.as_bytes();
+    let epoch_bytes = epoch.to_le_bytes();
+    let record_key = compute_record_address(&authority, &[prefix, &epoch_bytes])?;
+
+    let differences = match fetcher
+        .get_dz_account(&record_key, CommitmentConfig::confirmed())
+        .await?
+    {
+        None => vec![format!("no approved parameters record at {record_key}")],
+        Some(account) => {
+            let approved: ApprovedParameters =
+                ApprovedParameters::deserialize(&mut &account.data[size_of::<RecordData>()..])
+                    .with_context(|| {
+                        format!("Failed to deserialize approved parameters at {record_key}")
+                    })?;
+            if approved.epoch != epoch {
+                bail!(
+                    "Approved parameters at {record_key} are for epoch {}, expected {epoch}",
+                    approved.epoch
+                );
+            }
+            approved.diff(&settings.shapley, &settings.pools)
+        }
+    };
+
+    if differences.is_empty() {
+        info!("Reward parameters match governance approval {record_key}");
+        return Ok(Some(record_key));
+    }
+
+    metrics::counter!("doublezero_contributor_rewards_unapproved_parameters").increment(1);
+    let report = differences.join("\n  - ");
+    if !force {
+        bail!(
+            "Reward parameters for epoch {epoch} are not approved by governance:\n  - {report}\n\
+             Re-run with --force-unapproved to calculate anyway"
+        );
+    }
+
+    warn!("Proceeding with unapproved reward parameters for epoch {epoch}:\n  - {report}");
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::AllocationRule;
+
+    fn shapley() -> ShapleySettings {
+        ShapleySettings {
+            operator_uptime: 0.98,
+            contiguity_bonus: 5.0,
+            demand_multiplier: 1.2,
+        }
+    }
+
+    fn pool(name: &str, share: f64) -> RewardPoolSettings {
+        RewardPoolSettings {
+            name: name.to_string(),
+            share,
+            allocation: AllocationRule::Shapley,
+        }
+    }
+
+    fn approved() -> ApprovedParameters {
+        ApprovedParameters {
+            epoch: 100,
+            shapley_settings: shapley(),
+            reward_pools: vec![pool("base", 0.7), pool("performance", 0.3)],
+        }
+    }
+
+    #[test]
+    fn test_matching_parameters() {
+        let pools = vec![pool("performance", 0.3), pool("base", 0.7)];
+        assert!(approved().diff(&shapley(), &pools).is_empty());
+    }
+
+    #[test]
+    fn test_shapley_mismatch() {
+        let mut configured = shapley();
+        configured.demand_multiplier = 1.5;
+
+        let differences = approved().diff(&configured, &approved().reward_pools);
+        assert_eq!(differences.len(), 1);
+        assert!(differences[0].starts_with("shapley.demand_multiplier"));
+    }
+
+    #[test]
+    fn test_pool_mismatches() {
+        let pools = vec![pool("base", 0.6), pool("bonus", 0.4)];
+        let differences = approved().diff(&shapley(), &pools);
+        assert_eq!(
+            differences,
+            vec![
+                "pool base: approved 0.7 (Shapley), configured 0.6 (Shapley)".to_string(),
+                "pool performance: approved but not configured".to_string(),
+                "pool bonus: configured but not approved".to_string(),
+            ]
+        );
+    }
+}
@@ -1,4 +1,4 @@
-// slots in epoch
+// slots in a mainnet epoch, used when a leader schedule carries no epoch parameters
 pub const SLOTS_IN_EPOCH: f64 = 432000.0;
 
 // bits/sec to Gbps
@@ -1,5 +1,6 @@
 use crate::{
     calculator::{
+        eligibility,
         input::ShapleyInputs,
         shapley_handler::{
             PreviousEpochCache, build_demands, build_devices, build_private_links,
@@ -7,23 +8,42 @@ use crate::{
         },
         util::{calculate_city_weights, print_devices, print_private_links, print_public_links},
     },
-    ingestor::{demand::CityStats, fetcher::Fetcher, internet, types::FetchData},
+    cli::snapshot::CompleteSnapshot,
+    ingestor::{
+        demand::{self, CityStats},
+        epoch::{LeaderSchedule, print_epoch_params},
+        fetcher::Fetcher,
+        internet,
+        types::FetchData,
+        validation::{self, DataValidationReport},
+    },
     processor::{
         internet::{InternetTelemetryProcessor, InternetTelemetryStatMap, print_internet_stats},
         telemetry::{DZDTelemetryProcessor, DZDTelemetryStatMap, print_telemetry_stats},
     },
     settings::Settings,
 };
-use anyhow::Result;
+use anyhow::{Result, bail};
 use network_shapley::types::{Demand, Devices, PrivateLinks, PublicLinks};
 use std::collections::BTreeSet;
+use tabled::{Table, settings::Style};
 use tracing::{info, warn};
 
+/// Where the leader schedule used to build demands comes from
+enum DemandSource<'a> {
+    /// Fetch the leader schedule over RPC
+    Fetcher(&'a Fetcher),
+    /// Use an already known leader schedule (e.g. from a snapshot)
+    LeaderSchedule(&'a LeaderSchedule),
+}
+
 pub struct PreparedData {
     pub epoch: u64,
     pub device_telemetry: DZDTelemetryStatMap,
     pub internet_telemetry: InternetTelemetryStatMap,
     pub shapley_inputs: Option<ShapleyInputs>,
+    /// Consistency issues found in the data the telemetry and inputs were prepared from
+    pub validation: DataValidationReport,
 }
 
 impl PreparedData {
@@ -81,11 +101,85 @@ impl PreparedData {
             fetch_data.dz_internet = internet_data;
         };
 
+        check_internet_agents(&fetcher.settings, &mut fetch_data)?;
+
+        Self::assemble(
+            &fetcher.settings,
+            fetch_epoch,
+            &fetch_data,
+            &previous_epoch_cache,
+            DemandSource::Fetcher(fetcher),
+            require_shapley,
+        )
+        .await
+    }
+
+    /// Prepares all data needed for reward calculations from a snapshot instead of the chain.
+    /// # Args
+    ///
+    /// * `settings` - Settings used for processing
+    /// * `snapshot` - Snapshot exported via `snapshot all`
+    /// * `reprocess` - Re-decode the snapshot's embedded raw accounts (v2 only)
+    /// * `require_shapley` - Attach shapley_inputs output if set to true
+    ///
+    /// # Returns
+    /// Result<PreparedData>
+    pub async fn from_snapshot(
+        settings: &Settings,
+        snapshot: &CompleteSnapshot,
+        reprocess: bool,
+        require_shapley: bool,
+    ) -> Result<Self> {
+        let mut fetch_data = snapshot.fetch_data(reprocess)?;
+        check_internet_agents(settings, &mut fetch_data)?;
+        let Some(leader_schedule) = &snapshot.leader_schedule else {
+            bail!(
+                "Snapshot for epoch {} has no leader schedule",
+                snapshot.dz_epoch
+            )
+        };
+
+        if let Some(params) = &snapshot.epoch_params {
+            info!(
+                "Snapshot Epoch Parameters:\n{}",
+                print_epoch_params(&[("dz", &params.dz), ("solana", &params.solana)])
+            );
+        }
+
+        // NOTE: Previous epoch defaults and internet lookback need chain access, so a snapshot
+        // is processed with its own epoch's data only
+        warn!(
+            "Preparing data from snapshot for epoch {}; previous epoch lookup and internet lookback are not applied",
+            snapshot.dz_epoch
+        );
+
+        Self::assemble(
+            settings,
+            snapshot.dz_epoch,
+            &fetch_data,
+            &PreviousEpochCache::new(),
+            DemandSource::LeaderSchedule(leader_schedule),
+            require_shapley,
+        )
+        .await
+    }
+
+    async fn assemble(
+        settings: &Settings,
+        fetch_epoch: u64,
+        fetch_data: &FetchData,
+        previous_epoch_cache: &PreviousEpochCache,
+        demand_source: DemandSource<'_>,
+        require_shapley: bool,
+    ) -> Result<Self> {
+        let validation = validation::validate_fetch_data(fetch_data);
+        validation.log();
+
         // Process device telemetry
-        let device_telemetry = process_device_telemetry(&fetch_data)?;
+        let device_telemetry = process_device_telemetry(settings, fetch_data)?;
 
         // Process internet telemetry
-        let internet_telemetry = process_internet_telemetry(&fetch_data)?;
+        let internet_telemetry = process_internet_telemetry(fetch_data)?;
 
         if !require_shapley {
             return Ok(Self {
@@ -93,34 +187,39 @@ impl PreparedData {
                 device_telemetry,
                 internet_telemetry,
                 shapley_inputs: None,
+                validation,
             });
         }
 
         // Build devices
-        let devices = build_and_log_devices(&fetch_data)?;
+        let devices = build_and_log_devices(fetch_data)?;
 
         // Build private links
         let private_links = build_and_log_private_links(
-            &fetcher.settings,
-            &fetch_data,
+            settings,
+            fetch_data,
             &device_telemetry,
-            &previous_epoch_cache,
+            previous_epoch_cache,
         );
 
         // Build public links
         let public_links = build_and_log_public_links(
-            &fetcher.settings,
+            settings,
             &internet_telemetry,
-            &fetch_data,
-            &previous_epoch_cache,
+            fetch_data,
+            previous_epoch_cache,
         )?;
 
         // Build demands and city stats
-        let (demands, city_stats) = build_and_log_demands(fetcher, &fetch_data).await?;
+        let (demands, city_stats) =
+            build_and_log_demands(settings, demand_source, fetch_data).await?;
 
         // Calculate city weights once for consistency
         let city_weights = calculate_city_weights(&city_stats);
 
+        // Operators without an activated contributor are left out of the distribution
+        let exclusions = eligibility::find_exclusions(fetch_data, &settings.eligibility.overrides);
+
         // Create ShapleyInputs as single source of truth
         let shapley_inputs = ShapleyInputs {
             devices,
@@ -129,6 +228,7 @@ impl PreparedData {
             demands,
             city_stats,
             city_weights,
+            exclusions,
         };
 
         // Record overall Shapley inputs
@@ -166,13 +266,20 @@ impl PreparedData {
             device_telemetry,
             internet_telemetry,
             shapley_inputs: Some(shapley_inputs),
+            validation,
         })
     }
 }
 
 /// Process and aggregate device telemetry
-fn process_device_telemetry(fetch_data: &FetchData) -> Result<DZDTelemetryStatMap> {
-    let stat_map = DZDTelemetryProcessor::process(fetch_data)?;
+fn process_device_telemetry(
+    settings: &Settings,
+    fetch_data: &FetchData,
+) -> Result<DZDTelemetryStatMap> {
+    let stat_map = DZDTelemetryProcessor::process_with_maintenance(
+        fetch_data,
+        &settings.telemetry_defaults.maintenance_windows,
+    )?;
     info!(
         "Device Telemetry Aggregates: \n{}",
         print_telemetry_stats(&stat_map)
@@ -180,6 +287,16 @@ fn process_device_telemetry(fetch_data: &FetchData) -> Result<DZDTelemetryStatMa
     Ok(stat_map)
 }
 
+/// Drop or flag internet samples from unapproved agents and log each agent's contribution
+fn check_internet_agents(settings: &Settings, fetch_data: &mut FetchData) -> Result<()> {
+    let contributions = internet::apply_agent_allowlist(settings, &mut fetch_data.dz_internet)?;
+    info!(
+        "Internet Telemetry Agents: \n{}",
+        Table::new(contributions).with(Style::psql().remove_horizontals())
+    );
+    Ok(())
+}
+
 /// Process and aggregate internet telemetry
 fn process_internet_telemetry(fetch_data: &FetchData) -> Result<InternetTelemetryStatMap> {
     let stat_map = InternetTelemetryProcessor::process(fetch_data)?;
@@ -228,10 +345,17 @@ fn build_and_log_public_links(
 
 /// Build demands and city stats with logging
 async fn build_and_log_demands(
-    fetcher: &Fetcher,
+    settings: &Settings,
+    demand_source: DemandSource<'_>,
     fetch_data: &FetchData,
 ) -> Result<(Vec<Demand>, CityStats)> {
-    build_demands(fetcher, fetch_data).await
+    match demand_source {
+        DemandSource::Fetcher(fetcher) => build_demands(fetcher, fetch_data).await,
+        DemandSource::LeaderSchedule(leader_schedule) => {
+            let result = demand::build_with_schedule(settings, fetch_data, leader_schedule)?;
+            Ok((result.demands, result.city_stats))
+        }
+    }
 }
 
 /// Calculate expected number of internet telemetry links
@@ -0,0 +1,87 @@
+use crate::{
+    calculator::constants::MAX_UNIT_SHARE,
+    units::{Lamports, format_fixed_point},
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use doublezero_program_common::serializer;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+/// Token that rewards are denominated in, resolved from settings
+///
+/// Reward shares are unit-less proportions on-chain; the denomination is what turns a
+/// distribution total into integer token amounts, so it is recorded with the reward input.
+#[derive(Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+pub struct RewardDenomination {
+    #[serde(
+        serialize_with = "serializer::serialize_pubkey_as_string",
+        deserialize_with = "serializer::deserialize_pubkey_from_string"
+    )]
+    pub mint: Pubkey,
+    pub decimals: u8,
+    /// Tokens per SOL, when SOL-denominated amounts need converting
+    pub sol_conversion_rate: Option<f64>,
+}
+
+impl RewardDenomination {
+    /// Convert a whole-token amount to base units, rounding to the nearest unit
+    pub fn to_base_units(&self, amount: f64) -> u64 {
+        (amount * 10_f64.powi(self.decimals as i32)).round() as u64
+    }
+
+    /// Format base units as a whole-token amount with full precision
+    pub fn format_amount(&self, base_units: u64) -> String {
+        format_fixed_point(base_units, self.decimals)
+    }
+
+    /// Amount of a distribution total (in base units) owed for a unit share
+    pub fn reward_amount(&self, total_base_units: u64, unit_share: u32) -> u64 {
+        (total_base_units as u128 * unit_share as u128 / MAX_UNIT_SHARE as u128) as u64
+    }
+
+    /// Convert lamports to base units using the configured conversion rate
+    pub fn lamports_to_base_units(&self, lamports: u64) -> Option<u64> {
+        self.sol_conversion_rate
+            .map(|rate| self.to_base_units(Lamports(lamports).as_sol() * rate))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn denomination(decimals: u8) -> RewardDenomination {
+        RewardDenomination {
+            mint: Pubkey::new_unique(),
+            decimals,
+            sol_conversion_rate: Some(2_500.0),
+        }
+    }
+
+    #[test]
+    fn test_amounts_follow_decimals() {
+        let eight = denomination(8);
+        let six = denomination(6);
+
+        assert_eq!(eight.to_base_units(1.5), 150_000_000);
+        assert_eq!(six.to_base_units(1.5), 1_500_000);
+        assert_eq!(eight.format_amount(150_000_000), "1.50000000");
+        assert_eq!(six.format_amount(1_500_000), "1.500000");
+        assert_eq!(denomination(0).format_amount(42), "42");
+    }
+
+    #[test]
+    fn test_reward_amount_and_conversion() {
+        let denomination = denomination(6);
+
+        // A quarter share of 1,000 tokens
+        let total = denomination.to_base_units(1_000.0);
+        assert_eq!(denomination.reward_amount(total, 250_000_000), 250_000_000);
+
+        // 0.1 SOL at 2,500 tokens per SOL
+        assert_eq!(
+            denomination.lamports_to_base_units(100_000_000),
+            Some(250_000_000)
+        );
+    }
+}
@@ -0,0 +1,168 @@
+//! Dry-run artifacts
+//!
+//! A dry run writes every record payload it would have published to a directory instead of the
+//! ledger, byte for byte and named by the record address it would have been written to. Two
+//! dry runs, or a dry run and the records of a later real run, can then be compared directly.
+
+use crate::{
+    calculator::{epoch_provenance::content_address, recorder::compute_record_address},
+    run_id::RunId,
+    settings::Settings,
+};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tracing::info;
+
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// A payload the dry run would have written
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DryRunRecord {
+    pub description: String,
+    pub address: String,
+    pub bytes: usize,
+    /// SHA-256 of the payload
+    pub content_address: String,
+}
+
+/// Index of the payloads in a dry-run directory
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DryRunManifest {
+    pub epoch: u64,
+    pub rewards_accountant: String,
+    /// Run that produced the payloads (missing in older manifests)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<RunId>,
+    pub records: Vec<DryRunRecord>,
+}
+
+/// Collects the payloads of a dry run into `dry-run-epoch-<epoch>/`
+#[derive(Debug)]
+pub struct DryRunArtifacts {
+    dir: PathBuf,
+    rewards_accountant: Pubkey,
+    manifest: DryRunManifest,
+}
+
+impl DryRunArtifacts {
+    /// Location of the dry-run artifacts for an epoch, next to the scheduler state file
+    pub fn dir(settings: &Settings, epoch: u64) -> PathBuf {
+        Path::new(&settings.scheduler.state_file)
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join(format!("dry-run-epoch-{epoch}"))
+    }
+
+    /// Start a fresh set of artifacts in `dir`, replacing those of a previous dry run
+    pub fn create(dir: PathBuf, epoch: u64, rewards_accountant: Pubkey) -> Result<Self> {
+        if dir.exists() {
+            fs::remove_dir_all(&dir)
+                .with_context(|| format!("Failed to clear dry-run directory {dir:?}"))?;
+        }
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create dry-run directory {dir:?}"))?;
+
+        Ok(Self {
+            dir,
+            rewards_accountant,
+            manifest: DryRunManifest {
+                epoch,
+                rewards_accountant: rewards_accountant.to_string(),
+                run_id: None,
+                records: Vec::new(),
+            },
+        })
+    }
+
+    /// Record the run producing the payloads in the manifest
+    pub fn with_run_id(mut self, run_id: RunId) -> Self {
+        self.manifest.run_id = Some(run_id);
+        self
+    }
+
+    /// Write the payload the record at `seeds` would have held to `<address>.bin`
+    pub fn write(&mut self, seeds: &[&[u8]], payload: &[u8], description: &str) -> Result<Pubkey> {
+        let address = compute_record_address(&self.rewards_accountant, seeds)?;
+        let path = self.dir.join(format!("{address}.bin"));
+        fs::write(&path, payload)
+            .with_context(|| format!("Failed to write dry-run {description} to {path:?}"))?;
+
+        info!(
+            "  - {description}: {} bytes -> {}",
+            payload.len(),
+            path.display()
+        );
+        self.manifest.records.push(DryRunRecord {
+            description: description.to_string(),
+            address: address.to_string(),
+            bytes: payload.len(),
+            content_address: content_address(payload).to_string(),
+        });
+
+        Ok(address)
+    }
+
+    /// Write the manifest and return the directory holding the artifacts
+    pub fn finish(self) -> Result<PathBuf> {
+        let path = self.dir.join(MANIFEST_FILE);
+        fs::write(&path, serde_json::to_string_pretty(&self.manifest)?)
+            .with_context(|| format!("Failed to write dry-run manifest to {path:?}"))?;
+        info!(
+            "DRY-RUN: Wrote {} record payloads to {}",
+            self.manifest.records.len(),
+            self.dir.display()
+        );
+
+        Ok(self.dir)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payloads_are_named_by_record_address() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("dry-run-epoch-7");
+        let accountant = Pubkey::new_unique();
+        let seeds: &[&[u8]] = &[b"dz_device_telemetry", &7u64.to_le_bytes()];
+
+        let run_id = RunId::generate();
+        let mut artifacts = DryRunArtifacts::create(dir.clone(), 7, accountant)
+            .unwrap()
+            .with_run_id(run_id);
+        let address = artifacts
+            .write(seeds, b"payload", "device telemetry aggregates")
+            .unwrap();
+        assert_eq!(address, compute_record_address(&accountant, seeds).unwrap());
+        artifacts.finish().unwrap();
+
+        assert_eq!(
+            fs::read(dir.join(format!("{address}.bin"))).unwrap(),
+            b"payload"
+        );
+        let manifest: DryRunManifest =
+            serde_json::from_slice(&fs::read(dir.join(MANIFEST_FILE)).unwrap()).unwrap();
+        assert_eq!(manifest.epoch, 7);
+        assert_eq!(manifest.run_id, Some(run_id));
+        assert_eq!(manifest.records.len(), 1);
+        assert_eq!(manifest.records[0].address, address.to_string());
+        assert_eq!(
+            manifest.records[0].content_address,
+            content_address(b"payload").to_string()
+        );
+
+        // A new dry run replaces the artifacts of the previous one
+        DryRunArtifacts::create(dir.clone(), 7, accountant)
+            .unwrap()
+            .finish()
+            .unwrap();
+        assert!(!dir.join(format!("{address}.bin")).exists());
+    }
+}
@@ -0,0 +1,196 @@
+use crate::ingestor::types::FetchData;
+use borsh::{BorshDeserialize, BorshSerialize};
+use doublezero_serviceability::state::contributor::ContributorStatus;
+use network_shapley::shapley::{ShapleyOutput, ShapleyValue};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tracing::{info, warn};
+
+/// An operator left out of the reward distribution, and why
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+pub struct OperatorExclusion {
+    /// Operator pubkey (contributor owner)
+    pub operator: String,
+    pub reason: String,
+}
+
+/// A contributor with devices in the network
+#[derive(Debug, Clone)]
+struct ContributorState {
+    code: String,
+    status: String,
+    activated: bool,
+}
+
+/// Find operators that are not eligible for rewards
+///
+/// Operators are identified by contributor owner, the same ID devices are given in the
+/// Shapley inputs. An operator is eligible while at least one of its contributors with
+/// devices is activated; suspended or otherwise inactive operators are excluded unless
+/// listed in `overrides`.
+pub fn find_exclusions(fetch_data: &FetchData, overrides: &[String]) -> Vec<OperatorExclusion> {
+    let mut operators: BTreeMap<String, BTreeMap<String, ContributorState>> = BTreeMap::new();
+
+    for device in fetch_data.dz_serviceability.devices.values() {
+        let Some(contributor) = fetch_data
+            .dz_serviceability
+            .contributors
+            .get(&device.contributor_pk)
+        else {
+            continue;
+        };
+        operators
+            .entry(contributor.owner.to_string())
+            .or_default()
+            .insert(
+                device.contributor_pk.to_string(),
+                ContributorState {
+                    code: contributor.code.clone(),
+                    status: format!("{:?}", contributor.status),
+                    activated: contributor.status == ContributorStatus::Activated,
+                },
+            );
+    }
+
+    exclusions_from(operators, overrides)
+}
+
+fn exclusions_from(
+    operators: BTreeMap<String, BTreeMap<String, ContributorState>>,
+    overrides: &[String],
+) -> Vec<OperatorExclusion> {
+    let mut exclusions = Vec::new();
+
+    for (operator, contributors) in operators {
+        if contributors
+            .values()
+            .any(|contributor| contributor.activated)
+        {
+            continue;
+        }
+
+        let reason = format!(
+            "no activated contributor ({})",
+            contributors
+                .values()
+                .map(|contributor| format!("{}: {}", contributor.code, contributor.status))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+
+        if overrides.contains(&operator) {
+            info!("Operator {operator} kept eligible by override despite {reason}");
+            continue;
+        }
+
+        warn!("Excluding operator {operator} from rewards: {reason}");
+        exclusions.push(OperatorExclusion { operator, reason });
+    }
+
+    metrics::gauge!("doublezero_contributor_rewards_operators_excluded")
+        .set(exclusions.len() as f64);
+
+    exclusions
+}
+
+/// Drop excluded operators and spread their share across the remaining ones
+pub fn exclude_operators(
+    shapley_output: ShapleyOutput,
+    exclusions: &[OperatorExclusion],
+) -> ShapleyOutput {
+    let retained: Vec<(String, f64)> = shapley_output
+        .into_iter()
+        .filter(|(operator, _)| {
+            !exclusions
+                .iter()
+                .any(|exclusion| exclusion.operator == *operator)
+        })
+        .map(|(operator, val)| (operator, val.value))
+        .collect();
+    let total: f64 = retained.iter().map(|(_, value)| value).sum();
+
+    retained
+        .into_iter()
+        .map(|(operator, value)| {
+            let proportion = if total != 0.0 { value / total } else { 0.0 };
+            (operator, ShapleyValue { value, proportion })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contributor(code: &str, status: &str) -> (String, ContributorState) {
+        (
+            format!("{code}-pk"),
+            ContributorState {
+                code: code.to_string(),
+                status: status.to_string(),
+                activated: status == "Activated",
+            },
+        )
+    }
+
+    #[test]
+    fn test_exclusions() {
+        let operators = BTreeMap::from([
+            (
+                "OperatorA".to_string(),
+                BTreeMap::from([contributor("a1", "Activated")]),
+            ),
+            (
+                // One activated contributor keeps the operator eligible
+                "OperatorB".to_string(),
+                BTreeMap::from([
+                    contributor("b1", "Suspended"),
+                    contributor("b2", "Activated"),
+                ]),
+            ),
+            (
+                "OperatorC".to_string(),
+                BTreeMap::from([contributor("c1", "Suspended")]),
+            ),
+            (
+                "OperatorD".to_string(),
+                BTreeMap::from([contributor("d1", "Suspended")]),
+            ),
+        ]);
+
+        let exclusions = exclusions_from(operators, &["OperatorD".to_string()]);
+        assert_eq!(
+            exclusions,
+            vec![OperatorExclusion {
+                operator: "OperatorC".to_string(),
+                reason: "no activated contributor (c1: Suspended)".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_exclude_operators() {
+        let shapley_output: ShapleyOutput = [("OperatorA", 30.0), ("OperatorB", 10.0)]
+            .into_iter()
+            .map(|(operator, value)| {
+                (
+                    operator.to_string(),
+                    ShapleyValue {
+                        value,
+                        proportion: value / 40.0,
+                    },
+                )
+            })
+            .collect();
+        let exclusions = vec![OperatorExclusion {
+            operator: "OperatorA".to_string(),
+            reason: "suspended".to_string(),
+        }];
+
+        let output = exclude_operators(shapley_output, &exclusions);
+        assert_eq!(output.len(), 1);
+        let remaining = output.get("OperatorB").unwrap();
+        assert_eq!(remaining.value, 10.0);
+        assert_eq!(remaining.proportion, 1.0);
+    }
+}
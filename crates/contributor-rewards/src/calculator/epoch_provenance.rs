@@ -0,0 +1,173 @@
+//! Per-epoch provenance record
+//!
+//! Published to the DZ ledger next to the reward records, so anyone can tell which software,
+//! configuration and artifacts produced an epoch's rewards without access to the operator's
+//! exports. Artifacts are identified by the SHA-256 of their serialized bytes, which for the
+//! ledger records is the same payload written to the ledger.
+
+use crate::{run_id::RunId, settings::Settings};
+use anyhow::Result;
+use borsh::{BorshDeserialize, BorshSerialize};
+use chrono::Utc;
+use solana_sdk::hash::{Hash, hashv};
+use std::io::{self, Read};
+
+/// Seed suffix of the provenance record, after the contributor rewards prefix and epoch
+pub const PROVENANCE_SEED: &[u8] = b"provenance";
+
+/// Content address of one artifact used or produced by the run
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct ArtifactHash {
+    pub name: String,
+    pub hash: Hash,
+}
+
+/// What produced the rewards published for an epoch
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize)]
+pub struct EpochProvenance {
+    pub epoch: u64,
+    /// Version of the contributor-rewards crate that ran the calculation
+    pub software_version: String,
+    /// Hash of the effective settings, serialized as JSON
+    pub config_hash: Hash,
+    /// Data sources read and records written by the run
+    pub artifacts: Vec<ArtifactHash>,
+    /// Content address of the snapshot file, when calculated from a snapshot
+    pub snapshot_content_address: Option<Hash>,
+    /// Unix timestamp of the run
+    pub run_timestamp: i64,
+    /// Run that published the record, `None` in records written before it was stored
+    pub run_id: Option<RunId>,
+    /// Seed recorded by the run, `None` in records written before it was stored
+    pub rng_seed: Option<u64>,
+}
+
+impl BorshDeserialize for EpochProvenance {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let epoch = u64::deserialize_reader(reader)?;
+        let software_version = String::deserialize_reader(reader)?;
+        let config_hash = Hash::deserialize_reader(reader)?;
+        let artifacts = Vec::<ArtifactHash>::deserialize_reader(reader)?;
+        let snapshot_content_address = Option::<Hash>::deserialize_reader(reader)?;
+        let run_timestamp = i64::deserialize_reader(reader)?;
+
+        // Older records end before the run id or, later, before the seed
+        let run_id = read_appended::<RunId, _>(reader)?;
+        let rng_seed = read_appended::<u64, _>(reader)?;
+
+        Ok(Self {
+            epoch,
+            software_version,
+            config_hash,
+            artifacts,
+            snapshot_content_address,
+            run_timestamp,
+            run_id,
+            rng_seed,
+        })
+    }
+}
+
+/// Read an optional field appended after records were first written, `None` if the record ends
+/// before it
+fn read_appended<T: BorshDeserialize, R: Read>(reader: &mut R) -> io::Result<Option<T>> {
+    let mut tag = [0u8; 1];
+    if reader.read(&mut tag)? == 0 {
+        return Ok(None);
+    }
+    Option::<T>::deserialize_reader(&mut (&tag[..]).chain(reader))
+}
+
+impl EpochProvenance {
+    pub fn new(
+        epoch: u64,
+        settings: &Settings,
+        artifacts: &[(&str, &[u8])],
+        snapshot_content_address: Option<Hash>,
+        run_id: RunId,
+        rng_seed: u64,
+    ) -> Result<Self> {
+        Ok(Self {
+            epoch,
+            software_version: env!("CARGO_PKG_VERSION").to_string(),
+            config_hash: content_address(&serde_json::to_vec(settings)?),
+            artifacts: artifacts
+                .iter()
+                .map(|(name, data)| ArtifactHash {
+                    name: name.to_string(),
+                    hash: content_address(data),
+                })
+                .collect(),
+            snapshot_content_address,
+            run_timestamp: Utc::now().timestamp(),
+            run_id: Some(run_id),
+            rng_seed: Some(rng_seed),
+        })
+    }
+
+    /// Hash recorded for the artifact called `name`
+    pub fn artifact(&self, name: &str) -> Option<&Hash> {
+        self.artifacts
+            .iter()
+            .find(|artifact| artifact.name == name)
+            .map(|artifact| &artifact.hash)
+    }
+}
+
+/// SHA-256 of `data`
+pub fn content_address(data: &[u8]) -> Hash {
+    hashv(&[data])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_artifacts_are_content_addressed() {
+        let provenance = EpochProvenance {
+            epoch: 42,
+            software_version: "0.1.0".to_string(),
+            config_hash: content_address(b"{}"),
+            artifacts: vec![
+                ArtifactHash {
+                    name: "reward_input".to_string(),
+                    hash: content_address(b"input"),
+                },
+                ArtifactHash {
+                    name: "shapley_output".to_string(),
+                    hash: content_address(b"output"),
+                },
+            ],
+            snapshot_content_address: Some(content_address(b"snapshot")),
+            run_timestamp: 1_700_000_000,
+            run_id: Some(RunId::generate()),
+            rng_seed: Some(42),
+        };
+
+        assert_eq!(
+            provenance.artifact("shapley_output"),
+            Some(&content_address(b"output"))
+        );
+        assert_ne!(content_address(b"input"), content_address(b"output"));
+        assert!(provenance.artifact("device_telemetry").is_none());
+
+        let decoded: EpochProvenance =
+            borsh::from_slice(&borsh::to_vec(&provenance).unwrap()).unwrap();
+        assert_eq!(decoded, provenance);
+
+        // Records written before the seed was stored end after the run id
+        let mut legacy = borsh::to_vec(&provenance).unwrap();
+        legacy.truncate(legacy.len() - 9);
+        let decoded: EpochProvenance = borsh::from_slice(&legacy).unwrap();
+        assert_eq!(decoded.rng_seed, None);
+        assert_eq!(decoded.run_id, provenance.run_id);
+
+        // Records written before the run id was stored end after the timestamp
+        legacy.truncate(legacy.len() - 17);
+        let decoded: EpochProvenance = borsh::from_slice(&legacy).unwrap();
+        assert_eq!(decoded.run_id, None);
+        assert_eq!(decoded.rng_seed, None);
+        assert_eq!(decoded.run_timestamp, provenance.run_timestamp);
+    }
+}
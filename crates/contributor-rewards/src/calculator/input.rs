@@ -1,10 +1,20 @@
-use crate::{ingestor::demand::CityStats, settings::ShapleySettings};
+use crate::{
+    calculator::{denomination::RewardDenomination, eligibility::OperatorExclusion},
+    ingestor::demand::CityStats,
+    processor::constants::JITTER_AGGREGATION_VERSION,
+    rng,
+    settings::{RewardPoolSettings, ShapleySettings},
+};
 use anyhow::{Result, bail};
 use borsh::{BorshDeserialize, BorshSerialize};
 use chrono::Utc;
 use network_shapley::types::{Demands, Devices, PrivateLinks, PublicLinks};
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use solana_sdk::pubkey::Pubkey;
+use std::{
+    collections::BTreeMap,
+    io::{self, Read},
+};
 use svm_hash::sha2::{Hash, double_hash};
 
 // Domain separation prefixes for telemetry checksums
@@ -12,6 +22,9 @@ const PREFIX_DEVICE_TELEMETRY: &str = "dz_input_device_telemetry";
 const PREFIX_INTERNET_TELEMETRY: &str = "dz_input_internet_telemetry";
 const CHECKSUM_SUFFIX: &[u8] = b"checksum";
 
+// Jitter aggregation used by records written before the version was stored
+const LEGACY_JITTER_AGGREGATION_VERSION: u32 = 1;
+
 /// Summary statistics for a city
 #[derive(Debug, Clone, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
 pub struct CitySummary {
@@ -29,11 +42,15 @@ pub struct ShapleyInputs {
     pub demands: Demands,
     pub city_stats: CityStats,
     pub city_weights: BTreeMap<String, f64>, // Pre-calculated weights for consistency
+    pub exclusions: Vec<OperatorExclusion>,
 }
 
 /// Complete input configuration for reward calculations
 /// Stored on-chain for transparency and verification
-#[derive(Debug, Clone, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+///
+/// Fields after the telemetry checksums were appended after records were first published;
+/// older records end before them and deserialize with the legacy values noted on each field.
+#[derive(Debug, Clone, BorshSerialize, Serialize, Deserialize)]
 pub struct RewardInput {
     // Metadata
     pub epoch: u64,
@@ -52,6 +69,76 @@ pub struct RewardInput {
     // Checksums for telemetry data verification
     pub device_telemetry_checksum: Hash,
     pub internet_telemetry_checksum: Hash,
+
+    // Version of the jitter aggregation applied to the telemetry above (1 in older records)
+    pub jitter_aggregation_version: u32,
+
+    // Token the rewards for this epoch are denominated in (`None` in older records)
+    pub denomination: Option<RewardDenomination>,
+
+    // Reward pools the epoch was split across (empty when allocated purely by Shapley value)
+    pub reward_pools: Vec<RewardPoolSettings>,
+
+    // Operators excluded from the distribution as ineligible
+    pub excluded_operators: Vec<OperatorExclusion>,
+
+    // Governance record the parameters above were checked against, if any
+    pub approved_parameters: Option<Pubkey>,
+
+    // Seed recorded for reproducibility (`None` in older records)
+    pub rng_seed: Option<u64>,
+}
+
+impl BorshDeserialize for RewardInput {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let epoch = u64::deserialize_reader(reader)?;
+        let timestamp = i64::deserialize_reader(reader)?;
+        let shapley_settings = ShapleySettings::deserialize_reader(reader)?;
+        let devices = Devices::deserialize_reader(reader)?;
+        let private_links = PrivateLinks::deserialize_reader(reader)?;
+        let public_links = PublicLinks::deserialize_reader(reader)?;
+        let demands = Demands::deserialize_reader(reader)?;
+        let city_summaries = BTreeMap::<String, CitySummary>::deserialize_reader(reader)?;
+        let device_telemetry_checksum = Hash::deserialize_reader(reader)?;
+        let internet_telemetry_checksum = Hash::deserialize_reader(reader)?;
+
+        // Older records end after the checksums, or after any of the fields appended since
+        let jitter_aggregation_version =
+            read_appended::<u32, _>(reader)?.unwrap_or(LEGACY_JITTER_AGGREGATION_VERSION);
+        let denomination = read_appended::<Option<RewardDenomination>, _>(reader)?.flatten();
+        let reward_pools = read_appended(reader)?.unwrap_or_default();
+        let excluded_operators = read_appended(reader)?.unwrap_or_default();
+        let approved_parameters = read_appended::<Option<Pubkey>, _>(reader)?.flatten();
+        let rng_seed = read_appended::<Option<u64>, _>(reader)?.flatten();
+
+        Ok(Self {
+            epoch,
+            timestamp,
+            shapley_settings,
+            devices,
+            private_links,
+            public_links,
+            demands,
+            city_summaries,
+            device_telemetry_checksum,
+            internet_telemetry_checksum,
+            jitter_aggregation_version,
+            denomination,
+            reward_pools,
+            excluded_operators,
+            approved_parameters,
+            rng_seed,
+        })
+    }
+}
+
+/// Read a field appended after records were first written, `None` if the record ends before it
+fn read_appended<T: BorshDeserialize, R: Read>(reader: &mut R) -> io::Result<Option<T>> {
+    let mut first = [0u8; 1];
+    if reader.read(&mut first)? == 0 {
+        return Ok(None);
+    }
+    T::deserialize_reader(&mut (&first[..]).chain(reader)).map(Some)
 }
 
 /// Helper function to compute epoch-specific checksum
@@ -64,6 +151,8 @@ impl RewardInput {
     pub fn new(
         epoch: u64,
         shapley_settings: ShapleySettings,
+        denomination: RewardDenomination,
+        reward_pools: Vec<RewardPoolSettings>,
         shapley_inputs: &ShapleyInputs,
         device_telemetry_data: &[u8],
         internet_telemetry_data: &[u8],
@@ -112,9 +201,27 @@ impl RewardInput {
                 PREFIX_INTERNET_TELEMETRY,
                 epoch,
             ),
+            jitter_aggregation_version: JITTER_AGGREGATION_VERSION,
+            denomination: Some(denomination),
+            reward_pools,
+            excluded_operators: shapley_inputs.exclusions.clone(),
+            approved_parameters: None,
+            rng_seed: Some(rng::default_seed(epoch)),
         }
     }
 
+    /// Record the governance approval the parameters were checked against
+    pub fn with_approved_parameters(mut self, approved_parameters: Option<Pubkey>) -> Self {
+        self.approved_parameters = approved_parameters;
+        self
+    }
+
+    /// Record the run's seed
+    pub fn with_rng_seed(mut self, rng_seed: u64) -> Self {
+        self.rng_seed = Some(rng_seed);
+        self
+    }
+
     /// Validate checksums against provided telemetry data
     pub fn validate_checksums(
         &self,
@@ -149,6 +256,12 @@ impl RewardInput {
              Public Links: {}\n\
              Demands: {}\n\
              Cities: {}\n\
+             Jitter Aggregation Version: {}\n\
+             Denomination: {}\n\
+             Reward Pools: {}\n\
+             Excluded Operators: {}\n\
+             Approved Parameters: {}\n\
+             RNG Seed: {}\n\
              Shapley Settings:\n\
              - Operator Uptime: {}\n\
              - Contiguity Bonus: {}\n\
@@ -160,6 +273,20 @@ impl RewardInput {
             self.public_links.len(),
             self.demands.len(),
             self.city_summaries.len(),
+            self.jitter_aggregation_version,
+            self.denomination.as_ref().map_or_else(
+                || "none".to_string(),
+                |denomination| format!(
+                    "{} ({} decimals)",
+                    denomination.mint, denomination.decimals
+                )
+            ),
+            self.reward_pools.len(),
+            self.excluded_operators.len(),
+            self.approved_parameters
+                .map_or_else(|| "none".to_string(), |record| record.to_string()),
+            self.rng_seed
+                .map_or_else(|| "none".to_string(), |seed| seed.to_string()),
             self.shapley_settings.operator_uptime,
             self.shapley_settings.contiguity_bonus,
             self.shapley_settings.demand_multiplier,
@@ -191,11 +318,20 @@ mod tests {
             demands,
             city_stats,
             city_weights,
+            exclusions: vec![],
+        };
+
+        let denomination = RewardDenomination {
+            mint: solana_sdk::pubkey::Pubkey::new_unique(),
+            decimals: 8,
+            sol_conversion_rate: None,
         };
 
         RewardInput::new(
             100,
             shapley_settings,
+            denomination,
+            vec![],
             &shapley_inputs,
             b"test_device_data",
             b"test_internet_data",
@@ -204,7 +340,7 @@ mod tests {
 
     #[test]
     fn test_serialization() {
-        let input = create_test_input();
+        let input = create_test_input().with_rng_seed(7);
 
         // Serialize
         let serialized = borsh::to_vec(&input).unwrap();
@@ -219,6 +355,59 @@ mod tests {
             input.shapley_settings.operator_uptime,
             deserialized.shapley_settings.operator_uptime
         );
+        assert_eq!(deserialized.rng_seed, Some(7));
+    }
+
+    #[test]
+    fn test_deserialize_legacy_layout() {
+        let input = create_test_input();
+        let serialized = borsh::to_vec(&input).unwrap();
+
+        // Records written before the appended fields end after the telemetry checksums
+        let appended_len = borsh::to_vec(&(
+            input.jitter_aggregation_version,
+            &input.denomination,
+            &input.reward_pools,
+            &input.excluded_operators,
+            input.approved_parameters,
+            input.rng_seed,
+        ))
+        .unwrap()
+        .len();
+        let legacy = &serialized[..serialized.len() - appended_len];
+
+        let deserialized: RewardInput = borsh::from_slice(legacy).unwrap();
+        assert_eq!(deserialized.epoch, input.epoch);
+        assert_eq!(
+            deserialized.internet_telemetry_checksum,
+            input.internet_telemetry_checksum
+        );
+        assert_eq!(
+            deserialized.jitter_aggregation_version,
+            LEGACY_JITTER_AGGREGATION_VERSION
+        );
+        assert_eq!(deserialized.denomination, None);
+        assert!(deserialized.reward_pools.is_empty());
+        assert!(deserialized.excluded_operators.is_empty());
+        assert_eq!(deserialized.approved_parameters, None);
+        assert_eq!(deserialized.rng_seed, None);
+        assert!(
+            deserialized
+                .validate_checksums(b"test_device_data", b"test_internet_data")
+                .is_ok()
+        );
+
+        // Records written part-way through the appended fields keep what they stored
+        let jitter_len = borsh::to_vec(&input.jitter_aggregation_version)
+            .unwrap()
+            .len();
+        let with_jitter = &serialized[..serialized.len() - appended_len + jitter_len];
+        let deserialized: RewardInput = borsh::from_slice(with_jitter).unwrap();
+        assert_eq!(
+            deserialized.jitter_aggregation_version,
+            JITTER_AGGREGATION_VERSION
+        );
+        assert_eq!(deserialized.denomination, None);
     }
 
     #[test]
@@ -253,5 +442,6 @@ mod tests {
         assert!(summary.contains("Epoch: 100"));
         assert!(summary.contains("Operator Uptime: 0.98"));
         assert!(summary.contains("Devices: 0"));
+        assert!(summary.contains(&format!("RNG Seed: {}", rng::default_seed(100))));
     }
 }
@@ -1,10 +1,13 @@
 use crate::{
     calculator::{
+        epoch_provenance::{EpochProvenance, PROVENANCE_SEED},
         input::RewardInput,
         keypair_loader::load_keypair,
         proof::{ShapleyOutputStorage, generate_proof_from_shapley},
-        recorder::{compute_record_address, write_serialized_to_ledger},
+        recorder::{RecordAction, compute_record_address, write_serialized_to_ledger},
+        sharding::ShardIndex,
     },
+    cli::presenter,
     ingestor::fetcher::Fetcher,
     processor::{
         internet::{InternetTelemetryStatMap, print_internet_stats},
@@ -12,11 +15,14 @@ use crate::{
     },
     settings::Settings,
 };
-use anyhow::{Result, anyhow, bail};
+use anyhow::{Context, Result, anyhow, bail};
 use backon::{ExponentialBuilder, Retryable};
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use chrono::DateTime;
 use doublezero_program_tools::zero_copy;
 use doublezero_record::{instruction as record_ix, state::RecordData};
 use doublezero_revenue_distribution::state::ProgramConfig;
+use serde::{Deserialize, Serialize};
 use solana_client::{
     client_error::ClientError as SolanaClientError, nonblocking::rpc_client::RpcClient,
 };
@@ -24,7 +30,12 @@ use solana_sdk::{
     commitment_config::CommitmentConfig, message::Message, pubkey::Pubkey, signature::Keypair,
     signer::Signer, transaction::Transaction,
 };
-use std::{fmt, fs, mem::size_of, path::PathBuf, time::Duration};
+use std::{
+    fmt, fs,
+    mem::size_of,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 use tabled::{Table, Tabled, settings::Style};
 use tracing::{debug, info, warn};
 
@@ -95,32 +106,240 @@ pub async fn validate_rewards_accountant_keypair(
     Ok(())
 }
 
+/// Check that the DZ ledger and Solana RPCs belong to the configured network before
+/// anything is published
+pub async fn ensure_target_network(settings: &Settings, fetcher: &Fetcher) -> Result<()> {
+    let dz_ledger_genesis_hash = fetcher.dz_rpc_client.get_genesis_hash().await?;
+    let solana_genesis_hash = fetcher.solana_write_client.get_genesis_hash().await?;
+
+    settings.network.ensure_target_clusters(
+        &dz_ledger_genesis_hash.to_string(),
+        &solana_genesis_hash.to_string(),
+    )?;
+
+    info!("Target clusters match network {}", settings.network);
+    Ok(())
+}
+
 /// Result of a write operation
 #[derive(Debug)]
 pub enum WriteResult {
     Success(String),
+    /// A record write, with what it did to the ledger
+    Recorded(String, RecordAction),
     Failed(String, String), // (description, error)
 }
 
+/// A record write that failed, with everything needed to re-attempt it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FailedWrite {
+    pub description: String,
+    /// Record seeds, base64-encoded
+    pub seeds: Vec<String>,
+    /// Serialized record payload, base64-encoded
+    pub payload: String,
+    pub error: String,
+}
+
+impl FailedWrite {
+    pub fn new(description: &str, seeds: &[&[u8]], payload: &[u8], error: String) -> Self {
+        Self {
+            description: description.to_string(),
+            seeds: seeds.iter().map(|seed| BASE64.encode(seed)).collect(),
+            payload: BASE64.encode(payload),
+            error,
+        }
+    }
+
+    pub fn seeds(&self) -> Result<Vec<Vec<u8>>> {
+        self.seeds
+            .iter()
+            .map(|seed| {
+                BASE64
+                    .decode(seed)
+                    .with_context(|| format!("Invalid seed for {}", self.description))
+            })
+            .collect()
+    }
+
+    pub fn payload(&self) -> Result<Vec<u8>> {
+        BASE64
+            .decode(&self.payload)
+            .with_context(|| format!("Invalid payload for {}", self.description))
+    }
+}
+
+/// Failed record writes for an epoch, persisted so they can be retried without
+/// rerunning the calculation
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FailedWrites {
+    pub epoch: u64,
+    pub writes: Vec<FailedWrite>,
+}
+
+impl FailedWrites {
+    /// Location of the failed writes file for an epoch, next to the scheduler state file
+    pub fn path(settings: &Settings, epoch: u64) -> PathBuf {
+        Path::new(&settings.scheduler.state_file)
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join(format!("failed-writes-epoch-{epoch}.json"))
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read failed writes from {path:?}"))?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse failed writes from {path:?}"))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write failed writes to {path:?}"))
+    }
+
+    /// Persist the remaining failures, or remove a stale file once everything succeeded
+    pub fn persist(settings: &Settings, epoch: u64, summary: &WriteSummary) -> Result<()> {
+        let path = Self::path(settings, epoch);
+        if summary.failed_writes.is_empty() {
+            if path.exists() {
+                fs::remove_file(&path)?;
+                info!("All record writes for epoch {epoch} succeeded, removed {path:?}");
+            }
+            return Ok(());
+        }
+
+        Self {
+            epoch,
+            writes: summary.failed_writes.clone(),
+        }
+        .save(&path)?;
+        warn!(
+            "Persisted {} failed record writes to {path:?}; retry with `rewards retry-writes --epoch {epoch}`",
+            summary.failed_writes.len()
+        );
+        Ok(())
+    }
+}
+
 /// Summary of all ledger writes
 #[derive(Debug, Default)]
 pub struct WriteSummary {
     pub results: Vec<WriteResult>,
+    /// Record writes that failed and can be re-attempted
+    pub failed_writes: Vec<FailedWrite>,
+    /// Findings about the published data that did not stop the run
+    pub warnings: Vec<String>,
 }
 
 impl WriteSummary {
+    /// Summary seeded with previously persisted failures, ready for a retry pass
+    pub fn from_failed(writes: Vec<FailedWrite>) -> Self {
+        Self {
+            results: writes
+                .iter()
+                .map(|write| WriteResult::Failed(write.description.clone(), write.error.clone()))
+                .collect(),
+            failed_writes: writes,
+            warnings: Vec::new(),
+        }
+    }
+
     pub fn add_success(&mut self, description: String) {
         self.results.push(WriteResult::Success(description));
     }
 
+    pub fn add_record(&mut self, description: String, action: RecordAction) {
+        self.results
+            .push(WriteResult::Recorded(description, action));
+    }
+
     pub fn add_failure(&mut self, description: String, error: String) {
         self.results.push(WriteResult::Failed(description, error));
     }
 
+    pub fn add_warning(&mut self, warning: String) {
+        self.warnings.push(warning);
+    }
+
+    pub fn add_record_failure(&mut self, write: FailedWrite) {
+        self.add_failure(write.description.clone(), write.error.clone());
+        self.failed_writes.push(write);
+    }
+
+    /// Re-attempt every failed record write once, updating results in place
+    pub async fn retry_failed(
+        &mut self,
+        rpc_client: &RpcClient,
+        payer_signer: &Keypair,
+        overwrite: bool,
+        rps_limit: u32,
+    ) {
+        for mut write in std::mem::take(&mut self.failed_writes) {
+            let attempt = async {
+                let seeds = write.seeds()?;
+                let seeds: Vec<&[u8]> = seeds.iter().map(Vec::as_slice).collect();
+                write_serialized_to_ledger(
+                    rpc_client,
+                    payer_signer,
+                    &seeds,
+                    &write.payload()?,
+                    &write.description,
+                    overwrite,
+                    rps_limit,
+                )
+                .await
+            };
+
+            let action = match attempt.await {
+                Ok((_, action)) => {
+                    info!(
+                        "[OK] Retried write of {} succeeded ({action})",
+                        write.description
+                    );
+                    Some(action)
+                }
+                Err(e) => {
+                    warn!(
+                        "[FAILED] Retried write of {} failed: {}",
+                        write.description, e
+                    );
+                    write.error = e.to_string();
+                    None
+                }
+            };
+
+            if let Some(result) = self
+                .results
+                .iter_mut()
+                .find(|r| matches!(r, WriteResult::Failed(desc, _) if *desc == write.description))
+            {
+                *result = match action {
+                    Some(action) => WriteResult::Recorded(write.description.clone(), action),
+                    None => WriteResult::Failed(write.description.clone(), write.error.clone()),
+                };
+            }
+            if action.is_none() {
+                self.failed_writes.push(write);
+            }
+        }
+    }
+
     pub fn successful_count(&self) -> usize {
         self.results
             .iter()
-            .filter(|r| matches!(r, WriteResult::Success(_)))
+            .filter(|r| matches!(r, WriteResult::Success(_) | WriteResult::Recorded(_, _)))
+            .count()
+    }
+
+    /// Number of record writes that took `action`
+    pub fn record_count(&self, action: RecordAction) -> usize {
+        self.results
+            .iter()
+            .filter(|r| matches!(r, WriteResult::Recorded(_, a) if *a == action))
             .count()
     }
 
@@ -150,6 +369,20 @@ impl fmt::Display for WriteSummary {
             self.successful_count(),
             self.total_count()
         )?;
+        writeln!(
+            f,
+            "Records: {} created, {} rewritten, {} unchanged",
+            self.record_count(RecordAction::Created),
+            self.record_count(RecordAction::Rewritten),
+            self.record_count(RecordAction::Unchanged)
+        )?;
+
+        if !self.warnings.is_empty() {
+            writeln!(f, " Warnings:")?;
+            for warning in &self.warnings {
+                writeln!(f, "  [WARN] {warning}")?;
+            }
+        }
 
         if !self.all_successful() {
             writeln!(f, " Failed writes:")?;
@@ -163,6 +396,7 @@ impl fmt::Display for WriteSummary {
         for result in &self.results {
             match result {
                 WriteResult::Success(desc) => writeln!(f, "  [OK] {desc}")?,
+                WriteResult::Recorded(desc, action) => writeln!(f, "  [OK] {desc} ({action})")?,
                 WriteResult::Failed(desc, _) => writeln!(f, "  [FAILED] {desc}")?,
             }
         }
@@ -179,6 +413,7 @@ pub async fn write_serialized_and_track(
     serialized: &[u8],
     description: &str,
     summary: &mut WriteSummary,
+    overwrite: bool,
     rps_limit: u32,
 ) {
     match write_serialized_to_ledger(
@@ -187,23 +422,74 @@ pub async fn write_serialized_and_track(
         seeds,
         serialized,
         description,
+        overwrite,
         rps_limit,
     )
     .await
     {
-        Ok(_) => {
-            info!("[OK] Successfully wrote {}", description);
-            summary.add_success(description.to_string());
+        Ok((_, action)) => {
+            info!("[OK] {} {}", description, action);
+            summary.add_record(description.to_string(), action);
         }
         Err(e) => {
             warn!("[FAILED] Failed to write {}: {}", description, e);
-            summary.add_failure(description.to_string(), e.to_string());
+            summary.add_record_failure(FailedWrite::new(
+                description,
+                seeds,
+                serialized,
+                e.to_string(),
+            ));
         }
     }
 }
 
 // ========== READ OPERATIONS ==========
 
+/// Payload of the record at `seeds`, reassembled from its shards if it was too large for one
+/// account
+async fn read_record_payload(
+    fetcher: &Fetcher,
+    rewards_accountant: &Pubkey,
+    seeds: &[&[u8]],
+) -> Result<Option<Vec<u8>>> {
+    let record_key = compute_record_address(rewards_accountant, seeds)?;
+    let Some(account) = fetcher
+        .get_dz_account(&record_key, CommitmentConfig::confirmed())
+        .await?
+    else {
+        return Ok(None);
+    };
+    let payload = &account.data[size_of::<RecordData>()..];
+
+    let Some(index) = ShardIndex::decode(payload)? else {
+        return Ok(Some(payload.to_vec()));
+    };
+    debug!(
+        "Record {record_key} is sharded across {} accounts",
+        index.shard_count
+    );
+
+    let mut shards = Vec::with_capacity(index.shard_count as usize);
+    for shard in 0..index.shard_count {
+        let shard_seeds = ShardIndex::shard_seeds(seeds, shard);
+        let shard_seeds: Vec<&[u8]> = shard_seeds.iter().map(Vec::as_slice).collect();
+        let shard_key = compute_record_address(rewards_accountant, &shard_seeds)?;
+
+        let account = fetcher
+            .get_dz_account(&shard_key, CommitmentConfig::confirmed())
+            .await?
+            .ok_or_else(|| {
+                anyhow!("Shard {shard} of record {record_key} not found at {shard_key}")
+            })?;
+        shards.push(account.data[size_of::<RecordData>()..].to_vec());
+    }
+
+    index
+        .reassemble(shards)
+        .with_context(|| format!("Failed to reassemble record {record_key}"))
+        .map(Some)
+}
+
 /// Read telemetry aggregates from the ledger
 pub async fn read_telemetry_aggregates(
     settings: &Settings,
@@ -236,28 +522,17 @@ pub async fn read_telemetry_aggregates(
 
         debug!("Re-created record_key: {record_key}");
 
-        let maybe_account = (|| async {
-            fetcher
-                .dz_rpc_client
-                .get_account_with_commitment(&record_key, CommitmentConfig::confirmed())
-                .await
-        })
-        .retry(&ExponentialBuilder::default().with_jitter())
-        .notify(|err: &SolanaClientError, dur: Duration| {
-            info!("retrying error: {:?} with sleeping {:?}", err, dur)
-        })
-        .await?;
+        let maybe_payload = read_record_payload(&fetcher, &rewards_accountant, seeds).await?;
 
-        match maybe_account.value {
+        match maybe_payload {
             None => bail!("account {record_key} has no data!"),
-            Some(acc) => {
-                let stats: DZDTelemetryStatMap =
-                    borsh::from_slice(&acc.data[size_of::<RecordData>()..])?;
+            Some(payload) => {
+                let stats: DZDTelemetryStatMap = borsh::from_slice(&payload)?;
                 device_stats = Some(stats.clone());
-                println!(
+                presenter::output(format!(
                     "Device Telemetry Aggregates:\n{}",
                     print_telemetry_stats(&stats)
-                );
+                ));
             }
         }
     }
@@ -271,28 +546,17 @@ pub async fn read_telemetry_aggregates(
 
         debug!("Re-created record_key: {record_key}");
 
-        let maybe_account = (|| async {
-            fetcher
-                .dz_rpc_client
-                .get_account_with_commitment(&record_key, CommitmentConfig::confirmed())
-                .await
-        })
-        .retry(&ExponentialBuilder::default().with_jitter())
-        .notify(|err: &SolanaClientError, dur: Duration| {
-            info!("retrying error: {:?} with sleeping {:?}", err, dur)
-        })
-        .await?;
+        let maybe_payload = read_record_payload(&fetcher, &rewards_accountant, seeds).await?;
 
-        match maybe_account.value {
+        match maybe_payload {
             None => bail!("account {record_key} has no data!"),
-            Some(acc) => {
-                let stats: InternetTelemetryStatMap =
-                    borsh::from_slice(&acc.data[size_of::<RecordData>()..])?;
+            Some(payload) => {
+                let stats: InternetTelemetryStatMap = borsh::from_slice(&payload)?;
                 internet_stats = Some(stats.clone());
-                println!(
+                presenter::output(format!(
                     "Internet Telemetry Aggregates:\n{}",
                     print_internet_stats(&stats)
-                );
+                ));
             }
         }
     }
@@ -407,22 +671,12 @@ pub async fn read_reward_input(
 
     debug!("Fetching calculation input from: {}", record_key);
 
-    let maybe_account = (|| async {
-        fetcher
-            .dz_rpc_client
-            .get_account_with_commitment(&record_key, CommitmentConfig::confirmed())
-            .await
-    })
-    .retry(&ExponentialBuilder::default().with_jitter())
-    .notify(|err: &SolanaClientError, dur: Duration| {
-        info!("retrying error: {:?} with sleeping {:?}", err, dur)
-    })
-    .await?;
+    let maybe_payload = read_record_payload(&fetcher, &rewards_accountant, seeds).await?;
 
-    let input_config = match maybe_account.value {
+    let input_config = match maybe_payload {
         None => bail!("Calculation input account {record_key} not found for epoch {epoch}",),
-        Some(acc) => {
-            let data: RewardInput = borsh::from_slice(&acc.data[size_of::<RecordData>()..])?;
+        Some(payload) => {
+            let data: RewardInput = borsh::from_slice(&payload)?;
             data
         }
     };
@@ -437,7 +691,7 @@ pub async fn read_reward_input(
         value: String,
     }
 
-    let input_data = vec![
+    let mut input_data = vec![
         RewardInputDisplay {
             field: "Epoch".to_string(),
             value: input_config.epoch.to_string(),
@@ -478,13 +732,118 @@ pub async fn read_reward_input(
             field: "Demand Multiplier".to_string(),
             value: input_config.shapley_settings.demand_multiplier.to_string(),
         },
+        RewardInputDisplay {
+            field: "Denomination Mint".to_string(),
+            value: input_config.denomination.as_ref().map_or_else(
+                || "none".to_string(),
+                |denomination| denomination.mint.to_string(),
+            ),
+        },
+        RewardInputDisplay {
+            field: "Denomination Decimals".to_string(),
+            value: input_config.denomination.as_ref().map_or_else(
+                || "none".to_string(),
+                |denomination| denomination.decimals.to_string(),
+            ),
+        },
+        RewardInputDisplay {
+            field: "Approved Parameters".to_string(),
+            value: input_config
+                .approved_parameters
+                .map_or_else(|| "none".to_string(), |record| record.to_string()),
+        },
     ];
-
-    println!(
-        "{}",
-        Table::new(input_data).with(Style::psql().remove_horizontals())
+    input_data.extend(
+        input_config
+            .reward_pools
+            .iter()
+            .map(|pool| RewardInputDisplay {
+                field: format!("Reward Pool {}", pool.name),
+                value: format!("{:.2}% ({:?})", pool.share * 100.0, pool.allocation),
+            }),
     );
 
+    presenter::output(Table::new(input_data).with(Style::psql().remove_horizontals()));
+
+    Ok(())
+}
+
+/// Read and display the provenance record of an epoch
+pub async fn read_provenance(
+    settings: &Settings,
+    epoch: u64,
+    rewards_accountant: Option<Pubkey>,
+) -> Result<()> {
+    let fetcher = Fetcher::from_settings(settings)?;
+
+    // Auto-fetch rewards_accountant if not provided
+    let rewards_accountant =
+        get_rewards_accountant(&fetcher.solana_write_client, rewards_accountant).await?;
+
+    let prefix = get_contributor_rewards_prefix(settings)?;
+    let epoch_bytes = epoch.to_le_bytes();
+    let seeds: &[&[u8]] = &[&prefix, &epoch_bytes, PROVENANCE_SEED];
+    let record_key = compute_record_address(&rewards_accountant, seeds)?;
+
+    debug!("Fetching epoch provenance from: {}", record_key);
+
+    let provenance: EpochProvenance =
+        match read_record_payload(&fetcher, &rewards_accountant, seeds).await? {
+            None => bail!("Provenance record {record_key} not found for epoch {epoch}"),
+            Some(payload) => borsh::from_slice(&payload)?,
+        };
+
+    #[derive(Tabled)]
+    struct ProvenanceDisplay {
+        #[tabled(rename = "Field")]
+        field: String,
+        #[tabled(rename = "Value")]
+        value: String,
+    }
+
+    let row = |field: &str, value: String| ProvenanceDisplay {
+        field: field.to_string(),
+        value,
+    };
+    let mut rows = vec![
+        row("Epoch", provenance.epoch.to_string()),
+        row("Software Version", provenance.software_version.clone()),
+        row("Config Hash", provenance.config_hash.to_string()),
+        row(
+            "Snapshot",
+            provenance
+                .snapshot_content_address
+                .map_or_else(|| "none".to_string(), |hash| hash.to_string()),
+        ),
+        row(
+            "Run Timestamp",
+            DateTime::from_timestamp(provenance.run_timestamp, 0).map_or_else(
+                || provenance.run_timestamp.to_string(),
+                |timestamp| timestamp.to_rfc3339(),
+            ),
+        ),
+        row(
+            "Run ID",
+            provenance
+                .run_id
+                .map_or_else(|| "none".to_string(), |run_id| run_id.to_string()),
+        ),
+        row(
+            "RNG Seed",
+            provenance
+                .rng_seed
+                .map_or_else(|| "none".to_string(), |seed| seed.to_string()),
+        ),
+    ];
+    rows.extend(provenance.artifacts.iter().map(|artifact| {
+        row(
+            &format!("Artifact {}", artifact.name),
+            artifact.hash.to_string(),
+        )
+    }));
+
+    presenter::output(Table::new(rows).with(Style::psql().remove_horizontals()));
+
     Ok(())
 }
 
@@ -494,7 +853,10 @@ pub async fn check_contributor_reward(
     contributor_pubkey: &Pubkey,
     epoch: u64,
     rewards_accountant: Option<Pubkey>,
+    total_amount: Option<f64>,
 ) -> Result<()> {
+    let denomination = settings.denomination()?;
+
     // Fetch the shapley output storage
     let shapley_storage = read_shapley_output(settings, epoch, rewards_accountant).await?;
 
@@ -526,7 +888,7 @@ pub async fn check_contributor_reward(
         value: String,
     }
 
-    let verification_data = vec![
+    let mut verification_data = vec![
         RewardVerification {
             field: "Epoch".to_string(),
             value: epoch.to_string(),
@@ -554,6 +916,10 @@ pub async fn check_contributor_reward(
                 shapley_storage.total_unit_shares
             ),
         },
+        RewardVerification {
+            field: "Denomination".to_string(),
+            value: format!("{} ({} decimals)", denomination.mint, denomination.decimals),
+        },
         RewardVerification {
             field: "Verification Status".to_string(),
             value: if verification_result {
@@ -564,10 +930,22 @@ pub async fn check_contributor_reward(
         },
     ];
 
-    println!(
-        "{}",
-        Table::new(verification_data).with(Style::psql().remove_horizontals())
-    );
+    if let Some(total_amount) = total_amount {
+        let amount =
+            denomination.reward_amount(denomination.to_base_units(total_amount), reward.unit_share);
+        verification_data.insert(
+            verification_data.len() - 1,
+            RewardVerification {
+                field: "Reward Amount".to_string(),
+                value: format!(
+                    "{} ({amount} base units)",
+                    denomination.format_amount(amount)
+                ),
+            },
+        );
+    }
+
+    presenter::output(Table::new(verification_data).with(Style::psql().remove_horizontals()));
 
     if !verification_result {
         bail!("Merkle proof verification failed");
@@ -584,7 +962,8 @@ pub async fn write_shapley_output(
     _shapley_storage: &ShapleyOutputStorage,
     shapley_storage_bytes: &[u8],
     settings: &Settings,
-) -> Result<()> {
+    overwrite: bool,
+) -> Result<RecordAction> {
     let prefix = get_contributor_rewards_prefix(settings)?;
     let epoch_bytes = epoch.to_le_bytes();
     let seeds: &[&[u8]] = &[&prefix, &epoch_bytes, b"shapley_output"];
@@ -597,15 +976,18 @@ pub async fn write_shapley_output(
         shapley_storage_bytes,
         "shapley output storage",
         &mut summary,
+        overwrite,
         settings.rpc.rps_limit,
     )
     .await;
 
-    if !summary.all_successful() {
-        bail!("Failed to write shapley output storage");
+    match summary.results.pop() {
+        Some(WriteResult::Recorded(_, action)) => Ok(action),
+        Some(WriteResult::Failed(_, error)) => {
+            bail!("Failed to write shapley output storage: {error}")
+        }
+        _ => bail!("Failed to write shapley output storage"),
     }
-
-    Ok(())
 }
 
 /// Read shapley output storage from the ledger
@@ -620,35 +1002,40 @@ pub async fn read_shapley_output(
     let rewards_accountant =
         get_rewards_accountant(&fetcher.solana_write_client, rewards_accountant).await?;
 
+    match fetch_shapley_output(settings, &fetcher, &rewards_accountant, epoch).await? {
+        Some(shapley_storage) => Ok(shapley_storage),
+        None => {
+            let prefix = get_contributor_rewards_prefix(settings)?;
+            let epoch_bytes = epoch.to_le_bytes();
+            let storage_key = compute_record_address(
+                &rewards_accountant,
+                &[&prefix, &epoch_bytes, b"shapley_output"],
+            )?;
+            bail!("Shapley output storage account {storage_key} not found for epoch {epoch}")
+        }
+    }
+}
+
+/// Read shapley output storage written by `rewards_accountant`, if the epoch has one
+pub async fn fetch_shapley_output(
+    settings: &Settings,
+    fetcher: &Fetcher,
+    rewards_accountant: &Pubkey,
+    epoch: u64,
+) -> Result<Option<ShapleyOutputStorage>> {
     let prefix = get_contributor_rewards_prefix(settings)?;
     let epoch_bytes = epoch.to_le_bytes();
     let seeds: &[&[u8]] = &[&prefix, &epoch_bytes, b"shapley_output"];
-    let storage_key = compute_record_address(&rewards_accountant, seeds)?;
-
-    debug!("Fetching shapley output from: {}", storage_key);
 
-    let maybe_account = (|| async {
-        fetcher
-            .dz_rpc_client
-            .get_account_with_commitment(&storage_key, CommitmentConfig::confirmed())
-            .await
-    })
-    .retry(&ExponentialBuilder::default().with_jitter())
-    .notify(|err: &SolanaClientError, dur: Duration| {
-        info!("retrying error: {:?} with sleeping {:?}", err, dur)
-    })
-    .await?;
-
-    let shapley_storage = match maybe_account.value {
-        None => bail!("Shapley output storage account {storage_key} not found for epoch {epoch}",),
-        Some(acc) => {
-            let data: ShapleyOutputStorage =
-                borsh::from_slice(&acc.data[size_of::<RecordData>()..])?;
-            data
-        }
-    };
+    debug!(
+        "Fetching shapley output from: {}",
+        compute_record_address(rewards_accountant, seeds)?
+    );
 
-    Ok(shapley_storage)
+    read_record_payload(fetcher, rewards_accountant, seeds)
+        .await?
+        .map(|payload| borsh::from_slice(&payload).map_err(Into::into))
+        .transpose()
 }
 
 /// NOTE: This is mostly just for debugging
@@ -933,25 +1320,22 @@ pub async fn inspect_records(
         };
 
         // Try to fetch the account
-        let maybe_account = (|| async {
-            fetcher
-                .dz_rpc_client
-                .get_account_with_commitment(&record_key, CommitmentConfig::confirmed())
-                .await
-        })
-        .retry(&ExponentialBuilder::default().with_jitter())
-        .notify(|err: &SolanaClientError, dur: Duration| {
-            info!("retrying error: {:?} with sleeping {:?}", err, dur)
-        })
-        .await?;
+        let maybe_account = fetcher
+            .get_dz_account(&record_key, CommitmentConfig::confirmed())
+            .await?;
 
-        let (data_size, status) = match maybe_account.value {
+        let (data_size, status) = match maybe_account {
             None => (0, "Not found".to_string()),
             Some(acc) => {
                 let data_size = acc.data.len();
                 let actual_size = data_size - header_size;
                 if actual_size == 0 {
                     (actual_size, "Empty".to_string())
+                } else if let Some(index) = ShardIndex::decode(&acc.data[header_size..])? {
+                    (
+                        index.total_len as usize,
+                        format!("Sharded ({} shards)", index.shard_count),
+                    )
                 } else {
                     (actual_size, "Non Empty".to_string())
                 }
@@ -967,10 +1351,79 @@ pub async fn inspect_records(
         });
     }
 
-    println!(
-        "{}",
-        Table::new(records).with(Style::psql().remove_horizontals())
-    );
+    presenter::output(Table::new(records).with(Style::psql().remove_horizontals()));
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failed_writes_roundtrip() {
+        let epoch_bytes = 42u64.to_le_bytes();
+        let write = FailedWrite::new(
+            "device telemetry aggregates",
+            &[b"dz_device_telemetry", &epoch_bytes],
+            &[1, 2, 3],
+            "blockhash not found".to_string(),
+        );
+        assert_eq!(
+            write.seeds().unwrap(),
+            vec![b"dz_device_telemetry".to_vec(), epoch_bytes.to_vec()]
+        );
+        assert_eq!(write.payload().unwrap(), vec![1, 2, 3]);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("failed-writes-epoch-42.json");
+        let failed = FailedWrites {
+            epoch: 42,
+            writes: vec![write],
+        };
+        failed.save(&path).unwrap();
+        assert_eq!(FailedWrites::load(&path).unwrap(), failed);
+
+        let summary = WriteSummary::from_failed(failed.writes);
+        assert_eq!(summary.failed_count(), 1);
+        assert_eq!(summary.failed_writes.len(), 1);
+    }
+
+    #[test]
+    fn test_summary_reports_record_actions() {
+        let mut summary = WriteSummary::default();
+        summary.add_record(
+            "device telemetry aggregates".to_string(),
+            RecordAction::Unchanged,
+        );
+        summary.add_record(
+            "reward calculation input".to_string(),
+            RecordAction::Rewritten,
+        );
+        summary.add_success("merkle root posting".to_string());
+
+        assert!(summary.all_successful());
+        assert_eq!(summary.successful_count(), 3);
+        assert_eq!(summary.record_count(RecordAction::Unchanged), 1);
+        assert_eq!(summary.record_count(RecordAction::Created), 0);
+
+        let report = summary.to_string();
+        assert!(report.contains("Records: 0 created, 1 rewritten, 1 unchanged"));
+        assert!(report.contains("[OK] reward calculation input (rewritten)"));
+    }
+
+    #[test]
+    fn test_summary_reports_warnings() {
+        let mut summary = WriteSummary::default();
+        summary.add_success("merkle root posting".to_string());
+        assert!(!summary.to_string().contains("Warnings:"));
+
+        summary.add_warning("Reward share skew: operator A".to_string());
+        assert!(summary.all_successful());
+        assert!(
+            summary
+                .to_string()
+                .contains("Warnings:\n  [WARN] Reward share skew: operator A")
+        );
+    }
+}
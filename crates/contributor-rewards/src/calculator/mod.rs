@@ -1,12 +1,22 @@
+pub mod approval;
 pub mod constants;
 pub mod data_prep;
+pub mod denomination;
+pub mod dry_run;
+pub mod eligibility;
+pub mod epoch_provenance;
 pub mod input;
 pub mod keypair_loader;
 pub mod ledger_operations;
+pub mod notify;
 pub mod orchestrator;
+pub mod pools;
 pub mod proof;
 pub mod recorder;
 pub mod revenue_distribution;
 pub mod shapley_aggregator;
 pub mod shapley_handler;
+pub mod sharding;
+pub mod simulation;
+pub mod skew;
 pub mod util;
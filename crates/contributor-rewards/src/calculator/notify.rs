@@ -0,0 +1,306 @@
+//! Per-contributor reward notifications
+//!
+//! Once an epoch's rewards are published, every contributor in the contact registry is told its
+//! share and claim window by webhook, by email through an HTTP mail relay, or both. Contacts come
+//! from the config and, when a registry authority is set, from a registry record that authority
+//! posts on the ledger; a contributor listed in both is reached at its ledger entry.
+
+use crate::{
+    calculator::{constants::MAX_UNIT_SHARE, recorder::compute_record_address},
+    ingestor::fetcher::Fetcher,
+    settings::{ContributorContactSettings, NotificationSettings},
+};
+use anyhow::{Context, Result};
+use borsh::{BorshDeserialize, BorshSerialize};
+use doublezero_record::state::RecordData;
+use doublezero_revenue_distribution::types::RewardShare;
+use serde::Serialize;
+use serde_json::json;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use std::{collections::BTreeMap, mem::size_of};
+use tracing::{info, warn};
+
+/// Contacts an authority publishes on the ledger
+#[derive(Debug, Clone, Default, BorshSerialize, BorshDeserialize)]
+pub struct ContactRegistry {
+    pub contacts: Vec<ContributorContactSettings>,
+}
+
+/// What a contributor is told about its reward for an epoch
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RewardNotification {
+    pub epoch: u64,
+    pub contributor: String,
+    pub unit_share: u32,
+    /// Fraction of the epoch's rewards
+    pub share: f64,
+    /// Last epoch the reward can be claimed in, when a claim window is configured
+    pub claim_deadline_epoch: Option<u64>,
+}
+
+impl RewardNotification {
+    pub fn message(&self) -> String {
+        let mut message = format!(
+            "Contributor {} earned {:.2}% of the rewards for epoch {}.",
+            self.contributor,
+            self.share * 100.0,
+            self.epoch
+        );
+        if let Some(deadline) = self.claim_deadline_epoch {
+            message.push_str(&format!(" Claim it before the end of epoch {deadline}."));
+        }
+        message
+    }
+}
+
+/// Notify every registered contributor of its reward for `epoch`
+///
+/// Delivery failures are logged and counted rather than returned, so one unreachable contact
+/// does not stop the others from being notified.
+pub async fn notify_contributors(
+    settings: &NotificationSettings,
+    fetcher: &Fetcher,
+    epoch: u64,
+    rewards: &[RewardShare],
+) -> Result<()> {
+    let registry = read_contact_registry(settings, fetcher).await?;
+    let contacts = merge_contacts(&settings.contacts, registry);
+    let notifications =
+        build_notifications(epoch, rewards, &contacts, settings.claim_window_epochs);
+
+    let client = reqwest::Client::new();
+    let mut failed = 0;
+    for (contact, notification) in &notifications {
+        if let Some(webhook_url) = &contact.webhook_url {
+            let result = send_webhook(&client, webhook_url, notification).await;
+            failed += record_delivery("webhook", notification, result);
+        }
+        if let Some(email) = &contact.email {
+            let result = match &settings.email_relay_url {
+                Some(relay_url) => send_email(&client, relay_url, email, notification).await,
+                None => Err(anyhow::anyhow!("no email_relay_url configured")),
+            };
+            failed += record_delivery("email", notification, result);
+        }
+    }
+
+    info!(
+        "Notified {} contributors of their epoch {epoch} rewards ({failed} deliveries failed)",
+        notifications.len()
+    );
+    Ok(())
+}
+
+/// Contacts on the registry authority's ledger record, if one is configured and posted
+async fn read_contact_registry(
+    settings: &NotificationSettings,
+    fetcher: &Fetcher,
+) -> Result<Vec<ContributorContactSettings>> {
+    let Some(authority) = &settings.registry_authority else {
+        return Ok(Vec::new());
+    };
+    let authority: Pubkey = authority
+        .parse()
+        .context("Invalid contact registry authority")?;
+    let record_key = compute_record_address(&authority, &[settings.registry_prefix.as_bytes()])?;
+
+    let Some(account) = fetcher
+        .get_dz_account(&record_key, CommitmentConfig::confirmed())
+        .await?
+    else {
+        warn!("No contact registry record at {record_key}; using configured contacts only");
+        return Ok(Vec::new());
+    };
+
+    let registry = ContactRegistry::deserialize(&mut &account.data[size_of::<RecordData>()..])
+        .with_context(|| format!("Failed to deserialize contact registry at {record_key}"))?;
+    Ok(registry.contacts)
+}
+
+/// Configured contacts with `registry` entries taking precedence, one per contributor
+pub fn merge_contacts(
+    configured: &[ContributorContactSettings],
+    registry: Vec<ContributorContactSettings>,
+) -> Vec<ContributorContactSettings> {
+    let mut contacts: BTreeMap<String, ContributorContactSettings> = configured
+        .iter()
+        .map(|contact| (contact.contributor.clone(), contact.clone()))
+        .collect();
+    contacts.extend(
+        registry
+            .into_iter()
+            .map(|contact| (contact.contributor.clone(), contact)),
+    );
+    contacts.into_values().collect()
+}
+
+/// A notification for each contact whose contributor has a reward in `epoch`
+pub fn build_notifications<'a>(
+    epoch: u64,
+    rewards: &[RewardShare],
+    contacts: &'a [ContributorContactSettings],
+    claim_window_epochs: Option<u64>,
+) -> Vec<(&'a ContributorContactSettings, RewardNotification)> {
+    let rewards: BTreeMap<String, &RewardShare> = rewards
+        .iter()
+        .map(|reward| (reward.contributor_key.to_string(), reward))
+        .collect();
+
+    contacts
+        .iter()
+        .filter_map(|contact| {
+            let reward = rewards.get(&contact.contributor)?;
+            Some((
+                contact,
+                RewardNotification {
+                    epoch,
+                    contributor: contact.contributor.clone(),
+                    unit_share: reward.unit_share,
+                    share: reward.unit_share as f64 / MAX_UNIT_SHARE,
+                    claim_deadline_epoch: claim_window_epochs.map(|window| epoch + window),
+                },
+            ))
+        })
+        .collect()
+}
+
+/// Log and count a delivery, returning 1 if it failed
+fn record_delivery(
+    channel: &'static str,
+    notification: &RewardNotification,
+    result: Result<()>,
+) -> usize {
+    match result {
+        Ok(()) => {
+            metrics::counter!(
+                "doublezero_contributor_rewards_notifications_sent",
+                "channel" => channel
+            )
+            .increment(1);
+            0
+        }
+        Err(e) => {
+            warn!(
+                "Failed to notify contributor {} by {channel}: {e:#}",
+                notification.contributor
+            );
+            metrics::counter!(
+                "doublezero_contributor_rewards_notifications_failed",
+                "channel" => channel
+            )
+            .increment(1);
+            1
+        }
+    }
+}
+
+async fn send_webhook(
+    client: &reqwest::Client,
+    webhook_url: &str,
+    notification: &RewardNotification,
+) -> Result<()> {
+    let body = json!({
+        "event": "contributor_reward",
+        "message": notification.message(),
+        "details": notification,
+    });
+
+    client
+        .post(webhook_url)
+        .json(&body)
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(())
+}
+
+async fn send_email(
+    client: &reqwest::Client,
+    relay_url: &str,
+    to: &str,
+    notification: &RewardNotification,
+) -> Result<()> {
+    let body = json!({
+        "to": to,
+        "subject": format!("DoubleZero rewards for epoch {}", notification.epoch),
+        "text": notification.message(),
+    });
+
+    client
+        .post(relay_url)
+        .json(&body)
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contact(contributor: &Pubkey, webhook_url: &str) -> ContributorContactSettings {
+        ContributorContactSettings {
+            contributor: contributor.to_string(),
+            webhook_url: Some(webhook_url.to_string()),
+            email: None,
+        }
+    }
+
+    #[test]
+    fn test_registry_overrides_configured_contacts() {
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let configured = vec![
+            contact(&a, "https://a.example.com"),
+            contact(&b, "https://b.example.com"),
+        ];
+        let registry = vec![contact(&b, "https://b.example.org")];
+
+        let contacts = merge_contacts(&configured, registry);
+        assert_eq!(contacts.len(), 2);
+        let b_contact = contacts
+            .iter()
+            .find(|contact| contact.contributor == b.to_string())
+            .unwrap();
+        assert_eq!(
+            b_contact.webhook_url.as_deref(),
+            Some("https://b.example.org")
+        );
+    }
+
+    #[test]
+    fn test_build_notifications() {
+        let (a, b, c) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let rewards = vec![
+            RewardShare::new(a, 750_000_000, false, 0).unwrap(),
+            RewardShare::new(b, 250_000_000, false, 0).unwrap(),
+        ];
+        // c has a contact but no reward this epoch
+        let contacts = vec![
+            contact(&a, "https://a.example.com"),
+            contact(&c, "https://c.example.com"),
+        ];
+
+        let notifications = build_notifications(42, &rewards, &contacts, Some(10));
+        assert_eq!(notifications.len(), 1);
+        let (_, notification) = &notifications[0];
+        assert_eq!(notification.contributor, a.to_string());
+        assert_eq!(notification.share, 0.75);
+        assert_eq!(notification.claim_deadline_epoch, Some(52));
+        assert_eq!(
+            notification.message(),
+            format!(
+                "Contributor {a} earned 75.00% of the rewards for epoch 42. Claim it before the end of epoch 52."
+            )
+        );
+
+        let notifications = build_notifications(42, &rewards, &contacts, None);
+        assert_eq!(notifications[0].1.claim_deadline_epoch, None);
+    }
+}
@@ -1,34 +1,45 @@
 use crate::{
     calculator::{
+        approval,
         data_prep::PreparedData,
+        dry_run::DryRunArtifacts,
+        eligibility::exclude_operators,
+        epoch_provenance::{self, EpochProvenance},
         input::RewardInput,
         keypair_loader::load_keypair,
-        ledger_operations,
+        ledger_operations, notify, pools,
         proof::{ContributorRewardsMerkleTree, ShapleyOutputStorage},
         revenue_distribution::post_rewards_merkle_root,
-        shapley_aggregator::aggregate_shapley_outputs,
-        util::print_demands,
+        shapley_aggregator::{aggregate_shapley_outputs, compute_per_city_outputs},
+        skew,
     },
+    cli::snapshot::CompleteSnapshot,
     ingestor::fetcher::Fetcher,
+    processor::{compact::CompactLinkStatMap, memory},
+    run_id::RunId,
     settings::Settings,
 };
-use anyhow::{Context, Result, bail};
-use network_shapley::{shapley::ShapleyInput, types::Demand};
-use rayon::prelude::*;
-use solana_sdk::pubkey::Pubkey;
-use std::{collections::BTreeMap, path::PathBuf, time::Instant};
+use anyhow::{Result, bail};
+use solana_sdk::{hash::Hash, pubkey::Pubkey};
+use std::{
+    path::{Path, PathBuf},
+    time::Instant,
+};
 use tabled::{builder::Builder as TableBuilder, settings::Style};
 use tracing::{info, warn};
 
 #[derive(Debug, Clone)]
 pub struct Orchestrator {
     pub settings: Settings,
+    /// Identifies this process run in logs, records and artifacts
+    pub run_id: RunId,
 }
 
 impl Orchestrator {
     pub fn new(settings: &Settings) -> Self {
         Self {
             settings: settings.clone(),
+            run_id: RunId::generate(),
         }
     }
 
@@ -36,21 +47,95 @@ impl Orchestrator {
         &self.settings
     }
 
+    pub fn run_id(&self) -> RunId {
+        self.run_id
+    }
+
     pub async fn calculate_rewards(
         &self,
         epoch: Option<u64>,
         keypair_path: Option<PathBuf>,
         dry_run: bool,
+        force_unapproved: bool,
+        overwrite_records: bool,
     ) -> Result<()> {
         let epoch_start = Instant::now();
         let fetcher = Fetcher::from_settings(&self.settings)?;
 
         // Prepare all data
         let prep_data = PreparedData::new(&fetcher, epoch, true).await?;
+
+        self.calculate_rewards_with(
+            &fetcher,
+            prep_data,
+            None,
+            keypair_path,
+            dry_run,
+            force_unapproved,
+            overwrite_records,
+            epoch_start,
+        )
+        .await
+    }
+
+    /// Calculate rewards from a previously exported snapshot instead of live chain data
+    pub async fn calculate_rewards_from_snapshot(
+        &self,
+        snapshot_path: &Path,
+        reprocess: bool,
+        keypair_path: Option<PathBuf>,
+        dry_run: bool,
+        force_unapproved: bool,
+        overwrite_records: bool,
+    ) -> Result<()> {
+        // Snapshots lack the previous epoch defaults and internet lookback applied by a live
+        // run, so their results may differ and are never written to the ledger
+        if !dry_run {
+            bail!("Calculating rewards from a snapshot is only supported as a dry run");
+        }
+
+        let epoch_start = Instant::now();
+        let fetcher = Fetcher::from_settings(&self.settings)?;
+
+        let snapshot = CompleteSnapshot::from_path(snapshot_path)?;
+        let snapshot_content_address =
+            epoch_provenance::content_address(&std::fs::read(snapshot_path)?);
+        info!(
+            "Calculating rewards from v{} snapshot for epoch {} (reprocess: {})",
+            snapshot.version, snapshot.dz_epoch, reprocess
+        );
+        let prep_data =
+            PreparedData::from_snapshot(&self.settings, &snapshot, reprocess, true).await?;
+
+        self.calculate_rewards_with(
+            &fetcher,
+            prep_data,
+            Some(snapshot_content_address),
+            keypair_path,
+            dry_run,
+            force_unapproved,
+            overwrite_records,
+            epoch_start,
+        )
+        .await
+    }
+
+    async fn calculate_rewards_with(
+        &self,
+        fetcher: &Fetcher,
+        prep_data: PreparedData,
+        snapshot_content_address: Option<Hash>,
+        keypair_path: Option<PathBuf>,
+        dry_run: bool,
+        force_unapproved: bool,
+        overwrite_records: bool,
+        epoch_start: Instant,
+    ) -> Result<()> {
         let fetch_epoch = prep_data.epoch;
         let fetch_epoch_bytes = fetch_epoch.to_le_bytes();
         let device_telemetry = prep_data.device_telemetry;
         let internet_telemetry = prep_data.internet_telemetry;
+        let validation = prep_data.validation;
 
         // Track current epoch being processed
         metrics::gauge!("doublezero_contributor_rewards_current_epoch").set(fetch_epoch as f64);
@@ -59,97 +144,45 @@ impl Orchestrator {
             bail!("Shapley inputs required for reward calculation but were not prepared")
         };
 
+        // Refuse to publish rewards computed with parameters governance has not approved
+        let approved_parameters = if dry_run {
+            None
+        } else {
+            approval::check_approved_parameters(
+                &self.settings,
+                fetcher,
+                fetch_epoch,
+                force_unapproved,
+            )
+            .await?
+        };
+
         let device_telemetry_bytes = borsh::to_vec(&device_telemetry)?;
         let internet_telemetry_bytes = borsh::to_vec(&internet_telemetry)?;
 
+        // Recorded with the input and provenance so the run can be reproduced
+        let rng_seed = self.settings.rng_seed(fetch_epoch);
+        info!("RNG seed: {rng_seed}");
+
         let input_config = RewardInput::new(
             fetch_epoch,
             self.settings.shapley.clone(),
+            self.settings.denomination()?,
+            self.settings.pools.clone(),
             &shapley_inputs,
             &device_telemetry_bytes,
             &internet_telemetry_bytes,
-        );
+        )
+        .with_approved_parameters(approved_parameters)
+        .with_rng_seed(rng_seed);
 
         let device_payload_bytes = device_telemetry_bytes.len();
         let internet_payload_bytes = internet_telemetry_bytes.len();
 
-        // Group demands by start city
-        let mut demands_by_city: BTreeMap<String, Vec<Demand>> = BTreeMap::new();
-        for demand in shapley_inputs.demands.clone() {
-            demands_by_city
-                .entry(demand.start.clone())
-                .or_default()
-                .push(demand);
-        }
-        let demand_groups: Vec<(String, Vec<Demand>)> = demands_by_city.into_iter().collect();
-
         // Collect per-city Shapley outputs in parallel
         let start_time = Instant::now();
-        let per_city_shapley_outputs: BTreeMap<String, Vec<(String, f64)>> = demand_groups
-            .par_iter()
-            .map(|(city, demands)| {
-                let city_name = city.clone();
-                let city_start = Instant::now();
-                info!(
-                    "City: {city_name}, Demand: \n{}",
-                    print_demands(demands, 1_000_000)
-                );
-
-                // Build shapley inputs
-                let input = ShapleyInput {
-                    private_links: shapley_inputs.private_links.clone(),
-                    devices: shapley_inputs.devices.clone(),
-                    demands: demands.clone(),
-                    public_links: shapley_inputs.public_links.clone(),
-                    operator_uptime: self.settings.shapley.operator_uptime,
-                    contiguity_bonus: self.settings.shapley.contiguity_bonus,
-                    demand_multiplier: self.settings.shapley.demand_multiplier,
-                };
-
-                // Shapley output
-                let output = input
-                    .compute()
-                    .map_err(|err| {
-                        metrics::counter!(
-                            "doublezero_contributor_rewards_shapley_computations_failed",
-                            "city" => city_name.clone()
-                        )
-                        .increment(1);
-                        warn!(error = ?err, city = %city_name, "Failed to compute Shapley values");
-                        err
-                    })
-                    .with_context(|| format!("failed to compute Shapley values for {city_name}"))?;
-
-                // Track Shapley computation metrics
-                metrics::histogram!(
-                    "doublezero_contributor_rewards_shapley_computation_duration",
-                    "city" => city_name.clone()
-                )
-                .record(city_start.elapsed().as_secs_f64());
-                metrics::counter!(
-                    "doublezero_contributor_rewards_shapley_computations",
-                    "city" => city_name.clone()
-                )
-                .increment(1);
-
-                // Print per-city table
-                let table = TableBuilder::from(output.clone())
-                    .build()
-                    .with(Style::psql().remove_horizontals())
-                    .to_string();
-                info!("Shapley Output for {city_name}:\n{}", table);
-
-                // Store raw values for aggregation
-                let city_values: Vec<(String, f64)> = output
-                    .into_iter()
-                    .map(|(operator, shapley_value)| (operator, shapley_value.value))
-                    .collect();
-
-                Ok((city_name, city_values))
-            })
-            .collect::<Result<Vec<_>>>()?
-            .into_iter()
-            .collect();
+        let per_city_shapley_outputs =
+            compute_per_city_outputs(&self.settings.shapley, &shapley_inputs)?;
 
         let elapsed = start_time.elapsed();
 
@@ -170,6 +203,26 @@ impl Orchestrator {
             let shapley_output =
                 aggregate_shapley_outputs(&per_city_shapley_outputs, &shapley_inputs.city_weights)?;
 
+            // Ineligible operators forfeit their share to the remaining operators
+            let exclusions = &shapley_inputs.exclusions;
+            let mut shapley_output = exclude_operators(shapley_output, exclusions);
+
+            // Split across configured reward pools, recording each pool's allocation
+            let mut pool_rewards = Vec::new();
+            if !self.settings.pools.is_empty() {
+                let mut allocations =
+                    pools::allocate_pools(&self.settings.pools, &shapley_output, &shapley_inputs);
+                for allocation in &mut allocations {
+                    // Pools allocated by device count still see excluded operators' devices
+                    allocation.output =
+                        exclude_operators(std::mem::take(&mut allocation.output), exclusions);
+                }
+                for allocation in &allocations {
+                    pool_rewards.extend(allocation.to_reward_shares(fetch_epoch)?);
+                }
+                shapley_output = pools::combine_pools(&allocations);
+            }
+
             // Print shapley_output table
             let mut table_builder = TableBuilder::default();
             table_builder.push_record(["Operator", "Value", "Proportion (%)"]);
@@ -198,10 +251,24 @@ impl Orchestrator {
             let merkle_root = merkle_tree.compute_root()?;
             info!("merkle_root: {:#?}", merkle_root);
 
+            // Compare shares against recent epochs to catch drastically redistributed rewards
+            let skews = skew::check_reward_skew(
+                &self.settings,
+                fetcher,
+                fetch_epoch,
+                merkle_tree.rewards(),
+            )
+            .await
+            .unwrap_or_else(|e| {
+                warn!("Failed to analyze reward share skew: {e:#}");
+                Vec::new()
+            });
+
             let shapley_storage = ShapleyOutputStorage {
                 epoch: fetch_epoch,
                 rewards: merkle_tree.rewards().to_vec(),
                 total_unit_shares: merkle_tree.rewards().iter().map(|r| r.unit_share).sum(),
+                pools: pool_rewards,
             };
 
             // Record payload sizes to monitor ledger write growth
@@ -210,6 +277,22 @@ impl Orchestrator {
             let reward_input_len = reward_input_bytes.len();
             let shapley_storage_len = shapley_storage_bytes.len();
 
+            // Record what produced this epoch's rewards
+            let provenance = EpochProvenance::new(
+                fetch_epoch,
+                &self.settings,
+                &[
+                    ("device_telemetry", device_telemetry_bytes.as_slice()),
+                    ("internet_telemetry", internet_telemetry_bytes.as_slice()),
+                    ("reward_input", reward_input_bytes.as_slice()),
+                    ("shapley_output", shapley_storage_bytes.as_slice()),
+                ],
+                snapshot_content_address,
+                self.run_id,
+                rng_seed,
+            )?;
+            let provenance_bytes = borsh::to_vec(&provenance)?;
+
             metrics::gauge!(
                 "doublezero_contributor_rewards_ledger_write_bytes",
                 "type" => "device"
@@ -235,6 +318,9 @@ impl Orchestrator {
             if !dry_run {
                 let payer_signer = load_keypair(&keypair_path)?;
 
+                // Never publish artifacts for one network to another network's clusters
+                ledger_operations::ensure_target_network(&self.settings, fetcher).await?;
+
                 // Validate keypair matches ProgramConfig
                 ledger_operations::validate_rewards_accountant_keypair(
                     &fetcher.solana_write_client,
@@ -245,6 +331,20 @@ impl Orchestrator {
                 let mut summary = ledger_operations::WriteSummary::default();
                 let ledger_start = Instant::now();
 
+                for share_skew in &skews {
+                    summary.add_warning(format!("Reward share skew: {}", share_skew.describe()));
+                }
+                // Each issue was logged when the data was prepared
+                for (kind, count) in validation.counts() {
+                    summary.add_warning(format!("Data validation: {count} {kind} issues"));
+                }
+                if !skews.is_empty()
+                    && let Some(webhook_url) = &self.settings.skew.webhook_url
+                    && let Err(e) = skew::notify_skew(webhook_url, fetch_epoch, &skews).await
+                {
+                    warn!("Failed to send reward share skew notification: {e:#}");
+                }
+
                 // Write device telemetry
                 let device_prefix = self.settings.prefixes.device_telemetry.as_bytes();
                 ledger_operations::write_serialized_and_track(
@@ -254,6 +354,7 @@ impl Orchestrator {
                     &device_telemetry_bytes,
                     "device telemetry aggregates",
                     &mut summary,
+                    overwrite_records,
                     self.settings.rpc.rps_limit,
                 )
                 .await;
@@ -267,6 +368,7 @@ impl Orchestrator {
                     &internet_telemetry_bytes,
                     "internet telemetry aggregates",
                     &mut summary,
+                    overwrite_records,
                     self.settings.rpc.rps_limit,
                 )
                 .await;
@@ -280,22 +382,43 @@ impl Orchestrator {
                     &reward_input_bytes,
                     "reward calculation input",
                     &mut summary,
+                    overwrite_records,
                     self.settings.rpc.rps_limit,
                 )
                 .await;
 
                 // Write shapley output storage instead of individual proofs
-                ledger_operations::write_shapley_output(
+                let shapley_action = ledger_operations::write_shapley_output(
                     &fetcher.dz_rpc_client,
                     &payer_signer,
                     fetch_epoch,
                     &shapley_storage,
                     &shapley_storage_bytes,
                     &self.settings,
+                    overwrite_records,
                 )
                 .await?;
 
-                summary.add_success("shapley output storage".to_string());
+                summary.add_record("shapley output storage".to_string(), shapley_action);
+
+                // Write epoch provenance
+                let contributor_rewards_prefix =
+                    self.settings.prefixes.contributor_rewards.as_bytes();
+                ledger_operations::write_serialized_and_track(
+                    &fetcher.dz_rpc_client,
+                    &payer_signer,
+                    &[
+                        contributor_rewards_prefix,
+                        &fetch_epoch_bytes,
+                        epoch_provenance::PROVENANCE_SEED,
+                    ],
+                    &provenance_bytes,
+                    "epoch provenance",
+                    &mut summary,
+                    overwrite_records,
+                    self.settings.rpc.rps_limit,
+                )
+                .await;
 
                 // Post merkle root to revenue distribution program
                 info!(
@@ -324,6 +447,28 @@ impl Orchestrator {
                     }
                 }
 
+                // Re-attempt failed record writes once, then persist whatever still fails
+                // so it can be retried later via `rewards retry-writes`
+                if !summary.failed_writes.is_empty() {
+                    info!(
+                        "Retrying {} failed record writes",
+                        summary.failed_writes.len()
+                    );
+                    summary
+                        .retry_failed(
+                            &fetcher.dz_rpc_client,
+                            &payer_signer,
+                            overwrite_records,
+                            self.settings.rpc.rps_limit,
+                        )
+                        .await;
+                }
+                if let Err(e) =
+                    ledger_operations::FailedWrites::persist(&self.settings, fetch_epoch, &summary)
+                {
+                    warn!("Failed to persist failed record writes: {e:#}");
+                }
+
                 // Track ledger operation metrics
                 metrics::histogram!("doublezero_contributor_rewards_ledger_write_duration")
                     .record(ledger_start.elapsed().as_secs_f64());
@@ -348,19 +493,82 @@ impl Orchestrator {
                         summary.total_count()
                     );
                 }
+
+                if self.settings.notifications.enabled
+                    && let Err(e) = notify::notify_contributors(
+                        &self.settings.notifications,
+                        &fetcher,
+                        fetch_epoch,
+                        merkle_tree.rewards(),
+                    )
+                    .await
+                {
+                    warn!("Failed to notify contributors: {e:#}");
+                }
             } else {
                 info!(
                     "DRY-RUN: Would perform batch writes for epoch {}",
                     fetch_epoch
                 );
-                info!("  - Device telemetry: {} bytes", device_payload_bytes);
-                info!("  - Internet telemetry: {} bytes", internet_payload_bytes);
-                info!("  - Reward input: {} bytes", reward_input_len);
-                info!(
-                    "  - Shapley output storage: {} bytes ({} contributors)",
-                    shapley_storage_len,
-                    merkle_tree.len()
-                );
+                let rewards_accountant =
+                    ledger_operations::get_rewards_accountant(&fetcher.solana_write_client, None)
+                        .await?;
+                let mut artifacts = DryRunArtifacts::create(
+                    DryRunArtifacts::dir(&self.settings, fetch_epoch),
+                    fetch_epoch,
+                    rewards_accountant,
+                )?
+                .with_run_id(self.run_id);
+
+                let contributor_rewards_prefix =
+                    self.settings.prefixes.contributor_rewards.as_bytes();
+                artifacts.write(
+                    &[
+                        self.settings.prefixes.device_telemetry.as_bytes(),
+                        &fetch_epoch_bytes,
+                    ],
+                    &device_telemetry_bytes,
+                    "device telemetry aggregates",
+                )?;
+                artifacts.write(
+                    &[
+                        self.settings.prefixes.internet_telemetry.as_bytes(),
+                        &fetch_epoch_bytes,
+                    ],
+                    &internet_telemetry_bytes,
+                    "internet telemetry aggregates",
+                )?;
+                artifacts.write(
+                    &[
+                        self.settings.prefixes.reward_input.as_bytes(),
+                        &fetch_epoch_bytes,
+                    ],
+                    &reward_input_bytes,
+                    "reward calculation input",
+                )?;
+                artifacts.write(
+                    &[
+                        contributor_rewards_prefix,
+                        &fetch_epoch_bytes,
+                        b"shapley_output",
+                    ],
+                    &shapley_storage_bytes,
+                    &format!(
+                        "shapley output storage ({} contributors)",
+                        merkle_tree.len()
+                    ),
+                )?;
+                artifacts.write(
+                    &[
+                        contributor_rewards_prefix,
+                        &fetch_epoch_bytes,
+                        epoch_provenance::PROVENANCE_SEED,
+                    ],
+                    &provenance_bytes,
+                    "epoch provenance",
+                )?;
+                artifacts.finish()?;
+
                 info!("  - Merkle root to post: {:?}", merkle_root);
                 info!("  - Would post merkle root to revenue distribution program");
             }
@@ -373,6 +581,20 @@ impl Orchestrator {
         metrics::histogram!("doublezero_contributor_rewards_epoch_processing_duration")
             .record(epoch_start.elapsed().as_secs_f64());
 
+        // Peak memory of the whole run, for capacity planning
+        if let Some(peak_bytes) = memory::peak_rss_bytes() {
+            let budget = memory::memory_budget().map_or_else(
+                || "no memory budget".to_string(),
+                |budget| format!("memory budget {} MiB", budget >> 20),
+            );
+            info!(
+                "Peak memory usage: {:.1} MiB ({budget})",
+                peak_bytes as f64 / (1024.0 * 1024.0)
+            );
+            metrics::gauge!("doublezero_contributor_rewards_peak_memory_bytes")
+                .set(peak_bytes as f64);
+        }
+
         Ok(())
     }
 
@@ -398,12 +620,14 @@ impl Orchestrator {
         contributor: &Pubkey,
         epoch: u64,
         rewards_accountant: Option<Pubkey>,
+        total_amount: Option<f64>,
     ) -> Result<()> {
         ledger_operations::check_contributor_reward(
             &self.settings,
             contributor,
             epoch,
             rewards_accountant,
+            total_amount,
         )
         .await
     }
@@ -416,6 +640,14 @@ impl Orchestrator {
         ledger_operations::read_reward_input(&self.settings, epoch, rewards_accountant).await
     }
 
+    pub async fn read_provenance(
+        &self,
+        epoch: u64,
+        rewards_accountant: Option<Pubkey>,
+    ) -> Result<()> {
+        ledger_operations::read_provenance(&self.settings, epoch, rewards_accountant).await
+    }
+
     pub async fn realloc_record(
         &self,
         r#type: String,
@@ -444,6 +676,7 @@ impl Orchestrator {
         keypair_path: Option<PathBuf>,
         dry_run: bool,
         telemetry_type: String,
+        overwrite_records: bool,
     ) -> Result<()> {
         let fetcher = Fetcher::from_settings(&self.settings)?;
 
@@ -461,6 +694,7 @@ impl Orchestrator {
 
         if !dry_run {
             let payer_signer = load_keypair(&keypair_path)?;
+            ledger_operations::ensure_target_network(&self.settings, &fetcher).await?;
 
             // Validate keypair matches ProgramConfig
             ledger_operations::validate_rewards_accountant_keypair(
@@ -481,6 +715,7 @@ impl Orchestrator {
                     &borsh::to_vec(&device_telemetry)?,
                     "device telemetry aggregates",
                     &mut summary,
+                    overwrite_records,
                     self.settings.rpc.rps_limit,
                 )
                 .await;
@@ -496,11 +731,28 @@ impl Orchestrator {
                     &borsh::to_vec(&internet_telemetry)?,
                     "internet telemetry aggregates",
                     &mut summary,
+                    overwrite_records,
                     self.settings.rpc.rps_limit,
                 )
                 .await;
             }
 
+            if !summary.failed_writes.is_empty() {
+                summary
+                    .retry_failed(
+                        &fetcher.dz_rpc_client,
+                        &payer_signer,
+                        overwrite_records,
+                        self.settings.rpc.rps_limit,
+                    )
+                    .await;
+            }
+            if let Err(e) =
+                ledger_operations::FailedWrites::persist(&self.settings, fetch_epoch, &summary)
+            {
+                warn!("Failed to persist failed record writes: {e:#}");
+            }
+
             // Log final summary
             info!("{}", summary);
 
@@ -517,18 +769,128 @@ impl Orchestrator {
                 "DRY-RUN: Would write telemetry aggregates for epoch {}",
                 fetch_epoch
             );
+            let rewards_accountant =
+                ledger_operations::get_rewards_accountant(&fetcher.solana_write_client, None)
+                    .await?;
+            let mut artifacts = DryRunArtifacts::create(
+                DryRunArtifacts::dir(&self.settings, fetch_epoch),
+                fetch_epoch,
+                rewards_accountant,
+            )?
+            .with_run_id(self.run_id);
+
             if telemetry_type == "device" || telemetry_type == "all" {
+                let compact = CompactLinkStatMap::from_stat_map(&device_telemetry);
                 info!(
-                    "  - Device telemetry: {} bytes",
-                    borsh::to_vec(&device_telemetry)?.len()
+                    "  - Device telemetry: {} bytes compact",
+                    compact.encoded_len()
                 );
+                if !compact.fits_in_record() {
+                    warn!(
+                        "  - Device telemetry exceeds a single record even when compacted; it will be sharded"
+                    );
+                }
+                artifacts.write(
+                    &[
+                        self.settings.prefixes.device_telemetry.as_bytes(),
+                        &fetch_epoch.to_le_bytes(),
+                    ],
+                    &borsh::to_vec(&device_telemetry)?,
+                    "device telemetry aggregates",
+                )?;
             }
             if telemetry_type == "internet" || telemetry_type == "all" {
-                info!(
-                    "  - Internet telemetry: {} bytes",
-                    borsh::to_vec(&internet_telemetry)?.len()
-                );
+                artifacts.write(
+                    &[
+                        self.settings.prefixes.internet_telemetry.as_bytes(),
+                        &fetch_epoch.to_le_bytes(),
+                    ],
+                    &borsh::to_vec(&internet_telemetry)?,
+                    "internet telemetry aggregates",
+                )?;
             }
+            artifacts.finish()?;
+        }
+
+        Ok(())
+    }
+
+    /// Re-attempt only the record writes that failed for an epoch, as persisted by a
+    /// previous run, instead of rerunning the calculation
+    pub async fn retry_failed_writes(
+        &self,
+        epoch: u64,
+        keypair_path: Option<PathBuf>,
+        dry_run: bool,
+        overwrite_records: bool,
+    ) -> Result<()> {
+        let path = ledger_operations::FailedWrites::path(&self.settings, epoch);
+        if !path.exists() {
+            info!("No failed record writes recorded for epoch {epoch} ({path:?})");
+            return Ok(());
+        }
+        let failed = ledger_operations::FailedWrites::load(&path)?;
+        if failed.epoch != epoch {
+            bail!(
+                "Failed writes file {path:?} is for epoch {}, expected {epoch}",
+                failed.epoch
+            );
+        }
+
+        if dry_run {
+            info!(
+                "DRY-RUN: Would retry {} failed record writes for epoch {}",
+                failed.writes.len(),
+                epoch
+            );
+            let fetcher = Fetcher::from_settings(&self.settings)?;
+            let rewards_accountant =
+                ledger_operations::get_rewards_accountant(&fetcher.solana_write_client, None)
+                    .await?;
+            let mut artifacts = DryRunArtifacts::create(
+                DryRunArtifacts::dir(&self.settings, epoch),
+                epoch,
+                rewards_accountant,
+            )?
+            .with_run_id(self.run_id);
+            for write in &failed.writes {
+                info!("  - {} (last error: {})", write.description, write.error);
+                let seeds = write.seeds()?;
+                let seeds: Vec<&[u8]> = seeds.iter().map(Vec::as_slice).collect();
+                artifacts.write(&seeds, &write.payload()?, &write.description)?;
+            }
+            artifacts.finish()?;
+            return Ok(());
+        }
+
+        let fetcher = Fetcher::from_settings(&self.settings)?;
+        let payer_signer = load_keypair(&keypair_path)?;
+        ledger_operations::ensure_target_network(&self.settings, &fetcher).await?;
+        ledger_operations::validate_rewards_accountant_keypair(
+            &fetcher.solana_write_client,
+            &payer_signer,
+        )
+        .await?;
+
+        let mut summary = ledger_operations::WriteSummary::from_failed(failed.writes);
+        summary
+            .retry_failed(
+                &fetcher.dz_rpc_client,
+                &payer_signer,
+                overwrite_records,
+                self.settings.rpc.rps_limit,
+            )
+            .await;
+        ledger_operations::FailedWrites::persist(&self.settings, epoch, &summary)?;
+
+        info!("{}", summary);
+
+        if !summary.all_successful() {
+            bail!(
+                "Some writes failed: {}/{} successful",
+                summary.successful_count(),
+                summary.total_count()
+            );
         }
 
         Ok(())
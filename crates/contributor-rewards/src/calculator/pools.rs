@@ -0,0 +1,309 @@
+use crate::{
+    calculator::{
+        constants::MAX_UNIT_SHARE, input::ShapleyInputs, proof::ContributorRewardsMerkleTree,
+    },
+    settings::{AllocationRule, RewardPoolSettings},
+};
+use anyhow::Result;
+use borsh::{BorshDeserialize, BorshSerialize};
+use doublezero_revenue_distribution::types::RewardShare;
+use network_shapley::shapley::{ShapleyOutput, ShapleyValue};
+use std::collections::BTreeMap;
+use tracing::{info, warn};
+
+/// Allocation of a single pool, with proportions relative to the pool
+#[derive(Debug, Clone)]
+pub struct PoolAllocation {
+    pub name: String,
+    pub share: f64,
+    pub output: ShapleyOutput,
+}
+
+/// Per-pool reward shares recorded alongside the unified rewards
+#[derive(Debug, Clone, BorshDeserialize, BorshSerialize)]
+pub struct PoolRewardShares {
+    pub name: String,
+    /// Fraction of the epoch's rewards assigned to the pool, in unit shares
+    pub unit_share: u32,
+    /// Reward shares within the pool, summing to the full unit share
+    pub rewards: Vec<RewardShare>,
+}
+
+impl PoolAllocation {
+    /// Convert to fixed-point reward shares for recording
+    ///
+    /// Returns `None` for pools that allocated nothing.
+    pub fn to_reward_shares(&self, epoch: u64) -> Result<Option<PoolRewardShares>> {
+        if self.output.is_empty() {
+            return Ok(None);
+        }
+        let tree = ContributorRewardsMerkleTree::new(epoch, &self.output)?;
+        Ok(Some(PoolRewardShares {
+            name: self.name.clone(),
+            unit_share: (self.share.clamp(0.0, 1.0) * MAX_UNIT_SHARE).round() as u32,
+            rewards: tree.rewards().to_vec(),
+        }))
+    }
+}
+
+/// Allocate every pool from the consolidated Shapley output and the epoch's inputs
+pub fn allocate_pools(
+    pools: &[RewardPoolSettings],
+    shapley_output: &ShapleyOutput,
+    shapley_inputs: &ShapleyInputs,
+) -> Vec<PoolAllocation> {
+    pools
+        .iter()
+        .map(|pool| {
+            let output = allocate_pool(&pool.allocation, shapley_output, shapley_inputs);
+            info!(
+                "Reward pool {} ({:.2}% of rewards, {:?}): {} operators",
+                pool.name,
+                pool.share * 100.0,
+                pool.allocation,
+                output.len()
+            );
+            metrics::gauge!(
+                "doublezero_contributor_rewards_pool_operator_count",
+                "pool" => pool.name.clone()
+            )
+            .set(output.len() as f64);
+
+            PoolAllocation {
+                name: pool.name.clone(),
+                share: pool.share,
+                output,
+            }
+        })
+        .collect()
+}
+
+/// Split a single pool across operators according to its allocation rule
+pub fn allocate_pool(
+    rule: &AllocationRule,
+    shapley_output: &ShapleyOutput,
+    shapley_inputs: &ShapleyInputs,
+) -> ShapleyOutput {
+    match rule {
+        AllocationRule::Shapley => normalize(
+            shapley_output
+                .iter()
+                .map(|(operator, val)| (operator.clone(), val.value)),
+        ),
+        AllocationRule::SlaGated { min_uptime } => {
+            let uptimes = operator_uptimes(shapley_inputs);
+            normalize(
+                shapley_output
+                    .iter()
+                    .filter(|(operator, _)| {
+                        uptimes
+                            .get(operator.as_str())
+                            .is_some_and(|uptime| uptime >= min_uptime)
+                    })
+                    .map(|(operator, val)| (operator.clone(), val.value)),
+            )
+        }
+        AllocationRule::FlatPerDevice => {
+            let mut device_counts: BTreeMap<String, f64> = BTreeMap::new();
+            for device in &shapley_inputs.devices {
+                *device_counts.entry(device.operator.clone()).or_default() += 1.0;
+            }
+            normalize(device_counts)
+        }
+    }
+}
+
+/// Combine pool allocations into the single output the merkle tree is built from
+///
+/// Each operator's proportion (and value) is the share-weighted sum of its proportions
+/// across pools.
+/// Pools that allocated nothing are dropped and the remaining shares rescaled, so the
+/// combined proportions still sum to one.
+pub fn combine_pools(allocations: &[PoolAllocation]) -> ShapleyOutput {
+    let allocated_share: f64 = allocations
+        .iter()
+        .filter(|allocation| !allocation.output.is_empty())
+        .map(|allocation| allocation.share)
+        .sum();
+
+    for allocation in allocations.iter().filter(|a| a.output.is_empty()) {
+        warn!(
+            "Reward pool {} allocated nothing; its share is redistributed across other pools",
+            allocation.name
+        );
+    }
+
+    if allocated_share == 0.0 {
+        return ShapleyOutput::default();
+    }
+
+    let mut combined: BTreeMap<String, f64> = BTreeMap::new();
+    for allocation in allocations {
+        let weight = allocation.share / allocated_share;
+        for (operator, val) in allocation.output.iter() {
+            *combined.entry(operator.clone()).or_default() += val.proportion * weight;
+        }
+    }
+
+    normalize(combined)
+}
+
+/// Mean uptime of each operator's private links
+///
+/// A link counts towards both operators when it spans two of them.
+fn operator_uptimes(shapley_inputs: &ShapleyInputs) -> BTreeMap<String, f64> {
+    let device_operators: BTreeMap<&str, &str> = shapley_inputs
+        .devices
+        .iter()
+        .map(|device| (device.device.as_str(), device.operator.as_str()))
+        .collect();
+
+    let mut totals: BTreeMap<String, (f64, usize)> = BTreeMap::new();
+    for link in &shapley_inputs.private_links {
+        let mut operators = [link.device1.as_str(), link.device2.as_str()]
+            .into_iter()
+            .filter_map(|device| device_operators.get(device).copied())
+            .collect::<Vec<_>>();
+        operators.dedup();
+
+        for operator in operators {
+            let entry = totals.entry(operator.to_string()).or_default();
+            entry.0 += link.uptime;
+            entry.1 += 1;
+        }
+    }
+
+    totals
+        .into_iter()
+        .map(|(operator, (sum, count))| (operator, sum / count as f64))
+        .collect()
+}
+
+/// Turn raw per-operator weights into proportions, dropping operators without weight
+fn normalize(weights: impl IntoIterator<Item = (String, f64)>) -> ShapleyOutput {
+    let weights: Vec<(String, f64)> = weights
+        .into_iter()
+        .filter(|(_, weight)| *weight > 0.0)
+        .collect();
+    let total: f64 = weights.iter().map(|(_, weight)| weight).sum();
+
+    weights
+        .into_iter()
+        .map(|(operator, value)| {
+            (
+                operator,
+                ShapleyValue {
+                    value,
+                    proportion: value / total,
+                },
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use network_shapley::types::{Device, PrivateLink};
+
+    fn shapley_inputs() -> ShapleyInputs {
+        let device = |code: &str, operator: &str| Device {
+            device: code.to_string(),
+            edge: 10,
+            operator: operator.to_string(),
+        };
+        ShapleyInputs {
+            devices: vec![
+                device("A1", "OperatorA"),
+                device("A2", "OperatorA"),
+                device("B1", "OperatorB"),
+                device("C1", "OperatorC"),
+            ],
+            private_links: vec![
+                PrivateLink::new("A1".to_string(), "A2".to_string(), 1.0, 10.0, 1.0, None),
+                PrivateLink::new("A2".to_string(), "B1".to_string(), 1.0, 10.0, 0.995, None),
+                PrivateLink::new("B1".to_string(), "C1".to_string(), 1.0, 10.0, 0.9, None),
+            ],
+            public_links: vec![],
+            demands: vec![],
+            city_stats: BTreeMap::new(),
+            city_weights: BTreeMap::new(),
+            exclusions: vec![],
+        }
+    }
+
+    fn shapley_output() -> ShapleyOutput {
+        [
+            ("OperatorA", 60.0),
+            ("OperatorB", 30.0),
+            ("OperatorC", 10.0),
+        ]
+        .into_iter()
+        .map(|(operator, value)| {
+            (
+                operator.to_string(),
+                ShapleyValue {
+                    value,
+                    proportion: value / 100.0,
+                },
+            )
+        })
+        .collect()
+    }
+
+    fn proportion(output: &ShapleyOutput, operator: &str) -> f64 {
+        output.get(operator).map_or(0.0, |val| val.proportion)
+    }
+
+    #[test]
+    fn test_allocation_rules() {
+        let inputs = shapley_inputs();
+        let output = shapley_output();
+
+        let shapley = allocate_pool(&AllocationRule::Shapley, &output, &inputs);
+        assert!((proportion(&shapley, "OperatorA") - 0.6).abs() < 1e-9);
+
+        // OperatorA averages 0.9975, OperatorB 0.9475 and OperatorC 0.9
+        let gated = allocate_pool(
+            &AllocationRule::SlaGated { min_uptime: 0.99 },
+            &output,
+            &inputs,
+        );
+        assert_eq!(gated.len(), 1);
+        assert!((proportion(&gated, "OperatorA") - 1.0).abs() < 1e-9);
+
+        let flat = allocate_pool(&AllocationRule::FlatPerDevice, &output, &inputs);
+        assert!((proportion(&flat, "OperatorA") - 0.5).abs() < 1e-9);
+        assert!((proportion(&flat, "OperatorC") - 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_combine_pools() {
+        let inputs = shapley_inputs();
+        let output = shapley_output();
+        let pools = vec![
+            RewardPoolSettings {
+                name: "base".to_string(),
+                share: 0.5,
+                allocation: AllocationRule::FlatPerDevice,
+            },
+            RewardPoolSettings {
+                name: "performance".to_string(),
+                share: 0.5,
+                allocation: AllocationRule::SlaGated { min_uptime: 0.99 },
+            },
+        ];
+
+        let combined = combine_pools(&allocate_pools(&pools, &output, &inputs));
+        assert!((proportion(&combined, "OperatorA") - 0.75).abs() < 1e-9);
+        assert!((proportion(&combined, "OperatorB") - 0.125).abs() < 1e-9);
+        let total: f64 = combined.values().map(|val| val.proportion).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+
+        // A pool nobody qualifies for hands its share to the others
+        let mut pools = pools;
+        pools[1].allocation = AllocationRule::SlaGated { min_uptime: 1.0 };
+        let combined = combine_pools(&allocate_pools(&pools, &output, &inputs));
+        assert!((proportion(&combined, "OperatorA") - 0.5).abs() < 1e-9);
+    }
+}
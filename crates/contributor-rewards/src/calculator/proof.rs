@@ -1,4 +1,4 @@
-use crate::calculator::constants::MAX_UNIT_SHARE;
+use crate::calculator::{constants::MAX_UNIT_SHARE, pools::PoolRewardShares};
 use anyhow::{Result, anyhow, bail};
 use borsh::{BorshDeserialize, BorshSerialize};
 use doublezero_revenue_distribution::types::{RewardShare, UnitShare32};
@@ -24,6 +24,8 @@ pub struct ShapleyOutputStorage {
     pub epoch: u64,
     pub rewards: Vec<RewardShare>,
     pub total_unit_shares: u32, // Should equal 1_000_000_000 for validation
+    /// Per-pool allocations the unified rewards above were combined from
+    pub pools: Vec<PoolRewardShares>,
 }
 
 #[derive(Debug)]
@@ -348,6 +350,7 @@ mod tests {
             epoch: 600,
             rewards: tree.rewards().to_vec(),
             total_unit_shares: tree.rewards().iter().map(|r| r.unit_share).sum(),
+            pools: vec![],
         };
 
         // Test generating proof for Alice
@@ -1,4 +1,5 @@
-use anyhow::Result;
+use crate::calculator::sharding::{MAX_RECORD_DATA_LEN, ShardIndex};
+use anyhow::{Result, bail};
 use backon::{ExponentialBuilder, Retryable};
 use doublezero_record::{
     ID as RECORD_PROGRAM_ID, instruction as record_instruction, state::RecordData,
@@ -9,6 +10,7 @@ use solana_client::{
     rpc_config::RpcSendTransactionConfig,
 };
 use solana_sdk::{
+    account::Account,
     commitment_config::{CommitmentConfig, CommitmentLevel},
     hash::hashv,
     instruction::Instruction,
@@ -19,8 +21,83 @@ use solana_sdk::{
     transaction::VersionedTransaction,
 };
 use solana_system_interface::instruction as system_instruction;
-use std::{num::NonZeroU32, time::Duration};
-use tracing::info;
+use std::{fmt, num::NonZeroU32, time::Duration};
+use tracing::{info, warn};
+
+/// Most a program may grow an account by in one instruction
+const MAX_REALLOC_INCREASE: usize = 10_240;
+
+/// Reallocate instructions packed into each growth transaction
+const REALLOC_IXS_PER_TX: usize = 8;
+
+// One byte more and the transaction is too large.
+// WRITE_CHUNK_SIZE is set to 1,013 bytes to stay well within Solana's transaction size limits.
+// This ensures each chunk + transaction overhead remains under the maximum transaction size,
+// avoiding rejection due to tx size boundaries.
+const WRITE_CHUNK_SIZE: usize = 1_013;
+
+/// What writing a record did to the ledger
+///
+/// Ordered by how much changed, so the action for a sharded write is the largest of the
+/// actions taken for its index and shards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RecordAction {
+    /// An identical record was already on the ledger
+    Unchanged,
+    /// No record existed yet, or an earlier write of the same payload was interrupted
+    Created,
+    /// A record with different content was replaced
+    Rewritten,
+}
+
+impl fmt::Display for RecordAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordAction::Unchanged => write!(f, "unchanged"),
+            RecordAction::Created => write!(f, "created"),
+            RecordAction::Rewritten => write!(f, "rewritten"),
+        }
+    }
+}
+
+/// Action needed to make a record hold `payload`, given the payload it already holds
+pub fn record_action(existing: Option<&[u8]>, payload: &[u8]) -> RecordAction {
+    match existing {
+        None => RecordAction::Created,
+        Some(existing) if hashv(&[existing]) == hashv(&[payload]) => RecordAction::Unchanged,
+        Some(existing) if is_partial_write(existing, payload) => RecordAction::Created,
+        Some(_) => RecordAction::Rewritten,
+    }
+}
+
+/// Whether `existing` is a record sized for `payload` whose write was interrupted
+///
+/// Records are created zeroed and written chunk by chunk, so every chunk of such a record is
+/// either still zeroed or already holds the payload's chunk.
+fn is_partial_write(existing: &[u8], payload: &[u8]) -> bool {
+    existing.len() == payload.len()
+        && existing
+            .chunks(WRITE_CHUNK_SIZE)
+            .zip(payload.chunks(WRITE_CHUNK_SIZE))
+            .all(|(existing, payload)| {
+                existing == payload || existing.iter().all(|&byte| byte == 0)
+            })
+}
+
+/// Payload of the account at `record_key`, which must be a record
+///
+/// Anyone can send lamports to a record address before the record is created, so an account
+/// there is not necessarily a record.
+fn record_payload<'a>(record_key: &Pubkey, account: &'a Account) -> Result<&'a [u8]> {
+    if account.owner != RECORD_PROGRAM_ID || account.data.len() < size_of::<RecordData>() {
+        bail!(
+            "Account {record_key} is not a record (owner {}, {} bytes)",
+            account.owner,
+            account.data.len()
+        );
+    }
+    Ok(&account.data[size_of::<RecordData>()..])
+}
 
 pub fn make_record_key(payer_signer: &Keypair, seeds: &[&[u8]]) -> Result<Pubkey> {
     let payer_key = payer_signer.pubkey();
@@ -64,8 +141,24 @@ pub async fn try_create_record(
     })
     .await?;
 
-    if maybe_account.value.is_some() {
+    if let Some(account) = maybe_account.value {
         info!("Found existing record_key: {record_key}");
+        let current_space = record_payload(&record_key, &account)?.len();
+        if current_space < space {
+            grow_record(
+                rpc_client,
+                payer_signer,
+                &record_key,
+                account.lamports,
+                current_space,
+                space,
+            )
+            .await?;
+        } else if current_space > space {
+            warn!(
+                "Record {record_key} holds {current_space} bytes, more than the {space} being written"
+            );
+        }
         return Ok(record_key);
     }
 
@@ -134,6 +227,64 @@ pub async fn try_create_record(
     Ok(record_key)
 }
 
+/// Grow an existing record so a larger payload can be rewritten in place
+///
+/// The record program can only grow an account by [`MAX_REALLOC_INCREASE`] bytes per
+/// instruction, so the growth is split into steps, with the first transaction topping up
+/// rent for the final size.
+async fn grow_record(
+    rpc_client: &RpcClient,
+    payer_signer: &Keypair,
+    record_key: &Pubkey,
+    lamports: u64,
+    current_space: usize,
+    space: usize,
+) -> Result<()> {
+    let payer_key = payer_signer.pubkey();
+    info!("Growing record {record_key} from {current_space} to {space} bytes");
+
+    let rent_exemption_lamports = (|| async {
+        rpc_client
+            .get_minimum_balance_for_rent_exemption(space + size_of::<RecordData>())
+            .await
+    })
+    .retry(&ExponentialBuilder::default().with_jitter())
+    .notify(|err: &SolanaClientError, dur: Duration| {
+        info!("retrying error: {:?} with sleeping {:?}", err, dur)
+    })
+    .await?;
+
+    let steps: Vec<usize> = (current_space..space)
+        .step_by(MAX_REALLOC_INCREASE)
+        .skip(1)
+        .chain([space])
+        .collect();
+
+    let mut instructions = Vec::with_capacity(steps.len() + 1);
+    if rent_exemption_lamports > lamports {
+        instructions.push(system_instruction::transfer(
+            &payer_key,
+            record_key,
+            rent_exemption_lamports - lamports,
+        ));
+    }
+    instructions.extend(
+        steps
+            .into_iter()
+            .map(|len| record_instruction::reallocate(record_key, &payer_key, len as u64)),
+    );
+
+    for batch in instructions.chunks(REALLOC_IXS_PER_TX) {
+        let transaction = new_transaction(rpc_client, batch, &[payer_signer]).await?;
+        let tx_sig = rpc_client
+            .send_and_confirm_transaction(&transaction)
+            .await?;
+        info!("Reallocate record tx: {tx_sig}");
+    }
+
+    Ok(())
+}
+
 pub async fn write_record_chunks(
     rpc_client: &RpcClient,
     payer_signer: &Keypair,
@@ -141,26 +292,20 @@ pub async fn write_record_chunks(
     data: &[u8],
     rps_limit: u32,
 ) -> Result<()> {
-    // One byte more and the transaction is too large.
-    // CHUNK_SIZE is set to 1,013 bytes to stay well within Solana's transaction size limits.
-    // This ensures each chunk + transaction overhead remains under the maximum transaction size,
-    // avoiding rejection due to tx size boundaries.
-    const CHUNK_SIZE: usize = 1_013;
-
     let payer_key = payer_signer.pubkey();
 
-    let num_chunks = data.len() / CHUNK_SIZE + 1;
+    let num_chunks = data.len() / WRITE_CHUNK_SIZE + 1;
 
     // Create rate limiter from settings
     let rate_limiter = RateLimiter::direct(Quota::per_second(
         NonZeroU32::new(rps_limit).expect("RPS limit must be > 0"),
     ));
-    for (i, chunk) in data.chunks(CHUNK_SIZE).enumerate() {
+    for (i, chunk) in data.chunks(WRITE_CHUNK_SIZE).enumerate() {
         // Apply rate limiting before sending each chunk
         rate_limiter.until_ready().await;
 
         let chunk_len = chunk.len();
-        let offset = i * CHUNK_SIZE;
+        let offset = i * WRITE_CHUNK_SIZE;
 
         let write_ix = record_instruction::write(record_key, &payer_key, offset as u64, chunk);
         let transaction = new_transaction(rpc_client, &[write_ix], &[payer_signer]).await?;
@@ -227,26 +372,227 @@ pub fn create_record_seed_string(seeds: &[&[u8]]) -> String {
     seed
 }
 
+/// Write `payload` to the record at `seeds` unless it already holds exactly that
+///
+/// A record with different content is only replaced when `overwrite` is set, while one left
+/// partly written by an interrupted attempt is finished. Records that need to shrink are closed
+/// and recreated, since the record program can only grow them.
+async fn write_record(
+    rpc_client: &RpcClient,
+    payer_signer: &Keypair,
+    seeds: &[&[u8]],
+    payload: &[u8],
+    overwrite: bool,
+    rps_limit: u32,
+) -> Result<(Pubkey, RecordAction)> {
+    let record_key = make_record_key(payer_signer, seeds)?;
+
+    let maybe_account = (|| async {
+        rpc_client
+            .get_account_with_commitment(&record_key, CommitmentConfig::confirmed())
+            .await
+    })
+    .retry(&ExponentialBuilder::default().with_jitter())
+    .notify(|err: &SolanaClientError, dur: Duration| {
+        info!("retrying error: {:?} with sleeping {:?}", err, dur)
+    })
+    .await?;
+
+    let existing = maybe_account
+        .value
+        .as_ref()
+        .map(|account| record_payload(&record_key, account))
+        .transpose()?;
+    let action = record_action(existing, payload);
+
+    match (action, existing) {
+        (RecordAction::Unchanged, _) => {
+            info!("Record {record_key} already holds this payload, skipping write");
+            return Ok((record_key, action));
+        }
+        (RecordAction::Rewritten, Some(existing)) => {
+            if !overwrite {
+                bail!(
+                    "Record {record_key} already holds different content ({} bytes, hash {}; new payload {} bytes, hash {}). \
+                     Re-run with --overwrite-records to replace it",
+                    existing.len(),
+                    hashv(&[existing]),
+                    payload.len(),
+                    hashv(&[payload])
+                );
+            }
+            warn!(
+                "Overwriting record {record_key} ({} bytes -> {} bytes)",
+                existing.len(),
+                payload.len()
+            );
+            if existing.len() > payload.len() {
+                close_record(rpc_client, payer_signer, &record_key).await?;
+            }
+        }
+        _ => {}
+    }
+
+    try_create_record(rpc_client, payer_signer, seeds, payload.len()).await?;
+    write_record_chunks(rpc_client, payer_signer, &record_key, payload, rps_limit).await?;
+
+    Ok((record_key, action))
+}
+
+/// Close a record so it can be recreated at the same address with a smaller size
+async fn close_record(
+    rpc_client: &RpcClient,
+    payer_signer: &Keypair,
+    record_key: &Pubkey,
+) -> Result<()> {
+    let payer_key = payer_signer.pubkey();
+    let close_ix = record_instruction::close_account(record_key, &payer_key, &payer_key);
+
+    let transaction = new_transaction(rpc_client, &[close_ix], &[payer_signer]).await?;
+    let tx_sig = rpc_client
+        .send_and_confirm_transaction(&transaction)
+        .await?;
+    info!("Close record tx: {tx_sig}");
+
+    Ok(())
+}
+
+/// Write a serialized payload to the record at `seeds`, sharding it if needed
+///
+/// Writes are idempotent: records already holding the payload are left untouched and records
+/// left partly written are finished, so an interrupted or repeated run can safely be
+/// re-executed. Records holding different content are only replaced when `overwrite` is set.
 pub async fn write_serialized_to_ledger(
     rpc_client: &RpcClient,
     payer_signer: &Keypair,
     seeds: &[&[u8]],
     serialized: &[u8],
     data_type: &str,
+    overwrite: bool,
     rps_limit: u32,
-) -> Result<Pubkey> {
+) -> Result<(Pubkey, RecordAction)> {
     info!(
         "Writing {} to ledger ({} bytes)",
         data_type,
         serialized.len()
     );
 
-    // Create the record account
-    let record_key = try_create_record(rpc_client, payer_signer, seeds, serialized.len()).await?;
+    // Payloads too large for one account are spread over shard records, and the record at
+    // the usual address holds the index readers reassemble them from
+    let mut action = RecordAction::Unchanged;
+    let index;
+    let payload = if serialized.len() > MAX_RECORD_DATA_LEN {
+        let (shard_index, shards) = ShardIndex::split(serialized, MAX_RECORD_DATA_LEN);
+        info!(
+            "{} exceeds a single record; writing {} shards",
+            data_type, shard_index.shard_count
+        );
 
-    // Write the data in chunks
-    write_record_chunks(rpc_client, payer_signer, &record_key, serialized, rps_limit).await?;
+        for (i, shard) in shards.into_iter().enumerate() {
+            let shard_seeds = ShardIndex::shard_seeds(seeds, i as u32);
+            let shard_seeds: Vec<&[u8]> = shard_seeds.iter().map(Vec::as_slice).collect();
+
+            let (shard_key, shard_action) = write_record(
+                rpc_client,
+                payer_signer,
+                &shard_seeds,
+                shard,
+                overwrite,
+                rps_limit,
+            )
+            .await?;
+            info!(
+                "Shard {} of {} at {}: {}",
+                i + 1,
+                data_type,
+                shard_key,
+                shard_action
+            );
+            action = action.max(shard_action);
+        }
+        metrics::counter!("doublezero_contributor_rewards_record_shards_written", "type" => data_type.to_string())
+            .increment(shard_index.shard_count as u64);
+
+        index = shard_index.encode()?;
+        &index
+    } else {
+        serialized
+    };
+
+    let (record_key, record_action) = write_record(
+        rpc_client,
+        payer_signer,
+        seeds,
+        payload,
+        overwrite,
+        rps_limit,
+    )
+    .await?;
+    let action = action.max(record_action);
 
-    info!("Successfully wrote {} to {}", data_type, record_key);
-    Ok(record_key)
+    metrics::counter!("doublezero_contributor_rewards_record_writes", "type" => data_type.to_string(), "action" => action.to_string())
+        .increment(1);
+    info!("{} at {}: {}", data_type, record_key, action);
+    Ok((record_key, action))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_action() {
+        assert_eq!(record_action(None, &[1, 2, 3]), RecordAction::Created);
+        assert_eq!(
+            record_action(Some(&[1, 2, 3]), &[1, 2, 3]),
+            RecordAction::Unchanged
+        );
+        assert_eq!(
+            record_action(Some(&[1, 2, 3]), &[1, 2, 4]),
+            RecordAction::Rewritten
+        );
+        assert_eq!(
+            record_action(Some(&[1, 2, 3, 0]), &[1, 2, 3]),
+            RecordAction::Rewritten
+        );
+    }
+
+    #[test]
+    fn test_interrupted_write_is_resumed() {
+        let payload: Vec<u8> = (0..3 * WRITE_CHUNK_SIZE).map(|i| i as u8 | 1).collect();
+
+        // Created zeroed, with only the first chunk written
+        let mut existing = vec![0; payload.len()];
+        existing[..WRITE_CHUNK_SIZE].copy_from_slice(&payload[..WRITE_CHUNK_SIZE]);
+        assert_eq!(
+            record_action(Some(&existing), &payload),
+            RecordAction::Created
+        );
+        assert_eq!(
+            record_action(Some(&vec![0; payload.len()]), &payload),
+            RecordAction::Created
+        );
+
+        // A chunk holding other content means a different payload was written
+        existing[WRITE_CHUNK_SIZE] = 2;
+        assert_eq!(
+            record_action(Some(&existing), &payload),
+            RecordAction::Rewritten
+        );
+    }
+
+    #[test]
+    fn test_record_payload_requires_a_record() {
+        let record_key = Pubkey::new_unique();
+        let header = size_of::<RecordData>();
+
+        let prefunded = Account::new(1_000_000, 0, &solana_system_interface::program::ID);
+        assert!(record_payload(&record_key, &prefunded).is_err());
+
+        let truncated = Account::new(1_000_000, header - 1, &RECORD_PROGRAM_ID);
+        assert!(record_payload(&record_key, &truncated).is_err());
+
+        let record = Account::new(1_000_000, header + 3, &RECORD_PROGRAM_ID);
+        assert_eq!(record_payload(&record_key, &record).unwrap(), &[0, 0, 0]);
+    }
 }
@@ -1,7 +1,16 @@
-use anyhow::Result;
-use network_shapley::shapley::{ShapleyOutput, ShapleyValue};
-use std::collections::BTreeMap;
-use tracing::info;
+use crate::{
+    calculator::{input::ShapleyInputs, util::print_demands},
+    settings::ShapleySettings,
+};
+use anyhow::{Context, Result};
+use network_shapley::{
+    shapley::{ShapleyInput, ShapleyOutput, ShapleyValue},
+    types::Demand,
+};
+use rayon::prelude::*;
+use std::{collections::BTreeMap, time::Instant};
+use tabled::{builder::Builder as TableBuilder, settings::Style};
+use tracing::{info, warn};
 
 /// Aggregates per-city Shapley outputs using pre-calculated stake-share weights
 ///
@@ -69,6 +78,90 @@ pub fn aggregate_shapley_outputs(
     Ok(consolidated)
 }
 
+/// Compute Shapley values for each city's demands in parallel
+///
+/// # Returns
+/// Map of city to list of (operator, raw_value) tuples, ready for aggregation
+pub fn compute_per_city_outputs(
+    shapley: &ShapleySettings,
+    inputs: &ShapleyInputs,
+) -> Result<BTreeMap<String, Vec<(String, f64)>>> {
+    // Group demands by start city
+    let mut demands_by_city: BTreeMap<String, Vec<Demand>> = BTreeMap::new();
+    for demand in inputs.demands.clone() {
+        demands_by_city
+            .entry(demand.start.clone())
+            .or_default()
+            .push(demand);
+    }
+    let demand_groups: Vec<(String, Vec<Demand>)> = demands_by_city.into_iter().collect();
+
+    demand_groups
+        .par_iter()
+        .map(|(city, demands)| {
+            let city_name = city.clone();
+            let city_start = Instant::now();
+            info!(
+                "City: {city_name}, Demand: \n{}",
+                print_demands(demands, 1_000_000)
+            );
+
+            // Build shapley inputs
+            let input = ShapleyInput {
+                private_links: inputs.private_links.clone(),
+                devices: inputs.devices.clone(),
+                demands: demands.clone(),
+                public_links: inputs.public_links.clone(),
+                operator_uptime: shapley.operator_uptime,
+                contiguity_bonus: shapley.contiguity_bonus,
+                demand_multiplier: shapley.demand_multiplier,
+            };
+
+            // Shapley output
+            let output = input
+                .compute()
+                .map_err(|err| {
+                    metrics::counter!(
+                        "doublezero_contributor_rewards_shapley_computations_failed",
+                        "city" => city_name.clone()
+                    )
+                    .increment(1);
+                    warn!(error = ?err, city = %city_name, "Failed to compute Shapley values");
+                    err
+                })
+                .with_context(|| format!("failed to compute Shapley values for {city_name}"))?;
+
+            // Track Shapley computation metrics
+            metrics::histogram!(
+                "doublezero_contributor_rewards_shapley_computation_duration",
+                "city" => city_name.clone()
+            )
+            .record(city_start.elapsed().as_secs_f64());
+            metrics::counter!(
+                "doublezero_contributor_rewards_shapley_computations",
+                "city" => city_name.clone()
+            )
+            .increment(1);
+
+            // Print per-city table
+            let table = TableBuilder::from(output.clone())
+                .build()
+                .with(Style::psql().remove_horizontals())
+                .to_string();
+            info!("Shapley Output for {city_name}:\n{}", table);
+
+            // Store raw values for aggregation
+            let city_values: Vec<(String, f64)> = output
+                .into_iter()
+                .map(|(operator, shapley_value)| (operator, shapley_value.value))
+                .collect();
+
+            Ok((city_name, city_values))
+        })
+        .collect::<Result<Vec<_>>>()
+        .map(|outputs| outputs.into_iter().collect())
+}
+
 /// Round a float to specified decimal places
 fn round_to_decimals(value: f64, decimals: u32) -> f64 {
     let multiplier = 10_f64.powi(decimals as i32);
@@ -1,11 +1,12 @@
 use crate::{
-    calculator::constants::{BPS_TO_GBPS, DEFAULT_EDGE_BANDWIDTH_GBPS, SEC_TO_MS, SEC_TO_US},
+    calculator::constants::{DEFAULT_EDGE_BANDWIDTH_GBPS, SEC_TO_MS},
+    codes::ExchangeCode,
     ingestor::{demand, fetcher::Fetcher, types::FetchData},
     processor::{
-        constants::PENALTY_RTT_US, internet::InternetTelemetryStatMap,
+        bandwidth::LinkBandwidth, constants::PENALTY_RTT_US, internet::InternetTelemetryStatMap,
         telemetry::DZDTelemetryStatMap,
     },
-    settings::{Settings, network::Network},
+    settings::Settings,
 };
 use anyhow::Result;
 use doublezero_serviceability::state::{
@@ -16,7 +17,7 @@ use network_shapley::types::{
 };
 use solana_sdk::pubkey::Pubkey;
 use std::collections::BTreeMap;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 // (city1_code, city2_code)
 type CityPair = (String, String);
@@ -128,23 +129,12 @@ pub fn build_public_links(
             .exchanges
             .get(&device.exchange_pk)
         {
-            match settings.network {
-                Network::MainnetBeta | Network::Mainnet => {
-                    // NOTE: On mainnet-beta, the exchange struct has the city itself as its code
-                    // so we can directly use device's exchange pk -> exchange code
-                    exchange_to_location.insert(device.exchange_pk, exchange.code.clone());
-                }
-                Network::Testnet | Network::Devnet => {
-                    // NOTE: On testnet, the exchange codes are prefixed by 'x', we can strip and use that
-                    // This is unwise to be fair, but if we "standardize" that the exchanges which are on
-                    // testnet will always have the 'x' prefix, this will be just fine
-                    let ex_code = if let Some(c) = exchange.code.strip_prefix('x') {
-                        c.to_string()
-                    } else {
-                        exchange.code.clone()
-                    };
-                    exchange_to_location.insert(device.exchange_pk, ex_code);
+            // Exchange codes were validated on ingestion, so an error here is a bad prefix
+            match ExchangeCode::parse(&exchange.code).and_then(|code| code.city(settings.network)) {
+                Ok(city) => {
+                    exchange_to_location.insert(device.exchange_pk, city.to_string());
                 }
+                Err(e) => warn!("No location for exchange {}: {e}", device.exchange_pk),
             }
         }
     }
@@ -269,8 +259,8 @@ pub fn build_private_links(
             _ => continue,
         };
 
-        // Convert bandwidth from bits/sec to Gbps for network-shapley
-        let bandwidth_gbps = (link.bandwidth / BPS_TO_GBPS) as f64;
+        // Declared on the link account in bits/sec
+        let bandwidth = LinkBandwidth::new(link.bandwidth);
 
         // Create circuit key to match telemetry stats
         let circuit_key = format!("{}:{}:{}", link.side_a_pk, link.side_z_pk, link_pk);
@@ -339,27 +329,28 @@ pub fn build_private_links(
             PENALTY_RTT_US
         };
 
-        let uptime = stats
-            .map(|stats| {
-                // Calculate time range in seconds
-                let time_range_seconds =
-                    (fetch_data.end_us.saturating_sub(fetch_data.start_us)) as f64 / SEC_TO_US;
-
-                // Expected samples: one every 10 seconds
-                let expected_samples = time_range_seconds / 10.0;
-
-                // Uptime = actual samples / expected samples
-                if expected_samples > 0.0 {
-                    (stats.total_samples as f64 / expected_samples).clamp(0.0, 1.0)
-                } else {
-                    0.0
-                }
-            })
-            .unwrap_or(0.0); // Default to 0% if no stats found
+        // Derived from probe continuity, with maintenance windows excluded
+        let uptime = stats.map(|stats| stats.uptime).unwrap_or(0.0); // Default to 0% if no stats found
 
         // Convert latency from microseconds to milliseconds
         let latency_ms = latency_us / SEC_TO_MS;
 
+        // The declared bandwidth is still used, flagged links are surfaced for review
+        let bandwidth_flag = bandwidth.check(stats);
+        if bandwidth_flag.is_flagged() {
+            warn!(
+                "Private link {} → {} ({link_pk}) declares {:.3} Gbps which looks inconsistent: {bandwidth_flag}",
+                from_device.code,
+                to_device.code,
+                bandwidth.gbps()
+            );
+            metrics::counter!(
+                "doublezero_contributor_rewards_link_bandwidth_flagged",
+                "flag" => bandwidth_flag.kind()
+            )
+            .increment(1);
+        }
+
         // network-shapley-rs expects the following units for PrivateLink:
         // - latency: milliseconds (ms) - we convert from microseconds
         // - bandwidth: gigabits per second (Gbps) - we convert from bits/sec
@@ -368,7 +359,7 @@ pub fn build_private_links(
             from_device.code.to_string(),
             to_device.code.to_string(),
             latency_ms,
-            bandwidth_gbps,
+            bandwidth.gbps(),
             uptime,
             None,
         ));
@@ -0,0 +1,147 @@
+//! Splitting payloads too large for a single record account
+//!
+//! A payload that fits is written to its record account unchanged. One that does not is split
+//! into shards written to accounts derived from the original seeds, and the original record
+//! holds a [`ShardIndex`] instead, so readers find it at the usual address and reassemble.
+
+use anyhow::{Result, bail, ensure};
+use borsh::{BorshDeserialize, BorshSerialize};
+use doublezero_record::state::RecordData;
+use solana_sdk::hash::{Hash, hashv};
+
+/// Largest payload a single record account can hold: the runtime's 10 MiB account limit
+/// less the record header
+pub const MAX_RECORD_DATA_LEN: usize = 10 * 1024 * 1024 - size_of::<RecordData>();
+
+/// Marks a record whose payload is a shard index rather than the data itself
+///
+/// Borsh payloads never start with these bytes in practice: read as a length prefix they
+/// would claim billions of entries.
+const SHARD_INDEX_MAGIC: &[u8; 8] = b"DZSHARDS";
+
+/// Seed distinguishing shard accounts from the record they belong to
+const SHARD_SEED: &[u8] = b"shard";
+
+/// Stored in place of an oversized payload, pointing at its shards
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct ShardIndex {
+    pub total_len: u64,
+    pub shard_count: u32,
+    /// Hash of the full payload, checked after reassembly
+    pub payload_hash: [u8; 32],
+}
+
+impl ShardIndex {
+    /// Split `payload` into shards of at most `shard_len` bytes
+    pub fn split(payload: &[u8], shard_len: usize) -> (Self, Vec<&[u8]>) {
+        let shards: Vec<&[u8]> = payload.chunks(shard_len).collect();
+        let index = Self {
+            total_len: payload.len() as u64,
+            shard_count: shards.len() as u32,
+            payload_hash: hashv(&[payload]).to_bytes(),
+        };
+        (index, shards)
+    }
+
+    /// Record payload holding this index
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        let mut bytes = SHARD_INDEX_MAGIC.to_vec();
+        bytes.extend(borsh::to_vec(self)?);
+        Ok(bytes)
+    }
+
+    /// The index stored in a record payload, if the payload is one
+    ///
+    /// Bytes after the index are ignored, since a record that once held a larger payload
+    /// keeps its size.
+    pub fn decode(payload: &[u8]) -> Result<Option<Self>> {
+        match payload.strip_prefix(SHARD_INDEX_MAGIC) {
+            Some(mut index) => Ok(Some(Self::deserialize(&mut index)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Seeds for the shard account at `shard`, derived from the record's own seeds
+    pub fn shard_seeds(seeds: &[&[u8]], shard: u32) -> Vec<Vec<u8>> {
+        seeds
+            .iter()
+            .map(|seed| seed.to_vec())
+            .chain([SHARD_SEED.to_vec(), shard.to_le_bytes().to_vec()])
+            .collect()
+    }
+
+    /// Join shards back into the payload, checking length and hash
+    pub fn reassemble(&self, shards: Vec<Vec<u8>>) -> Result<Vec<u8>> {
+        ensure!(
+            shards.len() == self.shard_count as usize,
+            "Expected {} shards, found {}",
+            self.shard_count,
+            shards.len()
+        );
+
+        let payload = shards.concat();
+        ensure!(
+            payload.len() as u64 == self.total_len,
+            "Reassembled payload is {} bytes, expected {}",
+            payload.len(),
+            self.total_len
+        );
+        if hashv(&[&payload]) != Hash::new_from_array(self.payload_hash) {
+            bail!("Reassembled payload hash does not match its shard index");
+        }
+
+        Ok(payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_and_reassemble() {
+        let payload: Vec<u8> = (0..10_000u32).map(|i| i as u8).collect();
+        let (index, shards) = ShardIndex::split(&payload, 4_096);
+        assert_eq!(index.shard_count, 3);
+        assert!(shards.iter().all(|shard| shard.len() <= 4_096));
+
+        let mut encoded = index.encode().unwrap();
+        encoded.extend([0; 16]);
+        let decoded = ShardIndex::decode(&encoded).unwrap().unwrap();
+        assert_eq!(decoded, index);
+
+        let shards = shards.into_iter().map(<[u8]>::to_vec).collect();
+        assert_eq!(decoded.reassemble(shards).unwrap(), payload);
+    }
+
+    #[test]
+    fn test_reassemble_rejects_tampered_shards() {
+        let payload = vec![7u8; 100];
+        let (index, shards) = ShardIndex::split(&payload, 40);
+
+        let mut tampered: Vec<Vec<u8>> = shards.into_iter().map(<[u8]>::to_vec).collect();
+        tampered[1][0] = 8;
+        assert!(index.reassemble(tampered.clone()).is_err());
+
+        tampered.pop();
+        assert!(index.reassemble(tampered).is_err());
+    }
+
+    #[test]
+    fn test_plain_payload_is_not_an_index() {
+        let payload = borsh::to_vec(&vec![1u64, 2, 3]).unwrap();
+        assert!(ShardIndex::decode(&payload).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_shard_seeds_are_distinct() {
+        let seeds: &[&[u8]] = &[b"prefix", &7u64.to_le_bytes()];
+        let first = ShardIndex::shard_seeds(seeds, 0);
+        let second = ShardIndex::shard_seeds(seeds, 1);
+        assert_ne!(first, second);
+        assert_eq!(
+            &first[..2],
+            &[b"prefix".to_vec(), 7u64.to_le_bytes().to_vec()]
+        );
+    }
+}
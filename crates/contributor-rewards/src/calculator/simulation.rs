@@ -0,0 +1,323 @@
+//! Projected impact of onboarding a contributor
+//!
+//! A prospective contributor's devices and links, with the telemetry performance they expect to
+//! deliver, are added to an epoch's Shapley inputs. Running the calculation with and without them
+//! shows the share the contributor would earn and how much of it comes out of existing operators'
+//! shares. Reward pools are not applied, so shares are plain Shapley proportions.
+
+use crate::{
+    calculator::{
+        constants::DEFAULT_EDGE_BANDWIDTH_GBPS,
+        eligibility::exclude_operators,
+        input::ShapleyInputs,
+        shapley_aggregator::{aggregate_shapley_outputs, compute_per_city_outputs},
+    },
+    settings::ShapleySettings,
+};
+use anyhow::{Context, Result, bail, ensure};
+use network_shapley::{
+    shapley::ShapleyOutput,
+    types::{Device, PrivateLink},
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeSet, path::Path};
+
+fn default_edge_gbps() -> u32 {
+    DEFAULT_EDGE_BANDWIDTH_GBPS
+}
+
+/// Hypothetical device, one row of the devices CSV
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulatedDevice {
+    /// Device code, placed in a city the same way existing device codes are
+    pub code: String,
+    /// Name the contributor is reported under
+    pub operator: String,
+    #[serde(default = "default_edge_gbps")]
+    pub edge_gbps: u32,
+}
+
+/// Hypothetical link with its assumed performance, one row of the links CSV
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulatedLink {
+    pub device_a: String,
+    pub device_z: String,
+    pub latency_ms: f64,
+    pub bandwidth_gbps: f64,
+    /// Fraction between 0.0 and 1.0
+    pub uptime: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContributorSimulation {
+    pub devices: Vec<SimulatedDevice>,
+    pub links: Vec<SimulatedLink>,
+}
+
+impl ContributorSimulation {
+    pub fn from_csv_files(devices_path: &Path, links_path: &Path) -> Result<Self> {
+        Ok(Self {
+            devices: read_csv(devices_path)?,
+            links: read_csv(links_path)?,
+        })
+    }
+
+    /// Operators the simulated devices belong to
+    pub fn operators(&self) -> BTreeSet<&str> {
+        self.devices
+            .iter()
+            .map(|device| device.operator.as_str())
+            .collect()
+    }
+
+    /// Add the simulated devices and links to `inputs`
+    pub fn apply(&self, inputs: &ShapleyInputs) -> Result<ShapleyInputs> {
+        self.validate(inputs)?;
+
+        let mut inputs = inputs.clone();
+        inputs
+            .devices
+            .extend(self.devices.iter().map(|device| Device {
+                device: device.code.clone(),
+                edge: device.edge_gbps,
+                operator: device.operator.clone(),
+            }));
+        inputs.private_links.extend(self.links.iter().map(|link| {
+            PrivateLink::new(
+                link.device_a.clone(),
+                link.device_z.clone(),
+                link.latency_ms,
+                link.bandwidth_gbps,
+                link.uptime,
+                None,
+            )
+        }));
+
+        Ok(inputs)
+    }
+
+    fn validate(&self, inputs: &ShapleyInputs) -> Result<()> {
+        ensure!(!self.devices.is_empty(), "No devices to simulate");
+
+        let existing_codes: BTreeSet<&str> = inputs
+            .devices
+            .iter()
+            .map(|device| device.device.as_str())
+            .collect();
+        let mut simulated_codes = BTreeSet::new();
+        for device in &self.devices {
+            if existing_codes.contains(device.code.as_str()) {
+                bail!("Device {} already exists in the network", device.code);
+            }
+            if !simulated_codes.insert(device.code.as_str()) {
+                bail!("Device {} is listed more than once", device.code);
+            }
+        }
+
+        for link in &self.links {
+            for code in [&link.device_a, &link.device_z] {
+                ensure!(
+                    simulated_codes.contains(code.as_str())
+                        || existing_codes.contains(code.as_str()),
+                    "Link {} → {} references unknown device {code}",
+                    link.device_a,
+                    link.device_z
+                );
+            }
+            ensure!(
+                link.latency_ms > 0.0 && link.bandwidth_gbps > 0.0,
+                "Link {} → {} needs a positive latency and bandwidth",
+                link.device_a,
+                link.device_z
+            );
+            ensure!(
+                (0.0..=1.0).contains(&link.uptime),
+                "Link {} → {} uptime must be between 0.0 and 1.0",
+                link.device_a,
+                link.device_z
+            );
+        }
+
+        Ok(())
+    }
+}
+
+fn read_csv<T: for<'de> Deserialize<'de>>(path: &Path) -> Result<Vec<T>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_path(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+
+    reader
+        .deserialize()
+        .collect::<Result<Vec<T>, _>>()
+        .with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Consolidated Shapley output for `inputs`, with ineligible operators excluded
+pub fn project_shapley_output(
+    shapley: &ShapleySettings,
+    inputs: &ShapleyInputs,
+) -> Result<ShapleyOutput> {
+    let per_city_outputs = compute_per_city_outputs(shapley, inputs)?;
+    let shapley_output = aggregate_shapley_outputs(&per_city_outputs, &inputs.city_weights)?;
+    Ok(exclude_operators(shapley_output, &inputs.exclusions))
+}
+
+/// An operator's share before and after the simulated onboarding
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OperatorImpact {
+    pub operator: String,
+    pub simulated: bool,
+    pub baseline_proportion: f64,
+    pub projected_proportion: f64,
+    /// Projected minus baseline proportion, negative when diluted
+    pub change: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulationReport {
+    pub epoch: u64,
+    /// Combined projected share of the simulated operators
+    pub simulated_proportion: f64,
+    /// Share existing operators give up, summed over those diluted
+    pub diluted_proportion: f64,
+    /// Sorted by projected share, descending
+    pub operators: Vec<OperatorImpact>,
+}
+
+impl SimulationReport {
+    pub fn new(
+        epoch: u64,
+        simulation: &ContributorSimulation,
+        baseline: &ShapleyOutput,
+        projected: &ShapleyOutput,
+    ) -> Self {
+        let simulated_operators = simulation.operators();
+        let proportion = |output: &ShapleyOutput, operator: &str| {
+            output.get(operator).map_or(0.0, |value| value.proportion)
+        };
+
+        let mut operators: Vec<OperatorImpact> = baseline
+            .keys()
+            .chain(projected.keys())
+            .map(String::as_str)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(|operator| {
+                let baseline_proportion = proportion(baseline, operator);
+                let projected_proportion = proportion(projected, operator);
+                OperatorImpact {
+                    operator: operator.to_string(),
+                    simulated: simulated_operators.contains(operator),
+                    baseline_proportion,
+                    projected_proportion,
+                    change: projected_proportion - baseline_proportion,
+                }
+            })
+            .collect();
+        operators.sort_by(|a, b| b.projected_proportion.total_cmp(&a.projected_proportion));
+
+        let simulated_proportion = operators
+            .iter()
+            .filter(|impact| impact.simulated)
+            .map(|impact| impact.projected_proportion)
+            .sum();
+        let diluted_proportion = operators
+            .iter()
+            .filter(|impact| !impact.simulated && impact.change < 0.0)
+            .map(|impact| -impact.change)
+            .sum();
+
+        Self {
+            epoch,
+            simulated_proportion,
+            diluted_proportion,
+            operators,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use network_shapley::shapley::ShapleyValue;
+    use std::collections::BTreeMap;
+
+    fn inputs() -> ShapleyInputs {
+        ShapleyInputs {
+            devices: vec![Device {
+                device: "FRA1".to_string(),
+                edge: 10,
+                operator: "Alpha".to_string(),
+            }],
+            private_links: vec![],
+            public_links: vec![],
+            demands: vec![],
+            city_stats: BTreeMap::new(),
+            city_weights: BTreeMap::new(),
+            exclusions: vec![],
+        }
+    }
+
+    fn simulation(device_z: &str, uptime: f64) -> ContributorSimulation {
+        ContributorSimulation {
+            devices: vec![SimulatedDevice {
+                code: "AMS1".to_string(),
+                operator: "Newcomer".to_string(),
+                edge_gbps: 10,
+            }],
+            links: vec![SimulatedLink {
+                device_a: "AMS1".to_string(),
+                device_z: device_z.to_string(),
+                latency_ms: 7.5,
+                bandwidth_gbps: 10.0,
+                uptime,
+            }],
+        }
+    }
+
+    fn output(shares: &[(&str, f64)]) -> ShapleyOutput {
+        shares
+            .iter()
+            .map(|(operator, proportion)| {
+                (
+                    operator.to_string(),
+                    ShapleyValue {
+                        value: *proportion * 100.0,
+                        proportion: *proportion,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_apply() {
+        let simulated = simulation("FRA1", 0.99).apply(&inputs()).unwrap();
+        assert_eq!(simulated.devices.len(), 2);
+        assert_eq!(simulated.devices[1].operator, "Newcomer");
+        assert_eq!(simulated.private_links.len(), 1);
+
+        assert!(simulation("LON1", 0.99).apply(&inputs()).is_err());
+        assert!(simulation("FRA1", 1.5).apply(&inputs()).is_err());
+
+        let mut duplicate = simulation("FRA1", 0.99);
+        duplicate.devices[0].code = "FRA1".to_string();
+        assert!(duplicate.apply(&inputs()).is_err());
+    }
+
+    #[test]
+    fn test_report() {
+        let baseline = output(&[("Alpha", 0.6), ("Beta", 0.4)]);
+        let projected = output(&[("Alpha", 0.5), ("Beta", 0.35), ("Newcomer", 0.15)]);
+        let report = SimulationReport::new(7, &simulation("FRA1", 0.99), &baseline, &projected);
+
+        assert_eq!(report.operators.len(), 3);
+        assert_eq!(report.operators[0].operator, "Alpha");
+        assert!((report.operators[0].change + 0.1).abs() < 1e-9);
+        assert!(report.operators[2].simulated);
+        assert!((report.simulated_proportion - 0.15).abs() < 1e-9);
+        assert!((report.diluted_proportion - 0.15).abs() < 1e-9);
+    }
+}
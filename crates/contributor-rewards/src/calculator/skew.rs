@@ -0,0 +1,223 @@
+//! Reward share skew against recent epochs
+//!
+//! Data or regression issues can produce rewards that look plausible on their own but move a
+//! large share between operators. After each calculation, every operator's share is compared
+//! against its average over the preceding `skew.trailing_epochs` published epochs;
+//! operators absent from an epoch count as a zero share for it.
+
+use crate::{
+    calculator::{
+        constants::MAX_UNIT_SHARE,
+        ledger_operations::{fetch_shapley_output, get_rewards_accountant},
+    },
+    ingestor::fetcher::Fetcher,
+    settings::Settings,
+};
+use anyhow::Result;
+use doublezero_revenue_distribution::types::RewardShare;
+use serde::Serialize;
+use serde_json::json;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{BTreeMap, BTreeSet};
+use tabled::{Table, Tabled, settings::Style};
+use tracing::{debug, info, warn};
+
+/// An operator whose share moved further than allowed from its trailing average
+#[derive(Debug, Clone, PartialEq, Serialize, Tabled)]
+pub struct ShareSkew {
+    pub operator: String,
+    #[tabled(display = "display_percent")]
+    pub share: f64,
+    #[tabled(display = "display_percent")]
+    pub trailing_share: f64,
+    #[tabled(display = "display_percent")]
+    pub shift: f64,
+}
+
+impl ShareSkew {
+    pub fn describe(&self) -> String {
+        format!(
+            "operator {} share {} vs trailing {} ({:+.2} points)",
+            self.operator,
+            display_percent(&self.share),
+            display_percent(&self.trailing_share),
+            self.shift * 100.0
+        )
+    }
+}
+
+fn display_percent(value: &f64) -> String {
+    format!("{:.2}%", value * 100.0)
+}
+
+/// Each contributor's fraction of the total rewards
+pub fn shares(rewards: &[RewardShare]) -> BTreeMap<Pubkey, f64> {
+    rewards
+        .iter()
+        .map(|reward| {
+            (
+                reward.contributor_key,
+                reward.unit_share as f64 / MAX_UNIT_SHARE,
+            )
+        })
+        .collect()
+}
+
+/// Operators whose current share differs from their trailing average by more than
+/// `max_share_shift`, largest shift first
+///
+/// Nothing is reported without any trailing epochs to compare against.
+pub fn find_skews(
+    current: &BTreeMap<Pubkey, f64>,
+    trailing: &[BTreeMap<Pubkey, f64>],
+    max_share_shift: f64,
+) -> Vec<ShareSkew> {
+    if trailing.is_empty() {
+        return Vec::new();
+    }
+
+    let operators: BTreeSet<&Pubkey> = current
+        .keys()
+        .chain(trailing.iter().flat_map(|epoch| epoch.keys()))
+        .collect();
+
+    let mut skews: Vec<ShareSkew> = operators
+        .into_iter()
+        .filter_map(|operator| {
+            let share = current.get(operator).copied().unwrap_or_default();
+            let trailing_share = trailing
+                .iter()
+                .map(|epoch| epoch.get(operator).copied().unwrap_or_default())
+                .sum::<f64>()
+                / trailing.len() as f64;
+            let shift = share - trailing_share;
+
+            (shift.abs() > max_share_shift).then(|| ShareSkew {
+                operator: operator.to_string(),
+                share,
+                trailing_share,
+                shift,
+            })
+        })
+        .collect();
+    skews.sort_by(|a, b| b.shift.abs().total_cmp(&a.shift.abs()));
+
+    skews
+}
+
+/// Compare `rewards` for `epoch` against the shares published for the preceding epochs
+pub async fn check_reward_skew(
+    settings: &Settings,
+    fetcher: &Fetcher,
+    epoch: u64,
+    rewards: &[RewardShare],
+) -> Result<Vec<ShareSkew>> {
+    let trailing_epochs = settings.skew.trailing_epochs;
+    let max_share_shift = settings.skew.max_share_shift;
+    if trailing_epochs == 0 {
+        return Ok(Vec::new());
+    }
+
+    let rewards_accountant = get_rewards_accountant(&fetcher.solana_write_client, None).await?;
+
+    let mut trailing = Vec::new();
+    for trailing_epoch in epoch.saturating_sub(trailing_epochs)..epoch {
+        match fetch_shapley_output(settings, fetcher, &rewards_accountant, trailing_epoch).await? {
+            Some(storage) => trailing.push(shares(&storage.rewards)),
+            None => debug!("No shapley output for epoch {trailing_epoch}, skipping"),
+        }
+    }
+
+    if trailing.is_empty() {
+        info!(
+            "No rewards published in the {trailing_epochs} epochs before {epoch}, skipping skew analysis"
+        );
+        return Ok(Vec::new());
+    }
+
+    let skews = find_skews(&shares(rewards), &trailing, max_share_shift);
+    metrics::gauge!("doublezero_contributor_rewards_skewed_operators").set(skews.len() as f64);
+
+    if skews.is_empty() {
+        info!(
+            "Reward shares for epoch {epoch} are within {} of the {}-epoch trailing average",
+            display_percent(&max_share_shift),
+            trailing.len()
+        );
+    } else {
+        warn!(
+            "{} operators' reward shares for epoch {epoch} shifted more than {} from the {}-epoch trailing average:\n{}",
+            skews.len(),
+            display_percent(&max_share_shift),
+            trailing.len(),
+            Table::new(&skews).with(Style::psql().remove_horizontals())
+        );
+    }
+
+    Ok(skews)
+}
+
+/// Post the skewed shares of `epoch` to `webhook_url`
+pub async fn notify_skew(webhook_url: &str, epoch: u64, skews: &[ShareSkew]) -> Result<()> {
+    let body = json!({
+        "event": "reward_share_skew",
+        "message": format!(
+            "{} operators' reward shares for epoch {epoch} shifted beyond the configured threshold",
+            skews.len()
+        ),
+        "details": {
+            "epoch": epoch,
+            "operators": skews,
+        },
+    });
+
+    reqwest::Client::new()
+        .post(webhook_url)
+        .json(&body)
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stable_shares_are_not_skewed() {
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let current = BTreeMap::from([(a, 0.52), (b, 0.48)]);
+        let trailing = vec![
+            BTreeMap::from([(a, 0.5), (b, 0.5)]),
+            BTreeMap::from([(a, 0.55), (b, 0.45)]),
+        ];
+
+        assert!(find_skews(&current, &trailing, 0.05).is_empty());
+        assert!(find_skews(&current, &[], 0.05).is_empty());
+    }
+
+    #[test]
+    fn test_redistributed_shares_are_skewed() {
+        let (a, b, c) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        // c drops out and its share moves to a, along with some of b's
+        let current = BTreeMap::from([(a, 0.75), (b, 0.25)]);
+        let trailing = vec![
+            BTreeMap::from([(a, 0.4), (b, 0.3), (c, 0.3)]),
+            BTreeMap::from([(a, 0.4), (b, 0.3), (c, 0.3)]),
+        ];
+
+        let skews = find_skews(&current, &trailing, 0.1);
+        assert_eq!(skews.len(), 2);
+        assert_eq!(skews[0].operator, a.to_string());
+        assert!((skews[0].shift - 0.35).abs() < 1e-9);
+        assert_eq!(skews[1].operator, c.to_string());
+        assert_eq!(skews[1].share, 0.0);
+        assert!((skews[1].shift + 0.3).abs() < 1e-9);
+    }
+}
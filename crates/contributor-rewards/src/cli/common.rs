@@ -1,14 +1,26 @@
-use crate::cli::traits::Exportable;
+use crate::{
+    cli::{presenter, traits::Exportable},
+    settings::{OutputSettings, Settings},
+};
 use anyhow::Result;
+use chrono::Utc;
 use clap::{Args, ValueEnum};
 use serde::{Deserialize, Serialize};
 use std::{
     fmt,
-    fs::{File, create_dir_all},
+    fs::{self, File, create_dir_all},
     io::Write,
-    path::Path,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
 };
-use tracing::info;
+use tracing::{info, warn};
+
+// Placeholders substituted into --output-dir and --output-file
+const EPOCH_PLACEHOLDER: &str = "{epoch}";
+const DATE_PLACEHOLDER: &str = "{date}";
+const COMMAND_PLACEHOLDER: &str = "{command}";
+const NETWORK_PLACEHOLDER: &str = "{network}";
+const DATE_FORMAT: &str = "%Y-%m-%d";
 
 /// Unified output format for all CLI commands
 #[derive(Debug, Clone, Copy, ValueEnum, Serialize, Deserialize)]
@@ -39,15 +51,40 @@ pub struct OutputOptions {
     pub output_format: OutputFormat,
 
     /// Directory to export files
+    /// May contain {epoch}, {date}, {command} and {network} placeholders
     #[arg(short = 'o', long, value_name = "DIR")]
     pub output_dir: Option<String>,
 
     /// Specific output file path
+    /// May contain {epoch}, {date}, {command} and {network} placeholders
     #[arg(long, value_name = "FILE")]
     pub output_file: Option<String>,
 }
 
 impl OutputOptions {
+    /// Resolve path placeholders for this export and apply the configured retention policy
+    ///
+    /// Retention only prunes directories created from the same `--output-dir` template, and
+    /// never the one being written to. Pruning failures are logged rather than returned.
+    /// `{network}` keeps exports from different networks apart when they share a root.
+    pub fn prepare(&self, settings: &Settings, command: &str, epoch: u64) -> Self {
+        let date = Utc::now().format(DATE_FORMAT).to_string();
+        let network = settings.network.to_string();
+        let render = |template: &String| render_template(template, command, epoch, &date, &network);
+
+        if let Some(template) = &self.output_dir
+            && settings.output.has_retention()
+        {
+            prune_output_dirs(template, &render(template), &settings.output, epoch);
+        }
+
+        Self {
+            output_format: self.output_format,
+            output_dir: self.output_dir.as_ref().map(render),
+            output_file: self.output_file.as_ref().map(render),
+        }
+    }
+
     /// Write exportable data to file or stdout
     pub fn write<T: Exportable>(&self, data: &T, default_filename: &str) -> Result<()> {
         let content = data.export(self.output_format)?;
@@ -79,13 +116,141 @@ impl OutputOptions {
             info!("Exported to: {}", file_path.display());
         } else {
             // Write to stdout
-            println!("{content}");
+            presenter::output(content);
         }
 
         Ok(())
     }
 }
 
+fn render_template(template: &str, command: &str, epoch: u64, date: &str, network: &str) -> String {
+    template
+        .replace(EPOCH_PLACEHOLDER, &epoch.to_string())
+        .replace(DATE_PLACEHOLDER, date)
+        .replace(COMMAND_PLACEHOLDER, command)
+        .replace(NETWORK_PLACEHOLDER, network)
+}
+
+/// Remove sibling export directories that fall outside the retention policy
+///
+/// The directory containing the first templated path component is scanned, and only entries
+/// whose names match that component's template are considered.
+fn prune_output_dirs(template: &str, current: &str, settings: &OutputSettings, epoch: u64) {
+    let mut base = PathBuf::new();
+    let mut pattern = None;
+    for component in Path::new(template).components() {
+        let component = component.as_os_str().to_string_lossy();
+        if component.contains('{') {
+            pattern = Some(component.into_owned());
+            break;
+        }
+        base.push(component.as_ref());
+    }
+    let Some(pattern) = pattern else {
+        return;
+    };
+    let current = Path::new(current);
+
+    let entries = match fs::read_dir(&base) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    let now = SystemTime::now();
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_dir() || current.starts_with(&path) {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let Some(entry_epoch) = match_component(&pattern, &name) else {
+            continue;
+        };
+
+        let expired_by_epoch = matches!(
+            (settings.retention_epochs, entry_epoch),
+            (Some(keep), Some(entry_epoch)) if entry_epoch.saturating_add(keep) < epoch
+        );
+        let expired_by_age = settings.retention_days.is_some_and(|days| {
+            entry
+                .metadata()
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .and_then(|modified| now.duration_since(modified).ok())
+                .is_some_and(|age| age > Duration::from_secs(days * 86_400))
+        });
+
+        if expired_by_epoch || expired_by_age {
+            match fs::remove_dir_all(&path) {
+                Ok(()) => info!("Pruned export directory {}", path.display()),
+                Err(e) => warn!("Failed to prune export directory {}: {e}", path.display()),
+            }
+        }
+    }
+}
+
+/// Match a directory name against a templated path component
+///
+/// Returns `None` when the name does not match, otherwise the `{epoch}` value if the
+/// template contains one.
+fn match_component(pattern: &str, name: &str) -> Option<Option<u64>> {
+    let mut epoch = None;
+    match_from(pattern, name, &mut epoch).then_some(epoch)
+}
+
+fn match_from(pattern: &str, name: &str, epoch: &mut Option<u64>) -> bool {
+    let Some(start) = pattern.find('{') else {
+        return pattern == name;
+    };
+    let Some(rest) = name.strip_prefix(&pattern[..start]) else {
+        return false;
+    };
+    let pattern = &pattern[start..];
+
+    let (placeholder, pattern_rest) = match [
+        EPOCH_PLACEHOLDER,
+        DATE_PLACEHOLDER,
+        COMMAND_PLACEHOLDER,
+        NETWORK_PLACEHOLDER,
+    ]
+    .into_iter()
+    .find(|placeholder| pattern.starts_with(placeholder))
+    {
+        Some(placeholder) => (placeholder, &pattern[placeholder.len()..]),
+        // Not a known placeholder; treat the brace literally
+        None => {
+            return rest.starts_with('{') && match_from(&pattern[1..], &rest[1..], epoch);
+        }
+    };
+
+    // Try the longest candidate first for each placeholder
+    let accepts = |candidate: &str| match placeholder {
+        EPOCH_PLACEHOLDER => candidate.chars().all(|c| c.is_ascii_digit()),
+        DATE_PLACEHOLDER => {
+            candidate.len() == 10
+                && chrono::NaiveDate::parse_from_str(candidate, DATE_FORMAT).is_ok()
+        }
+        _ => candidate
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'),
+    };
+    for end in (1..=rest.len()).rev() {
+        if !rest.is_char_boundary(end) || !accepts(&rest[..end]) {
+            continue;
+        }
+        let mut captured = *epoch;
+        if placeholder == EPOCH_PLACEHOLDER {
+            captured = rest[..end].parse().ok();
+        }
+        if match_from(pattern_rest, &rest[end..], &mut captured) {
+            *epoch = captured;
+            return true;
+        }
+    }
+
+    false
+}
+
 /// Common filter options for telemetry and other data
 #[derive(Args, Debug, Clone)]
 pub struct FilterOptions {
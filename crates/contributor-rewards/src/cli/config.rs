@@ -0,0 +1,75 @@
+use crate::settings::migration::{self, RENAMED_KEYS};
+use anyhow::{Result, bail};
+use clap::Subcommand;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommands {
+    /// Migrate an older config file and/or DZ__ environment to the current schema
+    #[command(
+        about = "Migrate an old configuration to the current schema",
+        after_help = r#"Examples:
+    # Migrate an old config file, writing the result to a new file
+    contributor-rewards -c old.config.toml config migrate --output config.toml
+
+    # Migrate settings from DZ__ environment variables (and .env) to stdout
+    contributor-rewards config migrate
+    "#
+    )]
+    Migrate {
+        /// Where to write the migrated TOML (prints to stdout if omitted)
+        #[arg(short = 'o', long, value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        /// Overwrite the output file if it already exists
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+/// Handle config commands
+///
+/// These run before settings are loaded, since the input may not match the current schema
+pub fn handle(config_path: Option<&Path>, cmd: ConfigCommands) -> Result<()> {
+    match cmd {
+        ConfigCommands::Migrate { output, force } => migrate(config_path, output, force),
+    }
+}
+
+fn migrate(config_path: Option<&Path>, output: Option<PathBuf>, force: bool) -> Result<()> {
+    let raw = migration::load_raw(config_path)?;
+    let report = migration::migrate(raw)?;
+
+    for (old, new) in &report.renamed {
+        eprintln!("deprecated: `{old}` is now `{new}`");
+    }
+    for key in &report.unknown {
+        eprintln!("unknown: `{key}` is not part of the current schema and was dropped");
+    }
+    if report.renamed.is_empty() && report.unknown.is_empty() {
+        eprintln!(
+            "Configuration already matches the current schema ({} known renames checked)",
+            RENAMED_KEYS.len()
+        );
+    }
+
+    let toml = report.to_toml()?;
+    match output {
+        Some(path) => {
+            if path.exists() && !force {
+                bail!(
+                    "{} already exists; pass --force to overwrite",
+                    path.display()
+                );
+            }
+            fs::write(&path, toml)?;
+            eprintln!("Wrote migrated configuration to {}", path.display());
+        }
+        None => print!("{toml}"),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,217 @@
+use crate::{
+    calculator::{
+        data_prep::PreparedData,
+        orchestrator::Orchestrator,
+        simulation::{ContributorSimulation, SimulationReport, project_shapley_output},
+    },
+    cli::{
+        common::{OutputFormat, OutputOptions},
+        snapshot::CompleteSnapshot,
+    },
+    ingestor::{fetcher::Fetcher, fixture},
+};
+use anyhow::{Result, bail};
+use clap::Subcommand;
+use std::path::PathBuf;
+use tabled::{builder::Builder as TableBuilder, settings::Style};
+use tracing::info;
+
+/// Development and debugging commands
+#[derive(Subcommand, Debug)]
+pub enum DebugCommands {
+    #[command(
+        about = "Export a small, anonymized test fixture from a live epoch",
+        after_help = r#"Examples:
+    # Keep every 20th latency sample of epoch 9 and write it next to the other fixtures
+    debug export-fixture --epoch 9 --scale 0.05 --output-file tests/fixture-epoch-9.json
+
+    # Keep every 10th sample and print the fixture
+    debug export-fixture --epoch 9 --scale 0.1"#
+    )]
+    ExportFixture {
+        /// DZ epoch to sample
+        #[arg(short, long, value_name = "EPOCH")]
+        epoch: u64,
+
+        /// Fraction of latency samples to keep
+        #[arg(long, default_value_t = 0.05)]
+        scale: f64,
+
+        /// Output format for export
+        #[arg(short = 'f', long, default_value = "json-pretty")]
+        output_format: OutputFormat,
+
+        /// Specific output file path
+        #[arg(long, value_name = "FILE")]
+        output_file: Option<PathBuf>,
+    },
+    #[command(
+        about = "Project the rewards of a prospective contributor and the dilution of existing operators",
+        after_help = r#"Examples:
+    # Add the devices and links of a prospective contributor to epoch 42
+    debug simulate-add --devices devices.csv --links links.csv --epoch 42
+
+    # Simulate against a snapshot and save the report as CSV
+    debug simulate-add --devices devices.csv --links links.csv --snapshot snapshot.json -f csv --output-file impact.csv
+
+devices.csv columns: code,operator[,edge_gbps]
+links.csv columns:   device_a,device_z,latency_ms,bandwidth_gbps,uptime
+
+Links may connect simulated devices to each other or to existing devices. Latency, bandwidth
+and uptime are the performance the contributor expects to deliver."#
+    )]
+    SimulateAdd {
+        /// CSV of the devices to add
+        #[arg(long, value_name = "FILE")]
+        devices: PathBuf,
+
+        /// CSV of the links to add, with their assumed telemetry performance
+        #[arg(long, value_name = "FILE")]
+        links: PathBuf,
+
+        /// DZ epoch to simulate against
+        #[arg(
+            short,
+            long,
+            value_name = "EPOCH",
+            required_unless_present = "snapshot"
+        )]
+        epoch: Option<u64>,
+
+        /// Simulate against a snapshot exported via `snapshot all` instead of chain data
+        #[arg(long, value_name = "FILE", conflicts_with = "epoch")]
+        snapshot: Option<PathBuf>,
+
+        /// Output format for export
+        #[arg(short = 'f', long, default_value = "json-pretty")]
+        output_format: OutputFormat,
+
+        /// Specific output file path
+        #[arg(long, value_name = "FILE")]
+        output_file: Option<PathBuf>,
+    },
+}
+
+/// Handle debug commands
+pub async fn handle(orchestrator: &Orchestrator, cmd: DebugCommands) -> Result<()> {
+    match cmd {
+        DebugCommands::ExportFixture {
+            epoch,
+            scale,
+            output_format,
+            output_file,
+        } => {
+            info!("Exporting fixture for DZ epoch {epoch} at scale {scale}");
+
+            let fetcher = Fetcher::from_settings(orchestrator.settings())?;
+            let (fetch_epoch, fetch_data) = fetcher.fetch(Some(epoch)).await?;
+            let fixture = fixture::build_fixture(&fetch_data, scale)?;
+
+            info!("Fixture: {fixture}");
+
+            let export_options = OutputOptions {
+                output_format,
+                output_dir: None,
+                output_file: output_file.map(|p| p.to_string_lossy().to_string()),
+            }
+            .prepare(orchestrator.settings(), "fixture", fetch_epoch);
+
+            let default_filename = format!("fixture-epoch-{fetch_epoch}");
+            export_options.write(&fixture, &default_filename)?;
+
+            Ok(())
+        }
+        DebugCommands::SimulateAdd {
+            devices,
+            links,
+            epoch,
+            snapshot,
+            output_format,
+            output_file,
+        } => {
+            handle_simulate_add(
+                orchestrator,
+                devices,
+                links,
+                epoch,
+                snapshot,
+                output_format,
+                output_file,
+            )
+            .await
+        }
+    }
+}
+
+async fn handle_simulate_add(
+    orchestrator: &Orchestrator,
+    devices: PathBuf,
+    links: PathBuf,
+    epoch: Option<u64>,
+    snapshot: Option<PathBuf>,
+    output_format: OutputFormat,
+    output_file: Option<PathBuf>,
+) -> Result<()> {
+    let settings = orchestrator.settings();
+    let simulation = ContributorSimulation::from_csv_files(&devices, &links)?;
+    info!(
+        "Simulating {} devices and {} links",
+        simulation.devices.len(),
+        simulation.links.len()
+    );
+
+    let prep_data = match snapshot {
+        Some(snapshot) => {
+            let snapshot = CompleteSnapshot::from_path(&snapshot)?;
+            PreparedData::from_snapshot(settings, &snapshot, false, true).await?
+        }
+        None => {
+            let fetcher = Fetcher::from_settings(settings)?;
+            PreparedData::new(&fetcher, epoch, true).await?
+        }
+    };
+    let Some(baseline_inputs) = prep_data.shapley_inputs else {
+        bail!("Shapley inputs required for simulation but were not prepared")
+    };
+
+    let simulated_inputs = simulation.apply(&baseline_inputs)?;
+    let baseline = project_shapley_output(&settings.shapley, &baseline_inputs)?;
+    let projected = project_shapley_output(&settings.shapley, &simulated_inputs)?;
+    let report = SimulationReport::new(prep_data.epoch, &simulation, &baseline, &projected);
+
+    let mut table_builder = TableBuilder::default();
+    table_builder.push_record(["Operator", "Baseline (%)", "Projected (%)", "Change (pp)"]);
+    for impact in &report.operators {
+        let operator = if impact.simulated {
+            format!("{} (simulated)", impact.operator)
+        } else {
+            impact.operator.clone()
+        };
+        table_builder.push_record([
+            operator,
+            format!("{:.2}", impact.baseline_proportion * 100.0),
+            format!("{:.2}", impact.projected_proportion * 100.0),
+            format!("{:+.2}", impact.change * 100.0),
+        ]);
+    }
+    let table = table_builder
+        .build()
+        .with(Style::psql().remove_horizontals())
+        .to_string();
+    info!(
+        "Projected impact for epoch {}: simulated operators {:.2}%, existing operators diluted by {:.2}pp\n{table}",
+        report.epoch,
+        report.simulated_proportion * 100.0,
+        report.diluted_proportion * 100.0
+    );
+
+    let export_options = OutputOptions {
+        output_format,
+        output_dir: None,
+        output_file: output_file.map(|p| p.to_string_lossy().to_string()),
+    }
+    .prepare(settings, "simulate-add", report.epoch);
+
+    let default_filename = format!("simulate-add-epoch-{}", report.epoch);
+    export_options.write(&report, &default_filename)
+}
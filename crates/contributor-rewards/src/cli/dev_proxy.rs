@@ -0,0 +1,54 @@
+use crate::{
+    ingestor::dev_proxy::{self, RpcCache},
+    settings::Settings,
+};
+use anyhow::Result;
+use clap::Args;
+use std::{collections::BTreeMap, net::SocketAddr, path::PathBuf, time::Duration};
+
+/// Run a local caching proxy for the DZ and Solana read RPC endpoints
+#[derive(Args, Debug)]
+#[command(after_help = r#"Examples:
+    # Start the proxy with the endpoints from the current configuration
+    contributor-rewards dev-proxy --listen 0.0.0.0:18899 --cache-dir /srv/rpc-cache
+
+    # Point a teammate's run at it
+    DZ__RPC__DZ_URL=http://proxy-host:18899/dz \
+    DZ__RPC__SOLANA_READ_URL=http://proxy-host:18899/solana \
+    contributor-rewards snapshot all --epoch 9"#)]
+pub struct DevProxyArgs {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:18899")]
+    listen: SocketAddr,
+
+    /// Directory holding cached responses (defaults to a directory under the system temp dir)
+    #[arg(long, value_name = "DIR")]
+    cache_dir: Option<PathBuf>,
+
+    /// TTL in seconds for methods without a built-in TTL
+    #[arg(long, default_value_t = 60)]
+    default_ttl_secs: u64,
+}
+
+/// Handle the dev-proxy command
+pub async fn handle(settings: &Settings, args: DevProxyArgs) -> Result<()> {
+    let DevProxyArgs {
+        listen,
+        cache_dir,
+        default_ttl_secs,
+    } = args;
+
+    let cache_dir = cache_dir.unwrap_or_else(|| {
+        std::env::temp_dir()
+            .join("doublezero-contributor-rewards")
+            .join(format!("rpc-cache-{}", settings.network))
+    });
+    let cache = RpcCache::new(cache_dir, Duration::from_secs(default_ttl_secs))?;
+
+    let upstreams = BTreeMap::from([
+        ("dz".to_string(), settings.rpc.dz_url.clone()),
+        ("solana".to_string(), settings.rpc.solana_read_url.clone()),
+    ]);
+
+    dev_proxy::serve(listen, upstreams, cache).await
+}
@@ -1,4 +1,5 @@
 use crate::{
+    calculator::simulation::SimulationReport,
     cli::{
         common::{OutputFormat, collection_to_csv, to_json_string},
         traits::Exportable,
@@ -48,3 +49,13 @@ impl Exportable for Vec<DZDTelemetryStats> {
         }
     }
 }
+
+impl Exportable for SimulationReport {
+    fn export(&self, format: OutputFormat) -> Result<String> {
+        match format {
+            OutputFormat::Csv => collection_to_csv(&self.operators),
+            OutputFormat::Json => to_json_string(self, false),
+            OutputFormat::JsonPretty => to_json_string(self, true),
+        }
+    }
+}
@@ -7,16 +7,23 @@ use crate::{
     },
     cli::{
         common::{OutputFormat, OutputOptions, to_json_string},
+        presenter,
         traits::Exportable,
     },
     ingestor::{demand, fetcher::Fetcher},
-    processor::{internet::InternetTelemetryProcessor, telemetry::DZDTelemetryProcessor},
+    processor::{
+        compare::{EpochComparison, EpochMetrics},
+        internet::InternetTelemetryProcessor,
+        telemetry::DZDTelemetryProcessor,
+        topology::TopologyGraph,
+    },
 };
 use anyhow::{Result, bail};
-use clap::Subcommand;
+use clap::{Subcommand, ValueEnum};
 use network_shapley::types::{Demand, Demands, Devices, PrivateLinks, PublicLinks};
 use solana_sdk::pubkey::Pubkey;
-use std::{collections::BTreeSet, path::PathBuf};
+use std::{collections::BTreeSet, fs, path::PathBuf};
+use tabled::{Table, settings::Style};
 use tracing::{info, warn};
 
 /// Inspect commands for analyzing rewards and Shapley calculations
@@ -85,6 +92,72 @@ pub enum InspectCommands {
         #[arg(long, value_name = "FILE")]
         output_file: Option<PathBuf>,
     },
+
+    #[command(
+        about = "Compare processed metrics between two or more epochs",
+        after_help = r#"Examples:
+    # Compare link latency, coverage and demand between epochs 41 and 42
+    inspect compare-epochs 41 42
+
+    # Walk several epochs without fetching leader schedules
+    inspect compare-epochs 40 41 42 --skip-demand
+
+    # Export the comparisons as JSON
+    inspect compare-epochs 41 42 --output-file compare-41-42.json"#
+    )]
+    CompareEpochs {
+        /// DZ epochs to compare, each against the one before it
+        #[arg(value_name = "EPOCH", num_args = 2.., required = true)]
+        epochs: Vec<u64>,
+
+        /// Skip building demand, leaving out demand shifts
+        #[arg(long)]
+        skip_demand: bool,
+
+        /// Output format for exports
+        #[arg(short = 'f', long, default_value = "json-pretty")]
+        output_format: OutputFormat,
+
+        /// Directory to export files
+        #[arg(short = 'o', long, value_name = "DIR")]
+        output_dir: Option<PathBuf>,
+
+        /// Specific output file path
+        #[arg(long, value_name = "FILE")]
+        output_file: Option<PathBuf>,
+    },
+
+    #[command(
+        about = "Export the network topology of an epoch with its latency and uptime aggregates",
+        after_help = r#"Examples:
+    # Render epoch 42 as a Graphviz graph
+    inspect topology --epoch 42 | dot -Tsvg -o topology.svg
+
+    # Export the graph structure as JSON
+    inspect topology --epoch 42 --format json --output-file topology-42.json"#
+    )]
+    Topology {
+        /// DZ epoch to export (defaults to the previous epoch)
+        #[arg(short, long, value_name = "EPOCH")]
+        epoch: Option<u64>,
+
+        /// Graph format
+        #[arg(long, value_enum, default_value_t = TopologyFormat::Graphviz)]
+        format: TopologyFormat,
+
+        /// Write to this file instead of stdout
+        #[arg(long, value_name = "FILE")]
+        output_file: Option<PathBuf>,
+    },
+}
+
+/// Output format of `inspect topology`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TopologyFormat {
+    /// Graphviz DOT
+    Graphviz,
+    /// Nodes and edges as JSON
+    Json,
 }
 
 /// Container for Shapley inputs using existing types
@@ -113,6 +186,24 @@ impl Exportable for ShapleyInputs {
     }
 }
 
+/// Comparisons between consecutive epochs given to `inspect compare-epochs`
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct EpochComparisons {
+    pub comparisons: Vec<EpochComparison>,
+}
+
+impl Exportable for EpochComparisons {
+    fn export(&self, format: OutputFormat) -> Result<String> {
+        match format {
+            OutputFormat::Csv => {
+                bail!("CSV export not supported for epoch comparisons. Use JSON format instead.")
+            }
+            OutputFormat::Json => to_json_string(self, false),
+            OutputFormat::JsonPretty => to_json_string(self, true),
+        }
+    }
+}
+
 /// Handle inspect commands
 pub async fn handle(orchestrator: &Orchestrator, cmd: InspectCommands) -> Result<()> {
     match cmd {
@@ -140,6 +231,28 @@ pub async fn handle(orchestrator: &Orchestrator, cmd: InspectCommands) -> Result
             )
             .await
         }
+        InspectCommands::CompareEpochs {
+            epochs,
+            skip_demand,
+            output_format,
+            output_dir,
+            output_file,
+        } => {
+            handle_compare_epochs(
+                orchestrator,
+                epochs,
+                skip_demand,
+                output_format,
+                output_dir,
+                output_file,
+            )
+            .await
+        }
+        InspectCommands::Topology {
+            epoch,
+            format,
+            output_file,
+        } => handle_inspect_topology(orchestrator, epoch, format, output_file).await,
     }
 }
 
@@ -253,7 +366,8 @@ async fn handle_inspect_shapley(
         output_format,
         output_dir: output_dir.map(|p| p.to_string_lossy().to_string()),
         output_file: output_file.map(|p| p.to_string_lossy().to_string()),
-    };
+    }
+    .prepare(orchestrator.settings(), "shapley-debug", fetch_epoch);
 
     let default_filename = format!(
         "shapley-debug-{}-epoch-{fetch_epoch}",
@@ -262,18 +376,169 @@ async fn handle_inspect_shapley(
     output_options.write(&shapley_inputs, &default_filename)?;
 
     // Print summary
-    println!("\nShapley Debug Summary:");
-    println!("----------------------");
-    println!("Epoch: {fetch_epoch}");
-    println!(
-        "Demands: {}",
-        if use_test_demands { "Test" } else { "Real" }
+    presenter::detail(format!(
+        "\nShapley Debug Summary:\n\
+         ----------------------\n\
+         Epoch: {fetch_epoch}\n\
+         Demands: {}\n\
+         Cities: {}\n\
+         Devices: {}\n\
+         Private Links: {}\n\
+         Public Links: {}\n\
+         Demands: {}",
+        if use_test_demands { "Test" } else { "Real" },
+        shapley_inputs.cities.len(),
+        shapley_inputs.devices.len(),
+        shapley_inputs.private_links.len(),
+        shapley_inputs.public_links.len(),
+        shapley_inputs.demands.len()
+    ));
+
+    Ok(())
+}
+
+async fn handle_compare_epochs(
+    orchestrator: &Orchestrator,
+    epochs: Vec<u64>,
+    skip_demand: bool,
+    output_format: OutputFormat,
+    output_dir: Option<PathBuf>,
+    output_file: Option<PathBuf>,
+) -> Result<()> {
+    let fetcher = Fetcher::from_settings(orchestrator.settings())?;
+
+    // Each epoch is fetched and processed independently, then compared pairwise
+    let mut metrics = Vec::with_capacity(epochs.len());
+    for epoch in &epochs {
+        info!("Fetching and processing epoch {epoch}");
+        let (fetch_epoch, fetch_data) = fetcher.fetch(Some(*epoch)).await?;
+
+        let city_stats = if skip_demand {
+            None
+        } else {
+            Some(demand::build(&fetcher, &fetch_data).await?.city_stats)
+        };
+
+        metrics.push(EpochMetrics {
+            epoch: fetch_epoch,
+            device_stats: DZDTelemetryProcessor::process(&fetch_data)?,
+            internet_stats: InternetTelemetryProcessor::process(&fetch_data)?,
+            city_stats,
+        });
+    }
+
+    let comparisons = EpochComparisons {
+        comparisons: metrics
+            .windows(2)
+            .map(|pair| EpochComparison::new(&pair[0], &pair[1]))
+            .collect(),
+    };
+
+    if output_dir.is_some() || output_file.is_some() {
+        let last_epoch = metrics.last().map_or(0, |m| m.epoch);
+        let output_options = OutputOptions {
+            output_format,
+            output_dir: output_dir.map(|p| p.to_string_lossy().to_string()),
+            output_file: output_file.map(|p| p.to_string_lossy().to_string()),
+        }
+        .prepare(orchestrator.settings(), "compare-epochs", last_epoch);
+
+        let default_filename = format!(
+            "compare-epochs-{}",
+            epochs
+                .iter()
+                .map(|epoch| epoch.to_string())
+                .collect::<Vec<_>>()
+                .join("-")
+        );
+        return output_options.write(&comparisons, &default_filename);
+    }
+
+    for comparison in &comparisons.comparisons {
+        let coverage = &comparison.coverage;
+        presenter::output(format!(
+            "\nEpoch {} -> {}\n\
+             ----------------\n\
+             Device links added: {}, removed: {}\n\
+             Internet links added: {}, removed: {}\n\
+             Missing data ratio: {:.4} -> {:.4}",
+            comparison.from_epoch,
+            comparison.to_epoch,
+            coverage.device_added.len(),
+            coverage.device_removed.len(),
+            coverage.internet_added.len(),
+            coverage.internet_removed.len(),
+            coverage.from_missing_data_ratio,
+            coverage.to_missing_data_ratio,
+        ));
+        for circuit in coverage.device_added.iter().chain(&coverage.internet_added) {
+            presenter::detail(format!("  + {circuit}"));
+        }
+        for circuit in coverage
+            .device_removed
+            .iter()
+            .chain(&coverage.internet_removed)
+        {
+            presenter::detail(format!("  - {circuit}"));
+        }
+
+        presenter::output(format!(
+            "\nLink latency deltas:\n{}",
+            Table::new(&comparison.latency_deltas).with(Style::psql().remove_horizontals())
+        ));
+        if !comparison.demand_shifts.is_empty() {
+            presenter::output(format!(
+                "\nDemand shifts:\n{}",
+                Table::new(&comparison.demand_shifts).with(Style::psql().remove_horizontals())
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_inspect_topology(
+    orchestrator: &Orchestrator,
+    epoch: Option<u64>,
+    format: TopologyFormat,
+    output_file: Option<PathBuf>,
+) -> Result<()> {
+    let fetcher = Fetcher::from_settings(orchestrator.settings())?;
+    let (fetch_epoch, fetch_data) = fetcher.fetch(epoch).await?;
+
+    let device_stats = DZDTelemetryProcessor::process_with_maintenance(
+        &fetch_data,
+        &orchestrator
+            .settings()
+            .telemetry_defaults
+            .maintenance_windows,
+    )?;
+    let internet_stats = InternetTelemetryProcessor::process(&fetch_data)?;
+
+    let graph = TopologyGraph::build(
+        fetch_epoch,
+        &fetch_data.dz_serviceability,
+        &device_stats,
+        &internet_stats,
     );
-    println!("Cities: {}", shapley_inputs.cities.len());
-    println!("Devices: {}", shapley_inputs.devices.len());
-    println!("Private Links: {}", shapley_inputs.private_links.len());
-    println!("Public Links: {}", shapley_inputs.public_links.len());
-    println!("Demands: {}", shapley_inputs.demands.len());
+    info!(
+        "Topology of epoch {fetch_epoch}: {} nodes, {} edges",
+        graph.nodes.len(),
+        graph.edges.len()
+    );
+
+    let rendered = match format {
+        TopologyFormat::Graphviz => graph.to_dot(),
+        TopologyFormat::Json => to_json_string(&graph, true)?,
+    };
+
+    match output_file {
+        Some(path) => {
+            fs::write(&path, rendered)?;
+            info!("Wrote topology to {}", path.display());
+        }
+        None => presenter::output(rendered),
+    }
 
     Ok(())
 }
@@ -1,6 +1,10 @@
 pub mod common;
+pub mod config;
+pub mod debug;
+pub mod dev_proxy;
 pub mod impls;
 pub mod inspect;
+pub mod presenter;
 pub mod rewards;
 pub mod scheduler;
 pub mod snapshot;
@@ -0,0 +1,53 @@
+//! Routing for command output.
+//!
+//! Commands produce two kinds of output: their result (an exported document or a result
+//! table) and supplementary human-readable text around it. Results always go to stdout;
+//! everything else goes to stderr, alongside the logs, so stdout can be piped straight
+//! into other tools. `--quiet` suppresses the supplementary text entirely.
+
+use clap::ValueEnum;
+use std::{
+    fmt,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Format of log lines written to stderr
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    #[default]
+    #[value(name = "text")]
+    Text,
+    #[value(name = "json")]
+    Json,
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Text => write!(f, "text"),
+            Self::Json => write!(f, "json"),
+        }
+    }
+}
+
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// Write a command's result to stdout
+pub fn output(content: impl fmt::Display) {
+    println!("{content}");
+}
+
+/// Write supplementary human-readable text to stderr, unless running quietly
+pub fn detail(content: impl fmt::Display) {
+    if !is_quiet() {
+        eprintln!("{content}");
+    }
+}
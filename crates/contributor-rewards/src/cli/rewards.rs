@@ -17,17 +17,38 @@ pub enum RewardsCommands {
     calculate-rewards --epoch 123 -k keypair.json
 
     # Dry run to preview without writing to DZ ledger
-    calculate-rewards --epoch 123 --dry-run"#
+    calculate-rewards --epoch 123 --dry-run
+
+    # Dry run from a snapshot, re-processing its embedded raw accounts
+    calculate-rewards --snapshot epoch-123-v2.json --reprocess --dry-run"#
     )]
     CalculateRewards {
         /// DZ epoch to calculate rewards for (defaults to previous epoch)
-        #[arg(short, long, value_name = "EPOCH")]
+        #[arg(short, long, value_name = "EPOCH", conflicts_with = "snapshot")]
         epoch: Option<u64>,
 
-        /// Skip writing to ledger and show what would be written
+        /// Skip writing to ledger and save what would be written to dry-run-epoch-<EPOCH>/
         #[arg(long)]
         dry_run: bool,
 
+        /// Calculate from a snapshot file exported via `snapshot all` instead of chain data.
+        /// Dry run only: snapshots do not carry the previous epoch defaults or internet
+        /// lookback a live run applies, so their results must not be published
+        #[arg(long, value_name = "FILE", requires = "dry_run")]
+        snapshot: Option<PathBuf>,
+
+        /// Re-process the snapshot's embedded raw accounts (requires a v2 snapshot)
+        #[arg(long, requires = "snapshot")]
+        reprocess: bool,
+
+        /// Publish even if the parameters do not match the governance-approved ones
+        #[arg(long)]
+        force_unapproved: bool,
+
+        /// Replace records already on the ledger that hold different content
+        #[arg(long)]
+        overwrite_records: bool,
+
         /// Path to keypair file for signing transactions
         #[arg(
             short = 'k',
@@ -62,6 +83,10 @@ pub enum RewardsCommands {
         #[arg(short = 't', long, default_value = "all", value_name = "TYPE")]
         r#type: String,
 
+        /// Replace records already on the ledger that hold different content
+        #[arg(long)]
+        overwrite_records: bool,
+
         /// Export results to CSV file
         #[arg(short = 'o', long, value_name = "FILE")]
         output_csv: Option<PathBuf>,
@@ -87,6 +112,11 @@ pub enum RewardsCommands {
         /// Rewards accountant public key (auto-fetched from ProgramConfig if not provided)
         #[arg(short = 'r', long, value_name = "PUBKEY")]
         rewards_accountant: Option<Pubkey>,
+
+        /// Total rewards distributed for the epoch, in whole tokens of the configured
+        /// denomination; shows the contributor's integer token amount
+        #[arg(long, value_name = "AMOUNT")]
+        total_amount: Option<f64>,
     },
     #[command(
         about = "Read and display the reward input configuration for an epoch",
@@ -106,6 +136,21 @@ pub enum RewardsCommands {
         #[arg(short = 'r', long, value_name = "PUBKEY")]
         rewards_accountant: Option<Pubkey>,
     },
+    #[command(
+        about = "Read the provenance record of an epoch's rewards from the ledger",
+        after_help = r#"Examples:
+    # Show which software, config and artifacts produced epoch 123's rewards
+    read-provenance --epoch 123"#
+    )]
+    ReadProvenance {
+        /// DZ epoch number to read provenance for
+        #[arg(short, long, value_name = "EPOCH")]
+        epoch: u64,
+
+        /// Rewards accountant public key (auto-fetched from ProgramConfig if not provided)
+        #[arg(short = 'r', long, value_name = "PUBKEY")]
+        rewards_accountant: Option<Pubkey>,
+    },
     #[command(
         about = "Reallocate a record account to change its size",
         after_help = r#"Examples:
@@ -189,7 +234,7 @@ pub enum RewardsCommands {
         #[arg(short, long, value_name = "EPOCH")]
         epoch: Option<u64>,
 
-        /// Skip writing to ledger and show what would be written
+        /// Skip writing to ledger and save what would be written to dry-run-epoch-<EPOCH>/
         #[arg(long)]
         dry_run: bool,
 
@@ -197,6 +242,37 @@ pub enum RewardsCommands {
         #[arg(short = 't', long, default_value = "all", value_name = "TYPE")]
         r#type: String,
 
+        /// Path to keypair file for signing transactions
+        #[arg(
+            short = 'k',
+            long,
+            value_name = "FILE",
+            required_unless_present = "dry_run"
+        )]
+        keypair: Option<PathBuf>,
+    },
+    #[command(
+        about = "Retry only the ledger record writes that failed in a previous run",
+        after_help = r#"Examples:
+    # Retry failed writes for epoch 123
+    retry-writes --epoch 123 -k keypair.json
+
+    # Show which writes would be retried
+    retry-writes --epoch 123 --dry-run"#
+    )]
+    RetryWrites {
+        /// DZ epoch whose failed writes should be retried
+        #[arg(short, long, value_name = "EPOCH")]
+        epoch: u64,
+
+        /// Save the failed writes to dry-run-epoch-<EPOCH>/ without retrying them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Replace records already on the ledger that hold different content
+        #[arg(long)]
+        overwrite_records: bool,
+
         /// Path to keypair file for signing transactions
         #[arg(
             short = 'k',
@@ -214,12 +290,30 @@ pub async fn handle(orchestrator: &Orchestrator, cmd: RewardsCommands) -> Result
         RewardsCommands::CalculateRewards {
             epoch,
             dry_run,
+            snapshot,
+            reprocess,
+            force_unapproved,
+            overwrite_records,
             keypair,
-        } => {
-            orchestrator
-                .calculate_rewards(epoch, keypair, dry_run)
-                .await
-        }
+        } => match snapshot {
+            Some(snapshot) => {
+                orchestrator
+                    .calculate_rewards_from_snapshot(
+                        &snapshot,
+                        reprocess,
+                        keypair,
+                        dry_run,
+                        force_unapproved,
+                        overwrite_records,
+                    )
+                    .await
+            }
+            None => {
+                orchestrator
+                    .calculate_rewards(epoch, keypair, dry_run, force_unapproved, overwrite_records)
+                    .await
+            }
+        },
         RewardsCommands::ReadTelemAgg {
             epoch,
             rewards_accountant,
@@ -234,9 +328,10 @@ pub async fn handle(orchestrator: &Orchestrator, cmd: RewardsCommands) -> Result
             contributor,
             epoch,
             rewards_accountant,
+            total_amount,
         } => {
             orchestrator
-                .check_contributor_reward(&contributor, epoch, rewards_accountant)
+                .check_contributor_reward(&contributor, epoch, rewards_accountant, total_amount)
                 .await
         }
         RewardsCommands::ReadRewardInput {
@@ -247,6 +342,14 @@ pub async fn handle(orchestrator: &Orchestrator, cmd: RewardsCommands) -> Result
                 .read_reward_input(epoch, rewards_accountant)
                 .await
         }
+        RewardsCommands::ReadProvenance {
+            epoch,
+            rewards_accountant,
+        } => {
+            orchestrator
+                .read_provenance(epoch, rewards_accountant)
+                .await
+        }
         RewardsCommands::ReallocRecord {
             r#type,
             epoch,
@@ -272,10 +375,21 @@ pub async fn handle(orchestrator: &Orchestrator, cmd: RewardsCommands) -> Result
             epoch,
             dry_run,
             r#type,
+            overwrite_records,
+            keypair,
+        } => {
+            orchestrator
+                .write_telemetry_aggregates(epoch, keypair, dry_run, r#type, overwrite_records)
+                .await
+        }
+        RewardsCommands::RetryWrites {
+            epoch,
+            dry_run,
+            overwrite_records,
             keypair,
         } => {
             orchestrator
-                .write_telemetry_aggregates(epoch, keypair, dry_run, r#type)
+                .retry_failed_writes(epoch, keypair, dry_run, overwrite_records)
                 .await
         }
     }
@@ -5,15 +5,22 @@ use crate::{
         traits::Exportable,
     },
     ingestor::{
-        epoch::{EpochFinder, LeaderSchedule},
+        epoch::{EpochFinder, EpochParams, LeaderSchedule, print_epoch_params},
         fetcher::Fetcher,
+        fingerprint::FingerprintAlgorithm,
+        provenance::{self, Provenance},
+        raw::RawAccounts,
         types::FetchData,
     },
+    run_id::RunId,
 };
-use anyhow::{Result, bail};
+use anyhow::{Context, Result, bail};
 use clap::Subcommand;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 use tracing::{info, warn};
 
 /// Snapshot export commands for raw chain data
@@ -26,13 +33,32 @@ pub enum SnapshotCommands {
     snapshot all --epoch 9 --output-format json --output-file epoch-9-complete.json
 
     # Export to directory with automatic naming
-    snapshot all --epoch 9 --output-format json-pretty --output-dir ./snapshots/"#
+    snapshot all --epoch 9 --output-format json-pretty --output-dir ./snapshots/
+
+    # Export a v2 snapshot with embedded raw accounts for re-processing
+    snapshot all --epoch 9 --raw --output-file epoch-9-v2.json
+
+    # Fingerprint the raw accounts with BLAKE3 instead of SHA-256
+    snapshot all --epoch 9 --raw --fingerprint blake3 --output-file epoch-9-v2.json"#
     )]
     All {
         /// DZ epoch to snapshot
         #[arg(short, long, value_name = "EPOCH")]
         epoch: Option<u64>,
 
+        /// Embed compressed raw account bytes (snapshot format v2)
+        #[arg(long)]
+        raw: bool,
+
+        /// Hash algorithm for the raw account fingerprints
+        #[arg(
+            long,
+            value_name = "ALGORITHM",
+            default_value = "sha256",
+            requires = "raw"
+        )]
+        fingerprint: FingerprintAlgorithm,
+
         /// Output format for export
         #[arg(short = 'f', long, default_value = "json-pretty")]
         output_format: OutputFormat,
@@ -99,14 +125,79 @@ pub enum SnapshotCommands {
     },
 }
 
+/// Snapshot format containing processed data only
+pub const SNAPSHOT_FORMAT_V1: u32 = 1;
+/// Snapshot format additionally embedding raw account bytes
+pub const SNAPSHOT_FORMAT_V2: u32 = 2;
+
+fn default_snapshot_version() -> u32 {
+    SNAPSHOT_FORMAT_V1
+}
+
 /// Complete snapshot containing all data
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CompleteSnapshot {
+    /// Snapshot format version (missing in v1 snapshots)
+    #[serde(default = "default_snapshot_version")]
+    pub version: u32,
     pub dz_epoch: u64,
     pub solana_epoch: Option<u64>,
     pub fetch_data: FetchData,
     pub leader_schedule: Option<LeaderSchedule>,
     pub metadata: SnapshotMetadata,
+    /// Epoch parameters derived from each ledger at capture time (missing in older snapshots)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub epoch_params: Option<SnapshotEpochParams>,
+    /// RPC endpoints, genesis hashes and slot ranges the data was read from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+    /// Raw account bytes, only present in v2 snapshots
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_accounts: Option<RawAccounts>,
+}
+
+/// Epoch parameters of both ledgers as derived when the snapshot was taken
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotEpochParams {
+    pub dz: EpochParams,
+    pub solana: EpochParams,
+}
+
+impl CompleteSnapshot {
+    /// Load a snapshot from a JSON file, verifying cross-hashes for v2 snapshots
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read snapshot {}", path.display()))?;
+        let snapshot: Self = serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse snapshot {}", path.display()))?;
+
+        match (snapshot.version, &snapshot.raw_accounts) {
+            (SNAPSHOT_FORMAT_V1, _) => {}
+            (SNAPSHOT_FORMAT_V2, Some(raw_accounts)) => {
+                raw_accounts.verify(&snapshot.fetch_data)?;
+            }
+            (SNAPSHOT_FORMAT_V2, None) => {
+                bail!("Snapshot {} is v2 but has no raw accounts", path.display())
+            }
+            (version, _) => bail!("Unsupported snapshot version {version}"),
+        }
+
+        Ok(snapshot)
+    }
+
+    /// Fetch data to use for calculations, re-decoded from raw accounts when `reprocess` is set
+    pub fn fetch_data(&self, reprocess: bool) -> Result<FetchData> {
+        if !reprocess {
+            return Ok(self.fetch_data.clone());
+        }
+
+        let Some(raw_accounts) = &self.raw_accounts else {
+            bail!(
+                "Snapshot has no raw accounts to reprocess; re-export it with `snapshot all --raw`"
+            )
+        };
+        raw_accounts.reprocess(self.dz_epoch, &self.fetch_data)
+    }
 }
 
 /// Metadata about the snapshot
@@ -119,6 +210,9 @@ pub struct SnapshotMetadata {
     pub devices_count: usize,
     pub internet_samples_count: usize,
     pub device_samples_count: usize,
+    /// Run that captured the snapshot (missing in older snapshots)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<RunId>,
 }
 
 // Implement Exportable traits
@@ -153,6 +247,8 @@ pub async fn handle(orchestrator: &Orchestrator, cmd: SnapshotCommands) -> Resul
     match cmd {
         SnapshotCommands::All {
             epoch,
+            raw,
+            fingerprint,
             output_format,
             output_dir,
             output_file,
@@ -162,6 +258,9 @@ pub async fn handle(orchestrator: &Orchestrator, cmd: SnapshotCommands) -> Resul
             // Create fetcher
             let fetcher = Fetcher::from_settings(orchestrator.settings())?;
 
+            let dz_source = provenance::begin("dz_ledger", &fetcher.dz_rpc_client).await?;
+            let solana_source = provenance::begin("solana", &fetcher.solana_read_client).await?;
+
             // Fetch data for epoch
             let (fetch_epoch, fetch_data) = fetcher.fetch(epoch).await?;
 
@@ -198,6 +297,28 @@ pub async fn handle(orchestrator: &Orchestrator, cmd: SnapshotCommands) -> Resul
                 None
             };
 
+            // Record the epoch parameters both ledgers report so the snapshot is self-describing
+            let epoch_params = match (
+                epoch_finder.dz_epoch_params().await,
+                epoch_finder.solana_epoch_params().await,
+            ) {
+                (Ok(dz), Ok(solana)) => {
+                    info!(
+                        "Epoch Parameters:\n{}",
+                        print_epoch_params(&[("dz", &dz), ("solana", &solana)])
+                    );
+                    Some(SnapshotEpochParams { dz, solana })
+                }
+                (Err(e), _) | (_, Err(e)) => {
+                    warn!("Failed to derive epoch parameters: {}", e);
+                    None
+                }
+            };
+
+            let mut provenance = Provenance::default();
+            provenance.push(dz_source.finish(&fetcher.dz_rpc_client).await?);
+            provenance.push(solana_source.finish(&fetcher.solana_read_client).await?);
+
             // Create metadata
             let metadata = SnapshotMetadata {
                 created_at: chrono::Utc::now().to_rfc3339(),
@@ -207,15 +328,29 @@ pub async fn handle(orchestrator: &Orchestrator, cmd: SnapshotCommands) -> Resul
                 devices_count: fetch_data.dz_serviceability.devices.len(),
                 internet_samples_count: fetch_data.dz_internet.internet_latency_samples.len(),
                 device_samples_count: fetch_data.dz_telemetry.device_latency_samples.len(),
+                run_id: Some(orchestrator.run_id()),
+            };
+
+            let (version, raw_accounts) = if raw {
+                (
+                    SNAPSHOT_FORMAT_V2,
+                    Some(RawAccounts::capture(&fetch_data, fingerprint)?),
+                )
+            } else {
+                (SNAPSHOT_FORMAT_V1, None)
             };
 
             // Create complete snapshot
             let snapshot = CompleteSnapshot {
+                version,
                 dz_epoch: fetch_epoch,
                 solana_epoch,
                 fetch_data,
                 leader_schedule,
                 metadata,
+                epoch_params,
+                provenance: Some(provenance),
+                raw_accounts,
             };
 
             // Export based on options
@@ -223,7 +358,8 @@ pub async fn handle(orchestrator: &Orchestrator, cmd: SnapshotCommands) -> Resul
                 output_format,
                 output_dir: output_dir.map(|p| p.to_string_lossy().to_string()),
                 output_file: output_file.map(|p| p.to_string_lossy().to_string()),
-            };
+            }
+            .prepare(orchestrator.settings(), "snapshot", fetch_epoch);
 
             let default_filename = format!("snapshot-epoch-{fetch_epoch}");
             export_options.write(&snapshot, &default_filename)?;
@@ -259,7 +395,8 @@ pub async fn handle(orchestrator: &Orchestrator, cmd: SnapshotCommands) -> Resul
                 output_format,
                 output_dir: output_dir.map(|p| p.to_string_lossy().to_string()),
                 output_file: output_file.map(|p| p.to_string_lossy().to_string()),
-            };
+            }
+            .prepare(orchestrator.settings(), "fetch-data", fetch_epoch);
 
             let default_filename = format!("fetch-data-epoch-{fetch_epoch}");
             export_options.write(&fetch_data, &default_filename)?;
@@ -300,7 +437,8 @@ pub async fn handle(orchestrator: &Orchestrator, cmd: SnapshotCommands) -> Resul
                 output_format,
                 output_dir: output_dir.map(|p| p.to_string_lossy().to_string()),
                 output_file: output_file.map(|p| p.to_string_lossy().to_string()),
-            };
+            }
+            .prepare(orchestrator.settings(), "leader-schedule", epoch);
 
             let default_filename = format!("leader-schedule-epoch-{epoch}");
             export_options.write(&leader_schedule, &default_filename)?;
@@ -5,23 +5,33 @@ use crate::{
             FilterOptions, OutputFormat, OutputOptions, ThresholdOptions, collection_to_csv,
             to_json_string,
         },
+        presenter,
         traits::Exportable,
     },
+    codes::{CityCode, ExchangeCode},
     ingestor::{
         fetcher::Fetcher,
+        internet,
         types::{DZDeviceLatencySamples, DZInternetLatencySamples, KeyedAccounts},
+        validation::AgentContribution,
     },
     processor::{
+        bandwidth::LinkBandwidth,
         internet::{InternetTelemetryProcessor, InternetTelemetryStats},
+        outage::LinkOutage,
         telemetry::{DZDTelemetryProcessor, DZDTelemetryStats},
+        util::mean_confidence_interval,
     },
+    settings::network::Network,
+    units::{Lamports, Micros},
 };
 use anyhow::{Result, bail};
 use clap::Subcommand;
 use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
 use std::{collections::BTreeMap, str::FromStr};
 use tabled::{Table, Tabled, settings::Style};
-use tracing::info;
+use tracing::{info, warn};
 
 /// Telemetry type selection
 #[derive(Debug, Clone, Copy)]
@@ -129,7 +139,10 @@ pub enum TelemetryCommands {
     telemetry analyze --type device --epoch 9 --min-packet-loss 0.01
 
     # Export analysis results
-    telemetry analyze --type internet --epoch 9 --output-format csv --output-file issues.csv"#
+    telemetry analyze --type internet --epoch 9 --output-format csv --output-file issues.csv
+
+    # List when each device link was degraded or down
+    telemetry analyze --type device --epoch 9 --show-outages"#
     )]
     Analyze {
         /// Telemetry type to analyze (internet or device)
@@ -149,6 +162,10 @@ pub enum TelemetryCommands {
         #[command(flatten)]
         thresholds: ThresholdOptions,
 
+        /// List each link's degraded and down intervals
+        #[arg(long)]
+        show_outages: bool,
+
         /// Output options
         #[command(flatten)]
         output: OutputOptions,
@@ -197,6 +214,8 @@ pub struct InternetStatsExport {
     pub total_links: usize,
     pub total_samples: usize,
     pub stats: Vec<InternetLinkStats>,
+    /// Sample accounts and samples contributed by each collector agent
+    pub agents: Vec<AgentContribution>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -204,12 +223,22 @@ pub struct InternetLinkStats {
     pub from_city: String,
     pub to_city: String,
     pub samples: usize,
+    /// Successful RTT samples the latency aggregates are based on
+    pub rtt_samples: u64,
     pub mean_latency_ms: f64,
+    /// Lower bound of the 95% confidence interval for the mean latency
+    pub mean_latency_ci95_low_ms: f64,
+    /// Upper bound of the 95% confidence interval for the mean latency
+    pub mean_latency_ci95_high_ms: f64,
     pub median_latency_ms: f64,
     pub p95_latency_ms: f64,
     pub p99_latency_ms: f64,
     pub packet_loss: f64,
     pub jitter_ms: f64,
+    pub rfc3550_jitter_ms: f64,
+    pub jitter_stddev_ms: f64,
+    /// Fewer RTT samples than `inet_lookback.min_samples_per_link`
+    pub low_confidence: bool,
 }
 
 /// Device telemetry statistics export
@@ -227,14 +256,27 @@ pub struct DeviceLinkStats {
     pub city: String,
     pub exchange: String,
     pub samples: usize,
+    /// Successful RTT samples the latency aggregates are based on
+    pub rtt_samples: u64,
     pub mean_latency_ms: f64,
+    /// Lower bound of the 95% confidence interval for the mean latency
+    pub mean_latency_ci95_low_ms: f64,
+    /// Upper bound of the 95% confidence interval for the mean latency
+    pub mean_latency_ci95_high_ms: f64,
     pub median_latency_ms: f64,
     pub p95_latency_ms: f64,
     pub p99_latency_ms: f64,
     pub packet_loss: f64,
     pub jitter_ms: f64,
+    pub rfc3550_jitter_ms: f64,
+    pub jitter_stddev_ms: f64,
     pub uptime: f64,
+    /// Bandwidth declared on the serviceability link account
     pub bandwidth_mbps: f64,
+    /// Whether the declared bandwidth is consistent with the link's telemetry
+    pub bandwidth_flag: String,
+    /// Fewer RTT samples than `inet_lookback.min_samples_per_link`
+    pub low_confidence: bool,
 }
 
 /// Link quality analysis results
@@ -244,6 +286,9 @@ pub struct LinkQualityAnalysis {
     pub telemetry_type: String,
     pub issues_found: usize,
     pub problematic_links: Vec<ProblematicLink>,
+    /// Degraded and down intervals, only with `--show-outages`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub outages: Vec<LinkOutage>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -410,18 +455,25 @@ pub async fn handle(orchestrator: &Orchestrator, cmd: TelemetryCommands) -> Resu
             telemetry_type,
             epoch,
             thresholds,
+            show_outages,
             output,
         } => match telemetry_type {
             TelemetryType::Internet => {
-                handle_internet_analyze(orchestrator, epoch, thresholds, output).await
+                handle_internet_analyze(orchestrator, epoch, thresholds, show_outages, output).await
             }
             TelemetryType::Device => {
-                handle_device_analyze(orchestrator, epoch, thresholds, output).await
+                handle_device_analyze(orchestrator, epoch, thresholds, show_outages, output).await
             }
             TelemetryType::All => {
-                handle_internet_analyze(orchestrator, epoch, thresholds.clone(), output.clone())
-                    .await?;
-                handle_device_analyze(orchestrator, epoch, thresholds, output).await
+                handle_internet_analyze(
+                    orchestrator,
+                    epoch,
+                    thresholds.clone(),
+                    show_outages,
+                    output.clone(),
+                )
+                .await?;
+                handle_device_analyze(orchestrator, epoch, thresholds, show_outages, output).await
             }
         },
         TelemetryCommands::Rent {
@@ -440,62 +492,66 @@ async fn handle_internet_stats(
 ) -> Result<()> {
     info!("Calculating internet telemetry statistics");
 
+    let min_samples = orchestrator.settings().inet_lookback.min_samples_per_link as u64;
+
     // Create fetcher
     let fetcher = Fetcher::from_settings(orchestrator.settings())?;
 
     // Fetch data for epoch
-    let (fetch_epoch, fetch_data) = fetcher.fetch(epoch).await?;
+    let (fetch_epoch, mut fetch_data) = fetcher.fetch(epoch).await?;
 
     info!("Processing telemetry for epoch {}", fetch_epoch);
 
+    // Only samples from approved agents count towards the stats
+    let agents =
+        internet::apply_agent_allowlist(orchestrator.settings(), &mut fetch_data.dz_internet)?;
+
     // Process internet telemetry
     let internet_stats = InternetTelemetryProcessor::process(&fetch_data)?;
 
     // Filter stats if requested
-    let filtered_stats: BTreeMap<String, InternetTelemetryStats> = internet_stats
-        .into_iter()
-        .filter(|(key, _)| {
-            let parts: Vec<&str> = key.split('_').collect();
-            if parts.len() != 2 {
-                return false;
-            }
-            let origin = parts[0];
-            let target = parts[1];
+    let network = orchestrator.settings().network;
+    let filtered_stats: Vec<(InternetTelemetryStats, CityCode, CityCode)> = internet_stats
+        .into_values()
+        .filter_map(|stats| {
+            let (from_city, to_city) = route_cities(&stats, network)?;
 
             let from_match = filters
                 .from_city
                 .as_ref()
-                .is_none_or(|city| origin.contains(city));
+                .is_none_or(|city| from_city.matches(city));
             let to_match = filters
                 .to_city
                 .as_ref()
-                .is_none_or(|city| target.contains(city));
+                .is_none_or(|city| to_city.matches(city));
 
-            from_match && to_match
+            (from_match && to_match).then_some((stats, from_city, to_city))
         })
         .collect();
 
     // Convert to export format
     let mut stats_list = Vec::new();
-    for (route, stats) in &filtered_stats {
-        let parts: Vec<&str> = route.split('_').collect();
-        if parts.len() == 2 {
-            // Extract city codes from exchange codes (remove 'x' prefix)
-            let from_city = parts[0].trim_start_matches('x').to_string();
-            let to_city = parts[1].trim_start_matches('x').to_string();
-
-            stats_list.push(InternetLinkStats {
-                from_city,
-                to_city,
-                samples: stats.total_samples,
-                mean_latency_ms: stats.rtt_mean_us / 1000.0,
-                median_latency_ms: stats.rtt_median_us / 1000.0,
-                p95_latency_ms: stats.rtt_p95_us / 1000.0,
-                p99_latency_ms: stats.rtt_p99_us / 1000.0,
-                packet_loss: stats.packet_loss,
-                jitter_ms: stats.avg_jitter_us / 1000.0,
-            });
-        }
+    for (stats, from_city, to_city) in &filtered_stats {
+        let (ci_low_us, ci_high_us) =
+            mean_confidence_interval(stats.rtt_mean_us, stats.rtt_stddev_us, stats.success_count);
+
+        stats_list.push(InternetLinkStats {
+            from_city: from_city.to_string(),
+            to_city: to_city.to_string(),
+            samples: stats.total_samples,
+            rtt_samples: stats.success_count,
+            mean_latency_ms: Micros(stats.rtt_mean_us).as_millis(),
+            mean_latency_ci95_low_ms: Micros(ci_low_us).as_millis(),
+            mean_latency_ci95_high_ms: Micros(ci_high_us).as_millis(),
+            median_latency_ms: Micros(stats.rtt_median_us).as_millis(),
+            p95_latency_ms: Micros(stats.rtt_p95_us).as_millis(),
+            p99_latency_ms: Micros(stats.rtt_p99_us).as_millis(),
+            packet_loss: stats.packet_loss,
+            jitter_ms: Micros(stats.avg_jitter_us).as_millis(),
+            rfc3550_jitter_ms: Micros(stats.rfc3550_jitter_us).as_millis(),
+            jitter_stddev_ms: Micros(stats.jitter_stddev_us).as_millis(),
+            low_confidence: stats.success_count < min_samples,
+        });
     }
 
     let stats_export = InternetStatsExport {
@@ -503,14 +559,11 @@ async fn handle_internet_stats(
         total_links: stats_list.len(),
         total_samples: fetch_data.dz_internet.internet_latency_samples.len(),
         stats: stats_list,
+        agents,
     };
 
     // Export based on options
-    let export_options = OutputOptions {
-        output_format: output.output_format,
-        output_dir: output.output_dir.clone(),
-        output_file: output.output_file.clone(),
-    };
+    let export_options = output.prepare(orchestrator.settings(), "internet-stats", fetch_epoch);
 
     let default_filename = format!("internet-stats-epoch-{fetch_epoch}");
     export_options.write(&stats_export, &default_filename)?;
@@ -527,6 +580,8 @@ async fn handle_device_stats(
 ) -> Result<()> {
     info!("Calculating device telemetry statistics");
 
+    let min_samples = orchestrator.settings().inet_lookback.min_samples_per_link as u64;
+
     // Create fetcher
     let fetcher = Fetcher::from_settings(orchestrator.settings())?;
 
@@ -536,7 +591,13 @@ async fn handle_device_stats(
     info!("Processing telemetry for epoch {}", fetch_epoch);
 
     // Process device telemetry
-    let device_stats = DZDTelemetryProcessor::process(&fetch_data)?;
+    let (device_stats, outages) = DZDTelemetryProcessor::process_with_outages(
+        &fetch_data,
+        &orchestrator
+            .settings()
+            .telemetry_defaults
+            .maintenance_windows,
+    )?;
 
     // Get city for filtering (prefer city over from_city)
     let city_filter = filters.city.or(filters.from_city);
@@ -587,19 +648,39 @@ async fn handle_device_stats(
             .map(|e| e.code.clone())
             .unwrap_or_else(|| "Unknown".to_string());
 
+        let (ci_low_us, ci_high_us) =
+            mean_confidence_interval(stats.rtt_mean_us, stats.rtt_stddev_us, stats.success_count);
+
+        // Links missing from serviceability show up as undeclared
+        let bandwidth = LinkBandwidth::new(
+            fetch_data
+                .dz_serviceability
+                .links
+                .get(&stats.link_pubkey)
+                .map(|link| link.bandwidth)
+                .unwrap_or_default(),
+        );
+
         stats_list.push(DeviceLinkStats {
             circuit: stats.circuit.clone(),
             city: location,
             exchange,
             samples: stats.total_samples,
-            mean_latency_ms: stats.rtt_mean_us / 1000.0,
-            median_latency_ms: stats.rtt_median_us / 1000.0,
-            p95_latency_ms: stats.rtt_p95_us / 1000.0,
-            p99_latency_ms: stats.rtt_p99_us / 1000.0,
+            rtt_samples: stats.success_count,
+            mean_latency_ms: Micros(stats.rtt_mean_us).as_millis(),
+            mean_latency_ci95_low_ms: Micros(ci_low_us).as_millis(),
+            mean_latency_ci95_high_ms: Micros(ci_high_us).as_millis(),
+            median_latency_ms: Micros(stats.rtt_median_us).as_millis(),
+            p95_latency_ms: Micros(stats.rtt_p95_us).as_millis(),
+            p99_latency_ms: Micros(stats.rtt_p99_us).as_millis(),
             packet_loss: stats.packet_loss,
-            jitter_ms: stats.avg_jitter_us / 1000.0,
-            uptime: 1.0,            // Default for now
-            bandwidth_mbps: 1000.0, // Default for now
+            jitter_ms: Micros(stats.avg_jitter_us).as_millis(),
+            rfc3550_jitter_ms: Micros(stats.rfc3550_jitter_us).as_millis(),
+            jitter_stddev_ms: Micros(stats.jitter_stddev_us).as_millis(),
+            uptime: stats.uptime,
+            bandwidth_mbps: bandwidth.mbps(),
+            bandwidth_flag: bandwidth.check(Some(stats)).to_string(),
+            low_confidence: stats.success_count < min_samples,
         });
     }
 
@@ -611,11 +692,7 @@ async fn handle_device_stats(
     };
 
     // Export based on options
-    let export_options = OutputOptions {
-        output_format: output.output_format,
-        output_dir: output.output_dir.clone(),
-        output_file: output.output_file.clone(),
-    };
+    let export_options = output.prepare(orchestrator.settings(), "device-stats", fetch_epoch);
 
     let default_filename = format!("device-stats-epoch-{fetch_epoch}");
     export_options.write(&stats_export, &default_filename)?;
@@ -671,11 +748,7 @@ async fn handle_internet_export(
     info!("Exporting {} samples", samples.len());
 
     // Export based on options
-    let export_options = OutputOptions {
-        output_format: output.output_format,
-        output_dir: output.output_dir.clone(),
-        output_file: output.output_file.clone(),
-    };
+    let export_options = output.prepare(orchestrator.settings(), "internet-samples", fetch_epoch);
 
     // Create export wrapper
     #[derive(Serialize)]
@@ -715,7 +788,7 @@ async fn handle_internet_export(
         std::fs::write(&path, output_str)?;
         info!("Exported to {path}");
     } else {
-        println!("{output_str}");
+        presenter::output(output_str);
     }
 
     Ok(())
@@ -763,11 +836,7 @@ async fn handle_device_export(
     info!("Exporting {} samples", samples.len());
 
     // Export based on options
-    let export_options = OutputOptions {
-        output_format: output.output_format,
-        output_dir: output.output_dir.clone(),
-        output_file: output.output_file.clone(),
-    };
+    let export_options = output.prepare(orchestrator.settings(), "device-samples", fetch_epoch);
 
     // Create export wrapper
     #[derive(Serialize)]
@@ -807,7 +876,7 @@ async fn handle_device_export(
         std::fs::write(&path, output_str)?;
         info!("Exported to {path}");
     } else {
-        println!("{output_str}");
+        presenter::output(output_str);
     }
 
     Ok(())
@@ -817,6 +886,7 @@ async fn handle_internet_analyze(
     orchestrator: &Orchestrator,
     epoch: Option<u64>,
     thresholds: ThresholdOptions,
+    show_outages: bool,
     output: OutputOptions,
 ) -> Result<()> {
     info!("Analyzing internet link quality");
@@ -828,7 +898,7 @@ async fn handle_internet_analyze(
     let (fetch_epoch, fetch_data) = fetcher.fetch(epoch).await?;
 
     // Process internet telemetry
-    let internet_stats = InternetTelemetryProcessor::process(&fetch_data)?;
+    let (internet_stats, outages) = InternetTelemetryProcessor::process_with_outages(&fetch_data)?;
 
     // Default thresholds
     let latency_threshold = thresholds.threshold_ms.unwrap_or(200.0);
@@ -837,20 +907,19 @@ async fn handle_internet_analyze(
 
     // Find problematic links
     let mut problematic_links = Vec::new();
-    for (route, stats) in &internet_stats {
-        let parts: Vec<&str> = route.split('_').collect();
-        if parts.len() != 2 {
+    let network = orchestrator.settings().network;
+    for stats in internet_stats.values() {
+        let Some((from_city, to_city)) = route_cities(stats, network) else {
             continue;
-        }
-
-        let from_city = parts[0].trim_start_matches('x').to_string();
-        let to_city = parts[1].trim_start_matches('x').to_string();
+        };
+        let from_city = from_city.to_string();
+        let to_city = to_city.to_string();
 
         let mut issues = Vec::new();
         let mut severity = "low";
 
-        let mean_latency_ms = stats.rtt_mean_us / 1000.0;
-        let jitter_ms = stats.avg_jitter_us / 1000.0;
+        let mean_latency_ms = Micros(stats.rtt_mean_us).as_millis();
+        let jitter_ms = Micros(stats.avg_jitter_us).as_millis();
 
         if mean_latency_ms > latency_threshold {
             issues.push("high_latency");
@@ -903,19 +972,20 @@ async fn handle_internet_analyze(
             .then(b.mean_latency_ms.partial_cmp(&a.mean_latency_ms).unwrap())
     });
 
+    if show_outages {
+        print_outages(&outages);
+    }
+
     let analysis = LinkQualityAnalysis {
         epoch: fetch_epoch,
         telemetry_type: "internet".to_string(),
         issues_found: problematic_links.len(),
         problematic_links,
+        outages: if show_outages { outages } else { Vec::new() },
     };
 
     // Export based on options
-    let export_options = OutputOptions {
-        output_format: output.output_format,
-        output_dir: output.output_dir.clone(),
-        output_file: output.output_file.clone(),
-    };
+    let export_options = output.prepare(orchestrator.settings(), "internet-analysis", fetch_epoch);
 
     let default_filename = format!("internet-analysis-epoch-{fetch_epoch}");
     export_options.write(&analysis, &default_filename)?;
@@ -931,6 +1001,7 @@ async fn handle_device_analyze(
     orchestrator: &Orchestrator,
     epoch: Option<u64>,
     thresholds: ThresholdOptions,
+    show_outages: bool,
     output: OutputOptions,
 ) -> Result<()> {
     info!("Analyzing device performance");
@@ -942,30 +1013,38 @@ async fn handle_device_analyze(
     let (fetch_epoch, fetch_data) = fetcher.fetch(epoch).await?;
 
     // Process device telemetry
-    let device_stats = DZDTelemetryProcessor::process(&fetch_data)?;
+    let (device_stats, outages) = DZDTelemetryProcessor::process_with_outages(
+        &fetch_data,
+        &orchestrator
+            .settings()
+            .telemetry_defaults
+            .maintenance_windows,
+    )?;
 
     // Default thresholds
     let latency_threshold = thresholds.threshold_ms.unwrap_or(100.0);
-    let _uptime_threshold = thresholds.min_uptime.unwrap_or(0.95);
+    let uptime_threshold = thresholds.min_uptime.unwrap_or(0.95);
     // Note: min_bandwidth removed from common ThresholdOptions
     // To re-enable, add min_bandwidth field to ThresholdOptions
 
     // Find problematic devices
     let mut problematic_links = Vec::new();
-    for (route, stats) in &device_stats {
-        let parts: Vec<&str> = route.split('_').collect();
-        if parts.len() != 2 {
-            continue;
-        }
-
-        let from_device = parts[0].to_string();
-        let to_device = parts[1].to_string();
+    let devices = &fetch_data.dz_serviceability.devices;
+    let device_code = |pubkey: &Pubkey| {
+        devices
+            .get(pubkey)
+            .map(|device| device.code.clone())
+            .unwrap_or_else(|| pubkey.to_string())
+    };
+    for stats in device_stats.values() {
+        let from_device = device_code(&stats.origin_device);
+        let to_device = device_code(&stats.target_device);
 
         let mut issues = Vec::new();
         let mut severity = "low";
 
-        let mean_latency_ms = stats.rtt_mean_us / 1000.0;
-        let jitter_ms = stats.avg_jitter_us / 1000.0;
+        let mean_latency_ms = Micros(stats.rtt_mean_us).as_millis();
+        let jitter_ms = Micros(stats.avg_jitter_us).as_millis();
 
         if mean_latency_ms > latency_threshold {
             issues.push("high_latency");
@@ -992,6 +1071,13 @@ async fn handle_device_analyze(
             }
         }
 
+        if stats.uptime < uptime_threshold {
+            issues.push("low_uptime");
+            if severity == "low" {
+                severity = "medium";
+            }
+        }
+
         if !issues.is_empty() {
             problematic_links.push(ProblematicLink {
                 from_location: from_device,
@@ -1018,19 +1104,20 @@ async fn handle_device_analyze(
             .then(b.mean_latency_ms.partial_cmp(&a.mean_latency_ms).unwrap())
     });
 
+    if show_outages {
+        print_outages(&outages);
+    }
+
     let analysis = LinkQualityAnalysis {
         epoch: fetch_epoch,
         telemetry_type: "device".to_string(),
         issues_found: problematic_links.len(),
         problematic_links,
+        outages: if show_outages { outages } else { Vec::new() },
     };
 
     // Export based on options
-    let export_options = OutputOptions {
-        output_format: output.output_format,
-        output_dir: output.output_dir.clone(),
-        output_file: output.output_file.clone(),
-    };
+    let export_options = output.prepare(orchestrator.settings(), "device-analysis", fetch_epoch);
 
     let default_filename = format!("device-analysis-epoch-{fetch_epoch}");
     export_options.write(&analysis, &default_filename)?;
@@ -1042,6 +1129,33 @@ async fn handle_device_analyze(
     Ok(())
 }
 
+/// City codes at both ends of an internet route, `None` (logged) if either code is malformed
+fn route_cities(stats: &InternetTelemetryStats, network: Network) -> Option<(CityCode, CityCode)> {
+    let city = |code: &str| ExchangeCode::parse(code).and_then(|code| code.city(network));
+    match (
+        city(&stats.origin_exchange_code),
+        city(&stats.target_exchange_code),
+    ) {
+        (Ok(from_city), Ok(to_city)) => Some((from_city, to_city)),
+        (Err(e), _) | (_, Err(e)) => {
+            warn!("Skipping route {}: {e}", stats.circuit);
+            None
+        }
+    }
+}
+
+fn print_outages(outages: &[LinkOutage]) {
+    if outages.is_empty() {
+        info!("No degraded or down intervals found");
+        return;
+    }
+    presenter::output(
+        Table::new(outages)
+            .with(Style::psql().remove_horizontals())
+            .to_string(),
+    );
+}
+
 async fn handle_telemetry_rent_analysis(
     orchestrator: &Orchestrator,
     telemetry_type: TelemetryType,
@@ -1049,7 +1163,6 @@ async fn handle_telemetry_rent_analysis(
     output: OutputOptions,
 ) -> Result<()> {
     const LAMPORTS_PER_BYTE: u64 = 6_960;
-    const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
 
     info!("Calculating rent requirements for telemetry accounts");
 
@@ -1074,7 +1187,7 @@ async fn handle_telemetry_rent_analysis(
         }
 
         let total_rent_lamports = total_bytes as u64 * LAMPORTS_PER_BYTE;
-        let total_rent_sol = total_rent_lamports as f64 / LAMPORTS_PER_SOL;
+        let total_rent_sol = Lamports(total_rent_lamports).as_sol();
         let avg_size = if accounts.is_empty() {
             0
         } else {
@@ -1131,7 +1244,7 @@ async fn handle_telemetry_rent_analysis(
         total_accounts,
         total_bytes,
         total_rent_lamports,
-        total_rent_sol: total_rent_lamports as f64 / LAMPORTS_PER_SOL,
+        total_rent_sol: Lamports(total_rent_lamports).as_sol(),
     };
 
     let analysis = TelemetryRentAnalysis {
@@ -1142,9 +1255,10 @@ async fn handle_telemetry_rent_analysis(
     };
 
     // Display summary
-    println!();
-    println!("Telemetry Accounts Rent Analysis - Epoch {fetch_epoch}");
-    println!("=================================================\n");
+    presenter::detail(format!(
+        "\nTelemetry Accounts Rent Analysis - Epoch {fetch_epoch}\n\
+         =================================================\n"
+    ));
 
     // Build table rows
     let mut rows = Vec::new();
@@ -1159,7 +1273,7 @@ async fn handle_telemetry_rent_analysis(
                 device.total_bytes as f64 / (1024.0 * 1024.0)
             ),
             avg_size: format!("{} bytes", device.average_account_size_bytes),
-            rent_sol: format!("{:.6} SOL", device.total_rent_sol),
+            rent_sol: Lamports(device.total_rent_lamports).to_string(),
         });
     }
 
@@ -1173,18 +1287,18 @@ async fn handle_telemetry_rent_analysis(
                 internet.total_bytes as f64 / (1024.0 * 1024.0)
             ),
             avg_size: format!("{} bytes", internet.average_account_size_bytes),
-            rent_sol: format!("{:.6} SOL", internet.total_rent_sol),
+            rent_sol: Lamports(internet.total_rent_lamports).to_string(),
         });
     }
 
     // Display telemetry breakdown table
     if !rows.is_empty() {
         let table = Table::new(rows).with(Style::psql()).to_string();
-        println!("{table}");
+        presenter::detail(table);
     }
 
     // Display combined summary table
-    println!("\nCombined Summary:");
+    presenter::detail("\nCombined Summary:");
     let total_rows = vec![
         RentTotalRow {
             category: "Total Accounts".to_string(),
@@ -1205,23 +1319,22 @@ async fn handle_telemetry_rent_analysis(
         RentTotalRow {
             category: "Total Rent Required".to_string(),
             value: format!(
-                "{:.6} SOL ({} lamports)",
-                analysis.combined_summary.total_rent_sol,
+                "{} ({} lamports)",
+                Lamports(analysis.combined_summary.total_rent_lamports),
                 analysis.combined_summary.total_rent_lamports
             ),
         },
     ];
 
     let total_table = Table::new(total_rows).with(Style::psql()).to_string();
-    println!("{total_table}");
-    println!();
+    presenter::detail(format!("{total_table}\n"));
 
     // Export based on options
-    let export_options = OutputOptions {
-        output_format: output.output_format,
-        output_dir: output.output_dir.clone(),
-        output_file: output.output_file.clone(),
-    };
+    let export_options = output.prepare(
+        orchestrator.settings(),
+        "telemetry-rent-analysis",
+        fetch_epoch,
+    );
 
     let default_filename = format!("telemetry-rent-analysis-epoch-{fetch_epoch}");
     export_options.write(&analysis, &default_filename)?;
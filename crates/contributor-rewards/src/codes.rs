@@ -0,0 +1,192 @@
+//! Exchange and city codes
+//!
+//! Codes are compared in normalized form: trimmed and lowercase. On mainnet an exchange's code
+//! is the code of its city, while on testnet and devnet it carries an `x` prefix in front of it
+//! (`xams` for `ams`). Codes may contain underscores, so keys combining several codes must never
+//! be split apart again; carry the typed codes alongside them instead.
+
+use crate::{ingestor::validation, settings::network::Network};
+use doublezero_serviceability::state::exchange::Exchange;
+use solana_sdk::pubkey::Pubkey;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    str::FromStr,
+};
+use thiserror::Error;
+use tracing::warn;
+
+/// Prefix of exchange codes outside mainnet
+const EXCHANGE_PREFIX: char = 'x';
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum CodeError {
+    #[error("Code is empty")]
+    Empty,
+
+    #[error("Code {code:?} contains {ch:?}; only letters, digits, '_' and '-' are allowed")]
+    InvalidCharacter { code: String, ch: char },
+
+    #[error("Exchange code {code:?} has no city code after its '{EXCHANGE_PREFIX}' prefix")]
+    MissingCity { code: String },
+}
+
+fn normalize(code: &str) -> Result<String, CodeError> {
+    let code = code.trim().to_lowercase();
+    if code.is_empty() {
+        return Err(CodeError::Empty);
+    }
+    if let Some(ch) = code
+        .chars()
+        .find(|ch| !(ch.is_ascii_alphanumeric() || *ch == '_' || *ch == '-'))
+    {
+        return Err(CodeError::InvalidCharacter { code, ch });
+    }
+    Ok(code)
+}
+
+/// Code of an exchange as registered in serviceability
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ExchangeCode(String);
+
+impl ExchangeCode {
+    pub fn parse(code: &str) -> Result<Self, CodeError> {
+        normalize(code).map(Self)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// City the exchange is in, which outside mainnet drops the exchange prefix
+    pub fn city(&self, network: Network) -> Result<CityCode, CodeError> {
+        if network.is_production() {
+            return Ok(CityCode(self.0.clone()));
+        }
+        match self.0.strip_prefix(EXCHANGE_PREFIX) {
+            Some("") => Err(CodeError::MissingCity {
+                code: self.0.clone(),
+            }),
+            Some(city) => Ok(CityCode(city.to_string())),
+            None => Ok(CityCode(self.0.clone())),
+        }
+    }
+}
+
+impl FromStr for ExchangeCode {
+    type Err = CodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for ExchangeCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Code of a city (location) that exchanges are in
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CityCode(String);
+
+impl CityCode {
+    pub fn parse(code: &str) -> Result<Self, CodeError> {
+        normalize(code).map(Self)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Whether the code contains `filter`, ignoring case
+    pub fn matches(&self, filter: &str) -> bool {
+        self.0.contains(&filter.trim().to_lowercase())
+    }
+}
+
+impl FromStr for CityCode {
+    type Err = CodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for CityCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Drop exchanges whose code is malformed or shared with another exchange once normalized
+///
+/// Samples of a dropped exchange are then skipped like any other sample of an unknown
+/// exchange, rather than being attributed to whichever exchange happened to match.
+pub fn retain_unambiguous_exchanges(exchanges: &mut BTreeMap<Pubkey, Exchange>) {
+    let mut by_code: BTreeMap<ExchangeCode, Vec<Pubkey>> = BTreeMap::new();
+    let mut rejected = BTreeSet::new();
+
+    for (pubkey, exchange) in exchanges.iter() {
+        match ExchangeCode::parse(&exchange.code) {
+            Ok(code) => by_code.entry(code).or_default().push(*pubkey),
+            Err(err) => {
+                warn!("Skipping exchange {pubkey}: {err}");
+                validation::record_skipped("exchange", "invalid_code");
+                rejected.insert(*pubkey);
+            }
+        }
+    }
+
+    for (code, pubkeys) in by_code.into_iter().filter(|(_, pubkeys)| pubkeys.len() > 1) {
+        warn!(
+            "Skipping {} exchanges sharing code {code}: {}",
+            pubkeys.len(),
+            pubkeys
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        for pubkey in pubkeys {
+            validation::record_skipped("exchange", "ambiguous_code");
+            rejected.insert(pubkey);
+        }
+    }
+
+    exchanges.retain(|pubkey, _| !rejected.contains(pubkey));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exchange_code_city() {
+        let code = ExchangeCode::parse(" xAMS ").unwrap();
+        assert_eq!(code.as_str(), "xams");
+        assert_eq!(code.city(Network::Testnet).unwrap().as_str(), "ams");
+        assert_eq!(code.city(Network::MainnetBeta).unwrap().as_str(), "xams");
+
+        // Underscores are part of the code, and only one prefix is dropped
+        let code = ExchangeCode::parse("xxian_2").unwrap();
+        assert_eq!(code.city(Network::Devnet).unwrap().as_str(), "xian_2");
+
+        let code = ExchangeCode::parse("x").unwrap();
+        assert!(code.city(Network::Testnet).is_err());
+
+        assert_eq!(ExchangeCode::parse("  "), Err(CodeError::Empty));
+        assert!(matches!(
+            ExchangeCode::parse("ams→fra"),
+            Err(CodeError::InvalidCharacter { ch: '→', .. })
+        ));
+    }
+
+    #[test]
+    fn test_city_code_matches() {
+        let city = CityCode::parse("new_york").unwrap();
+        assert!(city.matches("York"));
+        assert!(!city.matches("ams"));
+    }
+}
@@ -0,0 +1,304 @@
+//! Resumable fetch progress
+//!
+//! A full fetch issues one `getProgramAccounts` call per serviceability account type plus one
+//! per telemetry program section, and can run for the better part of an hour. Each completed
+//! section is written to a checkpoint file in the temp directory so a retry for the same epoch
+//! picks up where the failed attempt stopped. A checkpoint is discarded when it was written for
+//! a different epoch or different settings, and removed once the fetch completes.
+
+use crate::{
+    ingestor::{
+        fingerprint::{Fingerprint, FingerprintAlgorithm},
+        raw::RawAccount,
+    },
+    settings::Settings,
+};
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::{
+    collections::BTreeMap,
+    fs,
+    future::Future,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+use tracing::{info, warn};
+
+const CHECKPOINT_DIR: &str = "doublezero-contributor-rewards";
+
+/// A completed section, with a fingerprint over its account bytes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CheckpointSection {
+    accounts: Vec<RawAccount>,
+    hash: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CheckpointState {
+    epoch: u64,
+    /// Fingerprint of the settings the checkpoint was written with
+    settings_hash: String,
+    sections: BTreeMap<String, CheckpointSection>,
+}
+
+/// Fetch progress for one epoch, persisted after every completed section
+#[derive(Debug)]
+pub struct FetchCheckpoint {
+    path: PathBuf,
+    state: Mutex<CheckpointState>,
+}
+
+impl FetchCheckpoint {
+    /// Open the checkpoint for `epoch` in the temp directory, resuming any compatible progress
+    pub fn open(settings: &Settings, epoch: u64) -> Result<Self> {
+        let path = std::env::temp_dir()
+            .join(CHECKPOINT_DIR)
+            .join(format!("fetch-{}-{epoch}.json", settings.network));
+        Ok(Self::open_at(path, epoch, settings_hash(settings)?))
+    }
+
+    fn open_at(path: PathBuf, epoch: u64, settings_hash: String) -> Self {
+        let fresh = CheckpointState {
+            epoch,
+            settings_hash: settings_hash.clone(),
+            sections: BTreeMap::new(),
+        };
+
+        let state = match load(&path) {
+            Ok(Some(state)) if state.epoch == epoch && state.settings_hash == settings_hash => {
+                info!(
+                    "Resuming fetch for epoch {epoch} from checkpoint {} ({} sections complete)",
+                    path.display(),
+                    state.sections.len()
+                );
+                metrics::counter!("doublezero_contributor_rewards_fetch_checkpoint_resumes")
+                    .increment(1);
+                state
+            }
+            Ok(Some(_)) => {
+                info!(
+                    "Discarding checkpoint {} written for a different epoch or settings",
+                    path.display()
+                );
+                fresh
+            }
+            Ok(None) => fresh,
+            Err(e) => {
+                warn!("Ignoring unreadable checkpoint {}: {e:#}", path.display());
+                fresh
+            }
+        };
+
+        Self {
+            path,
+            state: Mutex::new(state),
+        }
+    }
+
+    /// Accounts for a completed section, if the checkpoint holds an intact copy
+    pub fn get(&self, section: &str) -> Option<Vec<(Pubkey, Vec<u8>)>> {
+        let mut state = self.state.lock().expect("checkpoint lock poisoned");
+        let stored = state.sections.get(section)?;
+
+        match decode_section(stored) {
+            Ok(accounts) => Some(accounts),
+            Err(e) => {
+                warn!("Refetching section {section}: {e:#}");
+                state.sections.remove(section);
+                None
+            }
+        }
+    }
+
+    /// Record a completed section and persist the checkpoint
+    pub fn record(&self, section: &str, accounts: &[(Pubkey, Vec<u8>)]) -> Result<()> {
+        let stored = CheckpointSection {
+            accounts: accounts
+                .iter()
+                .map(|(pubkey, data)| RawAccount::encode(*pubkey, data))
+                .collect::<Result<Vec<_>>>()?,
+            hash: hash_accounts(accounts).to_string(),
+        };
+
+        let mut state = self.state.lock().expect("checkpoint lock poisoned");
+        state.sections.insert(section.to_string(), stored);
+        save(&self.path, &state)
+    }
+
+    /// Remove the checkpoint once the fetch it tracks has completed
+    pub fn finish(self) {
+        if let Err(e) = fs::remove_file(&self.path)
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            warn!("Failed to remove checkpoint {}: {e}", self.path.display());
+        }
+    }
+}
+
+/// Run `fetch` for a section unless the checkpoint already holds it
+pub async fn resume<F, Fut>(
+    checkpoint: Option<&FetchCheckpoint>,
+    section: &str,
+    fetch: F,
+) -> Result<Vec<(Pubkey, Vec<u8>)>>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<Vec<(Pubkey, Vec<u8>)>>>,
+{
+    let Some(checkpoint) = checkpoint else {
+        return fetch().await;
+    };
+
+    if let Some(accounts) = checkpoint.get(section) {
+        info!(
+            "Using {} checkpointed accounts for {section}",
+            accounts.len()
+        );
+        return Ok(accounts);
+    }
+
+    let accounts = fetch().await?;
+    // A checkpoint that cannot be written only costs the resume, not the fetch
+    if let Err(e) = checkpoint.record(section, &accounts) {
+        warn!("Failed to checkpoint {section}: {e:#}");
+    }
+    Ok(accounts)
+}
+
+fn decode_section(stored: &CheckpointSection) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+    let accounts = stored
+        .accounts
+        .iter()
+        .map(|raw| Ok((raw.pubkey, raw.decode()?)))
+        .collect::<Result<Vec<_>>>()?;
+
+    let expected: Fingerprint = stored.hash.parse()?;
+    let hash = hash_accounts_with(&accounts, expected.algorithm);
+    if hash != expected {
+        bail!("checkpoint hash mismatch: expected {expected}, computed {hash}");
+    }
+
+    Ok(accounts)
+}
+
+fn hash_accounts(accounts: &[(Pubkey, Vec<u8>)]) -> Fingerprint {
+    hash_accounts_with(accounts, FingerprintAlgorithm::default())
+}
+
+fn hash_accounts_with(
+    accounts: &[(Pubkey, Vec<u8>)],
+    algorithm: FingerprintAlgorithm,
+) -> Fingerprint {
+    let mut hasher = algorithm.hasher();
+    for (pubkey, data) in accounts {
+        hasher.update(pubkey.as_ref());
+        hasher.update((data.len() as u64).to_le_bytes());
+        hasher.update(data);
+    }
+    hasher.finalize()
+}
+
+fn settings_hash(settings: &Settings) -> Result<String> {
+    let bytes = serde_json::to_vec(settings).context("Failed to serialize settings")?;
+    Ok(FingerprintAlgorithm::default().digest(&bytes).to_string())
+}
+
+fn load(path: &Path) -> Result<Option<CheckpointState>> {
+    let contents = match fs::read(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    };
+    Ok(Some(serde_json::from_slice(&contents).with_context(
+        || format!("Failed to parse {}", path.display()),
+    )?))
+}
+
+/// Write via a temp file and rename, so a crash mid-write leaves the previous checkpoint intact
+fn save(path: &Path, state: &CheckpointState) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create checkpoint directory {}", dir.display()))?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_vec(state)?)
+        .with_context(|| format!("Failed to write {}", tmp.display()))?;
+    fs::rename(&tmp, path).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SETTINGS_HASH: &str = "sha256:00";
+
+    fn accounts() -> Vec<(Pubkey, Vec<u8>)> {
+        vec![(Pubkey::new_unique(), vec![1, 2, 3])]
+    }
+
+    #[tokio::test]
+    async fn test_resume_skips_completed_sections() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fetch.json");
+        let expected = accounts();
+
+        let checkpoint = FetchCheckpoint::open_at(path.clone(), 10, SETTINGS_HASH.to_string());
+        let fetched = resume(Some(&checkpoint), "device_telemetry", || async {
+            Ok(expected.clone())
+        })
+        .await
+        .unwrap();
+        assert_eq!(fetched, expected);
+
+        // A retry for the same epoch must not refetch
+        let checkpoint = FetchCheckpoint::open_at(path.clone(), 10, SETTINGS_HASH.to_string());
+        let resumed = resume(Some(&checkpoint), "device_telemetry", || async {
+            anyhow::bail!("section should have been resumed")
+        })
+        .await
+        .unwrap();
+        assert_eq!(resumed, expected);
+
+        checkpoint.finish();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_checkpoint_invalidated_by_epoch_or_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fetch.json");
+
+        let checkpoint = FetchCheckpoint::open_at(path.clone(), 10, SETTINGS_HASH.to_string());
+        checkpoint
+            .record("internet_telemetry", &accounts())
+            .unwrap();
+
+        let other_epoch = FetchCheckpoint::open_at(path.clone(), 11, SETTINGS_HASH.to_string());
+        assert!(other_epoch.get("internet_telemetry").is_none());
+
+        let other_settings = FetchCheckpoint::open_at(path.clone(), 10, "sha256:01".to_string());
+        assert!(other_settings.get("internet_telemetry").is_none());
+    }
+
+    #[test]
+    fn test_corrupt_section_is_refetched() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fetch.json");
+
+        let checkpoint = FetchCheckpoint::open_at(path.clone(), 10, SETTINGS_HASH.to_string());
+        checkpoint
+            .record("internet_telemetry", &accounts())
+            .unwrap();
+        checkpoint
+            .state
+            .lock()
+            .unwrap()
+            .sections
+            .get_mut("internet_telemetry")
+            .unwrap()
+            .hash = FingerprintAlgorithm::default().digest(b"other").to_string();
+
+        assert!(checkpoint.get("internet_telemetry").is_none());
+    }
+}
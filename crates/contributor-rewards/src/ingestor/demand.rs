@@ -1,9 +1,7 @@
 use crate::{
-    calculator::constants::{
-        DEMAND_MULTICAST_ENABLED, DEMAND_TRAFFIC, DEMAND_TYPE, SLOTS_IN_EPOCH,
-    },
+    calculator::constants::{DEMAND_MULTICAST_ENABLED, DEMAND_TRAFFIC, DEMAND_TYPE},
     ingestor::{
-        epoch::{EpochFinder, LeaderSchedule},
+        epoch::{EpochFinder, LeaderSchedule, print_epoch_params},
         fetcher::Fetcher,
         types::FetchData,
     },
@@ -72,6 +70,15 @@ pub async fn build(fetcher: &Fetcher, fetch_data: &FetchData) -> Result<DemandBu
         .fetch_leader_schedule(dz_epoch, timestamp_us)
         .await?;
 
+    // Surface the DZ ledger's own epoch parameters alongside the Solana ones
+    match epoch_finder.dz_epoch_params().await {
+        Ok(params) => info!(
+            "DZ Epoch Parameters:\n{}",
+            print_epoch_params(&[("dz", &params)])
+        ),
+        Err(e) => warn!("Failed to derive DZ epoch parameters: {}", e),
+    }
+
     build_with_schedule(&fetcher.settings, fetch_data, &leader_schedule)
 }
 
@@ -192,8 +199,18 @@ pub fn build_with_schedule(
         bail!("Could not build any city_stats!")
     }
 
-    // Generate demands
-    let demands = generate(&city_stats);
+    // Generate demands, weighting by the slot budget of the schedule's Solana epoch
+    match &leader_schedule.epoch_params {
+        Some(params) => info!(
+            "Solana Epoch Parameters:\n{}",
+            print_epoch_params(&[("solana", params)])
+        ),
+        None => warn!(
+            "Leader schedule has no epoch parameters; assuming {} slots per epoch",
+            leader_schedule.slots_per_epoch()
+        ),
+    }
+    let demands = generate(&city_stats, leader_schedule.slots_per_epoch());
     if demands.is_empty() {
         bail!("Could not build any demands!")
     }
@@ -250,7 +267,7 @@ pub fn build_city_stats(
 }
 
 /// Generates demand entries for cities
-pub fn generate(city_stats: &CityStats) -> Demands {
+pub fn generate(city_stats: &CityStats, slots_in_epoch: f64) -> Demands {
     // Filter cities with validators once
     let cities_with_validators: Vec<(&String, &CityStat)> = city_stats
         .iter()
@@ -273,7 +290,7 @@ pub fn generate(city_stats: &CityStats) -> Demands {
                     // Calculate priority using formula: (1/slots_in_epoch) * (total_stake_proxy/validator_count)
                     let slots_per_validator =
                         end_stats.total_stake_proxy as f64 / end_stats.validator_count as f64;
-                    let priority = (1.0 / SLOTS_IN_EPOCH) * slots_per_validator;
+                    let priority = (1.0 / slots_in_epoch) * slots_per_validator;
 
                     Some(Demand {
                         start: start_city.to_string(),
@@ -0,0 +1,291 @@
+//! Caching JSON-RPC proxy for development
+//!
+//! Iterating on processing code means refetching the same epoch over and over, and every
+//! developer doing so pulls the same multi-GB `getProgramAccounts` responses from the
+//! providers. The proxy sits in front of the DZ and Solana read endpoints, each under its own
+//! path (`/dz`, `/solana`), and answers repeated requests from a shared on-disk cache.
+//!
+//! Entries are content-addressed on upstream, method and params, so request ids do not
+//! matter, and expire after a per-method TTL: responses that can never change (blocks,
+//! transactions, leader schedules) live for a day, account reads for minutes and chain tips
+//! for seconds. Anything that submits or depends on a fresh blockhash is never cached.
+
+use anyhow::{Context, Result, bail};
+use axum::{
+    Router,
+    body::Bytes,
+    extract::{Path as UrlPath, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::post,
+};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::BTreeMap,
+    fs,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+use tracing::{debug, info, warn};
+
+/// Responses that never change once the chain has moved past them
+const IMMUTABLE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+/// Account and program reads, stable enough for a working session on a past epoch
+const ACCOUNT_TTL: Duration = Duration::from_secs(10 * 60);
+/// Chain tips, cached only to absorb bursts
+const TIP_TTL: Duration = Duration::from_secs(5);
+
+/// How long a response to `method` may be served from cache, or `None` to always forward
+pub fn method_ttl(method: &str, default_ttl: Duration) -> Option<Duration> {
+    match method {
+        "sendTransaction"
+        | "simulateTransaction"
+        | "requestAirdrop"
+        | "getLatestBlockhash"
+        | "isBlockhashValid"
+        | "getSignatureStatuses"
+        | "getFeeForMessage" => None,
+        "getBlock" | "getBlockTime" | "getTransaction" | "getLeaderSchedule"
+        | "getInflationReward" | "getGenesisHash" | "getBlocks" => Some(IMMUTABLE_TTL),
+        "getProgramAccounts" | "getMultipleAccounts" | "getAccountInfo" => Some(ACCOUNT_TTL),
+        "getEpochInfo" | "getSlot" | "getBlockHeight" => Some(TIP_TTL),
+        _ => Some(default_ttl),
+    }
+}
+
+/// On-disk response cache shared by everyone using the proxy
+#[derive(Debug, Clone)]
+pub struct RpcCache {
+    dir: PathBuf,
+    default_ttl: Duration,
+}
+
+impl RpcCache {
+    pub fn new(dir: impl Into<PathBuf>, default_ttl: Duration) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create cache directory {}", dir.display()))?;
+        Ok(Self { dir, default_ttl })
+    }
+
+    pub fn ttl(&self, method: &str) -> Option<Duration> {
+        method_ttl(method, self.default_ttl)
+    }
+
+    /// Content address of a request; the JSON-RPC id and version are not part of it
+    pub fn key(upstream: &str, method: &str, params: &Value) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(upstream);
+        hasher.update([0]);
+        hasher.update(method);
+        hasher.update([0]);
+        hasher.update(params.to_string());
+        format!("{:x}", hasher.finalize())
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.json"))
+    }
+
+    /// Cached response body for `key` if it is younger than `ttl`
+    pub fn get(&self, key: &str, ttl: Duration) -> Option<Vec<u8>> {
+        let path = self.path(key);
+        let age = fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())?;
+        if age >= ttl {
+            return None;
+        }
+        fs::read(path).ok()
+    }
+
+    pub fn put(&self, key: &str, body: &[u8]) -> Result<()> {
+        let path = self.path(key);
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, body)?;
+        fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+/// Copy the request's id into a response served for another request with the same content
+fn with_request_id(body: &[u8], id: &Value) -> Result<Vec<u8>> {
+    let mut response: Value = serde_json::from_slice(body)?;
+    if let Some(object) = response.as_object_mut() {
+        object.insert("id".to_string(), id.clone());
+    }
+    Ok(serde_json::to_vec(&response)?)
+}
+
+struct ProxyState {
+    cache: RpcCache,
+    /// Upstream URL by path segment
+    upstreams: BTreeMap<String, String>,
+    client: reqwest::Client,
+}
+
+/// Serve the proxy on `listen` until Ctrl-C
+pub async fn serve(
+    listen: SocketAddr,
+    upstreams: BTreeMap<String, String>,
+    cache: RpcCache,
+) -> Result<()> {
+    for (name, url) in &upstreams {
+        info!("Proxying http://{listen}/{name} -> {url}");
+    }
+    info!("Caching responses in {}", cache.dir().display());
+
+    let state = Arc::new(ProxyState {
+        cache,
+        upstreams,
+        client: reqwest::Client::new(),
+    });
+    let app = Router::new()
+        .route("/{upstream}", post(handle_request))
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(listen)
+        .await
+        .with_context(|| format!("Failed to bind {listen}"))?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await?;
+
+    Ok(())
+}
+
+async fn handle_request(
+    State(state): State<Arc<ProxyState>>,
+    UrlPath(upstream): UrlPath<String>,
+    body: Bytes,
+) -> Response {
+    match proxy_request(&state, &upstream, body).await {
+        Ok(body) => ([(header::CONTENT_TYPE, "application/json")], body).into_response(),
+        Err(err) => {
+            warn!("Proxy request to {upstream} failed: {err:#}");
+            (StatusCode::BAD_GATEWAY, err.to_string()).into_response()
+        }
+    }
+}
+
+async fn proxy_request(state: &ProxyState, upstream: &str, body: Bytes) -> Result<Vec<u8>> {
+    let Some(url) = state.upstreams.get(upstream) else {
+        bail!("Unknown upstream '{upstream}'");
+    };
+
+    // Batches and anything unparseable go straight through
+    let request: Value = serde_json::from_slice(&body).unwrap_or(Value::Null);
+    let method = request.get("method").and_then(Value::as_str);
+    let cacheable = method.and_then(|method| Some((method, state.cache.ttl(method)?)));
+
+    let Some((method, ttl)) = cacheable else {
+        metrics::counter!("doublezero_contributor_rewards_dev_proxy_requests", "upstream" => upstream.to_string(), "result" => "bypass")
+            .increment(1);
+        return forward(state, url, body).await;
+    };
+
+    let params = request.get("params").cloned().unwrap_or(Value::Null);
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let key = RpcCache::key(upstream, method, &params);
+
+    if let Some(cached) = state.cache.get(&key, ttl) {
+        debug!("Cache hit for {method} on {upstream}");
+        metrics::counter!("doublezero_contributor_rewards_dev_proxy_requests", "upstream" => upstream.to_string(), "result" => "hit")
+            .increment(1);
+        return with_request_id(&cached, &id);
+    }
+
+    metrics::counter!("doublezero_contributor_rewards_dev_proxy_requests", "upstream" => upstream.to_string(), "result" => "miss")
+        .increment(1);
+    let response = forward(state, url, body).await?;
+
+    // Errors are often transient (rate limits, unavailable slots), so only results are kept
+    let is_result = serde_json::from_slice::<Value>(&response)
+        .map(|value| value.get("result").is_some())
+        .unwrap_or(false);
+    if is_result && let Err(err) = state.cache.put(&key, &response) {
+        warn!("Failed to cache {method} response: {err:#}");
+    }
+
+    Ok(response)
+}
+
+async fn forward(state: &ProxyState, url: &str, body: Bytes) -> Result<Vec<u8>> {
+    let response = state
+        .client
+        .post(url)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(body)
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(response.bytes().await?.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_key_ignores_request_id() {
+        let params = json!(["Ser1ce", {"encoding": "base64"}]);
+        let key = RpcCache::key("dz", "getProgramAccounts", &params);
+        assert_eq!(key, RpcCache::key("dz", "getProgramAccounts", &params));
+        assert_ne!(key, RpcCache::key("solana", "getProgramAccounts", &params));
+        assert_ne!(key, RpcCache::key("dz", "getAccountInfo", &params));
+        assert_ne!(
+            key,
+            RpcCache::key("dz", "getProgramAccounts", &json!(["Other"]))
+        );
+    }
+
+    #[test]
+    fn test_method_ttls() {
+        let default_ttl = Duration::from_secs(60);
+        assert_eq!(method_ttl("sendTransaction", default_ttl), None);
+        assert_eq!(method_ttl("getLatestBlockhash", default_ttl), None);
+        assert_eq!(method_ttl("getBlock", default_ttl), Some(IMMUTABLE_TTL));
+        assert_eq!(
+            method_ttl("getProgramAccounts", default_ttl),
+            Some(ACCOUNT_TTL)
+        );
+        assert_eq!(
+            method_ttl("getVoteAccounts", default_ttl),
+            Some(default_ttl)
+        );
+    }
+
+    #[test]
+    fn test_cache_expiry() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = RpcCache::new(dir.path(), Duration::from_secs(60)).unwrap();
+        let key = RpcCache::key("dz", "getSlot", &Value::Null);
+
+        assert!(cache.get(&key, Duration::from_secs(60)).is_none());
+        cache
+            .put(&key, br#"{"jsonrpc":"2.0","result":7,"id":1}"#)
+            .unwrap();
+        assert!(cache.get(&key, Duration::from_secs(60)).is_some());
+        assert!(cache.get(&key, Duration::ZERO).is_none());
+    }
+
+    #[test]
+    fn test_cached_response_takes_request_id() {
+        let cached = br#"{"jsonrpc":"2.0","result":7,"id":1}"#;
+        let response: Value =
+            serde_json::from_slice(&with_request_id(cached, &json!(42)).unwrap()).unwrap();
+        assert_eq!(response["id"], 42);
+        assert_eq!(response["result"], 7);
+    }
+}
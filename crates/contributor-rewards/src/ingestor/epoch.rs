@@ -4,10 +4,15 @@
 //! - Calculate Solana epochs from slots
 //! - Estimate slots from timestamps
 //! - Find epochs corresponding to specific timestamps
-
-use crate::cli::{
-    common::{OutputFormat, to_json_string},
-    traits::Exportable,
+//! - Derive epoch parameters (schedule and slot duration) from the ledger itself
+
+use crate::{
+    calculator::constants::SLOTS_IN_EPOCH,
+    cli::{
+        common::{OutputFormat, to_json_string},
+        traits::Exportable,
+    },
+    units::Micros,
 };
 use anyhow::{Result, anyhow, bail};
 use backon::{ExponentialBuilder, Retryable};
@@ -18,11 +23,73 @@ use solana_client::{
 };
 use solana_sdk::epoch_schedule::EpochSchedule;
 use std::{collections::BTreeMap, sync::Arc, time::Duration};
-use tracing::{debug, info};
+use tabled::{Table, Tabled, settings::Style};
+use tracing::{debug, info, warn};
 
 /// Approximate slot duration in microseconds (400ms)
+///
+/// Only used as a fallback when the ledger reports no recent performance samples
 pub const SLOT_DURATION_US: u64 = 400_000;
 
+/// Number of recent performance samples used to measure the slot duration
+const PERF_SAMPLE_LIMIT: usize = 30;
+
+/// Epoch parameters derived from a ledger's epoch schedule sysvar and recent slot timing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EpochParams {
+    pub slots_per_epoch: u64,
+    pub first_normal_epoch: u64,
+    pub first_normal_slot: u64,
+    pub warmup: bool,
+    /// Measured (or fallback) slot duration in microseconds
+    pub slot_duration_us: u64,
+}
+
+impl EpochParams {
+    pub fn from_schedule(schedule: &EpochSchedule, slot_duration_us: u64) -> Self {
+        Self {
+            slots_per_epoch: schedule.slots_per_epoch,
+            first_normal_epoch: schedule.first_normal_epoch,
+            first_normal_slot: schedule.first_normal_slot,
+            warmup: schedule.warmup,
+            slot_duration_us,
+        }
+    }
+
+    /// Approximate wall-clock duration of a normal epoch in microseconds
+    pub fn epoch_duration_us(&self) -> u64 {
+        self.slots_per_epoch.saturating_mul(self.slot_duration_us)
+    }
+}
+
+/// Render epoch parameters per ledger as a table for the run summary
+pub fn print_epoch_params(params: &[(&str, &EpochParams)]) -> String {
+    #[derive(Tabled)]
+    struct EpochParamsRow {
+        ledger: String,
+        slots_per_epoch: u64,
+        first_normal_epoch: u64,
+        first_normal_slot: u64,
+        warmup: bool,
+        slot_duration_ms: String,
+        epoch_duration_hours: String,
+    }
+
+    let rows = params.iter().map(|(ledger, p)| EpochParamsRow {
+        ledger: ledger.to_string(),
+        slots_per_epoch: p.slots_per_epoch,
+        first_normal_epoch: p.first_normal_epoch,
+        first_normal_slot: p.first_normal_slot,
+        warmup: p.warmup,
+        slot_duration_ms: format!("{:.1}", Micros(p.slot_duration_us as f64).as_millis()),
+        epoch_duration_hours: format!("{:.2}", p.epoch_duration_us() as f64 / 3_600_000_000.0),
+    });
+
+    Table::new(rows)
+        .with(Style::psql().remove_horizontals())
+        .to_string()
+}
+
 // key: validator_pk, val: slot count
 pub type LeaderScheduleMap = BTreeMap<String, usize>;
 
@@ -31,6 +98,18 @@ pub type LeaderScheduleMap = BTreeMap<String, usize>;
 pub struct LeaderSchedule {
     pub solana_epoch: u64,
     pub schedule_map: LeaderScheduleMap,
+    /// Solana epoch parameters used to locate this schedule (missing in older exports)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub epoch_params: Option<EpochParams>,
+}
+
+impl LeaderSchedule {
+    /// Slots in the Solana epoch, falling back to the mainnet constant for older exports
+    pub fn slots_per_epoch(&self) -> f64 {
+        self.epoch_params
+            .map(|p| p.slots_per_epoch as f64)
+            .unwrap_or(SLOTS_IN_EPOCH)
+    }
 }
 
 impl Exportable for LeaderSchedule {
@@ -60,6 +139,7 @@ pub fn estimate_slot_from_timestamp(
     timestamp_us: u64,
     current_slot: u64,
     current_time_us: u64,
+    slot_duration_us: u64,
 ) -> Result<u64> {
     if timestamp_us > current_time_us {
         bail!("Timestamp {timestamp_us} is in the future");
@@ -67,7 +147,7 @@ pub fn estimate_slot_from_timestamp(
 
     // Calculate approximate slot at the given timestamp
     let time_diff_us = current_time_us - timestamp_us;
-    let slots_ago = time_diff_us / SLOT_DURATION_US;
+    let slots_ago = time_diff_us / slot_duration_us.max(1);
 
     if slots_ago > current_slot {
         bail!("Timestamp {timestamp_us} is too far in the past");
@@ -76,6 +156,43 @@ pub fn estimate_slot_from_timestamp(
     Ok(current_slot - slots_ago)
 }
 
+/// Measure the average slot duration from the ledger's recent performance samples
+///
+/// Falls back to `SLOT_DURATION_US` when the ledger reports no usable samples
+pub async fn fetch_slot_duration_us(client: &RpcClient) -> Result<u64> {
+    let samples = (|| async {
+        client
+            .get_recent_performance_samples(Some(PERF_SAMPLE_LIMIT))
+            .await
+    })
+    .retry(&ExponentialBuilder::default().with_jitter())
+    .notify(|err: &SolanaClientError, dur: Duration| {
+        info!(
+            "retrying get_recent_performance_samples error: {:?} with sleeping {:?}",
+            err, dur
+        )
+    })
+    .await?;
+
+    let (slots, secs) = samples.iter().fold((0u64, 0u64), |(slots, secs), sample| {
+        (
+            slots + sample.num_slots,
+            secs + sample.sample_period_secs as u64,
+        )
+    });
+
+    if slots == 0 || secs == 0 {
+        warn!(
+            "No usable performance samples from {}; assuming {}us slots",
+            client.url(),
+            SLOT_DURATION_US
+        );
+        return Ok(SLOT_DURATION_US);
+    }
+
+    Ok(secs * 1_000_000 / slots)
+}
+
 /// Helper for finding epochs at specific timestamps
 ///
 /// This struct manages the epoch schedule and provides methods for
@@ -93,6 +210,10 @@ pub struct EpochFinder {
     dz_schedule: Option<EpochSchedule>,
     /// Cached Solana epoch schedule
     solana_schedule: Option<EpochSchedule>,
+    /// Cached DZ slot duration in microseconds
+    dz_slot_duration_us: Option<u64>,
+    /// Cached Solana slot duration in microseconds
+    solana_slot_duration_us: Option<u64>,
 }
 
 impl EpochFinder {
@@ -107,6 +228,8 @@ impl EpochFinder {
             solana_read_client,
             dz_schedule: None,
             solana_schedule: None,
+            dz_slot_duration_us: None,
+            solana_slot_duration_us: None,
         }
     }
 
@@ -152,6 +275,34 @@ impl EpochFinder {
             .expect("solana_schedule cannot be none"))
     }
 
+    /// Get the DZ epoch parameters derived from its epoch schedule sysvar and slot timing
+    pub async fn dz_epoch_params(&mut self) -> Result<EpochParams> {
+        let slot_duration_us = match self.dz_slot_duration_us {
+            Some(duration) => duration,
+            None => {
+                let duration = fetch_slot_duration_us(&self.dz_rpc_client).await?;
+                self.dz_slot_duration_us = Some(duration);
+                duration
+            }
+        };
+        let schedule = self.get_dz_schedule().await?;
+        Ok(EpochParams::from_schedule(schedule, slot_duration_us))
+    }
+
+    /// Get the Solana epoch parameters derived from its epoch schedule sysvar and slot timing
+    pub async fn solana_epoch_params(&mut self) -> Result<EpochParams> {
+        let slot_duration_us = match self.solana_slot_duration_us {
+            Some(duration) => duration,
+            None => {
+                let duration = fetch_slot_duration_us(&self.solana_read_client).await?;
+                self.solana_slot_duration_us = Some(duration);
+                duration
+            }
+        };
+        let schedule = self.get_solana_schedule().await?;
+        Ok(EpochParams::from_schedule(schedule, slot_duration_us))
+    }
+
     /// Find the Solana epoch that was active at a given timestamp
     ///
     /// This uses the Solana network to map timestamps to Solana epochs
@@ -166,9 +317,14 @@ impl EpochFinder {
 
         let current_time_us = Utc::now().timestamp_micros() as u64;
 
-        // Estimate the slot at the given timestamp
-        let target_slot =
-            estimate_slot_from_timestamp(timestamp_us, current_slot, current_time_us)?;
+        // Estimate the slot at the given timestamp using the measured slot duration
+        let params = self.solana_epoch_params().await?;
+        let target_slot = estimate_slot_from_timestamp(
+            timestamp_us,
+            current_slot,
+            current_time_us,
+            params.slot_duration_us,
+        )?;
 
         // Get SOLANA epoch schedule and calculate epoch
         let schedule = self.get_solana_schedule().await?;
@@ -246,6 +402,7 @@ impl EpochFinder {
         Ok(LeaderSchedule {
             solana_epoch,
             schedule_map,
+            epoch_params: Some(self.solana_epoch_params().await?),
         })
     }
 }
@@ -277,17 +434,59 @@ mod tests {
 
         // Test normal case - 400 seconds ago (1000 slots)
         let timestamp_us = current_time_us - 400_000_000;
-        let result = estimate_slot_from_timestamp(timestamp_us, current_slot, current_time_us);
+        let result = estimate_slot_from_timestamp(
+            timestamp_us,
+            current_slot,
+            current_time_us,
+            SLOT_DURATION_US,
+        );
         assert_eq!(result.unwrap(), 999000);
 
+        // Same window with 200ms slots covers twice as many slots
+        let result =
+            estimate_slot_from_timestamp(timestamp_us, current_slot, current_time_us, 200_000);
+        assert_eq!(result.unwrap(), 998000);
+
         // Test future timestamp
         let future_timestamp = current_time_us + 1000;
-        let result = estimate_slot_from_timestamp(future_timestamp, current_slot, current_time_us);
+        let result = estimate_slot_from_timestamp(
+            future_timestamp,
+            current_slot,
+            current_time_us,
+            SLOT_DURATION_US,
+        );
         assert!(result.is_err());
 
         // Test too far in the past
         let ancient_timestamp = 0;
-        let result = estimate_slot_from_timestamp(ancient_timestamp, current_slot, current_time_us);
+        let result = estimate_slot_from_timestamp(
+            ancient_timestamp,
+            current_slot,
+            current_time_us,
+            SLOT_DURATION_US,
+        );
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_epoch_params_from_schedule() {
+        let schedule = EpochSchedule {
+            slots_per_epoch: 216000,
+            leader_schedule_slot_offset: 216000,
+            warmup: false,
+            first_normal_epoch: 0,
+            first_normal_slot: 0,
+        };
+        let params = EpochParams::from_schedule(&schedule, 200_000);
+
+        assert_eq!(params.slots_per_epoch, 216000);
+        assert_eq!(params.epoch_duration_us(), 43_200_000_000);
+    }
+
+    #[test]
+    fn test_leader_schedule_slots_per_epoch_fallback() {
+        let schedule: LeaderSchedule =
+            serde_json::from_str(r#"{"solana_epoch": 1, "schedule_map": {}}"#).unwrap();
+        assert_eq!(schedule.slots_per_epoch(), SLOTS_IN_EPOCH);
+    }
 }
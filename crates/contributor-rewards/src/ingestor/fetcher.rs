@@ -1,12 +1,19 @@
 use crate::{
-    ingestor::{internet, serviceability, telemetry, types::FetchData},
+    ingestor::{
+        checkpoint::FetchCheckpoint,
+        internet,
+        rpc_pool::{AccountCache, pooled_account_cache, pooled_client},
+        serviceability, telemetry,
+        types::FetchData,
+        window,
+    },
     settings::Settings,
 };
 use anyhow::Result;
 use chrono::Utc;
 use solana_client::nonblocking::rpc_client::RpcClient;
-use solana_sdk::commitment_config::CommitmentConfig;
-use std::sync::Arc;
+use solana_sdk::{account::Account, commitment_config::CommitmentConfig, pubkey::Pubkey};
+use std::{sync::Arc, time::Duration};
 use tracing::info;
 
 /// Combined network and telemetry data
@@ -15,31 +22,38 @@ pub struct Fetcher {
     pub dz_rpc_client: Arc<RpcClient>,
     pub solana_read_client: Arc<RpcClient>,
     pub solana_write_client: Arc<RpcClient>,
+    /// Short-lived cache for DZ ledger account reads
+    pub dz_account_cache: Arc<AccountCache>,
     pub settings: Settings,
 }
 
 impl Fetcher {
+    /// Build a fetcher backed by the process-wide RPC client pool
     pub fn from_settings(settings: &Settings) -> Result<Self> {
-        let dz_rpc_client = RpcClient::new_with_commitment(
-            settings.rpc.dz_url.to_string(),
-            CommitmentConfig::finalized(),
-        );
-        let solana_read_client = RpcClient::new_with_commitment(
-            settings.rpc.solana_read_url.to_string(),
-            CommitmentConfig::finalized(),
-        );
-        let solana_write_client = RpcClient::new_with_commitment(
-            settings.rpc.solana_write_url.to_string(),
-            CommitmentConfig::finalized(),
-        );
+        let commitment = CommitmentConfig::finalized();
         Ok(Self {
-            dz_rpc_client: Arc::new(dz_rpc_client),
-            solana_read_client: Arc::new(solana_read_client),
-            solana_write_client: Arc::new(solana_write_client),
+            dz_rpc_client: pooled_client(&settings.rpc.dz_url, commitment),
+            solana_read_client: pooled_client(&settings.rpc.solana_read_url, commitment),
+            solana_write_client: pooled_client(&settings.rpc.solana_write_url, commitment),
+            dz_account_cache: pooled_account_cache(
+                &settings.rpc.dz_url,
+                Duration::from_secs(settings.rpc.account_cache_ttl_secs),
+            ),
             settings: settings.clone(),
         })
     }
 
+    /// Read an account from the DZ ledger, served from the short-lived cache when possible
+    pub async fn get_dz_account(
+        &self,
+        address: &Pubkey,
+        commitment: CommitmentConfig,
+    ) -> Result<Option<Account>> {
+        self.dz_account_cache
+            .get_account(&self.dz_rpc_client, address, commitment)
+            .await
+    }
+
     /// Fetch all data for the previous epoch
     pub async fn fetch(&self, epoch: Option<u64>) -> Result<(u64, FetchData)> {
         let search_epoch = match epoch {
@@ -75,12 +89,25 @@ impl Fetcher {
             self.settings.programs.telemetry_program_id
         );
 
+        // Resume whatever a failed attempt for this epoch already fetched
+        let checkpoint = FetchCheckpoint::open(&self.settings, epoch)?;
+
         // Fetch all data in parallel
         let fetch_start = std::time::Instant::now();
         let (serviceability_data, telemetry_data, internet_data) = tokio::try_join!(
-            serviceability::fetch(&self.dz_rpc_client, &self.settings),
-            telemetry::fetch(&self.dz_rpc_client, &self.settings, epoch),
-            internet::fetch(&self.dz_rpc_client, &self.settings, epoch)
+            serviceability::fetch(&self.dz_rpc_client, &self.settings, Some(&checkpoint)),
+            telemetry::fetch(
+                &self.dz_rpc_client,
+                &self.settings,
+                epoch,
+                Some(&checkpoint)
+            ),
+            internet::fetch(
+                &self.dz_rpc_client,
+                &self.settings,
+                epoch,
+                Some(&checkpoint)
+            )
         )?;
 
         metrics::histogram!("doublezero_contributor_rewards_data_fetch_duration", "epoch" => epoch.to_string())
@@ -89,6 +116,14 @@ impl Fetcher {
             .increment(1);
 
         let (start_us, end_us) = telemetry_data.start_end_us()?;
+        window::check_window(
+            &self.dz_rpc_client,
+            &self.settings.time_window,
+            epoch,
+            start_us,
+            end_us,
+        )
+        .await?;
 
         info!(
             "Epoch {} time range: {} to {} microseconds",
@@ -103,6 +138,7 @@ impl Fetcher {
             end_us,
             fetched_at: Utc::now(),
         };
+        checkpoint.finish();
 
         Ok((epoch, data))
     }
@@ -0,0 +1,157 @@
+use anyhow::{Result, bail};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{fmt, str::FromStr};
+
+/// Hash algorithms fingerprints can be computed with
+///
+/// Fingerprints carry their algorithm (`<algorithm>:<hex>`), so verification keeps
+/// working for artifacts produced before a change of default.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum FingerprintAlgorithm {
+    #[default]
+    Sha256,
+    Blake3,
+}
+
+impl FingerprintAlgorithm {
+    pub const ALL: [Self; 2] = [Self::Sha256, Self::Blake3];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Sha256 => "sha256",
+            Self::Blake3 => "blake3",
+        }
+    }
+
+    pub fn hasher(&self) -> FingerprintHasher {
+        match self {
+            Self::Sha256 => FingerprintHasher::Sha256(Sha256::new()),
+            Self::Blake3 => FingerprintHasher::Blake3(Box::new(blake3::Hasher::new())),
+        }
+    }
+
+    /// Fingerprint a single buffer
+    pub fn digest(&self, data: &[u8]) -> Fingerprint {
+        let mut hasher = self.hasher();
+        hasher.update(data);
+        hasher.finalize()
+    }
+}
+
+impl fmt::Display for FingerprintAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for FingerprintAlgorithm {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match Self::ALL
+            .into_iter()
+            .find(|algorithm| algorithm.name() == s)
+        {
+            Some(algorithm) => Ok(algorithm),
+            None => bail!("Unsupported fingerprint algorithm: {s}"),
+        }
+    }
+}
+
+/// Incremental hasher for any registered algorithm
+pub enum FingerprintHasher {
+    Sha256(Sha256),
+    Blake3(Box<blake3::Hasher>),
+}
+
+impl FingerprintHasher {
+    pub fn update(&mut self, data: impl AsRef<[u8]>) {
+        match self {
+            Self::Sha256(hasher) => hasher.update(data),
+            Self::Blake3(hasher) => {
+                hasher.update(data.as_ref());
+            }
+        }
+    }
+
+    pub fn finalize(self) -> Fingerprint {
+        match self {
+            Self::Sha256(hasher) => Fingerprint {
+                algorithm: FingerprintAlgorithm::Sha256,
+                hex: format!("{:x}", hasher.finalize()),
+            },
+            Self::Blake3(hasher) => Fingerprint {
+                algorithm: FingerprintAlgorithm::Blake3,
+                hex: hasher.finalize().to_hex().to_string(),
+            },
+        }
+    }
+}
+
+/// A digest tagged with the algorithm that produced it, rendered as `<algorithm>:<hex>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fingerprint {
+    pub algorithm: FingerprintAlgorithm,
+    pub hex: String,
+}
+
+impl fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.algorithm, self.hex)
+    }
+}
+
+impl FromStr for Fingerprint {
+    type Err = anyhow::Error;
+
+    /// Parse `<algorithm>:<hex>`; a bare hex digest predates algorithm tags and is SHA-256
+    fn from_str(s: &str) -> Result<Self> {
+        let (algorithm, hex) = match s.split_once(':') {
+            Some((algorithm, hex)) => (algorithm.parse()?, hex),
+            None => (FingerprintAlgorithm::Sha256, s),
+        };
+        if hex.is_empty() || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            bail!("Invalid {algorithm} fingerprint digest: {hex}");
+        }
+        Ok(Self {
+            algorithm,
+            hex: hex.to_ascii_lowercase(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint_roundtrip() {
+        for algorithm in FingerprintAlgorithm::ALL {
+            let fingerprint = algorithm.digest(b"doublezero");
+            let rendered = fingerprint.to_string();
+            assert!(rendered.starts_with(&format!("{algorithm}:")));
+            assert_eq!(rendered.parse::<Fingerprint>().unwrap(), fingerprint);
+        }
+
+        let sha256 = FingerprintAlgorithm::Sha256.digest(b"doublezero");
+        let blake3 = FingerprintAlgorithm::Blake3.digest(b"doublezero");
+        assert_ne!(sha256.hex, blake3.hex);
+    }
+
+    #[test]
+    fn test_untagged_fingerprint_is_sha256() {
+        let sha256 = FingerprintAlgorithm::Sha256.digest(b"doublezero");
+        let legacy: Fingerprint = sha256.hex.parse().unwrap();
+        assert_eq!(legacy, sha256);
+    }
+
+    #[test]
+    fn test_invalid_fingerprints() {
+        assert!("md5:abcdef".parse::<Fingerprint>().is_err());
+        assert!("sha256:".parse::<Fingerprint>().is_err());
+        assert!("sha256:not-hex".parse::<Fingerprint>().is_err());
+    }
+}
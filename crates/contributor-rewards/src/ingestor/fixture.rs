@@ -0,0 +1,191 @@
+//! Small, anonymized test fixtures cut from live epoch data
+//!
+//! The full topology is kept so processor and Shapley tests see realistic networks; only the
+//! latency series are thinned out, keeping every n-th sample so their distribution and time
+//! coverage survive. Every pubkey is then replaced by a synthetic one, consistently across the
+//! whole fixture, so references between accounts still resolve.
+
+use crate::ingestor::types::FetchData;
+use anyhow::{Result, bail};
+use serde_json::{Map, Value};
+use solana_sdk::{hash::hashv, pubkey::Pubkey};
+use std::{collections::HashMap, str::FromStr};
+
+// Domain separator for synthetic fixture pubkeys
+const FIXTURE_KEY_SEED: &[u8] = b"contributor-rewards-fixture";
+
+/// Downsample `fetch_data` to roughly `scale` of its latency samples and re-key every pubkey
+pub fn build_fixture(fetch_data: &FetchData, scale: f64) -> Result<FetchData> {
+    let mut fixture = fetch_data.clone();
+    downsample(&mut fixture, scale)?;
+    anonymize(&fixture)
+}
+
+/// Keep every n-th latency sample, with n the nearest whole number to `1 / scale`
+///
+/// Sampling intervals are stretched by the same factor so the series still span their epoch.
+pub fn downsample(fetch_data: &mut FetchData, scale: f64) -> Result<()> {
+    if !(scale > 0.0 && scale <= 1.0) {
+        bail!("Fixture scale must be in (0, 1], got {scale}");
+    }
+    let stride = (1.0 / scale).round().max(1.0) as usize;
+
+    for series in fetch_data.dz_telemetry.device_latency_samples.iter_mut() {
+        series.samples = thin(&series.samples, series.sample_count, stride);
+        series.sample_count = series.samples.len() as u32;
+        series.sampling_interval_us *= stride as u64;
+    }
+    for series in fetch_data.dz_internet.internet_latency_samples.iter_mut() {
+        series.samples = thin(&series.samples, series.sample_count, stride);
+        series.sample_count = series.samples.len() as u32;
+        series.sampling_interval_us *= stride as u64;
+    }
+
+    Ok(())
+}
+
+fn thin(samples: &[u32], sample_count: u32, stride: usize) -> Vec<u32> {
+    let recorded = samples.len().min(sample_count as usize);
+    samples[..recorded]
+        .iter()
+        .step_by(stride)
+        .copied()
+        .collect()
+}
+
+/// Replace every pubkey in `fetch_data` with a synthetic one
+///
+/// Keys are assigned in order of first appearance, so the same input always produces the same
+/// fixture and nothing about the original keys can be recovered from it. The default pubkey is
+/// left alone since it marks unset references.
+pub fn anonymize(fetch_data: &FetchData) -> Result<FetchData> {
+    let mut value = serde_json::to_value(fetch_data)?;
+    Rekeyer::default().rekey_value(&mut value);
+    Ok(serde_json::from_value(value)?)
+}
+
+#[derive(Default)]
+struct Rekeyer {
+    keys: HashMap<Pubkey, Pubkey>,
+}
+
+impl Rekeyer {
+    fn rekey_value(&mut self, value: &mut Value) {
+        match value {
+            Value::String(s) => {
+                if let Some(rekeyed) = self.rekey_str(s) {
+                    *s = rekeyed;
+                }
+            }
+            Value::Array(values) => values.iter_mut().for_each(|v| self.rekey_value(v)),
+            Value::Object(map) => {
+                let entries = std::mem::take(map);
+                *map = entries
+                    .into_iter()
+                    .map(|(key, mut v)| {
+                        self.rekey_value(&mut v);
+                        (self.rekey_str(&key).unwrap_or(key), v)
+                    })
+                    .collect::<Map<_, _>>();
+            }
+            Value::Null | Value::Bool(_) | Value::Number(_) => {}
+        }
+    }
+
+    fn rekey_str(&mut self, s: &str) -> Option<String> {
+        let pubkey = Pubkey::from_str(s).ok()?;
+        if pubkey == Pubkey::default() {
+            return None;
+        }
+        Some(self.rekey(pubkey).to_string())
+    }
+
+    fn rekey(&mut self, pubkey: Pubkey) -> Pubkey {
+        let next = self.keys.len() as u64;
+        *self.keys.entry(pubkey).or_insert_with(|| {
+            Pubkey::new_from_array(hashv(&[FIXTURE_KEY_SEED, &next.to_le_bytes()]).to_bytes())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingestor::types::DZDeviceLatencySamples;
+
+    fn series(origin: Pubkey, target: Pubkey, samples: Vec<u32>) -> DZDeviceLatencySamples {
+        DZDeviceLatencySamples {
+            pubkey: Pubkey::new_unique(),
+            epoch: 42,
+            origin_device_pk: origin,
+            target_device_pk: target,
+            link_pk: Pubkey::new_unique(),
+            origin_device_location_pk: Pubkey::new_unique(),
+            target_device_location_pk: Pubkey::new_unique(),
+            origin_device_agent_pk: Pubkey::default(),
+            sampling_interval_us: 10_000_000,
+            start_timestamp_us: 1_700_000_000_000_000,
+            sample_count: samples.len() as u32,
+            samples,
+        }
+    }
+
+    #[test]
+    fn test_downsample_keeps_every_nth_sample() {
+        let mut fetch_data = FetchData::default();
+        let mut full = series(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            (0..100).collect(),
+        );
+        // Unrecorded trailing slots are dropped
+        full.sample_count = 95;
+        fetch_data.dz_telemetry.device_latency_samples.push(full);
+
+        downsample(&mut fetch_data, 0.05).unwrap();
+
+        let thinned = &fetch_data.dz_telemetry.device_latency_samples[0];
+        assert_eq!(thinned.samples, vec![0, 20, 40, 60, 80]);
+        assert_eq!(thinned.sample_count, 5);
+        assert_eq!(thinned.sampling_interval_us, 200_000_000);
+
+        assert!(downsample(&mut fetch_data, 0.0).is_err());
+        assert!(downsample(&mut fetch_data, 1.5).is_err());
+    }
+
+    #[test]
+    fn test_anonymize_rekeys_consistently() {
+        let (a, b, c) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let mut fetch_data = FetchData::default();
+        fetch_data
+            .dz_telemetry
+            .device_latency_samples
+            .extend([series(a, b, vec![1]), series(b, c, vec![2])]);
+
+        let anonymized = anonymize(&fetch_data).unwrap();
+        let samples = &anonymized.dz_telemetry.device_latency_samples;
+
+        let originals = [a, b, c];
+        for key in [
+            samples[0].origin_device_pk,
+            samples[0].target_device_pk,
+            samples[1].target_device_pk,
+        ] {
+            assert!(!originals.contains(&key));
+        }
+        assert_eq!(samples[0].target_device_pk, samples[1].origin_device_pk);
+        assert_ne!(samples[0].origin_device_pk, samples[1].target_device_pk);
+        assert_eq!(samples[0].origin_device_agent_pk, Pubkey::default());
+
+        // Same input, same fixture
+        let again = anonymize(&fetch_data).unwrap();
+        assert_eq!(
+            again.dz_telemetry.device_latency_samples[1].target_device_pk,
+            samples[1].target_device_pk
+        );
+    }
+}
@@ -1,7 +1,10 @@
 use crate::{
     ingestor::{
+        checkpoint::{self, FetchCheckpoint},
         inet_accumulator::{EpochData, InetLookbackAccumulator, InetLookbackConfig},
-        types::{DZInternetData, DZInternetLatencySamples},
+        low_rpc,
+        types::{DZInternetData, DZInternetLatencySamples, KeyedAccounts},
+        validation::{self, AgentContribution},
     },
     settings::Settings,
 };
@@ -17,7 +20,7 @@ use solana_client::{
     rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
     rpc_filter::{Memcmp, RpcFilterType},
 };
-use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use solana_sdk::{account::Account, commitment_config::CommitmentConfig, pubkey::Pubkey};
 use std::{str::FromStr, time::Duration};
 use tracing::{debug, info, warn};
 
@@ -26,10 +29,13 @@ use tracing::{debug, info, warn};
 const ACCOUNT_TYPE_DISCRIMINATOR: u8 = AccountType::InternetLatencySamples as u8;
 
 /// Fetch telemetry data for a specific epoch using RPC filtering
+///
+/// With a checkpoint, accounts fetched by an earlier attempt for the same epoch are reused.
 pub async fn fetch(
     rpc_client: &RpcClient,
     settings: &Settings,
     epoch: u64,
+    checkpoint: Option<&FetchCheckpoint>,
 ) -> Result<DZInternetData> {
     let program_id = &settings.programs.telemetry_program_id;
     let program_pubkey = Pubkey::from_str(program_id)
@@ -55,23 +61,64 @@ pub async fn fetch(
         ..RpcProgramAccountsConfig::default()
     };
 
-    let accounts = (|| async {
-        rpc_client
-            .get_program_accounts_with_config(&program_pubkey, config.clone())
-            .await
-    })
-    .retry(&ExponentialBuilder::default().with_jitter())
-    .notify(|err: &SolanaClientError, dur: Duration| {
-        info!("retrying error: {:?} with sleeping {:?}", err, dur)
+    let accounts = checkpoint::resume(checkpoint, "internet_telemetry", || async {
+        let accounts = if settings.rpc.low_rpc {
+            low_rpc::get_program_accounts(
+                rpc_client,
+                settings.rpc.low_rpc_rps_limit,
+                &program_pubkey,
+                &config,
+            )
+            .await?
+        } else {
+            (|| async {
+                rpc_client
+                    .get_program_accounts_with_config(&program_pubkey, config.clone())
+                    .await
+            })
+            .retry(&ExponentialBuilder::default().with_jitter())
+            .notify(|err: &SolanaClientError, dur: Duration| {
+                info!("retrying error: {:?} with sleeping {:?}", err, dur)
+            })
+            .await?
+        };
+
+        info!(
+            "Found {} internet accounts for epoch {}",
+            accounts.len(),
+            epoch
+        );
+
+        Ok(validation::retain_valid(
+            accounts,
+            &program_pubkey,
+            ACCOUNT_TYPE_DISCRIMINATOR,
+            "internet_telemetry",
+        )
+        .into_iter()
+        .map(|(pubkey, account)| (pubkey, account.data))
+        .collect())
     })
     .await?;
 
-    info!(
-        "Found {} internet accounts for epoch {}",
-        accounts.len(),
-        epoch
-    );
+    let accounts = accounts
+        .into_iter()
+        .map(|(pubkey, data)| {
+            (
+                pubkey,
+                Account {
+                    data,
+                    ..Account::default()
+                },
+            )
+        })
+        .collect();
+
+    Ok(from_accounts(accounts, epoch))
+}
 
+/// Decode raw internet latency sample accounts for a specific epoch
+pub fn from_accounts(accounts: KeyedAccounts, epoch: u64) -> DZInternetData {
     let mut internet_latency_samples = Vec::new();
     let batch_size = 100;
     let mut error_count = 0;
@@ -92,6 +139,7 @@ pub async fn fetch(
                             "Unexpected epoch mismatch: expected {}, got {}",
                             epoch, samples.header.epoch
                         );
+                        validation::record_skipped("internet_telemetry", "epoch_mismatch");
                         continue;
                     }
 
@@ -107,6 +155,7 @@ pub async fn fetch(
                 }
                 Err(e) => {
                     warn!("Failed to deserialize internet account {}: {}", pubkey, e);
+                    validation::record_skipped("internet_telemetry", "corrupt");
                     error_count += 1;
                 }
             }
@@ -121,7 +170,7 @@ pub async fn fetch(
     );
 
     if internet_latency_samples.is_empty() {
-        return Ok(DZInternetData::default());
+        return DZInternetData::default();
     }
 
     let total_samples: usize = internet_latency_samples
@@ -134,10 +183,40 @@ pub async fn fetch(
         "DZD internet stats for epoch {epoch}, total_samples={total_samples}, avg_samples_per_account={avg_samples_per_account}",
     );
 
-    Ok(DZInternetData {
+    DZInternetData {
         internet_latency_samples,
         accounts,
-    })
+    }
+}
+
+/// Check internet sample writers against `internet_agents.allowlist`
+///
+/// Samples from unapproved agents are dropped unless `internet_agents.flag_only` is set.
+pub fn apply_agent_allowlist(
+    settings: &Settings,
+    data: &mut DZInternetData,
+) -> Result<Vec<AgentContribution>> {
+    let allowlist = settings
+        .internet_agents
+        .allowlist
+        .iter()
+        .map(|agent| {
+            Pubkey::from_str(agent).with_context(|| format!("Invalid internet agent: {agent}"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let contributions =
+        validation::validate_internet_agents(data, &allowlist, settings.internet_agents.flag_only);
+    for contribution in &contributions {
+        metrics::gauge!(
+            "doublezero_contributor_rewards_internet_agent_samples",
+            "agent" => contribution.agent.clone(),
+            "approved" => contribution.approved.to_string()
+        )
+        .set(contribution.samples as f64);
+    }
+
+    Ok(contributions)
 }
 
 /// Fetch internet telemetry data using the lookback accumulator
@@ -167,7 +246,7 @@ pub async fn fetch_with_accumulator(
         let current_epoch = target_epoch.saturating_sub(i);
 
         // Fetch data for this epoch
-        let data = fetch(rpc_client, settings, current_epoch).await?;
+        let data = fetch(rpc_client, settings, current_epoch, None).await?;
 
         if data.internet_latency_samples.is_empty() {
             warn!(
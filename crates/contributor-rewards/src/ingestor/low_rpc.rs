@@ -0,0 +1,139 @@
+//! Reading program accounts through a public, rate-limited RPC
+//!
+//! `getProgramAccounts` with full account data is the heaviest request an RPC serves, and public
+//! endpoints throttle or reject it. In low RPC mode the matching addresses are listed with an
+//! empty data slice, then the accounts are read with batched `getMultipleAccounts`, all under a
+//! requests-per-second budget shared by every fetch in the process. Slow, but it completes.
+
+use anyhow::Result;
+use backon::{ExponentialBuilder, Retryable};
+use governor::{DefaultDirectRateLimiter, Quota, RateLimiter};
+use solana_account_decoder::UiDataSliceConfig;
+use solana_client::{
+    client_error::ClientError as SolanaClientError,
+    nonblocking::rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+use std::{
+    num::NonZeroU32,
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+use tracing::{debug, info};
+
+/// Most accounts `getMultipleAccounts` returns per request
+const MAX_ACCOUNTS_PER_REQUEST: usize = 100;
+
+/// Batches between progress updates
+const PROGRESS_INTERVAL: usize = 10;
+
+/// Budget shared by every low RPC fetch, created with the first fetch's limit
+static RATE_LIMITER: OnceLock<DefaultDirectRateLimiter> = OnceLock::new();
+
+fn rate_limiter(rps_limit: u32) -> &'static DefaultDirectRateLimiter {
+    RATE_LIMITER.get_or_init(|| {
+        RateLimiter::direct(Quota::per_second(
+            NonZeroU32::new(rps_limit).expect("Low RPC rate limit must be > 0"),
+        ))
+    })
+}
+
+/// Fetch the accounts of `program_pubkey` matching `config`'s filters within `rps_limit`
+pub async fn get_program_accounts(
+    rpc_client: &RpcClient,
+    rps_limit: u32,
+    program_pubkey: &Pubkey,
+    config: &RpcProgramAccountsConfig,
+) -> Result<Vec<(Pubkey, Account)>> {
+    let rate_limiter = rate_limiter(rps_limit);
+
+    let mut list_config = config.clone();
+    list_config.account_config.data_slice = Some(UiDataSliceConfig {
+        offset: 0,
+        length: 0,
+    });
+
+    rate_limiter.until_ready().await;
+    let addresses: Vec<Pubkey> = (|| async {
+        rpc_client
+            .get_program_accounts_with_config(program_pubkey, list_config.clone())
+            .await
+    })
+    .retry(&ExponentialBuilder::default().with_jitter())
+    .notify(|err: &SolanaClientError, dur: Duration| {
+        info!("retrying error: {:?} with sleeping {:?}", err, dur)
+    })
+    .await?
+    .into_iter()
+    .map(|(pubkey, _)| pubkey)
+    .collect();
+
+    let batches = addresses.len().div_ceil(MAX_ACCOUNTS_PER_REQUEST);
+    info!(
+        "Reading {} accounts of {program_pubkey} in {batches} batches at {rps_limit} requests/s, estimated completion in {:.0?}",
+        addresses.len(),
+        estimated_duration(batches, rps_limit)
+    );
+
+    let account_config = RpcAccountInfoConfig {
+        data_slice: None,
+        ..config.account_config.clone()
+    };
+
+    let start = Instant::now();
+    let mut accounts = Vec::with_capacity(addresses.len());
+    for (i, batch) in addresses.chunks(MAX_ACCOUNTS_PER_REQUEST).enumerate() {
+        rate_limiter.until_ready().await;
+        let response = (|| async {
+            rpc_client
+                .get_multiple_accounts_with_config(batch, account_config.clone())
+                .await
+        })
+        .retry(&ExponentialBuilder::default().with_jitter())
+        .notify(|err: &SolanaClientError, dur: Duration| {
+            info!("retrying error: {:?} with sleeping {:?}", err, dur)
+        })
+        .await?;
+
+        // Accounts closed since they were listed come back empty
+        accounts.extend(
+            batch
+                .iter()
+                .zip(response.value)
+                .filter_map(|(pubkey, account)| account.map(|account| (*pubkey, account))),
+        );
+
+        let done = i + 1;
+        if done % PROGRESS_INTERVAL == 0 && done < batches {
+            info!(
+                "Read {done}/{batches} batches, estimated completion in {:.0?}",
+                estimated_duration(batches - done, rps_limit)
+            );
+        }
+    }
+    debug!(
+        "Reading {} accounts of {program_pubkey} took {:?}",
+        accounts.len(),
+        start.elapsed()
+    );
+
+    Ok(accounts)
+}
+
+/// Time `requests` take under a budget of `rps_limit` requests per second
+pub fn estimated_duration(requests: usize, rps_limit: u32) -> Duration {
+    Duration::from_secs_f64(requests as f64 / f64::from(rps_limit.max(1)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimated_duration() {
+        assert_eq!(estimated_duration(0, 2), Duration::ZERO);
+        assert_eq!(estimated_duration(30, 2), Duration::from_secs(15));
+        assert_eq!(estimated_duration(5, 0), Duration::from_secs(5));
+    }
+}
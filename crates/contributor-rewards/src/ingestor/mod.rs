@@ -1,8 +1,18 @@
+pub mod checkpoint;
 pub mod demand;
+pub mod dev_proxy;
 pub mod epoch;
 pub mod fetcher;
+pub mod fingerprint;
+pub mod fixture;
 pub mod inet_accumulator;
 pub mod internet;
+pub mod low_rpc;
+pub mod provenance;
+pub mod raw;
+pub mod rpc_pool;
 pub mod serviceability;
 pub mod telemetry;
 pub mod types;
+pub mod validation;
+pub mod window;
@@ -0,0 +1,241 @@
+use crate::ingestor::{
+    fingerprint::{Fingerprint, FingerprintAlgorithm},
+    internet, serviceability, telemetry,
+    types::FetchData,
+};
+use anyhow::{Context, Result, bail};
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use doublezero_program_common::serializer;
+use serde::{Deserialize, Serialize};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+use tracing::{info, warn};
+
+/// zstd compression level used for embedded account data
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// A single raw account with its data zstd-compressed and base64-encoded
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RawAccount {
+    #[serde(
+        serialize_with = "serializer::serialize_pubkey_as_string",
+        deserialize_with = "serializer::deserialize_pubkey_from_string"
+    )]
+    pub pubkey: Pubkey,
+    pub data: String,
+}
+
+impl RawAccount {
+    pub(crate) fn encode(pubkey: Pubkey, data: &[u8]) -> Result<Self> {
+        let compressed = zstd::encode_all(data, COMPRESSION_LEVEL)
+            .with_context(|| format!("Failed to compress account {pubkey}"))?;
+        Ok(Self {
+            pubkey,
+            data: BASE64.encode(compressed),
+        })
+    }
+
+    pub(crate) fn decode(&self) -> Result<Vec<u8>> {
+        let compressed = BASE64
+            .decode(&self.data)
+            .with_context(|| format!("Invalid base64 data for account {}", self.pubkey))?;
+        zstd::decode_all(&compressed[..])
+            .with_context(|| format!("Failed to decompress account {}", self.pubkey))
+    }
+}
+
+/// Raw account bytes embedded in a v2 snapshot, alongside cross-hashes
+/// tying them to the processed data stored in the same snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RawAccounts {
+    pub serviceability: Vec<RawAccount>,
+    pub device_telemetry: Vec<RawAccount>,
+    pub internet_telemetry: Vec<RawAccount>,
+    /// Fingerprint (`<algorithm>:<hex>`) over all (uncompressed) raw account bytes
+    pub raw_hash: String,
+    /// Fingerprint over the processed fetch data at capture time
+    pub processed_hash: String,
+}
+
+impl RawAccounts {
+    /// Capture the raw accounts retained by the ingestors when `fetch_data` was fetched
+    pub fn capture(fetch_data: &FetchData, algorithm: FingerprintAlgorithm) -> Result<Self> {
+        let serviceability = fetch_data
+            .dz_serviceability
+            .accounts
+            .iter()
+            .map(|(pubkey, data)| RawAccount::encode(*pubkey, data))
+            .collect::<Result<Vec<_>>>()?;
+        let device_telemetry = fetch_data
+            .dz_telemetry
+            .accounts
+            .iter()
+            .map(|(pubkey, account)| RawAccount::encode(*pubkey, &account.data))
+            .collect::<Result<Vec<_>>>()?;
+        let internet_telemetry = fetch_data
+            .dz_internet
+            .accounts
+            .iter()
+            .map(|(pubkey, account)| RawAccount::encode(*pubkey, &account.data))
+            .collect::<Result<Vec<_>>>()?;
+
+        if serviceability.is_empty() {
+            bail!("No raw serviceability accounts available to embed in snapshot");
+        }
+
+        let mut raw = Self {
+            serviceability,
+            device_telemetry,
+            internet_telemetry,
+            raw_hash: String::new(),
+            processed_hash: hash_processed(fetch_data, algorithm)?.to_string(),
+        };
+        raw.raw_hash = raw.compute_raw_hash(algorithm)?.to_string();
+
+        Ok(raw)
+    }
+
+    /// Check both cross-hashes against the embedded raw bytes and the given processed data
+    ///
+    /// Each hash is recomputed with the algorithm it was recorded with.
+    pub fn verify(&self, fetch_data: &FetchData) -> Result<()> {
+        let expected: Fingerprint = self.raw_hash.parse()?;
+        let raw_hash = self.compute_raw_hash(expected.algorithm)?;
+        if raw_hash != expected {
+            bail!("Snapshot raw account hash mismatch: expected {expected}, computed {raw_hash}");
+        }
+
+        let expected: Fingerprint = self.processed_hash.parse()?;
+        let processed_hash = hash_processed(fetch_data, expected.algorithm)?;
+        if processed_hash != expected {
+            bail!(
+                "Snapshot processed data hash mismatch: expected {expected}, computed {processed_hash}"
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Re-run the ingestor decoding over the embedded raw accounts
+    ///
+    /// `original` supplies the fields that are not derived from account data (e.g. `fetched_at`)
+    /// so the result can be compared against the stored processed data.
+    pub fn reprocess(&self, epoch: u64, original: &FetchData) -> Result<FetchData> {
+        let serviceability_accounts = self
+            .serviceability
+            .iter()
+            .map(|raw| Ok((raw.pubkey, raw.decode()?)))
+            .collect::<Result<Vec<_>>>()?;
+
+        let dz_serviceability = serviceability::from_accounts(serviceability_accounts)?;
+        let dz_telemetry = telemetry::from_accounts(decode_keyed(&self.device_telemetry)?, epoch);
+        let dz_internet = internet::from_accounts(decode_keyed(&self.internet_telemetry)?, epoch);
+        let (start_us, end_us) = dz_telemetry.start_end_us()?;
+
+        let reprocessed = FetchData {
+            dz_serviceability,
+            dz_telemetry,
+            dz_internet,
+            start_us,
+            end_us,
+            fetched_at: original.fetched_at,
+        };
+
+        let expected: Fingerprint = self.processed_hash.parse()?;
+        if hash_processed(&reprocessed, expected.algorithm)? == expected {
+            info!("Reprocessed snapshot data matches the stored processed data");
+        } else {
+            warn!(
+                "Reprocessed snapshot data differs from the stored processed data; processing has changed since capture"
+            );
+        }
+
+        Ok(reprocessed)
+    }
+
+    fn compute_raw_hash(&self, algorithm: FingerprintAlgorithm) -> Result<Fingerprint> {
+        let mut hasher = algorithm.hasher();
+        for (section, accounts) in [
+            ("serviceability", &self.serviceability),
+            ("device_telemetry", &self.device_telemetry),
+            ("internet_telemetry", &self.internet_telemetry),
+        ] {
+            hasher.update(section.as_bytes());
+            for raw in accounts {
+                let data = raw.decode()?;
+                hasher.update(raw.pubkey.as_ref());
+                hasher.update((data.len() as u64).to_le_bytes());
+                hasher.update(&data);
+            }
+        }
+        Ok(hasher.finalize())
+    }
+}
+
+fn decode_keyed(accounts: &[RawAccount]) -> Result<Vec<(Pubkey, Account)>> {
+    accounts
+        .iter()
+        .map(|raw| {
+            Ok((
+                raw.pubkey,
+                Account {
+                    data: raw.decode()?,
+                    ..Account::default()
+                },
+            ))
+        })
+        .collect()
+}
+
+fn hash_processed(fetch_data: &FetchData, algorithm: FingerprintAlgorithm) -> Result<Fingerprint> {
+    let bytes = serde_json::to_vec(fetch_data).context("Failed to serialize fetch data")?;
+    Ok(algorithm.digest(&bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_raw_account_roundtrip() {
+        let data = vec![7u8; 1024];
+        let raw = RawAccount::encode(Pubkey::new_unique(), &data).unwrap();
+
+        assert!(raw.data.len() < data.len());
+        assert_eq!(raw.decode().unwrap(), data);
+    }
+
+    fn raw_accounts(algorithm: FingerprintAlgorithm) -> RawAccounts {
+        let mut raw = RawAccounts {
+            serviceability: vec![RawAccount::encode(Pubkey::new_unique(), &[1, 2, 3]).unwrap()],
+            device_telemetry: vec![],
+            internet_telemetry: vec![],
+            raw_hash: String::new(),
+            processed_hash: hash_processed(&FetchData::default(), algorithm)
+                .unwrap()
+                .to_string(),
+        };
+        raw.raw_hash = raw.compute_raw_hash(algorithm).unwrap().to_string();
+        raw
+    }
+
+    #[test]
+    fn test_raw_hash_detects_tampering() {
+        for algorithm in FingerprintAlgorithm::ALL {
+            let mut raw = raw_accounts(algorithm);
+            assert!(raw.raw_hash.starts_with(algorithm.name()));
+            assert!(raw.verify(&FetchData::default()).is_ok());
+
+            raw.serviceability[0] =
+                RawAccount::encode(raw.serviceability[0].pubkey, &[1, 2, 4]).unwrap();
+            assert!(raw.verify(&FetchData::default()).is_err());
+        }
+    }
+
+    #[test]
+    fn test_untagged_hashes_verify_as_sha256() {
+        let mut raw = raw_accounts(FingerprintAlgorithm::Sha256);
+        raw.raw_hash = raw.raw_hash.trim_start_matches("sha256:").to_string();
+        raw.processed_hash = raw.processed_hash.trim_start_matches("sha256:").to_string();
+        assert!(raw.verify(&FetchData::default()).is_ok());
+    }
+}
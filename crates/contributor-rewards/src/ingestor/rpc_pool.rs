@@ -0,0 +1,160 @@
+use anyhow::{Result, anyhow};
+use backon::{ExponentialBuilder, Retryable};
+use futures::future::{BoxFuture, FutureExt, Shared};
+use solana_client::{
+    client_error::ClientError as SolanaClientError, nonblocking::rpc_client::RpcClient,
+};
+use solana_sdk::{
+    account::Account,
+    commitment_config::{CommitmentConfig, CommitmentLevel},
+    pubkey::Pubkey,
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+use tracing::{debug, info};
+
+/// RPC clients shared across every `Fetcher` in the process, keyed by (url, commitment)
+static CLIENT_POOL: OnceLock<Mutex<HashMap<(String, CommitmentLevel), Arc<RpcClient>>>> =
+    OnceLock::new();
+
+/// Account caches shared across every `Fetcher` in the process, keyed by url
+static ACCOUNT_CACHE_POOL: OnceLock<Mutex<HashMap<String, Arc<AccountCache>>>> = OnceLock::new();
+
+/// Get (or create) the pooled RPC client for a given url and commitment
+pub fn pooled_client(url: &str, commitment: CommitmentConfig) -> Arc<RpcClient> {
+    let pool = CLIENT_POOL.get_or_init(Default::default);
+    let mut pool = pool.lock().expect("rpc client pool lock poisoned");
+    pool.entry((url.to_string(), commitment.commitment))
+        .or_insert_with(|| {
+            debug!("Creating pooled RPC client for {url}");
+            Arc::new(RpcClient::new_with_commitment(url.to_string(), commitment))
+        })
+        .clone()
+}
+
+/// Get (or create) the pooled account cache for a given url
+///
+/// The TTL is only applied when the cache is first created for the url
+pub fn pooled_account_cache(url: &str, ttl: Duration) -> Arc<AccountCache> {
+    let pool = ACCOUNT_CACHE_POOL.get_or_init(Default::default);
+    let mut pool = pool.lock().expect("account cache pool lock poisoned");
+    pool.entry(url.to_string())
+        .or_insert_with(|| Arc::new(AccountCache::new(ttl)))
+        .clone()
+}
+
+type SharedAccountFetch = Shared<BoxFuture<'static, Result<Option<Account>, String>>>;
+
+/// Short-lived cache of account reads keyed by (address, commitment)
+///
+/// Concurrent reads of the same key are coalesced into a single RPC request.
+/// Failed reads are never cached. A TTL of zero disables caching entirely.
+pub struct AccountCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<(Pubkey, CommitmentLevel), (Instant, SharedAccountFetch)>>,
+}
+
+impl AccountCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Fetch an account, serving from the cache when a fresh entry exists
+    pub async fn get_account(
+        &self,
+        client: &Arc<RpcClient>,
+        address: &Pubkey,
+        commitment: CommitmentConfig,
+    ) -> Result<Option<Account>> {
+        if self.ttl.is_zero() {
+            return fetch_account(client.clone(), *address, commitment)
+                .await
+                .map_err(|e| anyhow!(e));
+        }
+
+        let key = (*address, commitment.commitment);
+        let fetch = {
+            let mut entries = self.entries.lock().expect("account cache lock poisoned");
+            let now = Instant::now();
+            entries.retain(|_, (inserted_at, _)| now.duration_since(*inserted_at) < self.ttl);
+
+            match entries.get(&key) {
+                Some((_, fetch)) => {
+                    debug!("Account cache hit for {address}");
+                    fetch.clone()
+                }
+                None => {
+                    let fetch = fetch_account(client.clone(), *address, commitment)
+                        .boxed()
+                        .shared();
+                    entries.insert(key, (now, fetch.clone()));
+                    fetch
+                }
+            }
+        };
+
+        let result = fetch.await;
+        if result.is_err() {
+            self.invalidate(address, commitment);
+        }
+
+        result.map_err(|e| anyhow!(e))
+    }
+
+    /// Drop any cached entry for the given key
+    pub fn invalidate(&self, address: &Pubkey, commitment: CommitmentConfig) {
+        self.entries
+            .lock()
+            .expect("account cache lock poisoned")
+            .remove(&(*address, commitment.commitment));
+    }
+}
+
+async fn fetch_account(
+    client: Arc<RpcClient>,
+    address: Pubkey,
+    commitment: CommitmentConfig,
+) -> Result<Option<Account>, String> {
+    (|| async {
+        client
+            .get_account_with_commitment(&address, commitment)
+            .await
+    })
+    .retry(&ExponentialBuilder::default().with_jitter())
+    .notify(|err: &SolanaClientError, dur: Duration| {
+        info!("retrying error: {:?} with sleeping {:?}", err, dur)
+    })
+    .await
+    .map(|response| response.value)
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pooled_client_is_reused() {
+        let a = pooled_client("http://localhost:8899", CommitmentConfig::confirmed());
+        let b = pooled_client("http://localhost:8899", CommitmentConfig::confirmed());
+        let c = pooled_client("http://localhost:8899", CommitmentConfig::finalized());
+
+        assert!(Arc::ptr_eq(&a, &b));
+        assert!(!Arc::ptr_eq(&a, &c));
+    }
+
+    #[test]
+    fn test_pooled_account_cache_is_reused() {
+        let a = pooled_account_cache("http://localhost:8899", Duration::from_secs(5));
+        let b = pooled_account_cache("http://localhost:8899", Duration::from_secs(10));
+
+        assert!(Arc::ptr_eq(&a, &b));
+        assert_eq!(b.ttl, Duration::from_secs(5));
+    }
+}
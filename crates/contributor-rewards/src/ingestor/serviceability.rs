@@ -1,4 +1,13 @@
-use crate::{ingestor::types::DZServiceabilityData, settings::Settings};
+use crate::{
+    codes,
+    ingestor::{
+        checkpoint::{self, FetchCheckpoint},
+        low_rpc,
+        types::DZServiceabilityData,
+        validation,
+    },
+    settings::Settings,
+};
 use anyhow::{Context, Result};
 use backon::{ExponentialBuilder, Retryable};
 use doublezero_serviceability::state::{
@@ -32,87 +41,115 @@ const PROCESSED_ACCOUNT_TYPES: &[AccountType] = &[
     AccountType::AccessPass,
 ];
 
-pub async fn fetch(rpc_client: &RpcClient, settings: &Settings) -> Result<DZServiceabilityData> {
+/// Fetch the current serviceability state, one account type at a time
+///
+/// With a checkpoint, account types fetched by an earlier attempt are reused.
+pub async fn fetch(
+    rpc_client: &RpcClient,
+    settings: &Settings,
+    checkpoint: Option<&FetchCheckpoint>,
+) -> Result<DZServiceabilityData> {
     // NOTE: This fetches current serviceability state only
     // Historical state is not available as serviceability accounts
     // don't have timestamp/epoch fields and updates overwrite data.
     // This creates a temporal mismatch with historical telemetry data.
-    let mut serviceability_data = DZServiceabilityData::default();
-    let mut total_processed = 0;
+    let mut accounts = Vec::new();
     let mut total_errors = 0;
 
     // Fetch each account type separately with RPC filtering
     for account_type in PROCESSED_ACCOUNT_TYPES {
-        match fetch_by_type(rpc_client, settings, *account_type).await {
+        let section = format!("serviceability:{account_type}");
+        let result = checkpoint::resume(checkpoint, &section, || {
+            fetch_by_type(rpc_client, settings, *account_type)
+        })
+        .await;
+        match result {
             Err(e) => {
                 warn!("Failed to fetch {} accounts: {}", account_type, e);
                 total_errors += 1;
             }
-            Ok(accounts) => {
-                debug!("Processing {} {} accounts", accounts.len(), account_type);
-
-                for (pubkey, account_data) in accounts {
-                    if account_data.is_empty() {
-                        continue;
-                    }
-
-                    match account_type {
-                        AccountType::Location => {
-                            let location = Location::try_from(&account_data[..])?;
-                            serviceability_data.locations.insert(pubkey, location);
-                            total_processed += 1;
-                        }
-                        AccountType::Exchange => {
-                            let exchange = Exchange::try_from(&account_data[..])?;
-                            serviceability_data.exchanges.insert(pubkey, exchange);
-                            total_processed += 1;
-                        }
-                        AccountType::Device => {
-                            let device = Device::try_from(&account_data[..])?;
-                            serviceability_data.devices.insert(pubkey, device);
-                            total_processed += 1;
-                        }
-                        AccountType::Link => {
-                            let link = Link::try_from(&account_data[..])?;
-                            serviceability_data.links.insert(pubkey, link);
-                            total_processed += 1;
-                        }
-                        AccountType::User => {
-                            let user = User::try_from(&account_data[..])?;
-                            serviceability_data.users.insert(pubkey, user);
-                            total_processed += 1;
-                        }
-                        AccountType::MulticastGroup => {
-                            let group = MulticastGroup::try_from(&account_data[..])?;
-                            serviceability_data.multicast_groups.insert(pubkey, group);
-                            total_processed += 1;
-                        }
-                        AccountType::Contributor => {
-                            let contributor = Contributor::try_from(&account_data[..])?;
-                            serviceability_data.contributors.insert(pubkey, contributor);
-                            total_processed += 1;
-                        }
-                        AccountType::AccessPass => {
-                            let access_pass = AccessPass::try_from(&account_data[..])?;
-                            serviceability_data
-                                .access_passes
-                                .insert(pubkey, access_pass);
-                            total_processed += 1;
-                        }
-                        _ => {
-                            warn!(
-                                "Unexpected account type {:?} in processed list",
-                                account_type
-                            );
-                        }
-                    }
+            Ok(type_accounts) => accounts.extend(type_accounts),
+        }
+    }
+
+    let serviceability_data = from_accounts(accounts)?;
+    if total_errors > 0 {
+        warn!("Failed to fetch {total_errors} serviceability account types");
+    }
+
+    Ok(serviceability_data)
+}
+
+/// Decode raw serviceability accounts, dispatching on the account type discriminator
+pub fn from_accounts(accounts: Vec<(Pubkey, Vec<u8>)>) -> Result<DZServiceabilityData> {
+    let mut serviceability_data = DZServiceabilityData::default();
+    let mut total_processed = 0;
+
+    for account_type in PROCESSED_ACCOUNT_TYPES {
+        let type_accounts = accounts
+            .iter()
+            .filter(|(_, data)| data.first() == Some(&(*account_type as u8)));
+
+        for (pubkey, account_data) in type_accounts {
+            let pubkey = *pubkey;
+
+            match account_type {
+                AccountType::Location => {
+                    let location = Location::try_from(&account_data[..])?;
+                    serviceability_data.locations.insert(pubkey, location);
+                    total_processed += 1;
+                }
+                AccountType::Exchange => {
+                    let exchange = Exchange::try_from(&account_data[..])?;
+                    serviceability_data.exchanges.insert(pubkey, exchange);
+                    total_processed += 1;
+                }
+                AccountType::Device => {
+                    let device = Device::try_from(&account_data[..])?;
+                    serviceability_data.devices.insert(pubkey, device);
+                    total_processed += 1;
+                }
+                AccountType::Link => {
+                    let link = Link::try_from(&account_data[..])?;
+                    serviceability_data.links.insert(pubkey, link);
+                    total_processed += 1;
+                }
+                AccountType::User => {
+                    let user = User::try_from(&account_data[..])?;
+                    serviceability_data.users.insert(pubkey, user);
+                    total_processed += 1;
+                }
+                AccountType::MulticastGroup => {
+                    let group = MulticastGroup::try_from(&account_data[..])?;
+                    serviceability_data.multicast_groups.insert(pubkey, group);
+                    total_processed += 1;
+                }
+                AccountType::Contributor => {
+                    let contributor = Contributor::try_from(&account_data[..])?;
+                    serviceability_data.contributors.insert(pubkey, contributor);
+                    total_processed += 1;
+                }
+                AccountType::AccessPass => {
+                    let access_pass = AccessPass::try_from(&account_data[..])?;
+                    serviceability_data
+                        .access_passes
+                        .insert(pubkey, access_pass);
+                    total_processed += 1;
+                }
+                _ => {
+                    warn!(
+                        "Unexpected account type {:?} in processed list",
+                        account_type
+                    );
                 }
             }
         }
     }
 
+    codes::retain_unambiguous_exchanges(&mut serviceability_data.exchanges);
+
     info!(
-        "Processed {} serviceability accounts, contributors={}, locations={}, exchanges={}, devices={}, links={}, users={}, mcast_groups={}, access_passes={}. Errors={}",
+        "Processed {} serviceability accounts, contributors={}, locations={}, exchanges={}, devices={}, links={}, users={}, mcast_groups={}, access_passes={}",
         total_processed,
         serviceability_data.contributors.len(),
         serviceability_data.locations.len(),
@@ -122,9 +159,9 @@ pub async fn fetch(rpc_client: &RpcClient, settings: &Settings) -> Result<DZServ
         serviceability_data.users.len(),
         serviceability_data.multicast_groups.len(),
         serviceability_data.access_passes.len(),
-        total_errors,
     );
 
+    serviceability_data.accounts = accounts;
     Ok(serviceability_data)
 }
 
@@ -154,22 +191,39 @@ async fn fetch_by_type(
     };
 
     let start = Instant::now();
-    let accounts = (|| async {
-        rpc_client
-            .get_program_accounts_with_config(&program_pubkey, config.clone())
-            .await
-    })
-    .retry(&ExponentialBuilder::default().with_jitter())
-    .notify(|err: &SolanaClientError, dur: Duration| {
-        info!("retrying error: {:?} with sleeping {:?}", err, dur)
-    })
-    .await?;
+    let accounts = if settings.rpc.low_rpc {
+        low_rpc::get_program_accounts(
+            rpc_client,
+            settings.rpc.low_rpc_rps_limit,
+            &program_pubkey,
+            &config,
+        )
+        .await?
+    } else {
+        (|| async {
+            rpc_client
+                .get_program_accounts_with_config(&program_pubkey, config.clone())
+                .await
+        })
+        .retry(&ExponentialBuilder::default().with_jitter())
+        .notify(|err: &SolanaClientError, dur: Duration| {
+            info!("retrying error: {:?} with sleeping {:?}", err, dur)
+        })
+        .await?
+    };
     debug!(
         "Fetching serviceability account took: {:?}",
         start.elapsed()
     );
 
     debug!("Found {} {} accounts", accounts.len(), account_type);
+    let accounts = validation::retain_valid(
+        accounts,
+        &program_pubkey,
+        account_type as u8,
+        "serviceability",
+    );
+
     // Convert from Vec<(Pubkey, Account)> to Vec<(Pubkey, Vec<u8>)>
     let accounts_with_data: Vec<(Pubkey, Vec<u8>)> = accounts
         .into_iter()
@@ -1,5 +1,10 @@
 use crate::{
-    ingestor::types::{DZDTelemetryData, DZDeviceLatencySamples},
+    ingestor::{
+        checkpoint::{self, FetchCheckpoint},
+        low_rpc,
+        types::{DZDTelemetryData, DZDeviceLatencySamples, KeyedAccounts},
+        validation,
+    },
     settings::Settings,
 };
 use anyhow::{Context, Result};
@@ -14,7 +19,7 @@ use solana_client::{
     rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
     rpc_filter::{Memcmp, RpcFilterType},
 };
-use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use solana_sdk::{account::Account, commitment_config::CommitmentConfig, pubkey::Pubkey};
 use std::{
     str::FromStr,
     time::{Duration, Instant},
@@ -26,10 +31,13 @@ use tracing::{debug, info, warn};
 const ACCOUNT_TYPE_DISCRIMINATOR: u8 = AccountType::DeviceLatencySamples as u8;
 
 /// Fetch telemetry data for a specific epoch using RPC filtering
+///
+/// With a checkpoint, accounts fetched by an earlier attempt for the same epoch are reused.
 pub async fn fetch(
     dz_rpc_client: &RpcClient,
     settings: &Settings,
     epoch: u64,
+    checkpoint: Option<&FetchCheckpoint>,
 ) -> Result<DZDTelemetryData> {
     let program_id = &settings.programs.telemetry_program_id;
     let program_pubkey = Pubkey::from_str(program_id)
@@ -55,25 +63,66 @@ pub async fn fetch(
         ..RpcProgramAccountsConfig::default()
     };
 
-    let start = Instant::now();
-    let accounts = (|| async {
-        dz_rpc_client
-            .get_program_accounts_with_config(&program_pubkey, config.clone())
-            .await
-    })
-    .retry(&ExponentialBuilder::default().with_jitter())
-    .notify(|err: &SolanaClientError, dur: Duration| {
-        info!("retrying error: {:?} with sleeping {:?}", err, dur)
+    let accounts = checkpoint::resume(checkpoint, "device_telemetry", || async {
+        let start = Instant::now();
+        let accounts = if settings.rpc.low_rpc {
+            low_rpc::get_program_accounts(
+                dz_rpc_client,
+                settings.rpc.low_rpc_rps_limit,
+                &program_pubkey,
+                &config,
+            )
+            .await?
+        } else {
+            (|| async {
+                dz_rpc_client
+                    .get_program_accounts_with_config(&program_pubkey, config.clone())
+                    .await
+            })
+            .retry(&ExponentialBuilder::default().with_jitter())
+            .notify(|err: &SolanaClientError, dur: Duration| {
+                info!("retrying error: {:?} with sleeping {:?}", err, dur)
+            })
+            .await?
+        };
+        debug!("Fetching telemetry account took: {:?}", start.elapsed());
+
+        info!(
+            "Found {} telemetry accounts for epoch {}",
+            accounts.len(),
+            epoch
+        );
+
+        Ok(validation::retain_valid(
+            accounts,
+            &program_pubkey,
+            ACCOUNT_TYPE_DISCRIMINATOR,
+            "device_telemetry",
+        )
+        .into_iter()
+        .map(|(pubkey, account)| (pubkey, account.data))
+        .collect())
     })
     .await?;
-    debug!("Fetching telemetry account took: {:?}", start.elapsed());
 
-    info!(
-        "Found {} telemetry accounts for epoch {}",
-        accounts.len(),
-        epoch
-    );
+    let accounts = accounts
+        .into_iter()
+        .map(|(pubkey, data)| {
+            (
+                pubkey,
+                Account {
+                    data,
+                    ..Account::default()
+                },
+            )
+        })
+        .collect();
+
+    Ok(from_accounts(accounts, epoch))
+}
 
+/// Decode raw device latency sample accounts for a specific epoch
+pub fn from_accounts(accounts: KeyedAccounts, epoch: u64) -> DZDTelemetryData {
     let mut device_latency_samples = Vec::new();
     let batch_size = 100;
     let mut error_count = 0;
@@ -94,6 +143,7 @@ pub async fn fetch(
                             "Unexpected epoch mismatch: expected {}, got {}",
                             epoch, samples.header.epoch
                         );
+                        validation::record_skipped("device_telemetry", "epoch_mismatch");
                         continue;
                     }
 
@@ -109,6 +159,7 @@ pub async fn fetch(
                 }
                 Err(e) => {
                     warn!("Failed to deserialize telemetry account {}: {}", pubkey, e);
+                    validation::record_skipped("device_telemetry", "corrupt");
                     error_count += 1;
                 }
             }
@@ -123,7 +174,7 @@ pub async fn fetch(
     );
 
     if device_latency_samples.is_empty() {
-        return Ok(DZDTelemetryData::default());
+        return DZDTelemetryData::default();
     }
 
     let total_samples: usize = device_latency_samples.iter().map(|d| d.samples.len()).sum();
@@ -133,10 +184,10 @@ pub async fn fetch(
         "DZD Telemetry stats for epoch {epoch}, total_samples={total_samples}, avg_samples_per_account={avg_samples_per_account}",
     );
 
-    Ok(DZDTelemetryData {
+    DZDTelemetryData {
         device_latency_samples,
         accounts,
-    })
+    }
 }
 
 #[cfg(test)]
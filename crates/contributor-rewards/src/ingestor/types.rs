@@ -121,6 +121,9 @@ pub struct DZServiceabilityData {
         deserialize_with = "serializer::deserialize_pubkey_btreemap"
     )]
     pub access_passes: BTreeMap<Pubkey, DZAccessPass>,
+    /// Raw account data the maps above were decoded from
+    #[serde(skip)]
+    pub accounts: Vec<(Pubkey, Vec<u8>)>,
 }
 
 /// DB representation of DeviceLatencySamples
@@ -0,0 +1,555 @@
+use crate::ingestor::types::{DZInternetData, FetchData, KeyedAccounts};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Display, Formatter},
+};
+use tabled::Tabled;
+use thiserror::Error;
+use tracing::warn;
+
+/// Reasons a fetched account is rejected before decoding
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum AccountValidationError {
+    #[error("Account {pubkey} is owned by {owner}, expected {expected}")]
+    ForeignOwner {
+        pubkey: Pubkey,
+        owner: Pubkey,
+        expected: Pubkey,
+    },
+
+    #[error("Account {pubkey} has no data")]
+    Empty { pubkey: Pubkey },
+
+    #[error("Account {pubkey} has discriminator {found}, expected {expected}")]
+    UnexpectedDiscriminator {
+        pubkey: Pubkey,
+        found: u8,
+        expected: u8,
+    },
+}
+
+impl AccountValidationError {
+    /// Short label used for metrics
+    pub fn reason(&self) -> &'static str {
+        match self {
+            Self::ForeignOwner { .. } => "foreign_owner",
+            Self::Empty { .. } => "empty",
+            Self::UnexpectedDiscriminator { .. } => "unexpected_discriminator",
+        }
+    }
+}
+
+/// Check that an account returned by the RPC belongs to the program and has the
+/// expected account type discriminator
+///
+/// The RPC filters should already guarantee both; this guards against providers
+/// returning mixed results.
+pub fn validate_account(
+    pubkey: &Pubkey,
+    account: &Account,
+    program_id: &Pubkey,
+    discriminator: u8,
+) -> Result<(), AccountValidationError> {
+    if account.owner != *program_id {
+        return Err(AccountValidationError::ForeignOwner {
+            pubkey: *pubkey,
+            owner: account.owner,
+            expected: *program_id,
+        });
+    }
+
+    match account.data.first() {
+        None => Err(AccountValidationError::Empty { pubkey: *pubkey }),
+        Some(&found) if found != discriminator => {
+            Err(AccountValidationError::UnexpectedDiscriminator {
+                pubkey: *pubkey,
+                found,
+                expected: discriminator,
+            })
+        }
+        Some(_) => Ok(()),
+    }
+}
+
+/// Drop accounts that fail validation, logging and counting each one
+pub fn retain_valid(
+    accounts: KeyedAccounts,
+    program_id: &Pubkey,
+    discriminator: u8,
+    kind: &'static str,
+) -> KeyedAccounts {
+    let total = accounts.len();
+    let valid: KeyedAccounts = accounts
+        .into_iter()
+        .filter(|(pubkey, account)| {
+            match validate_account(pubkey, account, program_id, discriminator) {
+                Ok(()) => true,
+                Err(err) => {
+                    warn!("Skipping {kind} account: {err}");
+                    record_skipped(kind, err.reason());
+                    false
+                }
+            }
+        })
+        .collect();
+
+    if valid.len() < total {
+        warn!(
+            "Skipped {} of {total} {kind} accounts that failed validation",
+            total - valid.len()
+        );
+    }
+
+    valid
+}
+
+/// Count an account skipped during ingestion
+pub fn record_skipped(kind: &'static str, reason: &'static str) {
+    metrics::counter!(
+        "doublezero_contributor_rewards_accounts_skipped",
+        "kind" => kind,
+        "reason" => reason
+    )
+    .increment(1);
+}
+
+/// Internet latency samples written by one collector agent
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Tabled)]
+pub struct AgentContribution {
+    pub agent: String,
+    /// Listed in the agent allowlist (always true without an allowlist)
+    pub approved: bool,
+    /// Sample accounts written by the agent
+    pub accounts: usize,
+    pub samples: usize,
+}
+
+/// Check who wrote each internet sample account against the approved agents
+///
+/// An empty allowlist approves every agent. Accounts from unknown agents are dropped, or kept
+/// with a warning when `flag_only` is set. Returns each agent's contribution, dropped or not.
+pub fn validate_internet_agents(
+    data: &mut DZInternetData,
+    allowlist: &[Pubkey],
+    flag_only: bool,
+) -> Vec<AgentContribution> {
+    let is_approved = |agent: &Pubkey| allowlist.is_empty() || allowlist.contains(agent);
+
+    let mut contributions: BTreeMap<Pubkey, AgentContribution> = BTreeMap::new();
+    for samples in &data.internet_latency_samples {
+        let contribution = contributions
+            .entry(samples.oracle_agent_pk)
+            .or_insert_with(|| AgentContribution {
+                agent: samples.oracle_agent_pk.to_string(),
+                approved: is_approved(&samples.oracle_agent_pk),
+                accounts: 0,
+                samples: 0,
+            });
+        contribution.accounts += 1;
+        contribution.samples += samples.samples.len();
+    }
+
+    for contribution in contributions.values().filter(|c| !c.approved) {
+        warn!(
+            "{} internet sample accounts ({} samples) written by unapproved agent {}{}",
+            contribution.accounts,
+            contribution.samples,
+            contribution.agent,
+            if flag_only { "" } else { "; dropping them" }
+        );
+    }
+
+    if !flag_only {
+        data.internet_latency_samples.retain(|samples| {
+            let approved = is_approved(&samples.oracle_agent_pk);
+            if !approved {
+                record_skipped("internet_telemetry", "unapproved_agent");
+            }
+            approved
+        });
+    }
+
+    contributions.into_values().collect()
+}
+
+/// Inconsistency in fetched epoch data that does not stop processing but may skew its results
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DataIssue {
+    /// Device that is not a side of any link
+    DeviceWithoutLinks { device: String },
+    /// Link with a side that is not a known device
+    LinkWithUnknownDevice { link: String, device: String },
+    /// Telemetry account referencing a device, link or exchange that was not fetched
+    TelemetryWithUnknownEntity {
+        account: String,
+        entity_kind: String,
+        entity: String,
+    },
+    /// Telemetry account whose samples fall partly outside the epoch's time window
+    SamplesOutsideWindow {
+        account: String,
+        start_us: u64,
+        end_us: u64,
+    },
+    /// Category with nothing fetched
+    EmptyCategory { category: String },
+}
+
+impl DataIssue {
+    /// Short label used for metrics
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::DeviceWithoutLinks { .. } => "device_without_links",
+            Self::LinkWithUnknownDevice { .. } => "link_with_unknown_device",
+            Self::TelemetryWithUnknownEntity { .. } => "telemetry_with_unknown_entity",
+            Self::SamplesOutsideWindow { .. } => "samples_outside_window",
+            Self::EmptyCategory { .. } => "empty_category",
+        }
+    }
+}
+
+impl Display for DataIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DeviceWithoutLinks { device } => write!(f, "Device {device} has no links"),
+            Self::LinkWithUnknownDevice { link, device } => {
+                write!(f, "Link {link} references unknown device {device}")
+            }
+            Self::TelemetryWithUnknownEntity {
+                account,
+                entity_kind,
+                entity,
+            } => write!(
+                f,
+                "Telemetry account {account} references unknown {entity_kind} {entity}"
+            ),
+            Self::SamplesOutsideWindow {
+                account,
+                start_us,
+                end_us,
+            } => write!(
+                f,
+                "Telemetry account {account} has samples from {start_us} to {end_us}, outside the epoch window"
+            ),
+            Self::EmptyCategory { category } => write!(f, "No {category} were fetched"),
+        }
+    }
+}
+
+/// Structured result of checking fetched epoch data for internal consistency
+///
+/// Nothing is dropped; callers decide whether the issues warrant halting or are carried as
+/// caveats of the run.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataValidationReport {
+    pub issues: Vec<DataIssue>,
+}
+
+impl DataValidationReport {
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Number of issues of each kind
+    pub fn counts(&self) -> BTreeMap<&'static str, usize> {
+        let mut counts = BTreeMap::new();
+        for issue in &self.issues {
+            *counts.entry(issue.kind()).or_default() += 1;
+        }
+        counts
+    }
+
+    /// Log every issue and count each kind
+    pub fn log(&self) {
+        for issue in &self.issues {
+            warn!("Data validation: {issue}");
+        }
+        for (kind, count) in self.counts() {
+            metrics::counter!(
+                "doublezero_contributor_rewards_data_issues",
+                "kind" => kind
+            )
+            .increment(count as u64);
+        }
+        if !self.is_clean() {
+            warn!(
+                "Fetched data has {} validation issues: {:?}",
+                self.issues.len(),
+                self.counts()
+            );
+        }
+    }
+}
+
+/// Check fetched epoch data for dangling references, samples outside the epoch's window and
+/// empty categories
+pub fn validate_fetch_data(data: &FetchData) -> DataValidationReport {
+    let serviceability = &data.dz_serviceability;
+    let mut issues = Vec::new();
+
+    for (category, empty) in [
+        ("locations", serviceability.locations.is_empty()),
+        ("exchanges", serviceability.exchanges.is_empty()),
+        ("devices", serviceability.devices.is_empty()),
+        ("links", serviceability.links.is_empty()),
+        (
+            "device telemetry samples",
+            data.dz_telemetry.device_latency_samples.is_empty(),
+        ),
+        (
+            "internet telemetry samples",
+            data.dz_internet.internet_latency_samples.is_empty(),
+        ),
+    ] {
+        if empty {
+            issues.push(DataIssue::EmptyCategory {
+                category: category.to_string(),
+            });
+        }
+    }
+
+    let mut linked_devices = BTreeSet::new();
+    for link in serviceability.links.values() {
+        for device_pk in [link.side_a_pk, link.side_z_pk] {
+            if serviceability.devices.contains_key(&device_pk) {
+                linked_devices.insert(device_pk);
+            } else {
+                issues.push(DataIssue::LinkWithUnknownDevice {
+                    link: link.code.clone(),
+                    device: device_pk.to_string(),
+                });
+            }
+        }
+    }
+    for (device_pk, device) in &serviceability.devices {
+        if !linked_devices.contains(device_pk) {
+            issues.push(DataIssue::DeviceWithoutLinks {
+                device: device.code.clone(),
+            });
+        }
+    }
+
+    let mut check_window = |account: &Pubkey, start_us: u64, interval_us: u64, count: u32| {
+        let end_us = start_us + count as u64 * interval_us;
+        if start_us < data.start_us || end_us > data.end_us {
+            issues.push(DataIssue::SamplesOutsideWindow {
+                account: account.to_string(),
+                start_us,
+                end_us,
+            });
+        }
+    };
+    for samples in &data.dz_telemetry.device_latency_samples {
+        check_window(
+            &samples.pubkey,
+            samples.start_timestamp_us,
+            samples.sampling_interval_us,
+            samples.sample_count,
+        );
+    }
+    for samples in &data.dz_internet.internet_latency_samples {
+        check_window(
+            &samples.pubkey,
+            samples.start_timestamp_us,
+            samples.sampling_interval_us,
+            samples.sample_count,
+        );
+    }
+
+    let mut unknown_entity = |account: &Pubkey, entity_kind: &str, entity: &Pubkey| {
+        issues.push(DataIssue::TelemetryWithUnknownEntity {
+            account: account.to_string(),
+            entity_kind: entity_kind.to_string(),
+            entity: entity.to_string(),
+        });
+    };
+    for samples in &data.dz_telemetry.device_latency_samples {
+        for device_pk in [&samples.origin_device_pk, &samples.target_device_pk] {
+            if !serviceability.devices.contains_key(device_pk) {
+                unknown_entity(&samples.pubkey, "device", device_pk);
+            }
+        }
+        if !serviceability.links.contains_key(&samples.link_pk) {
+            unknown_entity(&samples.pubkey, "link", &samples.link_pk);
+        }
+    }
+    for samples in &data.dz_internet.internet_latency_samples {
+        for exchange_pk in [&samples.origin_exchange_pk, &samples.target_exchange_pk] {
+            if !serviceability.exchanges.contains_key(exchange_pk) {
+                unknown_entity(&samples.pubkey, "exchange", exchange_pk);
+            }
+        }
+    }
+
+    DataValidationReport { issues }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingestor::types::{DZDeviceLatencySamples, DZInternetLatencySamples};
+
+    fn account(owner: Pubkey, data: Vec<u8>) -> Account {
+        Account {
+            lamports: 1,
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    #[test]
+    fn test_validate_account() {
+        let program_id = Pubkey::new_unique();
+        let pubkey = Pubkey::new_unique();
+
+        assert!(
+            validate_account(&pubkey, &account(program_id, vec![3, 1]), &program_id, 3).is_ok()
+        );
+
+        let foreign = validate_account(
+            &pubkey,
+            &account(Pubkey::new_unique(), vec![3]),
+            &program_id,
+            3,
+        )
+        .unwrap_err();
+        assert_eq!(foreign.reason(), "foreign_owner");
+
+        let empty =
+            validate_account(&pubkey, &account(program_id, vec![]), &program_id, 3).unwrap_err();
+        assert_eq!(empty, AccountValidationError::Empty { pubkey });
+
+        let wrong_type =
+            validate_account(&pubkey, &account(program_id, vec![4]), &program_id, 3).unwrap_err();
+        assert_eq!(wrong_type.reason(), "unexpected_discriminator");
+    }
+
+    #[test]
+    fn test_retain_valid() {
+        let program_id = Pubkey::new_unique();
+        let good = Pubkey::new_unique();
+        let accounts = vec![
+            (good, account(program_id, vec![3])),
+            (Pubkey::new_unique(), account(Pubkey::new_unique(), vec![3])),
+            (Pubkey::new_unique(), account(program_id, vec![1])),
+        ];
+
+        let valid = retain_valid(accounts, &program_id, 3, "device_telemetry");
+        assert_eq!(valid.len(), 1);
+        assert_eq!(valid[0].0, good);
+    }
+
+    fn internet_data(agents: &[Pubkey]) -> DZInternetData {
+        DZInternetData {
+            internet_latency_samples: agents
+                .iter()
+                .map(|agent| DZInternetLatencySamples {
+                    pubkey: Pubkey::new_unique(),
+                    epoch: 1,
+                    data_provider_name: "ripeatlas".to_string(),
+                    oracle_agent_pk: *agent,
+                    origin_exchange_pk: Pubkey::new_unique(),
+                    target_exchange_pk: Pubkey::new_unique(),
+                    sampling_interval_us: 1_000_000,
+                    start_timestamp_us: 0,
+                    samples: vec![10_000, 11_000],
+                    sample_count: 2,
+                })
+                .collect(),
+            accounts: vec![],
+        }
+    }
+
+    #[test]
+    fn test_unapproved_agents_dropped() {
+        let approved = Pubkey::new_unique();
+        let unknown = Pubkey::new_unique();
+        let mut data = internet_data(&[approved, approved, unknown]);
+
+        let contributions = validate_internet_agents(&mut data, &[approved], false);
+        assert_eq!(data.internet_latency_samples.len(), 2);
+
+        let unknown_contribution = contributions
+            .iter()
+            .find(|c| c.agent == unknown.to_string())
+            .unwrap();
+        assert!(!unknown_contribution.approved);
+        assert_eq!(unknown_contribution.accounts, 1);
+
+        let approved_contribution = contributions
+            .iter()
+            .find(|c| c.agent == approved.to_string())
+            .unwrap();
+        assert_eq!(approved_contribution.samples, 4);
+    }
+
+    #[test]
+    fn test_unapproved_agents_flagged() {
+        let mut data = internet_data(&[Pubkey::new_unique()]);
+        let contributions = validate_internet_agents(&mut data, &[Pubkey::new_unique()], true);
+        assert_eq!(data.internet_latency_samples.len(), 1);
+        assert!(!contributions[0].approved);
+    }
+
+    #[test]
+    fn test_empty_allowlist_approves_all() {
+        let mut data = internet_data(&[Pubkey::new_unique(), Pubkey::new_unique()]);
+        let contributions = validate_internet_agents(&mut data, &[], false);
+        assert_eq!(data.internet_latency_samples.len(), 2);
+        assert!(contributions.iter().all(|c| c.approved));
+    }
+
+    #[test]
+    fn test_validate_fetch_data() {
+        let link_pk = Pubkey::new_unique();
+        let mut data = FetchData {
+            dz_internet: internet_data(&[Pubkey::new_unique()]),
+            start_us: 0,
+            end_us: 10_000_000,
+            ..Default::default()
+        };
+        data.dz_telemetry
+            .device_latency_samples
+            .push(DZDeviceLatencySamples {
+                pubkey: Pubkey::new_unique(),
+                epoch: 1,
+                origin_device_pk: Pubkey::new_unique(),
+                target_device_pk: Pubkey::new_unique(),
+                link_pk,
+                origin_device_location_pk: Pubkey::new_unique(),
+                target_device_location_pk: Pubkey::new_unique(),
+                origin_device_agent_pk: Pubkey::new_unique(),
+                sampling_interval_us: 1_000_000,
+                start_timestamp_us: 9_000_000,
+                samples: vec![500, 510],
+                sample_count: 2,
+            });
+
+        let report = validate_fetch_data(&data);
+        assert!(!report.is_clean());
+
+        let counts = report.counts();
+        // Locations, exchanges, devices and links
+        assert_eq!(counts["empty_category"], 4);
+        // Two devices and the link of the device series, two exchanges of the internet series
+        assert_eq!(counts["telemetry_with_unknown_entity"], 5);
+        // The device series ends at 11s, past the 10s window end
+        assert_eq!(counts["samples_outside_window"], 1);
+        assert!(report.issues.iter().any(|issue| matches!(
+            issue,
+            DataIssue::TelemetryWithUnknownEntity { entity_kind, entity, .. }
+                if entity_kind == "link" && *entity == link_pk.to_string()
+        )));
+
+        let json = serde_json::to_string(&report).unwrap();
+        assert_eq!(
+            serde_json::from_str::<DataValidationReport>(&json).unwrap(),
+            report
+        );
+    }
+}
@@ -0,0 +1,247 @@
+//! Sanity checks on the telemetry time window of an epoch
+//!
+//! The window an epoch is processed over is derived from the telemetry samples themselves, so a
+//! skewed agent clock or a corrupt account can silently stretch, shift or empty it. These checks
+//! reject such windows before any processing, against the configured bounds, the local clock and
+//! the block times of the epoch on the DZ ledger.
+
+use crate::settings::TimeWindowSettings;
+use anyhow::{Result, bail};
+use chrono::{DateTime, Utc};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use tracing::{debug, warn};
+
+const US_PER_SEC: u64 = 1_000_000;
+
+/// Block times bounding an epoch on the ledger
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EpochBlockTimes {
+    pub start_us: u64,
+    /// Start of the following epoch, `None` while the epoch is still in progress
+    pub end_us: Option<u64>,
+}
+
+/// Check ordering, length and that the window does not end in the future
+pub fn validate_window(
+    epoch: u64,
+    start_us: u64,
+    end_us: u64,
+    now_us: u64,
+    settings: &TimeWindowSettings,
+) -> Result<()> {
+    if start_us >= end_us {
+        bail!(
+            "Telemetry window for epoch {epoch} is empty or inverted: starts at {} but ends at {}; \
+             check the start timestamps and sample counts of its telemetry accounts",
+            format_us(start_us),
+            format_us(end_us)
+        );
+    }
+
+    let length_secs = (end_us - start_us) / US_PER_SEC;
+    if length_secs > settings.max_window_secs {
+        bail!(
+            "Telemetry window for epoch {epoch} spans {length_secs}s ({} to {}), more than \
+             time_window.max_window_secs ({}s); a telemetry account likely has a bad start \
+             timestamp",
+            format_us(start_us),
+            format_us(end_us),
+            settings.max_window_secs
+        );
+    }
+
+    let skew_us = settings.max_clock_skew_secs * US_PER_SEC;
+    if end_us > now_us.saturating_add(skew_us) {
+        bail!(
+            "Telemetry window for epoch {epoch} ends at {}, {}s ahead of the local clock ({}), \
+             beyond time_window.max_clock_skew_secs ({}s); check the clocks of this host and of \
+             the telemetry agents",
+            format_us(end_us),
+            (end_us - now_us) / US_PER_SEC,
+            format_us(now_us),
+            settings.max_clock_skew_secs
+        );
+    }
+
+    Ok(())
+}
+
+/// Check that the window lies within the epoch's block times, give or take the clock skew
+pub fn validate_against_block_times(
+    epoch: u64,
+    start_us: u64,
+    end_us: u64,
+    block_times: &EpochBlockTimes,
+    settings: &TimeWindowSettings,
+) -> Result<()> {
+    let skew_us = settings.max_clock_skew_secs * US_PER_SEC;
+
+    if start_us.saturating_add(skew_us) < block_times.start_us {
+        bail!(
+            "Telemetry window for epoch {epoch} starts at {}, {}s before the epoch's first block \
+             ({}); the samples likely belong to an earlier epoch",
+            format_us(start_us),
+            (block_times.start_us - start_us) / US_PER_SEC,
+            format_us(block_times.start_us)
+        );
+    }
+
+    if let Some(epoch_end_us) = block_times.end_us
+        && end_us > epoch_end_us.saturating_add(skew_us)
+    {
+        bail!(
+            "Telemetry window for epoch {epoch} ends at {}, {}s after the epoch's last block \
+             ({}); the samples likely run into a later epoch",
+            format_us(end_us),
+            (end_us - epoch_end_us) / US_PER_SEC,
+            format_us(epoch_end_us)
+        );
+    }
+
+    Ok(())
+}
+
+/// Run every check on the window of `epoch`
+///
+/// Block times that cannot be read (e.g. from a ledger that pruned them) only skip the block time
+/// comparison.
+pub async fn check_window(
+    dz_rpc_client: &RpcClient,
+    settings: &TimeWindowSettings,
+    epoch: u64,
+    start_us: u64,
+    end_us: u64,
+) -> Result<()> {
+    let now_us = Utc::now().timestamp_micros() as u64;
+    validate_window(epoch, start_us, end_us, now_us, settings)?;
+
+    match fetch_epoch_block_times(dz_rpc_client, epoch).await {
+        Ok(block_times) => {
+            debug!("Epoch {epoch} block times: {block_times:?}");
+            validate_against_block_times(epoch, start_us, end_us, &block_times, settings)
+        }
+        Err(e) => {
+            warn!("Skipping block time check of the epoch {epoch} window: {e:#}");
+            Ok(())
+        }
+    }
+}
+
+/// Block times of the first block of `epoch` and of the one following it
+pub async fn fetch_epoch_block_times(client: &RpcClient, epoch: u64) -> Result<EpochBlockTimes> {
+    let schedule = client.get_epoch_schedule().await?;
+    let current_slot = client.get_slot().await?;
+
+    let start_us = first_block_time_us(client, schedule.get_first_slot_in_epoch(epoch)).await?;
+    let next_slot = schedule.get_first_slot_in_epoch(epoch + 1);
+    let end_us = if next_slot <= current_slot {
+        Some(first_block_time_us(client, next_slot).await?)
+    } else {
+        None
+    };
+
+    Ok(EpochBlockTimes { start_us, end_us })
+}
+
+/// Block time of the first block produced at or after `slot`, since leaders may skip slots
+async fn first_block_time_us(client: &RpcClient, slot: u64) -> Result<u64> {
+    let Some(block_slot) = client
+        .get_blocks_with_limit(slot, 1)
+        .await?
+        .first()
+        .copied()
+    else {
+        bail!("No block found at or after slot {slot}");
+    };
+    let block_time = client.get_block_time(block_slot).await?;
+
+    Ok(block_time as u64 * US_PER_SEC)
+}
+
+fn format_us(timestamp_us: u64) -> String {
+    DateTime::<Utc>::from_timestamp_micros(timestamp_us as i64)
+        .map(|dt| dt.to_rfc3339())
+        .unwrap_or_else(|| format!("{timestamp_us}us"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW_US: u64 = 1_760_000_000 * US_PER_SEC;
+    const HOUR_US: u64 = 3600 * US_PER_SEC;
+
+    #[test]
+    fn test_validate_window() {
+        let settings = TimeWindowSettings::default();
+        let start_us = NOW_US - 48 * HOUR_US;
+
+        assert!(validate_window(1, start_us, NOW_US, NOW_US, &settings).is_ok());
+
+        // Inverted or empty
+        assert!(validate_window(1, NOW_US, start_us, NOW_US, &settings).is_err());
+        assert!(validate_window(1, NOW_US, NOW_US, NOW_US, &settings).is_err());
+
+        // Longer than the maximum window
+        let err = validate_window(1, NOW_US - 96 * HOUR_US, NOW_US, NOW_US, &settings)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("max_window_secs"));
+
+        // In the future, within and beyond the tolerated skew
+        let skew_us = settings.max_clock_skew_secs * US_PER_SEC;
+        assert!(validate_window(1, start_us, NOW_US + skew_us, NOW_US, &settings).is_ok());
+        let err = validate_window(1, start_us, NOW_US + skew_us + 1, NOW_US, &settings)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("max_clock_skew_secs"));
+    }
+
+    #[test]
+    fn test_validate_against_block_times() {
+        let settings = TimeWindowSettings::default();
+        let skew_us = settings.max_clock_skew_secs * US_PER_SEC;
+        let block_times = EpochBlockTimes {
+            start_us: NOW_US - 48 * HOUR_US,
+            end_us: Some(NOW_US),
+        };
+
+        assert!(
+            validate_against_block_times(
+                1,
+                block_times.start_us - skew_us,
+                NOW_US + skew_us,
+                &block_times,
+                &settings
+            )
+            .is_ok()
+        );
+
+        // Starts in an earlier epoch
+        assert!(
+            validate_against_block_times(
+                1,
+                block_times.start_us - skew_us - 1,
+                NOW_US,
+                &block_times,
+                &settings
+            )
+            .is_err()
+        );
+
+        // Runs into a later epoch, unless the epoch is still in progress
+        let end_us = NOW_US + skew_us + 1;
+        assert!(
+            validate_against_block_times(1, block_times.start_us, end_us, &block_times, &settings)
+                .is_err()
+        );
+        let in_progress = EpochBlockTimes {
+            end_us: None,
+            ..block_times
+        };
+        assert!(
+            validate_against_block_times(1, in_progress.start_us, end_us, &in_progress, &settings)
+                .is_ok()
+        );
+    }
+}
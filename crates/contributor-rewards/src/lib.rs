@@ -1,6 +1,10 @@
 pub mod calculator;
 pub mod cli;
+pub mod codes;
 pub mod ingestor;
 pub mod processor;
+pub mod rng;
+pub mod run_id;
 pub mod scheduler;
 pub mod settings;
+pub mod units;
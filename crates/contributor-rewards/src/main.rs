@@ -4,12 +4,18 @@ use anyhow::Result;
 use clap::{Parser, Subcommand};
 use doublezero_contributor_rewards::{
     calculator::orchestrator::Orchestrator,
-    cli::{inspect::InspectCommands, rewards::RewardsCommands},
+    cli::{
+        dev_proxy::DevProxyArgs,
+        inspect::InspectCommands,
+        presenter::{self, LogFormat},
+        rewards::RewardsCommands,
+    },
+    processor::memory,
     settings::Settings,
 };
 use metrics_exporter_prometheus::PrometheusBuilder;
 use std::path::PathBuf;
-use tracing::{debug, warn};
+use tracing::{Instrument, debug, info, info_span, warn};
 use tracing_subscriber::{EnvFilter, layer::SubscriberExt, util::SubscriberInitExt};
 
 #[derive(Parser, Debug)]
@@ -38,7 +44,22 @@ Examples:
     contributor-rewards read-telem-agg --epoch 123
 
     # Check a contributor's reward
-    contributor-rewards check-reward --contributor <PUBKEY> --epoch 123"#
+    contributor-rewards check-reward --contributor <PUBKEY> --epoch 123
+
+    # Pipe a JSON export without logs or summaries on the terminal
+    contributor-rewards --quiet telemetry stats --type internet -f json | jq .
+
+    # Migrate an old config file to the current schema
+    contributor-rewards -c old.config.toml config migrate -o config.toml
+
+    # Share cached chain reads with the team through a local proxy
+    contributor-rewards dev-proxy --listen 0.0.0.0:18899
+
+    # Cut a small, anonymized test fixture from a live epoch
+    contributor-rewards debug export-fixture --epoch 123 --scale 0.05
+
+    # Verify an epoch's inputs against a public rate-limited RPC
+    contributor-rewards --low-rpc calculate-rewards --epoch 123 --dry-run"#
 )]
 pub struct Cli {
     /// Path to the configuration file (TOML format)
@@ -47,6 +68,18 @@ pub struct Cli {
     #[clap(short = 'c', long, value_name = "FILE")]
     pub config: Option<PathBuf>,
 
+    /// Format of log lines, which are always written to stderr
+    #[arg(long, global = true, value_enum, default_value_t = LogFormat::Text)]
+    pub log_format: LogFormat,
+
+    /// Only log errors and suppress supplementary output, leaving just the command's result
+    #[arg(short = 'q', long, global = true)]
+    pub quiet: bool,
+
+    /// Read program accounts in small rate-limited batches so public RPCs can be used (slow)
+    #[arg(long, global = true)]
+    pub low_rpc: bool,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -75,16 +108,40 @@ pub enum Commands {
         #[command(subcommand)]
         cmd: doublezero_contributor_rewards::cli::scheduler::SchedulerCommands,
     },
+    /// Manage configuration files
+    Config {
+        #[command(subcommand)]
+        cmd: doublezero_contributor_rewards::cli::config::ConfigCommands,
+    },
+    /// Run a caching RPC proxy so a team shares chain reads during development
+    DevProxy(DevProxyArgs),
+    /// Development and debugging tools
+    Debug {
+        #[command(subcommand)]
+        cmd: doublezero_contributor_rewards::cli::debug::DebugCommands,
+    },
 }
 
 impl Cli {
     pub async fn run(self) -> Result<()> {
-        let settings = if let Some(config_path) = &self.config {
+        presenter::set_quiet(self.quiet);
+
+        // Config commands operate on configurations that may not load under the current schema
+        if let Commands::Config { cmd } = self.command {
+            return doublezero_contributor_rewards::cli::config::handle(
+                self.config.as_deref(),
+                cmd,
+            );
+        }
+
+        let mut settings = if let Some(config_path) = &self.config {
             Settings::from_path(config_path)?
         } else {
             Settings::from_env()?
         };
-        init_logging(&settings.log_level)?;
+        settings.rpc.low_rpc |= self.low_rpc;
+        init_logging(&settings.log_level, self.log_format, self.quiet)?;
+        memory::set_memory_budget(settings.processing.memory_budget_mb);
 
         // Initialize metrics exporter if enabled
         if let Some(metrics) = &settings.metrics {
@@ -103,24 +160,42 @@ impl Cli {
 
         let orchestrator = Orchestrator::new(&settings);
 
+        // Every span of the run carries its id, so logs can be matched to the records it wrote
+        let run_span = info_span!("run", run_id = %orchestrator.run_id());
+        info!(parent: &run_span, "Starting run {}", orchestrator.run_id());
+
         // Route to module handlers
-        match self.command {
-            Commands::Rewards(cmd) => {
-                doublezero_contributor_rewards::cli::rewards::handle(&orchestrator, cmd).await
-            }
-            Commands::Inspect { cmd } => {
-                doublezero_contributor_rewards::cli::inspect::handle(&orchestrator, cmd).await
-            }
-            Commands::Snapshot { cmd } => {
-                doublezero_contributor_rewards::cli::snapshot::handle(&orchestrator, cmd).await
-            }
-            Commands::Telemetry { cmd } => {
-                doublezero_contributor_rewards::cli::telemetry::handle(&orchestrator, cmd).await
-            }
-            Commands::Scheduler { cmd } => {
-                doublezero_contributor_rewards::cli::scheduler::handle(&orchestrator, cmd).await
+        let command = self.command;
+        async move {
+            match command {
+                Commands::Rewards(cmd) => {
+                    doublezero_contributor_rewards::cli::rewards::handle(&orchestrator, cmd).await
+                }
+                Commands::Inspect { cmd } => {
+                    doublezero_contributor_rewards::cli::inspect::handle(&orchestrator, cmd).await
+                }
+                Commands::Snapshot { cmd } => {
+                    doublezero_contributor_rewards::cli::snapshot::handle(&orchestrator, cmd).await
+                }
+                Commands::Telemetry { cmd } => {
+                    doublezero_contributor_rewards::cli::telemetry::handle(&orchestrator, cmd).await
+                }
+                Commands::Scheduler { cmd } => {
+                    doublezero_contributor_rewards::cli::scheduler::handle(&orchestrator, cmd).await
+                }
+                Commands::DevProxy(args) => {
+                    doublezero_contributor_rewards::cli::dev_proxy::handle(&settings, args).await
+                }
+                Commands::Debug { cmd } => {
+                    doublezero_contributor_rewards::cli::debug::handle(&orchestrator, cmd).await
+                }
+                Commands::Config { .. } => {
+                    unreachable!("config commands are handled before loading settings")
+                }
             }
         }
+        .instrument(run_span)
+        .await
     }
 }
 
@@ -130,16 +205,25 @@ async fn main() -> Result<()> {
     cli.run().await
 }
 
-fn init_logging(log_level: &str) -> Result<()> {
-    tracing_subscriber::registry()
-        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(log_level)))
-        .with(
-            tracing_subscriber::fmt::layer()
-                .with_target(false)
-                .with_thread_ids(false)
-                .with_thread_names(false),
-        )
-        .init();
+fn init_logging(log_level: &str, log_format: LogFormat, quiet: bool) -> Result<()> {
+    let filter = if quiet {
+        EnvFilter::new("error")
+    } else {
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(log_level))
+    };
+
+    // Logs go to stderr so stdout only carries command results
+    let layer = tracing_subscriber::fmt::layer()
+        .with_writer(std::io::stderr)
+        .with_target(false)
+        .with_thread_ids(false)
+        .with_thread_names(false);
+
+    let registry = tracing_subscriber::registry().with(filter);
+    match log_format {
+        LogFormat::Text => registry.with(layer).init(),
+        LogFormat::Json => registry.with(layer.json()).init(),
+    }
 
     Ok(())
 }
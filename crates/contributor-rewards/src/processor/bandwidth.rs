@@ -0,0 +1,108 @@
+use crate::{calculator::constants::BPS_TO_GBPS, processor::telemetry::DZDTelemetryStats};
+use std::fmt;
+
+const BPS_TO_MBPS: f64 = 1_000_000.0;
+
+// Largest link capacity considered plausible for a single DZ circuit
+const MAX_PLAUSIBLE_GBPS: f64 = 800.0;
+
+// Probe loss above which a link cannot be carrying anything near its declared capacity
+const MAX_LOSS_AT_CAPACITY: f64 = 0.05;
+
+/// Declared bandwidth of a serviceability link, converted for the consumers that need it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinkBandwidth {
+    pub bps: u64,
+}
+
+impl LinkBandwidth {
+    pub fn new(bps: u64) -> Self {
+        Self { bps }
+    }
+
+    pub fn gbps(&self) -> f64 {
+        self.bps as f64 / BPS_TO_GBPS as f64
+    }
+
+    pub fn mbps(&self) -> f64 {
+        self.bps as f64 / BPS_TO_MBPS
+    }
+
+    /// Compare the declared bandwidth against what the link's telemetry shows
+    ///
+    /// Latency probes don't measure throughput, so this only catches declarations that
+    /// cannot be right: nothing declared, an implausible capacity, or probe loss high
+    /// enough that the link cannot be delivering what it claims.
+    pub fn check(&self, stats: Option<&DZDTelemetryStats>) -> BandwidthFlag {
+        if self.bps == 0 {
+            return BandwidthFlag::Undeclared;
+        }
+        if self.gbps() > MAX_PLAUSIBLE_GBPS {
+            return BandwidthFlag::Implausible;
+        }
+        match stats {
+            Some(stats) if stats.packet_loss > MAX_LOSS_AT_CAPACITY => BandwidthFlag::HighLoss,
+            _ => BandwidthFlag::Consistent,
+        }
+    }
+}
+
+/// Outcome of checking a link's declared bandwidth against observed behavior
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BandwidthFlag {
+    Consistent,
+    /// The link declares no bandwidth
+    Undeclared,
+    /// The declared bandwidth exceeds any realistic circuit
+    Implausible,
+    /// Probe loss is too high for the declared capacity to be usable
+    HighLoss,
+}
+
+impl BandwidthFlag {
+    pub fn is_flagged(&self) -> bool {
+        *self != Self::Consistent
+    }
+
+    /// Short label used for metrics and exports
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Consistent => "consistent",
+            Self::Undeclared => "undeclared",
+            Self::Implausible => "implausible",
+            Self::HighLoss => "high_loss",
+        }
+    }
+}
+
+impl fmt::Display for BandwidthFlag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.kind())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bandwidth_conversions() {
+        let bandwidth = LinkBandwidth::new(500_000_000);
+        assert_eq!(bandwidth.gbps(), 0.5);
+        assert_eq!(bandwidth.mbps(), 500.0);
+    }
+
+    #[test]
+    fn test_bandwidth_check() {
+        assert_eq!(LinkBandwidth::new(0).check(None), BandwidthFlag::Undeclared);
+        assert_eq!(
+            LinkBandwidth::new(10_000_000_000_000).check(None),
+            BandwidthFlag::Implausible
+        );
+        // Without telemetry there is nothing to contradict the declaration
+        assert_eq!(
+            LinkBandwidth::new(10_000_000_000).check(None),
+            BandwidthFlag::Consistent
+        );
+    }
+}
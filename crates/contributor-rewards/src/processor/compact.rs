@@ -0,0 +1,468 @@
+//! Compact ledger encoding for processed link metrics
+//!
+//! The device stat map is written to DZ record accounts as plain borsh, which spends most of
+//! its bytes on repeated pubkeys, circuit labels and f64s carrying more precision than the
+//! probes measure. The compact form stores every pubkey once, quantizes latencies to whole
+//! microseconds and ratios to parts per million, delta-encodes the RTT percentile ladder and
+//! writes every integer as a LEB128 varint.
+
+use crate::{
+    calculator::sharding::MAX_RECORD_DATA_LEN,
+    processor::telemetry::{DZDTelemetryStatMap, DZDTelemetryStats},
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_sdk::pubkey::Pubkey;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::{Error, ErrorKind, Read, Result, Write},
+};
+
+const PPM: f64 = 1_000_000.0;
+
+// Upper bound for one encoded link: three pubkey indices plus nineteen values, each a
+// varint of at most ten bytes
+const MAX_ENCODED_LINK_LEN: usize = 22 * 10;
+
+/// Processed metrics for one circuit, quantized for the ledger
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompactLinkStats {
+    pub origin_device: Pubkey,
+    pub target_device: Pubkey,
+    pub link_pubkey: Pubkey,
+    pub rtt_mean_us: u64,
+    pub rtt_min_us: u64,
+    pub rtt_median_us: u64,
+    pub rtt_p90_us: u64,
+    pub rtt_p95_us: u64,
+    pub rtt_p99_us: u64,
+    pub rtt_max_us: u64,
+    pub rtt_stddev_us: u64,
+    pub avg_jitter_us: u64,
+    pub jitter_ewma_us: u64,
+    pub max_jitter_us: u64,
+    pub rfc3550_jitter_us: u64,
+    pub jitter_stddev_us: u64,
+    pub loss_count: u64,
+    pub success_count: u64,
+    pub total_samples: u64,
+    pub packet_loss_ppm: u32,
+    pub missing_data_ppm: u32,
+    pub uptime_ppm: u32,
+}
+
+impl CompactLinkStats {
+    pub fn from_stats(stats: &DZDTelemetryStats) -> Self {
+        Self {
+            origin_device: stats.origin_device,
+            target_device: stats.target_device,
+            link_pubkey: stats.link_pubkey,
+            rtt_mean_us: quantize_us(stats.rtt_mean_us),
+            rtt_min_us: quantize_us(stats.rtt_min_us),
+            rtt_median_us: quantize_us(stats.rtt_median_us),
+            rtt_p90_us: quantize_us(stats.rtt_p90_us),
+            rtt_p95_us: quantize_us(stats.rtt_p95_us),
+            rtt_p99_us: quantize_us(stats.rtt_p99_us),
+            rtt_max_us: quantize_us(stats.rtt_max_us),
+            rtt_stddev_us: quantize_us(stats.rtt_stddev_us),
+            avg_jitter_us: quantize_us(stats.avg_jitter_us),
+            jitter_ewma_us: quantize_us(stats.jitter_ewma_us),
+            max_jitter_us: quantize_us(stats.max_jitter_us),
+            rfc3550_jitter_us: quantize_us(stats.rfc3550_jitter_us),
+            jitter_stddev_us: quantize_us(stats.jitter_stddev_us),
+            loss_count: stats.loss_count,
+            success_count: stats.success_count,
+            total_samples: stats.total_samples as u64,
+            packet_loss_ppm: quantize_ratio(stats.packet_loss),
+            missing_data_ppm: quantize_ratio(stats.missing_data_ratio),
+            uptime_ppm: quantize_ratio(stats.uptime),
+        }
+    }
+
+    /// Key used by the stat maps (`origin:target:link`)
+    pub fn circuit_key(&self) -> String {
+        format!(
+            "{}:{}:{}",
+            self.origin_device, self.target_device, self.link_pubkey
+        )
+    }
+
+    /// Expand back into stats, labelling the circuit by pubkeys since codes are not stored
+    pub fn to_stats(&self) -> DZDTelemetryStats {
+        DZDTelemetryStats {
+            circuit: format!(
+                "{} → {} ({})",
+                self.origin_device, self.target_device, self.link_pubkey
+            ),
+            link_pubkey: self.link_pubkey,
+            origin_device: self.origin_device,
+            target_device: self.target_device,
+            rtt_mean_us: self.rtt_mean_us as f64,
+            rtt_median_us: self.rtt_median_us as f64,
+            rtt_min_us: self.rtt_min_us as f64,
+            rtt_max_us: self.rtt_max_us as f64,
+            rtt_p90_us: self.rtt_p90_us as f64,
+            rtt_p95_us: self.rtt_p95_us as f64,
+            rtt_p99_us: self.rtt_p99_us as f64,
+            rtt_stddev_us: self.rtt_stddev_us as f64,
+            avg_jitter_us: self.avg_jitter_us as f64,
+            jitter_ewma_us: self.jitter_ewma_us as f64,
+            max_jitter_us: self.max_jitter_us as f64,
+            rfc3550_jitter_us: self.rfc3550_jitter_us as f64,
+            jitter_stddev_us: self.jitter_stddev_us as f64,
+            packet_loss: self.packet_loss_ppm as f64 / PPM,
+            loss_count: self.loss_count,
+            success_count: self.success_count,
+            total_samples: self.total_samples as usize,
+            missing_data_ratio: self.missing_data_ppm as f64 / PPM,
+            uptime: self.uptime_ppm as f64 / PPM,
+        }
+    }
+
+    /// RTT percentiles in ascending order, the order they are delta-encoded in
+    fn rtt_ladder(&self) -> [u64; 6] {
+        [
+            self.rtt_min_us,
+            self.rtt_median_us,
+            self.rtt_p90_us,
+            self.rtt_p95_us,
+            self.rtt_p99_us,
+            self.rtt_max_us,
+        ]
+    }
+}
+
+/// Compact form of a [`DZDTelemetryStatMap`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompactLinkStatMap {
+    pub links: Vec<CompactLinkStats>,
+}
+
+impl CompactLinkStatMap {
+    pub fn from_stat_map(stats: &DZDTelemetryStatMap) -> Self {
+        Self {
+            links: stats.values().map(CompactLinkStats::from_stats).collect(),
+        }
+    }
+
+    pub fn to_stat_map(&self) -> DZDTelemetryStatMap {
+        self.links
+            .iter()
+            .map(|link| (link.circuit_key(), link.to_stats()))
+            .collect()
+    }
+
+    /// Exact encoded size in bytes
+    pub fn encoded_len(&self) -> usize {
+        borsh::object_length(self).unwrap_or(usize::MAX)
+    }
+
+    /// Upper bound on the encoded size of `stats`, without encoding it
+    pub fn estimate_len(stats: &DZDTelemetryStatMap) -> usize {
+        let pubkeys = stats
+            .values()
+            .flat_map(|stats| [stats.origin_device, stats.target_device, stats.link_pubkey])
+            .collect::<BTreeSet<_>>()
+            .len();
+        2 * varint_len(u64::MAX) + pubkeys * 32 + stats.len() * MAX_ENCODED_LINK_LEN
+    }
+
+    /// Whether the encoded map fits in a single record account
+    pub fn fits_in_record(&self) -> bool {
+        self.encoded_len() <= MAX_RECORD_DATA_LEN
+    }
+
+    fn pubkey_table(&self) -> Vec<Pubkey> {
+        let mut table: Vec<Pubkey> = self
+            .links
+            .iter()
+            .flat_map(|link| [link.origin_device, link.target_device, link.link_pubkey])
+            .collect();
+        table.sort();
+        table.dedup();
+        table
+    }
+}
+
+impl BorshSerialize for CompactLinkStatMap {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        let table = self.pubkey_table();
+        let index: BTreeMap<Pubkey, u64> = table
+            .iter()
+            .enumerate()
+            .map(|(i, pubkey)| (*pubkey, i as u64))
+            .collect();
+
+        write_varint(writer, table.len() as u64)?;
+        for pubkey in &table {
+            writer.write_all(pubkey.as_ref())?;
+        }
+
+        write_varint(writer, self.links.len() as u64)?;
+        for link in &self.links {
+            for pubkey in [link.origin_device, link.target_device, link.link_pubkey] {
+                write_varint(writer, index[&pubkey])?;
+            }
+
+            let ladder = link.rtt_ladder();
+            write_varint(writer, ladder[0])?;
+            for pair in ladder.windows(2) {
+                write_varint(writer, zigzag(pair[1] as i64 - pair[0] as i64))?;
+            }
+
+            for value in [
+                link.rtt_mean_us,
+                link.rtt_stddev_us,
+                link.avg_jitter_us,
+                link.jitter_ewma_us,
+                link.max_jitter_us,
+                link.rfc3550_jitter_us,
+                link.jitter_stddev_us,
+                link.loss_count,
+                link.success_count,
+                link.total_samples,
+                link.packet_loss_ppm as u64,
+                link.missing_data_ppm as u64,
+                link.uptime_ppm as u64,
+            ] {
+                write_varint(writer, value)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl BorshDeserialize for CompactLinkStatMap {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let table_len = read_varint(reader)?;
+        let mut table = Vec::new();
+        for _ in 0..table_len {
+            let mut bytes = [0u8; 32];
+            reader.read_exact(&mut bytes)?;
+            table.push(Pubkey::new_from_array(bytes));
+        }
+        let pubkey = |reader: &mut R| -> Result<Pubkey> {
+            let i = read_varint(reader)?;
+            table.get(i as usize).copied().ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!("pubkey index {i} out of range"),
+                )
+            })
+        };
+
+        let link_count = read_varint(reader)?;
+        let mut links = Vec::new();
+        for _ in 0..link_count {
+            let origin_device = pubkey(reader)?;
+            let target_device = pubkey(reader)?;
+            let link_pubkey = pubkey(reader)?;
+
+            let mut ladder = [0u64; 6];
+            ladder[0] = read_varint(reader)?;
+            for i in 1..ladder.len() {
+                ladder[i] = ladder[i - 1]
+                    .checked_add_signed(unzigzag(read_varint(reader)?))
+                    .ok_or_else(|| Error::new(ErrorKind::InvalidData, "RTT delta out of range"))?;
+            }
+
+            let mut values = [0u64; 13];
+            for value in values.iter_mut() {
+                *value = read_varint(reader)?;
+            }
+            let ppm = |value: u64| -> Result<u32> {
+                u32::try_from(value)
+                    .map_err(|_| Error::new(ErrorKind::InvalidData, "ratio out of range"))
+            };
+
+            links.push(CompactLinkStats {
+                origin_device,
+                target_device,
+                link_pubkey,
+                rtt_min_us: ladder[0],
+                rtt_median_us: ladder[1],
+                rtt_p90_us: ladder[2],
+                rtt_p95_us: ladder[3],
+                rtt_p99_us: ladder[4],
+                rtt_max_us: ladder[5],
+                rtt_mean_us: values[0],
+                rtt_stddev_us: values[1],
+                avg_jitter_us: values[2],
+                jitter_ewma_us: values[3],
+                max_jitter_us: values[4],
+                rfc3550_jitter_us: values[5],
+                jitter_stddev_us: values[6],
+                loss_count: values[7],
+                success_count: values[8],
+                total_samples: values[9],
+                packet_loss_ppm: ppm(values[10])?,
+                missing_data_ppm: ppm(values[11])?,
+                uptime_ppm: ppm(values[12])?,
+            });
+        }
+
+        Ok(Self { links })
+    }
+}
+
+fn quantize_us(us: f64) -> u64 {
+    if us.is_finite() && us > 0.0 {
+        us.round() as u64
+    } else {
+        0
+    }
+}
+
+fn quantize_ratio(ratio: f64) -> u32 {
+    if ratio.is_finite() {
+        (ratio.clamp(0.0, 1.0) * PPM).round() as u32
+    } else {
+        0
+    }
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn unzigzag(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+fn varint_len(mut value: u64) -> usize {
+    let mut len = 1;
+    while value >= 0x80 {
+        value >>= 7;
+        len += 1;
+    }
+    len
+}
+
+fn write_varint<W: Write>(writer: &mut W, mut value: u64) -> Result<()> {
+    while value >= 0x80 {
+        writer.write_all(&[(value as u8) | 0x80])?;
+        value >>= 7;
+    }
+    writer.write_all(&[value as u8])
+}
+
+fn read_varint<R: Read>(reader: &mut R) -> Result<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let mut byte = [0u8; 1];
+        reader.read_exact(&mut byte)?;
+        value |= ((byte[0] & 0x7f) as u64) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(Error::new(ErrorKind::InvalidData, "varint too long"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(origin: Pubkey, target: Pubkey, link: Pubkey) -> DZDTelemetryStats {
+        DZDTelemetryStats {
+            circuit: "a → b (ab)".to_string(),
+            link_pubkey: link,
+            origin_device: origin,
+            target_device: target,
+            rtt_mean_us: 1520.4,
+            rtt_median_us: 1500.0,
+            rtt_min_us: 1400.2,
+            rtt_max_us: 9800.0,
+            rtt_p90_us: 1700.0,
+            rtt_p95_us: 1900.0,
+            rtt_p99_us: 4100.0,
+            rtt_stddev_us: 210.6,
+            avg_jitter_us: 35.0,
+            jitter_ewma_us: 30.0,
+            max_jitter_us: 800.0,
+            rfc3550_jitter_us: 28.0,
+            jitter_stddev_us: 40.0,
+            packet_loss: 0.0125,
+            loss_count: 25,
+            success_count: 1975,
+            total_samples: 2000,
+            missing_data_ratio: 0.001,
+            uptime: 0.999,
+        }
+    }
+
+    fn stat_map() -> DZDTelemetryStatMap {
+        let (a, b, link) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        [stats(a, b, link), stats(b, a, link)]
+            .into_iter()
+            .map(|stats| {
+                (
+                    format!(
+                        "{}:{}:{}",
+                        stats.origin_device, stats.target_device, stats.link_pubkey
+                    ),
+                    stats,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_compact_roundtrip() {
+        let compact = CompactLinkStatMap::from_stat_map(&stat_map());
+        let bytes = borsh::to_vec(&compact).unwrap();
+        assert_eq!(bytes.len(), compact.encoded_len());
+
+        let decoded = CompactLinkStatMap::try_from_slice(&bytes).unwrap();
+        assert_eq!(decoded, compact);
+
+        let expanded = decoded.to_stat_map();
+        let original = stat_map();
+        assert_eq!(
+            expanded.keys().collect::<Vec<_>>(),
+            original.keys().collect::<Vec<_>>()
+        );
+        let link = expanded.values().next().unwrap();
+        assert_eq!(link.rtt_mean_us, 1520.0);
+        assert_eq!(link.packet_loss, 0.0125);
+        assert_eq!(link.total_samples, 2000);
+    }
+
+    #[test]
+    fn test_compact_is_smaller_and_estimate_is_an_upper_bound() {
+        let stats = stat_map();
+        let compact = CompactLinkStatMap::from_stat_map(&stats);
+
+        assert!(compact.encoded_len() < borsh::to_vec(&stats).unwrap().len() / 2);
+        assert!(compact.encoded_len() <= CompactLinkStatMap::estimate_len(&stats));
+        assert!(compact.fits_in_record());
+    }
+
+    #[test]
+    fn test_out_of_order_percentiles_roundtrip() {
+        let mut stats = stat_map();
+        // Penalty values or interpolation can leave the ladder non-monotonic
+        stats.values_mut().next().unwrap().rtt_p90_us = 1000.0;
+        let compact = CompactLinkStatMap::from_stat_map(&stats);
+
+        let bytes = borsh::to_vec(&compact).unwrap();
+        assert_eq!(CompactLinkStatMap::try_from_slice(&bytes).unwrap(), compact);
+    }
+
+    #[test]
+    fn test_varint() {
+        for value in [0, 1, 127, 128, 16_383, 16_384, u64::MAX] {
+            let mut bytes = Vec::new();
+            write_varint(&mut bytes, value).unwrap();
+            assert_eq!(bytes.len(), varint_len(value));
+            assert_eq!(read_varint(&mut &bytes[..]).unwrap(), value);
+        }
+        for value in [0, -1, 1, i64::MIN, i64::MAX] {
+            assert_eq!(unzigzag(zigzag(value)), value);
+        }
+    }
+}
@@ -0,0 +1,329 @@
+//! Side-by-side comparison of processed metrics across epochs
+//!
+//! Each epoch is processed on its own into an [`EpochMetrics`]; comparing two of them pairs up
+//! links and cities by their stat map keys and reports how latency, coverage and demand moved
+//! between them.
+
+use crate::{
+    ingestor::demand::CityStats,
+    processor::{
+        internet::InternetTelemetryStatMap, telemetry::DZDTelemetryStatMap, util::display_us_as_ms,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use tabled::Tabled;
+
+/// Processed metrics for one epoch
+#[derive(Debug, Clone, Default)]
+pub struct EpochMetrics {
+    pub epoch: u64,
+    pub device_stats: DZDTelemetryStatMap,
+    pub internet_stats: InternetTelemetryStatMap,
+    /// Validator distribution by city, absent when demand was not built
+    pub city_stats: Option<CityStats>,
+}
+
+/// Latency change for a circuit measured in both epochs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Tabled)]
+pub struct LatencyDelta {
+    pub kind: String,
+    pub circuit: String,
+    #[tabled(display = "display_us_as_ms", rename = "from_p95(ms)")]
+    pub from_rtt_p95_us: f64,
+    #[tabled(display = "display_us_as_ms", rename = "to_p95(ms)")]
+    pub to_rtt_p95_us: f64,
+    #[tabled(display = "display_us_as_ms", rename = "delta_p95(ms)")]
+    pub delta_rtt_p95_us: f64,
+    #[tabled(display = "display_ratio", rename = "from_loss")]
+    pub from_packet_loss: f64,
+    #[tabled(display = "display_ratio", rename = "to_loss")]
+    pub to_packet_loss: f64,
+}
+
+/// Circuits that appeared or disappeared between two epochs
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CoverageChange {
+    pub device_added: Vec<String>,
+    pub device_removed: Vec<String>,
+    pub internet_added: Vec<String>,
+    pub internet_removed: Vec<String>,
+    /// Mean share of expected samples missing, per epoch
+    pub from_missing_data_ratio: f64,
+    pub to_missing_data_ratio: f64,
+}
+
+/// Change in a city's share of validator stake
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Tabled)]
+pub struct DemandShift {
+    pub city: String,
+    pub from_validators: usize,
+    pub to_validators: usize,
+    #[tabled(display = "display_ratio", rename = "from_share")]
+    pub from_stake_share: f64,
+    #[tabled(display = "display_ratio", rename = "to_share")]
+    pub to_stake_share: f64,
+    #[tabled(display = "display_ratio", rename = "delta_share")]
+    pub delta_stake_share: f64,
+}
+
+/// Every canned comparison between two epochs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EpochComparison {
+    pub from_epoch: u64,
+    pub to_epoch: u64,
+    /// Sorted by largest absolute p95 change first
+    pub latency_deltas: Vec<LatencyDelta>,
+    pub coverage: CoverageChange,
+    /// Sorted by largest absolute share change first; empty without city stats
+    pub demand_shifts: Vec<DemandShift>,
+}
+
+impl EpochComparison {
+    pub fn new(from: &EpochMetrics, to: &EpochMetrics) -> Self {
+        let mut latency_deltas: Vec<LatencyDelta> = from
+            .device_stats
+            .iter()
+            .filter_map(|(key, from_stats)| {
+                let to_stats = to.device_stats.get(key)?;
+                Some(LatencyDelta {
+                    kind: "device".to_string(),
+                    circuit: to_stats.circuit.clone(),
+                    from_rtt_p95_us: from_stats.rtt_p95_us,
+                    to_rtt_p95_us: to_stats.rtt_p95_us,
+                    delta_rtt_p95_us: to_stats.rtt_p95_us - from_stats.rtt_p95_us,
+                    from_packet_loss: from_stats.packet_loss,
+                    to_packet_loss: to_stats.packet_loss,
+                })
+            })
+            .chain(from.internet_stats.iter().filter_map(|(key, from_stats)| {
+                let to_stats = to.internet_stats.get(key)?;
+                Some(LatencyDelta {
+                    kind: "internet".to_string(),
+                    circuit: to_stats.circuit.clone(),
+                    from_rtt_p95_us: from_stats.rtt_p95_us,
+                    to_rtt_p95_us: to_stats.rtt_p95_us,
+                    delta_rtt_p95_us: to_stats.rtt_p95_us - from_stats.rtt_p95_us,
+                    from_packet_loss: from_stats.packet_loss,
+                    to_packet_loss: to_stats.packet_loss,
+                })
+            }))
+            .collect();
+        latency_deltas.sort_by(|a, b| {
+            b.delta_rtt_p95_us
+                .abs()
+                .total_cmp(&a.delta_rtt_p95_us.abs())
+        });
+
+        let (device_added, device_removed) =
+            key_changes(&from.device_stats, &to.device_stats, |stats| &stats.circuit);
+        let (internet_added, internet_removed) =
+            key_changes(&from.internet_stats, &to.internet_stats, |stats| {
+                &stats.circuit
+            });
+        let coverage = CoverageChange {
+            device_added,
+            device_removed,
+            internet_added,
+            internet_removed,
+            from_missing_data_ratio: mean_missing_data_ratio(from),
+            to_missing_data_ratio: mean_missing_data_ratio(to),
+        };
+
+        let demand_shifts = match (&from.city_stats, &to.city_stats) {
+            (Some(from_cities), Some(to_cities)) => demand_shifts(from_cities, to_cities),
+            _ => Vec::new(),
+        };
+
+        Self {
+            from_epoch: from.epoch,
+            to_epoch: to.epoch,
+            latency_deltas,
+            coverage,
+            demand_shifts,
+        }
+    }
+}
+
+/// Circuits of the entries only in `to`, then of those only in `from`
+fn key_changes<V>(
+    from: &BTreeMap<String, V>,
+    to: &BTreeMap<String, V>,
+    circuit: impl Fn(&V) -> &String,
+) -> (Vec<String>, Vec<String>) {
+    let only_in = |a: &BTreeMap<String, V>, b: &BTreeMap<String, V>| -> Vec<String> {
+        a.iter()
+            .filter(|(key, _)| !b.contains_key(*key))
+            .map(|(_, stats)| circuit(stats).clone())
+            .collect()
+    };
+    (only_in(to, from), only_in(from, to))
+}
+
+fn mean_missing_data_ratio(metrics: &EpochMetrics) -> f64 {
+    let ratios: Vec<f64> = metrics
+        .device_stats
+        .values()
+        .map(|stats| stats.missing_data_ratio)
+        .chain(
+            metrics
+                .internet_stats
+                .values()
+                .map(|stats| stats.missing_data_ratio),
+        )
+        .collect();
+    if ratios.is_empty() {
+        return 0.0;
+    }
+    ratios.iter().sum::<f64>() / ratios.len() as f64
+}
+
+fn demand_shifts(from: &CityStats, to: &CityStats) -> Vec<DemandShift> {
+    let total = |cities: &CityStats| -> f64 {
+        cities
+            .values()
+            .map(|city| city.total_stake_proxy as f64)
+            .sum()
+    };
+    let share = |cities: &CityStats, city: &str, total: f64| -> f64 {
+        match cities.get(city) {
+            Some(stat) if total > 0.0 => stat.total_stake_proxy as f64 / total,
+            _ => 0.0,
+        }
+    };
+    let (from_total, to_total) = (total(from), total(to));
+
+    let mut shifts: Vec<DemandShift> = from
+        .keys()
+        .chain(to.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .map(|city| {
+            let from_stake_share = share(from, city, from_total);
+            let to_stake_share = share(to, city, to_total);
+            DemandShift {
+                city: city.clone(),
+                from_validators: from.get(city).map_or(0, |stat| stat.validator_count),
+                to_validators: to.get(city).map_or(0, |stat| stat.validator_count),
+                from_stake_share,
+                to_stake_share,
+                delta_stake_share: to_stake_share - from_stake_share,
+            }
+        })
+        .collect();
+    shifts.sort_by(|a, b| {
+        b.delta_stake_share
+            .abs()
+            .total_cmp(&a.delta_stake_share.abs())
+    });
+    shifts
+}
+
+fn display_ratio(ratio: &f64) -> String {
+    format!("{ratio:.4}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ingestor::demand::CityStat, processor::telemetry::DZDTelemetryStats};
+    use solana_sdk::pubkey::Pubkey;
+
+    fn device_stats(circuit: &str, rtt_p95_us: f64) -> DZDTelemetryStats {
+        DZDTelemetryStats {
+            circuit: circuit.to_string(),
+            link_pubkey: Pubkey::new_unique(),
+            origin_device: Pubkey::new_unique(),
+            target_device: Pubkey::new_unique(),
+            rtt_mean_us: rtt_p95_us,
+            rtt_median_us: rtt_p95_us,
+            rtt_min_us: rtt_p95_us,
+            rtt_max_us: rtt_p95_us,
+            rtt_p90_us: rtt_p95_us,
+            rtt_p95_us,
+            rtt_p99_us: rtt_p95_us,
+            rtt_stddev_us: 0.0,
+            avg_jitter_us: 0.0,
+            jitter_ewma_us: 0.0,
+            max_jitter_us: 0.0,
+            rfc3550_jitter_us: 0.0,
+            jitter_stddev_us: 0.0,
+            packet_loss: 0.0,
+            loss_count: 0,
+            success_count: 100,
+            total_samples: 100,
+            missing_data_ratio: 0.1,
+            uptime: 1.0,
+        }
+    }
+
+    fn metrics(epoch: u64, links: &[(&str, f64)]) -> EpochMetrics {
+        EpochMetrics {
+            epoch,
+            device_stats: links
+                .iter()
+                .map(|(circuit, p95)| (circuit.to_string(), device_stats(circuit, *p95)))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_latency_deltas_sorted_by_magnitude() {
+        let from = metrics(1, &[("a", 1_000.0), ("b", 2_000.0)]);
+        let to = metrics(2, &[("a", 1_100.0), ("b", 1_000.0)]);
+
+        let comparison = EpochComparison::new(&from, &to);
+        let deltas: Vec<(&str, f64)> = comparison
+            .latency_deltas
+            .iter()
+            .map(|delta| (delta.circuit.as_str(), delta.delta_rtt_p95_us))
+            .collect();
+        assert_eq!(deltas, vec![("b", -1_000.0), ("a", 100.0)]);
+    }
+
+    #[test]
+    fn test_coverage_changes() {
+        let from = metrics(1, &[("a", 1_000.0), ("b", 2_000.0)]);
+        let to = metrics(2, &[("b", 2_000.0), ("c", 3_000.0)]);
+
+        let coverage = EpochComparison::new(&from, &to).coverage;
+        assert_eq!(coverage.device_added, vec!["c".to_string()]);
+        assert_eq!(coverage.device_removed, vec!["a".to_string()]);
+        assert!((coverage.to_missing_data_ratio - 0.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_demand_shifts() {
+        let city = |validator_count, total_stake_proxy| CityStat {
+            validator_count,
+            total_stake_proxy,
+        };
+        let mut from = metrics(1, &[]);
+        from.city_stats = Some(CityStats::from([
+            ("ams".to_string(), city(1, 100)),
+            ("fra".to_string(), city(1, 100)),
+        ]));
+        let mut to = metrics(2, &[]);
+        to.city_stats = Some(CityStats::from([
+            ("ams".to_string(), city(3, 300)),
+            ("nyc".to_string(), city(1, 100)),
+        ]));
+
+        let shifts = EpochComparison::new(&from, &to).demand_shifts;
+        assert_eq!(shifts.len(), 3);
+        assert_eq!(shifts[0].city, "fra");
+        assert!((shifts[0].delta_stake_share + 0.5).abs() < 1e-9);
+        let ams = shifts.iter().find(|shift| shift.city == "ams").unwrap();
+        assert!((ams.delta_stake_share - 0.25).abs() < 1e-9);
+        assert_eq!(ams.to_validators, 3);
+    }
+
+    #[test]
+    fn test_demand_shifts_need_both_epochs() {
+        let mut from = metrics(1, &[]);
+        from.city_stats = Some(CityStats::new());
+        let to = metrics(2, &[]);
+        assert!(EpochComparison::new(&from, &to).demand_shifts.is_empty());
+    }
+}
@@ -4,3 +4,13 @@
 pub const PENALTY_RTT_US: f64 = 1_000_000.0;
 // Jitter (high): 100ms
 pub const PENALTY_JITTER_US: f64 = 100_000.0;
+
+// Two-sided 95% z-score for normal approximation confidence intervals
+pub const Z_SCORE_95: f64 = 1.96;
+
+/// Version of the jitter aggregation method recorded in the reward input
+///
+/// 1: average of absolute RTT deltas, averaged across sample sets
+/// 2: adds RFC 3550 interarrival jitter and delta standard deviation, pooled
+///    across sample sets by delta count
+pub const JITTER_AGGREGATION_VERSION: u32 = 2;
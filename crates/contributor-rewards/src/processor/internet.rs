@@ -1,6 +1,6 @@
 use crate::{
     ingestor::types::{DZInternetLatencySamples, FetchData},
-    processor::{process::process_internet_samples, util::display_us_as_ms},
+    processor::{outage::LinkOutage, process::process_internet_samples, util::display_us_as_ms},
 };
 use anyhow::Result;
 use borsh::{BorshDeserialize, BorshSerialize};
@@ -63,6 +63,10 @@ pub struct InternetTelemetryStats {
     pub jitter_ewma_us: f64,
     #[tabled(display = "display_us_as_ms", rename = "max_jitter(ms)")]
     pub max_jitter_us: f64,
+    #[tabled(display = "display_us_as_ms", rename = "rfc3550_jitter(ms)")]
+    pub rfc3550_jitter_us: f64,
+    #[tabled(display = "display_us_as_ms", rename = "jitter_stddev(ms)")]
+    pub jitter_stddev_us: f64,
     #[tabled(rename = "loss_rate")]
     pub packet_loss: f64,
     #[tabled(rename = "loss_count")]
@@ -87,6 +91,13 @@ pub fn print_internet_stats(map: &InternetTelemetryStatMap) -> String {
 
 impl InternetTelemetryProcessor {
     pub fn process(fetch_data: &FetchData) -> Result<InternetTelemetryStatMap> {
+        Self::process_with_outages(fetch_data).map(|(stats, _)| stats)
+    }
+
+    /// Process internet telemetry, also returning each circuit's degraded and down intervals
+    pub fn process_with_outages(
+        fetch_data: &FetchData,
+    ) -> Result<(InternetTelemetryStatMap, Vec<LinkOutage>)> {
         // Build exchange PK to xchange code mapping (internet telemetry uses exchange PKs)
         let exchange_pk_to_code: BTreeMap<Pubkey, String> = fetch_data
             .dz_serviceability
@@ -109,6 +120,7 @@ impl InternetTelemetryProcessor {
 
         // Convert from generic TelemetryStatistics to InternetTelemetryStats
         let mut result = BTreeMap::new();
+        let mut outages = Vec::new();
 
         // Need to get the first sample from each group to extract oracle agent
         let mut sample_by_key: BTreeMap<String, &DZInternetLatencySamples> = BTreeMap::new();
@@ -187,6 +199,8 @@ impl InternetTelemetryProcessor {
                     avg_jitter_us: stats.avg_jitter_us,
                     jitter_ewma_us: stats.ewma_jitter_us,
                     max_jitter_us: stats.max_jitter_us,
+                    rfc3550_jitter_us: stats.rfc3550_jitter_us,
+                    jitter_stddev_us: stats.jitter_delta_stddev_us,
                     packet_loss: stats.packet_loss,
                     loss_count: stats.loss_count,
                     success_count: stats.success_count,
@@ -194,10 +208,16 @@ impl InternetTelemetryProcessor {
                     missing_data_ratio: stats.missing_data_ratio,
                 };
 
+                outages.extend(
+                    stats
+                        .outages
+                        .iter()
+                        .map(|outage| LinkOutage::new(&internet_stats.circuit, outage)),
+                );
                 result.insert(circuit_key, internet_stats);
             }
         }
 
-        Ok(result)
+        Ok((result, outages))
     }
 }
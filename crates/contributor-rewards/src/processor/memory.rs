@@ -0,0 +1,107 @@
+//! Memory budget for telemetry processing
+//!
+//! Circuits are aggregated in parallel, and each holds its successful RTTs while their
+//! percentiles are computed. With a budget set, circuits are aggregated in batches whose estimated
+//! working memory fits within it, so a large epoch takes longer instead of being OOM killed. The
+//! process's peak resident memory is read back from the kernel for the run summary.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Estimated bytes held per sample while its circuit is aggregated: the RTT and its MAD
+/// deviation, plus the jitter buffers of the series being added
+pub const WORKING_BYTES_PER_SAMPLE: u64 = 36;
+
+/// Budget in bytes shared by every processing run in the process, 0 when unlimited
+static MEMORY_BUDGET: AtomicU64 = AtomicU64::new(0);
+
+/// Limit telemetry processing to `budget_mb` MiB of working memory, or lift the limit with `None`
+pub fn set_memory_budget(budget_mb: Option<u64>) {
+    let bytes = budget_mb.map_or(0, |mb| mb.saturating_mul(1024 * 1024));
+    MEMORY_BUDGET.store(bytes, Ordering::Relaxed);
+}
+
+/// Working memory budget in bytes, if one is set
+pub fn memory_budget() -> Option<u64> {
+    match MEMORY_BUDGET.load(Ordering::Relaxed) {
+        0 => None,
+        bytes => Some(bytes),
+    }
+}
+
+/// Split `items`, each paired with its estimated working bytes, into consecutive batches whose
+/// estimates add up to at most `budget`
+///
+/// An item over budget on its own gets a batch to itself. Without a budget everything is one
+/// batch.
+pub fn batch_by_budget<T>(
+    items: impl IntoIterator<Item = (T, u64)>,
+    budget: Option<u64>,
+) -> Vec<Vec<T>> {
+    let mut batches = Vec::new();
+    let mut batch = Vec::new();
+    let mut batch_bytes = 0u64;
+
+    for (item, bytes) in items {
+        if let Some(budget) = budget
+            && !batch.is_empty()
+            && batch_bytes.saturating_add(bytes) > budget
+        {
+            batches.push(std::mem::take(&mut batch));
+            batch_bytes = 0;
+        }
+        batch.push(item);
+        batch_bytes = batch_bytes.saturating_add(bytes);
+    }
+    if !batch.is_empty() {
+        batches.push(batch);
+    }
+
+    batches
+}
+
+/// Peak resident set size of this process in bytes
+///
+/// Read from `/proc/self/status`, so only available on Linux.
+pub fn peak_rss_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    parse_peak_rss(&status)
+}
+
+fn parse_peak_rss(status: &str) -> Option<u64> {
+    let kb = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmHWM:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    Some(kb * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_by_budget() {
+        let items = [("a", 40), ("b", 50), ("c", 20), ("d", 150), ("e", 10)];
+
+        assert_eq!(
+            batch_by_budget(items, None),
+            vec![vec!["a", "b", "c", "d", "e"]]
+        );
+        assert_eq!(
+            batch_by_budget(items, Some(100)),
+            vec![vec!["a", "b"], vec!["c"], vec!["d"], vec!["e"]]
+        );
+        assert!(batch_by_budget(Vec::<(&str, u64)>::new(), Some(100)).is_empty());
+    }
+
+    #[test]
+    fn test_parse_peak_rss() {
+        let status = "Name:\tcontributor-rew\nVmPeak:\t  912340 kB\nVmHWM:\t  204800 kB\nVmRSS:\t  102400 kB\n";
+        assert_eq!(parse_peak_rss(status), Some(204800 * 1024));
+        assert_eq!(parse_peak_rss("Name:\tcontributor-rew\n"), None);
+    }
+}
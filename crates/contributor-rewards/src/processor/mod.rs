@@ -1,6 +1,12 @@
+pub mod bandwidth;
+pub mod compact;
+pub mod compare;
 pub mod constants;
 pub mod internet;
+pub mod memory;
+pub mod outage;
 pub mod process;
 pub mod stats;
 pub mod telemetry;
+pub mod topology;
 pub mod util;
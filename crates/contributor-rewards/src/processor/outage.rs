@@ -0,0 +1,334 @@
+//! Segmentation of a link's epoch into up, degraded and down intervals
+//!
+//! Probe results from every sample account of a circuit are laid on one timeline of expected
+//! probe intervals. A run of consecutive missing probes at least
+//! [`OutageThresholds::down_after_samples`] long is an outage; outside of outages, fixed windows
+//! whose share of missing probes reaches [`OutageThresholds::degraded_loss_ratio`] are degraded.
+//! Uptime is computed from the same timeline, so outages and uptime always agree on which
+//! probes were missing.
+
+use crate::processor::util::{SampleSeries, display_us_as_ms};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use tabled::Tabled;
+
+/// Consecutive missing probes before a link counts as down
+pub const DEFAULT_DOWN_AFTER_SAMPLES: usize = 3;
+/// Probes per window when looking for degraded periods
+pub const DEFAULT_DEGRADED_WINDOW_SAMPLES: usize = 20;
+/// Share of missing probes in a window at which the link counts as degraded
+pub const DEFAULT_DEGRADED_LOSS_RATIO: f64 = 0.1;
+
+/// State of a link over an interval
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkState {
+    Up,
+    Degraded,
+    Down,
+}
+
+impl fmt::Display for LinkState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinkState::Up => write!(f, "up"),
+            LinkState::Degraded => write!(f, "degraded"),
+            LinkState::Down => write!(f, "down"),
+        }
+    }
+}
+
+/// Thresholds used to segment a timeline
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OutageThresholds {
+    pub down_after_samples: usize,
+    pub degraded_window_samples: usize,
+    pub degraded_loss_ratio: f64,
+}
+
+impl Default for OutageThresholds {
+    fn default() -> Self {
+        Self {
+            down_after_samples: DEFAULT_DOWN_AFTER_SAMPLES,
+            degraded_window_samples: DEFAULT_DEGRADED_WINDOW_SAMPLES,
+            degraded_loss_ratio: DEFAULT_DEGRADED_LOSS_RATIO,
+        }
+    }
+}
+
+/// Result of one expected probe interval
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Probe {
+    Ok,
+    Missing,
+    /// Inside an excluded time range, e.g. a maintenance window
+    Excluded,
+}
+
+/// Expected probe intervals of a circuit between `start_us` and the end of the window
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProbeTimeline {
+    pub start_us: u64,
+    pub interval_us: u64,
+    pub probes: Vec<Probe>,
+}
+
+impl ProbeTimeline {
+    /// Lay the successful samples of every series on the expected probe intervals in
+    /// `[start_us, end_us)`, at the finest sampling interval among them
+    ///
+    /// Returns `None` when no series has a sampling interval.
+    pub fn new(
+        series: &[SampleSeries<'_>],
+        start_us: u64,
+        end_us: u64,
+        excluded: &[(u64, u64)],
+    ) -> Option<Self> {
+        let interval_us = series
+            .iter()
+            .map(|s| s.sampling_interval_us)
+            .filter(|&interval| interval > 0)
+            .min()?;
+        let is_excluded = |ts: u64| excluded.iter().any(|&(from, to)| ts >= from && ts < to);
+
+        let mut probes: Vec<Probe> = (start_us..end_us)
+            .step_by(interval_us as usize)
+            .map(|ts| {
+                if is_excluded(ts) {
+                    Probe::Excluded
+                } else {
+                    Probe::Missing
+                }
+            })
+            .collect();
+
+        for s in series {
+            for (i, &sample) in s.samples.iter().enumerate() {
+                let ts = s.start_timestamp_us + i as u64 * s.sampling_interval_us;
+                if sample == 0 || ts < start_us || ts >= end_us || is_excluded(ts) {
+                    continue;
+                }
+                let index = ((ts - start_us) / interval_us) as usize;
+                if let Some(probe) = probes.get_mut(index) {
+                    *probe = Probe::Ok;
+                }
+            }
+        }
+
+        Some(Self {
+            start_us,
+            interval_us,
+            probes,
+        })
+    }
+
+    /// Fraction of expected (not excluded) probes that succeeded; 1.0 when nothing was expected
+    pub fn uptime(&self) -> f64 {
+        let expected = self
+            .probes
+            .iter()
+            .filter(|probe| **probe != Probe::Excluded)
+            .count();
+        if expected == 0 {
+            return 1.0;
+        }
+        let observed = self
+            .probes
+            .iter()
+            .filter(|probe| **probe == Probe::Ok)
+            .count();
+        observed as f64 / expected as f64
+    }
+
+    /// State of the link at each probe interval
+    pub fn states(&self, thresholds: &OutageThresholds) -> Vec<LinkState> {
+        let mut states = vec![LinkState::Up; self.probes.len()];
+
+        // Long enough runs of missing probes are outages
+        let mut run_start = None;
+        for (i, probe) in self.probes.iter().chain([&Probe::Ok]).enumerate() {
+            match (probe, run_start) {
+                (Probe::Missing, None) => run_start = Some(i),
+                (Probe::Missing, Some(_)) => {}
+                (_, Some(start)) => {
+                    if i - start >= thresholds.down_after_samples.max(1) {
+                        states[start..i].fill(LinkState::Down);
+                    }
+                    run_start = None;
+                }
+                (_, None) => {}
+            }
+        }
+
+        // Elsewhere, lossy windows are degraded
+        let window = thresholds.degraded_window_samples.max(1);
+        for (chunk, probes) in self.probes.chunks(window).enumerate() {
+            let expected = probes.iter().filter(|p| **p != Probe::Excluded).count();
+            let missing = probes.iter().filter(|p| **p == Probe::Missing).count();
+            if expected == 0 || (missing as f64 / expected as f64) < thresholds.degraded_loss_ratio
+            {
+                continue;
+            }
+            let range = chunk * window..chunk * window + probes.len();
+            for state in &mut states[range] {
+                if *state == LinkState::Up {
+                    *state = LinkState::Degraded;
+                }
+            }
+        }
+
+        states
+    }
+
+    /// Degraded and down intervals, in time order
+    pub fn outages(&self, thresholds: &OutageThresholds) -> Vec<Outage> {
+        let states = self.states(thresholds);
+
+        let mut outages = Vec::new();
+        let mut start = 0;
+        while start < states.len() {
+            let state = states[start];
+            let end = states[start..]
+                .iter()
+                .position(|s| *s != state)
+                .map_or(states.len(), |len| start + len);
+
+            if state != LinkState::Up {
+                let start_us = self.start_us + start as u64 * self.interval_us;
+                let end_us = self.start_us + end as u64 * self.interval_us;
+                outages.push(Outage {
+                    start_us,
+                    end_us,
+                    duration_us: end_us - start_us,
+                    severity: state,
+                    missing_samples: self.probes[start..end]
+                        .iter()
+                        .filter(|p| **p == Probe::Missing)
+                        .count(),
+                });
+            }
+            start = end;
+        }
+
+        outages
+    }
+}
+
+/// A degraded or down interval of a link
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Outage {
+    pub start_us: u64,
+    pub end_us: u64,
+    pub duration_us: u64,
+    pub severity: LinkState,
+    /// Expected probes without a successful sample
+    pub missing_samples: usize,
+}
+
+/// An outage of a named circuit, for display and export
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Tabled)]
+pub struct LinkOutage {
+    pub circuit: String,
+    pub severity: LinkState,
+    pub start_us: u64,
+    pub end_us: u64,
+    #[tabled(display = "display_us_as_ms", rename = "duration(ms)")]
+    pub duration_us: f64,
+    pub missing_samples: usize,
+}
+
+impl LinkOutage {
+    pub fn new(circuit: &str, outage: &Outage) -> Self {
+        Self {
+            circuit: circuit.to_string(),
+            severity: outage.severity,
+            start_us: outage.start_us,
+            end_us: outage.end_us,
+            duration_us: outage.duration_us as f64,
+            missing_samples: outage.missing_samples,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timeline(samples: &[u32]) -> ProbeTimeline {
+        let series = [SampleSeries {
+            samples,
+            start_timestamp_us: 0,
+            sampling_interval_us: 10,
+        }];
+        ProbeTimeline::new(&series, 0, samples.len() as u64 * 10, &[]).unwrap()
+    }
+
+    fn thresholds() -> OutageThresholds {
+        OutageThresholds {
+            down_after_samples: 3,
+            degraded_window_samples: 5,
+            degraded_loss_ratio: 0.4,
+        }
+    }
+
+    #[test]
+    fn test_healthy_link_has_no_outages() {
+        let timeline = timeline(&[100; 20]);
+        assert!(timeline.outages(&thresholds()).is_empty());
+        assert_eq!(timeline.uptime(), 1.0);
+    }
+
+    #[test]
+    fn test_consecutive_missing_samples_are_down() {
+        // Windows: [1, 1, 1, 1, 0] [0, 0, 1, 1, 1] [1, 1, 1, 1, 1]
+        let timeline = timeline(&[1, 1, 1, 1, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1]);
+        let outages = timeline.outages(&thresholds());
+
+        assert_eq!(
+            outages,
+            vec![
+                Outage {
+                    start_us: 40,
+                    end_us: 70,
+                    duration_us: 30,
+                    severity: LinkState::Down,
+                    missing_samples: 3,
+                },
+                Outage {
+                    start_us: 70,
+                    end_us: 100,
+                    duration_us: 30,
+                    severity: LinkState::Degraded,
+                    missing_samples: 0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scattered_loss_is_degraded() {
+        // Two isolated losses in the first window, one in the second
+        let timeline = timeline(&[1, 0, 1, 0, 1, 1, 1, 0, 1, 1]);
+        let outages = timeline.outages(&thresholds());
+
+        assert_eq!(outages.len(), 1);
+        assert_eq!(outages[0].severity, LinkState::Degraded);
+        assert_eq!((outages[0].start_us, outages[0].end_us), (0, 50));
+        assert_eq!(outages[0].missing_samples, 2);
+        assert!((timeline.uptime() - 0.7).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_excluded_probes_are_not_outages() {
+        let samples = [1, 0, 0, 0, 0, 1];
+        let series = [SampleSeries {
+            samples: &samples,
+            start_timestamp_us: 0,
+            sampling_interval_us: 10,
+        }];
+        let timeline = ProbeTimeline::new(&series, 0, 60, &[(10, 50)]).unwrap();
+
+        assert!(timeline.outages(&thresholds()).is_empty());
+        assert_eq!(timeline.uptime(), 1.0);
+    }
+}
@@ -1,25 +1,37 @@
 use crate::{
     ingestor::types::{DZDeviceLatencySamples, DZInternetLatencySamples},
     processor::{
+        memory::{WORKING_BYTES_PER_SAMPLE, batch_by_budget, memory_budget},
+        outage::{OutageThresholds, ProbeTimeline},
         stats::{
-            TelemetryStatistics, extract_device_samples_in_range,
-            extract_internet_samples_in_range, get_device_grouping_key, get_internet_grouping_key,
+            TelemetryStatistics, get_device_grouping_key, get_internet_grouping_key,
+            sample_index_range,
         },
         util::{
-            JitterStats, calculate_jitter_statistics, calculate_packet_loss_stats,
-            calculate_rtt_statistics,
+            JitterStats, PacketLossStats, SampleSeries, calculate_jitter_statistics,
+            calculate_rtt_statistics_u32,
         },
     },
 };
 use anyhow::Result;
+use rayon::prelude::*;
+use solana_sdk::pubkey::Pubkey;
 use std::collections::BTreeMap;
-use tracing::debug;
+use tracing::{debug, warn};
+
+/// Time ranges (start_us, end_us) per device during which it is not expected to report
+pub type DeviceExclusions = BTreeMap<Pubkey, Vec<(u64, u64)>>;
 
 /// Process device telemetry samples into statistics
+///
+/// Probe intervals inside `exclusions` for either end of a circuit do not count against uptime.
+/// Circuits are independent and processed in parallel; results are keyed by circuit, so they do
+/// not depend on scheduling.
 pub fn process_device_samples(
     samples: &[DZDeviceLatencySamples],
     start_us: u64,
     end_us: u64,
+    exclusions: &DeviceExclusions,
 ) -> Result<BTreeMap<String, TelemetryStatistics>> {
     let process_start = std::time::Instant::now();
 
@@ -43,12 +55,21 @@ pub fn process_device_samples(
         .increment(samples.len() as u64);
 
     // Process each group
-    let mut results = BTreeMap::new();
-
-    for (key, sample_group) in grouped_samples {
-        let stats = calculate_device_group_statistics(&sample_group, start_us, end_us)?;
-        results.insert(key, stats);
-    }
+    let results = process_groups(
+        grouped_samples,
+        |s| s.samples.len(),
+        |sample_group| {
+            let excluded: Vec<(u64, u64)> = sample_group
+                .first()
+                .into_iter()
+                .flat_map(|s| [s.origin_device_pk, s.target_device_pk])
+                .filter_map(|device_pk| exclusions.get(&device_pk))
+                .flatten()
+                .copied()
+                .collect();
+            calculate_device_group_statistics(sample_group, start_us, end_us, &excluded)
+        },
+    )?;
 
     // Track processing time
     metrics::histogram!("doublezero_contributor_rewards_telemetry_processing_duration", "type" => "device")
@@ -57,7 +78,7 @@ pub fn process_device_samples(
     Ok(results)
 }
 
-/// Process internet telemetry samples into statistics
+/// Process internet telemetry samples into statistics, one route per task
 pub fn process_internet_samples(
     samples: &[DZInternetLatencySamples],
     start_us: u64,
@@ -85,12 +106,11 @@ pub fn process_internet_samples(
         .increment(samples.len() as u64);
 
     // Process each group
-    let mut results = BTreeMap::new();
-
-    for (key, sample_group) in grouped_samples {
-        let stats = calculate_internet_group_statistics(&sample_group, start_us, end_us)?;
-        results.insert(key, stats);
-    }
+    let results = process_groups(
+        grouped_samples,
+        |s| s.samples.len(),
+        |sample_group| calculate_internet_group_statistics(sample_group, start_us, end_us),
+    )?;
 
     // Track processing time
     metrics::histogram!("doublezero_contributor_rewards_telemetry_processing_duration", "type" => "internet")
@@ -99,38 +119,84 @@ pub fn process_internet_samples(
     Ok(results)
 }
 
+/// Run `calculate` over every group in parallel
+///
+/// With a memory budget set, groups are processed in batches whose estimated working memory,
+/// from their sample counts, fits within it.
+fn process_groups<S: Sync>(
+    grouped_samples: BTreeMap<String, Vec<&S>>,
+    sample_count: impl Fn(&S) -> usize,
+    calculate: impl Fn(&[&S]) -> Result<TelemetryStatistics> + Sync,
+) -> Result<BTreeMap<String, TelemetryStatistics>> {
+    let budget = memory_budget();
+    let sized_groups = grouped_samples.into_iter().map(|(key, sample_group)| {
+        let bytes = sample_group
+            .iter()
+            .map(|s| sample_count(s) as u64)
+            .sum::<u64>()
+            .saturating_mul(WORKING_BYTES_PER_SAMPLE);
+        if let Some(budget) = budget
+            && bytes > budget
+        {
+            warn!(
+                "Circuit {key} needs an estimated {} MiB, more than the {} MiB memory budget",
+                bytes.div_ceil(1 << 20),
+                budget >> 20
+            );
+        }
+        ((key, sample_group), bytes)
+    });
+
+    let batches = batch_by_budget(sized_groups, budget);
+    if batches.len() > 1 {
+        debug!(
+            "Processing groups in {} batches to stay within the memory budget",
+            batches.len()
+        );
+    }
+
+    let mut results = BTreeMap::new();
+    for batch in batches {
+        let stats = batch
+            .into_par_iter()
+            .map(|(key, sample_group)| Ok((key, calculate(&sample_group)?)))
+            .collect::<Result<Vec<_>>>()?;
+        results.extend(stats);
+    }
+
+    Ok(results)
+}
+
 /// Calculate statistics for a group of device telemetry samples
 fn calculate_device_group_statistics(
     samples: &[&DZDeviceLatencySamples],
     start_us: u64,
     end_us: u64,
+    excluded: &[(u64, u64)],
 ) -> Result<TelemetryStatistics> {
-    let mut all_values = Vec::new();
-    let mut all_raw_samples = Vec::new();
-    let mut total_samples_in_range = 0usize;
-    let mut jitter_indices = Vec::new();
-
-    // Collect all RTT values and track indices for jitter calculation
+    let mut accumulator = CircuitAccumulator::default();
     for sample in samples {
-        let (values, start_idx, end_idx) =
-            extract_device_samples_in_range(sample, start_us, end_us);
-
-        if start_idx < end_idx {
-            all_values.extend(values);
-            total_samples_in_range += end_idx - start_idx;
-            jitter_indices.push((&sample.samples[..], start_idx, end_idx));
-
-            // Collect raw samples for packet loss calculation
-            all_raw_samples.extend(&sample.samples[start_idx..end_idx]);
-        }
+        let (start_idx, end_idx) = sample_index_range(
+            sample.start_timestamp_us,
+            sample.sampling_interval_us,
+            sample.sample_count,
+            start_us,
+            end_us,
+        );
+        accumulator.add(&sample.samples, start_idx, end_idx)?;
     }
 
-    calculate_statistics_common(
-        all_values,
-        all_raw_samples,
-        jitter_indices,
-        total_samples_in_range,
-    )
+    let series: Vec<SampleSeries> = samples
+        .iter()
+        .map(|s| SampleSeries {
+            samples: &s.samples,
+            start_timestamp_us: s.start_timestamp_us,
+            sampling_interval_us: s.sampling_interval_us,
+        })
+        .collect();
+    let timeline = ProbeTimeline::new(&series, start_us, end_us, excluded);
+
+    Ok(accumulator.finish(timeline))
 }
 
 /// Calculate statistics for a group of internet telemetry samples
@@ -139,133 +205,170 @@ fn calculate_internet_group_statistics(
     start_us: u64,
     end_us: u64,
 ) -> Result<TelemetryStatistics> {
-    let mut all_values = Vec::new();
-    let mut all_raw_samples = Vec::new();
-    let mut total_samples_in_range = 0usize;
-    let mut jitter_indices = Vec::new();
-
-    // Collect all RTT values and track indices for jitter calculation
+    let mut accumulator = CircuitAccumulator::default();
     for sample in samples {
-        let (values, start_idx, end_idx) =
-            extract_internet_samples_in_range(sample, start_us, end_us);
-
-        if start_idx < end_idx {
-            all_values.extend(values);
-            total_samples_in_range += end_idx - start_idx;
-            jitter_indices.push((&sample.samples[..], start_idx, end_idx));
-
-            // Collect raw samples for packet loss calculation
-            all_raw_samples.extend(&sample.samples[start_idx..end_idx]);
-        }
+        let (start_idx, end_idx) = sample_index_range(
+            sample.start_timestamp_us,
+            sample.sampling_interval_us,
+            sample.sample_count,
+            start_us,
+            end_us,
+        );
+        accumulator.add(&sample.samples, start_idx, end_idx)?;
     }
 
-    calculate_statistics_common(
-        all_values,
-        all_raw_samples,
-        jitter_indices,
-        total_samples_in_range,
-    )
+    let series: Vec<SampleSeries> = samples
+        .iter()
+        .map(|s| SampleSeries {
+            samples: &s.samples,
+            start_timestamp_us: s.start_timestamp_us,
+            sampling_interval_us: s.sampling_interval_us,
+        })
+        .collect();
+    let timeline = ProbeTimeline::new(&series, start_us, end_us, &[]);
+
+    Ok(accumulator.finish(timeline))
 }
 
-/// Common statistics calculation logic
-fn calculate_statistics_common(
-    all_values: Vec<f64>,
-    all_raw_samples: Vec<u32>,
-    jitter_indices: Vec<(&[u32], usize, usize)>,
+/// Running aggregate of a circuit's samples, fed one sample series at a time
+///
+/// Only the successful RTTs are kept, as integers, because percentiles and MAD need all of them.
+/// Packet loss is counted and jitter computed as each series is added, so the samples are never
+/// copied into intermediate vectors.
+#[derive(Default)]
+struct CircuitAccumulator {
+    rtt_values: Vec<u32>,
+    success_count: u64,
+    loss_count: u64,
     total_samples_in_range: usize,
-) -> Result<TelemetryStatistics> {
-    // Calculate RTT statistics
-    let rtt_stats = calculate_rtt_statistics(&all_values)?;
+    jitter: Vec<JitterStats>,
+}
 
-    // Calculate jitter statistics
-    let jitter_stats = calculate_combined_jitter(&jitter_indices)?;
+impl CircuitAccumulator {
+    /// Add the samples of one series between `start_idx` and `end_idx`
+    fn add(&mut self, samples: &[u32], start_idx: usize, end_idx: usize) -> Result<()> {
+        if start_idx >= end_idx {
+            return Ok(());
+        }
 
-    // Calculate packet loss statistics
-    let packet_loss_stats = calculate_packet_loss_stats(&all_raw_samples);
+        self.total_samples_in_range += end_idx - start_idx;
+        for &sample in &samples[start_idx..end_idx] {
+            if sample > 0 {
+                self.success_count += 1;
+                self.rtt_values.push(sample);
+            } else {
+                self.loss_count += 1;
+            }
+        }
 
-    // Calculate missing data ratio
-    // Total samples includes both successful (non-zero) and failed (zero) samples
-    let missing_data_ratio = if total_samples_in_range > 0 {
-        packet_loss_stats.loss_rate
-    } else {
-        1.0 // If no samples, consider it 100% missing
-    };
+        let jitter_stats = calculate_jitter_statistics(samples, start_idx, end_idx)?;
+        if jitter_stats.avg_jitter_us > 0.0 || jitter_stats.peak_to_peak_us > 0.0 {
+            self.jitter.push(jitter_stats);
+        }
 
-    // Build the statistics
-    Ok(TelemetryStatistics {
-        circuit: String::new(), // Will be set by specific implementations
-        circuit_metadata: Default::default(), // Will be set by specific implementations
-        // RTT metrics
-        rtt_mean_us: rtt_stats.mean_us,
-        rtt_median_us: rtt_stats.median_us,
-        rtt_min_us: rtt_stats.min_us,
-        rtt_max_us: rtt_stats.max_us,
-        rtt_p90_us: rtt_stats.p90_us,
-        rtt_p95_us: rtt_stats.p95_us,
-        rtt_p99_us: rtt_stats.p99_us,
-        rtt_stddev_us: rtt_stats.stddev_us,
-        rtt_variance_us: rtt_stats.variance_us,
-        rtt_mad_us: rtt_stats.mad_us,
-        // Jitter metrics
-        avg_jitter_us: jitter_stats.avg_jitter_us,
-        max_jitter_us: jitter_stats.max_jitter_us,
-        ewma_jitter_us: jitter_stats.ewma_jitter_us,
-        jitter_delta_stddev_us: jitter_stats.delta_stddev_us,
-        jitter_peak_to_peak_us: jitter_stats.peak_to_peak_us,
-        // Packet loss metrics
-        packet_loss: packet_loss_stats.loss_rate,
-        success_count: packet_loss_stats.success_count,
-        loss_count: packet_loss_stats.loss_count,
-        success_rate: packet_loss_stats.success_rate,
-        loss_rate: packet_loss_stats.loss_rate,
-        // Total samples
-        total_samples: total_samples_in_range,
-        // Missing data tracking
-        missing_data_ratio,
-    })
-}
+        Ok(())
+    }
 
-/// Calculate combined jitter statistics from multiple sample sets
-fn calculate_combined_jitter(jitter_indices: &[(&[u32], usize, usize)]) -> Result<JitterStats> {
-    let mut all_avg_jitters = Vec::new();
-    let mut all_max_jitters = Vec::new();
-    let mut all_ewma_jitters = Vec::new();
-    let mut all_delta_stddevs = Vec::new();
-    let mut all_peak_to_peaks = Vec::new();
-
-    for (samples, start_idx, end_idx) in jitter_indices {
-        if *end_idx > *start_idx && *start_idx < samples.len() {
-            let jitter_stats = calculate_jitter_statistics(samples, *start_idx, *end_idx)?;
-            if jitter_stats.avg_jitter_us > 0.0 || jitter_stats.peak_to_peak_us > 0.0 {
-                all_avg_jitters.push(jitter_stats.avg_jitter_us);
-                all_max_jitters.push(jitter_stats.max_jitter_us);
-                all_ewma_jitters.push(jitter_stats.ewma_jitter_us);
-                all_delta_stddevs.push(jitter_stats.delta_stddev_us);
-                all_peak_to_peaks.push(jitter_stats.peak_to_peak_us);
-            }
-        }
+    fn finish(self, timeline: Option<ProbeTimeline>) -> Result<TelemetryStatistics> {
+        // Uptime and outages come from the same probe timeline
+        let uptime = timeline.as_ref().map_or(0.0, ProbeTimeline::uptime);
+        let outages = timeline
+            .map(|timeline| timeline.outages(&OutageThresholds::default()))
+            .unwrap_or_default();
+
+        // Calculate RTT statistics
+        let rtt_stats = calculate_rtt_statistics_u32(self.rtt_values)?;
+
+        // Calculate jitter statistics
+        let jitter_stats = combine_jitter_stats(&self.jitter);
+
+        // Calculate packet loss statistics
+        let packet_loss_stats = PacketLossStats::from_counts(self.success_count, self.loss_count);
+
+        // Calculate missing data ratio
+        // Total samples includes both successful (non-zero) and failed (zero) samples
+        let missing_data_ratio = if self.total_samples_in_range > 0 {
+            packet_loss_stats.loss_rate
+        } else {
+            1.0 // If no samples, consider it 100% missing
+        };
+
+        // Build the statistics
+        Ok(TelemetryStatistics {
+            circuit: String::new(), // Will be set by specific implementations
+            circuit_metadata: Default::default(), // Will be set by specific implementations
+            // RTT metrics
+            rtt_mean_us: rtt_stats.mean_us,
+            rtt_median_us: rtt_stats.median_us,
+            rtt_min_us: rtt_stats.min_us,
+            rtt_max_us: rtt_stats.max_us,
+            rtt_p90_us: rtt_stats.p90_us,
+            rtt_p95_us: rtt_stats.p95_us,
+            rtt_p99_us: rtt_stats.p99_us,
+            rtt_stddev_us: rtt_stats.stddev_us,
+            rtt_variance_us: rtt_stats.variance_us,
+            rtt_mad_us: rtt_stats.mad_us,
+            // Jitter metrics
+            avg_jitter_us: jitter_stats.avg_jitter_us,
+            max_jitter_us: jitter_stats.max_jitter_us,
+            ewma_jitter_us: jitter_stats.ewma_jitter_us,
+            rfc3550_jitter_us: jitter_stats.rfc3550_jitter_us,
+            jitter_delta_stddev_us: jitter_stats.delta_stddev_us,
+            jitter_peak_to_peak_us: jitter_stats.peak_to_peak_us,
+            // Packet loss metrics
+            packet_loss: packet_loss_stats.loss_rate,
+            success_count: packet_loss_stats.success_count,
+            loss_count: packet_loss_stats.loss_count,
+            success_rate: packet_loss_stats.success_rate,
+            loss_rate: packet_loss_stats.loss_rate,
+            // Total samples
+            total_samples: self.total_samples_in_range,
+            // Missing data tracking
+            missing_data_ratio,
+            uptime,
+            outages,
+        })
     }
+}
 
-    if all_avg_jitters.is_empty() {
-        return Ok(JitterStats::new_dead());
+/// Combine the jitter statistics of a circuit's sample sets
+///
+/// RFC 3550 jitter and delta standard deviation are weighted by each set's delta count,
+/// so a short bursty set is not diluted by being averaged against long quiet ones.
+fn combine_jitter_stats(all_stats: &[JitterStats]) -> JitterStats {
+    if all_stats.is_empty() {
+        return JitterStats::new_dead();
     }
 
     // Calculate overall jitter statistics
-    let avg_jitter = all_avg_jitters.iter().sum::<f64>() / all_avg_jitters.len() as f64;
-    let max_jitter = all_max_jitters
+    let n = all_stats.len() as f64;
+    let avg_jitter = all_stats.iter().map(|s| s.avg_jitter_us).sum::<f64>() / n;
+    let max_jitter = all_stats
         .iter()
-        .fold(0.0f64, |max, &val| val.max(max));
-    let ewma_jitter = all_ewma_jitters.iter().sum::<f64>() / all_ewma_jitters.len() as f64;
-    let delta_stddev = all_delta_stddevs.iter().sum::<f64>() / all_delta_stddevs.len() as f64;
-    let max_peak_to_peak = all_peak_to_peaks
+        .fold(0.0f64, |max, s| s.max_jitter_us.max(max));
+    let ewma_jitter = all_stats.iter().map(|s| s.ewma_jitter_us).sum::<f64>() / n;
+    let max_peak_to_peak = all_stats
         .iter()
-        .fold(0.0f64, |max, &val| val.max(max));
+        .fold(0.0f64, |max, s| s.peak_to_peak_us.max(max));
+
+    let delta_count: usize = all_stats.iter().map(|s| s.delta_count).sum();
+    let weighted = |f: fn(&JitterStats) -> f64| {
+        all_stats
+            .iter()
+            .map(|s| f(s) * s.delta_count as f64)
+            .sum::<f64>()
+            / delta_count as f64
+    };
+    let rfc3550_jitter = weighted(|s| s.rfc3550_jitter_us);
+    let delta_stddev = weighted(|s| s.delta_stddev_us.powi(2)).sqrt();
 
-    Ok(JitterStats {
+    JitterStats {
         avg_jitter_us: avg_jitter,
         max_jitter_us: max_jitter,
         ewma_jitter_us: ewma_jitter,
+        rfc3550_jitter_us: rfc3550_jitter,
         delta_stddev_us: delta_stddev,
         peak_to_peak_us: max_peak_to_peak,
-    })
+        delta_count,
+    }
 }
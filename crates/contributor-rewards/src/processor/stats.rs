@@ -1,4 +1,7 @@
-use crate::ingestor::types::{DZDeviceLatencySamples, DZInternetLatencySamples};
+use crate::{
+    ingestor::types::{DZDeviceLatencySamples, DZInternetLatencySamples},
+    processor::outage::Outage,
+};
 
 /// Common statistics structure for telemetry data
 #[derive(Debug, Clone, Default)]
@@ -20,6 +23,7 @@ pub struct TelemetryStatistics {
     pub avg_jitter_us: f64,
     pub max_jitter_us: f64,
     pub ewma_jitter_us: f64,
+    pub rfc3550_jitter_us: f64,
     pub jitter_delta_stddev_us: f64,
     pub jitter_peak_to_peak_us: f64,
     // Packet loss metrics
@@ -32,6 +36,10 @@ pub struct TelemetryStatistics {
     pub total_samples: usize,
     // Missing data tracking
     pub missing_data_ratio: f64,
+    // Fraction of expected probe intervals with a successful sample
+    pub uptime: f64,
+    // Degraded and down intervals, in time order
+    pub outages: Vec<Outage>,
 }
 
 /// Metadata about a circuit/route
@@ -83,19 +91,13 @@ fn extract_samples_common(
     start_us: u64,
     end_us: u64,
 ) -> (Vec<f64>, usize, usize) {
-    // Calculate sample indices that fall within the time range
-    let start_idx = if start_us > start_timestamp_us {
-        ((start_us - start_timestamp_us) / sampling_interval_us) as usize
-    } else {
-        0
-    };
-
-    let end_timestamp_us = start_timestamp_us + (sample_count as u64 * sampling_interval_us);
-    let end_idx = if end_us < end_timestamp_us {
-        ((end_us - start_timestamp_us) / sampling_interval_us) as usize
-    } else {
-        sample_count as usize
-    };
+    let (start_idx, end_idx) = sample_index_range(
+        start_timestamp_us,
+        sampling_interval_us,
+        sample_count,
+        start_us,
+        end_us,
+    );
 
     // Extract samples within range, filtering out failed samples (zeros)
     let mut values = Vec::new();
@@ -112,6 +114,31 @@ fn extract_samples_common(
     (values, start_idx, end_idx)
 }
 
+/// Indices `(start_idx, end_idx)` of the samples of a series that fall within a time range
+pub fn sample_index_range(
+    start_timestamp_us: u64,
+    sampling_interval_us: u64,
+    sample_count: u32,
+    start_us: u64,
+    end_us: u64,
+) -> (usize, usize) {
+    // Calculate sample indices that fall within the time range
+    let start_idx = if start_us > start_timestamp_us {
+        ((start_us - start_timestamp_us) / sampling_interval_us) as usize
+    } else {
+        0
+    };
+
+    let end_timestamp_us = start_timestamp_us + (sample_count as u64 * sampling_interval_us);
+    let end_idx = if end_us < end_timestamp_us {
+        ((end_us - start_timestamp_us) / sampling_interval_us) as usize
+    } else {
+        sample_count as usize
+    };
+
+    (start_idx, end_idx)
+}
+
 /// Get grouping key for device telemetry samples
 pub fn get_device_grouping_key(sample: &DZDeviceLatencySamples) -> String {
     format!(
@@ -1,6 +1,11 @@
 use crate::{
     ingestor::types::FetchData,
-    processor::{process::process_device_samples, util::display_us_as_ms},
+    processor::{
+        outage::LinkOutage,
+        process::{DeviceExclusions, process_device_samples},
+        util::display_us_as_ms,
+    },
+    settings::MaintenanceWindow,
 };
 use anyhow::Result;
 use borsh::{BorshDeserialize, BorshSerialize};
@@ -57,6 +62,10 @@ pub struct DZDTelemetryStats {
     pub jitter_ewma_us: f64,
     #[tabled(display = "display_us_as_ms", rename = "max_jitter(ms)")]
     pub max_jitter_us: f64,
+    #[tabled(display = "display_us_as_ms", rename = "rfc3550_jitter(ms)")]
+    pub rfc3550_jitter_us: f64,
+    #[tabled(display = "display_us_as_ms", rename = "jitter_stddev(ms)")]
+    pub jitter_stddev_us: f64,
     #[tabled(rename = "loss_rate")]
     pub packet_loss: f64,
     #[tabled(rename = "loss_count")]
@@ -67,6 +76,8 @@ pub struct DZDTelemetryStats {
     pub total_samples: usize,
     #[tabled(skip)]
     pub missing_data_ratio: f64,
+    #[tabled(rename = "uptime")]
+    pub uptime: f64,
 }
 
 pub struct DZDTelemetryProcessor;
@@ -81,6 +92,22 @@ pub fn print_telemetry_stats(map: &DZDTelemetryStatMap) -> String {
 
 impl DZDTelemetryProcessor {
     pub fn process(fetch_data: &FetchData) -> Result<DZDTelemetryStatMap> {
+        Self::process_with_maintenance(fetch_data, &[])
+    }
+
+    /// Process device telemetry, excluding maintenance windows from device uptime
+    pub fn process_with_maintenance(
+        fetch_data: &FetchData,
+        maintenance_windows: &[MaintenanceWindow],
+    ) -> Result<DZDTelemetryStatMap> {
+        Self::process_with_outages(fetch_data, maintenance_windows).map(|(stats, _)| stats)
+    }
+
+    /// Process device telemetry, also returning each circuit's degraded and down intervals
+    pub fn process_with_outages(
+        fetch_data: &FetchData,
+        maintenance_windows: &[MaintenanceWindow],
+    ) -> Result<(DZDTelemetryStatMap, Vec<LinkOutage>)> {
         // Build device pubkey to code mapping
         let device_pk_to_code: BTreeMap<Pubkey, String> = fetch_data
             .dz_serviceability
@@ -91,11 +118,30 @@ impl DZDTelemetryProcessor {
 
         let links = &fetch_data.dz_serviceability.links;
 
+        // Resolve maintenance windows by device pubkey or code
+        let mut exclusions = DeviceExclusions::new();
+        for window in maintenance_windows {
+            let device_pk = window.device.parse::<Pubkey>().ok().or_else(|| {
+                device_pk_to_code
+                    .iter()
+                    .find(|(_, code)| **code == window.device)
+                    .map(|(pk, _)| *pk)
+            });
+            match device_pk {
+                Some(device_pk) => exclusions
+                    .entry(device_pk)
+                    .or_default()
+                    .push((window.start_us, window.end_us)),
+                None => debug!("Maintenance window for unknown device {}", window.device),
+            }
+        }
+
         // Process device telemetry samples
         let generic_stats = process_device_samples(
             &fetch_data.dz_telemetry.device_latency_samples,
             fetch_data.start_us,
             fetch_data.end_us,
+            &exclusions,
         )?;
 
         debug!(
@@ -105,6 +151,7 @@ impl DZDTelemetryProcessor {
 
         // Convert from generic TelemetryStatistics to DZDTelemetryStats
         let mut result = DZDTelemetryStatMap::new();
+        let mut outages = Vec::new();
 
         for (circuit_key, stats) in generic_stats {
             // Parse circuit key to extract pubkeys
@@ -150,17 +197,26 @@ impl DZDTelemetryProcessor {
                     avg_jitter_us: stats.avg_jitter_us,
                     jitter_ewma_us: stats.ewma_jitter_us,
                     max_jitter_us: stats.max_jitter_us,
+                    rfc3550_jitter_us: stats.rfc3550_jitter_us,
+                    jitter_stddev_us: stats.jitter_delta_stddev_us,
                     packet_loss: stats.packet_loss,
                     loss_count: stats.loss_count,
                     success_count: stats.success_count,
                     total_samples: stats.total_samples,
                     missing_data_ratio: stats.missing_data_ratio,
+                    uptime: stats.uptime,
                 };
 
+                outages.extend(
+                    stats
+                        .outages
+                        .iter()
+                        .map(|outage| LinkOutage::new(&dz_stats.circuit, outage)),
+                );
                 result.insert(circuit_key, dz_stats);
             }
         }
 
-        Ok(result)
+        Ok((result, outages))
     }
 }
@@ -0,0 +1,294 @@
+//! Network topology of an epoch as a graph
+//!
+//! Exchanges and devices are nodes; links between devices, internet circuits between exchanges
+//! and the exchange each device sits in are edges. Link and circuit edges carry the epoch's
+//! latency and uptime aggregates when telemetry was recorded for them, so the graph shows the
+//! network as the reward calculation saw it.
+
+use crate::{
+    ingestor::types::DZServiceabilityData,
+    processor::{internet::InternetTelemetryStatMap, telemetry::DZDTelemetryStatMap},
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeKind {
+    Exchange,
+    Device,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeKind {
+    /// Device to the exchange it is in
+    LocatedIn,
+    /// Private link between two devices
+    Link,
+    /// Public internet circuit between two exchanges, per data provider
+    Internet,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TopologyNode {
+    /// Account pubkey
+    pub id: String,
+    pub kind: NodeKind,
+    pub code: String,
+    /// Account status, unset for exchanges
+    pub status: Option<String>,
+}
+
+/// Epoch aggregates of a link or circuit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EdgeStats {
+    pub rtt_mean_us: f64,
+    pub rtt_p95_us: f64,
+    pub packet_loss: f64,
+    /// Only tracked for private links
+    pub uptime: Option<f64>,
+    pub samples: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TopologyEdge {
+    pub kind: EdgeKind,
+    pub from: String,
+    pub to: String,
+    /// Link code or data provider name
+    pub label: Option<String>,
+    pub status: Option<String>,
+    /// Declared link bandwidth in bits/sec
+    pub bandwidth_bps: Option<u64>,
+    pub stats: Option<EdgeStats>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TopologyGraph {
+    pub epoch: u64,
+    pub nodes: Vec<TopologyNode>,
+    pub edges: Vec<TopologyEdge>,
+}
+
+impl TopologyGraph {
+    pub fn build(
+        epoch: u64,
+        serviceability: &DZServiceabilityData,
+        device_stats: &DZDTelemetryStatMap,
+        internet_stats: &InternetTelemetryStatMap,
+    ) -> Self {
+        let mut nodes = Vec::new();
+        let mut edges = Vec::new();
+
+        for (pubkey, exchange) in &serviceability.exchanges {
+            nodes.push(TopologyNode {
+                id: pubkey.to_string(),
+                kind: NodeKind::Exchange,
+                code: exchange.code.clone(),
+                status: None,
+            });
+        }
+
+        for (pubkey, device) in &serviceability.devices {
+            nodes.push(TopologyNode {
+                id: pubkey.to_string(),
+                kind: NodeKind::Device,
+                code: device.code.clone(),
+                status: Some(format!("{:?}", device.status)),
+            });
+            if serviceability.exchanges.contains_key(&device.exchange_pk) {
+                edges.push(TopologyEdge {
+                    kind: EdgeKind::LocatedIn,
+                    from: pubkey.to_string(),
+                    to: device.exchange_pk.to_string(),
+                    label: None,
+                    status: None,
+                    bandwidth_bps: None,
+                    stats: None,
+                });
+            }
+        }
+
+        for (link_pk, link) in &serviceability.links {
+            // Telemetry is directional, so take whichever direction was measured
+            let stats = device_stats
+                .get(&format!("{}:{}:{link_pk}", link.side_a_pk, link.side_z_pk))
+                .or_else(|| {
+                    device_stats.get(&format!("{}:{}:{link_pk}", link.side_z_pk, link.side_a_pk))
+                });
+
+            edges.push(TopologyEdge {
+                kind: EdgeKind::Link,
+                from: link.side_a_pk.to_string(),
+                to: link.side_z_pk.to_string(),
+                label: Some(link.code.clone()),
+                status: Some(format!("{:?}", link.status)),
+                bandwidth_bps: Some(link.bandwidth),
+                stats: stats.map(|stats| EdgeStats {
+                    rtt_mean_us: stats.rtt_mean_us,
+                    rtt_p95_us: stats.rtt_p95_us,
+                    packet_loss: stats.packet_loss,
+                    uptime: Some(stats.uptime),
+                    samples: stats.total_samples,
+                }),
+            });
+        }
+
+        for stats in internet_stats.values() {
+            edges.push(TopologyEdge {
+                kind: EdgeKind::Internet,
+                from: stats.origin_exchange_pk.to_string(),
+                to: stats.target_exchange_pk.to_string(),
+                label: Some(stats.data_provider_name.clone()),
+                status: None,
+                bandwidth_bps: None,
+                stats: Some(EdgeStats {
+                    rtt_mean_us: stats.rtt_mean_us,
+                    rtt_p95_us: stats.rtt_p95_us,
+                    packet_loss: stats.packet_loss,
+                    uptime: None,
+                    samples: stats.total_samples,
+                }),
+            });
+        }
+
+        Self {
+            epoch,
+            nodes,
+            edges,
+        }
+    }
+
+    /// Render as a Graphviz DOT digraph
+    ///
+    /// Exchanges are boxes and devices ellipses; links are solid, internet circuits dashed and
+    /// device locations dotted.
+    pub fn to_dot(&self) -> String {
+        let mut dot = format!("digraph \"epoch_{}\" {{\n", self.epoch);
+        dot.push_str("    rankdir=LR;\n");
+
+        for node in &self.nodes {
+            let shape = match node.kind {
+                NodeKind::Exchange => "box",
+                NodeKind::Device => "ellipse",
+            };
+            let mut label = node.code.clone();
+            if let Some(status) = &node.status {
+                let _ = write!(label, "\n{status}");
+            }
+            let _ = writeln!(
+                dot,
+                "    \"{}\" [label=\"{}\", shape={shape}];",
+                node.id,
+                escape(&label)
+            );
+        }
+
+        for edge in &self.edges {
+            let attributes = match edge.kind {
+                EdgeKind::LocatedIn => "style=dotted, arrowhead=none",
+                EdgeKind::Link => "style=solid, dir=both",
+                EdgeKind::Internet => "style=dashed, color=gray",
+            };
+            let label = edge_label(edge);
+            let _ = writeln!(
+                dot,
+                "    \"{}\" -> \"{}\" [label=\"{}\", {attributes}];",
+                edge.from,
+                edge.to,
+                escape(&label)
+            );
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn edge_label(edge: &TopologyEdge) -> String {
+    let mut lines: Vec<String> = edge.label.iter().cloned().collect();
+    if let Some(status) = &edge.status {
+        lines.push(status.clone());
+    }
+    if let Some(stats) = &edge.stats {
+        lines.push(format!(
+            "p95 {:.2}ms, loss {:.2}%",
+            stats.rtt_p95_us / 1000.0,
+            stats.packet_loss * 100.0
+        ));
+        if let Some(uptime) = stats.uptime {
+            lines.push(format!("uptime {:.2}%", uptime * 100.0));
+        }
+    }
+    lines.join("\n")
+}
+
+fn escape(label: &str) -> String {
+    label
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(id: &str, kind: NodeKind, code: &str) -> TopologyNode {
+        TopologyNode {
+            id: id.to_string(),
+            kind,
+            code: code.to_string(),
+            status: None,
+        }
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let graph = TopologyGraph {
+            epoch: 42,
+            nodes: vec![
+                node("ex1", NodeKind::Exchange, "xams"),
+                node("dev1", NodeKind::Device, "ams\"01"),
+                node("dev2", NodeKind::Device, "fra01"),
+            ],
+            edges: vec![
+                TopologyEdge {
+                    kind: EdgeKind::LocatedIn,
+                    from: "dev1".to_string(),
+                    to: "ex1".to_string(),
+                    label: None,
+                    status: None,
+                    bandwidth_bps: None,
+                    stats: None,
+                },
+                TopologyEdge {
+                    kind: EdgeKind::Link,
+                    from: "dev1".to_string(),
+                    to: "dev2".to_string(),
+                    label: Some("ams01:fra01".to_string()),
+                    status: Some("Activated".to_string()),
+                    bandwidth_bps: Some(10_000_000_000),
+                    stats: Some(EdgeStats {
+                        rtt_mean_us: 5_000.0,
+                        rtt_p95_us: 6_500.0,
+                        packet_loss: 0.001,
+                        uptime: Some(0.999),
+                        samples: 100,
+                    }),
+                },
+            ],
+        };
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph \"epoch_42\" {\n"));
+        assert!(dot.contains("\"ex1\" [label=\"xams\", shape=box];"));
+        assert!(dot.contains("\"dev1\" [label=\"ams\\\"01\", shape=ellipse];"));
+        assert!(dot.contains("\"dev1\" -> \"ex1\" [label=\"\", style=dotted, arrowhead=none];"));
+        assert!(dot.contains(
+            "\"dev1\" -> \"dev2\" [label=\"ams01:fra01\\nActivated\\np95 6.50ms, loss 0.10%\\nuptime 99.90%\", style=solid, dir=both];"
+        ));
+        assert!(dot.ends_with("}\n"));
+    }
+}
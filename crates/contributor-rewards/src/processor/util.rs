@@ -1,4 +1,10 @@
-use crate::processor::constants::{PENALTY_JITTER_US, PENALTY_RTT_US};
+use crate::{
+    processor::{
+        constants::{PENALTY_JITTER_US, PENALTY_RTT_US, Z_SCORE_95},
+        outage::ProbeTimeline,
+    },
+    units::Micros,
+};
 use anyhow::{Result, ensure};
 use std::cmp::Ordering;
 
@@ -39,8 +45,12 @@ pub struct JitterStats {
     pub avg_jitter_us: f64,
     pub max_jitter_us: f64,
     pub ewma_jitter_us: f64,
+    /// RFC 3550 interarrival jitter: J += (|D| - J) / 16, starting from J = 0
+    pub rfc3550_jitter_us: f64,
     pub delta_stddev_us: f64,
     pub peak_to_peak_us: f64,
+    /// Number of consecutive-sample deltas the stats were computed from
+    pub delta_count: usize,
 }
 
 impl JitterStats {
@@ -49,8 +59,10 @@ impl JitterStats {
             avg_jitter_us: PENALTY_JITTER_US,
             max_jitter_us: PENALTY_JITTER_US,
             ewma_jitter_us: PENALTY_JITTER_US,
+            rfc3550_jitter_us: PENALTY_JITTER_US,
             delta_stddev_us: PENALTY_JITTER_US,
             peak_to_peak_us: PENALTY_JITTER_US,
+            delta_count: 0,
         }
     }
 }
@@ -64,7 +76,22 @@ pub struct PacketLossStats {
 }
 
 pub fn display_us_as_ms(us: &f64) -> String {
-    format!("{}", us / 1000.0)
+    Micros(*us).as_millis().to_string()
+}
+
+/// 95% confidence interval for a mean computed from `count` samples with population
+/// standard deviation `stddev`, using the normal approximation
+///
+/// With fewer than two samples there is no spread to estimate, so the interval collapses
+/// to the mean; callers should treat such aggregates as low confidence.
+pub fn mean_confidence_interval(mean: f64, stddev: f64, count: u64) -> (f64, f64) {
+    if count < 2 {
+        return (mean, mean);
+    }
+
+    // Bessel-corrected standard error: s / sqrt(n) == stddev / sqrt(n - 1)
+    let half_width = Z_SCORE_95 * stddev / ((count - 1) as f64).sqrt();
+    (mean - half_width, mean + half_width)
 }
 
 pub fn calculate_rtt_statistics(values: &[f64]) -> Result<RttStats> {
@@ -139,6 +166,84 @@ pub fn calculate_rtt_statistics(values: &[f64]) -> Result<RttStats> {
     })
 }
 
+/// [`calculate_rtt_statistics`] over integer RTTs, sorting them in place rather than copying
+/// them as floats
+///
+/// Produces exactly the same statistics: every intermediate value is computed in the same order
+/// from the same inputs, and MAD deviations are taken at twice their size so they stay integers.
+pub fn calculate_rtt_statistics_u32(mut values: Vec<u32>) -> Result<RttStats> {
+    if values.is_empty() {
+        return Ok(RttStats::new_dead());
+    }
+
+    values.sort_unstable();
+
+    let len = values.len();
+    let n = len as f64;
+
+    // Basic statistics
+    let min = values[0] as f64;
+    let max = values[len - 1] as f64;
+
+    // Calculate median, and twice the median as an integer for the deviations
+    let (median, twice_median) = if len.is_multiple_of(2) {
+        let (lo, hi) = (values[len / 2 - 1], values[len / 2]);
+        ((lo as f64 + hi as f64) / 2.0, lo as u64 + hi as u64)
+    } else {
+        (values[len / 2] as f64, 2 * values[len / 2] as u64)
+    };
+
+    // Calculate mean and variance using Welford's algorithm (population)
+    let mut mean = 0.0;
+    let mut m2 = 0.0;
+    for (i, &value) in values.iter().enumerate() {
+        let value = value as f64;
+        let delta = value - mean;
+        mean += delta / (i + 1) as f64;
+        m2 += delta * (value - mean);
+    }
+    let variance = m2 / n;
+    let stddev = variance.sqrt();
+
+    // Calculate percentiles
+    let p90_index = ((n * 0.90).ceil() - 1.0).max(0.0) as usize;
+    let p95_index = ((n * 0.95).ceil() - 1.0).max(0.0) as usize;
+    let p99_index = ((n * 0.99).ceil() - 1.0).max(0.0) as usize;
+
+    let percentile = |index: usize| values.get(index).map_or(mean, |&v| v as f64);
+    let p90 = percentile(p90_index);
+    let p95 = percentile(p95_index);
+    let p99 = percentile(p99_index);
+
+    // Calculate MAD (Median Absolute Deviation) from doubled deviations
+    let mut deviations: Vec<u64> = values
+        .iter()
+        .map(|&v| (2 * v as u64).abs_diff(twice_median))
+        .collect();
+    drop(values);
+    deviations.sort_unstable();
+
+    let deviation = |index: usize| deviations[index] as f64 / 2.0;
+    let mad = if deviations.len().is_multiple_of(2) {
+        (deviation(deviations.len() / 2 - 1) + deviation(deviations.len() / 2)) / 2.0
+    } else {
+        deviation(deviations.len() / 2)
+    };
+
+    Ok(RttStats {
+        mean_us: mean,
+        median_us: median,
+        min_us: min,
+        max_us: max,
+        p90_us: p90,
+        p95_us: p95,
+        p99_us: p99,
+        stddev_us: stddev,
+        variance_us: variance,
+        mad_us: mad,
+    })
+}
+
 pub fn calculate_jitter_statistics(
     samples: &[u32],
     start_idx: usize,
@@ -175,6 +280,7 @@ pub fn calculate_jitter_statistics(
     let first_delta = ordered[1] - ordered[0];
     let first_abs = first_delta.abs();
     let mut ewma = first_abs;
+    let mut rfc3550 = first_abs / 16.0;
     let mut max_abs = first_abs;
     let mut min_abs = first_abs;
 
@@ -191,6 +297,7 @@ pub fn calculate_jitter_statistics(
 
         // EWMA update with α = 1/16 (matching Go implementation)
         ewma += (abs_delta - ewma) / 16.0;
+        rfc3550 += (abs_delta - rfc3550) / 16.0;
 
         if abs_delta > max_abs {
             max_abs = abs_delta;
@@ -223,11 +330,35 @@ pub fn calculate_jitter_statistics(
         avg_jitter_us: avg,
         max_jitter_us: max_abs,
         ewma_jitter_us: ewma,
+        rfc3550_jitter_us: rfc3550,
         delta_stddev_us: delta_stddev,
         peak_to_peak_us: peak_to_peak,
+        delta_count: signed_deltas.len(),
     })
 }
 
+/// A run of probe results starting at `start_timestamp_us`, one every `sampling_interval_us`
+pub struct SampleSeries<'a> {
+    pub samples: &'a [u32],
+    pub start_timestamp_us: u64,
+    pub sampling_interval_us: u64,
+}
+
+/// Fraction of expected probe intervals in `[start_us, end_us)` that produced a successful
+/// sample
+///
+/// Gaps count against uptime whether the probe failed (zero sample) or no sample was written
+/// at all. Intervals inside `excluded` time ranges (e.g. maintenance windows) are neither
+/// expected nor counted. A window that is entirely excluded reports full uptime.
+pub fn calculate_uptime(
+    series: &[SampleSeries<'_>],
+    start_us: u64,
+    end_us: u64,
+    excluded: &[(u64, u64)],
+) -> f64 {
+    ProbeTimeline::new(series, start_us, end_us, excluded).map_or(0.0, |timeline| timeline.uptime())
+}
+
 pub fn calculate_packet_loss(total_expected: usize, total_actual: usize) -> Result<f64> {
     ensure!(
         total_actual <= total_expected,
@@ -254,21 +385,28 @@ pub fn calculate_packet_loss_stats(samples: &[u32]) -> PacketLossStats {
         }
     }
 
-    let total = success_count + loss_count;
-    let (success_rate, loss_rate) = if total > 0 {
-        (
-            success_count as f64 / total as f64,
-            loss_count as f64 / total as f64,
-        )
-    } else {
-        (0.0, 0.0)
-    };
+    PacketLossStats::from_counts(success_count, loss_count)
+}
 
-    PacketLossStats {
-        success_count,
-        loss_count,
-        success_rate,
-        loss_rate,
+impl PacketLossStats {
+    /// Rates for `success_count` successful (non-zero) and `loss_count` failed samples
+    pub fn from_counts(success_count: u64, loss_count: u64) -> Self {
+        let total = success_count + loss_count;
+        let (success_rate, loss_rate) = if total > 0 {
+            (
+                success_count as f64 / total as f64,
+                loss_count as f64 / total as f64,
+            )
+        } else {
+            (0.0, 0.0)
+        };
+
+        Self {
+            success_count,
+            loss_count,
+            success_rate,
+            loss_rate,
+        }
     }
 }
 
@@ -276,6 +414,19 @@ pub fn calculate_packet_loss_stats(samples: &[u32]) -> PacketLossStats {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_mean_confidence_interval() {
+        let (low, high) = mean_confidence_interval(10_000.0, 300.0, 10);
+        assert!((low - 9_804.0).abs() < 1e-9);
+        assert!((high - 10_196.0).abs() < 1e-9);
+
+        // Wider with fewer samples
+        let (low_small, high_small) = mean_confidence_interval(10_000.0, 300.0, 4);
+        assert!(high_small - low_small > high - low);
+
+        assert_eq!(mean_confidence_interval(500.0, 0.0, 1), (500.0, 500.0));
+    }
+
     #[test]
     fn test_rtt_statistics() {
         let values = vec![100.0, 200.0, 300.0, 400.0, 500.0];
@@ -309,6 +460,30 @@ mod tests {
         assert_eq!(stats.mad_us, 0.0); // No variation in dead link
     }
 
+    #[test]
+    fn test_rtt_statistics_u32_matches_f64() {
+        // Odd and even lengths, duplicates, and medians that fall between two samples
+        let cases: Vec<Vec<u32>> = vec![
+            vec![1],
+            vec![7, 3],
+            vec![500, 100, 400, 200, 300],
+            vec![1201, 987, 1500, 987, 2004, 13, 650, 988],
+            (1..=1000u32).map(|i| (i * 7919) % 4093 + 1).collect(),
+        ];
+
+        for values in cases {
+            let floats: Vec<f64> = values.iter().map(|&v| v as f64).collect();
+            assert_eq!(
+                calculate_rtt_statistics_u32(values).unwrap(),
+                calculate_rtt_statistics(&floats).unwrap()
+            );
+        }
+        assert_eq!(
+            calculate_rtt_statistics_u32(Vec::new()).unwrap(),
+            RttStats::new_dead()
+        );
+    }
+
     #[test]
     fn test_jitter_statistics() {
         let samples = vec![100, 150, 140, 180, 170];
@@ -331,6 +506,21 @@ mod tests {
         assert!((stats.ewma_jitter_us - ewma).abs() < 0.001);
     }
 
+    #[test]
+    fn test_rfc3550_jitter() {
+        let samples = vec![100, 150, 140, 180, 170];
+        let stats = calculate_jitter_statistics(&samples, 0, 5).unwrap();
+
+        // RFC 3550 starts from J = 0, so a single burst is damped rather than taken whole
+        let mut jitter = 0.0;
+        for delta in [50.0, 10.0, 40.0, 10.0] {
+            jitter += (delta - jitter) / 16.0;
+        }
+        assert!((stats.rfc3550_jitter_us - jitter).abs() < 0.001);
+        assert!(stats.rfc3550_jitter_us < stats.ewma_jitter_us);
+        assert_eq!(stats.delta_count, 4);
+    }
+
     #[test]
     fn test_ipdv_with_packet_loss() {
         // Test with some zero values (packet loss)
@@ -365,6 +555,27 @@ mod tests {
         assert_eq!(stats.ewma_jitter_us, 20.0); // Only one delta, so EWMA = delta
     }
 
+    #[test]
+    fn test_uptime_from_gaps() {
+        // 10 expected probes, one failed and the last two never written
+        let samples = vec![100, 110, 0, 120, 100, 105, 100, 110];
+        let series = [SampleSeries {
+            samples: &samples,
+            start_timestamp_us: 1_000,
+            sampling_interval_us: 100,
+        }];
+
+        let uptime = calculate_uptime(&series, 1_000, 2_000, &[]);
+        assert!((uptime - 0.7).abs() < 0.001);
+
+        // Excluding the unreported tail as maintenance leaves 7 of 8 expected probes
+        let uptime = calculate_uptime(&series, 1_000, 2_000, &[(1_800, 2_000)]);
+        assert!((uptime - 0.875).abs() < 0.001);
+
+        assert_eq!(calculate_uptime(&series, 1_000, 2_000, &[(0, 5_000)]), 1.0);
+        assert_eq!(calculate_uptime(&[], 1_000, 2_000, &[]), 0.0);
+    }
+
     #[test]
     fn test_packet_loss() {
         assert_eq!(calculate_packet_loss(100, 95).unwrap(), 0.05);
@@ -0,0 +1,121 @@
+//! Per-run identifier
+//!
+//! Every process run gets a random version 4 UUID when its orchestrator starts. It is attached to
+//! the root tracing span and stored in the provenance record, snapshots and dry-run manifests, so
+//! a record on the ledger can be traced back to the run and log stream that produced it.
+
+use anyhow::{Result, bail};
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    collections::hash_map::RandomState,
+    fmt,
+    hash::{BuildHasher, Hasher},
+    process,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, BorshSerialize, BorshDeserialize)]
+pub struct RunId([u8; 16]);
+
+impl RunId {
+    /// Generate a new random (version 4) run id
+    pub fn generate() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos());
+
+        // Each RandomState is keyed from OS randomness
+        let mut bytes = [0u8; 16];
+        for (i, chunk) in bytes.chunks_mut(8).enumerate() {
+            let mut hasher = RandomState::new().build_hasher();
+            hasher.write_u128(nanos);
+            hasher.write_u32(process::id());
+            hasher.write_usize(i);
+            chunk.copy_from_slice(&hasher.finish().to_le_bytes());
+        }
+        bytes[6] = (bytes[6] & 0x0f) | 0x40;
+        bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+        Self(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+}
+
+impl fmt::Display for RunId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, byte) in self.0.iter().enumerate() {
+            if matches!(i, 4 | 6 | 8 | 10) {
+                f.write_str("-")?;
+            }
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for RunId {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let hex: String = s.chars().filter(|c| *c != '-').collect();
+        if hex.len() != 32 || !hex.is_ascii() {
+            bail!("Invalid run id: {s}");
+        }
+
+        let mut bytes = [0u8; 16];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)
+                .map_err(|_| anyhow::anyhow!("Invalid run id: {s}"))?;
+        }
+        Ok(Self(bytes))
+    }
+}
+
+impl Serialize for RunId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for RunId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate() {
+        let run_id = RunId::generate();
+        assert_eq!(run_id.as_bytes()[6] >> 4, 4);
+        assert_eq!(run_id.as_bytes()[8] >> 6, 0b10);
+        assert_ne!(run_id, RunId::generate());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let run_id: RunId = "67e55044-10b1-426f-9247-bb680e5fe0c8".parse().unwrap();
+        assert_eq!(run_id.to_string(), "67e55044-10b1-426f-9247-bb680e5fe0c8");
+
+        let json = serde_json::to_string(&run_id).unwrap();
+        assert_eq!(json, "\"67e55044-10b1-426f-9247-bb680e5fe0c8\"");
+        assert_eq!(serde_json::from_str::<RunId>(&json).unwrap(), run_id);
+
+        assert!("67e55044-10b1".parse::<RunId>().is_err());
+        assert!(
+            "zze55044-10b1-426f-9247-bb680e5fe0c8"
+                .parse::<RunId>()
+                .is_err()
+        );
+    }
+}
@@ -1,5 +1,5 @@
 pub mod state;
 pub mod worker;
 
-pub use state::SchedulerState;
+pub use state::{CatchUpStatus, SchedulerState};
 pub use worker::ScheduleWorker;
@@ -1,7 +1,7 @@
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::{fs, io::Write, path::Path};
+use std::{collections::BTreeMap, fs, io::Write, path::Path};
 use tracing::{debug, error, info, warn};
 
 /// Worker state persisted to disk
@@ -15,6 +15,36 @@ pub struct SchedulerState {
     pub last_success_time: Option<DateTime<Utc>>,
     /// Number of consecutive failures
     pub consecutive_failures: u32,
+    /// Catch-up status of recent epochs, keyed by epoch
+    #[serde(default)]
+    pub epochs: BTreeMap<u64, EpochRecord>,
+}
+
+/// Where an epoch stands in catch-up processing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CatchUpStatus {
+    /// Rewards were calculated and written by this worker
+    Processed,
+    /// Reward records were already on the ledger
+    AlreadyRecorded,
+    /// The last attempt failed; the epoch is retried on the next check
+    Failed,
+}
+
+impl CatchUpStatus {
+    pub fn is_done(&self) -> bool {
+        !matches!(self, Self::Failed)
+    }
+}
+
+/// Catch-up status of a single epoch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EpochRecord {
+    pub status: CatchUpStatus,
+    /// Number of processing attempts
+    pub attempts: u32,
+    pub updated_at: DateTime<Utc>,
 }
 
 impl Default for SchedulerState {
@@ -24,6 +54,7 @@ impl Default for SchedulerState {
             last_check_time: Utc::now(),
             last_success_time: None,
             consecutive_failures: 0,
+            epochs: BTreeMap::new(),
         }
     }
 }
@@ -111,7 +142,8 @@ impl SchedulerState {
 
     /// Update state after successful processing
     pub fn mark_success(&mut self, epoch: u64) {
-        self.last_processed_epoch = Some(epoch);
+        // Catch-up processes older epochs after newer ones may already be done
+        self.last_processed_epoch = self.last_processed_epoch.max(Some(epoch));
         self.last_success_time = Some(Utc::now());
         self.consecutive_failures = 0;
         info!("Marked epoch {} as successfully processed", epoch);
@@ -139,6 +171,41 @@ impl SchedulerState {
         }
     }
 
+    /// Record the catch-up outcome for an epoch
+    pub fn record_epoch(&mut self, epoch: u64, status: CatchUpStatus) {
+        let attempts = self.epochs.get(&epoch).map_or(0, |record| record.attempts);
+        self.epochs.insert(
+            epoch,
+            EpochRecord {
+                status,
+                attempts: attempts + 1,
+                updated_at: Utc::now(),
+            },
+        );
+    }
+
+    /// Epochs in the catch-up window ending at `target_epoch` that are not yet done,
+    /// oldest first
+    ///
+    /// Epochs whose status is unknown are included; callers check the ledger for
+    /// existing reward records before processing them.
+    pub fn catch_up_candidates(&self, target_epoch: u64, max_catch_up_epochs: u64) -> Vec<u64> {
+        let oldest = target_epoch.saturating_sub(max_catch_up_epochs.saturating_sub(1));
+        (oldest..=target_epoch)
+            .filter(|epoch| {
+                !self
+                    .epochs
+                    .get(epoch)
+                    .is_some_and(|record| record.status.is_done())
+            })
+            .collect()
+    }
+
+    /// Forget epochs that fell out of the catch-up window
+    pub fn prune_epochs(&mut self, oldest_epoch: u64) {
+        self.epochs.retain(|epoch, _| *epoch >= oldest_epoch);
+    }
+
     /// Check if we're in a failure state that should halt processing
     pub fn is_in_failure_state(&self, max_failures: u32) -> bool {
         self.consecutive_failures >= max_failures
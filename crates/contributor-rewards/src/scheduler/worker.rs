@@ -1,7 +1,7 @@
 use crate::{
     calculator::{orchestrator::Orchestrator, recorder::compute_record_address},
     ingestor::fetcher::Fetcher,
-    scheduler::state::SchedulerState,
+    scheduler::state::{CatchUpStatus, SchedulerState},
 };
 use anyhow::{Result, anyhow, bail};
 use backon::{ExponentialBuilder, Retryable};
@@ -151,7 +151,8 @@ impl ScheduleWorker {
         Ok(())
     }
 
-    /// Process rewards for the current epoch if needed
+    /// Process rewards for the latest completed epoch, catching up any epochs missed
+    /// while the worker was down
     async fn process_rewards(&self, state: &mut SchedulerState) -> Result<bool> {
         // Get current epoch
         let fetcher = Fetcher::from_settings(&self.orchestrator.settings)?;
@@ -171,8 +172,34 @@ impl ScheduleWorker {
             current_epoch, target_epoch
         );
 
-        // Check if we should process this epoch
-        if !state.should_process_epoch(target_epoch) {
+        let scheduler_settings = &self.orchestrator.settings.scheduler;
+        let candidates =
+            state.catch_up_candidates(target_epoch, scheduler_settings.max_catch_up_epochs);
+        if let Some(&oldest) = candidates.first() {
+            state.prune_epochs(oldest);
+        }
+
+        // Find the epochs that still lack reward records
+        let mut pending = Vec::with_capacity(candidates.len());
+        for epoch in candidates {
+            if self.dry_run {
+                // Dry runs never write, so only the local state tells what was handled
+                if epoch == target_epoch && state.should_process_epoch(epoch) {
+                    pending.push(epoch);
+                }
+            } else if self.rewards_exist_for_epoch(&fetcher, epoch).await? {
+                debug!("Rewards already exist for epoch {}", epoch);
+                state.record_epoch(epoch, CatchUpStatus::AlreadyRecorded);
+                state.mark_success(epoch);
+            } else {
+                pending.push(epoch);
+            }
+        }
+
+        metrics::gauge!("doublezero_contributor_rewards_scheduler_catch_up_backlog")
+            .set(pending.len() as f64);
+
+        if pending.is_empty() {
             info!(
                 "Epoch {} already processed (last processed: {:?}), waiting for new epoch",
                 target_epoch, state.last_processed_epoch
@@ -180,12 +207,39 @@ impl ScheduleWorker {
             return Ok(false);
         }
 
-        info!("Processing rewards for epoch {}", target_epoch);
+        if pending.len() > 1 {
+            info!(
+                "Catching up {} epochs missing rewards: {:?} (at most {} per check)",
+                pending.len(),
+                pending,
+                scheduler_settings.catch_up_batch_size
+            );
+        }
+
+        // Oldest first; stop at the first failure so epochs are never recorded out of order
+        for epoch in pending
+            .into_iter()
+            .take(scheduler_settings.catch_up_batch_size as usize)
+        {
+            if let Err(e) = self.process_epoch(epoch).await {
+                state.record_epoch(epoch, CatchUpStatus::Failed);
+                return Err(e.context(format!("Failed to process epoch {epoch}")));
+            }
+            state.record_epoch(epoch, CatchUpStatus::Processed);
+            state.mark_success(epoch);
+        }
+
+        Ok(true)
+    }
+
+    /// Calculate and write rewards for a single epoch
+    async fn process_epoch(&self, epoch: u64) -> Result<()> {
+        info!("Processing rewards for epoch {}", epoch);
 
         if self.dry_run {
             info!(
                 "DRY RUN: Would calculate and write rewards for epoch {}",
-                target_epoch
+                epoch
             );
             info!("DRY RUN: Skipping actual ledger writes");
 
@@ -197,37 +251,24 @@ impl ScheduleWorker {
                 "DRY RUN: Would write device telemetry, internet telemetry, reward input, and shapley outputs"
             );
 
-            // Mark success even in dry run so we track what we've processed
-            state.mark_success(target_epoch);
+            // Marked as processed even in dry run so we track what we've handled
             info!(
-                "DRY RUN: Marked epoch {} as processed (no chain writes)",
-                target_epoch
-            );
-        } else {
-            // Check if rewards already exist for this epoch (idempotency, only when not in dry-run)
-            if self.rewards_exist_for_epoch(&fetcher, target_epoch).await? {
-                info!(
-                    "Rewards already exist for epoch {}, marking as processed",
-                    target_epoch
-                );
-                state.mark_success(target_epoch);
-                return Ok(false);
-            }
-
-            // Calculate and write rewards for real
-            self.orchestrator
-                .calculate_rewards(Some(target_epoch), self.keypair_path.clone(), false)
-                .await?;
-
-            // Mark success
-            state.mark_success(target_epoch);
-            info!(
-                "Successfully calculated and wrote rewards for epoch {}",
-                target_epoch
+                "DRY RUN: Marking epoch {} as processed (no chain writes)",
+                epoch
             );
+            return Ok(());
         }
 
-        Ok(true)
+        // Calculate and write rewards for real
+        self.orchestrator
+            .calculate_rewards(Some(epoch), self.keypair_path.clone(), false, false, false)
+            .await?;
+
+        info!(
+            "Successfully calculated and wrote rewards for epoch {}",
+            epoch
+        );
+        Ok(())
     }
 
     /// Check if rewards already exist for a given epoch
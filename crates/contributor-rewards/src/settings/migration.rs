@@ -0,0 +1,194 @@
+use crate::settings::{Settings, validation::validate_config};
+use anyhow::{Context, Result};
+use config::{Config as ConfigBuilder, Environment, File};
+use serde_json::{Map, Value};
+use std::{collections::BTreeSet, path::Path};
+
+/// Keys that were renamed or moved, as (old dotted path, current dotted path)
+///
+/// Entries are applied in order, so a section rename is followed by renames inside it.
+pub const RENAMED_KEYS: &[(&str, &str)] = &[
+    ("worker", "scheduler"),
+    ("scheduler.interval_secs", "scheduler.interval_seconds"),
+    ("scheduler.dry_run", "scheduler.enable_dry_run"),
+];
+
+/// Result of migrating an old configuration to the current schema
+#[derive(Debug)]
+pub struct MigrationReport {
+    /// Settings validated against the current schema
+    pub settings: Settings,
+    /// Deprecated keys that were found and mapped, as (old, new)
+    pub renamed: Vec<(String, String)>,
+    /// Keys not part of the current schema; these are dropped from the output
+    pub unknown: Vec<String>,
+}
+
+impl MigrationReport {
+    /// Render the migrated settings as TOML
+    pub fn to_toml(&self) -> Result<String> {
+        toml::to_string_pretty(&self.settings).context("Failed to serialize settings to TOML")
+    }
+}
+
+/// Load an old configuration as an untyped tree, from a config file and/or DZ__ environment
+/// variables (including a `.env` file), without applying the current schema
+pub fn load_raw(path: Option<&Path>) -> Result<Value> {
+    let _ = dotenvy::dotenv();
+
+    let mut builder = ConfigBuilder::builder();
+    if let Some(path) = path {
+        builder = builder.add_source(File::with_name(&path.to_string_lossy()));
+    }
+    builder
+        .add_source(
+            Environment::with_prefix("DZ")
+                .separator("__")
+                .try_parsing(true),
+        )
+        .build()
+        .context("Failed to build configuration")?
+        .try_deserialize()
+        .context("Failed to read configuration")
+}
+
+/// Map renamed keys, validate against the current schema and report unknown keys
+pub fn migrate(mut raw: Value) -> Result<MigrationReport> {
+    let mut renamed = Vec::new();
+    for (old, new) in RENAMED_KEYS {
+        if rename_key(&mut raw, old, new) {
+            renamed.push((old.to_string(), new.to_string()));
+        }
+    }
+
+    let settings: Settings = serde_json::from_value(raw.clone())
+        .context("Migrated configuration does not match the current schema")?;
+    validate_config(&settings)?;
+
+    let known = leaf_paths(&serde_json::to_value(&settings)?);
+    let unknown = leaf_paths(&raw)
+        .into_iter()
+        .filter(|path| !known.contains(path))
+        .collect();
+
+    Ok(MigrationReport {
+        settings,
+        renamed,
+        unknown,
+    })
+}
+
+/// Move the value at `old` to `new`, keeping any value already present at `new`
+fn rename_key(root: &mut Value, old: &str, new: &str) -> bool {
+    let Some(value) = take_path(root, old) else {
+        return false;
+    };
+    insert_path(root, new, value);
+    true
+}
+
+fn take_path(root: &mut Value, path: &str) -> Option<Value> {
+    let (parent, key) = match path.rsplit_once('.') {
+        Some((parent, key)) => (get_path_mut(root, parent)?, key),
+        None => (root, path),
+    };
+    parent.as_object_mut()?.remove(key)
+}
+
+fn get_path_mut<'a>(root: &'a mut Value, path: &str) -> Option<&'a mut Value> {
+    path.split('.')
+        .try_fold(root, |node, key| node.as_object_mut()?.get_mut(key))
+}
+
+fn insert_path(root: &mut Value, path: &str, value: Value) {
+    let mut node = root;
+    let mut keys = path.split('.').peekable();
+    while let Some(key) = keys.next() {
+        let Some(map) = node.as_object_mut() else {
+            return;
+        };
+        if keys.peek().is_none() {
+            match (map.get_mut(key), value) {
+                (Some(Value::Object(existing)), Value::Object(incoming)) => {
+                    for (k, v) in incoming {
+                        existing.entry(k).or_insert(v);
+                    }
+                }
+                (Some(_), _) => {}
+                (None, value) => {
+                    map.insert(key.to_string(), value);
+                }
+            }
+            return;
+        }
+        node = map
+            .entry(key.to_string())
+            .or_insert_with(|| Value::Object(Map::new()));
+    }
+}
+
+fn leaf_paths(value: &Value) -> BTreeSet<String> {
+    fn walk(value: &Value, prefix: &str, out: &mut BTreeSet<String>) {
+        match value {
+            Value::Object(map) if !map.is_empty() => {
+                for (key, child) in map {
+                    let path = if prefix.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{prefix}.{key}")
+                    };
+                    walk(child, &path, out);
+                }
+            }
+            _ => {
+                out.insert(prefix.to_string());
+            }
+        }
+    }
+
+    let mut out = BTreeSet::new();
+    walk(value, "", &mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example_config() -> Value {
+        toml::from_str(include_str!("../../example.config.toml")).unwrap()
+    }
+
+    #[test]
+    fn test_migrate_current_config_is_noop() {
+        let report = migrate(example_config()).unwrap();
+
+        assert!(report.renamed.is_empty());
+        assert!(report.unknown.is_empty());
+    }
+
+    #[test]
+    fn test_migrate_renamed_and_unknown_keys() {
+        let mut raw = example_config();
+        let mut worker = take_path(&mut raw, "scheduler").unwrap();
+        let dry_run = take_path(&mut worker, "enable_dry_run").unwrap();
+        insert_path(&mut worker, "dry_run", dry_run);
+        insert_path(&mut raw, "worker", worker);
+        insert_path(&mut raw, "rpc.legacy_timeout", Value::from(30));
+
+        let report = migrate(raw).unwrap();
+
+        assert_eq!(
+            report.renamed,
+            vec![
+                ("worker".to_string(), "scheduler".to_string()),
+                (
+                    "scheduler.dry_run".to_string(),
+                    "scheduler.enable_dry_run".to_string()
+                ),
+            ]
+        );
+        assert_eq!(report.unknown, vec!["rpc.legacy_timeout".to_string()]);
+        assert!(!report.settings.scheduler.enable_dry_run);
+    }
+}
@@ -1,11 +1,14 @@
+pub mod migration;
 pub mod network;
 pub mod validation;
 
+use crate::{calculator::denomination::RewardDenomination, rng};
 use anyhow::{Context, Result};
 use borsh::{BorshDeserialize, BorshSerialize};
 use config::{Config as ConfigBuilder, Environment, File};
 use network::Network;
 use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
 use std::{fmt, net::SocketAddr, path::Path};
 use validation::validate_config;
 
@@ -32,6 +35,37 @@ pub struct Settings {
     pub scheduler: SchedulerSettings,
     /// Metrics settings
     pub metrics: Option<MetricsSettings>,
+    /// Reward token denomination
+    #[serde(default)]
+    pub denomination: DenominationSettings,
+    /// Named reward pools the epoch's rewards are split across
+    /// Leave empty to allocate everything by Shapley value
+    #[serde(default)]
+    pub pools: Vec<RewardPoolSettings>,
+    /// Export output retention
+    #[serde(default)]
+    pub output: OutputSettings,
+    /// Operator reward eligibility
+    #[serde(default)]
+    pub eligibility: EligibilitySettings,
+    /// Governance approval of reward parameters
+    #[serde(default)]
+    pub governance: GovernanceSettings,
+    /// Collector agents allowed to write internet latency samples
+    #[serde(default)]
+    pub internet_agents: InternetAgentSettings,
+    /// Reward share skew alerting
+    #[serde(default)]
+    pub skew: SkewSettings,
+    /// Sanity checks on the telemetry time window of a fetched epoch
+    #[serde(default)]
+    pub time_window: TimeWindowSettings,
+    /// Resource limits and seeding for telemetry processing
+    #[serde(default)]
+    pub processing: ProcessingSettings,
+    /// Per-contributor reward notifications
+    #[serde(default)]
+    pub notifications: NotificationSettings,
 }
 
 /// Shapley value calculation parameters for reward distribution
@@ -61,6 +95,23 @@ pub struct RpcSettings {
     pub commitment: String,
     /// Rate limit for RPC requests per second
     pub rps_limit: u32,
+    /// TTL in seconds for cached DZ ledger account reads (0 disables caching)
+    #[serde(default = "default_account_cache_ttl_secs")]
+    pub account_cache_ttl_secs: u64,
+    /// Read program accounts with batched, data-sliced requests for public rate-limited RPCs
+    #[serde(default)]
+    pub low_rpc: bool,
+    /// Requests per second allowed in low RPC mode
+    #[serde(default = "default_low_rpc_rps_limit")]
+    pub low_rpc_rps_limit: u32,
+}
+
+fn default_account_cache_ttl_secs() -> u64 {
+    5
+}
+
+fn default_low_rpc_rps_limit() -> u32 {
+    2
 }
 
 /// Solana program IDs for on-chain interactions
@@ -120,6 +171,270 @@ pub struct TelemetryDefaultSettings {
     /// Enable previous epoch lookup for public links
     /// If true, fetches previous epoch's average when current has insufficient data
     pub enable_previous_epoch_lookup: bool,
+    /// Planned maintenance windows excluded from device uptime
+    #[serde(default)]
+    pub maintenance_windows: Vec<MaintenanceWindow>,
+}
+
+/// A period during which a device is not expected to report telemetry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct MaintenanceWindow {
+    /// Device code or pubkey
+    pub device: String,
+    /// Window start as a unix timestamp in microseconds (inclusive)
+    pub start_us: u64,
+    /// Window end as a unix timestamp in microseconds (exclusive)
+    pub end_us: u64,
+}
+
+/// Token that rewards are denominated in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DenominationSettings {
+    /// Reward token mint; defaults to the 2Z mint for the configured network
+    #[serde(default)]
+    pub mint: Option<String>,
+    /// Number of decimals of the reward token mint
+    #[serde(default = "default_denomination_decimals")]
+    pub decimals: u8,
+    /// Reward tokens per SOL, used to convert SOL-denominated amounts
+    /// Leave unset when no conversion is needed
+    #[serde(default)]
+    pub sol_conversion_rate: Option<f64>,
+}
+
+fn default_denomination_decimals() -> u8 {
+    8
+}
+
+impl Default for DenominationSettings {
+    fn default() -> Self {
+        Self {
+            mint: None,
+            decimals: default_denomination_decimals(),
+            sol_conversion_rate: None,
+        }
+    }
+}
+
+/// A named tranche of an epoch's rewards
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct RewardPoolSettings {
+    /// Pool name, recorded alongside the pool's allocation
+    pub name: String,
+    /// Fraction of the epoch's rewards assigned to this pool (0.0-1.0)
+    /// Shares across all pools must sum to 1.0
+    pub share: f64,
+    /// How the pool is allocated across operators
+    pub allocation: AllocationRule,
+}
+
+/// How a reward pool is split across operators
+#[derive(
+    Debug, Clone, Copy, PartialEq, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
+)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum AllocationRule {
+    /// Proportional to each operator's Shapley value
+    Shapley,
+    /// Proportional to Shapley value among operators whose private links meet the uptime SLA
+    SlaGated {
+        /// Minimum mean uptime (0.0-1.0) across an operator's private links
+        min_uptime: f64,
+    },
+    /// Equal amount per device, regardless of performance
+    FlatPerDevice,
+}
+
+/// Retention policy for templated export directories
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OutputSettings {
+    /// Prune export directories last modified more than this many days ago
+    #[serde(default)]
+    pub retention_days: Option<u64>,
+    /// Prune export directories more than this many epochs older than the one being exported
+    /// Only applies to output directories templated with {epoch}
+    #[serde(default)]
+    pub retention_epochs: Option<u64>,
+}
+
+impl OutputSettings {
+    pub fn has_retention(&self) -> bool {
+        self.retention_days.is_some() || self.retention_epochs.is_some()
+    }
+}
+
+/// Which operators are eligible for rewards
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EligibilitySettings {
+    /// Operator pubkeys kept eligible even though none of their contributors are
+    /// activated, e.g. after a successful appeal
+    #[serde(default)]
+    pub overrides: Vec<String>,
+}
+
+/// Where governance-approved reward parameters are published
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GovernanceSettings {
+    /// Authority that posts the approved parameters record for each epoch
+    /// Leave unset to skip the approval check
+    #[serde(default)]
+    pub approval_authority: Option<String>,
+    /// Prefix for the approved parameters record account
+    #[serde(default = "default_approved_parameters_prefix")]
+    pub approved_parameters_prefix: String,
+}
+
+fn default_approved_parameters_prefix() -> String {
+    "dz_approved_reward_parameters".to_string()
+}
+
+impl Default for GovernanceSettings {
+    fn default() -> Self {
+        Self {
+            approval_authority: None,
+            approved_parameters_prefix: default_approved_parameters_prefix(),
+        }
+    }
+}
+
+/// Which collector agents may write internet latency samples
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InternetAgentSettings {
+    /// Approved oracle agent pubkeys
+    /// Leave empty to accept samples from any agent
+    #[serde(default)]
+    pub allowlist: Vec<String>,
+    /// Keep samples from unapproved agents, only reporting them, instead of dropping them
+    #[serde(default)]
+    pub flag_only: bool,
+}
+
+/// Comparison of each operator's reward share against its recent history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkewSettings {
+    /// Number of preceding epochs averaged into the trailing share
+    /// Set to 0 to skip the skew analysis
+    #[serde(default = "default_skew_trailing_epochs")]
+    pub trailing_epochs: u64,
+    /// Largest change in an operator's share, as a fraction of total rewards, before warning
+    #[serde(default = "default_skew_max_share_shift")]
+    pub max_share_shift: f64,
+    /// Webhook receiving a JSON body when an epoch's shares are skewed
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+}
+
+fn default_skew_trailing_epochs() -> u64 {
+    5
+}
+
+fn default_skew_max_share_shift() -> f64 {
+    0.05
+}
+
+impl Default for SkewSettings {
+    fn default() -> Self {
+        Self {
+            trailing_epochs: default_skew_trailing_epochs(),
+            max_share_shift: default_skew_max_share_shift(),
+            webhook_url: None,
+        }
+    }
+}
+
+/// Bounds on the telemetry time window derived for an epoch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeWindowSettings {
+    /// Longest window, in seconds, accepted for a single epoch
+    #[serde(default = "default_max_window_secs")]
+    pub max_window_secs: u64,
+    /// How far, in seconds, the window may reach past the local clock or the epoch's block
+    /// times before it is rejected
+    #[serde(default = "default_max_clock_skew_secs")]
+    pub max_clock_skew_secs: u64,
+}
+
+fn default_max_window_secs() -> u64 {
+    // Three days, comfortably above a DZ epoch
+    3 * 24 * 3600
+}
+
+fn default_max_clock_skew_secs() -> u64 {
+    300
+}
+
+impl Default for TimeWindowSettings {
+    fn default() -> Self {
+        Self {
+            max_window_secs: default_max_window_secs(),
+            max_clock_skew_secs: default_max_clock_skew_secs(),
+        }
+    }
+}
+
+/// Resource limits and seeding for telemetry processing
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProcessingSettings {
+    /// Working memory, in MiB, telemetry aggregation may use at once
+    /// Circuits are aggregated in smaller parallel batches to stay within it; unset is unlimited
+    #[serde(default)]
+    pub memory_budget_mb: Option<u64>,
+    /// Seed recorded with each run for reproducibility
+    /// Unset derives it from the epoch, so reruns of an epoch are reproducible either way
+    #[serde(default)]
+    pub rng_seed: Option<u64>,
+}
+
+/// Notifying contributors of their reward after each published epoch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationSettings {
+    /// Send notifications once an epoch's rewards are published
+    #[serde(default)]
+    pub enabled: bool,
+    /// Contributors to notify and where to reach them
+    #[serde(default)]
+    pub contacts: Vec<ContributorContactSettings>,
+    /// Authority that posts the contact registry record
+    /// Its entries take precedence over configured contacts; leave unset to use only those
+    #[serde(default)]
+    pub registry_authority: Option<String>,
+    /// Prefix for the contact registry record account
+    #[serde(default = "default_contact_registry_prefix")]
+    pub registry_prefix: String,
+    /// HTTP mail relay email notifications are posted to
+    #[serde(default)]
+    pub email_relay_url: Option<String>,
+    /// Epochs after publication a reward can be claimed in, included in notifications when set
+    #[serde(default)]
+    pub claim_window_epochs: Option<u64>,
+}
+
+fn default_contact_registry_prefix() -> String {
+    "dz_contributor_contacts".to_string()
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            contacts: Vec::new(),
+            registry_authority: None,
+            registry_prefix: default_contact_registry_prefix(),
+            email_relay_url: None,
+            claim_window_epochs: None,
+        }
+    }
+}
+
+/// Where to reach a contributor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct ContributorContactSettings {
+    /// Contributor pubkey
+    pub contributor: String,
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+    #[serde(default)]
+    pub email: Option<String>,
 }
 
 /// Scheduler configuration for automated rewards calculation
@@ -133,6 +448,21 @@ pub struct SchedulerSettings {
     pub max_consecutive_failures: u32,
     /// Enable dry run mode for worker
     pub enable_dry_run: bool,
+    /// How many epochs back (including the latest completed one) to look for epochs
+    /// missing reward records
+    #[serde(default = "default_max_catch_up_epochs")]
+    pub max_catch_up_epochs: u64,
+    /// Maximum number of missed epochs processed per check interval
+    #[serde(default = "default_catch_up_batch_size")]
+    pub catch_up_batch_size: u64,
+}
+
+fn default_max_catch_up_epochs() -> u64 {
+    10
+}
+
+fn default_catch_up_batch_size() -> u64 {
+    3
 }
 
 /// Scheduler configuration for automated rewards calculation
@@ -186,6 +516,32 @@ impl Settings {
 
         Ok(settings)
     }
+
+    /// Seed recorded for `epoch`
+    pub fn rng_seed(&self, epoch: u64) -> u64 {
+        self.processing
+            .rng_seed
+            .unwrap_or_else(|| rng::default_seed(epoch))
+    }
+
+    /// Resolve the reward denomination, defaulting the mint to the network's 2Z mint
+    pub fn denomination(&self) -> Result<RewardDenomination> {
+        let mint = match &self.denomination.mint {
+            Some(mint) => mint
+                .parse::<Pubkey>()
+                .with_context(|| format!("Invalid denomination mint {mint}"))?,
+            None if self.network.is_production() => {
+                doublezero_revenue_distribution::env::mainnet::DOUBLEZERO_MINT_KEY
+            }
+            None => doublezero_revenue_distribution::env::development::DOUBLEZERO_MINT_KEY,
+        };
+
+        Ok(RewardDenomination {
+            mint,
+            decimals: self.denomination.decimals,
+            sol_conversion_rate: self.denomination.sol_conversion_rate,
+        })
+    }
 }
 
 impl fmt::Display for Settings {
@@ -1,6 +1,11 @@
+use anyhow::{Result, bail};
 use serde::{Deserialize, Serialize};
 use std::{fmt, str::FromStr};
 
+// Genesis hashes of the production clusters; everything else is treated as non-production
+const DZ_LEDGER_MAINNET_BETA_GENESIS_HASH: &str = "5wVUvkFcFGYiKRUZ8Jp8Wc5swjhDEqT7hTdyssxDpC7P";
+const SOLANA_MAINNET_BETA_GENESIS_HASH: &str = "5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d";
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "kebab-case")]
 pub enum Network {
@@ -54,6 +59,36 @@ impl Network {
     pub fn is_production(&self) -> bool {
         matches!(self, Network::MainnetBeta | Network::Mainnet)
     }
+
+    /// Refuse to publish artifacts labeled for this network to the other environment's
+    /// clusters, e.g. mainnet rewards to a testnet ledger
+    pub fn ensure_target_clusters(
+        &self,
+        dz_ledger_genesis_hash: &str,
+        solana_genesis_hash: &str,
+    ) -> Result<()> {
+        let production = self.is_production();
+
+        if production != (dz_ledger_genesis_hash == DZ_LEDGER_MAINNET_BETA_GENESIS_HASH) {
+            bail!(
+                "Network is {self} but the DZ ledger RPC points at a {} ledger (genesis {dz_ledger_genesis_hash})",
+                environment(!production)
+            );
+        }
+
+        if production != (solana_genesis_hash == SOLANA_MAINNET_BETA_GENESIS_HASH) {
+            bail!(
+                "Network is {self} but the Solana RPC points at a {} cluster (genesis {solana_genesis_hash})",
+                environment(!production)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+fn environment(production: bool) -> &'static str {
+    if production { "mainnet" } else { "non-mainnet" }
 }
 
 #[cfg(test)]
@@ -78,6 +113,42 @@ mod tests {
         assert_eq!(Network::MainnetBeta.to_string(), "mainnet-beta");
     }
 
+    #[test]
+    fn test_ensure_target_clusters() {
+        let other = "4uhcVJyU9pJkvQyS88uRDiswHXSCkY3zQawwpjk2NsNY";
+
+        assert!(
+            Network::MainnetBeta
+                .ensure_target_clusters(
+                    DZ_LEDGER_MAINNET_BETA_GENESIS_HASH,
+                    SOLANA_MAINNET_BETA_GENESIS_HASH
+                )
+                .is_ok()
+        );
+        assert!(
+            Network::Testnet
+                .ensure_target_clusters(other, other)
+                .is_ok()
+        );
+
+        // Mainnet artifacts to a testnet ledger and vice versa
+        assert!(
+            Network::MainnetBeta
+                .ensure_target_clusters(other, SOLANA_MAINNET_BETA_GENESIS_HASH)
+                .is_err()
+        );
+        assert!(
+            Network::Testnet
+                .ensure_target_clusters(DZ_LEDGER_MAINNET_BETA_GENESIS_HASH, other)
+                .is_err()
+        );
+        assert!(
+            Network::Devnet
+                .ensure_target_clusters(other, SOLANA_MAINNET_BETA_GENESIS_HASH)
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_is_production() {
         assert!(!Network::Devnet.is_production());
@@ -1,6 +1,16 @@
-use crate::settings::Settings;
+use crate::settings::{AllocationRule, Settings};
 use anyhow::{Result, bail};
-use std::net::{IpAddr, SocketAddr};
+use solana_sdk::pubkey::Pubkey;
+use std::{
+    collections::BTreeSet,
+    net::{IpAddr, SocketAddr},
+};
+
+// Largest number of decimals whose scale (10^decimals) fits in a u64
+const MAX_DENOMINATION_DECIMALS: u8 = 19;
+
+// Allowed float error when checking that reward pool shares sum to 1.0
+const POOL_SHARE_TOLERANCE: f64 = 1e-9;
 
 /// Validate the configuration values
 pub fn validate_config(settings: &Settings) -> Result<()> {
@@ -57,6 +67,10 @@ pub fn validate_config(settings: &Settings) -> Result<()> {
         bail!("RPC rate limit must be greater than 0");
     }
 
+    if settings.rpc.low_rpc_rps_limit == 0 {
+        bail!("Low RPC rate limit must be greater than 0");
+    }
+
     // Validate program IDs
     if settings.programs.serviceability_program_id.is_empty() {
         bail!("Serviceability program ID cannot be empty");
@@ -136,6 +150,172 @@ pub fn validate_config(settings: &Settings) -> Result<()> {
         );
     }
 
+    for window in &settings.telemetry_defaults.maintenance_windows {
+        if window.start_us >= window.end_us {
+            bail!(
+                "Maintenance window for device {} must start before it ends, got {}..{}",
+                window.device,
+                window.start_us,
+                window.end_us
+            );
+        }
+    }
+
+    // Validate scheduler settings
+    if settings.scheduler.max_catch_up_epochs == 0 {
+        bail!("Scheduler max_catch_up_epochs must be greater than 0");
+    }
+
+    if settings.scheduler.catch_up_batch_size == 0 {
+        bail!("Scheduler catch_up_batch_size must be greater than 0");
+    }
+
+    // Validate denomination settings
+    if settings.denomination.decimals > MAX_DENOMINATION_DECIMALS {
+        bail!(
+            "Denomination decimals must not exceed {MAX_DENOMINATION_DECIMALS}, got {}",
+            settings.denomination.decimals
+        );
+    }
+
+    if let Some(rate) = settings.denomination.sol_conversion_rate
+        && !(rate.is_finite() && rate > 0.0)
+    {
+        bail!("Denomination sol_conversion_rate must be positive, got {rate}");
+    }
+
+    settings.denomination()?;
+
+    // Validate reward pools
+    if !settings.pools.is_empty() {
+        let mut names = BTreeSet::new();
+        for pool in &settings.pools {
+            if pool.name.is_empty() {
+                bail!("Reward pool name cannot be empty");
+            }
+            if !names.insert(pool.name.as_str()) {
+                bail!("Duplicate reward pool name {}", pool.name);
+            }
+            if !(0.0..=1.0).contains(&pool.share) {
+                bail!(
+                    "Reward pool {} share must be between 0.0 and 1.0, got {}",
+                    pool.name,
+                    pool.share
+                );
+            }
+            if let AllocationRule::SlaGated { min_uptime } = pool.allocation
+                && !(0.0..=1.0).contains(&min_uptime)
+            {
+                bail!(
+                    "Reward pool {} min_uptime must be between 0.0 and 1.0, got {min_uptime}",
+                    pool.name
+                );
+            }
+        }
+
+        let total_share: f64 = settings.pools.iter().map(|pool| pool.share).sum();
+        if (total_share - 1.0).abs() > POOL_SHARE_TOLERANCE {
+            bail!("Reward pool shares must sum to 1.0, got {total_share}");
+        }
+    }
+
+    // Validate output retention settings
+    if settings.output.retention_days == Some(0) {
+        bail!("Output retention_days must be greater than 0");
+    }
+
+    if settings.output.retention_epochs == Some(0) {
+        bail!("Output retention_epochs must be greater than 0");
+    }
+
+    // Validate eligibility overrides
+    for operator in &settings.eligibility.overrides {
+        if operator.parse::<Pubkey>().is_err() {
+            bail!("Invalid eligibility override operator pubkey: {operator}");
+        }
+    }
+
+    // Validate governance approval
+    if let Some(authority) = &settings.governance.approval_authority
+        && authority.parse::<Pubkey>().is_err()
+    {
+        bail!("Invalid governance approval authority pubkey: {authority}");
+    }
+    if settings.governance.approved_parameters_prefix.is_empty() {
+        bail!("Governance approved_parameters_prefix cannot be empty");
+    }
+
+    // Validate internet agent allowlist
+    for agent in &settings.internet_agents.allowlist {
+        if agent.parse::<Pubkey>().is_err() {
+            bail!("Invalid internet agent allowlist pubkey: {agent}");
+        }
+    }
+
+    // Validate skew alerting
+    if !(settings.skew.max_share_shift > 0.0 && settings.skew.max_share_shift <= 1.0) {
+        bail!(
+            "Skew max_share_shift must be in (0, 1], got {}",
+            settings.skew.max_share_shift
+        );
+    }
+    if let Some(url) = &settings.skew.webhook_url
+        && !url.starts_with("http://")
+        && !url.starts_with("https://")
+    {
+        bail!("Skew webhook URL must start with http:// or https://");
+    }
+
+    // Validate time window bounds
+    if settings.time_window.max_window_secs == 0 {
+        bail!("Time window max_window_secs must be greater than 0");
+    }
+
+    // Validate processing limits
+    if settings.processing.memory_budget_mb == Some(0) {
+        bail!("Processing memory_budget_mb must be greater than 0 when set");
+    }
+
+    // Validate contributor notifications
+    let notifications = &settings.notifications;
+    for contact in &notifications.contacts {
+        if contact.contributor.parse::<Pubkey>().is_err() {
+            bail!(
+                "Invalid notification contributor pubkey: {}",
+                contact.contributor
+            );
+        }
+        if let Some(url) = &contact.webhook_url
+            && !url.starts_with("http://")
+            && !url.starts_with("https://")
+        {
+            bail!(
+                "Notification webhook URL for {} must start with http:// or https://",
+                contact.contributor
+            );
+        }
+        if contact.email.is_some() && notifications.email_relay_url.is_none() {
+            bail!(
+                "Notification email for {} requires an email_relay_url",
+                contact.contributor
+            );
+        }
+    }
+    if let Some(authority) = &notifications.registry_authority
+        && authority.parse::<Pubkey>().is_err()
+    {
+        bail!("Invalid notification registry authority pubkey: {authority}");
+    }
+    if notifications.registry_prefix.is_empty() {
+        bail!("Notification registry_prefix cannot be empty");
+    }
+    if let Some(url) = &notifications.email_relay_url
+        && !url.starts_with("http://")
+        && !url.starts_with("https://")
+    {
+        bail!("Notification email relay URL must start with http:// or https://");
+    }
+
     if let Some(metrics) = &settings.metrics
         && !validate_socket_addr(&metrics.addr)
     {
@@ -156,8 +336,11 @@ fn validate_socket_addr(addr: &SocketAddr) -> bool {
 mod tests {
     use super::*;
     use crate::settings::{
-        InetLookbackSettings, MetricsSettings, PrefixSettings, ProgramSettings, RpcSettings,
-        SchedulerSettings, ShapleySettings, TelemetryDefaultSettings, network::Network,
+        ContributorContactSettings, DenominationSettings, EligibilitySettings, GovernanceSettings,
+        InetLookbackSettings, InternetAgentSettings, MetricsSettings, NotificationSettings,
+        OutputSettings, PrefixSettings, ProcessingSettings, ProgramSettings, RewardPoolSettings,
+        RpcSettings, SchedulerSettings, ShapleySettings, SkewSettings, TelemetryDefaultSettings,
+        TimeWindowSettings, network::Network,
     };
     use std::{net::SocketAddr, str::FromStr};
 
@@ -176,6 +359,9 @@ mod tests {
                 solana_write_url: "https://api.testnet.solana.com".to_string(),
                 commitment: "finalized".to_string(),
                 rps_limit: 10,
+                account_cache_ttl_secs: 5,
+                low_rpc: false,
+                low_rpc_rps_limit: 2,
             },
             programs: ProgramSettings {
                 serviceability_program_id: "11111111111111111111111111111111".to_string(),
@@ -198,16 +384,29 @@ mod tests {
                 missing_data_threshold: 0.7,
                 private_default_latency_ms: 1000.0,
                 enable_previous_epoch_lookup: true,
+                maintenance_windows: vec![],
             },
             scheduler: SchedulerSettings {
                 interval_seconds: 300,
                 state_file: "/var/lib/doublezero-contributor-rewards/scheduler.state".to_string(),
                 max_consecutive_failures: 10,
                 enable_dry_run: false,
+                max_catch_up_epochs: 10,
+                catch_up_batch_size: 3,
             },
             metrics: Some(MetricsSettings {
                 addr: SocketAddr::from_str("127.0.0.1:9090").unwrap(),
             }),
+            denomination: DenominationSettings::default(),
+            pools: vec![],
+            output: OutputSettings::default(),
+            eligibility: EligibilitySettings::default(),
+            governance: GovernanceSettings::default(),
+            internet_agents: InternetAgentSettings::default(),
+            skew: SkewSettings::default(),
+            time_window: TimeWindowSettings::default(),
+            processing: ProcessingSettings::default(),
+            notifications: NotificationSettings::default(),
         }
     }
 
@@ -297,4 +496,156 @@ mod tests {
         config.metrics = None;
         assert!(validate_config(&config).is_ok());
     }
+
+    #[test]
+    fn test_invalid_scheduler_catch_up() {
+        let mut config = create_valid_config();
+        config.scheduler.max_catch_up_epochs = 0;
+        assert!(validate_config(&config).is_err());
+
+        let mut config = create_valid_config();
+        config.scheduler.catch_up_batch_size = 0;
+        assert!(validate_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_invalid_denomination() {
+        let mut config = create_valid_config();
+        config.denomination.decimals = 20;
+        assert!(validate_config(&config).is_err());
+
+        let mut config = create_valid_config();
+        config.denomination.mint = Some("not-a-pubkey".to_string());
+        assert!(validate_config(&config).is_err());
+
+        let mut config = create_valid_config();
+        config.denomination.sol_conversion_rate = Some(0.0);
+        assert!(validate_config(&config).is_err());
+
+        config.denomination.sol_conversion_rate = Some(2_500.0);
+        config.denomination.decimals = 6;
+        assert!(validate_config(&config).is_ok());
+    }
+
+    #[test]
+    fn test_reward_pools() {
+        let mut config = create_valid_config();
+        config.pools = vec![
+            RewardPoolSettings {
+                name: "base".to_string(),
+                share: 0.7,
+                allocation: AllocationRule::FlatPerDevice,
+            },
+            RewardPoolSettings {
+                name: "performance".to_string(),
+                share: 0.3,
+                allocation: AllocationRule::SlaGated { min_uptime: 0.99 },
+            },
+        ];
+        assert!(validate_config(&config).is_ok());
+
+        // Shares must sum to 1.0
+        config.pools[1].share = 0.2;
+        assert!(validate_config(&config).is_err());
+        config.pools[1].share = 0.3;
+
+        // Names must be unique
+        config.pools[1].name = "base".to_string();
+        assert!(validate_config(&config).is_err());
+        config.pools[1].name = "performance".to_string();
+
+        config.pools[1].allocation = AllocationRule::SlaGated { min_uptime: 1.5 };
+        assert!(validate_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_eligibility_overrides() {
+        let mut config = create_valid_config();
+        config.eligibility.overrides = vec![Pubkey::new_unique().to_string()];
+        assert!(validate_config(&config).is_ok());
+
+        config
+            .eligibility
+            .overrides
+            .push("not-a-pubkey".to_string());
+        assert!(validate_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_governance_approval_authority() {
+        let mut config = create_valid_config();
+        config.governance.approval_authority = Some(Pubkey::new_unique().to_string());
+        assert!(validate_config(&config).is_ok());
+
+        config.governance.approval_authority = Some("not-a-pubkey".to_string());
+        assert!(validate_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_internet_agent_allowlist() {
+        let mut config = create_valid_config();
+        config.internet_agents.allowlist = vec![Pubkey::new_unique().to_string()];
+        assert!(validate_config(&config).is_ok());
+
+        config
+            .internet_agents
+            .allowlist
+            .push("not-a-pubkey".to_string());
+        assert!(validate_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_skew_settings() {
+        let mut config = create_valid_config();
+        config.skew.webhook_url = Some("https://hooks.example.com/skew".to_string());
+        assert!(validate_config(&config).is_ok());
+
+        config.skew.webhook_url = Some("not a url".to_string());
+        assert!(validate_config(&config).is_err());
+
+        config.skew.webhook_url = None;
+        config.skew.max_share_shift = 0.0;
+        assert!(validate_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_time_window_settings() {
+        let mut config = create_valid_config();
+        config.time_window.max_window_secs = 0;
+        assert!(validate_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_processing_settings() {
+        let mut config = create_valid_config();
+        config.processing.memory_budget_mb = Some(4096);
+        assert!(validate_config(&config).is_ok());
+
+        config.processing.memory_budget_mb = Some(0);
+        assert!(validate_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_notification_settings() {
+        let mut config = create_valid_config();
+        config.notifications.enabled = true;
+        config.notifications.contacts = vec![ContributorContactSettings {
+            contributor: Pubkey::new_unique().to_string(),
+            webhook_url: Some("https://hooks.example.com/rewards".to_string()),
+            email: None,
+        }];
+        assert!(validate_config(&config).is_ok());
+
+        config.notifications.contacts[0].email = Some("ops@example.com".to_string());
+        assert!(validate_config(&config).is_err());
+        config.notifications.email_relay_url = Some("https://mail.example.com/send".to_string());
+        assert!(validate_config(&config).is_ok());
+
+        config.notifications.contacts[0].webhook_url = Some("not a url".to_string());
+        assert!(validate_config(&config).is_err());
+        config.notifications.contacts[0].webhook_url = None;
+
+        config.notifications.contacts[0].contributor = "not-a-pubkey".to_string();
+        assert!(validate_config(&config).is_err());
+    }
 }
@@ -0,0 +1,199 @@
+//! Typed amounts so values are converted and displayed at the right scale
+//!
+//! Exports keep their raw integer or microsecond fields; these wrappers are for the
+//! conversions and human-readable output built from them.
+
+use serde::{Deserialize, Serialize};
+use std::{fmt, marker::PhantomData};
+
+pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+const SOL_DECIMALS: u8 = 9;
+const MICROS_PER_MILLI: f64 = 1_000.0;
+
+/// Format an integer amount with `decimals` implied decimal places, without rounding
+pub fn format_fixed_point(amount: u64, decimals: u8) -> String {
+    if decimals == 0 {
+        return amount.to_string();
+    }
+    let scale = 10_u64.pow(decimals as u32);
+    format!(
+        "{}.{:0width$}",
+        amount / scale,
+        amount % scale,
+        width = decimals as usize
+    )
+}
+
+/// SOL amount in lamports
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct Lamports(pub u64);
+
+impl Lamports {
+    /// Convert whole SOL to lamports, rounding to the nearest lamport
+    pub fn from_sol(sol: f64) -> Self {
+        Self((sol * LAMPORTS_PER_SOL as f64).round() as u64)
+    }
+
+    pub fn as_sol(&self) -> f64 {
+        self.0 as f64 / LAMPORTS_PER_SOL as f64
+    }
+}
+
+impl fmt::Display for Lamports {
+    /// Full-precision SOL, e.g. `1.500000000 SOL`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(&format!("{} SOL", format_fixed_point(self.0, SOL_DECIMALS)))
+    }
+}
+
+impl From<u64> for Lamports {
+    fn from(lamports: u64) -> Self {
+        Self(lamports)
+    }
+}
+
+/// Decimals and symbol of a token mint known at compile time
+pub trait MintDecimals {
+    const DECIMALS: u8;
+    const SYMBOL: &'static str;
+}
+
+/// The 2Z token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TwoZ;
+
+impl MintDecimals for TwoZ {
+    const DECIMALS: u8 = doublezero_revenue_distribution::DOUBLEZERO_MINT_DECIMALS;
+    const SYMBOL: &'static str = "2Z";
+}
+
+/// Token amount in the mint's base units
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TokenAmount<M: MintDecimals> {
+    pub base_units: u64,
+    #[serde(skip)]
+    mint: PhantomData<M>,
+}
+
+impl<M: MintDecimals> TokenAmount<M> {
+    pub fn new(base_units: u64) -> Self {
+        Self {
+            base_units,
+            mint: PhantomData,
+        }
+    }
+
+    /// Convert whole tokens to base units, rounding to the nearest unit
+    pub fn from_tokens(tokens: f64) -> Self {
+        Self::new((tokens * 10_f64.powi(M::DECIMALS as i32)).round() as u64)
+    }
+
+    pub fn as_tokens(&self) -> f64 {
+        self.base_units as f64 / 10_f64.powi(M::DECIMALS as i32)
+    }
+}
+
+// Manual impls so `M` itself needs none of these bounds
+impl<M: MintDecimals> fmt::Debug for TokenAmount<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "TokenAmount<{}>({})", M::SYMBOL, self.base_units)
+    }
+}
+
+impl<M: MintDecimals> Clone for TokenAmount<M> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<M: MintDecimals> Copy for TokenAmount<M> {}
+
+impl<M: MintDecimals> PartialEq for TokenAmount<M> {
+    fn eq(&self, other: &Self) -> bool {
+        self.base_units == other.base_units
+    }
+}
+
+impl<M: MintDecimals> Eq for TokenAmount<M> {}
+
+impl<M: MintDecimals> fmt::Display for TokenAmount<M> {
+    /// Full-precision whole tokens, e.g. `1.50000000 2Z`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(&format!(
+            "{} {}",
+            format_fixed_point(self.base_units, M::DECIMALS),
+            M::SYMBOL
+        ))
+    }
+}
+
+/// Duration in microseconds, the unit telemetry samples are recorded in
+#[derive(Debug, Default, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Micros(pub f64);
+
+impl Micros {
+    pub fn as_millis(&self) -> f64 {
+        self.0 / MICROS_PER_MILLI
+    }
+}
+
+impl fmt::Display for Micros {
+    /// Milliseconds, which is how latencies are presented
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match f.precision() {
+            Some(precision) => write!(f, "{:.precision$}ms", self.as_millis()),
+            None => write!(f, "{}ms", self.as_millis()),
+        }
+    }
+}
+
+impl From<f64> for Micros {
+    fn from(us: f64) -> Self {
+        Self(us)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lamports() {
+        assert_eq!(Lamports::from_sol(1.5), Lamports(1_500_000_000));
+        assert_eq!(Lamports(250_000_000).as_sol(), 0.25);
+        assert_eq!(Lamports(1_500_000_000).to_string(), "1.500000000 SOL");
+        assert_eq!(Lamports(1).to_string(), "0.000000001 SOL");
+    }
+
+    #[test]
+    fn test_token_amount() {
+        let amount = TokenAmount::<TwoZ>::from_tokens(1.5);
+        assert_eq!(amount.base_units, 150_000_000);
+        assert_eq!(amount.as_tokens(), 1.5);
+        assert_eq!(amount.to_string(), "1.50000000 2Z");
+        assert_eq!(
+            serde_json::to_string(&amount).unwrap(),
+            "150000000",
+            "serializes as raw base units"
+        );
+    }
+
+    #[test]
+    fn test_micros() {
+        assert_eq!(Micros(1_500.0).as_millis(), 1.5);
+        assert_eq!(Micros(1_500.0).to_string(), "1.5ms");
+        assert_eq!(format!("{:.2}", Micros(1_234.0)), "1.23ms");
+    }
+
+    #[test]
+    fn test_format_fixed_point() {
+        assert_eq!(format_fixed_point(42, 0), "42");
+        assert_eq!(format_fixed_point(1_500_000, 6), "1.500000");
+        assert_eq!(format_fixed_point(5, 3), "0.005");
+    }
+}
@@ -20,6 +20,9 @@ pub fn create_test_settings(
             solana_write_url: "https://test.com".to_string(),
             commitment: "confirmed".to_string(),
             rps_limit: 10,
+            account_cache_ttl_secs: 5,
+            low_rpc: false,
+            low_rpc_rps_limit: 2,
         },
         programs: settings::ProgramSettings {
             serviceability_program_id: "test".to_string(),
@@ -42,15 +45,28 @@ pub fn create_test_settings(
             missing_data_threshold: missing_threshold,
             private_default_latency_ms: private_default_ms,
             enable_previous_epoch_lookup: enable_previous,
+            maintenance_windows: vec![],
         },
         scheduler: settings::SchedulerSettings {
             interval_seconds: 300,
             state_file: "/var/lib/doublezero-contributor-rewards/scheduler.state".to_string(),
             max_consecutive_failures: 10,
             enable_dry_run: false,
+            max_catch_up_epochs: 10,
+            catch_up_batch_size: 3,
         },
         metrics: Some(settings::MetricsSettings {
             addr: "127.0.0.1:9090".parse().unwrap(),
         }),
+        denomination: settings::DenominationSettings::default(),
+        pools: vec![],
+        output: settings::OutputSettings::default(),
+        eligibility: settings::EligibilitySettings::default(),
+        governance: settings::GovernanceSettings::default(),
+        internet_agents: settings::InternetAgentSettings::default(),
+        skew: settings::SkewSettings::default(),
+        time_window: settings::TimeWindowSettings::default(),
+        processing: settings::ProcessingSettings::default(),
+        notifications: settings::NotificationSettings::default(),
     }
 }
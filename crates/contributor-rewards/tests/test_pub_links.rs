@@ -34,6 +34,9 @@ fn test_settings() -> settings::Settings {
             solana_write_url: "https://test.com".to_string(),
             commitment: "confirmed".to_string(),
             rps_limit: 10,
+            account_cache_ttl_secs: 5,
+            low_rpc: false,
+            low_rpc_rps_limit: 2,
         },
         programs: settings::ProgramSettings {
             serviceability_program_id: "test".to_string(),
@@ -56,16 +59,29 @@ fn test_settings() -> settings::Settings {
             missing_data_threshold: 0.7,
             private_default_latency_ms: 1000.0,
             enable_previous_epoch_lookup: true,
+            maintenance_windows: vec![],
         },
         scheduler: settings::SchedulerSettings {
             interval_seconds: 300,
             state_file: "/var/lib/doublezero-contributor-rewards/scheduler.state".to_string(),
             max_consecutive_failures: 10,
             enable_dry_run: false,
+            max_catch_up_epochs: 10,
+            catch_up_batch_size: 3,
         },
         metrics: Some(settings::MetricsSettings {
             addr: "127.0.0.1:9090".parse().unwrap(),
         }),
+        denomination: settings::DenominationSettings::default(),
+        pools: vec![],
+        output: settings::OutputSettings::default(),
+        eligibility: settings::EligibilitySettings::default(),
+        governance: settings::GovernanceSettings::default(),
+        internet_agents: settings::InternetAgentSettings::default(),
+        skew: settings::SkewSettings::default(),
+        time_window: settings::TimeWindowSettings::default(),
+        processing: settings::ProcessingSettings::default(),
+        notifications: settings::NotificationSettings::default(),
     }
 }
 
@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod tests {
-    use doublezero_contributor_rewards::scheduler::SchedulerState;
+    use doublezero_contributor_rewards::scheduler::{CatchUpStatus, SchedulerState};
     use tempfile::TempDir;
 
     #[test]
@@ -78,4 +78,50 @@ mod tests {
         let loaded = SchedulerState::load_or_default(&non_existent_path).unwrap();
         assert_eq!(loaded.last_processed_epoch, Some(42));
     }
+
+    #[test]
+    fn test_catch_up_candidates() {
+        let mut state = SchedulerState::default();
+        assert_eq!(state.catch_up_candidates(100, 4), vec![97, 98, 99, 100]);
+        assert_eq!(state.catch_up_candidates(100, 1), vec![100]);
+        assert_eq!(state.catch_up_candidates(1, 4), vec![0, 1]);
+
+        state.record_epoch(97, CatchUpStatus::AlreadyRecorded);
+        state.record_epoch(98, CatchUpStatus::Failed);
+        state.record_epoch(99, CatchUpStatus::Processed);
+
+        // Failed epochs are retried, done ones are skipped
+        assert_eq!(state.catch_up_candidates(100, 4), vec![98, 100]);
+
+        state.record_epoch(98, CatchUpStatus::Processed);
+        assert_eq!(state.epochs[&98].attempts, 2);
+
+        state.prune_epochs(98);
+        assert!(!state.epochs.contains_key(&97));
+    }
+
+    #[test]
+    fn test_catch_up_keeps_latest_processed_epoch() {
+        let mut state = SchedulerState::default();
+        state.mark_success(100);
+
+        // Catching up an older epoch does not move the high-water mark back
+        state.mark_success(97);
+        assert_eq!(state.last_processed_epoch, Some(100));
+    }
+
+    #[test]
+    fn test_epoch_records_persist() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("test.state");
+
+        let mut state = SchedulerState::default();
+        state.record_epoch(41, CatchUpStatus::Failed);
+        state.record_epoch(42, CatchUpStatus::Processed);
+        state.save(&state_file).unwrap();
+
+        let loaded = SchedulerState::load_or_default(&state_file).unwrap();
+        assert_eq!(loaded.epochs[&41].status, CatchUpStatus::Failed);
+        assert_eq!(loaded.epochs[&42].status, CatchUpStatus::Processed);
+    }
 }
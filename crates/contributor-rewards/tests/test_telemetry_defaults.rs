@@ -32,11 +32,14 @@ fn create_mock_device_stats(circuit: &str, missing_ratio: f64) -> DZDTelemetrySt
         jitter_ewma_us: 500.0,
         avg_jitter_us: 500.0,
         max_jitter_us: 1000.0,
+        rfc3550_jitter_us: 450.0,
+        jitter_stddev_us: 300.0,
         packet_loss: missing_ratio * 100.0,
         loss_count,
         success_count: success_count as u64,
         total_samples,
         missing_data_ratio: missing_ratio,
+        uptime: 1.0 - missing_ratio,
     }
 }
 
@@ -65,6 +68,8 @@ fn create_mock_internet_stats(circuit: &str, missing_ratio: f64) -> InternetTele
         jitter_ewma_us: 800.0,
         avg_jitter_us: 800.0,
         max_jitter_us: 1600.0,
+        rfc3550_jitter_us: 750.0,
+        jitter_stddev_us: 500.0,
         packet_loss: missing_ratio * 100.0,
         loss_count,
         success_count: success_count as u64,
@@ -0,0 +1,102 @@
+//! Minimal HTTP endpoint serving a scheduled command's [`ScheduleStatus`].
+//!
+//! Meant for liveness and readiness probes: `GET /health` answers with the
+//! status as JSON, `200 OK` while the command is healthy and
+//! `503 Service Unavailable` once it has failed too many times in a row.
+
+use std::net::{Ipv4Addr, SocketAddr};
+
+use anyhow::Result;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+use tracing::{info, warn};
+
+use crate::{Schedulable, ScheduleStatus};
+
+/// Consecutive failures at which the health endpoint reports unhealthy when
+/// none is given.
+pub const DEFAULT_HEALTH_MAX_FAILURES: usize = 3;
+
+/// Listen for health requests on `port` on all interfaces.
+pub(crate) async fn bind(port: u16) -> Result<TcpListener> {
+    let listener = TcpListener::bind(SocketAddr::from((Ipv4Addr::UNSPECIFIED, port))).await?;
+    info!("Serving schedule health on {}", listener.local_addr()?);
+    Ok(listener)
+}
+
+/// Answer health requests for `command` until the task is aborted.
+pub(crate) async fn serve<T: Schedulable>(
+    listener: TcpListener,
+    command: T,
+    max_failures: usize,
+) -> Result<()> {
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let status = command.status();
+        tokio::spawn(async move {
+            if let Err(e) = respond(stream, status, max_failures).await {
+                warn!("Failed to answer health request: {e}");
+            }
+        });
+    }
+}
+
+async fn respond(mut stream: TcpStream, status: ScheduleStatus, max_failures: usize) -> Result<()> {
+    // Only the request line matters
+    let mut buf = [0u8; 1024];
+    let len = stream.read(&mut buf).await?;
+    let request = String::from_utf8_lossy(&buf[..len]);
+    let path = request.split_whitespace().nth(1).unwrap_or("/");
+
+    let (code, body) = response(path, &status, max_failures)?;
+    let reason = match code {
+        200 => "OK",
+        404 => "Not Found",
+        _ => "Service Unavailable",
+    };
+    let response = format!(
+        "HTTP/1.1 {code} {reason}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+
+    Ok(())
+}
+
+/// Status code and body answering a request for `path`.
+fn response(path: &str, status: &ScheduleStatus, max_failures: usize) -> Result<(u16, String)> {
+    if !matches!(path, "/" | "/health") {
+        return Ok((404, r#"{"error":"not found"}"#.to_string()));
+    }
+
+    let code = if max_failures > 0 && status.consecutive_failures >= max_failures {
+        503
+    } else {
+        200
+    };
+    Ok((code, serde_json::to_string(status)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_response() {
+        let mut status = ScheduleStatus::new(Some("5m".to_string()), Vec::new());
+        let (code, body) = response("/health", &status, 3).unwrap();
+        assert_eq!(code, 200);
+        assert_eq!(
+            serde_json::from_str::<ScheduleStatus>(&body).unwrap(),
+            status
+        );
+
+        status.consecutive_failures = 3;
+        assert_eq!(response("/", &status, 3).unwrap().0, 503);
+        assert_eq!(response("/health", &status, 0).unwrap().0, 200);
+        assert_eq!(response("/metrics", &status, 3).unwrap().0, 404);
+    }
+}
@@ -0,0 +1,239 @@
+//! Bookkeeping of recent executions of a schedulable command.
+
+use std::{
+    collections::VecDeque,
+    fs,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Number of executions kept when no history size is given.
+pub const DEFAULT_HISTORY_SIZE: usize = 20;
+
+/// How an execution ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionOutcome {
+    Success,
+    Failure,
+}
+
+impl ExecutionOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::Failure => "failure",
+        }
+    }
+}
+
+/// A single run of `execute_once`, including its retries.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionRecord {
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    pub outcome: ExecutionOutcome,
+    /// Error message, for failed executions.
+    pub error: Option<String>,
+    /// Attempts made, more than one when failed attempts were retried.
+    #[serde(default = "default_attempts")]
+    pub attempts: u32,
+}
+
+fn default_attempts() -> u32 {
+    1
+}
+
+impl ExecutionRecord {
+    pub fn is_success(&self) -> bool {
+        self.outcome == ExecutionOutcome::Success
+    }
+}
+
+/// Ring buffer of the most recent executions.
+///
+/// Clones share the same buffer, so the copies of a command handed to the
+/// scheduler record into the history the host binary reads from.
+#[derive(Debug, Clone, Default)]
+pub struct ExecutionHistory {
+    records: Arc<Mutex<VecDeque<ExecutionRecord>>>,
+}
+
+impl ExecutionHistory {
+    /// Append a record, evicting the oldest ones beyond `capacity`.
+    pub fn push(&self, record: ExecutionRecord, capacity: usize) {
+        let mut records = self.records.lock().expect("history lock poisoned");
+        records.push_back(record);
+        while records.len() > capacity {
+            records.pop_front();
+        }
+    }
+
+    /// Recorded executions, oldest first.
+    pub fn records(&self) -> Vec<ExecutionRecord> {
+        let records = self.records.lock().expect("history lock poisoned");
+        records.iter().cloned().collect()
+    }
+
+    /// Replace the buffer with the records saved at `path`, if the file
+    /// exists.
+    pub fn load(&self, path: &Path, capacity: usize) -> Result<()> {
+        if !path.exists() {
+            return Ok(());
+        }
+
+        let saved: Vec<ExecutionRecord> = serde_json::from_slice(&fs::read(path)?)?;
+        let skip = saved.len().saturating_sub(capacity);
+
+        let mut records = self.records.lock().expect("history lock poisoned");
+        *records = saved.into_iter().skip(skip).collect();
+
+        Ok(())
+    }
+
+    /// Write the buffer to `path`, replacing it atomically.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_vec(&self.records())?;
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, json)?;
+        fs::rename(&tmp_path, path)?;
+
+        Ok(())
+    }
+}
+
+/// Recent run history of a schedulable command, for health endpoints.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduleStatus {
+    /// Schedule interval, or `None` for a one-time run.
+    pub schedule: Option<String>,
+    pub last_run: Option<DateTime<Utc>>,
+    pub last_success: Option<DateTime<Utc>>,
+    /// Failures since the last successful execution.
+    pub consecutive_failures: usize,
+    /// When the scheduler fires next, once known.
+    pub next_run: Option<DateTime<Utc>>,
+    /// Recent executions, oldest first.
+    pub executions: Vec<ExecutionRecord>,
+}
+
+impl ScheduleStatus {
+    pub fn new(schedule: Option<String>, executions: Vec<ExecutionRecord>) -> Self {
+        let last_run = executions.last().map(|record| record.started_at);
+        let last_success = executions
+            .iter()
+            .rev()
+            .find(|record| record.is_success())
+            .map(|record| record.started_at);
+        let consecutive_failures = executions
+            .iter()
+            .rev()
+            .take_while(|record| !record.is_success())
+            .count();
+
+        Self {
+            schedule,
+            last_run,
+            last_success,
+            consecutive_failures,
+            next_run: None,
+            executions,
+        }
+    }
+
+    pub fn with_next_run(mut self, next_run: Option<DateTime<Utc>>) -> Self {
+        self.next_run = next_run;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(outcome: ExecutionOutcome) -> ExecutionRecord {
+        ExecutionRecord {
+            started_at: Utc::now(),
+            duration_ms: 5,
+            outcome,
+            error: match outcome {
+                ExecutionOutcome::Success => None,
+                ExecutionOutcome::Failure => Some("boom".to_string()),
+            },
+            attempts: 1,
+        }
+    }
+
+    #[test]
+    fn test_history_evicts_oldest() {
+        let history = ExecutionHistory::default();
+        for duration_ms in 0..5 {
+            history.push(
+                ExecutionRecord {
+                    duration_ms,
+                    ..record(ExecutionOutcome::Success)
+                },
+                3,
+            );
+        }
+
+        let durations: Vec<u64> = history
+            .records()
+            .iter()
+            .map(|record| record.duration_ms)
+            .collect();
+        assert_eq!(durations, vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn test_history_shared_between_clones() {
+        let history = ExecutionHistory::default();
+        history
+            .clone()
+            .push(record(ExecutionOutcome::Success), DEFAULT_HISTORY_SIZE);
+        assert_eq!(history.records().len(), 1);
+    }
+
+    #[test]
+    fn test_history_save_and_load() {
+        let path = std::env::temp_dir().join(format!(
+            "doublezero-scheduled-command-history-{}.json",
+            std::process::id()
+        ));
+
+        let history = ExecutionHistory::default();
+        history.push(record(ExecutionOutcome::Success), 10);
+        history.push(record(ExecutionOutcome::Failure), 10);
+        history.save(&path).unwrap();
+
+        let loaded = ExecutionHistory::default();
+        loaded.load(&path, 1).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.records(), history.records()[1..].to_vec());
+    }
+
+    #[test]
+    fn test_status_counts_consecutive_failures() {
+        let executions = vec![
+            record(ExecutionOutcome::Failure),
+            record(ExecutionOutcome::Success),
+            record(ExecutionOutcome::Failure),
+            record(ExecutionOutcome::Failure),
+        ];
+        let status = ScheduleStatus::new(Some("5m".to_string()), executions.clone());
+
+        assert_eq!(status.consecutive_failures, 2);
+        assert_eq!(status.last_success, Some(executions[1].started_at));
+        assert_eq!(status.last_run, Some(executions[3].started_at));
+
+        let status = ScheduleStatus::new(None, Vec::new());
+        assert_eq!(status.consecutive_failures, 0);
+        assert_eq!(status.last_success, None);
+        assert_eq!(status.last_run, None);
+    }
+}
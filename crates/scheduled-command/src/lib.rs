@@ -1,7 +1,42 @@
 //! A library for making CLI commands schedulable with cron-like intervals.
 //!
 //! This library provides a simple trait that allows any command to be run once
-//! or on a scheduled interval based on a schedule string.
+//! or on a schedule, given either as an interval (e.g. "10m") or as a 6-field
+//! cron expression (e.g. "0 0 3 * * *") evaluated in `--schedule-timezone`.
+//!
+//! The most recent executions are kept in a ring buffer, optionally persisted
+//! with `--history-file`, and reported by [`Schedulable::status`] so host
+//! binaries can surface run history without their own bookkeeping.
+//!
+//! Every run is also reported through the [`metrics`] facade, so binaries that
+//! install a recorder (e.g. a Prometheus exporter) get schedule health for free:
+//!
+//! - `doublezero_scheduled_command_runs_total{outcome}`
+//! - `doublezero_scheduled_command_run_duration_seconds`
+//! - `doublezero_scheduled_command_last_success_timestamp`
+//! - `doublezero_scheduled_command_skipped_overlapping_total`, incremented when
+//!   a scheduled run is skipped because the previous one is still in progress
+//! - `doublezero_scheduled_command_retries_total`, incremented for every failed
+//!   attempt retried under the command's [`RetryPolicy`]
+//! - `doublezero_scheduled_command_skipped_locked_total`, incremented when a
+//!   run is skipped because another instance holds the command's [`RunLock`]
+//!
+//! A run that is due while the previous one is still in progress is handled by
+//! the [`OverlapPolicy`] set with `--overlap-policy`, and `--schedule-jitter`
+//! delays every scheduled run by a random amount so that schedulers sharing an
+//! RPC endpoint do not all hit it at the top of the slot. `--startup-delay`
+//! holds off the scheduler itself at boot, and `--run-immediately` runs once
+//! as soon as it starts rather than waiting for the first tick.
+//!
+//! On Ctrl+C or SIGTERM a scheduled command stops starting runs, calls
+//! [`Schedulable::on_shutdown`] and waits up to `--shutdown-drain-timeout` for
+//! the runs in progress to finish before returning.
+//!
+//! With `--health-port` the [`ScheduleStatus`] of a scheduled command is
+//! served as JSON at `/health` for liveness and readiness probes.
+//!
+//! Replicas of a command that run by accident are kept from both executing a
+//! tick by its [`Schedulable::run_lock`], `--lock-file` unless overridden.
 //!
 //! # Example
 //!
@@ -32,20 +67,145 @@
 //! }
 //! ```
 
-use std::time::Duration;
+mod health;
+mod history;
+mod lock;
+
+use std::{
+    fmt,
+    future::Future,
+    hash::{BuildHasher, Hasher, RandomState},
+    path::PathBuf,
+    pin::Pin,
+    str::FromStr,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
 
 use anyhow::{Result, bail};
-use clap::Args;
+use chrono::{DateTime, FixedOffset, Local, Utc};
+use clap::{Args, ValueEnum};
 use tokio_cron_scheduler::{Job, JobScheduler};
-use tracing::{error, info};
+use tracing::{error, info, warn};
+
+pub use health::DEFAULT_HEALTH_MAX_FAILURES;
+pub use history::{
+    DEFAULT_HISTORY_SIZE, ExecutionHistory, ExecutionOutcome, ExecutionRecord, ScheduleStatus,
+};
+pub use lock::{FileLock, RunLock, RunLockGuard};
+
+/// Default time runs in progress get to finish on shutdown.
+pub const DEFAULT_SHUTDOWN_DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
 
 /// Schedule configuration that can be flattened into command structs.
-#[derive(Debug, Args, Clone, Default)]
+#[derive(Debug, Args, Clone)]
 pub struct ScheduleOption {
-    /// Schedule interval (e.g. "5s", "10m", "2h"). If not provided, runs once
-    /// and exits.
-    #[arg(long, help = "Schedule interval (e.g. '5s', '10m', '2h')")]
+    /// Schedule interval (e.g. "5s", "10m", "2h") or 6-field cron expression
+    /// (sec min hour day-of-month month day-of-week). If not provided, runs
+    /// once and exits.
+    #[arg(
+        long,
+        help = "Schedule interval (e.g. '5s', '10m', '2h') or cron expression (e.g. '0 0 3 * * *')"
+    )]
     pub schedule: Option<String>,
+
+    /// Timezone the schedule is evaluated in: "utc", "local" or a fixed
+    /// offset such as "+02:00".
+    #[arg(long, value_name = "TZ", default_value = "utc")]
+    pub schedule_timezone: ScheduleTimezone,
+
+    /// What to do when a run is due while the previous one is still in
+    /// progress.
+    #[arg(long, value_enum, default_value_t = OverlapPolicy::Skip)]
+    pub overlap_policy: OverlapPolicy,
+
+    /// Run once right away at startup, without jitter, instead of waiting
+    /// for the first scheduled run.
+    #[arg(long)]
+    pub run_immediately: bool,
+
+    /// Wait this long (e.g. "30s") before starting the scheduler, so that
+    /// services deployed together do not all start running at once. Applies
+    /// to `--run-immediately` too.
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    pub startup_delay: Option<Duration>,
+
+    /// Delay every scheduled run by a random duration up to this (e.g. "30s").
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    pub schedule_jitter: Option<Duration>,
+
+    /// Number of recent executions kept for status reporting.
+    #[arg(long, default_value_t = DEFAULT_HISTORY_SIZE)]
+    pub history_size: usize,
+
+    /// File to persist the execution history to, so it survives restarts.
+    #[arg(long)]
+    pub history_file: Option<PathBuf>,
+
+    /// Attempts per run, including the first. Failed attempts are retried
+    /// with exponential backoff instead of waiting for the next run.
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    pub retry_attempts: u32,
+
+    /// Delay before the first retry, doubled for every following one.
+    #[arg(long, value_name = "DURATION", default_value = "1s", value_parser = parse_duration)]
+    pub retry_backoff: Duration,
+
+    /// Longest delay between retries.
+    #[arg(long, value_name = "DURATION", default_value = "1m", value_parser = parse_duration)]
+    pub retry_max_backoff: Duration,
+
+    /// How long to wait on shutdown for runs in progress to finish.
+    #[arg(long, value_name = "DURATION", default_value = "30s", value_parser = parse_duration)]
+    pub shutdown_drain_timeout: Duration,
+
+    /// Port to serve the schedule status on as JSON, for health probes.
+    #[arg(long, value_name = "PORT")]
+    pub health_port: Option<u16>,
+
+    /// Consecutive failures after which the health endpoint reports the
+    /// command as unhealthy. Zero never does.
+    #[arg(long, default_value_t = DEFAULT_HEALTH_MAX_FAILURES)]
+    pub health_max_failures: usize,
+
+    /// File locked for the duration of every run, so that only one of the
+    /// instances sharing it runs at a time and the others skip.
+    #[arg(long, value_name = "FILE")]
+    pub lock_file: Option<PathBuf>,
+
+    #[arg(skip)]
+    history: ExecutionHistory,
+
+    /// Next time the scheduler fires, shared between clones like the history.
+    #[arg(skip)]
+    next_run: Arc<std::sync::Mutex<Option<DateTime<Utc>>>>,
+}
+
+impl Default for ScheduleOption {
+    fn default() -> Self {
+        Self {
+            schedule: None,
+            schedule_timezone: ScheduleTimezone::default(),
+            overlap_policy: OverlapPolicy::default(),
+            run_immediately: false,
+            startup_delay: None,
+            schedule_jitter: None,
+            history_size: DEFAULT_HISTORY_SIZE,
+            history_file: None,
+            retry_attempts: RetryPolicy::default().max_attempts,
+            retry_backoff: RetryPolicy::default().initial_backoff,
+            retry_max_backoff: RetryPolicy::default().max_backoff,
+            shutdown_drain_timeout: DEFAULT_SHUTDOWN_DRAIN_TIMEOUT,
+            health_port: None,
+            health_max_failures: DEFAULT_HEALTH_MAX_FAILURES,
+            lock_file: None,
+            history: ExecutionHistory::default(),
+            next_run: Arc::default(),
+        }
+    }
 }
 
 impl ScheduleOption {
@@ -53,6 +213,135 @@ impl ScheduleOption {
     pub fn is_scheduled(&self) -> bool {
         self.schedule.is_some()
     }
+
+    /// Retry policy configured for failed runs.
+    pub fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: self.retry_attempts,
+            initial_backoff: self.retry_backoff,
+            max_backoff: self.retry_max_backoff,
+        }
+    }
+
+    /// Recent run history of the command this schedule belongs to.
+    pub fn status(&self) -> ScheduleStatus {
+        ScheduleStatus::new(self.schedule.clone(), self.history.records())
+            .with_next_run(*self.next_run.lock().expect("next run lock poisoned"))
+    }
+
+    fn set_next_run(&self, next_run: Option<DateTime<Utc>>) {
+        *self.next_run.lock().expect("next run lock poisoned") = next_run;
+    }
+
+    /// Restore the history saved to the history file, if there is one.
+    fn load_history(&self) {
+        let Some(path) = &self.history_file else {
+            return;
+        };
+
+        if let Err(e) = self.history.load(path, self.history_size) {
+            warn!(
+                "Failed to load execution history from {}: {e}",
+                path.display()
+            );
+        }
+    }
+
+    fn record_execution(&self, record: ExecutionRecord) {
+        self.history.push(record, self.history_size);
+
+        let Some(path) = &self.history_file else {
+            return;
+        };
+
+        if let Err(e) = self.history.save(path) {
+            warn!(
+                "Failed to save execution history to {}: {e}",
+                path.display()
+            );
+        }
+    }
+}
+
+/// How a failed run is retried before giving up until the next scheduled run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts per run, including the first. One disables retries.
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled for every following one.
+    pub initial_backoff: Duration,
+    /// Longest delay between retries.
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retrying after `attempt` (starting at 1) failed.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2_u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// What to do with a scheduled run that is due while the previous one is still
+/// in progress.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OverlapPolicy {
+    /// Skip the run.
+    #[default]
+    Skip,
+    /// Start the run once the previous one finishes, keeping at most one run
+    /// waiting.
+    Queue,
+    /// Start the run alongside the previous one.
+    Concurrent,
+}
+
+/// Timezone a schedule's cron expression is evaluated in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScheduleTimezone {
+    #[default]
+    Utc,
+    Local,
+    Fixed(FixedOffset),
+}
+
+impl FromStr for ScheduleTimezone {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "utc" | "z" => Ok(Self::Utc),
+            "local" => Ok(Self::Local),
+            offset => match offset.parse::<FixedOffset>() {
+                Ok(offset) => Ok(Self::Fixed(offset)),
+                Err(_) => bail!(
+                    "Invalid schedule timezone '{s}'. Expected 'utc', 'local' or an offset like '+02:00'"
+                ),
+            },
+        }
+    }
+}
+
+impl fmt::Display for ScheduleTimezone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Utc => write!(f, "UTC"),
+            Self::Local => write!(f, "local time"),
+            Self::Fixed(offset) => write!(f, "UTC{offset}"),
+        }
+    }
 }
 
 /// Trait for commands that can be scheduled to run at intervals.
@@ -64,6 +353,42 @@ pub trait Schedulable: Clone {
     /// Execute the command once - this is what implementors define.
     async fn execute_once(&self) -> Result<()>;
 
+    /// How overlapping scheduled runs are handled.
+    fn overlap_policy(&self) -> OverlapPolicy {
+        self.schedule().overlap_policy
+    }
+
+    /// How failed runs are retried.
+    fn retry_policy(&self) -> RetryPolicy {
+        self.schedule().retry_policy()
+    }
+
+    /// Whether a failed attempt is worth retrying, e.g. a transient RPC error
+    /// rather than bad input. Every error is by default.
+    fn is_retriable(&self, _error: &anyhow::Error) -> bool {
+        true
+    }
+
+    /// Called once on shutdown of a scheduled command, before waiting for the
+    /// runs in progress to finish, e.g. to have them stop at the next safe
+    /// point or to checkpoint their work.
+    async fn on_shutdown(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Lock taken before every run so that only one instance runs per tick,
+    /// e.g. an advisory lock record on chain for replicas on different hosts.
+    /// The schedule's `--lock-file` by default.
+    fn run_lock(&self) -> Option<Arc<dyn RunLock>> {
+        let path = self.schedule().lock_file.clone()?;
+        Some(Arc::new(FileLock::new(path)))
+    }
+
+    /// Recent run history, recorded by [`run_schedulable`].
+    fn status(&self) -> ScheduleStatus {
+        self.schedule().status()
+    }
+
     /// Execute the command, either once or on schedule.
     ///
     /// This method checks if a schedule is provided and either:
@@ -79,44 +404,378 @@ pub trait Schedulable: Clone {
 
 /// Run a schedulable command, handling both one-time and scheduled execution.
 pub async fn run_schedulable<T: Schedulable + Send + Sync + 'static>(command: &T) -> Result<()> {
+    command.schedule().load_history();
+
     match command.schedule().schedule.as_deref() {
         Some(schedule_str) => {
-            let cron_expr = schedule_to_cron(schedule_str)?;
+            let cron_expr = parse_schedule(schedule_str)?;
+            let timezone = command.schedule().schedule_timezone;
+            let jitter = command.schedule().schedule_jitter;
+            let overlap_policy = command.overlap_policy();
 
             let command_clone = command.clone();
-            let job = Job::new_async(cron_expr.as_str(), move |_uuid, _l| {
-                let command = command_clone.clone();
+            let slot = Arc::new(RunSlot::default());
+            let in_flight = InFlightRuns::default();
+            let shutting_down = Arc::new(AtomicBool::new(false));
+            let start_run = {
+                let in_flight = in_flight.clone();
+                let shutting_down = shutting_down.clone();
+                move |jitter: Option<Duration>| {
+                    let command = command_clone.clone();
+                    let slot = slot.clone();
+                    let in_flight = in_flight.clone();
+                    let shutting_down = shutting_down.clone();
+
+                    async move {
+                        if let Some(jitter) = jitter {
+                            tokio::time::sleep(random_delay(jitter)).await;
+                        }
 
-                Box::pin(async move {
-                    if let Err(e) = command.execute_once().await {
-                        error!("Command execution failed: {e}");
+                        let Some(_permit) = slot.acquire(overlap_policy).await else {
+                            warn!("Previous execution still in progress, skipping this one");
+                            metrics::counter!(
+                                "doublezero_scheduled_command_skipped_overlapping_total"
+                            )
+                            .increment(1);
+                            return;
+                        };
+
+                        // Counted before checking for shutdown, so a run either
+                        // starts in time to be drained or not at all
+                        let _in_flight = in_flight.start();
+                        if shutting_down.load(Ordering::Acquire) {
+                            return;
+                        }
+
+                        if let Err(e) = execute_recorded(&command).await {
+                            error!("Command execution failed: {e}");
+                        }
                     }
-                })
-            })?;
+                }
+            };
+            // Typed up front since the closure is only handed to the job in
+            // the timezone's match arm
+            let run = {
+                let start_run = start_run.clone();
+                move |_uuid, _l| -> Pin<Box<dyn Future<Output = ()> + Send>> {
+                    Box::pin(start_run(jitter))
+                }
+            };
+            let job = match timezone {
+                ScheduleTimezone::Utc => Job::new_async_tz(cron_expr.as_str(), Utc, run)?,
+                ScheduleTimezone::Local => Job::new_async_tz(cron_expr.as_str(), Local, run)?,
+                ScheduleTimezone::Fixed(offset) => {
+                    Job::new_async_tz(cron_expr.as_str(), offset, run)?
+                }
+            };
 
-            let sched = JobScheduler::new().await?;
-            sched.add(job).await?;
+            if let Some(delay) = command.schedule().startup_delay {
+                info!("Starting scheduler in {delay:?}");
+                tokio::select! {
+                    _ = tokio::time::sleep(delay) => {}
+                    result = shutdown_signal() => {
+                        result?;
+                        info!("Shutting down before the scheduler started");
+                        return Ok(());
+                    }
+                }
+            }
+
+            let mut sched = JobScheduler::new().await?;
+            let job_id = sched.add(job).await?;
             sched.start().await?;
 
-            info!("Scheduler started. Command will run every {schedule_str}");
+            if command.schedule().run_immediately {
+                info!("Running once at startup");
+                tokio::spawn(start_run(None));
+            }
+
+            // Refresh the next fire time reported by the status whenever it
+            // passes
+            let next_run_updater = tokio::spawn({
+                let mut sched = sched.clone();
+                let schedule = command.schedule().clone();
+                async move {
+                    loop {
+                        let next_run = sched.next_tick_for_job(job_id).await.ok().flatten();
+                        schedule.set_next_run(next_run);
+
+                        let until_next_run = next_run
+                            .and_then(|next_run| (next_run - Utc::now()).to_std().ok())
+                            .unwrap_or_default();
+                        tokio::time::sleep(until_next_run + Duration::from_secs(1)).await;
+                    }
+                }
+            });
+
+            let health_server = match command.schedule().health_port {
+                Some(port) => {
+                    let listener = health::bind(port).await?;
+                    let command = command.clone();
+                    let max_failures = command.schedule().health_max_failures;
+                    Some(tokio::spawn(async move {
+                        if let Err(e) = health::serve(listener, command, max_failures).await {
+                            error!("Health endpoint stopped: {e}");
+                        }
+                    }))
+                }
+                None => None,
+            };
+
+            if is_cron_expression(schedule_str) {
+                info!("Scheduler started. Command will run on '{schedule_str}' ({timezone})");
+            } else {
+                info!("Scheduler started. Command will run every {schedule_str}");
+            }
+            if let Some(jitter) = jitter {
+                info!("Runs are delayed by up to {jitter:?}");
+            }
             info!("Press Ctrl+C to stop...");
 
-            tokio::signal::ctrl_c().await?;
+            shutdown_signal().await?;
             info!("Shutting down...");
+
+            shutting_down.store(true, Ordering::Release);
+            sched.shutdown().await?;
+            next_run_updater.abort();
+            command.schedule().set_next_run(None);
+
+            if let Err(e) = command.on_shutdown().await {
+                error!("Shutdown hook failed: {e}");
+            }
+
+            let drain_timeout = command.schedule().shutdown_drain_timeout;
+            if tokio::time::timeout(drain_timeout, in_flight.drained())
+                .await
+                .is_err()
+            {
+                warn!(
+                    "{} runs still in progress after {drain_timeout:?}, exiting anyway",
+                    in_flight.count()
+                );
+            }
+
+            if let Some(health_server) = health_server {
+                health_server.abort();
+            }
         }
         None => {
-            command.execute_once().await?;
+            execute_recorded(command).await?;
         }
     }
 
     Ok(())
 }
 
-/// Convert a schedule string to a cron expression.
-///
-/// Supports formats like "5s", "10m", "2h" or plain numbers (treated as
-/// seconds). Maximum allowed duration is less than 24 hours.
-fn schedule_to_cron(s: &str) -> Result<String> {
+/// Run `execute_once` under the command's [`RunLock`], retrying failed
+/// attempts under its [`RetryPolicy`], and add the outcome to the command's
+/// history.
+async fn execute_recorded<T: Schedulable>(command: &T) -> Result<()> {
+    // Held across retries, so another instance cannot start in between
+    let _lock = match command.run_lock() {
+        Some(lock) => match lock.try_acquire().await? {
+            Some(guard) => Some(guard),
+            None => {
+                info!("Another instance holds the run lock, skipping this run");
+                metrics::counter!("doublezero_scheduled_command_skipped_locked_total").increment(1);
+                return Ok(());
+            }
+        },
+        None => None,
+    };
+
+    let started_at = Utc::now();
+    let start = Instant::now();
+    let retry_policy = command.retry_policy();
+
+    let mut attempts = 1;
+    let result = loop {
+        let result = command.execute_once().await;
+        let Err(e) = &result else {
+            break result;
+        };
+        if attempts >= retry_policy.max_attempts || !command.is_retriable(e) {
+            break result;
+        }
+
+        let backoff = retry_policy.backoff(attempts);
+        warn!(
+            "Attempt {attempts}/{} failed, retrying in {backoff:?}: {e:#}",
+            retry_policy.max_attempts
+        );
+        metrics::counter!("doublezero_scheduled_command_retries_total").increment(1);
+        tokio::time::sleep(backoff).await;
+        attempts += 1;
+    };
+
+    let elapsed = start.elapsed();
+
+    let (outcome, error) = match &result {
+        Ok(()) => (ExecutionOutcome::Success, None),
+        Err(e) => (ExecutionOutcome::Failure, Some(format!("{e:#}"))),
+    };
+    record_metrics(outcome, elapsed);
+    command.schedule().record_execution(ExecutionRecord {
+        started_at,
+        duration_ms: elapsed.as_millis() as u64,
+        outcome,
+        error,
+        attempts,
+    });
+
+    result
+}
+
+fn record_metrics(outcome: ExecutionOutcome, elapsed: Duration) {
+    metrics::counter!("doublezero_scheduled_command_runs_total", "outcome" => outcome.as_str())
+        .increment(1);
+    metrics::histogram!("doublezero_scheduled_command_run_duration_seconds")
+        .record(elapsed.as_secs_f64());
+
+    if outcome == ExecutionOutcome::Success {
+        metrics::gauge!("doublezero_scheduled_command_last_success_timestamp")
+            .set(Utc::now().timestamp() as f64);
+    }
+}
+
+/// Marks a scheduled execution as in progress until dropped, so a slow run is
+/// not overlapped by the next tick.
+struct RunningGuard<'a>(&'a AtomicBool);
+
+impl<'a> RunningGuard<'a> {
+    /// `None` if an execution is already in progress.
+    fn try_acquire(running: &'a AtomicBool) -> Option<Self> {
+        running
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .ok()
+            .map(|_| Self(running))
+    }
+}
+
+impl Drop for RunningGuard<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+/// Tracks the scheduled runs of a command to apply its [`OverlapPolicy`].
+#[derive(Default)]
+struct RunSlot {
+    /// Set while a run is in progress under [`OverlapPolicy::Skip`].
+    running: AtomicBool,
+    /// Set while a run waits for the previous one under [`OverlapPolicy::Queue`].
+    waiting: AtomicBool,
+    /// Held by the run in progress under [`OverlapPolicy::Queue`].
+    queue: tokio::sync::Mutex<()>,
+}
+
+/// Held for the duration of a scheduled run.
+enum RunPermit<'a> {
+    Running {
+        _guard: RunningGuard<'a>,
+    },
+    Queued {
+        _guard: tokio::sync::MutexGuard<'a, ()>,
+    },
+    Concurrent,
+}
+
+impl RunSlot {
+    /// Wait for the run to be allowed to start, or `None` if it is skipped.
+    async fn acquire(&self, policy: OverlapPolicy) -> Option<RunPermit<'_>> {
+        match policy {
+            OverlapPolicy::Skip => RunningGuard::try_acquire(&self.running)
+                .map(|guard| RunPermit::Running { _guard: guard }),
+            OverlapPolicy::Queue => {
+                let _waiting = RunningGuard::try_acquire(&self.waiting)?;
+                Some(RunPermit::Queued {
+                    _guard: self.queue.lock().await,
+                })
+            }
+            OverlapPolicy::Concurrent => Some(RunPermit::Concurrent),
+        }
+    }
+}
+
+/// Counts the scheduled runs in progress, so shutdown can wait for them.
+#[derive(Clone)]
+struct InFlightRuns(Arc<tokio::sync::watch::Sender<usize>>);
+
+impl Default for InFlightRuns {
+    fn default() -> Self {
+        Self(Arc::new(tokio::sync::watch::Sender::new(0)))
+    }
+}
+
+/// Counts a run as in progress until dropped.
+struct InFlightGuard(InFlightRuns);
+
+impl InFlightRuns {
+    fn start(&self) -> InFlightGuard {
+        self.0.send_modify(|count| *count += 1);
+        InFlightGuard(self.clone())
+    }
+
+    fn count(&self) -> usize {
+        *self.0.borrow()
+    }
+
+    /// Wait until no run is in progress.
+    async fn drained(&self) {
+        // The sender is held by `self`, so the channel cannot close
+        let _ = self.0.subscribe().wait_for(|count| *count == 0).await;
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.0.0.send_modify(|count| *count -= 1);
+    }
+}
+
+/// Resolve on Ctrl+C or SIGTERM.
+async fn shutdown_signal() -> Result<()> {
+    let mut sigterm = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())?;
+    tokio::select! {
+        _ = sigterm.recv() => {}
+        result = tokio::signal::ctrl_c() => result?,
+    }
+    Ok(())
+}
+
+/// Random duration between zero and `max`.
+fn random_delay(max: Duration) -> Duration {
+    // Every `RandomState` is randomly seeded, which is plenty to spread runs out
+    let random = RandomState::new().build_hasher().finish();
+    max.mul_f64(random as f64 / u64::MAX as f64)
+}
+
+/// Convert a schedule string, interval or cron expression, to a cron
+/// expression.
+fn parse_schedule(s: &str) -> Result<String> {
+    if !is_cron_expression(s) {
+        return schedule_to_cron(s);
+    }
+
+    let fields: Vec<&str> = s.split_whitespace().collect();
+    if fields.len() != 6 {
+        bail!(
+            "Cron schedule '{s}' has {} fields. Expected 6: sec min hour day-of-month month day-of-week",
+            fields.len()
+        );
+    }
+
+    Ok(fields.join(" "))
+}
+
+/// Intervals are a single token, so whitespace between fields means cron.
+fn is_cron_expression(s: &str) -> bool {
+    s.trim().contains(char::is_whitespace)
+}
+
+/// Parse a duration like "5s", "10m", "2h" or a plain number (treated as
+/// seconds).
+fn parse_duration(s: &str) -> Result<Duration> {
     let s = s.trim().to_lowercase();
 
     let duration = if let Some(num_str) = s.strip_suffix('s') {
@@ -133,6 +792,17 @@ fn schedule_to_cron(s: &str) -> Result<String> {
         Duration::from_secs(secs)
     };
 
+    Ok(duration)
+}
+
+/// Convert a schedule interval to a cron expression.
+///
+/// Supports the formats of [`parse_duration`]. Maximum allowed duration is
+/// less than 24 hours.
+fn schedule_to_cron(s: &str) -> Result<String> {
+    let duration = parse_duration(s)?;
+    let s = s.trim().to_lowercase();
+
     // Check if duration is 24 hours or more.
     if duration.as_secs() >= 24 * 3600 {
         bail!("Schedule duration '{s}' is too long. Maximum allowed is less than 24 hours");
@@ -179,6 +849,40 @@ mod tests {
         assert!(schedule_to_cron("23h").is_ok());
     }
 
+    #[test]
+    fn test_parse_schedule() {
+        // Intervals are converted.
+        assert_eq!(parse_schedule("10m").unwrap(), "0 */10 * * * *");
+
+        // Cron expressions are passed through with normalized whitespace.
+        assert_eq!(parse_schedule("0 0 3 * * *").unwrap(), "0 0 3 * * *");
+        assert_eq!(
+            parse_schedule(" 0  30 2 * * Mon-Fri ").unwrap(),
+            "0 30 2 * * Mon-Fri"
+        );
+
+        // Only 6-field expressions are accepted.
+        assert!(parse_schedule("0 3 * * *").is_err());
+        assert!(parse_schedule("0 0 3 * * * 2026").is_err());
+    }
+
+    #[test]
+    fn test_schedule_timezone() {
+        assert_eq!(
+            "utc".parse::<ScheduleTimezone>().unwrap(),
+            ScheduleTimezone::Utc
+        );
+        assert_eq!(
+            "Local".parse::<ScheduleTimezone>().unwrap(),
+            ScheduleTimezone::Local
+        );
+        assert_eq!(
+            "+02:00".parse::<ScheduleTimezone>().unwrap(),
+            ScheduleTimezone::Fixed(FixedOffset::east_opt(2 * 3600).unwrap())
+        );
+        assert!("Europe/Berlin".parse::<ScheduleTimezone>().is_err());
+    }
+
     #[test]
     fn test_schedule() {
         let schedule = ScheduleOption::default();
@@ -186,7 +890,200 @@ mod tests {
 
         let schedule = ScheduleOption {
             schedule: Some("5m".to_string()),
+            ..Default::default()
         };
         assert!(schedule.is_scheduled());
     }
+
+    #[test]
+    fn test_running_guard_prevents_overlap() {
+        let running = AtomicBool::new(false);
+
+        let guard = RunningGuard::try_acquire(&running);
+        assert!(guard.is_some());
+        assert!(RunningGuard::try_acquire(&running).is_none());
+
+        drop(guard);
+        assert!(RunningGuard::try_acquire(&running).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_run_slot_overlap_policies() {
+        let slot = RunSlot::default();
+
+        let running = slot.acquire(OverlapPolicy::Skip).await;
+        assert!(running.is_some());
+        assert!(slot.acquire(OverlapPolicy::Skip).await.is_none());
+        drop(running);
+
+        // Concurrent runs always start.
+        assert!(slot.acquire(OverlapPolicy::Concurrent).await.is_some());
+
+        // Only one queued run may wait for the run in progress.
+        let in_progress = slot.acquire(OverlapPolicy::Queue).await;
+        assert!(in_progress.is_some());
+        let waiting = RunningGuard::try_acquire(&slot.waiting);
+        assert!(slot.acquire(OverlapPolicy::Queue).await.is_none());
+
+        drop(waiting);
+        drop(in_progress);
+        assert!(slot.acquire(OverlapPolicy::Queue).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_in_flight_runs_drain() {
+        let in_flight = InFlightRuns::default();
+        in_flight.drained().await;
+
+        let run = in_flight.start();
+        assert_eq!(in_flight.count(), 1);
+        assert!(
+            tokio::time::timeout(Duration::from_millis(10), in_flight.drained())
+                .await
+                .is_err()
+        );
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            drop(run);
+        });
+        tokio::time::timeout(Duration::from_secs(5), in_flight.drained())
+            .await
+            .unwrap();
+        assert_eq!(in_flight.count(), 0);
+    }
+
+    #[test]
+    fn test_random_delay() {
+        let max = Duration::from_secs(30);
+        for _ in 0..100 {
+            assert!(random_delay(max) <= max);
+        }
+        assert_eq!(random_delay(Duration::ZERO), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_retry_backoff() {
+        let policy = RetryPolicy {
+            max_attempts: 5,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(5),
+        };
+        assert_eq!(policy.backoff(1), Duration::from_secs(1));
+        assert_eq!(policy.backoff(2), Duration::from_secs(2));
+        assert_eq!(policy.backoff(3), Duration::from_secs(4));
+        assert_eq!(policy.backoff(4), Duration::from_secs(5));
+        assert_eq!(policy.backoff(40), Duration::from_secs(5));
+    }
+
+    #[derive(Clone)]
+    struct FlakyCommand {
+        schedule: ScheduleOption,
+        failures: Arc<std::sync::atomic::AtomicU32>,
+        retriable: bool,
+    }
+
+    #[async_trait::async_trait]
+    impl Schedulable for FlakyCommand {
+        fn schedule(&self) -> &ScheduleOption {
+            &self.schedule
+        }
+
+        fn is_retriable(&self, _error: &anyhow::Error) -> bool {
+            self.retriable
+        }
+
+        async fn execute_once(&self) -> Result<()> {
+            let remaining = self.failures.load(Ordering::Acquire);
+            if remaining > 0 {
+                self.failures.store(remaining - 1, Ordering::Release);
+                bail!("rpc timeout");
+            }
+            Ok(())
+        }
+    }
+
+    fn flaky_command(failures: u32, retriable: bool) -> FlakyCommand {
+        FlakyCommand {
+            schedule: ScheduleOption {
+                retry_attempts: 3,
+                retry_backoff: Duration::ZERO,
+                ..Default::default()
+            },
+            failures: Arc::new(failures.into()),
+            retriable,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_schedulable_retries_failures() {
+        let command = flaky_command(2, true);
+        assert!(run_schedulable(&command).await.is_ok());
+        let status = command.status();
+        assert_eq!(status.consecutive_failures, 0);
+        assert_eq!(status.executions[0].attempts, 3);
+
+        let command = flaky_command(3, true);
+        assert!(run_schedulable(&command).await.is_err());
+        assert_eq!(command.status().executions[0].attempts, 3);
+
+        let command = flaky_command(1, false);
+        assert!(run_schedulable(&command).await.is_err());
+        assert_eq!(command.status().executions[0].attempts, 1);
+    }
+
+    #[derive(Clone)]
+    struct FailingCommand {
+        schedule: ScheduleOption,
+    }
+
+    #[async_trait::async_trait]
+    impl Schedulable for FailingCommand {
+        fn schedule(&self) -> &ScheduleOption {
+            &self.schedule
+        }
+
+        async fn execute_once(&self) -> Result<()> {
+            bail!("unreachable endpoint")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_schedulable_skips_when_locked() {
+        let lock_file = std::env::temp_dir().join(format!(
+            "doublezero-scheduled-command-run-{}.lock",
+            std::process::id()
+        ));
+        let command = FailingCommand {
+            schedule: ScheduleOption {
+                lock_file: Some(lock_file.clone()),
+                ..Default::default()
+            },
+        };
+
+        let held = FileLock::new(&lock_file).try_acquire().await.unwrap();
+        assert!(run_schedulable(&command).await.is_ok());
+        assert!(command.status().executions.is_empty());
+
+        drop(held);
+        assert!(run_schedulable(&command).await.is_err());
+        assert_eq!(command.status().executions.len(), 1);
+        std::fs::remove_file(&lock_file).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_run_schedulable_records_execution() {
+        let command = FailingCommand {
+            schedule: ScheduleOption::default(),
+        };
+        assert!(run_schedulable(&command.clone()).await.is_err());
+
+        let status = command.status();
+        assert_eq!(status.consecutive_failures, 1);
+        assert_eq!(status.executions.len(), 1);
+        assert_eq!(
+            status.executions[0].error.as_deref(),
+            Some("unreachable endpoint")
+        );
+    }
 }
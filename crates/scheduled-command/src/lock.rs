@@ -0,0 +1,89 @@
+//! Locks keeping replicas of a scheduled command from running the same tick.
+//!
+//! A run only starts once the command's [`RunLock`] is taken, and is skipped
+//! when another instance holds it. Locks are advisory: every replica has to
+//! be given the same one.
+
+use std::{
+    fs::{OpenOptions, TryLockError},
+    path::PathBuf,
+};
+
+use anyhow::{Context, Result};
+
+/// Held for the duration of a run, releasing the lock when dropped.
+pub struct RunLockGuard {
+    _held: Box<dyn Send + Sync>,
+}
+
+impl RunLockGuard {
+    /// Wrap whatever releases the lock on drop, e.g. a file handle or a
+    /// guard that clears a lock record.
+    pub fn new<T: Send + Sync + 'static>(held: T) -> Self {
+        Self {
+            _held: Box::new(held),
+        }
+    }
+}
+
+/// Lock shared by every instance of a command, taken before each run.
+#[async_trait::async_trait]
+pub trait RunLock: Send + Sync {
+    /// Take the lock, or `None` if another instance holds it.
+    async fn try_acquire(&self) -> Result<Option<RunLockGuard>>;
+}
+
+/// Exclusive lock on a file, for instances on one host or sharing a
+/// filesystem that supports locks.
+#[derive(Debug, Clone)]
+pub struct FileLock {
+    path: PathBuf,
+}
+
+impl FileLock {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait::async_trait]
+impl RunLock for FileLock {
+    async fn try_acquire(&self) -> Result<Option<RunLockGuard>> {
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open lock file {}", self.path.display()))?;
+
+        match file.try_lock() {
+            Ok(()) => Ok(Some(RunLockGuard::new(file))),
+            Err(TryLockError::WouldBlock) => Ok(None),
+            Err(TryLockError::Error(e)) => {
+                Err(e).with_context(|| format!("Failed to lock {}", self.path.display()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_file_lock_is_exclusive() {
+        let path = std::env::temp_dir().join(format!(
+            "doublezero-scheduled-command-{}.lock",
+            std::process::id()
+        ));
+        let lock = FileLock::new(&path);
+
+        let held = lock.try_acquire().await.unwrap();
+        assert!(held.is_some());
+        assert!(FileLock::new(&path).try_acquire().await.unwrap().is_none());
+
+        drop(held);
+        assert!(lock.try_acquire().await.unwrap().is_some());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
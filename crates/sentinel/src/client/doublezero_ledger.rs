@@ -1,11 +1,11 @@
-use crate::{Result, new_transaction};
+use crate::{Error, Result, new_transaction};
 
 use doublezero_program_tools::instruction::try_build_instruction;
 use doublezero_serviceability::{
     instructions::DoubleZeroInstruction,
     pda::{get_accesspass_pda, get_globalstate_pda},
     processors::accesspass::set::SetAccessPassArgs,
-    state::accesspass::AccessPassType,
+    state::accesspass::{AccessPass, AccessPassType},
 };
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_commitment_config::CommitmentConfig;
@@ -16,9 +16,31 @@ use solana_sdk::{
 };
 use solana_system_interface::program as system_program;
 use std::{net::Ipv4Addr, sync::Arc};
-use tracing::info;
+use tracing::{info, warn};
 use url::Url;
 
+/// Access pass found on the DZ ledger for a validator's service key and IP
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessPassState {
+    /// No pass has been issued yet
+    Missing,
+    /// A pass exists for the validator
+    Present,
+    /// A pass exists for something other than the validator; it is never overwritten
+    /// automatically
+    Mismatched,
+}
+
+impl AccessPassState {
+    pub fn of(pass_type: Option<&AccessPassType>, validator_id: &Pubkey) -> Self {
+        match pass_type {
+            None => Self::Missing,
+            Some(AccessPassType::SolanaValidator(id)) if id == validator_id => Self::Present,
+            Some(_) => Self::Mismatched,
+        }
+    }
+}
+
 pub struct DzRpcClient {
     client: RpcClient,
     payer: Arc<Keypair>,
@@ -37,6 +59,71 @@ impl DzRpcClient {
         }
     }
 
+    /// Fetch the access pass derived for the service key and client IP, if it exists
+    pub async fn get_access_pass(
+        &self,
+        service_key: &Pubkey,
+        client_ip: &Ipv4Addr,
+    ) -> Result<Option<AccessPass>> {
+        let (pass_pk, _) = get_accesspass_pda(&self.serviceability_id, client_ip, service_key);
+        let account = self
+            .client
+            .get_account_with_commitment(&pass_pk, CommitmentConfig::confirmed())
+            .await?
+            .value;
+
+        account
+            .map(|account| {
+                AccessPass::try_from(&account.data[..])
+                    .map_err(|err| Error::Deserialize(format!("access pass {pass_pk}: {err:?}")))
+            })
+            .transpose()
+    }
+
+    /// Issue an access pass unless an equivalent one already exists on the DZ ledger
+    ///
+    /// The pass address is derived deterministically from the client IP and service key, so a
+    /// pass left behind by an interrupted run is detected here and not issued a second time.
+    /// A pass held by a different validator fails with [`Error::AccessPassMismatch`] instead of
+    /// being overwritten, leaving the split state to be reconciled explicitly.
+    /// Returns `None` when no transaction was needed.
+    pub async fn ensure_access_pass(
+        &self,
+        service_key: &Pubkey,
+        client_ip: &Ipv4Addr,
+        validator_id: &Pubkey,
+    ) -> Result<Option<Signature>> {
+        let pass = self.get_access_pass(service_key, client_ip).await?;
+        let pass_type = pass.as_ref().map(|pass| &pass.accesspass_type);
+
+        match AccessPassState::of(pass_type, validator_id) {
+            AccessPassState::Present => {
+                info!(%validator_id, %client_ip, user = %service_key, "access pass already present");
+                metrics::counter!("doublezero_sentinel_access_pass_already_present").increment(1);
+                Ok(None)
+            }
+            AccessPassState::Mismatched => {
+                warn!(
+                    %validator_id,
+                    %client_ip,
+                    user = %service_key,
+                    existing = ?pass_type,
+                    "access pass exists for a different validator; not reissuing"
+                );
+                metrics::counter!("doublezero_sentinel_access_pass_mismatched").increment(1);
+                Err(Error::AccessPassMismatch {
+                    service_key: *service_key,
+                    client_ip: *client_ip,
+                    existing: format!("{pass_type:?}"),
+                })
+            }
+            AccessPassState::Missing => self
+                .issue_access_pass(service_key, client_ip, validator_id)
+                .await
+                .map(Some),
+        }
+    }
+
     pub async fn issue_access_pass(
         &self,
         service_key: &Pubkey,
@@ -26,7 +26,8 @@ use solana_client::{
 use solana_commitment_config::{CommitmentConfig, CommitmentLevel};
 use solana_sdk::{
     compute_budget::ComputeBudgetInstruction,
-    instruction::CompiledInstruction,
+    instruction::{CompiledInstruction, Instruction},
+    pubkey,
     pubkey::Pubkey,
     signature::{Keypair, Signature},
     signer::Signer,
@@ -36,6 +37,7 @@ use solana_transaction_status_client_types::{
     EncodedTransaction, TransactionBinaryEncoding, UiTransactionEncoding,
 };
 use std::{
+    collections::HashMap,
     net::{Ipv4Addr, SocketAddr},
     sync::Arc,
 };
@@ -43,6 +45,21 @@ use url::Url;
 
 const ACCESS_REQUEST_ACCOUNT_INDEX: usize = 2;
 
+// There should be ~5k CU buffer with these limits.
+pub const GRANT_ACCESS_COMPUTE_UNIT_LIMIT: u32 = 16_000;
+pub const DENY_ACCESS_COMPUTE_UNIT_LIMIT: u32 = 12_000;
+pub const MEMO_COMPUTE_UNIT_LIMIT: u32 = 5_000;
+
+// SPL Memo program, used to record why an access request was denied
+pub const MEMO_PROGRAM_ID: Pubkey = pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+// Grant or deny instructions batched into one transaction; each adds two account keys and an
+// instruction, and this many stay well under the 1232-byte transaction size limit.
+pub const MAX_ACCESS_DECISIONS_PER_TRANSACTION: usize = 10;
+
+// TODO: Consider using a priority fee API instead of a fixed price.
+pub const COMPUTE_UNIT_PRICE_MICRO_LAMPORTS: u64 = 100_000;
+
 pub struct SolRpcClient {
     client: RpcClient,
     payer: Arc<Keypair>,
@@ -61,26 +78,39 @@ impl SolRpcClient {
         access_request_key: &Pubkey,
         rent_beneficiary_key: &Pubkey,
     ) -> Result<Signature> {
+        self.grant_access_batch(&[(*access_request_key, *rent_beneficiary_key)])
+            .await
+    }
+
+    /// Grant several access requests, given as (access request, rent beneficiary) pairs, in a
+    /// single transaction; at most `MAX_ACCESS_DECISIONS_PER_TRANSACTION` fit
+    pub async fn grant_access_batch(&self, requests: &[(Pubkey, Pubkey)]) -> Result<Signature> {
         let signer = &self.payer;
-        let grant_ix = try_build_instruction(
-            &passport_id(),
-            GrantAccessAccounts::new(&signer.pubkey(), access_request_key, rent_beneficiary_key),
-            &PassportInstructionData::GrantAccess,
-        )?;
+        let mut instructions = requests
+            .iter()
+            .map(|(access_request_key, rent_beneficiary_key)| {
+                try_build_instruction(
+                    &passport_id(),
+                    GrantAccessAccounts::new(
+                        &signer.pubkey(),
+                        access_request_key,
+                        rent_beneficiary_key,
+                    ),
+                    &PassportInstructionData::GrantAccess,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
 
         let recent_blockhash = self.client.get_latest_blockhash().await?;
 
-        // There should be ~5k CU buffer with this limit.
-        let compute_limit_ix = ComputeBudgetInstruction::set_compute_unit_limit(16_000);
-
-        // TODO: Consider using a priority fee API instead of a fixed price.
-        let compute_price_ix = ComputeBudgetInstruction::set_compute_unit_price(100_000);
+        instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(
+            GRANT_ACCESS_COMPUTE_UNIT_LIMIT * requests.len() as u32,
+        ));
+        instructions.push(ComputeBudgetInstruction::set_compute_unit_price(
+            COMPUTE_UNIT_PRICE_MICRO_LAMPORTS,
+        ));
 
-        let transaction = new_transaction(
-            &[grant_ix, compute_limit_ix, compute_price_ix],
-            &[signer],
-            recent_blockhash,
-        );
+        let transaction = new_transaction(&instructions, &[signer], recent_blockhash);
 
         Ok(self
             .client
@@ -88,27 +118,48 @@ impl SolRpcClient {
             .await?)
     }
 
-    pub async fn deny_access(&self, access_request_key: &Pubkey) -> Result<Signature> {
-        let signer = &self.payer;
-        let deny_ix = try_build_instruction(
-            &passport_id(),
-            DenyAccessAccounts::new(&signer.pubkey(), access_request_key),
-            &PassportInstructionData::DenyAccess,
-        )?;
+    pub async fn deny_access(
+        &self,
+        access_request_key: &Pubkey,
+        memo: Option<String>,
+    ) -> Result<Signature> {
+        self.deny_access_batch(&[(*access_request_key, memo)]).await
+    }
 
-        // There should be ~5k CU buffer with this limit.
-        let compute_limit_ix = ComputeBudgetInstruction::set_compute_unit_limit(12_000);
+    /// Deny several access requests, given as (access request, memo) pairs, in a single
+    /// transaction; at most `MAX_ACCESS_DECISIONS_PER_TRANSACTION` fit. A memo, when given, is
+    /// written right after its deny instruction.
+    pub async fn deny_access_batch(
+        &self,
+        denials: &[(Pubkey, Option<String>)],
+    ) -> Result<Signature> {
+        let signer = &self.payer;
+        let mut instructions = Vec::with_capacity(denials.len() * 2 + 2);
+        let mut compute_unit_limit = 0;
+        for (access_request_key, memo) in denials {
+            instructions.push(try_build_instruction(
+                &passport_id(),
+                DenyAccessAccounts::new(&signer.pubkey(), access_request_key),
+                &PassportInstructionData::DenyAccess,
+            )?);
+            compute_unit_limit += DENY_ACCESS_COMPUTE_UNIT_LIMIT;
+
+            if let Some(memo) = memo {
+                instructions.push(memo_instruction(memo));
+                compute_unit_limit += MEMO_COMPUTE_UNIT_LIMIT;
+            }
+        }
 
-        // TODO: Consider using a priority fee API instead of a fixed price.
-        let compute_price_ix = ComputeBudgetInstruction::set_compute_unit_price(100_000);
+        instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(
+            compute_unit_limit,
+        ));
+        instructions.push(ComputeBudgetInstruction::set_compute_unit_price(
+            COMPUTE_UNIT_PRICE_MICRO_LAMPORTS,
+        ));
 
         let recent_blockhash = self.client.get_latest_blockhash().await?;
 
-        let transaction = new_transaction(
-            &[deny_ix, compute_limit_ix, compute_price_ix],
-            &[signer],
-            recent_blockhash,
-        );
+        let transaction = new_transaction(&instructions, &[signer], recent_blockhash);
 
         Ok(self
             .client
@@ -116,6 +167,24 @@ impl SolRpcClient {
             .await?)
     }
 
+    /// Public key of the signer paying for grant and deny transactions
+    pub fn payer_pubkey(&self) -> Pubkey {
+        self.payer.pubkey()
+    }
+
+    /// Signer balance in lamports
+    pub async fn get_balance(&self) -> Result<u64> {
+        Ok(self.client.get_balance(&self.payer.pubkey()).await?)
+    }
+
+    /// Request an airdrop to the signer; only honored by devnet and local validators
+    pub async fn request_airdrop(&self, lamports: u64) -> Result<Signature> {
+        Ok(self
+            .client
+            .request_airdrop(&self.payer.pubkey(), lamports)
+            .await?)
+    }
+
     pub async fn get_access_requests_from_signature(
         &self,
         signature: Signature,
@@ -247,6 +316,41 @@ impl SolRpcClient {
             });
         Ok(address)
     }
+
+    /// Activated stake delegated to the validator's vote accounts, or `None` if it has none in
+    /// the current (non-delinquent) vote-account set
+    pub async fn get_activated_stake(&self, validator_id: &Pubkey) -> Result<Option<u64>> {
+        let node_pubkey = validator_id.to_string();
+        let stake = self
+            .client
+            .get_vote_accounts()
+            .await?
+            .current
+            .iter()
+            .filter(|vote_account| vote_account.node_pubkey == node_pubkey)
+            .map(|vote_account| vote_account.activated_stake)
+            .reduce(|total, stake| total + stake);
+        Ok(stake)
+    }
+
+    /// Gossip IPv4 addresses of every cluster node, fetched in a single request
+    pub async fn get_gossip_ips(&self) -> Result<HashMap<Pubkey, Ipv4Addr>> {
+        let ips = self
+            .client
+            .get_cluster_nodes()
+            .await?
+            .into_iter()
+            .filter_map(|contact| {
+                let pubkey = contact.pubkey.parse::<Pubkey>().ok()?;
+                let ip = match contact.gossip? {
+                    SocketAddr::V4(addr_v4) => *addr_v4.ip(),
+                    SocketAddr::V6(addr_v6) => addr_v6.ip().to_ipv4_mapped()?,
+                };
+                Some((pubkey, ip))
+            })
+            .collect();
+        Ok(ips)
+    }
 }
 
 pub struct SolPubsubClient {
@@ -304,6 +408,14 @@ fn is_request_access_instruction(ix: &CompiledInstruction, static_account_keys:
             == PassportInstructionData::REQUEST_ACCESS
 }
 
+fn memo_instruction(memo: &str) -> Instruction {
+    Instruction {
+        program_id: MEMO_PROGRAM_ID,
+        accounts: vec![],
+        data: memo.as_bytes().to_vec(),
+    }
+}
+
 pub struct PreviousEpochSlots {
     current: u64,
     step: u64,
@@ -352,4 +464,12 @@ mod test {
             &(start_slot - 3 * PreviousEpochSlots::SLOTS_PER_EPOCH),
         );
     }
+
+    #[test]
+    fn test_memo_instruction() {
+        let ix = memo_instruction("denied: not_in_gossip");
+        assert_eq!(ix.program_id, MEMO_PROGRAM_ID);
+        assert!(ix.accounts.is_empty());
+        assert_eq!(ix.data, b"denied: not_in_gossip");
+    }
 }
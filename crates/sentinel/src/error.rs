@@ -3,9 +3,13 @@ use solana_client::{
     client_error::{ClientError, ClientErrorKind, reqwest::StatusCode},
     nonblocking::pubsub_client::PubsubClientError,
 };
-use solana_sdk::signature::{ParseSignatureError, Signature};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{ParseSignatureError, Signature},
+};
 use std::{
     future::Future,
+    net::Ipv4Addr,
     sync::atomic::{AtomicUsize, Ordering},
     time::Duration,
 };
@@ -16,6 +20,12 @@ pub type Result<T = ()> = std::result::Result<T, Error>;
 
 #[derive(Debug, Error)]
 pub enum Error {
+    #[error("access pass for user {service_key} at {client_ip} is held by {existing}")]
+    AccessPassMismatch {
+        service_key: Pubkey,
+        client_ip: Ipv4Addr,
+        existing: String,
+    },
     #[error("base64 decode error: {0}")]
     Base64Decode(#[from] base64::DecodeError),
     #[error("bincode deserialization error: {0}")]
@@ -24,6 +34,8 @@ pub enum Error {
     BorshIo(#[from] borsh::io::Error),
     #[error("deserialization error: {0}")]
     Deserialize(String),
+    #[error("dedupe store error: {0}")]
+    DedupeStore(String),
     #[error("instruction not found in transaction: {0}")]
     InstructionNotFound(Signature),
     #[error("invalid instruction data: {0}")]
@@ -34,6 +46,8 @@ pub enum Error {
     MissingProgramId(Signature),
     #[error("no transaction id signature")]
     MissingTxnSignature,
+    #[error("notification error: {0}")]
+    Notification(String),
     #[error("pubsub client error: {0}")]
     PubsubClient(Box<PubsubClientError>),
     #[error("request channel error: {0}")]
@@ -62,7 +76,23 @@ impl From<PubsubClientError> for Error {
     }
 }
 
+/// Default number of retries applied to transient RPC failures
+pub const DEFAULT_RPC_RETRIES: usize = 8;
+
 pub async fn rpc_with_retry<F, Fut, T>(operation: F, label: &'static str) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    rpc_with_retry_times(operation, label, DEFAULT_RPC_RETRIES).await
+}
+
+/// Like `rpc_with_retry`, with an explicit upper bound on the number of retries
+pub async fn rpc_with_retry_times<F, Fut, T>(
+    operation: F,
+    label: &'static str,
+    max_times: usize,
+) -> Result<T>
 where
     F: FnMut() -> Fut,
     Fut: Future<Output = Result<T>>,
@@ -72,7 +102,7 @@ where
     let backoff = ExponentialBuilder::default()
         .with_min_delay(Duration::from_secs(1))
         .with_max_delay(Duration::from_secs(30))
-        .with_max_times(8)
+        .with_max_times(max_times)
         .with_jitter();
 
     (move || op())
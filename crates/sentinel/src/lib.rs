@@ -9,6 +9,10 @@ use solana_sdk::{
     signer::Signer,
     transaction::VersionedTransaction,
 };
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
 
 pub mod client;
 pub mod constants;
@@ -25,14 +29,71 @@ pub struct AccessId {
     mode: AccessMode,
 }
 
+const OFFCHAIN_MSG_SUPPORTED_VSN: u8 = 0;
+
+/// Serialized offchain messages of access requests, keyed by their raw message
+///
+/// The message signed by a validator does not include the signature itself, so access modes
+/// differing only by signature (e.g. the same request resubmitted) share an entry. Meant to live
+/// for a batch of requests rather than for the lifetime of the sentinel.
+#[derive(Debug, Default)]
+pub struct AccessMessageCache {
+    messages: Mutex<HashMap<String, Arc<Vec<u8>>>>,
+}
+
+impl AccessMessageCache {
+    /// Serialized offchain message of `access_mode`, serializing it on first use
+    pub fn serialized_message(&self, access_mode: &AccessMode) -> Result<Arc<Vec<u8>>> {
+        let raw_message = AccessRequest::access_request_message(access_mode);
+        if let Some(serialized_msg) = self.messages.lock().unwrap().get(&raw_message) {
+            metrics::counter!("doublezero_sentinel_access_message_cache_hit").increment(1);
+            return Ok(serialized_msg.clone());
+        }
+
+        let serialized_msg = Arc::new(serialize_raw_message(&raw_message)?);
+        self.messages
+            .lock()
+            .unwrap()
+            .insert(raw_message, serialized_msg.clone());
+        Ok(serialized_msg)
+    }
+
+    pub fn len(&self) -> usize {
+        self.messages.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+fn serialize_raw_message(raw_message: &str) -> Result<Vec<u8>> {
+    let offchain_msg = OffchainMessage::new(OFFCHAIN_MSG_SUPPORTED_VSN, raw_message.as_bytes())?;
+    Ok(offchain_msg.serialize()?)
+}
+
 // Verify access request by and return validator_id (pubkey) if successful
 pub fn verify_access_request(access_mode: &AccessMode) -> Result<Pubkey> {
-    const OFFCHAIN_MSG_SUPPORTED_VSN: u8 = 0;
-
     let raw_message = AccessRequest::access_request_message(access_mode);
-    let offchain_msg = OffchainMessage::new(OFFCHAIN_MSG_SUPPORTED_VSN, raw_message.as_bytes())?;
-    let serialized_msg = offchain_msg.serialize()?;
+    let serialized_msg = serialize_raw_message(&raw_message)?;
 
+    verify_access_request_message(access_mode, &serialized_msg)
+}
+
+/// Same as [`verify_access_request`], taking the serialized message from `cache`
+pub fn verify_access_request_cached(
+    access_mode: &AccessMode,
+    cache: &AccessMessageCache,
+) -> Result<Pubkey> {
+    let serialized_msg = cache.serialized_message(access_mode)?;
+
+    verify_access_request_message(access_mode, &serialized_msg)
+}
+
+fn verify_access_request_message(
+    access_mode: &AccessMode,
+    serialized_msg: &[u8],
+) -> Result<Pubkey> {
     // Get the attestation
     let attestation = match access_mode {
         AccessMode::SolanaValidator(attestation) => attestation,
@@ -42,7 +103,7 @@ pub fn verify_access_request(access_mode: &AccessMode) -> Result<Pubkey> {
     // Get signature from attestation
     let signature: Signature = attestation.ed25519_signature.into();
 
-    if !signature.verify(attestation.validator_id.as_array(), &serialized_msg) {
+    if !signature.verify(attestation.validator_id.as_array(), serialized_msg) {
         return Err(Error::SignatureVerify);
     }
 
@@ -160,4 +221,45 @@ mod tests {
         let access_mode = AccessMode::SolanaValidator(attestation);
         assert!(verify_access_request(&access_mode).is_err());
     }
+
+    #[test]
+    fn test_signature_verification_cached() {
+        let service_key = Pubkey::new_unique();
+        let validator_id = Keypair::new();
+
+        let mut attestation = SolanaValidatorAttestation {
+            validator_id: validator_id.pubkey(),
+            service_key,
+            ed25519_signature: [0; 64],
+        };
+
+        let raw_message =
+            AccessRequest::access_request_message(&AccessMode::SolanaValidator(attestation));
+        let offchain_msg = OffchainMessage::new(0u8, raw_message.as_bytes()).unwrap();
+        let unsigned_mode = AccessMode::SolanaValidator(attestation);
+        attestation.ed25519_signature = validator_id
+            .sign_message(&offchain_msg.serialize().unwrap())
+            .into();
+        let access_mode = AccessMode::SolanaValidator(attestation);
+
+        let cache = AccessMessageCache::default();
+        assert_eq!(
+            verify_access_request_cached(&access_mode, &cache).unwrap(),
+            validator_id.pubkey()
+        );
+        // The unsigned request shares the cached message but still fails verification
+        assert!(matches!(
+            verify_access_request_cached(&unsigned_mode, &cache),
+            Err(Error::SignatureVerify)
+        ));
+        assert_eq!(cache.len(), 1);
+
+        // A request for another service key gets its own message
+        let other_mode = AccessMode::SolanaValidator(SolanaValidatorAttestation {
+            service_key: Pubkey::new_unique(),
+            ..attestation
+        });
+        assert!(verify_access_request_cached(&other_mode, &cache).is_err());
+        assert_eq!(cache.len(), 2);
+    }
 }
@@ -1,14 +1,20 @@
 use clap::Parser;
 use doublezero_ledger_sentinel::{
+    client::{doublezero_ledger::DzRpcClient, solana::SolRpcClient},
     constants::ENV_PREVIOUS_LEADER_EPOCHS,
-    sentinel::{PollingSentinel, ReqListener, Sentinel},
-    settings::{AppArgs, Settings},
+    sentinel::{
+        PollingSentinel, ReqListener, Sentinel,
+        drain::{drain_listener, mark_drained},
+        funding::FundingMonitor,
+        provisioning,
+    },
+    settings::{AppArgs, Command, Settings},
 };
 use metrics_exporter_prometheus::PrometheusBuilder;
 use solana_sdk::signer::Signer;
 use tokio::signal;
 use tokio_util::sync::CancellationToken;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[tokio::main]
@@ -30,8 +36,50 @@ async fn main() -> anyhow::Result<()> {
     let sol_rpc = settings.sol_rpc();
     let dz_rpc = settings.dz_rpc();
     let keypair = settings.keypair();
+    let ip_policy = settings
+        .ip_policy()
+        .map_err(|err| anyhow::anyhow!("invalid ip_allowlist entry {err}"))?;
+    let access_modes = settings.access_modes().map_err(anyhow::Error::msg)?;
+    let eligibility = settings.eligibility().map_err(anyhow::Error::msg)?;
+
+    if let Some(Command::Repair { dry_run }) = args.command {
+        info!(%sol_rpc, %dz_rpc, dry_run, "DoubleZero Ledger Sentinel running repair");
+
+        let dz_rpc_client = DzRpcClient::new(
+            dz_rpc,
+            keypair.clone(),
+            settings.serviceability_program_id()?,
+        );
+        let sol_rpc_client = SolRpcClient::new(sol_rpc, keypair);
+        let summary = provisioning::repair(
+            &dz_rpc_client,
+            &sol_rpc_client,
+            ENV_PREVIOUS_LEADER_EPOCHS,
+            &ip_policy,
+            &access_modes,
+            &eligibility,
+            settings.dz_provisioning_retries,
+            dry_run,
+        )
+        .await?;
+
+        info!(?summary, "repair complete");
+        return Ok(());
+    }
 
     let shutdown_listener = shutdown_listener();
+    // SIGTERM/ctrl-c stop immediately; SIGUSR1 drains in-flight work first
+    let drain_listener = drain_listener(&shutdown_listener);
+
+    let funding_policy = settings.funding_policy();
+    if settings.auto_airdrop && funding_policy.airdrop_amount.is_none() {
+        warn!(%sol_rpc, "auto_airdrop is only supported on devnet and localnet; ignoring");
+    }
+    let funding_monitor = FundingMonitor::new(
+        SolRpcClient::new(sol_rpc.clone(), keypair.clone()),
+        funding_policy,
+    );
+    tokio::spawn(funding_monitor.run(drain_listener.clone()));
 
     // If the poll_interval is set, do not use websocket conn
     if let Some(poll_interval) = args.poll_interval {
@@ -50,6 +98,14 @@ async fn main() -> anyhow::Result<()> {
             settings.serviceability_program_id()?,
             poll_interval,
             ENV_PREVIOUS_LEADER_EPOCHS,
+            settings.dz_provisioning_retries,
+            settings.verification_concurrency,
+            ip_policy,
+            access_modes,
+            eligibility,
+            settings.notifications(),
+            settings.dedupe_store()?,
+            settings.deny_reason_memos,
         )
         .await?;
 
@@ -58,7 +114,7 @@ async fn main() -> anyhow::Result<()> {
             _ = shutdown_listener.cancelled() => {
                 info!("shutdown signal received");
             },
-            result = polling_sentinel.run(shutdown_listener.clone()) => {
+            result = polling_sentinel.run(drain_listener.clone()) => {
                 if let Err(err) = result {
                     error!(?err, "polling sentinel exited with error");
                 }
@@ -83,6 +139,14 @@ async fn main() -> anyhow::Result<()> {
             settings.serviceability_program_id()?,
             rx,
             ENV_PREVIOUS_LEADER_EPOCHS,
+            settings.dz_provisioning_retries,
+            settings.max_batch_size,
+            ip_policy,
+            access_modes,
+            eligibility,
+            settings.notifications(),
+            settings.dedupe_store()?,
+            settings.deny_reason_memos,
         )
         .await?;
 
@@ -91,19 +155,30 @@ async fn main() -> anyhow::Result<()> {
             _ = shutdown_listener.cancelled() => {
                 info!("shutdown signal received");
             },
-            result = request_listener.run(shutdown_listener.clone()) => {
-                if let Err(err) = result {
-                    error!(?err, "sentinel request listener exited with error");
-                }
-            }
-            result = sentinel.run(shutdown_listener.clone()) => {
-                if let Err(err) = result {
-                    error!(?err, "sentinel handler exited with error");
-                }
-            }
+            _ = async {
+                // Whichever side stops first drains the other, so requests the listener
+                // has handed over are still handled before exiting
+                let listen = async {
+                    if let Err(err) = request_listener.run(drain_listener.clone()).await {
+                        error!(?err, "sentinel request listener exited with error");
+                    }
+                    drain_listener.cancel();
+                };
+                let handle = async {
+                    if let Err(err) = sentinel.run(drain_listener.clone()).await {
+                        error!(?err, "sentinel handler exited with error");
+                    }
+                    drain_listener.cancel();
+                };
+                tokio::join!(listen, handle)
+            } => {}
         }
     }
 
+    if drain_listener.is_cancelled() && !shutdown_listener.is_cancelled() {
+        mark_drained();
+    }
+
     info!("DoubleZero Ledger Sentinel shutting down");
 
     Ok(())
@@ -0,0 +1,265 @@
+//! Verification rules per access mode
+//!
+//! Each [`AccessMode`] is qualified by the [`AccessModeRules`] registered for its kind, so a new
+//! mode (e.g. RPC-provider or prepaid-user attestations) is supported by implementing its rules
+//! and registering them, without touching the request handlers. Settings choose which kinds are
+//! accepted; requests of any other kind are denied.
+
+use crate::{
+    Result,
+    sentinel::{Qualification, ValidatorVerifier, denial::DenialReason},
+};
+use doublezero_passport::instruction::AccessMode;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::{collections::BTreeMap, sync::Arc};
+use tracing::info;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessModeKind {
+    SolanaValidator,
+    SolanaValidatorWithBackupIds,
+}
+
+impl AccessModeKind {
+    pub const ALL: [Self; 2] = [Self::SolanaValidator, Self::SolanaValidatorWithBackupIds];
+
+    pub fn of(access_mode: &AccessMode) -> Self {
+        match access_mode {
+            AccessMode::SolanaValidator(_) => Self::SolanaValidator,
+            AccessMode::SolanaValidatorWithBackupIds { .. } => Self::SolanaValidatorWithBackupIds,
+        }
+    }
+
+    /// Short label used for metrics
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::SolanaValidator => "solana_validator",
+            Self::SolanaValidatorWithBackupIds => "solana_validator_with_backup_ids",
+        }
+    }
+}
+
+/// How requests of an access mode qualify for access
+#[async_trait::async_trait]
+pub trait AccessModeRules: Send + Sync {
+    /// Validators the request grants access to, or why it should be denied
+    ///
+    /// Called once the request's attestation has been verified as signed by `validator_id`.
+    async fn qualify(
+        &self,
+        verifier: &ValidatorVerifier<'_>,
+        validator_id: Pubkey,
+        access_mode: &AccessMode,
+    ) -> Result<Qualification>;
+}
+
+/// The primary validator must be eligible and in gossip; backups must be in gossip but not in the
+/// leader schedule
+pub struct SolanaValidatorRules;
+
+#[async_trait::async_trait]
+impl AccessModeRules for SolanaValidatorRules {
+    async fn qualify(
+        &self,
+        verifier: &ValidatorVerifier<'_>,
+        validator_id: Pubkey,
+        access_mode: &AccessMode,
+    ) -> Result<Qualification> {
+        // Extract attestation and backup IDs
+        let backup_ids = match access_mode {
+            AccessMode::SolanaValidator(_) => None,
+            AccessMode::SolanaValidatorWithBackupIds { backup_ids, .. } => Some(backup_ids),
+        };
+
+        // Check primary validator's stake and leader schedule eligibility
+        if let Some(reason) = verifier.check_eligibility(&validator_id).await? {
+            return Ok(Qualification::Denied(reason));
+        }
+
+        // Get primary validator IP immediately after the eligibility check
+        let validator_ip = match verifier
+            .get_and_validate_validator_ip(&validator_id)
+            .await?
+        {
+            Some(ip) => ip,
+            None => {
+                info!(
+                    %validator_id,
+                    "Validator failed gossip protocol ip qualification"
+                );
+                return Ok(Qualification::Denied(DenialReason::NotInGossip));
+            }
+        };
+
+        // Collect all validated IPs (starting with primary)
+        let mut ips = vec![(validator_id, validator_ip)];
+
+        // If we have backup IDs, verify they are NOT in leader schedule but ARE in gossip
+        if let Some(backup_ids) = backup_ids {
+            for backup_id in backup_ids {
+                // Backup should NOT be in leader schedule
+                if verifier
+                    .check_validator_in_leader_schedule(backup_id)
+                    .await?
+                {
+                    info!(
+                        %backup_id,
+                        "Backup validator is in leader schedule (should not be)"
+                    );
+                    return Ok(Qualification::Denied(DenialReason::BackupInLeaderSchedule));
+                }
+
+                // Check backup ID is in gossip and store IP
+                match verifier.get_and_validate_validator_ip(backup_id).await? {
+                    Some(ip) => {
+                        ips.push((*backup_id, ip));
+                    }
+                    None => {
+                        info!(
+                            %backup_id,
+                            "Backup validator not found in gossip"
+                        );
+                        return Ok(Qualification::Denied(DenialReason::BackupNotInGossip));
+                    }
+                }
+            }
+        }
+
+        Ok(Qualification::Qualified(ips))
+    }
+}
+
+/// The access mode kinds accepted, each with the rules its requests are qualified by
+#[derive(Clone)]
+pub struct AccessModes {
+    rules: BTreeMap<AccessModeKind, Arc<dyn AccessModeRules>>,
+}
+
+impl AccessModes {
+    /// Accept `kinds` with their built-in rules
+    pub fn new(kinds: &[AccessModeKind]) -> Self {
+        let rules = kinds
+            .iter()
+            .map(|kind| {
+                let rules: Arc<dyn AccessModeRules> = match kind {
+                    AccessModeKind::SolanaValidator
+                    | AccessModeKind::SolanaValidatorWithBackupIds => {
+                        Arc::new(SolanaValidatorRules)
+                    }
+                };
+                (*kind, rules)
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// Accept `kind` with `rules`, replacing any rules it already has
+    pub fn register(mut self, kind: AccessModeKind, rules: Arc<dyn AccessModeRules>) -> Self {
+        self.rules.insert(kind, rules);
+        self
+    }
+
+    /// Rules for `access_mode`, if its kind is accepted
+    pub fn rules(&self, access_mode: &AccessMode) -> Option<&dyn AccessModeRules> {
+        self.rules
+            .get(&AccessModeKind::of(access_mode))
+            .map(Arc::as_ref)
+    }
+
+    pub fn kinds(&self) -> impl Iterator<Item = AccessModeKind> + '_ {
+        self.rules.keys().copied()
+    }
+}
+
+impl Default for AccessModes {
+    fn default() -> Self {
+        Self::new(&AccessModeKind::ALL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::solana::SolRpcClient;
+    use doublezero_passport::instruction::SolanaValidatorAttestation;
+    use solana_sdk::signature::Keypair;
+    use url::Url;
+
+    struct DenyAll;
+
+    #[async_trait::async_trait]
+    impl AccessModeRules for DenyAll {
+        async fn qualify(
+            &self,
+            _verifier: &ValidatorVerifier<'_>,
+            _validator_id: Pubkey,
+            _access_mode: &AccessMode,
+        ) -> Result<Qualification> {
+            Ok(Qualification::Denied(DenialReason::NotInLeaderSchedule))
+        }
+    }
+
+    fn attestation() -> SolanaValidatorAttestation {
+        SolanaValidatorAttestation {
+            validator_id: Pubkey::new_unique(),
+            service_key: Pubkey::new_unique(),
+            ed25519_signature: [0u8; 64],
+        }
+    }
+
+    #[test]
+    fn test_access_mode_kind() {
+        let single = AccessMode::SolanaValidator(attestation());
+        let with_backups = AccessMode::SolanaValidatorWithBackupIds {
+            attestation: attestation(),
+            backup_ids: vec![Pubkey::new_unique()],
+        };
+        assert_eq!(AccessModeKind::of(&single), AccessModeKind::SolanaValidator);
+        assert_eq!(
+            AccessModeKind::of(&with_backups),
+            AccessModeKind::SolanaValidatorWithBackupIds
+        );
+
+        let kinds: Vec<AccessModeKind> =
+            serde_json::from_str(r#"["solana_validator", "solana_validator_with_backup_ids"]"#)
+                .unwrap();
+        assert_eq!(kinds, AccessModeKind::ALL);
+    }
+
+    #[test]
+    fn test_access_modes() {
+        let single = AccessMode::SolanaValidator(attestation());
+
+        assert!(AccessModes::default().rules(&single).is_some());
+
+        let backups_only = AccessModes::new(&[AccessModeKind::SolanaValidatorWithBackupIds]);
+        assert!(backups_only.rules(&single).is_none());
+        assert_eq!(
+            backups_only.kinds().collect::<Vec<_>>(),
+            vec![AccessModeKind::SolanaValidatorWithBackupIds]
+        );
+
+        let registered = backups_only.register(AccessModeKind::SolanaValidator, Arc::new(DenyAll));
+        assert!(registered.rules(&single).is_some());
+        assert_eq!(registered.kinds().count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_unaccepted_access_mode_denied() {
+        // Rejected before any RPC call, so the client never connects
+        let sol_rpc_client = SolRpcClient::new(
+            Url::parse("http://127.0.0.1:1235").unwrap(),
+            Arc::new(Keypair::new()),
+        );
+        let access_modes = AccessModes::new(&[AccessModeKind::SolanaValidatorWithBackupIds]);
+        let verifier = ValidatorVerifier::new(&sol_rpc_client, 0).with_access_modes(&access_modes);
+
+        let access_mode = AccessMode::SolanaValidator(attestation());
+        assert_eq!(
+            verifier.verify_qualifiers(&access_mode).await.unwrap(),
+            Qualification::Denied(DenialReason::UnsupportedAccessMode)
+        );
+    }
+}
@@ -0,0 +1,298 @@
+//! Persistent record of access requests the sentinel has already decided
+//!
+//! Request PDAs are stored with their outcome in a small JSON file, so after a restart requests
+//! granted or denied by the previous run are skipped instead of re-verified while the RPC still
+//! lists them. Entries older than the TTL are pruned whenever the store is loaded or written.
+//!
+//! Request PDAs are derived from the service key, and a decision closes the request account, so a
+//! validator submitting again after a denial reuses the PDA. An entry therefore only applies to
+//! the request instance it was recorded for: it is dropped once the account is seen closed, or
+//! when a new request transaction for the PDA arrives.
+
+use crate::{Error, Result, sentinel::notify::AccessDecision};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::{
+    collections::{HashMap, HashSet},
+    fs, io,
+    path::PathBuf,
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tracing::{debug, info};
+
+/// A decided access request
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProcessedRequest {
+    pub decision: AccessDecision,
+    /// Unix timestamp, in seconds, of the decision
+    pub processed_at: u64,
+}
+
+/// One entry of the store file
+#[derive(Serialize, Deserialize)]
+struct StoredRequest {
+    request_pda: String,
+    #[serde(flatten)]
+    request: ProcessedRequest,
+}
+
+pub struct DedupeStore {
+    /// Unset keeps the store in memory only
+    path: Option<PathBuf>,
+    ttl: Duration,
+    entries: Mutex<HashMap<Pubkey, ProcessedRequest>>,
+}
+
+impl DedupeStore {
+    /// A store that is not persisted, remembering decisions for the lifetime of the process
+    pub fn in_memory(ttl: Duration) -> Self {
+        Self {
+            path: None,
+            ttl,
+            entries: Mutex::default(),
+        }
+    }
+
+    /// Load the store at `path`; the file is created on the first recorded decision
+    pub fn open(path: impl Into<PathBuf>, ttl: Duration) -> Result<Self> {
+        let path = path.into();
+        let mut entries = match fs::read(&path) {
+            Ok(contents) => parse_entries(&contents)?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => HashMap::new(),
+            Err(err) => {
+                return Err(Error::DedupeStore(format!(
+                    "failed to read {}: {err}",
+                    path.display()
+                )));
+            }
+        };
+
+        let pruned = prune(&mut entries, unix_now(), ttl);
+        info!(
+            path = %path.display(),
+            entries = entries.len(),
+            pruned,
+            "loaded processed access requests"
+        );
+        metrics::gauge!("doublezero_sentinel_dedupe_store_entries").set(entries.len() as f64);
+
+        Ok(Self {
+            path: Some(path),
+            ttl,
+            entries: Mutex::new(entries),
+        })
+    }
+
+    /// The decision recorded for `request_pda`, unless it has expired
+    pub fn get(&self, request_pda: &Pubkey) -> Option<ProcessedRequest> {
+        let cutoff = unix_now().saturating_sub(self.ttl.as_secs());
+        self.entries
+            .lock()
+            .expect("dedupe store lock poisoned")
+            .get(request_pda)
+            .filter(|request| request.processed_at > cutoff)
+            .copied()
+    }
+
+    /// Whether `request_pda` was already decided, counting it as skipped if so
+    pub fn already_decided(&self, request_pda: &Pubkey) -> bool {
+        let Some(request) = self.get(request_pda) else {
+            return false;
+        };
+        debug!(
+            %request_pda,
+            decision = ?request.decision,
+            processed_at = request.processed_at,
+            "skipping already decided access request"
+        );
+        metrics::counter!("doublezero_sentinel_dedupe_store_skipped").increment(1);
+        true
+    }
+
+    /// Record `decision` for `request_pda` and write the store out
+    pub fn record(&self, request_pda: Pubkey, decision: AccessDecision) -> Result<()> {
+        let now = unix_now();
+        let mut entries = self.entries.lock().expect("dedupe store lock poisoned");
+        entries.insert(
+            request_pda,
+            ProcessedRequest {
+                decision,
+                processed_at: now,
+            },
+        );
+        prune(&mut entries, now, self.ttl);
+        self.write(&entries)
+    }
+
+    /// Drop entries for requests that are no longer outstanding, given every listed request PDA
+    pub fn retain_listed(&self, listed: &HashSet<Pubkey>) -> Result<()> {
+        let mut entries = self.entries.lock().expect("dedupe store lock poisoned");
+        let before = entries.len();
+        entries.retain(|request_pda, _| listed.contains(request_pda));
+        if entries.len() == before {
+            return Ok(());
+        }
+        debug!(
+            closed = before - entries.len(),
+            "dropping decided access requests whose accounts are closed"
+        );
+        self.write(&entries)
+    }
+
+    /// Drop the entry for `request_pda`, which was just requested again
+    pub fn forget(&self, request_pda: &Pubkey) -> Result<()> {
+        let mut entries = self.entries.lock().expect("dedupe store lock poisoned");
+        if entries.remove(request_pda).is_none() {
+            return Ok(());
+        }
+        self.write(&entries)
+    }
+
+    fn write(&self, entries: &HashMap<Pubkey, ProcessedRequest>) -> Result<()> {
+        metrics::gauge!("doublezero_sentinel_dedupe_store_entries").set(entries.len() as f64);
+
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        // Written alongside and renamed so a crash mid-write cannot truncate the store
+        let contents = serialize_entries(entries)?;
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, contents)
+            .and_then(|_| fs::rename(&tmp_path, path))
+            .map_err(|err| Error::DedupeStore(format!("failed to write {}: {err}", path.display())))
+    }
+}
+
+fn parse_entries(contents: &[u8]) -> Result<HashMap<Pubkey, ProcessedRequest>> {
+    let stored: Vec<StoredRequest> =
+        serde_json::from_slice(contents).map_err(|err| Error::DedupeStore(err.to_string()))?;
+
+    let mut entries = HashMap::with_capacity(stored.len());
+    for entry in stored {
+        let request_pda = entry.request_pda.parse().map_err(|_| {
+            Error::DedupeStore(format!("invalid request PDA {}", entry.request_pda))
+        })?;
+        entries.insert(request_pda, entry.request);
+    }
+    Ok(entries)
+}
+
+fn serialize_entries(entries: &HashMap<Pubkey, ProcessedRequest>) -> Result<Vec<u8>> {
+    let stored: Vec<StoredRequest> = entries
+        .iter()
+        .map(|(request_pda, request)| StoredRequest {
+            request_pda: request_pda.to_string(),
+            request: *request,
+        })
+        .collect();
+    serde_json::to_vec(&stored).map_err(|err| Error::DedupeStore(err.to_string()))
+}
+
+/// Drop entries decided more than `ttl` before `now`, returning how many were dropped
+fn prune(entries: &mut HashMap<Pubkey, ProcessedRequest>, now: u64, ttl: Duration) -> usize {
+    let cutoff = now.saturating_sub(ttl.as_secs());
+    let before = entries.len();
+    entries.retain(|_, request| request.processed_at > cutoff);
+    before - entries.len()
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TTL: Duration = Duration::from_secs(3600);
+
+    #[test]
+    fn test_persists_across_restarts() {
+        let path =
+            std::env::temp_dir().join(format!("sentinel-dedupe-{}.json", Pubkey::new_unique()));
+        let (granted, denied) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        let store = DedupeStore::open(&path, TTL).unwrap();
+        assert!(store.get(&granted).is_none());
+        store.record(granted, AccessDecision::Granted).unwrap();
+        store.record(denied, AccessDecision::Denied).unwrap();
+
+        let reopened = DedupeStore::open(&path, TTL).unwrap();
+        assert_eq!(
+            reopened.get(&granted).map(|request| request.decision),
+            Some(AccessDecision::Granted)
+        );
+        assert!(reopened.already_decided(&denied));
+        assert!(!reopened.already_decided(&Pubkey::new_unique()));
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_entries_end_with_the_request_instance() {
+        let store = DedupeStore::in_memory(TTL);
+        let (open, closed, resubmitted) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        for request_pda in [open, closed, resubmitted] {
+            store.record(request_pda, AccessDecision::Denied).unwrap();
+        }
+
+        // A denied request resubmitted under the same PDA is handled again
+        store.forget(&resubmitted).unwrap();
+        assert!(!store.already_decided(&resubmitted));
+
+        store.retain_listed(&HashSet::from([open])).unwrap();
+        assert!(store.already_decided(&open));
+        assert!(!store.already_decided(&closed));
+    }
+
+    #[test]
+    fn test_prune() {
+        let now = 10_000;
+        let (fresh, stale) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut entries = HashMap::from([
+            (
+                fresh,
+                ProcessedRequest {
+                    decision: AccessDecision::Granted,
+                    processed_at: now - 60,
+                },
+            ),
+            (
+                stale,
+                ProcessedRequest {
+                    decision: AccessDecision::Denied,
+                    processed_at: now - 7200,
+                },
+            ),
+        ]);
+
+        assert_eq!(prune(&mut entries, now, TTL), 1);
+        assert!(entries.contains_key(&fresh));
+        assert!(!entries.contains_key(&stale));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let entries = HashMap::from([(
+            Pubkey::new_unique(),
+            ProcessedRequest {
+                decision: AccessDecision::Denied,
+                processed_at: 1_700_000_000,
+            },
+        )]);
+        let contents = serialize_entries(&entries).unwrap();
+        assert_eq!(parse_entries(&contents).unwrap(), entries);
+        assert!(
+            parse_entries(br#"[{"request_pda":"nope","decision":"granted","processed_at":1}]"#)
+                .is_err()
+        );
+    }
+}
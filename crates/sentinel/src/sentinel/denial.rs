@@ -0,0 +1,86 @@
+//! Why access requests are denied
+//!
+//! Every denial carries a [`DenialReason`]. It is logged and counted per reason, included in
+//! published access events and, when enabled, written as a memo next to the deny instruction so
+//! the requesting validator can read it from the transaction.
+
+use serde::Serialize;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DenialReason {
+    /// The attestation is not signed by the validator it names
+    InvalidSignature,
+    /// The request's access mode is not accepted
+    UnsupportedAccessMode,
+    /// The validator is not in the current vote-account set
+    NotInVoteAccounts,
+    /// The validator's activated stake is below the minimum
+    InsufficientStake,
+    /// The validator is not in the leader schedule
+    NotInLeaderSchedule,
+    /// The validator has no usable IP in gossip
+    NotInGossip,
+    /// A backup validator is in the leader schedule
+    BackupInLeaderSchedule,
+    /// A backup validator has no usable IP in gossip
+    BackupNotInGossip,
+    /// A validator endpoint failed the IP policy
+    IpPolicy,
+}
+
+impl DenialReason {
+    /// Short label used for metrics, events and memos
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::InvalidSignature => "invalid_signature",
+            Self::UnsupportedAccessMode => "unsupported_access_mode",
+            Self::NotInVoteAccounts => "not_in_vote_accounts",
+            Self::InsufficientStake => "insufficient_stake",
+            Self::NotInLeaderSchedule => "not_in_leader_schedule",
+            Self::NotInGossip => "not_in_gossip",
+            Self::BackupInLeaderSchedule => "backup_in_leader_schedule",
+            Self::BackupNotInGossip => "backup_not_in_gossip",
+            Self::IpPolicy => "ip_policy",
+        }
+    }
+
+    /// Memo written alongside the deny instruction
+    ///
+    /// Kept short so a full batch of denials with memos still fits in one transaction.
+    pub fn memo(&self) -> String {
+        format!("denied: {}", self.as_str())
+    }
+
+    /// Count a denial for this reason
+    pub fn record(&self) {
+        metrics::counter!(
+            "doublezero_sentinel_access_denied_reason",
+            "reason" => self.as_str()
+        )
+        .increment(1);
+    }
+}
+
+impl fmt::Display for DenialReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_denial_reason_labels() {
+        let reason = DenialReason::BackupNotInGossip;
+        assert_eq!(
+            serde_json::to_value(reason).unwrap(),
+            serde_json::json!(reason.as_str())
+        );
+        assert_eq!(reason.memo(), "denied: backup_not_in_gossip");
+        assert_eq!(DenialReason::IpPolicy.to_string(), "ip_policy");
+    }
+}
@@ -0,0 +1,63 @@
+use tokio::signal::unix::{SignalKind, signal};
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+
+/// Token that stops the sentinel taking new work, for zero-downtime rolling deployments
+///
+/// Cancelled on SIGUSR1, or along with `shutdown_listener`. Unlike a shutdown, a drain lets
+/// in-flight verifications and grant transactions finish; the run loops return once idle and
+/// the process exits with `doublezero_sentinel_drained` set.
+pub fn drain_listener(shutdown_listener: &CancellationToken) -> CancellationToken {
+    let drain_listener = shutdown_listener.child_token();
+    let mut sigusr1 = signal(SignalKind::user_defined1()).expect("sigusr1 listener failed");
+
+    metrics::gauge!("doublezero_sentinel_draining").set(0);
+    metrics::gauge!("doublezero_sentinel_drained").set(0);
+
+    tokio::spawn({
+        let drain_listener = drain_listener.clone();
+        async move {
+            tokio::select! {
+                _ = sigusr1.recv() => {
+                    info!("drain signal received; finishing in-flight requests");
+                    metrics::gauge!("doublezero_sentinel_draining").set(1);
+                    drain_listener.cancel();
+                }
+                _ = drain_listener.cancelled() => {}
+            }
+        }
+    });
+
+    drain_listener
+}
+
+/// Report that every in-flight request has completed
+pub fn mark_drained() {
+    info!("drain complete");
+    metrics::gauge!("doublezero_sentinel_draining").set(0);
+    metrics::gauge!("doublezero_sentinel_drained").set(1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_shutdown_also_drains() {
+        let shutdown_listener = CancellationToken::new();
+        let drain_listener = drain_listener(&shutdown_listener);
+        assert!(!drain_listener.is_cancelled());
+
+        shutdown_listener.cancel();
+        assert!(drain_listener.is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn test_drain_leaves_shutdown_untouched() {
+        let shutdown_listener = CancellationToken::new();
+        let drain_listener = drain_listener(&shutdown_listener);
+
+        drain_listener.cancel();
+        assert!(!shutdown_listener.is_cancelled());
+    }
+}
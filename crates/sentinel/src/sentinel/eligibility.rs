@@ -0,0 +1,85 @@
+//! Validator eligibility for access
+//!
+//! Before a validator is granted access it must meet the configured [`Eligibility`]: be in the
+//! current vote-account set with at least the minimum activated stake, and optionally appear in
+//! one of the previous leader schedules. Each check that fails denies the request with its own
+//! [`DenialReason`].
+
+use crate::sentinel::denial::DenialReason;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Eligibility {
+    /// Require the validator to be in the current (non-delinquent) vote-account set
+    pub require_vote_account: bool,
+    /// Minimum activated stake, in lamports; a nonzero minimum also requires a vote account
+    pub min_activated_stake: u64,
+    /// Require the validator in one of the previous leader schedules
+    pub require_leader_schedule: bool,
+}
+
+impl Eligibility {
+    /// Whether the vote-account set has to be fetched to check eligibility
+    pub fn checks_vote_accounts(&self) -> bool {
+        self.require_vote_account || self.min_activated_stake > 0
+    }
+
+    /// Check the validator's activated stake, `None` if it is not in the vote-account set
+    pub fn check_stake(&self, activated_stake: Option<u64>) -> Result<(), DenialReason> {
+        if !self.checks_vote_accounts() {
+            return Ok(());
+        }
+        match activated_stake {
+            None => Err(DenialReason::NotInVoteAccounts),
+            Some(stake) if stake < self.min_activated_stake => Err(DenialReason::InsufficientStake),
+            Some(_) => Ok(()),
+        }
+    }
+}
+
+impl Default for Eligibility {
+    /// Only the leader schedule is checked
+    fn default() -> Self {
+        Self {
+            require_vote_account: false,
+            min_activated_stake: 0,
+            require_leader_schedule: true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_stake() {
+        let default = Eligibility::default();
+        assert!(!default.checks_vote_accounts());
+        assert_eq!(default.check_stake(None), Ok(()));
+
+        let vote_account = Eligibility {
+            require_vote_account: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            vote_account.check_stake(None),
+            Err(DenialReason::NotInVoteAccounts)
+        );
+        assert_eq!(vote_account.check_stake(Some(0)), Ok(()));
+
+        let min_stake = Eligibility {
+            min_activated_stake: 1_000,
+            ..Default::default()
+        };
+        assert!(min_stake.checks_vote_accounts());
+        assert_eq!(
+            min_stake.check_stake(None),
+            Err(DenialReason::NotInVoteAccounts)
+        );
+        assert_eq!(
+            min_stake.check_stake(Some(999)),
+            Err(DenialReason::InsufficientStake)
+        );
+        assert_eq!(min_stake.check_stake(Some(1_000)), Ok(()));
+    }
+}
@@ -0,0 +1,153 @@
+use crate::{
+    client::solana::{
+        COMPUTE_UNIT_PRICE_MICRO_LAMPORTS, GRANT_ACCESS_COMPUTE_UNIT_LIMIT, SolRpcClient,
+    },
+    error::rpc_with_retry,
+};
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use std::time::Duration;
+use tokio::time::interval;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+use url::Url;
+
+// Signer balance is sampled once a minute
+const BALANCE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+// Base fee for a single-signature transaction
+const SIGNATURE_FEE_LAMPORTS: u64 = 5_000;
+
+/// Lamports the signer spends per grant transaction: the signature fee plus the
+/// priority fee for the full compute unit limit
+pub const GRANT_COST_LAMPORTS: u64 = SIGNATURE_FEE_LAMPORTS
+    + GRANT_ACCESS_COMPUTE_UNIT_LIMIT as u64 * COMPUTE_UNIT_PRICE_MICRO_LAMPORTS / 1_000_000;
+
+/// How the signer balance is watched and topped up
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FundingPolicy {
+    /// Balance below which a warning is raised, in lamports
+    pub low_balance_threshold: u64,
+    /// Lamports to request when the balance runs low; `None` disables airdrops
+    pub airdrop_amount: Option<u64>,
+}
+
+/// Periodically reports the signer balance and, in dev clusters, keeps it funded
+pub struct FundingMonitor {
+    sol_rpc_client: SolRpcClient,
+    policy: FundingPolicy,
+}
+
+impl FundingMonitor {
+    pub fn new(sol_rpc_client: SolRpcClient, policy: FundingPolicy) -> Self {
+        Self {
+            sol_rpc_client,
+            policy,
+        }
+    }
+
+    pub async fn run(self, shutdown_listener: CancellationToken) {
+        let mut check_timer = interval(BALANCE_CHECK_INTERVAL);
+
+        loop {
+            tokio::select! {
+                biased;
+                _ = shutdown_listener.cancelled() => break,
+                _ = check_timer.tick() => self.check_balance().await,
+            }
+        }
+    }
+
+    async fn check_balance(&self) {
+        let balance = match rpc_with_retry(
+            || async { self.sol_rpc_client.get_balance().await },
+            "get_balance",
+        )
+        .await
+        {
+            Ok(balance) => balance,
+            Err(err) => {
+                error!(?err, "failed to fetch signer balance");
+                metrics::counter!("doublezero_sentinel_balance_check_failed").increment(1);
+                return;
+            }
+        };
+
+        let grants_remaining = grants_affordable(balance);
+        metrics::gauge!("doublezero_sentinel_signer_balance_lamports").set(balance as f64);
+        metrics::gauge!("doublezero_sentinel_grants_affordable").set(grants_remaining as f64);
+
+        if balance >= self.policy.low_balance_threshold {
+            return;
+        }
+
+        let payer = self.sol_rpc_client.payer_pubkey();
+        warn!(
+            %payer,
+            balance_sol = lamports_to_sol(balance),
+            threshold_sol = lamports_to_sol(self.policy.low_balance_threshold),
+            grants_remaining,
+            "signer balance is low; fund the sentinel wallet"
+        );
+        metrics::counter!("doublezero_sentinel_low_balance").increment(1);
+
+        let Some(amount) = self.policy.airdrop_amount else {
+            return;
+        };
+        match self.sol_rpc_client.request_airdrop(amount).await {
+            Ok(signature) => {
+                info!(%signature, %payer, amount_sol = lamports_to_sol(amount), "requested airdrop");
+                metrics::counter!("doublezero_sentinel_airdrop_requested").increment(1);
+            }
+            Err(err) => {
+                error!(?err, %payer, "airdrop request failed");
+                metrics::counter!("doublezero_sentinel_airdrop_failed").increment(1);
+            }
+        }
+    }
+}
+
+/// Number of grant transactions a balance covers
+pub fn grants_affordable(balance: u64) -> u64 {
+    balance / GRANT_COST_LAMPORTS
+}
+
+/// Whether the Solana RPC points at a cluster that hands out airdrops
+pub fn is_dev_cluster(sol_rpc: &Url) -> bool {
+    match sol_rpc.host_str() {
+        Some("localhost" | "127.0.0.1" | "0.0.0.0" | "[::1]") => true,
+        Some(host) => host.contains("devnet"),
+        None => false,
+    }
+}
+
+pub fn sol_to_lamports(sol: f64) -> u64 {
+    (sol * LAMPORTS_PER_SOL as f64).round() as u64
+}
+
+fn lamports_to_sol(lamports: u64) -> f64 {
+    lamports as f64 / LAMPORTS_PER_SOL as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grants_affordable() {
+        assert_eq!(GRANT_COST_LAMPORTS, 6_600);
+        assert_eq!(grants_affordable(0), 0);
+        assert_eq!(grants_affordable(6_599), 0);
+        assert_eq!(grants_affordable(sol_to_lamports(1.0)), 151_515);
+    }
+
+    #[test]
+    fn test_is_dev_cluster() {
+        let dev = |url: &str| is_dev_cluster(&Url::parse(url).unwrap());
+
+        assert!(dev("http://localhost:8899"));
+        assert!(dev("http://127.0.0.1:8899"));
+        assert!(dev("https://api.devnet.solana.com"));
+        assert!(!dev("https://api.testnet.solana.com"));
+        assert!(!dev("https://api.mainnet-beta.solana.com"));
+    }
+}
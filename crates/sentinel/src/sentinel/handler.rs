@@ -1,28 +1,54 @@
 use crate::{
     AccessId, Result,
-    client::{doublezero_ledger::DzRpcClient, solana::SolRpcClient},
+    client::{
+        doublezero_ledger::DzRpcClient,
+        solana::{MAX_ACCESS_DECISIONS_PER_TRANSACTION, SolRpcClient},
+    },
     error::rpc_with_retry,
-    sentinel::ValidatorVerifier,
+    sentinel::{
+        Qualification, ValidatorVerifier,
+        access_modes::AccessModes,
+        dedupe::DedupeStore,
+        denial::DenialReason,
+        eligibility::Eligibility,
+        ip_policy::IpPolicy,
+        notify::{AccessDecision, AccessEvent, Notifications},
+        provisioning::provision_access_passes,
+        reconcile::reconcile,
+    },
 };
 use doublezero_passport::instruction::AccessMode;
+use futures::future::join_all;
 use solana_sdk::{
     pubkey::Pubkey,
     signature::{Keypair, Signature},
 };
-use std::{net::Ipv4Addr, sync::Arc, time::Duration};
-use tokio::{sync::mpsc::UnboundedReceiver, time::interval};
+use std::{sync::Arc, time::Duration};
+use tokio::{
+    sync::mpsc::UnboundedReceiver,
+    time::{Instant, interval_at},
+};
 use tokio_util::sync::CancellationToken;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use url::Url;
 
+// Outstanding requests are reconciled on startup; if that fails, the first backfill tick fires
+// immediately instead so requests left by a previous run are not missed until the next cycle
 const BACKFILL_TIMER: Duration = Duration::from_secs(60 * 60);
 
 pub struct Sentinel {
     dz_rpc_client: DzRpcClient,
     sol_rpc_client: SolRpcClient,
     rx: UnboundedReceiver<Signature>,
-    #[allow(dead_code)]
     previous_leader_epochs: u8,
+    dz_provisioning_retries: usize,
+    max_batch_size: usize,
+    ip_policy: IpPolicy,
+    access_modes: AccessModes,
+    eligibility: Eligibility,
+    notifications: Notifications,
+    dedupe_store: DedupeStore,
+    deny_reason_memos: bool,
 }
 
 impl Sentinel {
@@ -33,17 +59,38 @@ impl Sentinel {
         serviceability_id: Pubkey,
         rx: UnboundedReceiver<Signature>,
         previous_leader_epochs: u8,
+        dz_provisioning_retries: usize,
+        max_batch_size: usize,
+        ip_policy: IpPolicy,
+        access_modes: AccessModes,
+        eligibility: Eligibility,
+        notifications: Notifications,
+        dedupe_store: DedupeStore,
+        deny_reason_memos: bool,
     ) -> Result<Self> {
         Ok(Self {
             dz_rpc_client: DzRpcClient::new(dz_rpc, keypair.clone(), serviceability_id),
             sol_rpc_client: SolRpcClient::new(sol_rpc, keypair),
             rx,
             previous_leader_epochs,
+            dz_provisioning_retries,
+            max_batch_size: max_batch_size.clamp(1, MAX_ACCESS_DECISIONS_PER_TRANSACTION),
+            ip_policy,
+            access_modes,
+            eligibility,
+            notifications,
+            dedupe_store,
+            deny_reason_memos,
         })
     }
 
     pub async fn run(&mut self, shutdown_listener: CancellationToken) -> Result<()> {
-        let mut backfill_timer = interval(BACKFILL_TIMER);
+        let first_backfill = if self.reconcile_on_startup().await {
+            Instant::now() + BACKFILL_TIMER
+        } else {
+            Instant::now()
+        };
+        let mut backfill_timer = interval_at(first_backfill, BACKFILL_TIMER);
 
         loop {
             tokio::select! {
@@ -65,95 +112,317 @@ impl Sentinel {
                         }
                     };
 
+                    self.forget_closed(access_ids.iter().map(|access_id| access_id.request_pda));
                     info!(count = access_ids.len(), "processing unhandled access requests");
 
-                    for ids in access_ids {
-                        if let Err(err) = self.handle_access_request(ids).await {
-                            error!(?err, "error encountered validating network access request");
+                    for batch in access_ids.chunks(self.max_batch_size) {
+                        // Requests left behind while draining are reconciled by the next instance
+                        if shutdown_listener.is_cancelled() {
+                            break;
                         }
+                        self.handle_access_requests(batch).await;
                     }
                 }
                 event = self.rx.recv() => {
                     if let Some(signature) = event {
-                        info!(%signature, "received access request txn");
-                        let access_ids = match rpc_with_retry(
-                            || async {
-                                self.sol_rpc_client.get_access_requests_from_signature(signature).await
-                            },
-                            "get_access_request_from_signature",
-                        ).await {
-                            Ok(ids) => ids,
-                            Err(err) => {
-                                error!(?err, %signature, "failed to fetch access request from signature after retries; skipping");
-                                metrics::counter!("doublezero_sentinel_signature_fetch_failed").increment(1);
-                                continue;
-                            }
-                        };
-
-                        for access_id in access_ids {
-                            if let Err(err) = self.handle_access_request(access_id).await {
-                                error!(?err, "error encountered validating network access request");
-                            }
+                        // Requests that arrived together are handled as one batch
+                        let mut signatures = vec![signature];
+                        while signatures.len() < self.max_batch_size
+                            && let Ok(signature) = self.rx.try_recv()
+                        {
+                            signatures.push(signature);
                         }
+                        self.handle_signatures(signatures).await;
                     }
                 }
             }
         }
 
+        // Requests the listener already handed over are finished rather than dropped
+        let mut signatures = Vec::new();
+        while let Ok(signature) = self.rx.try_recv() {
+            signatures.push(signature);
+        }
+        if !signatures.is_empty() {
+            self.handle_signatures(signatures).await;
+        }
+
         Ok(())
     }
 
-    async fn handle_access_request(&self, access_id: AccessId) -> Result<()> {
-        // Get the service key.
-        let service_key = access_id.mode.service_key();
+    async fn handle_signatures(&self, signatures: Vec<Signature>) {
+        let access_ids: Vec<AccessId> = join_all(
+            signatures
+                .into_iter()
+                .map(|signature| self.fetch_access_requests(signature)),
+        )
+        .await
+        .into_iter()
+        .flatten()
+        .collect();
+
+        // A request transaction opens a new request, even at a PDA decided before
+        for access_id in &access_ids {
+            if let Err(err) = self.dedupe_store.forget(&access_id.request_pda) {
+                warn!(?err, request_pda = %access_id.request_pda, "failed to forget resubmitted access request");
+            }
+        }
+
+        for batch in access_ids.chunks(self.max_batch_size) {
+            self.handle_access_requests(batch).await;
+        }
+    }
+
+    async fn fetch_access_requests(&self, signature: Signature) -> Vec<AccessId> {
+        info!(%signature, "received access request txn");
+        match rpc_with_retry(
+            || async {
+                self.sol_rpc_client
+                    .get_access_requests_from_signature(signature)
+                    .await
+            },
+            "get_access_request_from_signature",
+        )
+        .await
+        {
+            Ok(ids) => ids,
+            Err(err) => {
+                error!(
+                    ?err,
+                    %signature,
+                    "failed to fetch access request from signature after retries; skipping"
+                );
+                metrics::counter!("doublezero_sentinel_signature_fetch_failed").increment(1);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Handle the requests that are genuinely pending on startup, returning whether
+    /// reconciliation succeeded
+    async fn reconcile_on_startup(&self) -> bool {
+        let verifier = ValidatorVerifier::new(&self.sol_rpc_client, self.previous_leader_epochs)
+            .with_ip_policy(&self.ip_policy)
+            .with_access_passes(&self.dz_rpc_client)
+            .with_access_modes(&self.access_modes)
+            .with_eligibility(&self.eligibility);
+        let reconciliation = match reconcile(
+            &self.dz_rpc_client,
+            &self.sol_rpc_client,
+            &verifier,
+            &self.notifications,
+        )
+        .await
+        {
+            Ok(reconciliation) => reconciliation,
+            Err(err) => {
+                error!(
+                    ?err,
+                    "startup reconciliation failed; falling back to backfill"
+                );
+                metrics::counter!("doublezero_sentinel_startup_reconciliation_failed").increment(1);
+                return false;
+            }
+        };
+
+        self.forget_closed(
+            reconciliation
+                .pending
+                .iter()
+                .map(|access_id| access_id.request_pda)
+                .chain(reconciliation.granted.iter().copied()),
+        );
+        for request_pda in reconciliation.granted {
+            self.remember_decision(request_pda, AccessDecision::Granted);
+        }
+
+        for batch in reconciliation.pending.chunks(self.max_batch_size) {
+            self.handle_access_requests(batch).await;
+        }
+
+        true
+    }
+
+    /// Verify a batch of access requests concurrently, then grant and deny them in as few
+    /// transactions as possible
+    async fn handle_access_requests(&self, access_ids: &[AccessId]) {
+        // Requests decided earlier, possibly by a previous run, may still be listed
+        let access_ids: Vec<&AccessId> = access_ids
+            .iter()
+            .filter(|access_id| !self.dedupe_store.already_decided(&access_id.request_pda))
+            .collect();
+
+        let verified = join_all(
+            access_ids
+                .iter()
+                .map(|access_id| self.verify_qualifiers(&access_id.mode)),
+        )
+        .await;
+
+        let mut granted = Vec::new();
+        let mut denied = Vec::new();
+        for (access_id, verified) in access_ids.into_iter().zip(verified) {
+            let validator_ips = match verified {
+                Ok(Qualification::Qualified(validator_ips)) => validator_ips,
+                Ok(Qualification::Denied(reason)) => {
+                    denied.push((access_id, Some(reason)));
+                    continue;
+                }
+                Err(err) => {
+                    error!(?err, "error encountered validating network access request");
+                    continue;
+                }
+            };
+
+            // Issue access passes for all validators (primary + backups), skipping any
+            // already provisioned by an earlier, interrupted attempt
+            match provision_access_passes(
+                &self.dz_rpc_client,
+                &access_id.mode.service_key(),
+                &validator_ips,
+                self.dz_provisioning_retries,
+            )
+            .await
+            {
+                Ok(()) => granted.push((access_id, None)),
+                Err(err) => {
+                    error!(?err, "error encountered validating network access request");
+                }
+            }
+        }
+
+        self.decide_access_requests(AccessDecision::Granted, &granted)
+            .await;
+        self.decide_access_requests(AccessDecision::Denied, &denied)
+            .await;
+    }
+
+    /// Submit `decision` for every request, each with the reason it was denied, in one
+    /// transaction, falling back to a transaction per request if the batch fails so one bad
+    /// request cannot hold up the others
+    async fn decide_access_requests(
+        &self,
+        decision: AccessDecision,
+        requests: &[(&AccessId, Option<DenialReason>)],
+    ) {
+        if requests.len() > 1 {
+            match self.submit_decision(decision, requests).await {
+                Ok(signature) => {
+                    metrics::counter!("doublezero_sentinel_batched_decisions").increment(1);
+                    for (access_id, reason) in requests {
+                        self.record_decision(decision, access_id, *reason, &signature);
+                    }
+                    return;
+                }
+                Err(err) => {
+                    warn!(
+                        ?err,
+                        count = requests.len(),
+                        "batched access decision failed; submitting requests one at a time"
+                    );
+                }
+            }
+        }
 
-        let validator_ips = self.verify_qualifiers(&access_id.mode).await?;
+        for (access_id, reason) in requests {
+            match self
+                .submit_decision(decision, &[(*access_id, *reason)])
+                .await
+            {
+                Ok(signature) => self.record_decision(decision, access_id, *reason, &signature),
+                Err(err) => {
+                    error!(?err, "error encountered validating network access request");
+                }
+            }
+        }
+    }
 
-        if !validator_ips.is_empty() {
-            // Issue access passes for all validators (primary + backups)
-            for (validator_id, validator_ip) in validator_ips {
+    async fn submit_decision(
+        &self,
+        decision: AccessDecision,
+        requests: &[(&AccessId, Option<DenialReason>)],
+    ) -> Result<Signature> {
+        match decision {
+            AccessDecision::Granted => {
+                let requests: Vec<(Pubkey, Pubkey)> = requests
+                    .iter()
+                    .map(|(access_id, _)| (access_id.request_pda, access_id.rent_beneficiary_key))
+                    .collect();
                 rpc_with_retry(
-                    || async {
-                        self.dz_rpc_client
-                            .issue_access_pass(&service_key, &validator_ip, &validator_id)
-                            .await
-                    },
-                    "issue_access_pass",
+                    || async { self.sol_rpc_client.grant_access_batch(&requests).await },
+                    "grant_access",
                 )
-                .await?;
-                info!(%validator_id, %validator_ip, user = %service_key, "access pass issued");
+                .await
             }
+            AccessDecision::Denied => {
+                let denials: Vec<(Pubkey, Option<String>)> = requests
+                    .iter()
+                    .map(|(access_id, reason)| {
+                        let memo = reason
+                            .filter(|_| self.deny_reason_memos)
+                            .map(|reason| reason.memo());
+                        (access_id.request_pda, memo)
+                    })
+                    .collect();
+                rpc_with_retry(
+                    || async { self.sol_rpc_client.deny_access_batch(&denials).await },
+                    "deny_access",
+                )
+                .await
+            }
+        }
+    }
 
-            let signature = rpc_with_retry(
-                || async {
-                    self.sol_rpc_client
-                        .grant_access(&access_id.request_pda, &access_id.rent_beneficiary_key)
-                        .await
-                },
-                "grant_access",
-            )
-            .await?;
-            info!(%signature, user = %service_key, "access request granted");
-            metrics::counter!("doublezero_sentinel_access_granted").increment(1);
-        } else {
-            let signature = rpc_with_retry(
-                || async {
-                    self.sol_rpc_client
-                        .deny_access(&access_id.request_pda)
-                        .await
-                },
-                "deny_access",
-            )
-            .await?;
-            info!(%signature, user = %service_key, "access request denied");
-            metrics::counter!("doublezero_sentinel_access_denied").increment(1);
+    fn record_decision(
+        &self,
+        decision: AccessDecision,
+        access_id: &AccessId,
+        reason: Option<DenialReason>,
+        signature: &Signature,
+    ) {
+        let service_key = access_id.mode.service_key();
+        let mut event =
+            AccessEvent::new(decision, &access_id.mode, &access_id.request_pda, signature);
+        match decision {
+            AccessDecision::Granted => {
+                info!(%signature, user = %service_key, "access request granted");
+                metrics::counter!("doublezero_sentinel_access_granted").increment(1);
+            }
+            AccessDecision::Denied => {
+                info!(%signature, user = %service_key, reason = ?reason, "access request denied");
+                metrics::counter!("doublezero_sentinel_access_denied").increment(1);
+                if let Some(reason) = reason {
+                    reason.record();
+                    event = event.with_reason(reason);
+                }
+            }
         }
+        self.notifications.publish(event);
+        self.remember_decision(access_id.request_pda, decision);
+    }
 
-        Ok(())
+    fn remember_decision(&self, request_pda: Pubkey, decision: AccessDecision) {
+        if let Err(err) = self.dedupe_store.record(request_pda, decision) {
+            warn!(?err, %request_pda, "failed to record processed access request");
+        }
     }
 
-    async fn verify_qualifiers(&self, access_mode: &AccessMode) -> Result<Vec<(Pubkey, Ipv4Addr)>> {
-        let verifier = ValidatorVerifier::new(&self.sol_rpc_client, self.previous_leader_epochs);
+    /// Drop recorded decisions for requests missing from `listed`, whose accounts are closed
+    fn forget_closed(&self, listed: impl IntoIterator<Item = Pubkey>) {
+        if let Err(err) = self
+            .dedupe_store
+            .retain_listed(&listed.into_iter().collect())
+        {
+            warn!(?err, "failed to drop closed access requests");
+        }
+    }
+
+    async fn verify_qualifiers(&self, access_mode: &AccessMode) -> Result<Qualification> {
+        let verifier = ValidatorVerifier::new(&self.sol_rpc_client, self.previous_leader_epochs)
+            .with_ip_policy(&self.ip_policy)
+            .with_access_passes(&self.dz_rpc_client)
+            .with_access_modes(&self.access_modes)
+            .with_eligibility(&self.eligibility);
         verifier.verify_qualifiers(access_mode).await
     }
 }
@@ -483,7 +752,7 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_verify_qualifiers_signature_verify_error_returns_empty() {
+    async fn test_verify_qualifiers_signature_verify_error_denies() {
         // Build a real Sentinel; it won't hit network because we short-circuit on signature
         let (_tx, rx) = unbounded_channel();
         let keypair = Arc::new(Keypair::new());
@@ -496,6 +765,14 @@ mod tests {
             sol_rpc_client: SolRpcClient::new(sol_rpc, keypair),
             rx,
             previous_leader_epochs: 0,
+            dz_provisioning_retries: 8,
+            max_batch_size: 8,
+            ip_policy: IpPolicy::default(),
+            access_modes: AccessModes::default(),
+            eligibility: Eligibility::default(),
+            notifications: Notifications::default(),
+            dedupe_store: DedupeStore::in_memory(Duration::from_secs(60)),
+            deny_reason_memos: false,
         };
 
         // Invalid signature -> verify_access_request(...) should return Error::SignatureVerify
@@ -507,9 +784,10 @@ mod tests {
         let access_mode = AccessMode::SolanaValidator(attestation);
 
         let result = sentinel.verify_qualifiers(&access_mode).await.unwrap();
-        assert!(
-            result.is_empty(),
-            "expected empty vec when signature verification fails"
+        assert_eq!(
+            result,
+            Qualification::Denied(DenialReason::InvalidSignature),
+            "expected a signature denial when signature verification fails"
         );
     }
 }
@@ -0,0 +1,279 @@
+use crate::client::doublezero_ledger::AccessPassState;
+use doublezero_serviceability::state::accesspass::AccessPassType;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::{collections::HashMap, fmt, net::Ipv4Addr, str::FromStr};
+
+/// How requests whose endpoints fail the IP policy are treated
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum IpPolicyMode {
+    /// Do not check endpoints
+    #[default]
+    Off,
+    /// Log and count violations, but still grant access
+    Flag,
+    /// Deny access requests with any violation
+    Reject,
+}
+
+/// An IPv4 network in CIDR notation, e.g. `203.0.113.0/24`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Ipv4Cidr {
+    network: u32,
+    prefix_len: u8,
+}
+
+impl Ipv4Cidr {
+    pub fn contains(&self, ip: &Ipv4Addr) -> bool {
+        let mask = match self.prefix_len {
+            0 => 0,
+            len => u32::MAX << (32 - len),
+        };
+        u32::from(*ip) & mask == self.network & mask
+    }
+}
+
+impl FromStr for Ipv4Cidr {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (addr, prefix_len) = match s.split_once('/') {
+            Some((addr, len)) => (addr, len.parse::<u8>().map_err(|e| format!("{s}: {e}"))?),
+            None => (s, 32),
+        };
+        if prefix_len > 32 {
+            return Err(format!("{s}: prefix length must be at most 32"));
+        }
+        let addr = addr.parse::<Ipv4Addr>().map_err(|e| format!("{s}: {e}"))?;
+
+        Ok(Self {
+            network: u32::from(addr),
+            prefix_len,
+        })
+    }
+}
+
+impl fmt::Display for Ipv4Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", Ipv4Addr::from(self.network), self.prefix_len)
+    }
+}
+
+/// A reason a validator endpoint was considered implausible
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IpViolation {
+    /// Not a publicly routable address (RFC1918, loopback, link-local, CGNAT, ...)
+    NotPublic { validator_id: Pubkey, ip: Ipv4Addr },
+    /// The same address is claimed by more than one validator in the request
+    Duplicate { validator_id: Pubkey, ip: Ipv4Addr },
+    /// The address already has an access pass issued to another validator
+    DuplicatePass { validator_id: Pubkey, ip: Ipv4Addr },
+    /// Outside every configured allowlist prefix
+    NotAllowlisted { validator_id: Pubkey, ip: Ipv4Addr },
+}
+
+impl IpViolation {
+    /// Short label used for metrics
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::NotPublic { .. } => "not_public",
+            Self::Duplicate { .. } => "duplicate",
+            Self::DuplicatePass { .. } => "duplicate_pass",
+            Self::NotAllowlisted { .. } => "not_allowlisted",
+        }
+    }
+}
+
+impl fmt::Display for IpViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotPublic { validator_id, ip } => {
+                write!(f, "{validator_id} claims non-public address {ip}")
+            }
+            Self::Duplicate { validator_id, ip } => {
+                write!(
+                    f,
+                    "{validator_id} claims {ip}, already claimed in this request"
+                )
+            }
+            Self::DuplicatePass { validator_id, ip } => {
+                write!(
+                    f,
+                    "{validator_id} claims {ip}, which has an access pass issued to another validator"
+                )
+            }
+            Self::NotAllowlisted { validator_id, ip } => {
+                write!(f, "{validator_id} claims {ip}, outside the allowlist")
+            }
+        }
+    }
+}
+
+/// Sanity checks applied to the endpoints an access request would provision
+#[derive(Clone, Debug, Default)]
+pub struct IpPolicy {
+    pub mode: IpPolicyMode,
+    /// When non-empty, every endpoint must fall inside one of these prefixes
+    pub allowlist: Vec<Ipv4Cidr>,
+}
+
+impl IpPolicy {
+    pub fn new(mode: IpPolicyMode, allowlist: Vec<Ipv4Cidr>) -> Self {
+        Self { mode, allowlist }
+    }
+
+    /// Check the (validator_id, ip) pairs of a single request, given the access passes already
+    /// issued for its addresses
+    pub fn check(
+        &self,
+        validator_ips: &[(Pubkey, Ipv4Addr)],
+        existing_passes: &HashMap<Ipv4Addr, AccessPassType>,
+    ) -> Vec<IpViolation> {
+        if self.mode == IpPolicyMode::Off {
+            return vec![];
+        }
+
+        let mut violations = vec![];
+        let mut seen: HashMap<Ipv4Addr, Pubkey> = HashMap::new();
+
+        for (validator_id, ip) in validator_ips {
+            let (validator_id, ip) = (*validator_id, *ip);
+
+            if !is_public(&ip) {
+                violations.push(IpViolation::NotPublic { validator_id, ip });
+            }
+            if seen.insert(ip, validator_id).is_some() {
+                violations.push(IpViolation::Duplicate { validator_id, ip });
+            }
+            if AccessPassState::of(existing_passes.get(&ip), &validator_id)
+                == AccessPassState::Mismatched
+            {
+                violations.push(IpViolation::DuplicatePass { validator_id, ip });
+            }
+            if !self.allowlist.is_empty() && !self.allowlist.iter().any(|net| net.contains(&ip)) {
+                violations.push(IpViolation::NotAllowlisted { validator_id, ip });
+            }
+        }
+
+        violations
+    }
+}
+
+fn is_public(ip: &Ipv4Addr) -> bool {
+    // 100.64.0.0/10 shared address space (RFC6598) is not routable on the internet
+    let shared = ip.octets()[0] == 100 && (ip.octets()[1] & 0b1100_0000) == 64;
+
+    !(ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        || shared)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reject(allowlist: &[&str]) -> IpPolicy {
+        IpPolicy::new(
+            IpPolicyMode::Reject,
+            allowlist.iter().map(|net| net.parse().unwrap()).collect(),
+        )
+    }
+
+    #[test]
+    fn test_cidr_contains() {
+        let net: Ipv4Cidr = "64.130.0.0/16".parse().unwrap();
+        assert!(net.contains(&Ipv4Addr::new(64, 130, 7, 1)));
+        assert!(!net.contains(&Ipv4Addr::new(64, 131, 0, 1)));
+
+        let host: Ipv4Cidr = "64.130.7.1".parse().unwrap();
+        assert!(host.contains(&Ipv4Addr::new(64, 130, 7, 1)));
+        assert!(!host.contains(&Ipv4Addr::new(64, 130, 7, 2)));
+
+        assert!("64.130.0.0/33".parse::<Ipv4Cidr>().is_err());
+    }
+
+    #[test]
+    fn test_off_skips_checks() {
+        let policy = IpPolicy::default();
+        let ips = [(Pubkey::new_unique(), Ipv4Addr::new(10, 0, 0, 1))];
+        assert!(policy.check(&ips, &HashMap::new()).is_empty());
+    }
+
+    #[test]
+    fn test_rejects_non_public_and_duplicates() {
+        let policy = reject(&[]);
+        let (a, b, c) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let ips = [
+            (a, Ipv4Addr::new(64, 130, 7, 1)),
+            (b, Ipv4Addr::new(192, 168, 1, 1)),
+            (c, Ipv4Addr::new(64, 130, 7, 1)),
+        ];
+
+        let kinds: Vec<_> = policy
+            .check(&ips, &HashMap::new())
+            .iter()
+            .map(|v| v.kind())
+            .collect();
+        assert_eq!(kinds, vec!["not_public", "duplicate"]);
+        assert!(!is_public(&Ipv4Addr::new(100, 100, 0, 1)));
+    }
+
+    #[test]
+    fn test_rejects_addresses_with_another_validators_pass() {
+        let policy = reject(&[]);
+        let (validator_id, other_id) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (own_ip, taken_ip) = (Ipv4Addr::new(64, 130, 7, 1), Ipv4Addr::new(64, 130, 7, 2));
+        let existing_passes = HashMap::from([
+            (own_ip, AccessPassType::SolanaValidator(validator_id)),
+            (taken_ip, AccessPassType::SolanaValidator(other_id)),
+        ]);
+
+        assert!(
+            policy
+                .check(&[(validator_id, own_ip)], &existing_passes)
+                .is_empty()
+        );
+        assert_eq!(
+            policy.check(&[(validator_id, taken_ip)], &existing_passes),
+            vec![IpViolation::DuplicatePass {
+                validator_id,
+                ip: taken_ip
+            }]
+        );
+    }
+
+    #[test]
+    fn test_allowlist() {
+        let policy = reject(&["64.130.0.0/16"]);
+        let validator_id = Pubkey::new_unique();
+
+        assert!(
+            policy
+                .check(
+                    &[(validator_id, Ipv4Addr::new(64, 130, 1, 1))],
+                    &HashMap::new()
+                )
+                .is_empty()
+        );
+        assert_eq!(
+            policy.check(
+                &[(validator_id, Ipv4Addr::new(8, 8, 8, 8))],
+                &HashMap::new()
+            ),
+            vec![IpViolation::NotAllowlisted {
+                validator_id,
+                ip: Ipv4Addr::new(8, 8, 8, 8)
+            }]
+        );
+    }
+}
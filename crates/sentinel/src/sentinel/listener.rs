@@ -136,9 +136,11 @@ impl ReqListener {
 
             // Check the stream for new access requests and break on shutdown signals
             // If the stream returns a `None` then the server has disconnected and we resubscribe
-            while let Some(log_event) = request_stream.next().await
-                && !shutdown_listener.is_cancelled()
-            {
+            while let Some(log_event) = tokio::select! {
+                biased;
+                _ = shutdown_listener.cancelled() => None,
+                log_event = request_stream.next() => log_event,
+            } {
                 if log_event
                     .value
                     .logs
@@ -1,9 +1,19 @@
+pub mod access_modes;
+pub mod dedupe;
+pub mod denial;
+pub mod drain;
+pub mod eligibility;
+pub mod funding;
 pub mod handler;
+pub mod ip_policy;
 pub mod listener;
+pub mod notify;
 pub mod poller;
+pub mod provisioning;
+pub mod reconcile;
 pub mod verification;
 
 pub use handler::Sentinel;
 pub use listener::ReqListener;
 pub use poller::PollingSentinel;
-pub use verification::ValidatorVerifier;
+pub use verification::{Qualification, ValidatorVerifier};
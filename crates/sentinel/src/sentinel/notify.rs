@@ -0,0 +1,231 @@
+//! Outbound notifications of access request decisions
+//!
+//! Downstream provisioning systems subscribe to grant and deny events instead of polling chain
+//! state. Each transport implements [`AccessNotifier`]; publishing is best effort and never holds
+//! up handling of the next request.
+
+use crate::{Error, Result, sentinel::denial::DenialReason};
+use doublezero_passport::instruction::AccessMode;
+use serde::{Deserialize, Serialize};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use std::{sync::Arc, time::Duration};
+use tracing::{debug, warn};
+use url::Url;
+
+// Webhook deliveries that take longer than this are abandoned
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessDecision {
+    Granted,
+    Denied,
+}
+
+/// A decision on an access request, as published to downstream systems
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct AccessEvent {
+    pub decision: AccessDecision,
+    pub service_key: String,
+    pub validator_id: String,
+    pub backup_ids: Vec<String>,
+    pub request_pda: String,
+    pub signature: String,
+    /// Why the request was denied
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<DenialReason>,
+}
+
+impl AccessEvent {
+    pub fn new(
+        decision: AccessDecision,
+        access_mode: &AccessMode,
+        request_pda: &Pubkey,
+        signature: &Signature,
+    ) -> Self {
+        let (attestation, backup_ids) = match access_mode {
+            AccessMode::SolanaValidator(attestation) => (attestation, &[][..]),
+            AccessMode::SolanaValidatorWithBackupIds {
+                attestation,
+                backup_ids,
+            } => (attestation, backup_ids.as_slice()),
+        };
+
+        Self {
+            decision,
+            service_key: attestation.service_key.to_string(),
+            validator_id: attestation.validator_id.to_string(),
+            backup_ids: backup_ids.iter().map(ToString::to_string).collect(),
+            request_pda: request_pda.to_string(),
+            signature: signature.to_string(),
+            reason: None,
+        }
+    }
+
+    pub fn with_reason(mut self, reason: DenialReason) -> Self {
+        self.reason = Some(reason);
+        self
+    }
+}
+
+/// A transport access decisions are published over
+#[async_trait::async_trait]
+pub trait AccessNotifier: Send + Sync {
+    /// Short transport name used in logs and metrics
+    fn name(&self) -> &'static str;
+
+    async fn notify(&self, event: &AccessEvent) -> Result<()>;
+}
+
+/// Posts each event as a JSON body to an HTTP endpoint
+pub struct WebhookNotifier {
+    client: reqwest::Client,
+    url: Url,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: Url) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(WEBHOOK_TIMEOUT)
+            .build()
+            .expect("failed to build webhook client");
+        Self { client, url }
+    }
+}
+
+#[async_trait::async_trait]
+impl AccessNotifier for WebhookNotifier {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    async fn notify(&self, event: &AccessEvent) -> Result<()> {
+        self.client
+            .post(self.url.clone())
+            .json(event)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|err| Error::Notification(err.to_string()))?;
+        Ok(())
+    }
+}
+
+/// Every configured transport; with none configured, publishing is a no-op
+#[derive(Clone, Default)]
+pub struct Notifications {
+    notifiers: Vec<Arc<dyn AccessNotifier>>,
+}
+
+impl Notifications {
+    pub fn new(notifiers: Vec<Arc<dyn AccessNotifier>>) -> Self {
+        Self { notifiers }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.notifiers.is_empty()
+    }
+
+    /// Publish `event` on every transport in the background
+    pub fn publish(&self, event: AccessEvent) {
+        for notifier in &self.notifiers {
+            let notifier = notifier.clone();
+            let event = event.clone();
+            tokio::spawn(async move { deliver(notifier.as_ref(), &event).await });
+        }
+    }
+}
+
+async fn deliver(notifier: &dyn AccessNotifier, event: &AccessEvent) {
+    let transport = notifier.name();
+    match notifier.notify(event).await {
+        Ok(()) => {
+            debug!(transport, request_pda = %event.request_pda, "access decision published");
+            metrics::counter!("doublezero_sentinel_notification_sent", "transport" => transport)
+                .increment(1);
+        }
+        Err(err) => {
+            warn!(?err, transport, request_pda = %event.request_pda, "failed to publish access decision");
+            metrics::counter!("doublezero_sentinel_notification_failed", "transport" => transport)
+                .increment(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use doublezero_passport::instruction::SolanaValidatorAttestation;
+    use tokio::sync::mpsc::{UnboundedSender, unbounded_channel};
+
+    struct ChannelNotifier(UnboundedSender<AccessEvent>);
+
+    #[async_trait::async_trait]
+    impl AccessNotifier for ChannelNotifier {
+        fn name(&self) -> &'static str {
+            "channel"
+        }
+
+        async fn notify(&self, event: &AccessEvent) -> Result<()> {
+            self.0.send(event.clone()).unwrap();
+            Ok(())
+        }
+    }
+
+    fn access_mode(backup_ids: Vec<Pubkey>) -> AccessMode {
+        AccessMode::SolanaValidatorWithBackupIds {
+            attestation: SolanaValidatorAttestation {
+                validator_id: Pubkey::new_unique(),
+                service_key: Pubkey::new_unique(),
+                ed25519_signature: [0; 64],
+            },
+            backup_ids,
+        }
+    }
+
+    #[test]
+    fn test_access_event_json() {
+        let backup_id = Pubkey::new_unique();
+        let request_pda = Pubkey::new_unique();
+        let mode = access_mode(vec![backup_id]);
+        let event = AccessEvent::new(
+            AccessDecision::Denied,
+            &mode,
+            &request_pda,
+            &Signature::default(),
+        );
+        assert!(
+            serde_json::to_value(&event)
+                .unwrap()
+                .get("reason")
+                .is_none()
+        );
+
+        let json = serde_json::to_value(event.with_reason(DenialReason::NotInGossip)).unwrap();
+        assert_eq!(json["decision"], "denied");
+        assert_eq!(json["service_key"], mode.service_key().to_string());
+        assert_eq!(json["backup_ids"][0], backup_id.to_string());
+        assert_eq!(json["request_pda"], request_pda.to_string());
+        assert_eq!(json["reason"], "not_in_gossip");
+    }
+
+    #[tokio::test]
+    async fn test_publish_reaches_every_transport() {
+        let (tx, mut rx) = unbounded_channel();
+        let notifications = Notifications::new(vec![
+            Arc::new(ChannelNotifier(tx.clone())),
+            Arc::new(ChannelNotifier(tx)),
+        ]);
+
+        let event = AccessEvent::new(
+            AccessDecision::Granted,
+            &access_mode(vec![]),
+            &Pubkey::new_unique(),
+            &Signature::default(),
+        );
+        notifications.publish(event.clone());
+
+        assert_eq!(rx.recv().await, Some(event.clone()));
+        assert_eq!(rx.recv().await, Some(event));
+    }
+}
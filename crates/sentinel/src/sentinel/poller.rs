@@ -1,20 +1,29 @@
 use crate::{
-    AccessId, Result,
+    AccessId, AccessMessageCache, Result,
     client::{doublezero_ledger::DzRpcClient, solana::SolRpcClient},
     error::rpc_with_retry,
-    sentinel::ValidatorVerifier,
+    sentinel::{
+        Qualification, ValidatorVerifier,
+        access_modes::AccessModes,
+        dedupe::DedupeStore,
+        eligibility::Eligibility,
+        ip_policy::IpPolicy,
+        notify::{AccessDecision, AccessEvent, Notifications},
+        provisioning::provision_access_passes,
+        reconcile::reconcile,
+    },
 };
 use doublezero_passport::instruction::AccessMode;
+use futures::{StreamExt, stream};
 use retainer::Cache;
 use solana_sdk::{pubkey::Pubkey, signature::Keypair};
 use std::{
-    net::Ipv4Addr,
     sync::Arc,
     time::{Duration, Instant},
 };
 use tokio::time::interval;
 use tokio_util::sync::CancellationToken;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use url::Url;
 
 // cache ttl: 5 minutes
@@ -28,6 +37,14 @@ pub struct PollingSentinel {
     processed_cache: Arc<Cache<Pubkey, Instant>>,
     poll_interval: Duration,
     previous_leader_epochs: u8,
+    dz_provisioning_retries: usize,
+    verification_concurrency: usize,
+    ip_policy: IpPolicy,
+    access_modes: AccessModes,
+    eligibility: Eligibility,
+    notifications: Notifications,
+    dedupe_store: DedupeStore,
+    deny_reason_memos: bool,
 }
 
 impl PollingSentinel {
@@ -38,6 +55,14 @@ impl PollingSentinel {
         serviceability_id: Pubkey,
         poll_interval_secs: u64,
         previous_leader_epochs: u8,
+        dz_provisioning_retries: usize,
+        verification_concurrency: usize,
+        ip_policy: IpPolicy,
+        access_modes: AccessModes,
+        eligibility: Eligibility,
+        notifications: Notifications,
+        dedupe_store: DedupeStore,
+        deny_reason_memos: bool,
     ) -> Result<Self> {
         // Create cache with automatic background cleanup
         let processed_cache = Arc::new(Cache::new());
@@ -55,10 +80,20 @@ impl PollingSentinel {
             processed_cache,
             poll_interval: Duration::from_secs(poll_interval_secs),
             previous_leader_epochs,
+            dz_provisioning_retries,
+            verification_concurrency: verification_concurrency.max(1),
+            ip_policy,
+            access_modes,
+            eligibility,
+            notifications,
+            dedupe_store,
+            deny_reason_memos,
         })
     }
 
     pub async fn run(&mut self, shutdown_listener: CancellationToken) -> Result<()> {
+        self.reconcile_on_startup().await;
+
         let mut poll_timer = interval(self.poll_interval);
 
         loop {
@@ -83,6 +118,8 @@ impl PollingSentinel {
                         }
                     };
 
+                    self.forget_closed(access_ids.iter().map(|access_id| access_id.request_pda));
+
                     // Filter out already-processed requests
                     let mut new_requests = Vec::new();
                     let mut duplicate_count = 0;
@@ -93,6 +130,8 @@ impl PollingSentinel {
                             let age = processed_at.elapsed();
                             metrics::counter!("doublezero_sentinel_duplicate_request_filtered").increment(1);
                             metrics::histogram!("doublezero_sentinel_duplicate_age_seconds").record(age.as_secs_f64());
+                        } else if self.dedupe_store.already_decided(&access_id.request_pda) {
+                            duplicate_count += 1;
                         } else {
                             new_requests.push(access_id);
                         }
@@ -107,12 +146,39 @@ impl PollingSentinel {
 
                     info!(count = new_requests.len(), "processing unhandled access requests");
 
-                    for access_id in new_requests {
+                    // Requests are verified concurrently, while grants and denials are issued one
+                    // at a time in the order the requests were fetched
+                    let batch_started = Instant::now();
+                    let message_cache = AccessMessageCache::default();
+                    let mut verified_requests = stream::iter(new_requests)
+                        .map(|access_id| {
+                            let message_cache = &message_cache;
+                            async move {
+                                let verified = self
+                                    .verify_qualifiers_cached(&access_id.mode, message_cache)
+                                    .await;
+                                (access_id, verified)
+                            }
+                        })
+                        .buffered(self.verification_concurrency);
+
+                    while let Some((access_id, verified)) = verified_requests.next().await {
+                        // Requests left behind while draining are reconciled by the next instance
+                        if shutdown_listener.is_cancelled() {
+                            break;
+                        }
                         let request_pda = access_id.request_pda;
-                        match self.handle_access_request(access_id).await {
-                            Ok(_) => {
+                        let handled = match verified {
+                            Ok(qualification) => {
+                                self.decide_access_request(access_id, qualification).await
+                            }
+                            Err(err) => Err(err),
+                        };
+                        match handled {
+                            Ok(decision) => {
                                 // Only cache after successful processing
                                 self.processed_cache.insert(request_pda, Instant::now(), CACHE_TTL).await;
+                                self.remember_decision(request_pda, decision);
                             }
                             Err(err) => {
                                 error!(?err, "error encountered validating network access request; will retry on next poll");
@@ -120,6 +186,8 @@ impl PollingSentinel {
                             }
                         }
                     }
+                    metrics::histogram!("doublezero_sentinel_poll_batch_duration_seconds")
+                        .record(batch_started.elapsed().as_secs_f64());
                 }
             }
         }
@@ -127,7 +195,70 @@ impl PollingSentinel {
         Ok(())
     }
 
-    async fn handle_access_request(&self, access_id: AccessId) -> Result<()> {
+    /// Handle the requests that are genuinely pending on startup and cache everything handled,
+    /// so the first poll only picks up what is left
+    async fn reconcile_on_startup(&self) {
+        let verifier = ValidatorVerifier::new(&self.sol_rpc_client, self.previous_leader_epochs)
+            .with_ip_policy(&self.ip_policy)
+            .with_access_passes(&self.dz_rpc_client)
+            .with_access_modes(&self.access_modes)
+            .with_eligibility(&self.eligibility);
+        let reconciliation = match reconcile(
+            &self.dz_rpc_client,
+            &self.sol_rpc_client,
+            &verifier,
+            &self.notifications,
+        )
+        .await
+        {
+            Ok(reconciliation) => reconciliation,
+            Err(err) => {
+                error!(
+                    ?err,
+                    "startup reconciliation failed; the first poll will handle all requests"
+                );
+                metrics::counter!("doublezero_sentinel_startup_reconciliation_failed").increment(1);
+                return;
+            }
+        };
+
+        self.forget_closed(
+            reconciliation
+                .pending
+                .iter()
+                .map(|access_id| access_id.request_pda)
+                .chain(reconciliation.granted.iter().copied()),
+        );
+        for request_pda in reconciliation.granted {
+            self.processed_cache
+                .insert(request_pda, Instant::now(), CACHE_TTL)
+                .await;
+            self.remember_decision(request_pda, AccessDecision::Granted);
+        }
+
+        for access_id in reconciliation.pending {
+            let request_pda = access_id.request_pda;
+            if self.dedupe_store.already_decided(&request_pda) {
+                continue;
+            }
+            match self.handle_access_request(access_id).await {
+                Ok(decision) => {
+                    self.processed_cache
+                        .insert(request_pda, Instant::now(), CACHE_TTL)
+                        .await;
+                    self.remember_decision(request_pda, decision);
+                }
+                Err(err) => {
+                    error!(
+                        ?err,
+                        "error encountered validating network access request; will retry on next poll"
+                    );
+                }
+            }
+        }
+    }
+
+    async fn handle_access_request(&self, access_id: AccessId) -> Result<AccessDecision> {
         let service_key = match &access_id.mode {
             AccessMode::SolanaValidator(a) => a.service_key,
             AccessMode::SolanaValidatorWithBackupIds { attestation, .. } => attestation.service_key,
@@ -135,53 +266,119 @@ impl PollingSentinel {
 
         info!(%service_key, request_pda = %access_id.request_pda, "handling access request");
 
-        let validator_ips = self.verify_qualifiers(&access_id.mode).await?;
+        let qualification = self.verify_qualifiers(&access_id.mode).await?;
+
+        self.decide_access_request(access_id, qualification).await
+    }
+
+    /// Grant the request to the verified validators, or deny it with the reason it failed
+    async fn decide_access_request(
+        &self,
+        access_id: AccessId,
+        qualification: Qualification,
+    ) -> Result<AccessDecision> {
+        let service_key = match &access_id.mode {
+            AccessMode::SolanaValidator(a) => a.service_key,
+            AccessMode::SolanaValidatorWithBackupIds { attestation, .. } => attestation.service_key,
+        };
+
+        let decision = match qualification {
+            Qualification::Qualified(validator_ips) => {
+                // Issue access passes for all validators (primary + backups), skipping any
+                // already provisioned by an earlier, interrupted attempt
+                provision_access_passes(
+                    &self.dz_rpc_client,
+                    &service_key,
+                    &validator_ips,
+                    self.dz_provisioning_retries,
+                )
+                .await?;
 
-        if !validator_ips.is_empty() {
-            // Issue access passes for all validators (primary + backups)
-            for (validator_id, validator_ip) in validator_ips {
-                rpc_with_retry(
+                let signature = rpc_with_retry(
                     || async {
-                        self.dz_rpc_client
-                            .issue_access_pass(&service_key, &validator_ip, &validator_id)
+                        self.sol_rpc_client
+                            .grant_access(&access_id.request_pda, &access_id.rent_beneficiary_key)
                             .await
                     },
-                    "issue_access_pass",
+                    "grant_access",
                 )
                 .await?;
-                info!(%validator_id, %validator_ip, user = %service_key, "access pass issued");
+                info!(%signature, user = %service_key, "access request granted");
+                metrics::counter!("doublezero_sentinel_access_granted").increment(1);
+                self.notifications.publish(AccessEvent::new(
+                    AccessDecision::Granted,
+                    &access_id.mode,
+                    &access_id.request_pda,
+                    &signature,
+                ));
+                AccessDecision::Granted
             }
+            Qualification::Denied(reason) => {
+                let memo = self.deny_reason_memos.then(|| reason.memo());
+                let signature = rpc_with_retry(
+                    || async {
+                        self.sol_rpc_client
+                            .deny_access(&access_id.request_pda, memo.clone())
+                            .await
+                    },
+                    "deny_access",
+                )
+                .await?;
+                info!(%signature, user = %service_key, %reason, "access request denied");
+                metrics::counter!("doublezero_sentinel_access_denied").increment(1);
+                reason.record();
+                self.notifications.publish(
+                    AccessEvent::new(
+                        AccessDecision::Denied,
+                        &access_id.mode,
+                        &access_id.request_pda,
+                        &signature,
+                    )
+                    .with_reason(reason),
+                );
+                AccessDecision::Denied
+            }
+        };
+
+        Ok(decision)
+    }
 
-            let signature = rpc_with_retry(
-                || async {
-                    self.sol_rpc_client
-                        .grant_access(&access_id.request_pda, &access_id.rent_beneficiary_key)
-                        .await
-                },
-                "grant_access",
-            )
-            .await?;
-            info!(%signature, user = %service_key, "access request granted");
-            metrics::counter!("doublezero_sentinel_access_granted").increment(1);
-        } else {
-            let signature = rpc_with_retry(
-                || async {
-                    self.sol_rpc_client
-                        .deny_access(&access_id.request_pda)
-                        .await
-                },
-                "deny_access",
-            )
-            .await?;
-            info!(%signature, user = %service_key, "access request denied");
-            metrics::counter!("doublezero_sentinel_access_denied").increment(1);
+    fn remember_decision(&self, request_pda: Pubkey, decision: AccessDecision) {
+        if let Err(err) = self.dedupe_store.record(request_pda, decision) {
+            warn!(?err, %request_pda, "failed to record processed access request");
         }
+    }
 
-        Ok(())
+    /// Drop recorded decisions for requests missing from `listed`, whose accounts are closed
+    fn forget_closed(&self, listed: impl IntoIterator<Item = Pubkey>) {
+        if let Err(err) = self
+            .dedupe_store
+            .retain_listed(&listed.into_iter().collect())
+        {
+            warn!(?err, "failed to drop closed access requests");
+        }
     }
 
-    async fn verify_qualifiers(&self, access_mode: &AccessMode) -> Result<Vec<(Pubkey, Ipv4Addr)>> {
-        let verifier = ValidatorVerifier::new(&self.sol_rpc_client, self.previous_leader_epochs);
+    async fn verify_qualifiers(&self, access_mode: &AccessMode) -> Result<Qualification> {
+        let verifier = ValidatorVerifier::new(&self.sol_rpc_client, self.previous_leader_epochs)
+            .with_ip_policy(&self.ip_policy)
+            .with_access_passes(&self.dz_rpc_client)
+            .with_access_modes(&self.access_modes)
+            .with_eligibility(&self.eligibility);
+        verifier.verify_qualifiers(access_mode).await
+    }
+
+    async fn verify_qualifiers_cached(
+        &self,
+        access_mode: &AccessMode,
+        message_cache: &AccessMessageCache,
+    ) -> Result<Qualification> {
+        let verifier = ValidatorVerifier::new(&self.sol_rpc_client, self.previous_leader_epochs)
+            .with_ip_policy(&self.ip_policy)
+            .with_access_passes(&self.dz_rpc_client)
+            .with_access_modes(&self.access_modes)
+            .with_eligibility(&self.eligibility)
+            .with_message_cache(message_cache);
         verifier.verify_qualifiers(access_mode).await
     }
 }
@@ -189,6 +386,7 @@ impl PollingSentinel {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sentinel::denial::DenialReason;
     use doublezero_passport::instruction::SolanaValidatorAttestation;
     use solana_sdk::pubkey::Pubkey;
 
@@ -261,7 +459,7 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_verify_qualifiers_signature_verify_error_returns_empty() {
+    async fn test_verify_qualifiers_signature_verify_error_denies() {
         // Build a real PollingSentinel; it won't hit network because we short-circuit on signature
         let keypair = Arc::new(Keypair::new());
         let dz_rpc = Url::parse("http://127.0.0.1:1234").unwrap();
@@ -274,6 +472,14 @@ mod tests {
             processed_cache: Arc::new(Cache::new()),
             poll_interval: Duration::from_secs(15),
             previous_leader_epochs: 0,
+            dz_provisioning_retries: 8,
+            verification_concurrency: 1,
+            ip_policy: IpPolicy::default(),
+            access_modes: AccessModes::default(),
+            eligibility: Eligibility::default(),
+            notifications: Notifications::default(),
+            dedupe_store: DedupeStore::in_memory(Duration::from_secs(60)),
+            deny_reason_memos: false,
         };
 
         // Invalid signature -> verify_access_request(...) should return Error::SignatureVerify
@@ -285,9 +491,10 @@ mod tests {
         let access_mode = AccessMode::SolanaValidator(attestation);
 
         let result = sentinel.verify_qualifiers(&access_mode).await.unwrap();
-        assert!(
-            result.is_empty(),
-            "expected empty vec when signature verification fails"
+        assert_eq!(
+            result,
+            Qualification::Denied(DenialReason::InvalidSignature),
+            "expected a signature denial when signature verification fails"
         );
     }
 }
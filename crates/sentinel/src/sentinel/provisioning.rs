@@ -0,0 +1,258 @@
+use crate::{
+    AccessId, Result,
+    client::{
+        doublezero_ledger::{AccessPassState, DzRpcClient},
+        solana::SolRpcClient,
+    },
+    error::{rpc_with_retry, rpc_with_retry_times},
+    sentinel::{
+        Qualification, ValidatorVerifier, access_modes::AccessModes, eligibility::Eligibility,
+        ip_policy::IpPolicy,
+    },
+};
+use solana_sdk::pubkey::Pubkey;
+use std::net::Ipv4Addr;
+use tracing::{info, warn};
+
+/// Ensure every validator has an access pass on the DZ ledger, issuing only the missing ones
+///
+/// Provisioning is idempotent: re-running it after a partial failure (e.g. a restart between
+/// issuing passes and granting the Solana-side request) only fills in what is missing.
+pub async fn provision_access_passes(
+    dz_rpc_client: &DzRpcClient,
+    service_key: &Pubkey,
+    validator_ips: &[(Pubkey, Ipv4Addr)],
+    max_retries: usize,
+) -> Result<()> {
+    for (validator_id, validator_ip) in validator_ips {
+        let signature = rpc_with_retry_times(
+            || async {
+                dz_rpc_client
+                    .ensure_access_pass(service_key, validator_ip, validator_id)
+                    .await
+            },
+            "ensure_access_pass",
+            max_retries,
+        )
+        .await?;
+
+        if let Some(signature) = signature {
+            info!(%validator_id, %validator_ip, user = %service_key, %signature, "access pass issued");
+        }
+    }
+
+    Ok(())
+}
+
+/// Counts reported by a repair pass
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RepairSummary {
+    /// Outstanding access requests inspected
+    pub requests: usize,
+    /// Access passes already present on the DZ ledger
+    pub passes_present: usize,
+    /// Access passes missing from the DZ ledger (issued unless running dry)
+    pub passes_missing: usize,
+    /// Access passes held by a different validator; their requests are left for manual
+    /// reconciliation
+    pub passes_mismatched: usize,
+    /// Requests granted on Solana after their passes were reconciled
+    pub granted: usize,
+    /// Requests denied because no validator qualified
+    pub denied: usize,
+    /// Requests that could not be reconciled
+    pub failed: usize,
+}
+
+impl RepairSummary {
+    fn count_pass(&mut self, state: AccessPassState) {
+        match state {
+            AccessPassState::Missing => self.passes_missing += 1,
+            AccessPassState::Present => self.passes_present += 1,
+            AccessPassState::Mismatched => self.passes_mismatched += 1,
+        }
+    }
+}
+
+/// Reconcile every outstanding access request against the DZ ledger in a single pass
+///
+/// Requests whose DZ-side provisioning was interrupted are completed (missing passes issued,
+/// then the request granted). Requests with a pass held by a different validator are reported
+/// and left untouched. With `dry_run`, only the split state is reported.
+pub async fn repair(
+    dz_rpc_client: &DzRpcClient,
+    sol_rpc_client: &SolRpcClient,
+    previous_leader_epochs: u8,
+    ip_policy: &IpPolicy,
+    access_modes: &AccessModes,
+    eligibility: &Eligibility,
+    max_retries: usize,
+    dry_run: bool,
+) -> Result<RepairSummary> {
+    let access_ids = rpc_with_retry(
+        || async { sol_rpc_client.get_access_requests().await },
+        "get_access_requests",
+    )
+    .await?;
+
+    let verifier = ValidatorVerifier::new(sol_rpc_client, previous_leader_epochs)
+        .with_ip_policy(ip_policy)
+        .with_access_passes(dz_rpc_client)
+        .with_access_modes(access_modes)
+        .with_eligibility(eligibility);
+    let mut summary = RepairSummary {
+        requests: access_ids.len(),
+        ..Default::default()
+    };
+
+    for access_id in access_ids {
+        if let Err(err) = repair_request(
+            dz_rpc_client,
+            sol_rpc_client,
+            &verifier,
+            &access_id,
+            max_retries,
+            dry_run,
+            &mut summary,
+        )
+        .await
+        {
+            warn!(?err, request_pda = %access_id.request_pda, "failed to repair access request");
+            summary.failed += 1;
+        }
+    }
+
+    Ok(summary)
+}
+
+async fn repair_request(
+    dz_rpc_client: &DzRpcClient,
+    sol_rpc_client: &SolRpcClient,
+    verifier: &ValidatorVerifier<'_>,
+    access_id: &AccessId,
+    max_retries: usize,
+    dry_run: bool,
+    summary: &mut RepairSummary,
+) -> Result<()> {
+    let service_key = access_id.mode.service_key();
+    let validator_ips = match verifier.verify_qualifiers(&access_id.mode).await? {
+        Qualification::Qualified(validator_ips) => validator_ips,
+        Qualification::Denied(reason) => {
+            info!(user = %service_key, request_pda = %access_id.request_pda, %reason, dry_run, "request does not qualify; denying");
+            if !dry_run {
+                rpc_with_retry(
+                    || async {
+                        sol_rpc_client
+                            .deny_access(&access_id.request_pda, None)
+                            .await
+                    },
+                    "deny_access",
+                )
+                .await?;
+                reason.record();
+            }
+            summary.denied += 1;
+            return Ok(());
+        }
+    };
+
+    let mut mismatched = false;
+    for (validator_id, validator_ip) in &validator_ips {
+        let pass = rpc_with_retry(
+            || async {
+                dz_rpc_client
+                    .get_access_pass(&service_key, validator_ip)
+                    .await
+            },
+            "get_access_pass",
+        )
+        .await?;
+
+        let pass_type = pass.as_ref().map(|pass| &pass.accesspass_type);
+        let state = AccessPassState::of(pass_type, validator_id);
+        match state {
+            AccessPassState::Missing => {
+                info!(%validator_id, %validator_ip, user = %service_key, "access pass missing");
+            }
+            AccessPassState::Mismatched => {
+                warn!(%validator_id, %validator_ip, user = %service_key, existing = ?pass_type, "access pass held by a different validator");
+                mismatched = true;
+            }
+            AccessPassState::Present => {}
+        }
+        summary.count_pass(state);
+    }
+
+    if mismatched {
+        warn!(user = %service_key, request_pda = %access_id.request_pda, "leaving request with a mismatched access pass for manual reconciliation");
+        return Ok(());
+    }
+    if dry_run {
+        return Ok(());
+    }
+
+    provision_access_passes(dz_rpc_client, &service_key, &validator_ips, max_retries).await?;
+
+    let signature = rpc_with_retry(
+        || async {
+            sol_rpc_client
+                .grant_access(&access_id.request_pda, &access_id.rent_beneficiary_key)
+                .await
+        },
+        "grant_access",
+    )
+    .await?;
+    info!(%signature, user = %service_key, "access request granted");
+    summary.granted += 1;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use doublezero_serviceability::state::accesspass::AccessPassType;
+
+    #[test]
+    fn test_access_pass_states() {
+        let validator_id = Pubkey::new_unique();
+        let present = AccessPassType::SolanaValidator(validator_id);
+        let other = AccessPassType::SolanaValidator(Pubkey::new_unique());
+
+        assert_eq!(
+            AccessPassState::of(None, &validator_id),
+            AccessPassState::Missing
+        );
+        assert_eq!(
+            AccessPassState::of(Some(&present), &validator_id),
+            AccessPassState::Present
+        );
+        assert_eq!(
+            AccessPassState::of(Some(&other), &validator_id),
+            AccessPassState::Mismatched
+        );
+    }
+
+    #[test]
+    fn test_repair_summary_counts_pass_states() {
+        let mut summary = RepairSummary::default();
+        for state in [
+            AccessPassState::Present,
+            AccessPassState::Missing,
+            AccessPassState::Present,
+            AccessPassState::Mismatched,
+        ] {
+            summary.count_pass(state);
+        }
+
+        assert_eq!(
+            summary,
+            RepairSummary {
+                passes_present: 2,
+                passes_missing: 1,
+                passes_mismatched: 1,
+                ..Default::default()
+            }
+        );
+    }
+}
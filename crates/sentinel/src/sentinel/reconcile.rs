@@ -0,0 +1,225 @@
+use crate::{
+    AccessId, Result,
+    client::{doublezero_ledger::DzRpcClient, solana::SolRpcClient},
+    error::rpc_with_retry,
+    sentinel::{
+        Qualification, ValidatorVerifier,
+        notify::{AccessDecision, AccessEvent, Notifications},
+    },
+};
+use doublezero_passport::instruction::AccessMode;
+use doublezero_serviceability::state::accesspass::AccessPassType;
+use solana_sdk::pubkey::Pubkey;
+use tracing::{info, warn};
+
+/// Where an outstanding access request stands, judged from on-chain state
+///
+/// Granting or denying a request closes its Solana account, so every request found on startup
+/// is still open on Solana. What differs is how far its DZ-side provisioning got.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestState {
+    /// Every validator in the request already has an access pass; only the grant is outstanding
+    Provisioned,
+    /// Some, but not all, access passes were issued before the previous run stopped
+    PartiallyProvisioned,
+    /// No DZ-side state yet
+    Pending,
+}
+
+impl RequestState {
+    pub fn classify(passes_present: usize, validators: usize) -> Self {
+        match passes_present {
+            0 => Self::Pending,
+            n if n >= validators => Self::Provisioned,
+            _ => Self::PartiallyProvisioned,
+        }
+    }
+
+    /// Short label used for metrics
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Provisioned => "provisioned",
+            Self::PartiallyProvisioned => "partially_provisioned",
+            Self::Pending => "pending",
+        }
+    }
+}
+
+/// Counts reported by startup reconciliation
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ReconciliationSummary {
+    /// Outstanding access requests found on Solana
+    pub requests: usize,
+    /// Requests whose access passes were all present
+    pub provisioned: usize,
+    /// Requests with some access passes present
+    pub partially_provisioned: usize,
+    /// Requests with no access passes present
+    pub pending: usize,
+    /// Provisioned requests granted during reconciliation
+    pub granted: usize,
+}
+
+impl ReconciliationSummary {
+    fn record(&mut self, state: RequestState) {
+        match state {
+            RequestState::Provisioned => self.provisioned += 1,
+            RequestState::PartiallyProvisioned => self.partially_provisioned += 1,
+            RequestState::Pending => self.pending += 1,
+        }
+        metrics::counter!("doublezero_sentinel_startup_reconciled", "state" => state.kind())
+            .increment(1);
+    }
+}
+
+/// Outcome of startup reconciliation
+#[derive(Debug, Default)]
+pub struct Reconciliation {
+    pub summary: ReconciliationSummary,
+    /// Requests that still need to be verified and handled
+    pub pending: Vec<AccessId>,
+    /// Requests granted during reconciliation
+    pub granted: Vec<Pubkey>,
+}
+
+/// Scan outstanding access requests and sort out which ones genuinely need handling
+///
+/// Requests whose access passes were all issued by a previous run are granted straight away once
+/// they pass verification, since anyone can open a request for a service key that already holds
+/// passes; everything else is returned for the regular handler.
+pub async fn reconcile(
+    dz_rpc_client: &DzRpcClient,
+    sol_rpc_client: &SolRpcClient,
+    verifier: &ValidatorVerifier<'_>,
+    notifications: &Notifications,
+) -> Result<Reconciliation> {
+    let access_ids = rpc_with_retry(
+        || async { sol_rpc_client.get_access_requests().await },
+        "get_access_requests",
+    )
+    .await?;
+    let gossip_ips = rpc_with_retry(
+        || async { sol_rpc_client.get_gossip_ips().await },
+        "get_gossip_ips",
+    )
+    .await?;
+
+    let mut reconciliation = Reconciliation {
+        summary: ReconciliationSummary {
+            requests: access_ids.len(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    for access_id in access_ids {
+        let service_key = access_id.mode.service_key();
+        let validator_ids = validator_ids(&access_id.mode);
+
+        let mut passes_present = 0;
+        for validator_id in &validator_ids {
+            let Some(validator_ip) = gossip_ips.get(validator_id) else {
+                continue;
+            };
+            let pass = rpc_with_retry(
+                || async {
+                    dz_rpc_client
+                        .get_access_pass(&service_key, validator_ip)
+                        .await
+                },
+                "get_access_pass",
+            )
+            .await?;
+            let issued_to_validator = pass.is_some_and(|pass| {
+                matches!(pass.accesspass_type, AccessPassType::SolanaValidator(id) if id == *validator_id)
+            });
+            if issued_to_validator {
+                passes_present += 1;
+            }
+        }
+
+        let state = RequestState::classify(passes_present, validator_ids.len());
+        reconciliation.summary.record(state);
+
+        if state != RequestState::Provisioned {
+            reconciliation.pending.push(access_id);
+            continue;
+        }
+
+        // Requests that do not qualify are left for the regular handler to deny with a reason
+        let request_pda = access_id.request_pda;
+        match verifier.verify_qualifiers(&access_id.mode).await {
+            Ok(Qualification::Qualified(_)) => {}
+            Ok(Qualification::Denied(reason)) => {
+                info!(user = %service_key, %request_pda, %reason, "provisioned access request does not qualify; handling it as pending");
+                reconciliation.pending.push(access_id);
+                continue;
+            }
+            Err(err) => {
+                warn!(?err, %request_pda, "failed to verify provisioned access request; handling it as pending");
+                reconciliation.pending.push(access_id);
+                continue;
+            }
+        }
+
+        match rpc_with_retry(
+            || async {
+                sol_rpc_client
+                    .grant_access(&request_pda, &access_id.rent_beneficiary_key)
+                    .await
+            },
+            "grant_access",
+        )
+        .await
+        {
+            Ok(signature) => {
+                info!(%signature, user = %service_key, %request_pda, "previously provisioned access request granted");
+                metrics::counter!("doublezero_sentinel_access_granted").increment(1);
+                reconciliation.summary.granted += 1;
+                notifications.publish(AccessEvent::new(
+                    AccessDecision::Granted,
+                    &access_id.mode,
+                    &request_pda,
+                    &signature,
+                ));
+                reconciliation.granted.push(request_pda);
+            }
+            Err(err) => {
+                warn!(?err, %request_pda, "failed to grant provisioned access request; handling it as pending");
+                reconciliation.pending.push(access_id);
+            }
+        }
+    }
+
+    info!(summary = ?reconciliation.summary, "startup reconciliation complete");
+
+    Ok(reconciliation)
+}
+
+fn validator_ids(access_mode: &AccessMode) -> Vec<Pubkey> {
+    match access_mode {
+        AccessMode::SolanaValidator(attestation) => vec![attestation.validator_id],
+        AccessMode::SolanaValidatorWithBackupIds {
+            attestation,
+            backup_ids,
+        } => std::iter::once(attestation.validator_id)
+            .chain(backup_ids.iter().copied())
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(RequestState::classify(0, 1), RequestState::Pending);
+        assert_eq!(RequestState::classify(1, 1), RequestState::Provisioned);
+        assert_eq!(
+            RequestState::classify(2, 3),
+            RequestState::PartiallyProvisioned
+        );
+        assert_eq!(RequestState::classify(3, 3), RequestState::Provisioned);
+    }
+}
@@ -1,15 +1,43 @@
 use crate::{
-    Error, Result, client::solana::SolRpcClient, error::rpc_with_retry, verify_access_request,
+    AccessMessageCache, Error, Result,
+    client::{doublezero_ledger::DzRpcClient, solana::SolRpcClient},
+    error::rpc_with_retry,
+    sentinel::{
+        access_modes::{AccessModeKind, AccessModes},
+        denial::DenialReason,
+        eligibility::Eligibility,
+        ip_policy::{IpPolicy, IpPolicyMode},
+    },
+    verify_access_request, verify_access_request_cached,
 };
 use doublezero_passport::instruction::AccessMode;
 use solana_sdk::pubkey::Pubkey;
-use std::net::Ipv4Addr;
-use tracing::info;
+use std::{collections::HashMap, net::Ipv4Addr, sync::LazyLock};
+use tracing::{info, warn};
+
+// Every access mode kind with its built-in rules, used when none are configured
+static DEFAULT_ACCESS_MODES: LazyLock<AccessModes> = LazyLock::new(AccessModes::default);
+
+// Eligibility checked when none is configured
+static DEFAULT_ELIGIBILITY: LazyLock<Eligibility> = LazyLock::new(Eligibility::default);
+
+/// Outcome of verifying an access request's qualifiers
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Qualification {
+    /// Validated (validator_id, ip) pairs to grant access to
+    Qualified(Vec<(Pubkey, Ipv4Addr)>),
+    Denied(DenialReason),
+}
 
 /// Shared validator verification logic used by both WebSocket and polling modes
 pub struct ValidatorVerifier<'a> {
     sol_rpc_client: &'a SolRpcClient,
     previous_leader_epochs: u8,
+    ip_policy: Option<&'a IpPolicy>,
+    dz_rpc_client: Option<&'a DzRpcClient>,
+    message_cache: Option<&'a AccessMessageCache>,
+    access_modes: Option<&'a AccessModes>,
+    eligibility: Option<&'a Eligibility>,
 }
 
 impl<'a> ValidatorVerifier<'a> {
@@ -17,93 +45,165 @@ impl<'a> ValidatorVerifier<'a> {
         Self {
             sol_rpc_client,
             previous_leader_epochs,
+            ip_policy: None,
+            dz_rpc_client: None,
+            message_cache: None,
+            access_modes: None,
+            eligibility: None,
         }
     }
 
-    /// Verify access request qualifiers and return validated (validator_id, ip) pairs
-    pub async fn verify_qualifiers(
-        &self,
-        access_mode: &AccessMode,
-    ) -> Result<Vec<(Pubkey, Ipv4Addr)>> {
+    /// Apply endpoint sanity checks to the validated IPs
+    pub fn with_ip_policy(mut self, ip_policy: &'a IpPolicy) -> Self {
+        self.ip_policy = Some(ip_policy);
+        self
+    }
+
+    /// Check endpoints against the access passes already issued on the DZ ledger
+    pub fn with_access_passes(mut self, dz_rpc_client: &'a DzRpcClient) -> Self {
+        self.dz_rpc_client = Some(dz_rpc_client);
+        self
+    }
+
+    /// Share serialized access request messages with other verifications of a batch
+    pub fn with_message_cache(mut self, message_cache: &'a AccessMessageCache) -> Self {
+        self.message_cache = Some(message_cache);
+        self
+    }
+
+    /// Only accept these access modes, qualifying each by its registered rules
+    pub fn with_access_modes(mut self, access_modes: &'a AccessModes) -> Self {
+        self.access_modes = Some(access_modes);
+        self
+    }
+
+    /// Check validators against this eligibility instead of only the leader schedule
+    pub fn with_eligibility(mut self, eligibility: &'a Eligibility) -> Self {
+        self.eligibility = Some(eligibility);
+        self
+    }
+
+    /// Verify access request qualifiers, returning the validated (validator_id, ip) pairs or why
+    /// the request is denied
+    pub async fn verify_qualifiers(&self, access_mode: &AccessMode) -> Result<Qualification> {
+        let access_modes = self.access_modes.unwrap_or(&DEFAULT_ACCESS_MODES);
+        let Some(rules) = access_modes.rules(access_mode) else {
+            let kind = AccessModeKind::of(access_mode);
+            info!(kind = kind.as_str(), "access mode is not accepted");
+            metrics::counter!(
+                "doublezero_sentinel_access_mode_rejected",
+                "kind" => kind.as_str()
+            )
+            .increment(1);
+            return Ok(Qualification::Denied(DenialReason::UnsupportedAccessMode));
+        };
+
         // Return early if sig verification fails
-        let validator_id = match verify_access_request(access_mode) {
+        let verified = match self.message_cache {
+            Some(cache) => verify_access_request_cached(access_mode, cache),
+            None => verify_access_request(access_mode),
+        };
+        let validator_id = match verified {
             Ok(v) => v,
             Err(e @ Error::SignatureVerify) => {
                 return {
                     info!(error = %e, "signature verification failed");
-                    Ok(vec![])
+                    Ok(Qualification::Denied(DenialReason::InvalidSignature))
                 };
             }
             Err(e) => return Err(e),
         };
         info!(%validator_id, "Validator passed signature validation");
 
-        // Extract attestation and backup IDs
-        let backup_ids = match access_mode {
-            AccessMode::SolanaValidator(_) => None,
-            AccessMode::SolanaValidatorWithBackupIds { backup_ids, .. } => Some(backup_ids),
+        let ips = match rules.qualify(self, validator_id, access_mode).await? {
+            Qualification::Qualified(ips) => ips,
+            denied @ Qualification::Denied(_) => return Ok(denied),
         };
 
-        // Check primary validator is in leader schedule
         if !self
-            .check_validator_in_leader_schedule(&validator_id)
+            .passes_ip_policy(&access_mode.service_key(), &ips)
             .await?
         {
-            info!(
-                %validator_id,
-                "Validator failed leader schedule qualification"
-            );
-            return Ok(vec![]);
+            return Ok(Qualification::Denied(DenialReason::IpPolicy));
         }
 
-        // Get primary validator IP immediately after leader schedule check
-        let validator_ip = match self.get_and_validate_validator_ip(&validator_id).await? {
-            Some(ip) => ip,
-            None => {
-                info!(
-                    %validator_id,
-                    "Validator failed gossip protocol ip qualification"
-                );
-                return Ok(Default::default());
-            }
+        Ok(Qualification::Qualified(ips))
+    }
+
+    /// Check validated endpoints against the IP policy, returning false if the request
+    /// should be rejected
+    async fn passes_ip_policy(
+        &self,
+        service_key: &Pubkey,
+        ips: &[(Pubkey, Ipv4Addr)],
+    ) -> Result<bool> {
+        let Some(policy) = self
+            .ip_policy
+            .filter(|policy| policy.mode != IpPolicyMode::Off)
+        else {
+            return Ok(true);
         };
 
-        // Collect all validated IPs (starting with primary)
-        let mut ips = vec![(validator_id, validator_ip)];
-
-        // If we have backup IDs, verify they are NOT in leader schedule but ARE in gossip
-        if let Some(backup_ids) = backup_ids {
-            for backup_id in backup_ids {
-                // Backup should NOT be in leader schedule
-                if self.check_validator_in_leader_schedule(backup_id).await? {
-                    info!(
-                        %backup_id,
-                        "Backup validator is in leader schedule (should not be)"
-                    );
-                    return Ok(Default::default());
+        let mut existing_passes = HashMap::new();
+        if let Some(dz_rpc_client) = self.dz_rpc_client {
+            for (_, ip) in ips {
+                let pass = rpc_with_retry(
+                    || async { dz_rpc_client.get_access_pass(service_key, ip).await },
+                    "get_access_pass",
+                )
+                .await?;
+                if let Some(pass) = pass {
+                    existing_passes.insert(*ip, pass.accesspass_type);
                 }
+            }
+        }
 
-                // Check backup ID is in gossip and store IP
-                match self.get_and_validate_validator_ip(backup_id).await? {
-                    Some(ip) => {
-                        ips.push((*backup_id, ip));
-                    }
-                    None => {
-                        info!(
-                            %backup_id,
-                            "Backup validator not found in gossip"
-                        );
-                        return Ok(Default::default());
-                    }
-                }
+        let violations = policy.check(ips, &existing_passes);
+        for violation in &violations {
+            warn!(%violation, mode = ?policy.mode, "access request failed ip policy check");
+            metrics::counter!(
+                "doublezero_sentinel_ip_policy_violation",
+                "kind" => violation.kind()
+            )
+            .increment(1);
+        }
+
+        Ok(violations.is_empty() || policy.mode != IpPolicyMode::Reject)
+    }
+
+    /// Check that a validator may be granted access, returning why not if it may not
+    pub async fn check_eligibility(&self, validator_id: &Pubkey) -> Result<Option<DenialReason>> {
+        let eligibility = self.eligibility.unwrap_or(&DEFAULT_ELIGIBILITY);
+
+        if eligibility.checks_vote_accounts() {
+            let activated_stake = rpc_with_retry(
+                || async { self.sol_rpc_client.get_activated_stake(validator_id).await },
+                "get_activated_stake",
+            )
+            .await?;
+            if let Err(reason) = eligibility.check_stake(activated_stake) {
+                info!(%validator_id, ?activated_stake, %reason, "Validator failed stake qualification");
+                return Ok(Some(reason));
             }
         }
 
-        Ok(ips)
+        if eligibility.require_leader_schedule
+            && !self
+                .check_validator_in_leader_schedule(validator_id)
+                .await?
+        {
+            info!(
+                %validator_id,
+                "Validator failed leader schedule qualification"
+            );
+            return Ok(Some(DenialReason::NotInLeaderSchedule));
+        }
+
+        Ok(None)
     }
 
     /// Check that a validator is in the leader schedule
-    async fn check_validator_in_leader_schedule(&self, validator_id: &Pubkey) -> Result<bool> {
+    pub async fn check_validator_in_leader_schedule(&self, validator_id: &Pubkey) -> Result<bool> {
         rpc_with_retry(
             || async {
                 self.sol_rpc_client
@@ -116,7 +216,7 @@ impl<'a> ValidatorVerifier<'a> {
     }
 
     /// Get and validate a validator's IP from gossip
-    async fn get_and_validate_validator_ip(
+    pub async fn get_and_validate_validator_ip(
         &self,
         validator_id: &Pubkey,
     ) -> Result<Option<Ipv4Addr>> {
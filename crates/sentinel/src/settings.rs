@@ -1,4 +1,12 @@
-use clap::Parser;
+use crate::sentinel::{
+    access_modes::{AccessModeKind, AccessModes},
+    dedupe::DedupeStore,
+    eligibility::Eligibility,
+    funding::{self, FundingPolicy},
+    ip_policy::{IpPolicy, IpPolicyMode, Ipv4Cidr},
+    notify::{AccessNotifier, Notifications, WebhookNotifier},
+};
+use clap::{Parser, Subcommand};
 use config::{Config, Environment, File};
 use doublezero_serviceability::addresses::{devnet, mainnet, testnet};
 use serde::{Deserialize, Serialize};
@@ -9,6 +17,7 @@ use std::{
     path::{Path, PathBuf},
     str::FromStr,
     sync::Arc,
+    time::Duration,
 };
 use url::Url;
 
@@ -26,6 +35,19 @@ pub struct AppArgs {
     /// Enable polling mode with specified interval (in seconds), bypass websocket connection.
     #[arg(long)]
     pub poll_interval: Option<u64>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Reconcile outstanding access requests with the DZ ledger and exit
+    Repair {
+        /// Report missing access passes without issuing or granting anything
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -51,6 +73,74 @@ pub struct Settings {
     /// metrics listening endpoint
     #[serde(default = "default_metrics_addr")]
     metrics_addr: String,
+
+    /// Number of retries when provisioning access passes on the DZ ledger
+    #[serde(default = "default_dz_provisioning_retries")]
+    pub dz_provisioning_retries: usize,
+
+    /// Number of access requests verified concurrently per poll in polling mode
+    #[serde(default = "default_verification_concurrency")]
+    pub verification_concurrency: usize,
+
+    /// Most access requests verified together and decided in a single transaction in websocket
+    /// mode; capped at what fits in a transaction
+    #[serde(default = "default_max_batch_size")]
+    pub max_batch_size: usize,
+
+    /// Endpoint sanity checks for access requests: "off", "flag" or "reject"
+    #[serde(default)]
+    ip_policy: IpPolicyMode,
+
+    /// CIDR prefixes validator endpoints must fall in; empty allows any public address
+    #[serde(default)]
+    ip_allowlist: Vec<String>,
+
+    /// Access modes requests may use; requests of any other mode are denied
+    #[serde(default = "default_access_modes")]
+    access_modes: Vec<AccessModeKind>,
+
+    /// Require requesting validators to be in the current vote-account set
+    #[serde(default)]
+    require_vote_account: bool,
+
+    /// Minimum activated stake (in SOL) of requesting validators; nonzero also requires a vote
+    /// account
+    #[serde(default)]
+    min_activated_stake_sol: f64,
+
+    /// Require requesting validators in one of the previous leader schedules
+    #[serde(default = "default_require_leader_schedule")]
+    require_leader_schedule: bool,
+
+    /// Write the reason next to each deny instruction as an SPL memo, so validators can see why
+    /// their request was denied
+    #[serde(default)]
+    pub deny_reason_memos: bool,
+
+    /// Signer balance (in SOL) below which the sentinel warns that it needs funding
+    #[serde(default = "default_low_balance_threshold_sol")]
+    low_balance_threshold_sol: f64,
+
+    /// Request airdrops when the signer balance runs low; only honored on devnet and localnet
+    #[serde(default)]
+    pub auto_airdrop: bool,
+
+    /// Amount (in SOL) requested per airdrop
+    #[serde(default = "default_airdrop_amount_sol")]
+    airdrop_amount_sol: f64,
+
+    /// Endpoint access grant and deny events are posted to as JSON; unset disables the webhook
+    #[serde(default)]
+    notify_webhook_url: Option<String>,
+
+    /// File recording decided access requests so they are not re-verified after a restart;
+    /// unset remembers them in memory for the lifetime of the process only
+    #[serde(default)]
+    dedupe_store_path: Option<PathBuf>,
+
+    /// How long (in seconds) decided access requests are remembered
+    #[serde(default = "default_dedupe_ttl_secs")]
+    dedupe_ttl_secs: u64,
 }
 
 impl Settings {
@@ -115,6 +205,65 @@ impl Settings {
             .expect("invalid metrics network address and port")
     }
 
+    pub fn ip_policy(&self) -> Result<IpPolicy, String> {
+        let allowlist = self
+            .ip_allowlist
+            .iter()
+            .map(|net| net.parse::<Ipv4Cidr>())
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(IpPolicy::new(self.ip_policy, allowlist))
+    }
+
+    pub fn access_modes(&self) -> Result<AccessModes, String> {
+        if self.access_modes.is_empty() {
+            return Err("access_modes cannot be empty".to_string());
+        }
+        Ok(AccessModes::new(&self.access_modes))
+    }
+
+    pub fn eligibility(&self) -> Result<Eligibility, String> {
+        if !self.min_activated_stake_sol.is_finite() || self.min_activated_stake_sol < 0.0 {
+            return Err(format!(
+                "invalid min_activated_stake_sol {}",
+                self.min_activated_stake_sol
+            ));
+        }
+        Ok(Eligibility {
+            require_vote_account: self.require_vote_account,
+            min_activated_stake: funding::sol_to_lamports(self.min_activated_stake_sol),
+            require_leader_schedule: self.require_leader_schedule,
+        })
+    }
+
+    /// Balance monitoring policy; airdrops are only enabled against dev clusters
+    pub fn funding_policy(&self) -> FundingPolicy {
+        let airdrop_allowed = self.auto_airdrop && funding::is_dev_cluster(&self.sol_rpc());
+        FundingPolicy {
+            low_balance_threshold: funding::sol_to_lamports(self.low_balance_threshold_sol),
+            airdrop_amount: airdrop_allowed
+                .then(|| funding::sol_to_lamports(self.airdrop_amount_sol)),
+        }
+    }
+
+    /// Transports access decisions are published over
+    pub fn notifications(&self) -> Notifications {
+        let mut notifiers: Vec<Arc<dyn AccessNotifier>> = Vec::new();
+        if let Some(ref url) = self.notify_webhook_url {
+            let url = Url::parse(url).expect("invalid notify_webhook_url");
+            notifiers.push(Arc::new(WebhookNotifier::new(url)));
+        }
+        Notifications::new(notifiers)
+    }
+
+    /// Record of decided access requests, loaded from `dedupe_store_path` when set
+    pub fn dedupe_store(&self) -> crate::Result<DedupeStore> {
+        let ttl = Duration::from_secs(self.dedupe_ttl_secs);
+        match self.dedupe_store_path {
+            Some(ref path) => DedupeStore::open(path, ttl),
+            None => Ok(DedupeStore::in_memory(ttl)),
+        }
+    }
+
     pub fn serviceability_program_id(
         &self,
     ) -> Result<Pubkey, solana_sdk::pubkey::ParsePubkeyError> {
@@ -136,3 +285,35 @@ fn default_log() -> String {
 fn default_metrics_addr() -> String {
     "127.0.0.1:2112".to_string()
 }
+
+fn default_dz_provisioning_retries() -> usize {
+    crate::error::DEFAULT_RPC_RETRIES
+}
+
+fn default_verification_concurrency() -> usize {
+    8
+}
+
+fn default_max_batch_size() -> usize {
+    8
+}
+
+fn default_access_modes() -> Vec<AccessModeKind> {
+    AccessModeKind::ALL.to_vec()
+}
+
+fn default_require_leader_schedule() -> bool {
+    true
+}
+
+fn default_low_balance_threshold_sol() -> f64 {
+    1.0
+}
+
+fn default_airdrop_amount_sol() -> f64 {
+    1.0
+}
+
+fn default_dedupe_ttl_secs() -> u64 {
+    7 * 24 * 60 * 60
+}
@@ -0,0 +1,127 @@
+use std::{collections::BTreeMap, ffi::OsString, fs};
+
+use anyhow::Result;
+use clap::{Args, Command, Subcommand};
+
+use crate::{defaults, profile};
+
+#[derive(Debug, Args)]
+pub struct ConfigCommand {
+    #[command(subcommand)]
+    pub command: ConfigSubcommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigSubcommand {
+    /// Show the per-subcommand defaults file.
+    Show {
+        /// Show the options a subcommand would be run with, merging the defaults file
+        /// with the selected profile.
+        #[arg(long)]
+        effective: bool,
+
+        /// Subcommand to resolve the effective options for, e.g. `revenue-distribution fetch`.
+        #[arg(requires = "effective")]
+        subcommand: Vec<String>,
+    },
+}
+
+impl ConfigSubcommand {
+    pub async fn try_into_execute(self) -> Result<()> {
+        match self {
+            Self::Show {
+                effective: false, ..
+            } => show_defaults_file(),
+            Self::Show {
+                effective: true,
+                subcommand,
+            } => show_effective(&subcommand),
+        }
+    }
+}
+
+fn show_defaults_file() -> Result<()> {
+    let Some(path) = defaults::defaults_path() else {
+        println!("Cannot locate config directory for defaults");
+        return Ok(());
+    };
+    if !path.exists() {
+        println!("No defaults file at {}", path.display());
+        return Ok(());
+    }
+
+    println!("# {}", path.display());
+    print!("{}", fs::read_to_string(&path)?);
+
+    Ok(())
+}
+
+fn show_effective(subcommand_path: &[String]) -> Result<()> {
+    let root = <super::DoubleZeroSolanaCommand as Subcommand>::augment_subcommands(Command::new(
+        "doublezero-solana",
+    ));
+    let args = std::iter::once(OsString::from("doublezero-solana"))
+        .chain(subcommand_path.iter().map(OsString::from))
+        .collect::<Vec<_>>();
+    let (subcommand, path) = profile::resolve_subcommand(&root, &args);
+
+    let mut options = BTreeMap::new();
+
+    if let Some(table) = defaults::load_defaults()? {
+        for (long, default) in defaults::layered_defaults(&table, &path) {
+            options.insert(
+                long,
+                (
+                    defaults::option_values(&default.value).join(", "),
+                    default.section_name(),
+                ),
+            );
+        }
+    }
+
+    let cli_args = std::env::args_os().collect::<Vec<_>>();
+    if let Some(name) = profile::find_profile_name(&cli_args) {
+        let rpc_profile = profile::load_profile(&name)?;
+        for (names, value) in rpc_profile.option_values() {
+            let accepted = subcommand
+                .get_arguments()
+                .filter_map(|arg| arg.get_long())
+                .find(|long| names.contains(long));
+            if let Some(long) = accepted {
+                options.insert(long.to_string(), (value, format!("profile {name}")));
+            }
+        }
+    }
+
+    // Only options the subcommand accepts are applied
+    let accepted = subcommand
+        .get_arguments()
+        .filter_map(|arg| arg.get_long())
+        .collect::<Vec<_>>();
+    let options = options
+        .into_iter()
+        .filter(|(long, _)| path.is_empty() || accepted.contains(&long.as_str()))
+        .collect::<Vec<_>>();
+
+    let command_name = if path.is_empty() {
+        "all subcommands".to_string()
+    } else {
+        path.join(" ")
+    };
+    if options.is_empty() {
+        println!("No defaults apply to {command_name}");
+        return Ok(());
+    }
+
+    println!("Effective defaults for {command_name}:");
+    let width = options
+        .iter()
+        .map(|(long, _)| long.len())
+        .max()
+        .unwrap_or(0);
+    for (long, (value, source)) in options {
+        println!("  --{long:<width$}  {value}  ({source})");
+    }
+
+    Ok(())
+}
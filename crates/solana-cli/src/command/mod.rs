@@ -1,5 +1,7 @@
 mod ata;
+mod config;
 mod passport;
+mod prepaid;
 mod revenue_distribution;
 
 //
@@ -12,9 +14,15 @@ pub enum DoubleZeroSolanaCommand {
     /// Associated Token Account commands.
     Ata(ata::AtaCommand),
 
+    /// Per-subcommand defaults commands.
+    Config(config::ConfigCommand),
+
     /// Passport program commands.
     Passport(passport::PassportCommand),
 
+    /// Prepaid access pass commands.
+    Prepaid(prepaid::PrepaidCommand),
+
     /// Revenue distribution program commands.
     RevenueDistribution(revenue_distribution::RevenueDistributionCommand),
 }
@@ -23,7 +31,9 @@ impl DoubleZeroSolanaCommand {
     pub async fn try_into_execute(self) -> Result<()> {
         match self {
             Self::Ata(ata) => ata.command.try_into_execute().await,
+            Self::Config(config) => config.command.try_into_execute().await,
             Self::Passport(passport) => passport.command.try_into_execute().await,
+            Self::Prepaid(prepaid) => prepaid.command.try_into_execute().await,
             Self::RevenueDistribution(revenue_distribution) => {
                 revenue_distribution.command.try_into_execute().await
             }
@@ -1,6 +1,11 @@
-use anyhow::Result;
+use std::collections::HashSet;
+
+use anyhow::{Result, ensure};
 use clap::{Args, Subcommand};
-use doublezero_passport::state::{AccessRequest, ProgramConfig};
+use doublezero_passport::{
+    instruction::{AccessMode, SolanaValidatorAttestation},
+    state::{AccessRequest, ProgramConfig},
+};
 use doublezero_solana_client_tools::{rpc::SolanaConnection, zero_copy::ZeroCopyAccountOwned};
 use solana_sdk::pubkey::Pubkey;
 
@@ -40,6 +45,44 @@ impl PassportSubcommand {
 
 //
 
+/// Most backup validator IDs one access request can carry. Each adds 32 bytes to the request
+/// access instruction, and this many still fit in a single transaction with the compute budget
+/// instructions.
+const MAX_BACKUP_VALIDATOR_IDS: usize = 20;
+
+/// Access mode for the attested validator and its backup validators, if any.
+fn validator_access_mode(
+    attestation: SolanaValidatorAttestation,
+    backup_ids: &[Pubkey],
+) -> Result<AccessMode> {
+    if backup_ids.is_empty() {
+        return Ok(AccessMode::SolanaValidator(attestation));
+    }
+
+    ensure!(
+        backup_ids.len() <= MAX_BACKUP_VALIDATOR_IDS,
+        "At most {MAX_BACKUP_VALIDATOR_IDS} backup validator IDs are allowed, got {}",
+        backup_ids.len()
+    );
+
+    let mut seen = HashSet::new();
+    for backup_id in backup_ids {
+        ensure!(
+            *backup_id != attestation.validator_id,
+            "Backup validator ID ({backup_id}) is the primary validator ID"
+        );
+        ensure!(
+            seen.insert(backup_id),
+            "Backup validator ID ({backup_id}) is given more than once"
+        );
+    }
+
+    Ok(AccessMode::SolanaValidatorWithBackupIds {
+        attestation,
+        backup_ids: backup_ids.to_vec(),
+    })
+}
+
 async fn fetch_program_config(connection: &SolanaConnection) -> Result<(Pubkey, ProgramConfig)> {
     let (program_config_key, _) = ProgramConfig::find_address();
 
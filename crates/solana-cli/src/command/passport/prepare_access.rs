@@ -5,17 +5,14 @@ use clap::Args;
 use doublezero_ledger_sentinel::{
     client::solana::SolRpcClient, constants::ENV_PREVIOUS_LEADER_EPOCHS,
 };
-use doublezero_passport::{
-    instruction::{AccessMode, SolanaValidatorAttestation},
-    state::AccessRequest,
-};
+use doublezero_passport::{instruction::SolanaValidatorAttestation, state::AccessRequest};
 use doublezero_solana_client_tools::rpc::{SolanaConnection, SolanaConnectionOptions};
 use solana_sdk::{pubkey::Pubkey, signature::Keypair};
 
 use crate::helpers::{find_node_by_node_id, identify_cluster};
 
 /*
-   doublezero-solana passport request-access --doublezero-address SSSS --primary-validator-id AAA --backup-validator-ids BBB,CCC --signature XXXXX
+   doublezero-solana passport prepare-validator-access --doublezero-address SSSS --primary-validator-id AAA --backup-id BBB --backup-id CCC
 */
 
 #[derive(Debug, Args)]
@@ -26,8 +23,13 @@ pub struct PrepareValidatorAccessCommand {
     /// The validator's node ID (identity pubkey)
     #[arg(long, value_name = "PUBKEY")]
     primary_validator_id: Pubkey,
-    /// Optional backup validator IDs (identity pubkeys)
-    #[arg(long, value_name = "PUBKEY,PUBKEY,PUBKEY", value_delimiter = ',')]
+    /// Backup validator ID (identity pubkey). Repeat for every backup validator
+    #[arg(
+        long = "backup-id",
+        visible_alias = "backup-validator-ids",
+        value_name = "PUBKEY",
+        value_delimiter = ','
+    )]
     backup_validator_ids: Vec<Pubkey>,
 
     #[arg(long, default_value_t = false)]
@@ -47,6 +49,16 @@ impl PrepareValidatorAccessCommand {
             force,
         } = self;
 
+        // Create attestation. The signature is not part of the signed message.
+        let access_mode = super::validator_access_mode(
+            SolanaValidatorAttestation {
+                validator_id: primary_validator_id,
+                service_key: doublezero_address,
+                ed25519_signature: [0u8; 64],
+            },
+            &backup_validator_ids,
+        )?;
+
         // Establish a connection to the Solana cluster
         let connection = SolanaConnection::try_from(solana_connection_options)?;
         let sol_client = SolRpcClient::new(
@@ -147,27 +159,21 @@ impl PrepareValidatorAccessCommand {
             "\n\nTo request access, sign the following message with your validator's identity key:\n"
         );
 
-        // Create attestation
-        let attestation = SolanaValidatorAttestation {
-            validator_id: primary_validator_id,
-            service_key: doublezero_address,
-            ed25519_signature: [0u8; 64],
-        };
-
-        // Verify the signature.
-        let raw_message = if backup_validator_ids.is_empty() {
-            AccessRequest::access_request_message(&AccessMode::SolanaValidator(attestation))
-        } else {
-            AccessRequest::access_request_message(&AccessMode::SolanaValidatorWithBackupIds {
-                attestation,
-                backup_ids: backup_validator_ids.clone(),
-            })
-        };
+        let raw_message = AccessRequest::access_request_message(&access_mode);
 
         println!(
             "solana sign-offchain-message \\\n   {raw_message} \\\n   -k <identity-keypair-file.json>\n"
         );
 
+        let backup_args: String = backup_validator_ids
+            .iter()
+            .map(|backup_id| format!(" \\\n   --backup-id {backup_id}"))
+            .collect();
+        println!("Then submit the request with the signature:\n");
+        println!(
+            "doublezero-solana passport request-validator-access \\\n   --doublezero-address {doublezero_address} \\\n   --primary-validator-id {primary_validator_id}{backup_args} \\\n   --signature <SIGNATURE>\n"
+        );
+
         Ok(())
     }
 }
@@ -1,6 +1,6 @@
-use std::str::FromStr;
+use std::{path::PathBuf, str::FromStr};
 
-use anyhow::{Result, bail};
+use anyhow::{Context, Result, bail};
 use clap::Args;
 use doublezero_passport::{
     ID,
@@ -13,12 +13,18 @@ use doublezero_passport::{
 use doublezero_program_tools::instruction::try_build_instruction;
 use doublezero_solana_client_tools::payer::{SolanaPayerOptions, Wallet};
 use solana_sdk::{
-    compute_budget::ComputeBudgetInstruction, offchain_message::OffchainMessage, pubkey::Pubkey,
-    signature::Signature,
+    compute_budget::ComputeBudgetInstruction,
+    offchain_message::OffchainMessage,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, read_keypair_file},
+    signer::Signer,
 };
 
+use crate::helpers::find_node_by_node_id;
+
 /*
-   doublezero-solana passport request-access --doublezero-address SSSS --primary-validator-id AAA --backup-validator-ids BBB,CCC --signature XXXXX
+   doublezero-solana passport request-access --doublezero-address SSSS --primary-validator-id AAA --backup-id BBB --backup-id CCC --signature XXXXX
+   doublezero-solana passport request-access --doublezero-address SSSS --node-keypair identity.json --backup-id BBB --backup-id CCC
 */
 
 #[derive(Debug, Args)]
@@ -27,14 +33,37 @@ pub struct RequestValidatorAccessCommand {
     #[arg(long)]
     doublezero_address: Pubkey,
     /// The validator's node ID (identity pubkey)
-    #[arg(long, value_name = "PUBKEY")]
-    primary_validator_id: Pubkey,
-    /// Optional backup validator IDs (identity pubkeys)
-    #[arg(long, value_name = "PUBKEY,PUBKEY,PUBKEY", value_delimiter = ',')]
+    #[arg(
+        long,
+        value_name = "PUBKEY",
+        required_unless_present = "node_keypair",
+        conflicts_with = "node_keypair"
+    )]
+    primary_validator_id: Option<Pubkey>,
+    /// Path to the validator's identity keypair. The node ID is derived from this keypair and,
+    /// if no signature is provided, the access request message is signed locally
+    #[arg(long, value_name = "PATH")]
+    node_keypair: Option<PathBuf>,
+    /// Backup validator ID (identity pubkey). Repeat for every backup validator
+    #[arg(
+        long = "backup-id",
+        visible_alias = "backup-validator-ids",
+        value_name = "PUBKEY",
+        value_delimiter = ','
+    )]
     backup_validator_ids: Vec<Pubkey>,
     /// Base58-encoded ed25519 signature of the access request message (service_key=AAA,backup_ids=BBBB,CCCC,DDDD)
-    #[arg(long, short = 's', value_name = "BASE58_STRING")]
-    signature: String,
+    #[arg(
+        long,
+        short = 's',
+        value_name = "BASE58_STRING",
+        required_unless_present = "node_keypair"
+    )]
+    signature: Option<String>,
+
+    /// Skip cross-checking the node ID against gossip and vote accounts
+    #[arg(long, default_value_t = false)]
+    skip_identity_check: bool,
 
     /// Offchain message version. ONLY 0 IS SUPPORTED.
     #[arg(long, value_name = "U8", default_value = "0")]
@@ -55,7 +84,20 @@ impl RequestValidatorAccessCommand {
             bail!("Access request already exists: {address}");
         }
 
-        let tx_sig = self.request_access(&wallet).await?;
+        let node_keypair = self.try_load_node_keypair()?;
+        let primary_validator_id = match (&node_keypair, self.primary_validator_id) {
+            (Some(keypair), _) => keypair.pubkey(),
+            (None, Some(primary_validator_id)) => primary_validator_id,
+            (None, None) => bail!("Either --primary-validator-id or --node-keypair is required"),
+        };
+
+        if !self.skip_identity_check {
+            ensure_known_validator_identity(&wallet, &primary_validator_id).await?;
+        }
+
+        let tx_sig = self
+            .request_access(&wallet, &primary_validator_id, node_keypair.as_ref())
+            .await?;
 
         if let Some(tx_sig) = tx_sig {
             println!("Request Solana validator access: {tx_sig}");
@@ -66,26 +108,37 @@ impl RequestValidatorAccessCommand {
         Ok(())
     }
 
-    async fn request_access(&self, wallet: &Wallet) -> Result<Option<Signature>> {
+    fn try_load_node_keypair(&self) -> Result<Option<Keypair>> {
+        self.node_keypair
+            .as_ref()
+            .map(|path| {
+                read_keypair_file(path).map_err(|e| {
+                    anyhow::anyhow!("Failed to read node keypair {}: {e}", path.display())
+                })
+            })
+            .transpose()
+    }
+
+    async fn request_access(
+        &self,
+        wallet: &Wallet,
+        primary_validator_id: &Pubkey,
+        node_keypair: Option<&Keypair>,
+    ) -> Result<Option<Signature>> {
         let wallet_key = wallet.pubkey();
-        let ed25519_signature = Signature::from_str(&self.signature)?;
 
-        // Create attestation
-        let attestation = SolanaValidatorAttestation {
-            validator_id: self.primary_validator_id,
-            service_key: self.doublezero_address,
-            ed25519_signature: ed25519_signature.into(),
-        };
+        // Create attestation. The signature is not part of the signed message, so a placeholder
+        // is used until the real signature is known.
+        let mut access_mode = super::validator_access_mode(
+            SolanaValidatorAttestation {
+                validator_id: *primary_validator_id,
+                service_key: self.doublezero_address,
+                ed25519_signature: [0u8; 64],
+            },
+            &self.backup_validator_ids,
+        )?;
 
-        // Verify the signature.
-        let raw_message = if self.backup_validator_ids.is_empty() {
-            AccessRequest::access_request_message(&AccessMode::SolanaValidator(attestation))
-        } else {
-            AccessRequest::access_request_message(&AccessMode::SolanaValidatorWithBackupIds {
-                attestation,
-                backup_ids: self.backup_validator_ids.clone(),
-            })
-        };
+        let raw_message = AccessRequest::access_request_message(&access_mode);
 
         if self.solana_payer_options.signer_options.verbose {
             println!("Raw message: {raw_message}");
@@ -94,16 +147,31 @@ impl RequestValidatorAccessCommand {
         let message = OffchainMessage::new(self.message_version, raw_message.as_bytes())?;
         let serialized_message = message.serialize()?;
 
-        if !ed25519_signature.verify(self.primary_validator_id.as_array(), &serialized_message) {
+        let ed25519_signature = match (&self.signature, node_keypair) {
+            (Some(signature), _) => Signature::from_str(signature)?,
+            (None, Some(keypair)) => message
+                .sign(keypair)
+                .context("Failed to sign access request message with node keypair")?,
+            (None, None) => bail!("Either --signature or --node-keypair is required"),
+        };
+        match &mut access_mode {
+            AccessMode::SolanaValidator(attestation)
+            | AccessMode::SolanaValidatorWithBackupIds { attestation, .. } => {
+                attestation.ed25519_signature = ed25519_signature.into();
+            }
+        }
+
+        // Verify the signature.
+        if !ed25519_signature.verify(primary_validator_id.as_array(), &serialized_message) {
             bail!("Signature verification failed");
         } else if self.solana_payer_options.signer_options.verbose {
-            println!("Signature recovers node ID: {}", self.primary_validator_id);
+            println!("Signature recovers node ID: {primary_validator_id}");
         }
 
         let request_access_ix = try_build_instruction(
             &ID,
             RequestAccessAccounts::new(&wallet_key, &self.doublezero_address),
-            &PassportInstructionData::RequestAccess(AccessMode::SolanaValidator(attestation)),
+            &PassportInstructionData::RequestAccess(access_mode),
         )?;
 
         let (_, bump) = AccessRequest::find_address(&self.doublezero_address);
@@ -125,3 +193,29 @@ impl RequestValidatorAccessCommand {
         wallet.send_or_simulate_transaction(&transaction).await
     }
 }
+
+/// Make sure the node ID belongs to a validator known to the cluster before submitting, to catch
+/// pasted node IDs that do not match the intended validator.
+async fn ensure_known_validator_identity(wallet: &Wallet, node_id: &Pubkey) -> Result<()> {
+    let nodes = wallet.connection.get_cluster_nodes().await?;
+    if find_node_by_node_id(&nodes, node_id).is_none() {
+        bail!(
+            "Validator ID ({node_id}) is not visible in gossip. Pass --skip-identity-check to submit anyway"
+        );
+    }
+
+    let vote_accounts = wallet.connection.get_vote_accounts().await?;
+    let node_id_str = node_id.to_string();
+    let has_vote_account = vote_accounts
+        .current
+        .iter()
+        .chain(vote_accounts.delinquent.iter())
+        .any(|vote_account| vote_account.node_pubkey == node_id_str);
+    if !has_vote_account {
+        bail!(
+            "Validator ID ({node_id}) has no vote account. Pass --skip-identity-check to submit anyway"
+        );
+    }
+
+    Ok(())
+}
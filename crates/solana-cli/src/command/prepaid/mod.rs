@@ -0,0 +1,185 @@
+mod remind;
+mod status;
+
+//
+
+use std::{net::Ipv4Addr, time::Duration};
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use doublezero_serviceability::state::{
+    accesspass::AccessPassType, accountdata::AccountData, accounttype::AccountType,
+};
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_client::{
+    nonblocking::rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, MemcmpEncodedBytes, RpcFilterType},
+};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+
+/// Slot time assumed when the ledger reports no performance samples.
+const DEFAULT_SLOT_DURATION: Duration = Duration::from_millis(400);
+
+/// Number of recent performance samples averaged for the slot time.
+const PERFORMANCE_SAMPLE_COUNT: usize = 60;
+
+#[derive(Debug, Args)]
+pub struct PrepaidCommand {
+    #[command(subcommand)]
+    pub command: PrepaidSubcommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum PrepaidSubcommand {
+    /// Estimate how long a user's prepaid access passes remain valid.
+    Status(status::PrepaidStatusCommand),
+
+    /// Send webhook reminders for prepaid access passes about to expire.
+    Remind(remind::PrepaidRemindCommand),
+}
+
+impl PrepaidSubcommand {
+    pub async fn try_into_execute(self) -> Result<()> {
+        match self {
+            Self::Status(command) => command.try_into_execute().await,
+            Self::Remind(command) => command.try_into_execute().await,
+        }
+    }
+}
+
+//
+
+/// Remaining validity of a prepaid access pass.
+#[derive(Debug, Clone)]
+struct PrepaidEstimate {
+    access_pass_key: Pubkey,
+    user_payer: Pubkey,
+    client_ip: Ipv4Addr,
+    last_access_epoch: u64,
+    /// Time until the end of the last paid epoch, or `None` if the pass does
+    /// not expire.
+    remaining: Option<Duration>,
+}
+
+impl PrepaidEstimate {
+    fn remaining_days(&self) -> Option<f64> {
+        self.remaining
+            .map(|remaining| remaining.as_secs_f64() / 86_400.0)
+    }
+
+    fn display_remaining(&self) -> String {
+        match self.remaining_days() {
+            None => "unlimited".to_string(),
+            Some(days) if days <= 0.0 => "expired".to_string(),
+            Some(days) => format!("{days:.1} days"),
+        }
+    }
+}
+
+/// Current position on the DoubleZero Ledger used to project expiry.
+struct LedgerClock {
+    epoch: u64,
+    slot_index: u64,
+    slots_in_epoch: u64,
+    slot_duration: Duration,
+}
+
+impl LedgerClock {
+    async fn fetch(rpc_client: &RpcClient) -> Result<Self> {
+        let epoch_info = rpc_client.get_epoch_info().await?;
+        let samples = rpc_client
+            .get_recent_performance_samples(Some(PERFORMANCE_SAMPLE_COUNT))
+            .await?;
+
+        let (num_slots, period_secs) = samples.iter().fold((0, 0), |(slots, secs), sample| {
+            (
+                slots + sample.num_slots,
+                secs + u64::from(sample.sample_period_secs),
+            )
+        });
+        let slot_duration = if num_slots == 0 {
+            DEFAULT_SLOT_DURATION
+        } else {
+            Duration::from_secs_f64(period_secs as f64 / num_slots as f64)
+        };
+
+        Ok(Self {
+            epoch: epoch_info.epoch,
+            slot_index: epoch_info.slot_index,
+            slots_in_epoch: epoch_info.slots_in_epoch,
+            slot_duration,
+        })
+    }
+
+    /// Time until the end of `last_access_epoch`, or `None` if it never ends.
+    ///
+    /// Passes are valid through their last access epoch, so the rest of the
+    /// current epoch counts towards the remaining time.
+    fn remaining_until(&self, last_access_epoch: u64) -> Option<Duration> {
+        if last_access_epoch == u64::MAX {
+            return None;
+        }
+        if last_access_epoch < self.epoch {
+            return Some(Duration::ZERO);
+        }
+
+        let remaining_slots = (last_access_epoch - self.epoch)
+            .saturating_mul(self.slots_in_epoch)
+            .saturating_add(self.slots_in_epoch.saturating_sub(self.slot_index));
+        Some(
+            Duration::try_from_secs_f64(self.slot_duration.as_secs_f64() * remaining_slots as f64)
+                .unwrap_or(Duration::MAX),
+        )
+    }
+}
+
+/// Fetch every prepaid access pass, optionally only those paid for by
+/// `user_payer`, with their estimated remaining validity.
+async fn fetch_prepaid_estimates(
+    rpc_client: &RpcClient,
+    serviceability_program_id: &Pubkey,
+    user_payer: Option<&Pubkey>,
+) -> Result<Vec<PrepaidEstimate>> {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new(
+            0,
+            MemcmpEncodedBytes::Bytes(vec![AccountType::AccessPass as u8]),
+        ))]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(CommitmentConfig::confirmed()),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let accounts = rpc_client
+        .get_program_accounts_with_config(serviceability_program_id, config)
+        .await?;
+    let clock = LedgerClock::fetch(rpc_client).await?;
+
+    let mut estimates = Vec::new();
+    for (access_pass_key, account) in accounts {
+        let access_pass = AccountData::try_from(&account.data[..])?.get_accesspass()?;
+        if !matches!(access_pass.accesspass_type, AccessPassType::Prepaid) {
+            continue;
+        }
+        if user_payer.is_some_and(|user_payer| *user_payer != access_pass.user_payer) {
+            continue;
+        }
+
+        estimates.push(PrepaidEstimate {
+            access_pass_key,
+            user_payer: access_pass.user_payer,
+            client_ip: access_pass.client_ip,
+            last_access_epoch: access_pass.last_access_epoch,
+            remaining: clock.remaining_until(access_pass.last_access_epoch),
+        });
+    }
+
+    // Soonest to expire first; passes without expiry last.
+    estimates.sort_by_key(|estimate| estimate.remaining.unwrap_or(Duration::MAX));
+
+    Ok(estimates)
+}
@@ -0,0 +1,137 @@
+use anyhow::{Result, bail};
+use clap::Args;
+use doublezero_scheduled_command::{Schedulable, ScheduleOption};
+use doublezero_solana_client_tools::rpc::DoubleZeroLedgerConnectionOptions;
+use serde_json::json;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+
+use super::PrepaidEstimate;
+
+#[derive(Debug, Args, Clone)]
+pub struct PrepaidRemindCommand {
+    /// Remind about prepaid access passes expiring within this many days.
+    #[arg(long, default_value_t = 7.0)]
+    days: f64,
+
+    /// Webhook receiving a JSON body for each expiring access pass.
+    #[arg(long, value_name = "URL")]
+    webhook_url: String,
+
+    /// Only remind about access passes paid for by this user key.
+    #[arg(long, value_name = "PUBKEY")]
+    user_key: Option<Pubkey>,
+
+    /// Serviceability program ID on the DoubleZero Ledger.
+    #[arg(long, value_name = "PUBKEY")]
+    serviceability_program_id: Pubkey,
+
+    #[command(flatten)]
+    schedule: ScheduleOption,
+
+    #[command(flatten)]
+    dz_ledger_connection_options: DoubleZeroLedgerConnectionOptions,
+}
+
+impl PrepaidRemindCommand {
+    pub async fn try_into_execute(self) -> Result<()> {
+        if !(self.days.is_finite() && self.days > 0.0) {
+            bail!("--days must be positive, got {}", self.days);
+        }
+
+        self.execute().await
+    }
+}
+
+#[async_trait::async_trait]
+impl Schedulable for PrepaidRemindCommand {
+    fn schedule(&self) -> &ScheduleOption {
+        &self.schedule
+    }
+
+    async fn execute_once(&self) -> Result<()> {
+        let rpc_client = RpcClient::new_with_commitment(
+            self.dz_ledger_connection_options.dz_ledger_url.clone(),
+            CommitmentConfig::confirmed(),
+        );
+
+        let estimates = super::fetch_prepaid_estimates(
+            &rpc_client,
+            &self.serviceability_program_id,
+            self.user_key.as_ref(),
+        )
+        .await?;
+
+        let expiring = estimates
+            .into_iter()
+            .filter(|estimate| {
+                estimate
+                    .remaining_days()
+                    .is_some_and(|days| days <= self.days)
+            })
+            .collect::<Vec<_>>();
+
+        if expiring.is_empty() {
+            println!(
+                "No prepaid access passes expiring within {} days",
+                self.days
+            );
+            return Ok(());
+        }
+
+        let client = reqwest::Client::new();
+        let mut failures = 0;
+        for estimate in &expiring {
+            let result = client
+                .post(&self.webhook_url)
+                .json(&reminder_payload(estimate))
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+
+            match result {
+                Ok(_) => println!(
+                    "Reminded {} about access pass {} ({})",
+                    estimate.user_payer,
+                    estimate.access_pass_key,
+                    estimate.display_remaining()
+                ),
+                Err(err) => {
+                    failures += 1;
+                    eprintln!(
+                        "Failed to send reminder for access pass {}: {err}",
+                        estimate.access_pass_key
+                    );
+                }
+            }
+        }
+
+        if failures > 0 {
+            bail!(
+                "Failed to send {failures} of {} prepaid reminders",
+                expiring.len()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+fn reminder_payload(estimate: &PrepaidEstimate) -> serde_json::Value {
+    json!({
+        "event": "prepaid_access_expiring",
+        "message": format!(
+            "Prepaid access pass {} for {} expires in {}",
+            estimate.access_pass_key,
+            estimate.client_ip,
+            estimate.display_remaining()
+        ),
+        "details": {
+            "access_pass": estimate.access_pass_key.to_string(),
+            "user_payer": estimate.user_payer.to_string(),
+            "client_ip": estimate.client_ip.to_string(),
+            "last_access_epoch": estimate.last_access_epoch,
+            "remaining_days": estimate.remaining_days(),
+        },
+    })
+}
@@ -0,0 +1,71 @@
+use anyhow::Result;
+use clap::Args;
+use doublezero_solana_client_tools::rpc::DoubleZeroLedgerConnectionOptions;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+
+#[derive(Debug, Args)]
+pub struct PrepaidStatusCommand {
+    /// User key paying for the prepaid access passes.
+    #[arg(long, value_name = "PUBKEY")]
+    user_key: Pubkey,
+
+    /// Serviceability program ID on the DoubleZero Ledger.
+    #[arg(long, value_name = "PUBKEY")]
+    serviceability_program_id: Pubkey,
+
+    #[command(flatten)]
+    dz_ledger_connection_options: DoubleZeroLedgerConnectionOptions,
+}
+
+impl PrepaidStatusCommand {
+    pub async fn try_into_execute(self) -> Result<()> {
+        let PrepaidStatusCommand {
+            user_key,
+            serviceability_program_id,
+            dz_ledger_connection_options,
+        } = self;
+
+        let rpc_client = RpcClient::new_with_commitment(
+            dz_ledger_connection_options.dz_ledger_url,
+            CommitmentConfig::confirmed(),
+        );
+
+        let estimates =
+            super::fetch_prepaid_estimates(&rpc_client, &serviceability_program_id, Some(&user_key))
+                .await?;
+
+        println!("Prepaid access passes for {user_key}");
+        println!();
+
+        if estimates.is_empty() {
+            println!("... no prepaid access passes found");
+            println!();
+            return Ok(());
+        }
+
+        println!(
+            "Access pass                                  | Client IP       | Last epoch | Remaining"
+        );
+        println!(
+            "---------------------------------------------+-----------------+------------+----------------"
+        );
+        for estimate in estimates {
+            let last_access_epoch = if estimate.last_access_epoch == u64::MAX {
+                "-".to_string()
+            } else {
+                estimate.last_access_epoch.to_string()
+            };
+            println!(
+                "{:<44} | {:<15} | {:<10} | {}",
+                estimate.access_pass_key,
+                estimate.client_ip,
+                last_access_epoch,
+                estimate.display_remaining()
+            );
+        }
+        println!();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,249 @@
+use std::{collections::HashMap, str::FromStr};
+
+use anyhow::{Result, anyhow};
+use borsh::BorshDeserialize;
+use clap::Args;
+use doublezero_revenue_distribution::{
+    DOUBLEZERO_MINT_DECIMALS, ID as REVENUE_DISTRIBUTION_PROGRAM_ID,
+    instruction::RevenueDistributionInstructionData,
+    state::{self, ContributorRewards, Distribution},
+    types::DoubleZeroEpoch,
+};
+use doublezero_solana_client_tools::rpc::{SolanaConnection, SolanaConnectionOptions};
+use solana_client::{
+    rpc_client::GetConfirmedSignaturesForAddress2Config, rpc_config::RpcTransactionConfig,
+};
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use solana_transaction_status_client_types::{
+    EncodedConfirmedTransactionWithStatusMeta, UiTransactionEncoding, UiTransactionTokenBalance,
+    option_serializer::OptionSerializer,
+};
+
+use super::journal_history::{format_signed, format_utc, instruction_name};
+
+/// Signatures requested per page while scanning.
+const SIGNATURES_PAGE_SIZE: usize = 1_000;
+
+#[derive(Debug, Args)]
+pub struct ContributorHistoryCommand {
+    /// Service key the contributor rewards account was initialized with.
+    #[arg(long, value_name = "PUBKEY")]
+    service_key: Pubkey,
+
+    /// Maximum number of transactions to scan, newest first.
+    #[arg(long, default_value_t = 5_000)]
+    limit: usize,
+
+    #[command(flatten)]
+    connection_options: SolanaConnectionOptions,
+}
+
+struct ClaimEntry {
+    signature: String,
+    slot: u64,
+    block_time: Option<i64>,
+    dz_epoch: Option<u64>,
+    amount: i128,
+}
+
+impl ContributorHistoryCommand {
+    pub async fn try_into_execute(self) -> Result<()> {
+        let Self {
+            service_key,
+            limit,
+            connection_options,
+        } = self;
+
+        let connection = SolanaConnection::try_from(connection_options)?;
+        let (contributor_rewards_key, _) = ContributorRewards::find_address(&service_key);
+
+        // Claims reference the distribution they pay out of, so map every distribution
+        // address back to its epoch.
+        let (_, program_config) = super::try_fetch_program_config(&connection).await?;
+        let distributions = (0..=program_config.next_completed_dz_epoch.value())
+            .map(|epoch| {
+                let (distribution_key, _) = Distribution::find_address(DoubleZeroEpoch::new(epoch));
+                (distribution_key, epoch)
+            })
+            .collect::<HashMap<_, _>>();
+
+        let mut signatures = Vec::new();
+        let mut before = None;
+        while signatures.len() < limit {
+            let page = connection
+                .rpc_client
+                .get_signatures_for_address_with_config(
+                    &contributor_rewards_key,
+                    GetConfirmedSignaturesForAddress2Config {
+                        before,
+                        until: None,
+                        limit: Some(SIGNATURES_PAGE_SIZE.min(limit - signatures.len())),
+                        commitment: Some(CommitmentConfig::confirmed()),
+                    },
+                )
+                .await?;
+            let Some(last) = page.last() else {
+                break;
+            };
+            before = Some(Signature::from_str(&last.signature)?);
+            let exhausted = page.len() < SIGNATURES_PAGE_SIZE;
+            signatures.extend(page);
+            if exhausted {
+                break;
+            }
+        }
+
+        // Print oldest first.
+        signatures.reverse();
+
+        let mut claims = Vec::new();
+        for status in signatures.iter().filter(|status| status.err.is_none()) {
+            let signature = Signature::from_str(&status.signature)?;
+            let transaction = connection
+                .rpc_client
+                .get_transaction_with_config(
+                    &signature,
+                    RpcTransactionConfig {
+                        encoding: Some(UiTransactionEncoding::Base64),
+                        commitment: Some(CommitmentConfig::confirmed()),
+                        max_supported_transaction_version: Some(0),
+                    },
+                )
+                .await?;
+
+            if let Some(claim) =
+                try_decode_claim(status.signature.clone(), &transaction, &distributions)?
+            {
+                claims.push(claim);
+            }
+        }
+
+        println!("Service key: {service_key}");
+        println!("Contributor rewards: {contributor_rewards_key}\n");
+        println!(
+            "Time (UTC)           | Slot        | DZ epoch | 2Z claimed               | Signature"
+        );
+        println!(
+            "---------------------+-------------+----------+--------------------------+----------"
+        );
+
+        for claim in &claims {
+            let time = claim
+                .block_time
+                .map(format_utc)
+                .unwrap_or_else(|| "unknown".to_string());
+            let dz_epoch = claim
+                .dz_epoch
+                .map(|epoch| epoch.to_string())
+                .unwrap_or_else(|| "unknown".to_string());
+            println!(
+                "{time:<20} | {:<11} | {dz_epoch:<8} | {:>24} | {}",
+                claim.slot,
+                format_signed(claim.amount, DOUBLEZERO_MINT_DECIMALS as u32),
+                claim.signature
+            );
+        }
+
+        let total: i128 = claims.iter().map(|claim| claim.amount).sum();
+        let mut epochs = claims
+            .iter()
+            .filter_map(|claim| claim.dz_epoch)
+            .collect::<Vec<_>>();
+        epochs.sort_unstable();
+        epochs.dedup();
+
+        println!();
+        println!(
+            "Total claimed over {} claims across {} epochs: {} 2Z",
+            claims.len(),
+            epochs.len(),
+            format_signed(total, DOUBLEZERO_MINT_DECIMALS as u32)
+        );
+        if signatures.len() >= limit {
+            println!("Scanned the newest {limit} transactions; raise --limit to go further back");
+        }
+        println!();
+
+        Ok(())
+    }
+}
+
+/// Decode a transaction touching the contributor rewards account as a claim, if it is one.
+///
+/// The epoch comes from the distribution account the claim references and the amount from
+/// the drop in that distribution's 2Z token balance.
+fn try_decode_claim(
+    signature: String,
+    transaction: &EncodedConfirmedTransactionWithStatusMeta,
+    distributions: &HashMap<Pubkey, u64>,
+) -> Result<Option<ClaimEntry>> {
+    let versioned_transaction = transaction
+        .transaction
+        .transaction
+        .decode()
+        .ok_or_else(|| anyhow!("Failed to decode transaction {signature}"))?;
+    let meta = transaction
+        .transaction
+        .meta
+        .as_ref()
+        .ok_or_else(|| anyhow!("Missing transaction meta for {signature}"))?;
+
+    // Static keys first, then keys loaded from lookup tables (writable, then readonly).
+    let mut account_keys = versioned_transaction.message.static_account_keys().to_vec();
+    if let OptionSerializer::Some(loaded) = &meta.loaded_addresses {
+        for key in loaded.writable.iter().chain(&loaded.readonly) {
+            account_keys.push(Pubkey::from_str(key)?);
+        }
+    }
+
+    let is_claim = versioned_transaction
+        .message
+        .instructions()
+        .iter()
+        .filter(|ix| ix.program_id(&account_keys) == &REVENUE_DISTRIBUTION_PROGRAM_ID)
+        .filter_map(|ix| RevenueDistributionInstructionData::try_from_slice(&ix.data).ok())
+        .any(|data| is_claim_instruction(&instruction_name(&data)));
+    if !is_claim {
+        return Ok(None);
+    }
+
+    let distribution = account_keys
+        .iter()
+        .find_map(|key| distributions.get(key).map(|epoch| (*key, *epoch)));
+
+    let amount = match distribution {
+        Some((distribution_key, _)) => {
+            let (distribution_token_key, _) = state::find_2z_token_pda_address(&distribution_key);
+            let token_balance =
+                |balances: &OptionSerializer<Vec<UiTransactionTokenBalance>>| -> i128 {
+                    let OptionSerializer::Some(balances) = balances else {
+                        return 0;
+                    };
+                    balances
+                        .iter()
+                        .filter(|balance| {
+                            account_keys.get(balance.account_index as usize)
+                                == Some(&distribution_token_key)
+                        })
+                        .filter_map(|balance| balance.ui_token_amount.amount.parse::<i128>().ok())
+                        .sum()
+                };
+            token_balance(&meta.pre_token_balances) - token_balance(&meta.post_token_balances)
+        }
+        None => 0,
+    };
+
+    Ok(Some(ClaimEntry {
+        signature,
+        slot: transaction.slot,
+        block_time: transaction.block_time,
+        dz_epoch: distribution.map(|(_, epoch)| epoch),
+        amount,
+    }))
+}
+
+/// Reward payouts to contributors, whether relayed or claimed directly.
+fn is_claim_instruction(name: &str) -> bool {
+    name.contains("Claim") || (name.contains("Distribute") && name.contains("Reward"))
+}
@@ -11,6 +11,8 @@ use doublezero_revenue_distribution::{
 use doublezero_solana_client_tools::payer::{SolanaPayerOptions, Wallet};
 use solana_sdk::{compute_budget::ComputeBudgetInstruction, pubkey::Pubkey};
 
+use crate::multisig::MultisigOptions;
+
 #[derive(Debug, Args)]
 pub struct ContributorRewardsCommand {
     service_key: Pubkey,
@@ -20,6 +22,9 @@ pub struct ContributorRewardsCommand {
 
     #[command(flatten)]
     solana_payer_options: SolanaPayerOptions,
+
+    #[command(flatten)]
+    multisig_options: MultisigOptions,
 }
 
 impl ContributorRewardsCommand {
@@ -28,6 +33,7 @@ impl ContributorRewardsCommand {
             service_key,
             initialize,
             solana_payer_options,
+            multisig_options,
         } = self;
 
         if !initialize {
@@ -35,14 +41,21 @@ impl ContributorRewardsCommand {
         }
 
         let wallet = Wallet::try_from(solana_payer_options)?;
-        let wallet_key = wallet.pubkey();
+        let multisig_vault = multisig_options.vault();
+        let authority_key = multisig_vault.map_or(wallet.pubkey(), |vault| vault.key);
 
         let initialize_contributor_rewards_ix = try_build_instruction(
             &ID,
-            InitializeContributorRewardsAccounts::new(&wallet_key, &service_key),
+            InitializeContributorRewardsAccounts::new(&authority_key, &service_key),
             &RevenueDistributionInstructionData::InitializeContributorRewards(service_key),
         )?;
 
+        if let Some(vault) = multisig_vault {
+            return vault
+                .try_propose(&wallet, &[initialize_contributor_rewards_ix])
+                .await;
+        }
+
         let mut compute_unit_limit = 10_000;
 
         let (_, bump) = ContributorRewards::find_address(&service_key);
@@ -0,0 +1,301 @@
+use std::{fmt, str::FromStr};
+
+use anyhow::{Result, anyhow};
+use borsh::BorshDeserialize;
+use clap::Args;
+use doublezero_revenue_distribution::{
+    DOUBLEZERO_MINT_DECIMALS, ID as REVENUE_DISTRIBUTION_PROGRAM_ID,
+    instruction::RevenueDistributionInstructionData,
+    state::{self, Journal},
+};
+use doublezero_solana_client_tools::rpc::{SolanaConnection, SolanaConnectionOptions};
+use solana_client::{
+    rpc_client::GetConfirmedSignaturesForAddress2Config, rpc_config::RpcTransactionConfig,
+};
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use solana_transaction_status_client_types::{
+    EncodedConfirmedTransactionWithStatusMeta, UiTransactionEncoding, UiTransactionTokenBalance,
+    option_serializer::OptionSerializer,
+};
+
+#[derive(Debug, Args)]
+pub struct JournalHistoryCommand {
+    /// Maximum number of transactions to fetch, newest first.
+    #[arg(long, default_value_t = 50)]
+    limit: usize,
+
+    /// Only fetch transactions older than this signature (for paging).
+    #[arg(long, value_name = "SIGNATURE")]
+    before: Option<Signature>,
+
+    #[command(flatten)]
+    connection_options: SolanaConnectionOptions,
+}
+
+/// Journal balance movement implied by a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum JournalEvent {
+    Deposit,
+    Distribution,
+    Burn,
+    Other,
+}
+
+impl JournalEvent {
+    /// Classify by the program instruction first, falling back to the direction of the
+    /// balance change.
+    fn classify(instruction: Option<&str>, sol_delta: i128, token_delta: i128) -> Self {
+        if let Some(instruction) = instruction {
+            if instruction.contains("Burn") {
+                return Self::Burn;
+            }
+            if instruction.contains("Distribute") || instruction.contains("Sweep") {
+                return Self::Distribution;
+            }
+            if instruction.contains("Pay") || instruction.contains("Deposit") {
+                return Self::Deposit;
+            }
+        }
+
+        match (sol_delta.signum(), token_delta.signum()) {
+            (1, _) | (_, 1) => Self::Deposit,
+            (-1, _) | (_, -1) => Self::Distribution,
+            _ => Self::Other,
+        }
+    }
+}
+
+impl fmt::Display for JournalEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Self::Deposit => "deposit",
+            Self::Distribution => "distribution",
+            Self::Burn => "burn",
+            Self::Other => "other",
+        })
+    }
+}
+
+struct JournalEntry {
+    signature: String,
+    slot: u64,
+    block_time: Option<i64>,
+    instructions: Vec<String>,
+    event: JournalEvent,
+    sol_delta: i128,
+    token_delta: i128,
+}
+
+impl JournalHistoryCommand {
+    pub async fn try_into_execute(self) -> Result<()> {
+        let Self {
+            limit,
+            before,
+            connection_options,
+        } = self;
+
+        let connection = SolanaConnection::try_from(connection_options)?;
+        let journal_key = Journal::find_address().0;
+        let (journal_token_key, _) = state::find_2z_token_pda_address(&journal_key);
+
+        let mut signatures = connection
+            .rpc_client
+            .get_signatures_for_address_with_config(
+                &journal_key,
+                GetConfirmedSignaturesForAddress2Config {
+                    before,
+                    until: None,
+                    limit: Some(limit),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                },
+            )
+            .await?;
+
+        // Print oldest first.
+        signatures.reverse();
+
+        let mut entries = Vec::with_capacity(signatures.len());
+        for status in signatures.into_iter().filter(|status| status.err.is_none()) {
+            let signature = Signature::from_str(&status.signature)?;
+            let transaction = connection
+                .rpc_client
+                .get_transaction_with_config(
+                    &signature,
+                    RpcTransactionConfig {
+                        encoding: Some(UiTransactionEncoding::Base64),
+                        commitment: Some(CommitmentConfig::confirmed()),
+                        max_supported_transaction_version: Some(0),
+                    },
+                )
+                .await?;
+
+            entries.push(try_decode_entry(
+                status.signature,
+                &transaction,
+                &journal_key,
+                &journal_token_key,
+            )?);
+        }
+
+        println!("Journal: {journal_key}\n");
+        println!(
+            "Time (UTC)           | Slot        | Event        | SOL change        | 2Z change                | Instructions | Signature"
+        );
+        println!(
+            "---------------------+-------------+--------------+-------------------+--------------------------+--------------+----------"
+        );
+
+        for entry in &entries {
+            let time = entry
+                .block_time
+                .map(format_utc)
+                .unwrap_or_else(|| "unknown".to_string());
+            println!(
+                "{time:<20} | {:<11} | {:<12} | {:>17} | {:>24} | {} | {}",
+                entry.slot,
+                entry.event,
+                format_signed(entry.sol_delta, 9),
+                format_signed(entry.token_delta, DOUBLEZERO_MINT_DECIMALS as u32),
+                entry.instructions.join(", "),
+                entry.signature
+            );
+        }
+
+        let total_sol: i128 = entries.iter().map(|entry| entry.sol_delta).sum();
+        let total_2z: i128 = entries.iter().map(|entry| entry.token_delta).sum();
+        println!();
+        println!(
+            "Net change over {} transactions: {} SOL, {} 2Z",
+            entries.len(),
+            format_signed(total_sol, 9),
+            format_signed(total_2z, DOUBLEZERO_MINT_DECIMALS as u32)
+        );
+        if let Some(oldest) = entries.first() {
+            println!("Fetch older entries with --before {}", oldest.signature);
+        }
+        println!();
+
+        Ok(())
+    }
+}
+
+fn try_decode_entry(
+    signature: String,
+    transaction: &EncodedConfirmedTransactionWithStatusMeta,
+    journal_key: &Pubkey,
+    journal_token_key: &Pubkey,
+) -> Result<JournalEntry> {
+    let versioned_transaction = transaction
+        .transaction
+        .transaction
+        .decode()
+        .ok_or_else(|| anyhow!("Failed to decode transaction {signature}"))?;
+    let meta = transaction
+        .transaction
+        .meta
+        .as_ref()
+        .ok_or_else(|| anyhow!("Missing transaction meta for {signature}"))?;
+
+    // Static keys first, then keys loaded from lookup tables (writable, then readonly).
+    let mut account_keys = versioned_transaction.message.static_account_keys().to_vec();
+    if let OptionSerializer::Some(loaded) = &meta.loaded_addresses {
+        for key in loaded.writable.iter().chain(&loaded.readonly) {
+            account_keys.push(Pubkey::from_str(key)?);
+        }
+    }
+
+    let sol_delta = account_keys
+        .iter()
+        .position(|key| key == journal_key)
+        .map(|index| meta.post_balances[index] as i128 - meta.pre_balances[index] as i128)
+        .unwrap_or_default();
+
+    let token_balance = |balances: &OptionSerializer<Vec<UiTransactionTokenBalance>>| -> i128 {
+        let OptionSerializer::Some(balances) = balances else {
+            return 0;
+        };
+        balances
+            .iter()
+            .filter(|balance| {
+                account_keys.get(balance.account_index as usize) == Some(journal_token_key)
+            })
+            .filter_map(|balance| balance.ui_token_amount.amount.parse::<i128>().ok())
+            .sum()
+    };
+    let token_delta =
+        token_balance(&meta.post_token_balances) - token_balance(&meta.pre_token_balances);
+
+    let instructions = versioned_transaction
+        .message
+        .instructions()
+        .iter()
+        .filter(|ix| ix.program_id(&account_keys) == &REVENUE_DISTRIBUTION_PROGRAM_ID)
+        .map(|ix| match RevenueDistributionInstructionData::try_from_slice(&ix.data) {
+            Ok(data) => instruction_name(&data),
+            Err(_) => "Unknown".to_string(),
+        })
+        .collect::<Vec<_>>();
+
+    let event = JournalEvent::classify(
+        instructions.first().map(String::as_str),
+        sol_delta,
+        token_delta,
+    );
+
+    Ok(JournalEntry {
+        signature,
+        slot: transaction.slot,
+        block_time: transaction.block_time,
+        instructions,
+        event,
+        sol_delta,
+        token_delta,
+    })
+}
+
+/// Variant name of the decoded instruction, without its fields.
+pub(super) fn instruction_name(data: &RevenueDistributionInstructionData) -> String {
+    let debug = format!("{data:?}");
+    debug
+        .split(|c: char| c == '(' || c == '{' || c.is_whitespace())
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+pub(super) fn format_signed(amount: i128, decimals: u32) -> String {
+    let scale = 10i128.pow(decimals);
+    let sign = if amount < 0 { "-" } else { "+" };
+    let amount = amount.abs();
+    format!(
+        "{sign}{}.{:0width$}",
+        amount / scale,
+        amount % scale,
+        width = decimals as usize
+    )
+}
+
+/// Format a unix timestamp as `YYYY-MM-DD HH:MM:SS`.
+pub(super) fn format_utc(timestamp: i64) -> String {
+    let days = timestamp.div_euclid(86_400);
+    let seconds = timestamp.rem_euclid(86_400);
+
+    // Civil-from-days (Howard Hinnant), valid for the proleptic Gregorian calendar.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}",
+        seconds / 3_600,
+        seconds % 3_600 / 60,
+        seconds % 60
+    )
+}
@@ -1,5 +1,7 @@
+mod contributor_history;
 mod contributor_rewards;
 mod fetch;
+mod journal_history;
 mod relay;
 mod validator_deposit;
 
@@ -25,9 +27,15 @@ pub enum RevenueDistributionSubcommand {
     /// Contributor rewards account management.
     ContributorRewards(contributor_rewards::ContributorRewardsCommand),
 
+    /// History of reward claims paid to a contributor.
+    ContributorHistory(contributor_history::ContributorHistoryCommand),
+
     /// Solana validator deposit account management.
     ValidatorDeposit(validator_deposit::ValidatorDepositCommand),
 
+    /// Chronological ledger of transactions touching the Journal account.
+    JournalHistory(journal_history::JournalHistoryCommand),
+
     /// Relayer instructions for the Revenue Distribution program.
     Relay(relay::RevenueDistributionRelayCommand),
 }
@@ -37,7 +45,9 @@ impl RevenueDistributionSubcommand {
         match self {
             Self::Fetch(command) => command.try_into_execute().await,
             Self::ContributorRewards(command) => command.try_into_execute().await,
+            Self::ContributorHistory(command) => command.try_into_execute().await,
             Self::ValidatorDeposit(command) => command.try_into_execute().await,
+            Self::JournalHistory(command) => command.try_into_execute().await,
             Self::Relay(command) => command.inner.try_into_execute().await,
         }
     }
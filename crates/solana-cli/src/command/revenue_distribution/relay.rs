@@ -1,4 +1,4 @@
-use anyhow::Result;
+use anyhow::{Result, bail};
 use borsh::de::BorshDeserialize;
 use clap::{Args, Subcommand};
 use doublezero_solana_client_tools::{
@@ -6,10 +6,17 @@ use doublezero_solana_client_tools::{
     rpc::DoubleZeroLedgerConnectionOptions,
 };
 use doublezero_solana_validator_debt::{
-    ledger, transaction::Transaction, validator_debt::ComputedSolanaValidatorDebts,
+    ledger,
+    notify::{Milestone, Notifier},
+    payment_plan::{AllocationStrategy, PaymentPlan},
+    receipt::{self, PaymentReceipt},
+    transaction::Transaction,
+    validator_debt::ComputedSolanaValidatorDebts,
 };
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_commitment_config::CommitmentConfig;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
+use std::io::{self, BufRead, Write};
 
 #[derive(Debug, Args)]
 pub struct RevenueDistributionRelayCommand {
@@ -20,7 +27,37 @@ pub struct RevenueDistributionRelayCommand {
 #[derive(Debug, Subcommand)]
 pub enum RevenueDistributionRelaySubcommand {
     PaySolanaValidatorDebt {
-        #[arg(long)]
+        /// DZ epoch whose debt to pay. Repeat to pay debts across several epochs.
+        #[arg(long = "epoch", value_name = "EPOCH", required = true)]
+        epochs: Vec<u64>,
+
+        /// Maximum total amount (in lamports) to pay. Debts that do not fit are deferred.
+        #[arg(long, value_name = "LAMPORTS")]
+        max_amount: Option<u64>,
+
+        /// Only pay debts owed by these node IDs.
+        #[arg(long, value_name = "PUBKEY,PUBKEY,PUBKEY", value_delimiter = ',')]
+        only_node_ids: Vec<Pubkey>,
+
+        /// Order in which debts are paid when the budget does not cover all of them.
+        #[arg(long, value_enum, default_value_t = AllocationStrategy::OldestEpochFirst)]
+        strategy: AllocationStrategy,
+
+        /// Skip the confirmation prompt.
+        #[arg(long, short = 'y', default_value_t = false)]
+        yes: bool,
+
+        #[command(flatten)]
+        solana_payer_options: SolanaPayerOptions,
+
+        #[command(flatten)]
+        dz_ledger_connection_options: DoubleZeroLedgerConnectionOptions,
+    },
+
+    /// List the payment receipts recorded on the DZ ledger for a DZ epoch.
+    Receipts {
+        /// DZ epoch whose payment receipts to list.
+        #[arg(long, value_name = "EPOCH")]
         epoch: u64,
 
         #[command(flatten)]
@@ -37,11 +74,33 @@ impl RevenueDistributionRelaySubcommand {
     pub async fn try_into_execute(self) -> Result<()> {
         match self {
             Self::PaySolanaValidatorDebt {
-                epoch,
+                epochs,
+                max_amount,
+                only_node_ids,
+                strategy,
+                yes,
                 solana_payer_options,
                 dz_ledger_connection_options,
             } => {
                 execute_pay_solana_validator_debt(
+                    PayDebtOptions {
+                        epochs,
+                        max_amount,
+                        only_node_ids,
+                        strategy,
+                        yes,
+                    },
+                    solana_payer_options,
+                    dz_ledger_connection_options,
+                )
+                .await
+            }
+            Self::Receipts {
+                epoch,
+                solana_payer_options,
+                dz_ledger_connection_options,
+            } => {
+                execute_list_payment_receipts(
                     epoch,
                     solana_payer_options,
                     dz_ledger_connection_options,
@@ -52,37 +111,189 @@ impl RevenueDistributionRelaySubcommand {
     }
 }
 
+pub struct PayDebtOptions {
+    pub epochs: Vec<u64>,
+    pub max_amount: Option<u64>,
+    pub only_node_ids: Vec<Pubkey>,
+    pub strategy: AllocationStrategy,
+    pub yes: bool,
+}
+
 pub async fn execute_pay_solana_validator_debt(
+    options: PayDebtOptions,
+    solana_payer_options: SolanaPayerOptions,
+    dz_ledger_connection_options: DoubleZeroLedgerConnectionOptions,
+) -> Result<()> {
+    let PayDebtOptions {
+        mut epochs,
+        max_amount,
+        only_node_ids,
+        strategy,
+        yes,
+    } = options;
+    epochs.sort_unstable();
+    epochs.dedup();
+
+    let wallet = Wallet::try_from(solana_payer_options)?;
+    let dz_ledger_rpc_client = RpcClient::new_with_commitment(
+        dz_ledger_connection_options.dz_ledger_url,
+        CommitmentConfig::confirmed(),
+    );
+
+    let mut debts = Vec::with_capacity(epochs.len());
+    for epoch in epochs {
+        let deserialized = read_debts(&dz_ledger_rpc_client, &wallet.signer, epoch).await?;
+        debts.push((epoch, deserialized));
+    }
+
+    let plan = PaymentPlan::build(&debts, max_amount, &only_node_ids, strategy);
+    if plan.items.is_empty() {
+        bail!("No debts to pay with the given filters and budget");
+    }
+
+    println!("Payment plan ({strategy:?}):\n{}", plan.to_table());
+    println!(
+        "Paying {} debts totaling {} lamports; deferring {} debts totaling {} lamports",
+        plan.items.len(),
+        plan.total(),
+        plan.deferred.len(),
+        plan.deferred_total()
+    );
+
+    if !yes && !wallet.dry_run && !confirm("Proceed with payment?")? {
+        println!("Aborted");
+        return Ok(());
+    }
+
+    let dry_run = wallet.dry_run;
+    let transaction = Transaction::new(wallet.signer, dry_run, false); // hardcoding force as false as it doesn't matter here. will revisit later
+    for (epoch, deserialized) in &debts {
+        let items = plan.items_for_epoch(*epoch);
+        if items.is_empty() {
+            continue;
+        }
+        let node_ids: Vec<Pubkey> = items.iter().map(|item| item.node_id).collect();
+
+        let transactions = transaction
+            .pay_solana_validator_debt_for_node_ids(
+                &wallet.connection.rpc_client,
+                deserialized,
+                &node_ids,
+                *epoch,
+            )
+            .await?;
+        for (item, t) in items.into_iter().zip(transactions) {
+            let signature = transaction
+                .send_or_simulate_transaction(&wallet.connection.rpc_client, &t)
+                .await?;
+
+            // The payment has landed, so a failed receipt is reported but does not stop the run
+            if let Some(signature) = signature {
+                let receipt = PaymentReceipt::new(
+                    item.dz_epoch,
+                    item.node_id,
+                    item.amount,
+                    &signature,
+                    transaction.pubkey(),
+                );
+                if let Err(err) = receipt::write_receipt(
+                    &dz_ledger_rpc_client,
+                    &transaction.signer,
+                    &receipt,
+                    dz_ledger_rpc_client.commitment(),
+                )
+                .await
+                {
+                    eprintln!(
+                        "Failed to write payment receipt for {} in DZ epoch {epoch} ({signature}): {err:#}",
+                        item.node_id
+                    );
+                }
+            }
+        }
+    }
+
+    if !dry_run {
+        let mut dz_epochs: Vec<u64> = plan.items.iter().map(|item| item.dz_epoch).collect();
+        dz_epochs.sort_unstable();
+        dz_epochs.dedup();
+
+        Notifier::from_env()
+            .notify(Milestone::PaymentsCompleted {
+                dz_epochs,
+                payments: plan.items.len(),
+                total_paid: plan.total(),
+            })
+            .await;
+    }
+
+    Ok(())
+}
+
+pub async fn execute_list_payment_receipts(
     epoch: u64,
     solana_payer_options: SolanaPayerOptions,
     dz_ledger_connection_options: DoubleZeroLedgerConnectionOptions,
 ) -> Result<()> {
-    let prefix = b"solana_validator_debt_test";
-    let dz_epoch_bytes = epoch.to_le_bytes();
-    let seeds: &[&[u8]] = &[prefix, &dz_epoch_bytes];
     let wallet = Wallet::try_from(solana_payer_options)?;
     let dz_ledger_rpc_client = RpcClient::new_with_commitment(
         dz_ledger_connection_options.dz_ledger_url,
         CommitmentConfig::confirmed(),
     );
-    let read = ledger::read_from_ledger(
+
+    let debts = read_debts(&dz_ledger_rpc_client, &wallet.signer, epoch).await?;
+    let node_ids: Vec<Pubkey> = debts.debts.iter().map(|debt| debt.node_id).collect();
+    let receipts = receipt::fetch_receipts(
         &dz_ledger_rpc_client,
-        &wallet.signer,
+        &wallet.signer.pubkey(),
+        epoch,
+        &node_ids,
+    )
+    .await?;
+
+    if receipts.is_empty() {
+        println!("No payment receipts for DZ epoch {epoch}");
+        return Ok(());
+    }
+
+    println!("{}", receipt::to_table(&receipts));
+    println!(
+        "{} of {} debts paid in DZ epoch {epoch}, totaling {} lamports",
+        receipts.len(),
+        node_ids.len(),
+        receipts.iter().map(|receipt| receipt.amount).sum::<u64>()
+    );
+
+    Ok(())
+}
+
+/// Debts computed for `epoch`, as recorded on the DZ ledger by `signer`.
+async fn read_debts(
+    dz_ledger_rpc_client: &RpcClient,
+    signer: &Keypair,
+    epoch: u64,
+) -> Result<ComputedSolanaValidatorDebts> {
+    let prefix = b"solana_validator_debt_test";
+    let dz_epoch_bytes = epoch.to_le_bytes();
+    let seeds: &[&[u8]] = &[prefix, &dz_epoch_bytes];
+    let read = ledger::read_from_ledger(
+        dz_ledger_rpc_client,
+        signer,
         seeds,
         dz_ledger_rpc_client.commitment(),
     )
     .await?;
 
-    let deserialized = ComputedSolanaValidatorDebts::try_from_slice(read.1.as_slice())?;
+    Ok(ComputedSolanaValidatorDebts::try_from_slice(
+        read.1.as_slice(),
+    )?)
+}
 
-    let transaction = Transaction::new(wallet.signer, wallet.dry_run, false); // hardcoding force as false as it doesn't matter here. will revisit later
-    let transactions = transaction
-        .pay_solana_validator_debt(&wallet.connection.rpc_client, deserialized, epoch)
-        .await?;
-    for t in transactions {
-        transaction
-            .send_or_simulate_transaction(&wallet.connection.rpc_client, &t)
-            .await?;
-    }
-    Ok(())
+fn confirm(prompt: &str) -> Result<bool> {
+    print!("{prompt} [y/N] ");
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
 }
@@ -11,6 +11,8 @@ use doublezero_revenue_distribution::{
 use doublezero_solana_client_tools::payer::{SolanaPayerOptions, Wallet};
 use solana_sdk::{compute_budget::ComputeBudgetInstruction, pubkey::Pubkey};
 
+use crate::multisig::MultisigOptions;
+
 #[derive(Debug, Args)]
 pub struct ValidatorDepositCommand {
     node_id: Pubkey,
@@ -23,6 +25,9 @@ pub struct ValidatorDepositCommand {
 
     #[command(flatten)]
     solana_payer_options: SolanaPayerOptions,
+
+    #[command(flatten)]
+    multisig_options: MultisigOptions,
 }
 
 impl ValidatorDepositCommand {
@@ -32,10 +37,12 @@ impl ValidatorDepositCommand {
             initialize,
             fund,
             solana_payer_options,
+            multisig_options,
         } = self;
 
         let wallet = Wallet::try_from(solana_payer_options)?;
-        let wallet_key = wallet.pubkey();
+        let multisig_vault = multisig_options.vault();
+        let authority_key = multisig_vault.map_or(wallet.pubkey(), |vault| vault.key);
 
         // First check if the solana validator deposit is already initialized.
         let (deposit_key, deposit, mut deposit_balance) =
@@ -61,7 +68,7 @@ impl ValidatorDepositCommand {
         let and_initialized_str = if initialize || should_initialize {
             let initialize_solana_validator_deposit_ix = try_build_instruction(
                 &ID,
-                InitializeSolanaValidatorDepositAccounts::new(&wallet_key, &node_id),
+                InitializeSolanaValidatorDepositAccounts::new(&authority_key, &node_id),
                 &RevenueDistributionInstructionData::InitializeSolanaValidatorDeposit(node_id),
             )?;
 
@@ -80,7 +87,7 @@ impl ValidatorDepositCommand {
             deposit_balance += fund_lamports;
 
             let transfer_ix = solana_system_interface::instruction::transfer(
-                &wallet_key,
+                &authority_key,
                 &deposit_key,
                 fund_lamports,
             );
@@ -93,6 +100,10 @@ impl ValidatorDepositCommand {
             bail!("Nothing to do. Please specify `--initialize` or `--fund`");
         }
 
+        if let Some(vault) = multisig_vault {
+            return vault.try_propose(&wallet, &instructions).await;
+        }
+
         instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(
             compute_unit_limit,
         ));
@@ -0,0 +1,138 @@
+//! Default options per subcommand.
+//!
+//! Defaults live in `~/.config/doublezero/2z.toml` (or `$XDG_CONFIG_HOME/doublezero/2z.toml`).
+//! Top-level keys apply to every subcommand accepting the option, and each table narrows them
+//! down to a subcommand, with deeper tables taking precedence:
+//!
+//! ```toml
+//! url = "https://my-rpc.example.com"
+//! keypair = "/home/ops/.config/solana/ops.json"
+//! with-compute-unit-price = 5000
+//!
+//! [revenue-distribution]
+//! dz-ledger-url = "https://doublezero-ledger.example.com"
+//!
+//! [revenue-distribution.fetch]
+//! commitment = "finalized"
+//! ```
+//!
+//! Keys are long option names. Options given on the command line win over an RPC profile
+//! selected with `--profile`, which in turn wins over these defaults.
+
+use std::{collections::BTreeMap, ffi::OsString, fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Command;
+use toml::{Table, Value};
+
+use crate::profile::{config_dir, is_option_set, resolve_subcommand};
+
+pub fn defaults_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("2z.toml"))
+}
+
+/// Parsed defaults file, or `None` if there is none.
+pub fn load_defaults() -> Result<Option<Table>> {
+    let Some(path) = defaults_path().filter(|path| path.exists()) else {
+        return Ok(None);
+    };
+    let contents =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let table = contents
+        .parse::<Table>()
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+
+    Ok(Some(table))
+}
+
+/// A default option value and the table it came from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DefaultValue {
+    pub value: Value,
+    /// Subcommand path of the table, empty for top-level keys.
+    pub section: Vec<String>,
+}
+
+impl DefaultValue {
+    pub fn section_name(&self) -> String {
+        if self.section.is_empty() {
+            "(top level)".to_string()
+        } else {
+            format!("[{}]", self.section.join("."))
+        }
+    }
+}
+
+/// Defaults for the subcommand at `path`, keyed by long option name. Tables deeper along the
+/// path override the ones above them.
+pub fn layered_defaults(root: &Table, path: &[String]) -> BTreeMap<String, DefaultValue> {
+    let mut merged = BTreeMap::new();
+    let mut table = Some(root);
+
+    for depth in 0..=path.len() {
+        let Some(current) = table else {
+            break;
+        };
+        for (key, value) in current {
+            if !value.is_table() {
+                merged.insert(
+                    key.clone(),
+                    DefaultValue {
+                        value: value.clone(),
+                        section: path[..depth].to_vec(),
+                    },
+                );
+            }
+        }
+        table = path
+            .get(depth)
+            .and_then(|name| current.get(name))
+            .and_then(Value::as_table);
+    }
+
+    merged
+}
+
+/// Fill in the defaults for the invoked subcommand that were not given on the command line.
+///
+/// Returns the arguments unchanged when there is no defaults file.
+pub fn apply_defaults(command: &Command, args: Vec<OsString>) -> Result<Vec<OsString>> {
+    let Some(root) = load_defaults()? else {
+        return Ok(args);
+    };
+
+    let (subcommand, path) = resolve_subcommand(command, &args);
+    let mut expanded = args;
+
+    for (long, default) in layered_defaults(&root, &path) {
+        let Some(arg) = subcommand
+            .get_arguments()
+            .find(|arg| arg.get_long() == Some(long.as_str()))
+        else {
+            continue;
+        };
+        if is_option_set(&expanded, arg) {
+            continue;
+        }
+
+        if arg.get_action().takes_values() {
+            for value in option_values(&default.value) {
+                expanded.push(format!("--{long}").into());
+                expanded.push(value.into());
+            }
+        } else if default.value.as_bool() == Some(true) {
+            expanded.push(format!("--{long}").into());
+        }
+    }
+
+    Ok(expanded)
+}
+
+/// Command line values for a default; arrays repeat the option once per element.
+pub fn option_values(value: &Value) -> Vec<String> {
+    match value {
+        Value::String(value) => vec![value.clone()],
+        Value::Array(values) => values.iter().flat_map(option_values).collect(),
+        value => vec![value.to_string()],
+    }
+}
@@ -1,3 +1,6 @@
 pub mod command;
+pub mod defaults;
 pub mod helpers;
+pub mod multisig;
+pub use doublezero_cli_profile as profile;
 pub mod serviceability;
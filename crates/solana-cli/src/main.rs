@@ -1,19 +1,29 @@
 use anyhow::Result;
-use clap::Parser;
-use doublezero_solana_cli::command::DoubleZeroSolanaCommand;
+use clap::{CommandFactory, Parser};
+use doublezero_solana_cli::{command::DoubleZeroSolanaCommand, defaults, profile};
 
 #[derive(Debug, Parser)]
 #[command(term_width = 0)]
 #[command(version = option_env!("BUILD_VERSION").unwrap_or(env!("CARGO_PKG_VERSION")))]
 #[command(about = "DoubleZero Solana-related Commands", long_about = None)]
 struct DoubleZeroSolanaApp {
+    /// Named RPC profile from ~/.config/doublezero/cli.toml supplying default connection
+    /// options (URLs, commitment, compute unit price).
+    #[arg(long = profile::PROFILE_ARG, global = true, value_name = "NAME")]
+    _profile: Option<String>,
+
     #[command(subcommand)]
     command: DoubleZeroSolanaCommand,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    DoubleZeroSolanaApp::parse()
+    let command = DoubleZeroSolanaApp::command();
+    let args = profile::apply_profile(&command, std::env::args_os().collect())?;
+    // Profile options are already in place, so they take precedence over the defaults file
+    let args = defaults::apply_defaults(&command, args)?;
+
+    DoubleZeroSolanaApp::parse_from(args)
         .command
         .try_into_execute()
         .await
@@ -0,0 +1,389 @@
+//! Squads (v4) multisig proposals.
+//!
+//! With `--multisig <MS_PDA>`, a state-changing command does not execute its instructions with
+//! the payer as authority. Instead, the instructions are built with a vault of the multisig as
+//! authority and wrapped in a vault transaction with a proposal, which the multisig members then
+//! approve and execute with their usual Squads tooling. The payer creates both accounts, so it
+//! must be a member of the multisig with permission to initiate transactions.
+
+use anyhow::{Result, anyhow, bail, ensure};
+use clap::Args;
+use doublezero_solana_client_tools::payer::Wallet;
+use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction,
+    hash::hash,
+    instruction::{AccountMeta, Instruction},
+    message::Message,
+    pubkey,
+    pubkey::Pubkey,
+};
+
+pub const SQUADS_PROGRAM_ID: Pubkey = pubkey!("SQDS4ep65T869zMMBKyuUq6aD6EgTu8psMjkvj52pCf");
+
+const SEED_PREFIX: &[u8] = b"multisig";
+const SEED_VAULT: &[u8] = b"vault";
+const SEED_TRANSACTION: &[u8] = b"transaction";
+const SEED_PROPOSAL: &[u8] = b"proposal";
+
+/// Offset of `transaction_index` in a multisig account: discriminator, create key, config
+/// authority, threshold (u16) and time lock (u32) come before it.
+const TRANSACTION_INDEX_OFFSET: usize = 8 + 32 + 32 + 2 + 4;
+
+/// Creating the vault transaction and proposal accounts.
+const PROPOSE_COMPUTE_UNIT_LIMIT: u32 = 100_000;
+
+#[derive(Debug, Args)]
+pub struct MultisigOptions {
+    /// Propose the transaction to this Squads multisig instead of executing it. The transaction
+    /// is executed by a vault of the multisig once approved.
+    #[arg(long, value_name = "MS_PDA")]
+    pub multisig: Option<Pubkey>,
+
+    /// Index of the multisig vault acting as authority.
+    #[arg(long, value_name = "INDEX", default_value_t = 0, requires = "multisig")]
+    pub vault_index: u8,
+}
+
+impl MultisigOptions {
+    pub fn vault(&self) -> Option<SquadsVault> {
+        self.multisig
+            .map(|multisig_key| SquadsVault::new(multisig_key, self.vault_index))
+    }
+}
+
+/// Vault of a Squads multisig, authority of the instructions it executes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SquadsVault {
+    pub multisig_key: Pubkey,
+    pub index: u8,
+    pub key: Pubkey,
+}
+
+impl SquadsVault {
+    pub fn new(multisig_key: Pubkey, index: u8) -> Self {
+        let (key, _) = Pubkey::find_program_address(
+            &[SEED_PREFIX, multisig_key.as_ref(), SEED_VAULT, &[index]],
+            &SQUADS_PROGRAM_ID,
+        );
+
+        Self {
+            multisig_key,
+            index,
+            key,
+        }
+    }
+
+    /// Create a vault transaction executing `instructions` and a proposal for it.
+    ///
+    /// Instructions must use the vault as signer in place of the payer. Compute budget
+    /// instructions are left out of the proposal since they only apply to the transaction
+    /// creating it.
+    pub async fn try_propose(&self, wallet: &Wallet, instructions: &[Instruction]) -> Result<()> {
+        let multisig_account = wallet
+            .connection
+            .rpc_client
+            .get_account(&self.multisig_key)
+            .await?;
+        ensure!(
+            multisig_account.owner == SQUADS_PROGRAM_ID,
+            "{} is not a Squads multisig",
+            self.multisig_key
+        );
+        let transaction_index = try_next_transaction_index(&multisig_account.data)?;
+
+        let wallet_key = wallet.pubkey();
+        let proposal = SquadsProposal::new(self, transaction_index);
+
+        let mut propose_instructions = vec![
+            proposal.try_vault_transaction_create_ix(&wallet_key, instructions)?,
+            proposal.proposal_create_ix(&wallet_key),
+            ComputeBudgetInstruction::set_compute_unit_limit(PROPOSE_COMPUTE_UNIT_LIMIT),
+        ];
+
+        if let Some(ref compute_unit_price_ix) = wallet.compute_unit_price_ix {
+            propose_instructions.push(compute_unit_price_ix.clone());
+        }
+
+        let transaction = wallet.new_transaction(&propose_instructions).await?;
+        let tx_sig = wallet.send_or_simulate_transaction(&transaction).await?;
+
+        if let Some(tx_sig) = tx_sig {
+            println!("Proposed to multisig {}: {tx_sig}", self.multisig_key);
+            println!("Proposal: {}", proposal.proposal_key);
+            println!("Transaction index: {transaction_index}");
+            println!("Vault: {} (index {})", self.key, self.index);
+
+            wallet.print_verbose_output(&[tx_sig]).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Vault transaction and proposal accounts at a multisig transaction index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SquadsProposal {
+    pub vault: SquadsVault,
+    pub transaction_index: u64,
+    pub transaction_key: Pubkey,
+    pub proposal_key: Pubkey,
+}
+
+impl SquadsProposal {
+    pub fn new(vault: &SquadsVault, transaction_index: u64) -> Self {
+        let multisig_key = vault.multisig_key;
+        let index_bytes = transaction_index.to_le_bytes();
+
+        let (transaction_key, _) = Pubkey::find_program_address(
+            &[
+                SEED_PREFIX,
+                multisig_key.as_ref(),
+                SEED_TRANSACTION,
+                &index_bytes,
+            ],
+            &SQUADS_PROGRAM_ID,
+        );
+        let (proposal_key, _) = Pubkey::find_program_address(
+            &[
+                SEED_PREFIX,
+                multisig_key.as_ref(),
+                SEED_TRANSACTION,
+                &index_bytes,
+                SEED_PROPOSAL,
+            ],
+            &SQUADS_PROGRAM_ID,
+        );
+
+        Self {
+            vault: *vault,
+            transaction_index,
+            transaction_key,
+            proposal_key,
+        }
+    }
+
+    pub fn try_vault_transaction_create_ix(
+        &self,
+        creator_key: &Pubkey,
+        instructions: &[Instruction],
+    ) -> Result<Instruction> {
+        let transaction_message = try_serialize_vault_message(&self.vault.key, instructions)?;
+
+        // VaultTransactionCreateArgs: vault index, ephemeral signers, message and memo.
+        let args = borsh::to_vec(&(self.vault.index, 0_u8, transaction_message, None::<String>))?;
+
+        Ok(Instruction {
+            program_id: SQUADS_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new(self.vault.multisig_key, false),
+                AccountMeta::new(self.transaction_key, false),
+                AccountMeta::new_readonly(*creator_key, true),
+                AccountMeta::new(*creator_key, true),
+                AccountMeta::new_readonly(solana_system_interface::program::ID, false),
+            ],
+            data: [
+                anchor_discriminator("vault_transaction_create").as_slice(),
+                &args,
+            ]
+            .concat(),
+        })
+    }
+
+    pub fn proposal_create_ix(&self, creator_key: &Pubkey) -> Instruction {
+        // ProposalCreateArgs: transaction index and whether the proposal starts as a draft.
+        let mut data = anchor_discriminator("proposal_create").to_vec();
+        data.extend_from_slice(&self.transaction_index.to_le_bytes());
+        data.push(false as u8);
+
+        Instruction {
+            program_id: SQUADS_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new_readonly(self.vault.multisig_key, false),
+                AccountMeta::new(self.proposal_key, false),
+                AccountMeta::new_readonly(*creator_key, true),
+                AccountMeta::new(*creator_key, true),
+                AccountMeta::new_readonly(solana_system_interface::program::ID, false),
+            ],
+            data,
+        }
+    }
+}
+
+fn anchor_discriminator(instruction_name: &str) -> [u8; 8] {
+    let hash = hash(format!("global:{instruction_name}").as_bytes());
+    hash.to_bytes()[..8].try_into().unwrap()
+}
+
+fn try_next_transaction_index(multisig_data: &[u8]) -> Result<u64> {
+    let Some(index_bytes) =
+        multisig_data.get(TRANSACTION_INDEX_OFFSET..TRANSACTION_INDEX_OFFSET + 8)
+    else {
+        bail!("Multisig account data too short");
+    };
+    let transaction_index = u64::from_le_bytes(index_bytes.try_into().unwrap());
+
+    Ok(transaction_index + 1)
+}
+
+/// Serialize `instructions` as the Squads transaction message executed by `vault_key`.
+///
+/// The message is a compiled legacy message with compact (u8) lengths, except for instruction
+/// data which has a u16 length, and without address table lookups.
+fn try_serialize_vault_message(
+    vault_key: &Pubkey,
+    instructions: &[Instruction],
+) -> Result<Vec<u8>> {
+    let message = Message::new(instructions, Some(vault_key));
+    let header = message.header;
+    let num_keys = message.account_keys.len();
+
+    let mut data = vec![
+        header.num_required_signatures,
+        header.num_required_signatures - header.num_readonly_signed_accounts,
+        (num_keys
+            - usize::from(header.num_required_signatures)
+            - usize::from(header.num_readonly_unsigned_accounts)) as u8,
+    ];
+
+    data.push(try_u8_len(num_keys, "accounts")?);
+    for key in &message.account_keys {
+        data.extend_from_slice(key.as_ref());
+    }
+
+    data.push(try_u8_len(message.instructions.len(), "instructions")?);
+    for ix in &message.instructions {
+        data.push(ix.program_id_index);
+        data.push(try_u8_len(ix.accounts.len(), "instruction accounts")?);
+        data.extend_from_slice(&ix.accounts);
+        let Ok(data_len) = u16::try_from(ix.data.len()) else {
+            bail!("Instruction data too large for a multisig proposal");
+        };
+        data.extend_from_slice(&data_len.to_le_bytes());
+        data.extend_from_slice(&ix.data);
+    }
+
+    // No address table lookups.
+    data.push(0);
+
+    Ok(data)
+}
+
+fn try_u8_len(len: usize, what: &str) -> Result<u8> {
+    u8::try_from(len).map_err(|_| anyhow!("Too many {what} for a multisig proposal"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MULTISIG_KEY: Pubkey = Pubkey::new_from_array([1; 32]);
+
+    #[test]
+    fn test_anchor_discriminators() {
+        // Pinned to the Squads v4 IDL.
+        assert_eq!(
+            anchor_discriminator("vault_transaction_create"),
+            [48, 250, 78, 168, 208, 226, 218, 211]
+        );
+        assert_eq!(
+            anchor_discriminator("proposal_create"),
+            [220, 60, 73, 224, 30, 108, 79, 159]
+        );
+    }
+
+    #[test]
+    fn test_derived_addresses() {
+        let vault = SquadsVault::new(MULTISIG_KEY, 0);
+        assert_eq!(
+            vault.key,
+            pubkey!("5AqyarvEEwCuy891CxFLuQjqH6H8i4hc3d4rpyTcXHci")
+        );
+        assert_eq!(
+            SquadsVault::new(MULTISIG_KEY, 1).key,
+            pubkey!("AyVRqQKowVtNWVZ4j5wVM34qBRtW5fpx1HmgGxSDVuj4")
+        );
+
+        let proposal = SquadsProposal::new(&vault, 42);
+        assert_eq!(
+            proposal.transaction_key,
+            pubkey!("ExZZRuza1gpf4UAi3QrAzC239cGAC7XsWqsKm7MQvnGo")
+        );
+        assert_eq!(
+            proposal.proposal_key,
+            pubkey!("5zJoT1R5ch64nfDyUb1uhFsV86Kc8VVFKXiFgwQp6mji")
+        );
+    }
+
+    #[test]
+    fn test_serialize_vault_message() {
+        let vault_key = SquadsVault::new(MULTISIG_KEY, 0).key;
+        let writable_key = Pubkey::new_from_array([2; 32]);
+        let readonly_key = Pubkey::new_from_array([3; 32]);
+        let program_id = Pubkey::new_from_array([4; 32]);
+
+        let instructions = [Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(readonly_key, false),
+                AccountMeta::new(writable_key, false),
+                AccountMeta::new(vault_key, true),
+            ],
+            data: vec![7, 8, 9],
+        }];
+
+        // Header: one signer, writable, and one writable non-signer. Keys are ordered vault,
+        // writable, read-only, then the program.
+        let mut expected = vec![1, 1, 1, 4];
+        for key in [vault_key, writable_key, readonly_key, program_id] {
+            expected.extend_from_slice(key.as_ref());
+        }
+        // One instruction: program index, account indexes, u16 data length and data.
+        expected.extend_from_slice(&[1, 3, 3, 2, 1, 0, 3, 0, 7, 8, 9]);
+        // No address table lookups.
+        expected.push(0);
+
+        let message = try_serialize_vault_message(&vault_key, &instructions).unwrap();
+        assert_eq!(message, expected);
+
+        let creator_key = Pubkey::new_unique();
+        let proposal = SquadsProposal::new(&SquadsVault::new(MULTISIG_KEY, 0), 42);
+        let ix = proposal
+            .try_vault_transaction_create_ix(&creator_key, &instructions)
+            .unwrap();
+
+        // Discriminator, vault index, no ephemeral signers, message and no memo.
+        let mut expected_data = vec![48, 250, 78, 168, 208, 226, 218, 211, 0, 0];
+        expected_data.extend_from_slice(&(expected.len() as u32).to_le_bytes());
+        expected_data.extend_from_slice(&expected);
+        expected_data.push(0);
+        assert_eq!(ix.data, expected_data);
+        assert_eq!(ix.accounts[1].pubkey, proposal.transaction_key);
+
+        let ix = proposal.proposal_create_ix(&creator_key);
+        assert_eq!(
+            ix.data,
+            [
+                220, 60, 73, 224, 30, 108, 79, 159, 42, 0, 0, 0, 0, 0, 0, 0, 0
+            ]
+        );
+    }
+
+    #[test]
+    fn test_next_transaction_index() {
+        // Multisig account: discriminator, create key, config authority, threshold, time lock,
+        // transaction index, stale transaction index, rent collector, bump and members.
+        let mut data = vec![0; 8];
+        data.extend_from_slice(&[5; 32]);
+        data.extend_from_slice(&[0; 32]);
+        data.extend_from_slice(&2_u16.to_le_bytes());
+        data.extend_from_slice(&0_u32.to_le_bytes());
+        data.extend_from_slice(&41_u64.to_le_bytes());
+        data.extend_from_slice(&40_u64.to_le_bytes());
+        data.push(0);
+        data.push(255);
+        data.extend_from_slice(&0_u32.to_le_bytes());
+
+        assert_eq!(try_next_transaction_index(&data).unwrap(), 42);
+        assert!(try_next_transaction_index(&data[..TRANSACTION_INDEX_OFFSET + 7]).is_err());
+    }
+}
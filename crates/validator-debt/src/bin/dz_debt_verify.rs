@@ -0,0 +1,57 @@
+use anyhow::{Result, bail};
+use clap::Parser;
+use doublezero_solana_validator_debt::{
+    rpc::SolanaValidatorDebtConnectionOptions,
+    solana_debt_calculator::SolanaDebtCalculator,
+    verify::{Verdict, verify_validator_debt},
+};
+use solana_sdk::pubkey::Pubkey;
+use tracing_subscriber::{EnvFilter, layer::SubscriberExt, util::SubscriberInitExt};
+
+/// Check the debt DoubleZero charged a validator against public reward data. No keypair is
+/// required.
+#[derive(Debug, Parser)]
+#[command(term_width = 0)]
+#[command(version = option_env!("BUILD_VERSION").unwrap_or(env!("CARGO_PKG_VERSION")))]
+#[command(about = "Verify DoubleZero validator debt from public data", long_about = None)]
+struct DebtVerifyApp {
+    /// DoubleZero epoch the debt was charged for.
+    #[arg(long)]
+    epoch: u64,
+
+    /// Validator identity (node id) to verify.
+    #[arg(long, value_name = "PUBKEY")]
+    node_id: Pubkey,
+
+    #[command(flatten)]
+    solana_connection_options: SolanaValidatorDebtConnectionOptions,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::registry()
+        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn")))
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_target(false)
+                .with_thread_ids(false)
+                .with_thread_names(false),
+        )
+        .init();
+
+    let DebtVerifyApp {
+        epoch,
+        node_id,
+        solana_connection_options,
+    } = DebtVerifyApp::parse();
+
+    let solana_debt_calculator = SolanaDebtCalculator::try_from(solana_connection_options)?;
+    let verification = verify_validator_debt(&solana_debt_calculator, epoch, node_id).await?;
+    println!("{verification}");
+
+    if let Verdict::Mismatched(reason) = verification.verdict() {
+        bail!("Validator debt does not match public data: {reason}");
+    }
+
+    Ok(())
+}
@@ -8,6 +8,13 @@ use std::{collections::HashMap, time::Duration};
 use tracing::info;
 
 pub const LAMPORT_MULTIPLE: u64 = 5000;
+
+/// Validators whose leader slots are fetched at once
+pub const VALIDATOR_FETCH_CONCURRENCY: usize = 4;
+
+/// Block requests in flight at once for each validator
+pub const BLOCK_FETCH_CONCURRENCY: usize = 5;
+
 pub const fn get_first_slot_for_epoch(target_epoch: u64) -> u64 {
     DEFAULT_SLOTS_PER_EPOCH * target_epoch
 }
@@ -44,90 +51,97 @@ pub async fn get_block_rewards<T: ValidatorRewards>(
         })
         .collect();
 
-    let block_rewards = stream::iter(validator_schedules.into_iter().flat_map(
-        |(validator_id, slots)| {
-            println!("getting block rewards for {}", validator_id.clone());
-            slots
-                .into_iter()
-                .map(move |slot| (validator_id.clone(), slot))
-        },
-    ))
-    .map(|(validator_id, slot)| async move {
-        println!("getting blocks for {}", validator_id.clone());
-        match (|| async { api_provider.get_block_with_config(slot).await })
-            .retry(
-                &ExponentialBuilder::default()
-                    .with_max_times(5)
-                    .with_min_delay(Duration::from_millis(100))
-                    .with_max_delay(Duration::from_secs(10))
-                    .with_jitter(),
-            )
-            .when(|err| {
-                match err.kind() {
-                    ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => {
-                        // Don't retry if block isn't found
-                        !matches!(*code, -32009 | -32007)
-                    }
-                    _ => true, // Retry on all other errors
+    // Validators are fetched a few at a time, each with a bounded number of its leader slots in
+    // flight. Each validator's slots are summed before its entry is inserted, so the totals do
+    // not depend on the order requests complete in
+    let block_rewards = stream::iter(validator_schedules)
+        .map(|(validator_id, slots)| async move {
+            println!("getting block rewards for {validator_id}");
+            let rewards = stream::iter(slots)
+                .map(|slot| get_slot_rewards(api_provider, slot))
+                .buffer_unordered(BLOCK_FETCH_CONCURRENCY)
+                .try_fold(
+                    (0, 0),
+                    |(base, priority), (slot_base, slot_priority)| async move {
+                        Ok((base + slot_base, priority + slot_priority))
+                    },
+                )
+                .await?;
+            Ok::<_, anyhow::Error>((validator_id, rewards))
+        })
+        .buffer_unordered(VALIDATOR_FETCH_CONCURRENCY)
+        .try_collect::<HashMap<String, (u64, u64)>>()
+        .await?;
+
+    Ok(block_rewards)
+}
+
+/// Base and priority fee rewards of the block produced in `slot`, zero if it was skipped
+async fn get_slot_rewards<T: ValidatorRewards>(api_provider: &T, slot: u64) -> Result<(u64, u64)> {
+    match (|| async { api_provider.get_block_with_config(slot).await })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_max_times(5)
+                .with_min_delay(Duration::from_millis(100))
+                .with_max_delay(Duration::from_secs(10))
+                .with_jitter(),
+        )
+        .when(|err| {
+            match err.kind() {
+                ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => {
+                    // Don't retry if block isn't found
+                    !matches!(*code, -32009 | -32007)
                 }
-            })
-            .notify(|err, dur: Duration| {
-                info!(
-                    "get_block_with_config call failed, retrying in {:?}: {}",
-                    dur, err
-                );
-            })
-            .await
-        {
-            Ok(block) => {
-                let mut signature_lamports: u64 = 0;
-                if let Some(sigs) = &block.signatures {
-                    signature_lamports = sigs.len() as u64;
-                    signature_lamports *= 2500;
-                };
-                let lamports: u64 = block
-                    .rewards
-                    .as_ref()
-                    .map(|rewards| {
-                        rewards
-                            .iter()
-                            .filter_map(|reward| {
-                                if reward.reward_type == Some(Fee) {
-                                    Some(reward.lamports as u64)
-                                } else {
-                                    None
-                                }
-                            })
-                            .sum()
-                    })
-                    .ok_or_else(|| anyhow::anyhow!("no block rewards"))?;
-                Ok((
-                    validator_id,
-                    (signature_lamports, lamports - signature_lamports),
-                ))
+                _ => true, // Retry on all other errors
             }
+        })
+        .notify(|err, dur: Duration| {
+            info!(
+                "get_block_with_config call failed, retrying in {:?}: {}",
+                dur, err
+            );
+        })
+        .await
+    {
+        Ok(block) => {
+            let mut signature_lamports: u64 = 0;
+            if let Some(sigs) = &block.signatures {
+                signature_lamports = sigs.len() as u64;
+                signature_lamports *= 2500;
+            };
+            let lamports: u64 = block
+                .rewards
+                .as_ref()
+                .map(|rewards| {
+                    rewards
+                        .iter()
+                        .filter_map(|reward| {
+                            if reward.reward_type == Some(Fee) {
+                                Some(reward.lamports as u64)
+                            } else {
+                                None
+                            }
+                        })
+                        .sum()
+                })
+                .ok_or_else(|| anyhow::anyhow!("no block rewards"))?;
+            Ok((signature_lamports, lamports - signature_lamports))
+        }
 
-            Err(e) => {
-                if let ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) = e.kind()
-                {
-                    // -32_009 -  Slot x was skipped, or missing in long-term storage
-                    // -32_007 -  Requested block or slot does not exist
-                    if *code == -32_009 || *code == -32_007 {
-                        Ok((validator_id, (0, 0)))
-                    } else {
-                        bail!("Failed to fetch block for slot {slot}: {e}")
-                    }
+        Err(e) => {
+            if let ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) = e.kind() {
+                // -32_009 -  Slot x was skipped, or missing in long-term storage
+                // -32_007 -  Requested block or slot does not exist
+                if *code == -32_009 || *code == -32_007 {
+                    Ok((0, 0))
                 } else {
                     bail!("Failed to fetch block for slot {slot}: {e}")
                 }
+            } else {
+                bail!("Failed to fetch block for slot {slot}: {e}")
             }
         }
-    })
-    .buffer_unordered(20)
-    .try_collect::<HashMap<String, (u64, u64)>>()
-    .await?;
-
-    Ok(block_rewards)
+    }
 }
 
 #[cfg(test)]
@@ -203,4 +217,66 @@ mod tests {
         assert_eq!(base_rewards.0, block_reward.0 as u64);
         assert_eq!(base_rewards.1, block_reward.1);
     }
+
+    #[tokio::test]
+    async fn test_get_block_rewards_sums_all_leader_slots() {
+        let mut mock_api_provider = MockValidatorRewards::new();
+        let validator_id = "some_validator_pubkey".to_string();
+        let validator_ids = std::slice::from_ref(&validator_id);
+        let epoch = 100;
+
+        let mut leader_schedule = HashMap::new();
+        leader_schedule.insert(validator_id.clone(), vec![10, 11, 12]);
+
+        mock_api_provider
+            .expect_get_leader_schedule()
+            .times(1)
+            .returning(move || Ok(leader_schedule.clone()));
+
+        let mock_block = UiConfirmedBlock {
+            num_reward_partitions: Some(1),
+            signatures: Some(vec!["One".to_string(), "two".to_string()]),
+            rewards: Some(vec![Reward {
+                pubkey: validator_id.clone(),
+                lamports: 8000,
+                post_balance: 10000,
+                reward_type: Some(Fee),
+                commission: None,
+            }]),
+            previous_blockhash: "".to_string(),
+            blockhash: "".to_string(),
+            parent_slot: 0,
+            transactions: None,
+            block_time: None,
+            block_height: None,
+        };
+
+        let mock_epoch_info = EpochInfo {
+            epoch: 101,
+            slot_index: 1000,
+            absolute_slot: 100000,
+            block_height: 1030303,
+            slots_in_epoch: 4000,
+            transaction_count: Some(1000),
+        };
+
+        mock_api_provider
+            .expect_get_epoch_info()
+            .times(1)
+            .returning(move || Ok(mock_epoch_info.clone()));
+
+        mock_api_provider
+            .expect_get_block_with_config()
+            .times(3)
+            .returning(move |_| Ok(mock_block.clone()));
+
+        let rewards = get_block_rewards(&mock_api_provider, validator_ids, epoch)
+            .await
+            .unwrap();
+
+        let (base, priority) = rewards.get(&validator_id).unwrap();
+
+        assert_eq!(*base, 3 * 5000);
+        assert_eq!(*priority, 3 * 3000);
+    }
 }
@@ -1,4 +1,4 @@
-use anyhow::{Result, anyhow};
+use anyhow::{Result, anyhow, bail};
 use clap::Args;
 use doublezero_revenue_distribution::state::ProgramConfig;
 use doublezero_scheduled_command::{Schedulable, ScheduleOption};
@@ -13,9 +13,12 @@ use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::commitment_config::CommitmentConfig;
 
 use crate::{
+    inflation::InflationCommission,
     rpc::{JoinedSolanaEpochs, SolanaValidatorDebtConnectionOptions},
+    sanity::{DEFAULT_SANITY_TOLERANCE, SanityCheckConfig},
     solana_debt_calculator::SolanaDebtCalculator,
     transaction::Transaction,
+    waiver::DebtThresholds,
 };
 
 #[derive(Debug, Args, Clone)]
@@ -35,6 +38,41 @@ pub struct CalculateValidatorDebtCommand {
     /// Option to post validator debt only to the DoubleZero Ledger
     #[arg(long)]
     post_to_ledger_only: bool,
+
+    /// Flag validators whose debt is more than this factor above or below the
+    /// expectation derived from their activated stake and epoch credits. Stake
+    /// is read at the time of the check and credits come from the last Solana
+    /// epoch the debt covers, so the expectation drifts when recomputing older
+    /// epochs
+    #[arg(long, default_value_t = DEFAULT_SANITY_TOLERANCE)]
+    sanity_tolerance: f64,
+
+    /// Refuse to submit debt when any validator is flagged by the sanity check,
+    /// unless --force is given. Since the check uses current stake, valid debt
+    /// can be flagged after stake has moved
+    #[arg(long)]
+    block_on_sanity_failure: bool,
+
+    /// Charge inflation rewards before ("pre") or after ("post") the
+    /// validator's commission. The choice is stored in the debt record.
+    #[arg(long, value_enum, default_value_t = InflationCommission::PostCommission)]
+    inflation_commission: InflationCommission,
+
+    /// Waive validator debts below this amount (in lamports), recording them
+    /// as waived in the debt record. Zero waives nothing.
+    #[arg(long, value_name = "LAMPORTS", default_value_t = 0)]
+    min_validator_debt: u64,
+
+    /// Waive all debts and finalize the distribution without payments when
+    /// the total debt is below this amount (in lamports). Zero disables it.
+    #[arg(long, value_name = "LAMPORTS", default_value_t = 0)]
+    min_total_debt: u64,
+
+    /// Recompute debt for a distribution already finalized on Solana, writing
+    /// the result to a separate audit record instead of the canonical one.
+    /// Nothing is submitted to Solana.
+    #[arg(long)]
+    post_finalization_audit: bool,
 }
 
 #[async_trait::async_trait]
@@ -50,10 +88,20 @@ impl Schedulable for CalculateValidatorDebtCommand {
             solana_payer_options,
             dz_ledger_connection_options,
             post_to_ledger_only,
+            sanity_tolerance,
+            block_on_sanity_failure,
+            inflation_commission,
+            min_validator_debt,
+            min_total_debt,
+            post_finalization_audit,
         } = self;
 
         schedule_or_force.ensure_safe_execution()?;
 
+        if !(sanity_tolerance.is_finite() && *sanity_tolerance >= 1.0) {
+            bail!("--sanity-tolerance must be at least 1.0, got {sanity_tolerance}");
+        }
+
         let epoch = match epoch {
             Some(e) => *e,
             None => {
@@ -82,6 +130,16 @@ impl Schedulable for CalculateValidatorDebtCommand {
             transaction,
             epoch,
             *post_to_ledger_only,
+            SanityCheckConfig {
+                tolerance: *sanity_tolerance,
+                block_submission: *block_on_sanity_failure && !schedule_or_force.force,
+            },
+            *inflation_commission,
+            DebtThresholds {
+                min_validator_debt: *min_validator_debt,
+                min_total_debt: *min_total_debt,
+            },
+            *post_finalization_audit,
         )
         .await?;
 
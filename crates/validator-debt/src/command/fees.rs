@@ -0,0 +1,100 @@
+use std::path::PathBuf;
+
+use anyhow::{Result, ensure};
+use clap::{Args, Subcommand};
+use doublezero_solana_client_tools::log_info;
+use tabled::{Table, settings::Style};
+
+use crate::{
+    fee_history, rpc::SolanaValidatorDebtConnectionOptions,
+    solana_debt_calculator::SolanaDebtCalculator,
+};
+
+#[derive(Debug, Args)]
+pub struct FeesCommand {
+    #[command(subcommand)]
+    pub command: FeesSubcommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum FeesSubcommand {
+    /// Show the validator fee parameters of each distribution over a range of
+    /// DZ epochs.
+    History(FeeHistoryCommand),
+}
+
+impl FeesSubcommand {
+    pub async fn execute(self) -> Result<()> {
+        match self {
+            Self::History(command) => command.execute().await,
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct FeeHistoryCommand {
+    /// First DZ epoch to include.
+    #[arg(long)]
+    from_epoch: u64,
+
+    /// Last DZ epoch to include.
+    #[arg(long)]
+    to_epoch: u64,
+
+    /// Write the timeline as CSV to this file instead of printing a table.
+    #[arg(long, value_name = "PATH")]
+    csv: Option<PathBuf>,
+
+    #[command(flatten)]
+    solana_connection_options: SolanaValidatorDebtConnectionOptions,
+}
+
+impl FeeHistoryCommand {
+    pub async fn execute(self) -> Result<()> {
+        ensure!(
+            self.from_epoch <= self.to_epoch,
+            "--from-epoch {} is after --to-epoch {}",
+            self.from_epoch,
+            self.to_epoch
+        );
+
+        let solana_debt_calculator =
+            SolanaDebtCalculator::try_from(self.solana_connection_options)?;
+        let entries = fee_history::fetch_fee_history(
+            &solana_debt_calculator.solana_rpc_client,
+            self.from_epoch,
+            self.to_epoch,
+        )
+        .await?;
+
+        if let Some(path) = self.csv {
+            let mut writer = csv::Writer::from_path(&path)?;
+            for entry in &entries {
+                writer.serialize(entry)?;
+            }
+            writer.flush()?;
+
+            log_info!(
+                "Wrote fee parameters of {} distributions to {}",
+                entries.len(),
+                path.display()
+            );
+            return Ok(());
+        }
+
+        if entries.is_empty() {
+            println!(
+                "No distributions found for DZ epochs {} through {}",
+                self.from_epoch, self.to_epoch
+            );
+            return Ok(());
+        }
+
+        println!(
+            "{}",
+            Table::new(&entries).with(Style::psql().remove_horizontals())
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Args;
+use doublezero_solana_client_tools::log_info;
+use tabled::{Table, settings::Style};
+
+use crate::{
+    fixtures::RewardsFixture, inflation::InflationCommission,
+    rpc::SolanaValidatorDebtConnectionOptions, solana_debt_calculator::SolanaDebtCalculator,
+    worker::fetch_validator_pubkeys,
+};
+
+#[derive(Debug, Args)]
+pub struct RecordRewardsFixtureCommand {
+    /// Solana epoch to calculate rewards for.
+    #[arg(long)]
+    epoch: u64,
+
+    /// File to write the recorded responses and rewards to.
+    #[arg(long, value_name = "PATH")]
+    output: PathBuf,
+
+    /// Whether inflation rewards are taken before or after the validator's
+    /// commission.
+    #[arg(long, value_enum, default_value_t = InflationCommission::PostCommission)]
+    inflation_commission: InflationCommission,
+
+    #[command(flatten)]
+    solana_connection_options: SolanaValidatorDebtConnectionOptions,
+}
+
+impl RecordRewardsFixtureCommand {
+    pub async fn execute(self) -> Result<()> {
+        let solana_debt_calculator =
+            SolanaDebtCalculator::try_from(self.solana_connection_options)?;
+        let validator_ids =
+            fetch_validator_pubkeys(&solana_debt_calculator.ledger_rpc_client).await?;
+
+        let fixture = RewardsFixture::record(
+            solana_debt_calculator,
+            &validator_ids,
+            self.epoch,
+            self.inflation_commission,
+        )
+        .await?;
+        fixture.save(&self.output)?;
+
+        log_info!(
+            "Recorded rewards for {} validators in Solana epoch {} to {}",
+            fixture.rewards.len(),
+            self.epoch,
+            self.output.display()
+        );
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct ReplayRewardsFixtureCommand {
+    /// Fixture written by record-rewards-fixture.
+    #[arg(long, value_name = "PATH")]
+    fixture: PathBuf,
+}
+
+impl ReplayRewardsFixtureCommand {
+    pub async fn execute(self) -> Result<()> {
+        let fixture = RewardsFixture::load(&self.fixture)?;
+        let rewards = fixture.verify().await?;
+
+        println!(
+            "Replayed rewards for Solana epoch {} match the recording:\n{}",
+            fixture.solana_epoch,
+            Table::new(rewards).with(Style::psql().remove_horizontals())
+        );
+
+        Ok(())
+    }
+}
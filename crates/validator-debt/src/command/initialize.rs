@@ -18,6 +18,8 @@ use solana_sdk::{
     commitment_config::CommitmentConfig, compute_budget::ComputeBudgetInstruction, signer::Signer,
 };
 
+use crate::notify::{Milestone, Notifier};
+
 #[derive(Debug, Args, Clone)]
 pub struct InitializeDistributionCommand {
     #[command(flatten)]
@@ -136,6 +138,13 @@ impl Schedulable for InitializeDistributionCommand {
         if let Some(tx_sig) = tx_sig {
             log_info!("Initialize distribution: {tx_sig}");
 
+            Notifier::from_env()
+                .notify(Milestone::DistributionInitialized {
+                    dz_epoch: next_dz_epoch.value(),
+                    signature: tx_sig.to_string(),
+                })
+                .await;
+
             wallet.print_verbose_output(&[tx_sig]).await?;
         }
 
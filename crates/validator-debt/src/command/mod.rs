@@ -1,4 +1,6 @@
 mod calculate;
+mod fees;
+mod fixtures;
 mod initialize;
 
 //
@@ -32,6 +34,15 @@ pub enum ValidatorDebtCommand {
 
     FindSolanaEpoch(calculate::FindSolanaEpochCommand),
 
+    /// Record the RPC responses of a rewards calculation to a fixture file.
+    RecordRewardsFixture(fixtures::RecordRewardsFixtureCommand),
+
+    /// Re-run a recorded rewards calculation offline and check the result.
+    ReplayRewardsFixture(fixtures::ReplayRewardsFixtureCommand),
+
+    /// Validator fee parameter commands.
+    Fees(fees::FeesCommand),
+
     /// Finalize Epoch Transaction.
     FinalizeTransaction {
         #[command(flatten)]
@@ -51,6 +62,9 @@ impl ValidatorDebtCommand {
             ValidatorDebtCommand::InitializeDistribution(command) => command.execute().await,
             ValidatorDebtCommand::CalculateValidatorDebt(command) => command.execute().await,
             ValidatorDebtCommand::FindSolanaEpoch(command) => command.execute().await,
+            ValidatorDebtCommand::RecordRewardsFixture(command) => command.execute().await,
+            ValidatorDebtCommand::ReplayRewardsFixture(command) => command.execute().await,
+            ValidatorDebtCommand::Fees(command) => command.command.execute().await,
             ValidatorDebtCommand::FinalizeTransaction {
                 solana_connection_options,
                 epoch,
@@ -0,0 +1,194 @@
+use anyhow::{Result, anyhow};
+use borsh::{BorshDeserialize, BorshSerialize};
+use doublezero_program_tools::zero_copy;
+use doublezero_revenue_distribution::{state::Distribution, types::DoubleZeroEpoch};
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use tabled::Tabled;
+
+use crate::rpc::MAX_ACCOUNTS_PER_REQUEST;
+
+/// Validator fee parameters a debt was computed with, in basis points.
+#[derive(
+    Debug,
+    Default,
+    BorshDeserialize,
+    BorshSerialize,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+)]
+pub struct DebtFeeParameters {
+    pub base_block_rewards_bps: u16,
+    pub priority_block_rewards_bps: u16,
+    pub inflation_rewards_bps: u16,
+    pub jito_tips_bps: u16,
+    pub fixed_sol_amount: u64,
+}
+
+impl DebtFeeParameters {
+    pub fn from_distribution(distribution: &Distribution) -> Self {
+        let fee_parameters = &distribution.solana_validator_fee_parameters;
+        Self {
+            base_block_rewards_bps: u16::from(fee_parameters.base_block_rewards_pct),
+            priority_block_rewards_bps: u16::from(fee_parameters.priority_block_rewards_pct),
+            inflation_rewards_bps: u16::from(fee_parameters.inflation_rewards_pct),
+            jito_tips_bps: u16::from(fee_parameters.jito_tips_pct),
+            fixed_sol_amount: fee_parameters.fixed_sol_amount as u64,
+        }
+    }
+}
+
+/// Fee parameters of one DoubleZero epoch's distribution.
+#[derive(Debug, Clone, PartialEq, Serialize, Tabled)]
+pub struct FeeHistoryEntry {
+    pub dz_epoch: u64,
+    #[tabled(display = "display_bps", rename = "base_block_rewards")]
+    pub base_block_rewards_bps: u16,
+    #[tabled(display = "display_bps", rename = "priority_block_rewards")]
+    pub priority_block_rewards_bps: u16,
+    #[tabled(display = "display_bps", rename = "inflation_rewards")]
+    pub inflation_rewards_bps: u16,
+    #[tabled(display = "display_bps", rename = "jito_tips")]
+    pub jito_tips_bps: u16,
+    pub fixed_sol_amount: u64,
+    /// Community burn rate in units of 1e-7 percent.
+    #[tabled(display = "display_burn_rate", rename = "community_burn_rate")]
+    pub community_burn_rate: u32,
+    /// Whether any fee parameter differs from the previous entry.
+    pub changed: bool,
+}
+
+impl FeeHistoryEntry {
+    pub fn new(dz_epoch: u64, distribution: &Distribution) -> Self {
+        let fee_parameters = DebtFeeParameters::from_distribution(distribution);
+        Self {
+            dz_epoch,
+            base_block_rewards_bps: fee_parameters.base_block_rewards_bps,
+            priority_block_rewards_bps: fee_parameters.priority_block_rewards_bps,
+            inflation_rewards_bps: fee_parameters.inflation_rewards_bps,
+            jito_tips_bps: fee_parameters.jito_tips_bps,
+            fixed_sol_amount: fee_parameters.fixed_sol_amount,
+            community_burn_rate: u32::from(distribution.community_burn_rate),
+            changed: false,
+        }
+    }
+
+    fn same_parameters(&self, other: &Self) -> bool {
+        (
+            self.base_block_rewards_bps,
+            self.priority_block_rewards_bps,
+            self.inflation_rewards_bps,
+            self.jito_tips_bps,
+            self.fixed_sol_amount,
+            self.community_burn_rate,
+        ) == (
+            other.base_block_rewards_bps,
+            other.priority_block_rewards_bps,
+            other.inflation_rewards_bps,
+            other.jito_tips_bps,
+            other.fixed_sol_amount,
+            other.community_burn_rate,
+        )
+    }
+}
+
+fn display_bps(bps: &u16) -> String {
+    format!("{:.2}%", *bps as f64 / 100.0)
+}
+
+fn display_burn_rate(rate: &u32) -> String {
+    format!("{:.7}%", *rate as f64 / 10_000_000.0)
+}
+
+/// Flag every entry whose parameters differ from the entry before it. The
+/// first entry is never flagged since there is nothing to compare it to.
+pub fn mark_changes(entries: &mut [FeeHistoryEntry]) {
+    for i in 1..entries.len() {
+        let changed = !entries[i].same_parameters(&entries[i - 1]);
+        entries[i].changed = changed;
+    }
+}
+
+/// Fee parameters of every distribution from `from_dz_epoch` through
+/// `to_dz_epoch`. Epochs without a distribution account are skipped.
+pub async fn fetch_fee_history(
+    rpc_client: &RpcClient,
+    from_dz_epoch: u64,
+    to_dz_epoch: u64,
+) -> Result<Vec<FeeHistoryEntry>> {
+    let dz_epochs = (from_dz_epoch..=to_dz_epoch).collect::<Vec<_>>();
+
+    let mut entries = Vec::with_capacity(dz_epochs.len());
+    for chunk in dz_epochs.chunks(MAX_ACCOUNTS_PER_REQUEST) {
+        let keys = chunk
+            .iter()
+            .map(|dz_epoch| Distribution::find_address(DoubleZeroEpoch::new(*dz_epoch)).0)
+            .collect::<Vec<_>>();
+        let accounts = rpc_client.get_multiple_accounts(&keys).await?;
+
+        for (dz_epoch, account) in chunk.iter().zip(accounts) {
+            let Some(account) = account else {
+                continue;
+            };
+            let (distribution, _) =
+                zero_copy::checked_from_bytes_with_discriminator::<Distribution>(&account.data)
+                    .ok_or_else(|| {
+                        anyhow!("Failed to deserialize distribution for DZ epoch {dz_epoch}")
+                    })?;
+            entries.push(FeeHistoryEntry::new(*dz_epoch, distribution));
+        }
+    }
+
+    mark_changes(&mut entries);
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(dz_epoch: u64, base_block_rewards_bps: u16, fixed_sol_amount: u64) -> FeeHistoryEntry {
+        FeeHistoryEntry {
+            dz_epoch,
+            base_block_rewards_bps,
+            priority_block_rewards_bps: 500,
+            inflation_rewards_bps: 0,
+            jito_tips_bps: 500,
+            fixed_sol_amount,
+            community_burn_rate: 10_000_000,
+            changed: false,
+        }
+    }
+
+    #[test]
+    fn test_mark_changes() {
+        let mut entries = vec![
+            entry(10, 500, 0),
+            entry(11, 500, 0),
+            entry(13, 600, 0),
+            entry(14, 600, 0),
+            entry(15, 600, 5_000),
+        ];
+        mark_changes(&mut entries);
+
+        let changed = entries
+            .iter()
+            .map(|entry| (entry.dz_epoch, entry.changed))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            changed,
+            vec![
+                (10, false),
+                (11, false),
+                (13, true),
+                (14, false),
+                (15, true)
+            ]
+        );
+    }
+}
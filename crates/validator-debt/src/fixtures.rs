@@ -0,0 +1,493 @@
+//! Record/replay of the RPC responses a rewards calculation depends on
+//!
+//! `RecordingRewards` wraps a live `ValidatorRewards` provider and keeps every response
+//! it returns. The captured responses, together with the epoch, validator set and
+//! computed rewards, are written to a fixture file that `ReplayRewards` serves back
+//! offline, so the debt math can be re-run deterministically against real data.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    error::Error,
+    fs,
+    path::Path,
+    sync::Mutex,
+};
+
+use anyhow::{Context, Result, anyhow, ensure};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind},
+    nonblocking::rpc_client::RpcClient,
+    rpc_custom_error::{
+        JSON_RPC_SERVER_ERROR_LONG_TERM_STORAGE_SLOT_SKIPPED, JSON_RPC_SERVER_ERROR_SLOT_SKIPPED,
+    },
+    rpc_request::{RpcError, RpcResponseErrorData},
+    rpc_response::{RpcInflationReward, RpcVoteAccountStatus},
+};
+use solana_sdk::{commitment_config::CommitmentConfig, epoch_info::EpochInfo, pubkey::Pubkey};
+use solana_transaction_status_client_types::UiConfirmedBlock;
+
+use crate::{
+    inflation::InflationCommission,
+    rewards::{self, Reward},
+    solana_debt_calculator::ValidatorRewards,
+};
+
+/// RPC and HTTP responses captured during a run
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct RpcFixtures {
+    pub epoch_info: Option<EpochInfo>,
+    pub leader_schedule: Option<HashMap<String, Vec<usize>>>,
+    pub blocks: BTreeMap<u64, UiConfirmedBlock>,
+    /// Slots the RPC reported as skipped or missing
+    pub skipped_slots: BTreeSet<u64>,
+    /// JSON bodies of HTTP requests (Jito), keyed by URL
+    pub http: BTreeMap<String, serde_json::Value>,
+    pub vote_accounts: Option<RpcVoteAccountStatus>,
+    /// Inflation rewards by epoch, then vote account
+    pub inflation_rewards: BTreeMap<u64, BTreeMap<String, Option<RpcInflationReward>>>,
+    pub slot: Option<u64>,
+    pub block_times: BTreeMap<u64, i64>,
+}
+
+/// A recorded rewards calculation: its inputs, the responses it saw and its result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RewardsFixture {
+    pub solana_epoch: u64,
+    pub validator_ids: Vec<String>,
+    /// Missing in fixtures recorded before the commission basis was configurable
+    #[serde(default)]
+    pub inflation_commission: InflationCommission,
+    pub responses: RpcFixtures,
+    pub rewards: Vec<Reward>,
+}
+
+impl RewardsFixture {
+    /// Run the rewards calculation against `provider`, recording every response
+    pub async fn record<T: ValidatorRewards + Send + Sync>(
+        provider: T,
+        validator_ids: &[String],
+        solana_epoch: u64,
+        inflation_commission: InflationCommission,
+    ) -> Result<Self> {
+        let recorder = RecordingRewards::new(provider);
+        let epoch_rewards = rewards::get_total_rewards(
+            &recorder,
+            validator_ids,
+            solana_epoch,
+            inflation_commission,
+        )
+        .await?;
+
+        Ok(Self {
+            solana_epoch,
+            validator_ids: validator_ids.to_vec(),
+            inflation_commission,
+            responses: recorder.into_fixtures(),
+            rewards: epoch_rewards.rewards,
+        })
+    }
+
+    /// Re-run the rewards calculation offline from the recorded responses
+    pub async fn replay(&self) -> Result<Vec<Reward>> {
+        let replayer = ReplayRewards::new(self.responses.clone());
+        let epoch_rewards = rewards::get_total_rewards(
+            &replayer,
+            &self.validator_ids,
+            self.solana_epoch,
+            self.inflation_commission,
+        )
+        .await?;
+        Ok(epoch_rewards.rewards)
+    }
+
+    /// Replay and fail if the result differs from what was recorded
+    pub async fn verify(&self) -> Result<Vec<Reward>> {
+        let replayed = self.replay().await?;
+        ensure!(
+            replayed == self.rewards,
+            "Replayed rewards for Solana epoch {} differ from the recorded rewards",
+            self.solana_epoch
+        );
+        Ok(replayed)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read fixture {}", path.display()))?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse fixture {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write fixture {}", path.display()))
+    }
+}
+
+/// Passes calls through to a live provider and keeps a copy of each response
+pub struct RecordingRewards<T> {
+    inner: T,
+    fixtures: Mutex<RpcFixtures>,
+}
+
+impl<T: ValidatorRewards + Send + Sync> RecordingRewards<T> {
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            fixtures: Mutex::new(RpcFixtures::default()),
+        }
+    }
+
+    pub fn into_fixtures(self) -> RpcFixtures {
+        self.fixtures.into_inner().unwrap()
+    }
+
+    fn record(&self, f: impl FnOnce(&mut RpcFixtures)) {
+        f(&mut self.fixtures.lock().unwrap());
+    }
+}
+
+#[async_trait]
+impl<T: ValidatorRewards + Send + Sync> ValidatorRewards for RecordingRewards<T> {
+    fn solana_rpc_client(&self) -> &RpcClient {
+        self.inner.solana_rpc_client()
+    }
+    fn ledger_rpc_client(&self) -> &RpcClient {
+        self.inner.ledger_rpc_client()
+    }
+    fn solana_commitment_config(&self) -> CommitmentConfig {
+        self.inner.solana_commitment_config()
+    }
+    fn ledger_commitment_config(&self) -> CommitmentConfig {
+        self.inner.ledger_commitment_config()
+    }
+
+    async fn get_epoch_info(&self) -> Result<EpochInfo, ClientError> {
+        let epoch_info = self.inner.get_epoch_info().await?;
+        self.record(|fixtures| fixtures.epoch_info = Some(epoch_info.clone()));
+        Ok(epoch_info)
+    }
+
+    async fn get_leader_schedule(&self) -> Result<HashMap<String, Vec<usize>>> {
+        let schedule = self.inner.get_leader_schedule().await?;
+        self.record(|fixtures| fixtures.leader_schedule = Some(schedule.clone()));
+        Ok(schedule)
+    }
+
+    async fn get_block_with_config(&self, slot: u64) -> Result<UiConfirmedBlock, ClientError> {
+        match self.inner.get_block_with_config(slot).await {
+            Ok(block) => {
+                self.record(|fixtures| {
+                    fixtures.blocks.insert(slot, block.clone());
+                });
+                Ok(block)
+            }
+            Err(err) => {
+                if is_skipped_slot_error(&err) {
+                    self.record(|fixtures| {
+                        fixtures.skipped_slots.insert(slot);
+                    });
+                }
+                Err(err)
+            }
+        }
+    }
+
+    async fn get<U: DeserializeOwned + Send + 'static>(
+        &self,
+        url: &str,
+    ) -> Result<U, Box<dyn Error + Send + Sync>> {
+        // Fetch untyped so the body can be stored as-is
+        let body = self.inner.get::<serde_json::Value>(url).await?;
+        self.record(|fixtures| {
+            fixtures.http.insert(url.to_string(), body.clone());
+        });
+        Ok(serde_json::from_value(body)?)
+    }
+
+    async fn get_vote_accounts_with_config(&self) -> Result<RpcVoteAccountStatus, ClientError> {
+        let vote_accounts = self.inner.get_vote_accounts_with_config().await?;
+        self.record(|fixtures| fixtures.vote_accounts = Some(vote_accounts.clone()));
+        Ok(vote_accounts)
+    }
+
+    async fn get_inflation_reward(
+        &self,
+        vote_keys: Vec<Pubkey>,
+        epoch: u64,
+    ) -> Result<Vec<Option<RpcInflationReward>>, ClientError> {
+        let inflation_rewards = self
+            .inner
+            .get_inflation_reward(vote_keys.clone(), epoch)
+            .await?;
+        self.record(|fixtures| {
+            let recorded = fixtures.inflation_rewards.entry(epoch).or_default();
+            for (vote_key, reward) in vote_keys.iter().zip(&inflation_rewards) {
+                recorded.insert(vote_key.to_string(), reward.clone());
+            }
+        });
+        Ok(inflation_rewards)
+    }
+
+    async fn get_slot(&self) -> Result<u64, ClientError> {
+        let slot = self.inner.get_slot().await?;
+        self.record(|fixtures| fixtures.slot = Some(slot));
+        Ok(slot)
+    }
+
+    async fn get_block_time(&self, slot: u64) -> Result<i64, ClientError> {
+        let block_time = self.inner.get_block_time(slot).await?;
+        self.record(|fixtures| {
+            fixtures.block_times.insert(slot, block_time);
+        });
+        Ok(block_time)
+    }
+}
+
+/// Serves recorded responses; any request that was not recorded fails
+pub struct ReplayRewards {
+    fixtures: RpcFixtures,
+    // Nothing in the rewards calculation talks to the RPC clients directly
+    offline_rpc_client: RpcClient,
+}
+
+impl ReplayRewards {
+    pub fn new(fixtures: RpcFixtures) -> Self {
+        Self {
+            fixtures,
+            offline_rpc_client: RpcClient::new_mock("fails".to_string()),
+        }
+    }
+}
+
+#[async_trait]
+impl ValidatorRewards for ReplayRewards {
+    fn solana_rpc_client(&self) -> &RpcClient {
+        &self.offline_rpc_client
+    }
+    fn ledger_rpc_client(&self) -> &RpcClient {
+        &self.offline_rpc_client
+    }
+    fn solana_commitment_config(&self) -> CommitmentConfig {
+        CommitmentConfig::confirmed()
+    }
+    fn ledger_commitment_config(&self) -> CommitmentConfig {
+        CommitmentConfig::confirmed()
+    }
+
+    async fn get_epoch_info(&self) -> Result<EpochInfo, ClientError> {
+        self.fixtures
+            .epoch_info
+            .clone()
+            .ok_or_else(|| not_recorded("epoch info"))
+    }
+
+    async fn get_leader_schedule(&self) -> Result<HashMap<String, Vec<usize>>> {
+        self.fixtures
+            .leader_schedule
+            .clone()
+            .ok_or_else(|| anyhow!("No leader schedule recorded"))
+    }
+
+    async fn get_block_with_config(&self, slot: u64) -> Result<UiConfirmedBlock, ClientError> {
+        if self.fixtures.skipped_slots.contains(&slot) {
+            return Err(ClientErrorKind::RpcError(RpcError::RpcResponseError {
+                code: JSON_RPC_SERVER_ERROR_SLOT_SKIPPED,
+                message: format!("Slot {slot} was skipped (recorded)"),
+                data: RpcResponseErrorData::Empty,
+            })
+            .into());
+        }
+        self.fixtures
+            .blocks
+            .get(&slot)
+            .cloned()
+            .ok_or_else(|| not_recorded(&format!("block for slot {slot}")))
+    }
+
+    async fn get<U: DeserializeOwned + Send + 'static>(
+        &self,
+        url: &str,
+    ) -> Result<U, Box<dyn Error + Send + Sync>> {
+        let body = self
+            .fixtures
+            .http
+            .get(url)
+            .cloned()
+            .ok_or_else(|| format!("No response recorded for {url}"))?;
+        Ok(serde_json::from_value(body)?)
+    }
+
+    async fn get_vote_accounts_with_config(&self) -> Result<RpcVoteAccountStatus, ClientError> {
+        self.fixtures
+            .vote_accounts
+            .clone()
+            .ok_or_else(|| not_recorded("vote accounts"))
+    }
+
+    async fn get_inflation_reward(
+        &self,
+        vote_keys: Vec<Pubkey>,
+        epoch: u64,
+    ) -> Result<Vec<Option<RpcInflationReward>>, ClientError> {
+        let recorded = self
+            .fixtures
+            .inflation_rewards
+            .get(&epoch)
+            .ok_or_else(|| not_recorded(&format!("inflation rewards for epoch {epoch}")))?;
+        vote_keys
+            .iter()
+            .map(|vote_key| {
+                recorded.get(&vote_key.to_string()).cloned().ok_or_else(|| {
+                    not_recorded(&format!("inflation reward for {vote_key} in epoch {epoch}"))
+                })
+            })
+            .collect()
+    }
+
+    async fn get_slot(&self) -> Result<u64, ClientError> {
+        self.fixtures.slot.ok_or_else(|| not_recorded("slot"))
+    }
+
+    async fn get_block_time(&self, slot: u64) -> Result<i64, ClientError> {
+        self.fixtures
+            .block_times
+            .get(&slot)
+            .copied()
+            .ok_or_else(|| not_recorded(&format!("block time for slot {slot}")))
+    }
+}
+
+fn is_skipped_slot_error(err: &ClientError) -> bool {
+    matches!(
+        err.kind(),
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. })
+            if *code == JSON_RPC_SERVER_ERROR_SLOT_SKIPPED
+                || *code == JSON_RPC_SERVER_ERROR_LONG_TERM_STORAGE_SLOT_SKIPPED
+    )
+}
+
+fn not_recorded(what: &str) -> ClientError {
+    ClientErrorKind::Custom(format!("No {what} recorded in fixture")).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solana_debt_calculator::MockValidatorRewards;
+    use solana_client::rpc_response::RpcVoteAccountInfo;
+    use solana_sdk::reward_type::RewardType::Fee;
+
+    const VALIDATOR_ID: &str = "6WgdYhhGE53WrZ7ywJA15hBVkw7CRbQ8yDBBTwmBtAHN";
+    const VOTE_PUBKEY: &str = "6WgdYhhGE53WrZ7ywJA15hBVkw7CRbQ8yDBBTwmBtBBN";
+
+    fn live_provider(epoch: u64) -> MockValidatorRewards {
+        let mut provider = MockValidatorRewards::new();
+
+        provider.expect_get_epoch_info().returning(move || {
+            Ok(EpochInfo {
+                epoch,
+                slot_index: 100_000,
+                absolute_slot: 10_000_000,
+                block_height: 103_030_003,
+                slots_in_epoch: 5_000_000,
+                transaction_count: Some(1000),
+            })
+        });
+        provider
+            .expect_get_leader_schedule()
+            .returning(|| Ok(HashMap::from([(VALIDATOR_ID.to_string(), vec![10, 11])])));
+        provider
+            .expect_get_block_with_config()
+            .returning(|slot| match slot {
+                9_900_010 => Ok(UiConfirmedBlock {
+                    num_reward_partitions: Some(1),
+                    signatures: Some(vec!["One".to_string(), "Two".to_string()]),
+                    rewards: Some(vec![solana_transaction_status_client_types::Reward {
+                        pubkey: VALIDATOR_ID.to_string(),
+                        lamports: 40_000,
+                        post_balance: 40_000,
+                        reward_type: Some(Fee),
+                        commission: None,
+                    }]),
+                    previous_blockhash: "".to_string(),
+                    blockhash: "".to_string(),
+                    parent_slot: 0,
+                    transactions: None,
+                    block_time: None,
+                    block_height: None,
+                }),
+                _ => Err(ClientErrorKind::RpcError(RpcError::RpcResponseError {
+                    code: JSON_RPC_SERVER_ERROR_SLOT_SKIPPED,
+                    message: "skipped".to_string(),
+                    data: RpcResponseErrorData::Empty,
+                })
+                .into()),
+            });
+        provider.expect_get::<serde_json::Value>().returning(|_| {
+            Ok(serde_json::json!({
+                "total_count": 1,
+                "rewards": [{ "vote_account": VOTE_PUBKEY, "mev_revenue": 10_000 }],
+            }))
+        });
+        provider
+            .expect_get_vote_accounts_with_config()
+            .returning(|| {
+                Ok(RpcVoteAccountStatus {
+                    current: vec![RpcVoteAccountInfo {
+                        vote_pubkey: VOTE_PUBKEY.to_string(),
+                        node_pubkey: VALIDATOR_ID.to_string(),
+                        activated_stake: 4_200_000_000_000,
+                        epoch_vote_account: true,
+                        epoch_credits: vec![(812, 256, 128)],
+                        commission: 10,
+                        last_vote: 123_456_789,
+                        root_slot: 123_456_700,
+                    }],
+                    delinquent: vec![],
+                })
+            });
+        provider
+            .expect_get_inflation_reward()
+            .returning(move |_, _| {
+                Ok(vec![Some(RpcInflationReward {
+                    epoch,
+                    effective_slot: 123_456_789,
+                    amount: 2_500,
+                    post_balance: 1_500_002_500,
+                    commission: Some(1),
+                })])
+            });
+
+        provider
+    }
+
+    #[tokio::test]
+    async fn test_record_and_replay() {
+        let epoch = 824;
+        let validator_ids = vec![VALIDATOR_ID.to_string()];
+
+        let fixture = RewardsFixture::record(
+            live_provider(epoch),
+            &validator_ids,
+            epoch,
+            InflationCommission::PreCommission,
+        )
+        .await
+        .unwrap();
+        assert_eq!(fixture.responses.blocks.len(), 1);
+        assert_eq!(fixture.responses.skipped_slots, BTreeSet::from([9_900_011]));
+        assert_eq!(fixture.rewards.len(), 1);
+        assert_eq!(fixture.rewards[0].jito, 10_000);
+        // 1% commission grossed up to the stake's rewards
+        assert_eq!(fixture.rewards[0].inflation, 250_000);
+
+        // Round trip through the on-disk format before replaying
+        let fixture: RewardsFixture =
+            serde_json::from_str(&serde_json::to_string(&fixture).unwrap()).unwrap();
+        let replayed = fixture.verify().await.unwrap();
+        assert_eq!(replayed, fixture.rewards);
+    }
+}
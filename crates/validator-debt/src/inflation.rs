@@ -1,32 +1,97 @@
-use crate::solana_debt_calculator::ValidatorRewards;
+use crate::{solana_debt_calculator::ValidatorRewards, vote_accounts};
 use anyhow::{Result, anyhow};
-use backon::{ExponentialBuilder, Retryable};
+use borsh::{BorshDeserialize, BorshSerialize};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use solana_client::rpc_response::{RpcInflationReward, RpcVoteAccountStatus};
 use solana_sdk::pubkey::Pubkey;
-use std::{collections::HashMap, str::FromStr, time::Duration};
-use tracing::info;
+use std::{collections::HashMap, str::FromStr};
+use tracing::warn;
+
+/// Which side of the validator's commission inflation rewards are charged on
+///
+/// The inflation reward reported for a vote account is the commission it kept from the
+/// rewards of its delegated stake. Charging pre-commission grosses that back up to the
+/// rewards the stake earned as a whole.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    ValueEnum,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum InflationCommission {
+    /// Charge the commission the vote account received
+    #[default]
+    #[value(name = "post")]
+    PostCommission,
+    /// Charge the rewards of the delegated stake before the commission was taken
+    #[value(name = "pre")]
+    PreCommission,
+}
+
+impl InflationCommission {
+    /// Inflation reward to charge for a vote account's reported `reward`
+    ///
+    /// Pre-commission amounts cannot be derived for vote accounts charging no commission,
+    /// since nothing is reported for them; those are charged zero like post-commission.
+    pub fn charged_amount(&self, reward: &RpcInflationReward) -> u64 {
+        match self {
+            Self::PostCommission => reward.amount,
+            Self::PreCommission => match reward.commission {
+                Some(commission) if commission > 0 => {
+                    (reward.amount as u128 * 100 / commission as u128) as u64
+                }
+                _ => {
+                    if reward.amount > 0 {
+                        warn!(
+                            "inflation reward of {} lamports reported without a commission; charging it as is",
+                            reward.amount
+                        );
+                    }
+                    reward.amount
+                }
+            },
+        }
+    }
+}
 
 pub async fn get_inflation_rewards<T: ValidatorRewards + ?Sized>(
     solana_debt_calculator: &T,
     validator_ids: &[String],
     epoch: u64,
+    inflation_commission: InflationCommission,
 ) -> Result<HashMap<String, u64>> {
-    let mut vote_keys: Vec<Pubkey> = Vec::with_capacity(validator_ids.len());
-
     println!("get inflation rewards for epoch {epoch}");
-    let vote_accounts = (|| async {
-        solana_debt_calculator
-        .get_vote_accounts_with_config()
-        .await
-    }).retry(&ExponentialBuilder::default()
-        .with_max_times(5)
-        .with_min_delay(Duration::from_millis(100))
-        .with_max_delay(Duration::from_secs(10))
-        .with_jitter())
-    .notify(|err, dur: Duration| {
-        info!("get_vote_accounts_with_config call failed, retrying in {:?}: {}", dur, err);
-    }).await.map_err(|e| {
-        anyhow!("Failed to fetch get_vote_accounts_with_config for epoch {epoch} after retries: {e:#?}")
-    })?;
+    let vote_accounts = vote_accounts::fetch_vote_accounts(solana_debt_calculator, epoch).await?;
+
+    get_inflation_rewards_for_vote_accounts(
+        solana_debt_calculator,
+        &vote_accounts,
+        validator_ids,
+        epoch,
+        inflation_commission,
+    )
+    .await
+}
+
+/// Inflation rewards of `validator_ids`, located through already fetched `vote_accounts`
+pub async fn get_inflation_rewards_for_vote_accounts<T: ValidatorRewards + ?Sized>(
+    solana_debt_calculator: &T,
+    vote_accounts: &RpcVoteAccountStatus,
+    validator_ids: &[String],
+    epoch: u64,
+    inflation_commission: InflationCommission,
+) -> Result<HashMap<String, u64>> {
+    let mut vote_keys: Vec<Pubkey> = Vec::with_capacity(validator_ids.len());
+    let mut found_validator_ids: Vec<&String> = Vec::with_capacity(validator_ids.len());
 
     // this can be cleaned up i'm sure
     println!("getting vote account keys for inflation rewards");
@@ -41,7 +106,10 @@ pub async fn get_inflation_rewards<T: ValidatorRewards + ?Sized>(
             })
             .transpose()?
         {
-            Some(vote_account) => vote_keys.push(vote_account),
+            Some(vote_account) => {
+                vote_keys.push(vote_account);
+                found_validator_ids.push(validator_id);
+            }
             None => {
                 eprintln!("Validator ID {validator_id} not found");
                 continue;
@@ -56,14 +124,18 @@ pub async fn get_inflation_rewards<T: ValidatorRewards + ?Sized>(
     let rewards: Vec<u64> = inflation_rewards
         .iter()
         .map(|ir| match ir {
-            Some(rewards) => rewards.amount,
+            Some(rewards) => inflation_commission.charged_amount(rewards),
             None => 0,
         })
         .collect();
 
-    // probably a better way to do this
-    let inflation_rewards: HashMap<String, u64> =
-        validator_ids.iter().cloned().zip(rewards).collect();
+    // rewards are returned in the same order as the vote keys, which only cover validators
+    // that were found in the vote accounts
+    let inflation_rewards: HashMap<String, u64> = found_validator_ids
+        .into_iter()
+        .cloned()
+        .zip(rewards)
+        .collect();
     Ok(inflation_rewards)
 }
 
@@ -83,8 +155,8 @@ mod tests {
         let epoch = 100;
         let mock_rpc_vote_account_status = RpcVoteAccountStatus {
             current: vec![RpcVoteAccountInfo {
-                vote_pubkey: "some vote pubkey".to_string(),
-                node_pubkey: "some pubkey".to_string(),
+                vote_pubkey: Pubkey::new_unique().to_string(),
+                node_pubkey: validator_id.clone(),
                 activated_stake: 4_200_000_000_000,
                 epoch_vote_account: true,
                 epoch_credits: vec![(812, 256, 128), (811, 128, 64)],
@@ -114,9 +186,99 @@ mod tests {
             .returning(move |_, _| Ok(mock_rpc_inflation_reward.clone()));
 
         let inflation_reward: u64 = 2500;
-        let rewards = get_inflation_rewards(&mock_solana_debt_calculator, validator_ids, epoch)
-            .await
-            .unwrap();
+        let rewards = get_inflation_rewards(
+            &mock_solana_debt_calculator,
+            validator_ids,
+            epoch,
+            InflationCommission::PostCommission,
+        )
+        .await
+        .unwrap();
         assert_eq!(rewards.get(&validator_id), Some(&(inflation_reward)));
     }
+
+    #[tokio::test]
+    async fn test_inflation_rewards_skip_validators_without_vote_accounts() {
+        let mut mock_solana_debt_calculator = MockValidatorRewards::new();
+        let missing_id = Pubkey::new_unique().to_string();
+        let found_id = Pubkey::new_unique().to_string();
+        let validator_ids = vec![missing_id.clone(), found_id.clone()];
+        let vote_accounts = RpcVoteAccountStatus {
+            current: vec![RpcVoteAccountInfo {
+                vote_pubkey: Pubkey::new_unique().to_string(),
+                node_pubkey: found_id.clone(),
+                activated_stake: 4_200_000_000_000,
+                epoch_vote_account: true,
+                epoch_credits: vec![(812, 256, 128)],
+                commission: 10,
+                last_vote: 123456789,
+                root_slot: 123456700,
+            }],
+            delinquent: vec![],
+        };
+
+        // One vote key is requested, so one reward comes back
+        mock_solana_debt_calculator
+            .expect_get_inflation_reward()
+            .withf(|vote_keys, _| vote_keys.len() == 1)
+            .times(1)
+            .returning(|_, _| {
+                Ok(vec![Some(RpcInflationReward {
+                    epoch: 812,
+                    effective_slot: 123456789,
+                    amount: 2500,
+                    post_balance: 1_500_002_500,
+                    commission: Some(10),
+                })])
+            });
+
+        let rewards = get_inflation_rewards_for_vote_accounts(
+            &mock_solana_debt_calculator,
+            &vote_accounts,
+            &validator_ids,
+            812,
+            InflationCommission::PostCommission,
+        )
+        .await
+        .unwrap();
+
+        // The reward belongs to the validator that has the vote account, not the first one listed
+        assert_eq!(rewards.get(&found_id), Some(&2500));
+        assert_eq!(rewards.get(&missing_id), None);
+    }
+
+    #[test]
+    fn test_charged_amount() {
+        let reward = |amount, commission| RpcInflationReward {
+            epoch: 812,
+            effective_slot: 123456789,
+            amount,
+            post_balance: 1_500_000_000 + amount,
+            commission,
+        };
+
+        // 5% commission of 50_000 lamports of stake rewards
+        let commissioned = reward(2500, Some(5));
+        assert_eq!(
+            InflationCommission::PostCommission.charged_amount(&commissioned),
+            2500
+        );
+        assert_eq!(
+            InflationCommission::PreCommission.charged_amount(&commissioned),
+            50_000
+        );
+
+        let full_commission = reward(2500, Some(100));
+        assert_eq!(
+            InflationCommission::PreCommission.charged_amount(&full_commission),
+            2500
+        );
+
+        for no_commission in [reward(0, Some(0)), reward(0, None)] {
+            assert_eq!(
+                InflationCommission::PreCommission.charged_amount(&no_commission),
+                0
+            );
+        }
+    }
 }
@@ -1,9 +1,9 @@
-use crate::solana_debt_calculator::ValidatorRewards;
+use crate::{solana_debt_calculator::ValidatorRewards, vote_accounts::VoteAccountMap};
 use anyhow::{Result, anyhow};
 use backon::{ExponentialBuilder, Retryable};
 use serde::Deserialize;
 use std::{collections::HashMap, time::Duration};
-use tracing::info;
+use tracing::{debug, info};
 
 const JITO_BASE_URL: &str = "https://kobe.mainnet.jito.network/api/v1/";
 
@@ -23,10 +23,15 @@ pub struct JitoReward {
     pub mev_revenue: u64,
 }
 
+/// MEV revenue of each validator identity, summed across all of its vote accounts
+///
+/// Jito reports rewards per vote account; `vote_accounts` attributes them to the identities
+/// debts are keyed by.
 // may need to add in pagination
 pub async fn get_jito_rewards<'a, T: ValidatorRewards>(
     solana_debt_calculator: &T,
     validator_ids: &'a [String],
+    vote_accounts: &VoteAccountMap,
     epoch: u64,
 ) -> Result<HashMap<&'a str, u64>> {
     let url = format!(
@@ -58,19 +63,22 @@ pub async fn get_jito_rewards<'a, T: ValidatorRewards>(
             rewards.total_count
         );
     }
-    let jito_rewards = validator_ids
+    let mut jito_rewards = validator_ids
         .iter()
-        .map(|validator_id| {
-            println!("Fetching Jito rewards for validator_id {validator_id}");
-            let mev_revenue = rewards
-                .rewards
-                .iter()
-                .find(|reward| validator_id == &reward.vote_account)
-                .map(|reward| reward.mev_revenue)
-                .unwrap_or_default();
-            (validator_id.as_str(), mev_revenue)
-        })
+        .map(|validator_id| (validator_id.as_str(), 0))
         .collect::<HashMap<_, _>>();
+    for reward in &rewards.rewards {
+        let Some(validator_id) = vote_accounts.identity(&reward.vote_account) else {
+            debug!(
+                "No identity found for Jito vote account {}; {} lamports unattributed",
+                reward.vote_account, reward.mev_revenue
+            );
+            continue;
+        };
+        if let Some(mev_revenue) = jito_rewards.get_mut(validator_id) {
+            *mev_revenue += reward.mev_revenue;
+        }
+    }
 
     Ok(jito_rewards)
 }
@@ -79,14 +87,36 @@ pub async fn get_jito_rewards<'a, T: ValidatorRewards>(
 mod tests {
     use super::*;
     use crate::solana_debt_calculator::MockValidatorRewards;
+    use solana_client::rpc_response::{RpcVoteAccountInfo, RpcVoteAccountStatus};
+
+    fn vote_account(vote_pubkey: &str, node_pubkey: &str) -> RpcVoteAccountInfo {
+        RpcVoteAccountInfo {
+            vote_pubkey: vote_pubkey.to_string(),
+            node_pubkey: node_pubkey.to_string(),
+            activated_stake: 4_200_000_000_000,
+            epoch_vote_account: true,
+            epoch_credits: vec![(812, 256, 128)],
+            commission: 10,
+            last_vote: 123456789,
+            root_slot: 123456700,
+        }
+    }
 
     #[tokio::test]
     async fn test_get_jito_rewards() {
         let mut jito_mock_fetcher = MockValidatorRewards::new();
         let pubkey = "CvSb7wdQAFpHuSpTYTJnX5SYH4hCfQ9VuGnqrKaKwycB";
-        let validator_ids: &[String] = &[String::from(pubkey)];
+        let validator_id = "6WgdYhhGE53WrZ7ywJA15hBVkw7CRbQ8yDBBTwmBtAHN";
+        let validator_ids: &[String] = &[String::from(validator_id)];
         let epoch = 812;
         let expected_mev_revenue = 503423196855;
+        let vote_accounts = VoteAccountMap::new(
+            &RpcVoteAccountStatus {
+                current: vec![vote_account(pubkey, validator_id)],
+                delinquent: vec![],
+            },
+            epoch,
+        );
         jito_mock_fetcher
             .expect_get::<JitoRewards>()
             .withf(move |url| url.contains(&format!("epoch={epoch}")))
@@ -101,10 +131,51 @@ mod tests {
                 })
             });
 
-        let mock_response = get_jito_rewards(&jito_mock_fetcher, validator_ids, epoch)
+        let mock_response =
+            get_jito_rewards(&jito_mock_fetcher, validator_ids, &vote_accounts, epoch)
+                .await
+                .unwrap();
+
+        assert_eq!(mock_response.get(validator_id), Some(&expected_mev_revenue));
+    }
+
+    #[tokio::test]
+    async fn test_get_jito_rewards_across_vote_accounts() {
+        let mut jito_mock_fetcher = MockValidatorRewards::new();
+        let validator_id = "6WgdYhhGE53WrZ7ywJA15hBVkw7CRbQ8yDBBTwmBtAHN";
+        let validator_ids: &[String] = &[String::from(validator_id)];
+        let epoch = 812;
+        let vote_accounts = VoteAccountMap::new(
+            &RpcVoteAccountStatus {
+                current: vec![vote_account("new-vote", validator_id)],
+                delinquent: vec![
+                    vote_account("old-vote", validator_id),
+                    vote_account("other-vote", "other-validator"),
+                ],
+            },
+            epoch,
+        );
+        jito_mock_fetcher
+            .expect_get::<JitoRewards>()
+            .times(1)
+            .returning(move |_| {
+                Ok(JitoRewards {
+                    total_count: 3,
+                    rewards: ["new-vote", "old-vote", "other-vote"]
+                        .into_iter()
+                        .map(|vote_account| JitoReward {
+                            vote_account: vote_account.to_string(),
+                            mev_revenue: 1_000,
+                        })
+                        .collect(),
+                })
+            });
+
+        let rewards = get_jito_rewards(&jito_mock_fetcher, validator_ids, &vote_accounts, epoch)
             .await
             .unwrap();
 
-        assert_eq!(mock_response.get(pubkey), Some(&expected_mev_revenue));
+        assert_eq!(rewards.len(), 1);
+        assert_eq!(rewards.get(validator_id), Some(&2_000));
     }
 }
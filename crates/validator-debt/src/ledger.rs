@@ -1,12 +1,14 @@
 use anyhow::{Context, Result};
-use doublezero_record::state::RecordData;
+use doublezero_record::{instruction as record_instruction, state::RecordData};
 use doublezero_sdk::record::{self, client, state::read_record_data};
 use solana_client::{nonblocking::rpc_client::RpcClient, rpc_config::RpcSendTransactionConfig};
 use solana_sdk::{
     clock::Epoch,
     commitment_config::CommitmentConfig,
     hash::Hash,
+    pubkey::Pubkey,
     signer::{Signer, keypair::Keypair},
+    transaction::Transaction,
 };
 
 const SLOT_TIME_DURATION_SECONDS: f64 = 0.4;
@@ -47,6 +49,25 @@ pub async fn create_record_on_ledger<T: borsh::BorshSerialize>(
     let payer_key = payer_signer.pubkey();
 
     let serialized = borsh::to_vec(record_data)?;
+
+    // Chunks are written in place, so a record of a different size (such as one
+    // written before fields were appended to its layout) is recreated to fit
+    let record_key = record::pubkey::create_record_key(&payer_key, seeds);
+    let existing_len = rpc_client
+        .get_account_with_commitment(&record_key, commitment_config)
+        .await
+        .with_context(|| format!("Failed to fetch account {record_key}"))?
+        .value
+        .map(|account| account.data.len().saturating_sub(size_of::<RecordData>()));
+    if needs_recreate(existing_len, serialized.len()) {
+        println!(
+            "record {record_key} holds {} bytes, recreating it for {}",
+            existing_len.unwrap_or_default(),
+            serialized.len()
+        );
+        close_record(rpc_client, recent_blockhash, payer_signer, &record_key).await?;
+    }
+
     // todo : log signature
     let record = client::try_create_record(
         rpc_client,
@@ -83,15 +104,51 @@ pub async fn create_record_on_ledger<T: borsh::BorshSerialize>(
     Ok(())
 }
 
+/// Whether a record holding `existing_len` bytes has to be closed and
+/// recreated before `len` bytes are written to it
+fn needs_recreate(existing_len: Option<usize>, len: usize) -> bool {
+    existing_len.is_some_and(|existing_len| existing_len != len)
+}
+
+async fn close_record(
+    rpc_client: &RpcClient,
+    recent_blockhash: Hash,
+    payer_signer: &Keypair,
+    record_key: &Pubkey,
+) -> Result<()> {
+    let payer_key = payer_signer.pubkey();
+    let close_ix = record_instruction::close_account(record_key, &payer_key, &payer_key);
+    let transaction = Transaction::new_signed_with_payer(
+        &[close_ix],
+        Some(&payer_key),
+        &[payer_signer],
+        recent_blockhash,
+    );
+    let signature = rpc_client
+        .send_and_confirm_transaction(&transaction)
+        .await
+        .with_context(|| format!("Failed to close record {record_key}"))?;
+    println!("closed record {record_key}: {signature}");
+    Ok(())
+}
+
 pub async fn read_from_ledger(
     rpc_client: &RpcClient,
     payer_signer: &Keypair,
     seed: &[&[u8]],
     commitment_config: CommitmentConfig,
 ) -> Result<(RecordData, Vec<u8>)> {
-    let payer_key = payer_signer.pubkey();
+    read_from_ledger_for_payer(rpc_client, &payer_signer.pubkey(), seed, commitment_config).await
+}
 
-    let record_key = record::pubkey::create_record_key(&payer_key, seed);
+/// Read a record written by `payer_key`, for callers that only know its public key
+pub async fn read_from_ledger_for_payer(
+    rpc_client: &RpcClient,
+    payer_key: &Pubkey,
+    seed: &[&[u8]],
+    commitment_config: CommitmentConfig,
+) -> Result<(RecordData, Vec<u8>)> {
+    let record_key = record::pubkey::create_record_key(payer_key, seed);
     let get_account_response = rpc_client
         .get_account_with_commitment(&record_key, commitment_config)
         .await
@@ -139,8 +196,11 @@ async fn get_solana_epoch_from_dz_slot(
 mod tests {
     use super::*;
     use crate::{
+        inflation::InflationCommission,
         rewards::{EpochRewards, Reward},
         solana_debt_calculator::{SolanaDebtCalculator, ledger_rpc, solana_rpc},
+        validator_debt::{ComputedSolanaValidatorDebt, ComputedSolanaValidatorDebts},
+        waiver::DebtWaiver,
     };
 
     use solana_client::{
@@ -152,6 +212,73 @@ mod tests {
     use solana_transaction_status_client_types::{TransactionDetails, UiTransactionEncoding};
     use std::{str::FromStr, time::Duration};
 
+    #[test]
+    fn test_needs_recreate_when_layout_grows() {
+        let debts = ComputedSolanaValidatorDebts {
+            debts: vec![ComputedSolanaValidatorDebt {
+                node_id: Pubkey::new_unique(),
+                amount: 1_000,
+            }],
+            ..Default::default()
+        };
+        let serialized = borsh::to_vec(&debts).unwrap();
+
+        // Records written before the trailing fields were appended end after the debts
+        let legacy_len = serialized.len() - 3;
+
+        assert!(!needs_recreate(None, serialized.len()));
+        assert!(!needs_recreate(Some(serialized.len()), serialized.len()));
+        assert!(needs_recreate(Some(legacy_len), serialized.len()));
+        assert!(needs_recreate(Some(serialized.len()), legacy_len));
+    }
+
+    #[test]
+    fn test_reaudit_with_different_waiver_or_commission() {
+        let debt = ComputedSolanaValidatorDebt {
+            node_id: Pubkey::new_unique(),
+            amount: 500,
+        };
+        let audit = ComputedSolanaValidatorDebts {
+            debts: vec![debt],
+            inflation_commission: Some(InflationCommission::PostCommission),
+            debt_waiver: Some(DebtWaiver {
+                min_validator_debt: 1_000,
+                min_total_debt: 0,
+                distribution_waived: false,
+                waived: vec![debt],
+            }),
+            ..Default::default()
+        };
+        let audited = borsh::to_vec(&audit).unwrap();
+
+        // Dropping the waiver shrinks the record, and writing it in place would
+        // leave the old waiver's bytes trailing after it
+        let without_waiver = borsh::to_vec(&ComputedSolanaValidatorDebts {
+            debt_waiver: None,
+            ..audit.clone()
+        })
+        .unwrap();
+        assert!(needs_recreate(Some(audited.len()), without_waiver.len()));
+        let mut in_place = audited.clone();
+        in_place[..without_waiver.len()].copy_from_slice(&without_waiver);
+        assert!(borsh::from_slice::<ComputedSolanaValidatorDebts>(&in_place).is_err());
+
+        // Restoring it grows the record back
+        assert!(needs_recreate(Some(without_waiver.len()), audited.len()));
+
+        // A different commission keeps the size, so the record is rewritten in place
+        let pre_commission = ComputedSolanaValidatorDebts {
+            inflation_commission: Some(InflationCommission::PreCommission),
+            ..audit.clone()
+        };
+        let reaudited = borsh::to_vec(&pre_commission).unwrap();
+        assert!(!needs_recreate(Some(audited.len()), reaudited.len()));
+        assert_eq!(
+            borsh::from_slice::<ComputedSolanaValidatorDebts>(&reaudited).unwrap(),
+            pre_commission
+        );
+    }
+
     #[ignore = "needs remote connection"]
     #[tokio::test]
     async fn test_convert_dz_epoch_to_solana_epoch() -> anyhow::Result<()> {
@@ -2,12 +2,21 @@
 
 pub mod block;
 pub mod command;
+pub mod fee_history;
+pub mod fixtures;
 pub mod inflation;
 pub mod jito;
 pub mod ledger;
+pub mod notify;
+pub mod payment_plan;
+pub mod receipt;
 pub mod rewards;
 pub mod rpc;
+pub mod sanity;
 pub mod solana_debt_calculator;
 pub mod transaction;
 pub mod validator_debt;
+pub mod verify;
+pub mod vote_accounts;
+pub mod waiver;
 pub mod worker;
@@ -1,5 +1,6 @@
 use anyhow::Result;
-use clap::Parser;
+use clap::{CommandFactory, Parser};
+use doublezero_cli_profile as profile;
 use doublezero_solana_validator_debt::command::ValidatorDebtCommand;
 use tracing_subscriber::{EnvFilter, layer::SubscriberExt, util::SubscriberInitExt};
 
@@ -8,6 +9,11 @@ use tracing_subscriber::{EnvFilter, layer::SubscriberExt, util::SubscriberInitEx
 #[command(version = option_env!("BUILD_VERSION").unwrap_or(env!("CARGO_PKG_VERSION")))]
 #[command(about = "DoubleZero Solana Debt Calculation Commands", long_about = None)]
 struct ValidatorDebtApp {
+    /// Named RPC profile from ~/.config/doublezero/cli.toml supplying default connection
+    /// options (URLs, commitment, compute unit price).
+    #[arg(long = profile::PROFILE_ARG, global = true, value_name = "NAME")]
+    _profile: Option<String>,
+
     #[command(subcommand)]
     command: ValidatorDebtCommand,
 }
@@ -24,5 +30,10 @@ async fn main() -> Result<()> {
         )
         .init();
 
-    ValidatorDebtApp::parse().command.try_into_execute().await
+    let args = profile::apply_profile(&ValidatorDebtApp::command(), std::env::args_os().collect())?;
+
+    ValidatorDebtApp::parse_from(args)
+        .command
+        .try_into_execute()
+        .await
 }
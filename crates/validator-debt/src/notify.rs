@@ -0,0 +1,204 @@
+use std::env;
+
+use doublezero_solana_client_tools::log_warn;
+use serde_json::{Value, json};
+
+/// Generic webhook receiving a JSON body describing the milestone
+pub const WEBHOOK_URL_ENV: &str = "VALIDATOR_DEBT_WEBHOOK_URL";
+
+/// Slack incoming webhook receiving a plain text message
+pub const SLACK_WEBHOOK_URL_ENV: &str = "VALIDATOR_DEBT_SLACK_WEBHOOK_URL";
+
+/// Distribution lifecycle events worth surfacing to operators
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Milestone {
+    DistributionInitialized {
+        dz_epoch: u64,
+        signature: String,
+    },
+    DebtConfigured {
+        dz_epoch: u64,
+        total_debt: u64,
+        total_validators: u32,
+        merkle_root: String,
+        signature: String,
+    },
+    DistributionFinalized {
+        dz_epoch: u64,
+        signature: String,
+    },
+    PaymentsCompleted {
+        dz_epochs: Vec<u64>,
+        payments: usize,
+        total_paid: u64,
+    },
+}
+
+impl Milestone {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::DistributionInitialized { .. } => "distribution_initialized",
+            Self::DebtConfigured { .. } => "debt_configured",
+            Self::DistributionFinalized { .. } => "distribution_finalized",
+            Self::PaymentsCompleted { .. } => "payments_completed",
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            Self::DistributionInitialized {
+                dz_epoch,
+                signature,
+            } => format!("Distribution initialized for DZ epoch {dz_epoch} ({signature})"),
+            Self::DebtConfigured {
+                dz_epoch,
+                total_debt,
+                total_validators,
+                merkle_root,
+                signature,
+            } => format!(
+                "Debt configured for DZ epoch {dz_epoch}: {total_debt} lamports across {total_validators} validators, merkle root {merkle_root} ({signature})"
+            ),
+            Self::DistributionFinalized {
+                dz_epoch,
+                signature,
+            } => format!("Distribution finalized for DZ epoch {dz_epoch} ({signature})"),
+            Self::PaymentsCompleted {
+                dz_epochs,
+                payments,
+                total_paid,
+            } => format!(
+                "Paid {payments} validator debts totaling {total_paid} lamports for DZ epochs {dz_epochs:?}"
+            ),
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        let details = match self {
+            Self::DistributionInitialized {
+                dz_epoch,
+                signature,
+            }
+            | Self::DistributionFinalized {
+                dz_epoch,
+                signature,
+            } => json!({ "dz_epoch": dz_epoch, "signature": signature }),
+            Self::DebtConfigured {
+                dz_epoch,
+                total_debt,
+                total_validators,
+                merkle_root,
+                signature,
+            } => json!({
+                "dz_epoch": dz_epoch,
+                "total_debt": total_debt,
+                "total_validators": total_validators,
+                "merkle_root": merkle_root,
+                "signature": signature,
+            }),
+            Self::PaymentsCompleted {
+                dz_epochs,
+                payments,
+                total_paid,
+            } => json!({
+                "dz_epochs": dz_epochs,
+                "payments": payments,
+                "total_paid": total_paid,
+            }),
+        };
+
+        json!({
+            "milestone": self.name(),
+            "message": self.message(),
+            "details": details,
+        })
+    }
+}
+
+/// Best-effort delivery of milestone notifications
+///
+/// Endpoints are read from the environment; with none configured, notifying is a no-op.
+/// Delivery failures are logged and never fail the distribution step that triggered them.
+#[derive(Debug, Clone, Default)]
+pub struct Notifier {
+    webhook_url: Option<String>,
+    slack_webhook_url: Option<String>,
+}
+
+impl Notifier {
+    pub fn new(webhook_url: Option<String>, slack_webhook_url: Option<String>) -> Self {
+        Self {
+            webhook_url,
+            slack_webhook_url,
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let read = |key| env::var(key).ok().filter(|url: &String| !url.is_empty());
+        Self::new(read(WEBHOOK_URL_ENV), read(SLACK_WEBHOOK_URL_ENV))
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.webhook_url.is_some() || self.slack_webhook_url.is_some()
+    }
+
+    pub async fn notify(&self, milestone: Milestone) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let client = reqwest::Client::new();
+
+        if let Some(url) = &self.webhook_url {
+            post(&client, url, &milestone.to_json(), milestone.name()).await;
+        }
+        if let Some(url) = &self.slack_webhook_url {
+            let body = json!({ "text": milestone.message() });
+            post(&client, url, &body, milestone.name()).await;
+        }
+    }
+}
+
+async fn post(client: &reqwest::Client, url: &str, body: &Value, name: &str) {
+    let result = client
+        .post(url)
+        .json(body)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status());
+
+    if let Err(err) = result {
+        log_warn!("Failed to send {name} notification: {err}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debt_configured_payload() {
+        let milestone = Milestone::DebtConfigured {
+            dz_epoch: 42,
+            total_debt: 1_500,
+            total_validators: 3,
+            merkle_root: "root".to_string(),
+            signature: "sig".to_string(),
+        };
+
+        let payload = milestone.to_json();
+        assert_eq!(payload["milestone"], "debt_configured");
+        assert_eq!(payload["details"]["total_debt"], 1_500);
+        assert_eq!(payload["details"]["merkle_root"], "root");
+        assert_eq!(
+            payload["message"],
+            "Debt configured for DZ epoch 42: 1500 lamports across 3 validators, merkle root root (sig)"
+        );
+    }
+
+    #[test]
+    fn test_disabled_without_endpoints() {
+        assert!(!Notifier::default().is_enabled());
+        assert!(Notifier::new(None, Some("https://hooks.example.com".to_string())).is_enabled());
+    }
+}
@@ -0,0 +1,195 @@
+use clap::ValueEnum;
+use solana_sdk::pubkey::Pubkey;
+use tabled::{Table, Tabled, settings::Style};
+
+use crate::validator_debt::ComputedSolanaValidatorDebts;
+
+/// Order in which outstanding debts are paid when the budget does not cover all of them
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum AllocationStrategy {
+    /// Pay debts from the oldest DZ epoch first, largest debt first within an epoch
+    #[default]
+    OldestEpochFirst,
+    /// Pay the largest debts first regardless of epoch, oldest epoch first on ties
+    LargestDebtFirst,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Tabled)]
+pub struct PaymentItem {
+    pub dz_epoch: u64,
+    pub node_id: Pubkey,
+    pub amount: u64,
+}
+
+/// Itemized selection of debts to pay under an optional budget
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct PaymentPlan {
+    /// Debts that will be paid, in payment order
+    pub items: Vec<PaymentItem>,
+    /// Debts that did not fit within the budget
+    pub deferred: Vec<PaymentItem>,
+}
+
+impl PaymentPlan {
+    /// Build a plan from computed debts per DZ epoch
+    ///
+    /// Each debt is paid in full or not at all since its amount is committed to by the
+    /// distribution's merkle root. Debts are visited in strategy order and any debt that
+    /// still fits within the remaining budget is included.
+    pub fn build(
+        debts: &[(u64, ComputedSolanaValidatorDebts)],
+        max_amount: Option<u64>,
+        only_node_ids: &[Pubkey],
+        strategy: AllocationStrategy,
+    ) -> Self {
+        let mut candidates: Vec<PaymentItem> = debts
+            .iter()
+            .flat_map(|(dz_epoch, computed)| {
+                computed.debts.iter().map(|debt| PaymentItem {
+                    dz_epoch: *dz_epoch,
+                    node_id: debt.node_id,
+                    amount: debt.amount,
+                })
+            })
+            // Waived debts are recorded with a zero amount and need no payment
+            .filter(|item| item.amount > 0)
+            .filter(|item| only_node_ids.is_empty() || only_node_ids.contains(&item.node_id))
+            .collect();
+
+        match strategy {
+            AllocationStrategy::OldestEpochFirst => candidates.sort_by(|a, b| {
+                a.dz_epoch
+                    .cmp(&b.dz_epoch)
+                    .then(b.amount.cmp(&a.amount))
+                    .then(a.node_id.cmp(&b.node_id))
+            }),
+            AllocationStrategy::LargestDebtFirst => candidates.sort_by(|a, b| {
+                b.amount
+                    .cmp(&a.amount)
+                    .then(a.dz_epoch.cmp(&b.dz_epoch))
+                    .then(a.node_id.cmp(&b.node_id))
+            }),
+        }
+
+        let mut remaining = max_amount.unwrap_or(u64::MAX);
+        let mut plan = Self::default();
+        for item in candidates {
+            if item.amount <= remaining {
+                remaining -= item.amount;
+                plan.items.push(item);
+            } else {
+                plan.deferred.push(item);
+            }
+        }
+
+        plan
+    }
+
+    pub fn total(&self) -> u64 {
+        self.items.iter().map(|item| item.amount).sum()
+    }
+
+    pub fn deferred_total(&self) -> u64 {
+        self.deferred.iter().map(|item| item.amount).sum()
+    }
+
+    /// Debts to pay for a given DZ epoch, in payment order
+    pub fn items_for_epoch(&self, dz_epoch: u64) -> Vec<&PaymentItem> {
+        self.items
+            .iter()
+            .filter(|item| item.dz_epoch == dz_epoch)
+            .collect()
+    }
+
+    /// Node IDs to pay for a given DZ epoch, in payment order
+    pub fn node_ids_for_epoch(&self, dz_epoch: u64) -> Vec<Pubkey> {
+        self.items_for_epoch(dz_epoch)
+            .into_iter()
+            .map(|item| item.node_id)
+            .collect()
+    }
+
+    pub fn to_table(&self) -> String {
+        Table::new(&self.items)
+            .with(Style::psql().remove_horizontals())
+            .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validator_debt::ComputedSolanaValidatorDebt;
+    use svm_hash::sha2::Hash;
+
+    fn computed(debts: &[(Pubkey, u64)]) -> ComputedSolanaValidatorDebts {
+        ComputedSolanaValidatorDebts {
+            blockhash: Hash::new_unique(),
+            first_solana_epoch: 822,
+            last_solana_epoch: 822,
+            debts: debts
+                .iter()
+                .map(|(node_id, amount)| ComputedSolanaValidatorDebt {
+                    node_id: *node_id,
+                    amount: *amount,
+                })
+                .collect(),
+            fee_parameters: None,
+            inflation_commission: None,
+            debt_waiver: None,
+        }
+    }
+
+    #[test]
+    fn test_oldest_epoch_first_within_budget() {
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let debts = vec![
+            (11, computed(&[(a, 500)])),
+            (10, computed(&[(a, 300), (b, 100)])),
+        ];
+
+        let plan = PaymentPlan::build(&debts, Some(450), &[], AllocationStrategy::OldestEpochFirst);
+
+        assert_eq!(plan.total(), 400);
+        assert_eq!(plan.node_ids_for_epoch(10), vec![a, b]);
+        assert!(plan.node_ids_for_epoch(11).is_empty());
+        assert_eq!(plan.deferred_total(), 500);
+    }
+
+    #[test]
+    fn test_largest_debt_first_with_node_filter() {
+        let (a, b, c) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let debts = vec![
+            (10, computed(&[(a, 300), (b, 100)])),
+            (11, computed(&[(a, 500), (c, 1_000)])),
+        ];
+
+        let plan = PaymentPlan::build(
+            &debts,
+            Some(600),
+            &[a, b],
+            AllocationStrategy::LargestDebtFirst,
+        );
+
+        assert_eq!(
+            plan.items,
+            vec![
+                PaymentItem {
+                    dz_epoch: 11,
+                    node_id: a,
+                    amount: 500
+                },
+                PaymentItem {
+                    dz_epoch: 10,
+                    node_id: b,
+                    amount: 100
+                },
+            ]
+        );
+        assert_eq!(plan.deferred.len(), 1);
+    }
+}
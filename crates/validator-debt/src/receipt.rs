@@ -0,0 +1,166 @@
+use anyhow::{Context, Result};
+use borsh::{BorshDeserialize, BorshSerialize};
+use doublezero_sdk::record::{self, state::read_record_data};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+};
+use tabled::{Table, Tabled, settings::Style};
+
+use crate::{ledger, rpc::MAX_ACCOUNTS_PER_REQUEST};
+
+/// Seed prefix of payment receipt records on the DZ ledger.
+pub const RECEIPT_SEED_PREFIX: &[u8] = b"solana_validator_debt_receipt";
+
+/// Receipt of a paid validator debt, kept on the DZ ledger next to the debt
+/// records so the payment trail does not depend on Solana history retention.
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, Tabled)]
+pub struct PaymentReceipt {
+    pub dz_epoch: u64,
+    pub node_id: Pubkey,
+    pub amount: u64,
+    /// Solana signature of the payment transaction.
+    #[tabled(display = "display_signature")]
+    pub signature: [u8; 64],
+    pub payer: Pubkey,
+}
+
+impl PaymentReceipt {
+    pub fn new(
+        dz_epoch: u64,
+        node_id: Pubkey,
+        amount: u64,
+        signature: &Signature,
+        payer: Pubkey,
+    ) -> Self {
+        let mut signature_bytes = [0u8; 64];
+        signature_bytes.copy_from_slice(signature.as_ref());
+        Self {
+            dz_epoch,
+            node_id,
+            amount,
+            signature: signature_bytes,
+            payer,
+        }
+    }
+
+    pub fn signature(&self) -> Signature {
+        Signature::from(self.signature)
+    }
+}
+
+fn display_signature(signature: &[u8; 64]) -> String {
+    Signature::from(*signature).to_string()
+}
+
+/// One receipt record per node and DZ epoch, since each debt is paid once.
+fn receipt_seeds<'a>(dz_epoch_bytes: &'a [u8; 8], node_id: &'a Pubkey) -> [&'a [u8]; 3] {
+    [RECEIPT_SEED_PREFIX, dz_epoch_bytes, node_id.as_ref()]
+}
+
+/// Write `receipt` to the DZ ledger under `payer_signer`.
+pub async fn write_receipt(
+    rpc_client: &RpcClient,
+    payer_signer: &Keypair,
+    receipt: &PaymentReceipt,
+    commitment_config: CommitmentConfig,
+) -> Result<()> {
+    let dz_epoch_bytes = receipt.dz_epoch.to_le_bytes();
+    let seeds = receipt_seeds(&dz_epoch_bytes, &receipt.node_id);
+    let recent_blockhash = rpc_client.get_latest_blockhash().await?;
+
+    ledger::create_record_on_ledger(
+        rpc_client,
+        recent_blockhash,
+        payer_signer,
+        receipt,
+        commitment_config,
+        &seeds,
+    )
+    .await
+}
+
+/// Receipts written by `payer_key` for the given node IDs in `dz_epoch`.
+/// Nodes without a receipt are skipped.
+pub async fn fetch_receipts(
+    rpc_client: &RpcClient,
+    payer_key: &Pubkey,
+    dz_epoch: u64,
+    node_ids: &[Pubkey],
+) -> Result<Vec<PaymentReceipt>> {
+    let dz_epoch_bytes = dz_epoch.to_le_bytes();
+
+    let mut receipts = Vec::new();
+    for chunk in node_ids.chunks(MAX_ACCOUNTS_PER_REQUEST) {
+        let keys = chunk
+            .iter()
+            .map(|node_id| {
+                record::pubkey::create_record_key(
+                    payer_key,
+                    &receipt_seeds(&dz_epoch_bytes, node_id),
+                )
+            })
+            .collect::<Vec<_>>();
+        let accounts = rpc_client.get_multiple_accounts(&keys).await?;
+
+        for (key, account) in keys.iter().zip(accounts) {
+            let Some(account) = account else {
+                continue;
+            };
+            let (_, body) = read_record_data(&account.data)
+                .with_context(|| format!("Failed to parse record data from account {key}"))?;
+            let receipt = PaymentReceipt::try_from_slice(body)
+                .with_context(|| format!("Failed to deserialize payment receipt {key}"))?;
+            receipts.push(receipt);
+        }
+    }
+
+    Ok(receipts)
+}
+
+pub fn to_table(receipts: &[PaymentReceipt]) -> String {
+    Table::new(receipts)
+        .with(Style::psql().remove_horizontals())
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_receipt_round_trip() {
+        let signature = Signature::from([7u8; 64]);
+        let receipt = PaymentReceipt::new(
+            42,
+            Pubkey::new_unique(),
+            1_500,
+            &signature,
+            Pubkey::new_unique(),
+        );
+
+        let serialized = borsh::to_vec(&receipt).unwrap();
+        let deserialized = PaymentReceipt::try_from_slice(&serialized).unwrap();
+        assert_eq!(deserialized, receipt);
+        assert_eq!(deserialized.signature(), signature);
+        assert!(to_table(&[receipt]).contains(&signature.to_string()));
+    }
+
+    #[test]
+    fn test_receipt_seeds_per_node_and_epoch() {
+        let payer = Pubkey::new_unique();
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let key = |dz_epoch: u64, node_id: &Pubkey| {
+            record::pubkey::create_record_key(
+                &payer,
+                &receipt_seeds(&dz_epoch.to_le_bytes(), node_id),
+            )
+        };
+
+        assert_eq!(key(10, &a), key(10, &a));
+        assert_ne!(key(10, &a), key(10, &b));
+        assert_ne!(key(10, &a), key(11, &a));
+    }
+}
@@ -5,13 +5,19 @@
 //! - JITO rewards per epoch
 //!
 //! The rewards from all sources for an epoch are summed and associated with a validator_id
-use crate::{block, inflation, jito};
+use crate::{
+    block,
+    inflation::{self, InflationCommission},
+    jito,
+    vote_accounts::{self, VoteAccountMap},
+};
 
 use anyhow::{Result, anyhow};
 use borsh::{BorshDeserialize, BorshSerialize};
-use serde::Deserialize;
-use solana_sdk::clock::DEFAULT_SLOTS_PER_EPOCH;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use solana_sdk::{clock::DEFAULT_SLOTS_PER_EPOCH, pubkey::Pubkey};
+use std::{collections::HashMap, str::FromStr};
+use tabled::Tabled;
 
 use crate::solana_debt_calculator::ValidatorRewards;
 
@@ -23,7 +29,9 @@ pub struct EpochRewards {
     pub rewards: Vec<Reward>,
 }
 
-#[derive(Deserialize, Debug, BorshDeserialize, BorshSerialize)]
+#[derive(
+    Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Tabled, BorshDeserialize, BorshSerialize,
+)]
 pub struct Reward {
     pub epoch: u64,
     pub validator_id: String,
@@ -39,6 +47,7 @@ pub async fn get_rewards_between_timestamps(
     start_timestamp: u64,
     end_timestamp: u64,
     validator_ids: &[String],
+    inflation_commission: InflationCommission,
 ) -> Result<HashMap<u64, Vec<Reward>>> {
     let mut rewards: HashMap<u64, Vec<Reward>> = HashMap::new();
     let current_slot = solana_debt_calculator.get_slot().await?;
@@ -48,24 +57,67 @@ pub async fn get_rewards_between_timestamps(
     let start_epoch = epoch_from_timestamp(block_time, current_slot, start_timestamp)?;
     let end_epoch = epoch_from_timestamp(block_time, current_slot, end_timestamp)?;
     for epoch in start_epoch..=end_epoch {
-        let reward = get_total_rewards(solana_debt_calculator, validator_ids, epoch).await?;
+        let reward = get_total_rewards(
+            solana_debt_calculator,
+            validator_ids,
+            epoch,
+            inflation_commission,
+        )
+        .await?;
         rewards.insert(epoch, reward.rewards);
     }
     Ok(rewards)
 }
 
-// this function will return a hashmap of total rewards keyed by validator pubkey
+// this function will return the total rewards for each validator, sorted by validator pubkey
+//
+// Reward sources are fetched concurrently and complete in arbitrary order, so the output is
+// assembled in sorted node id order to keep the resulting debt record (and merkle root)
+// reproducible.
 pub async fn get_total_rewards(
     solana_debt_calculator: &impl ValidatorRewards,
     validator_ids: &[String],
     epoch: u64,
+    inflation_commission: InflationCommission,
 ) -> Result<EpochRewards> {
+    let (rewards, _) = get_total_rewards_with_vote_accounts(
+        solana_debt_calculator,
+        validator_ids,
+        epoch,
+        inflation_commission,
+    )
+    .await?;
+    Ok(rewards)
+}
+
+/// Total rewards along with the vote account to identity mapping Jito tips were attributed with
+pub async fn get_total_rewards_with_vote_accounts(
+    solana_debt_calculator: &impl ValidatorRewards,
+    validator_ids: &[String],
+    epoch: u64,
+    inflation_commission: InflationCommission,
+) -> Result<(EpochRewards, VoteAccountMap)> {
+    let validator_ids = sorted_validator_ids(validator_ids);
     let mut validator_rewards: Vec<Reward> = Vec::with_capacity(validator_ids.len());
 
+    let vote_accounts = vote_accounts::fetch_vote_accounts(solana_debt_calculator, epoch).await?;
+    let vote_account_map = VoteAccountMap::new(&vote_accounts, epoch);
+
     let (inflation_rewards, jito_rewards, block_rewards) = tokio::join!(
-        inflation::get_inflation_rewards(solana_debt_calculator, validator_ids, epoch,),
-        jito::get_jito_rewards(solana_debt_calculator, validator_ids, epoch),
-        block::get_block_rewards(solana_debt_calculator, validator_ids, epoch,)
+        inflation::get_inflation_rewards_for_vote_accounts(
+            solana_debt_calculator,
+            &vote_accounts,
+            &validator_ids,
+            epoch,
+            inflation_commission
+        ),
+        jito::get_jito_rewards(
+            solana_debt_calculator,
+            &validator_ids,
+            &vote_account_map,
+            epoch
+        ),
+        block::get_block_rewards(solana_debt_calculator, &validator_ids, epoch,)
     );
 
     let inflation_rewards = inflation_rewards?;
@@ -73,7 +125,7 @@ pub async fn get_total_rewards(
 
     let block_rewards = block_rewards?;
 
-    for validator_id in validator_ids {
+    for validator_id in &validator_ids {
         let mut total_reward: u64 = 0;
         let jito_reward = jito_rewards
             .get(validator_id.as_str())
@@ -102,7 +154,20 @@ pub async fn get_total_rewards(
         epoch,
         rewards: validator_rewards,
     };
-    Ok(rewards)
+    Ok((rewards, vote_account_map))
+}
+
+/// Deduplicate validator ids and sort them by their pubkey bytes
+fn sorted_validator_ids(validator_ids: &[String]) -> Vec<String> {
+    let mut validator_ids = validator_ids.to_vec();
+    validator_ids.sort_by_cached_key(|validator_id| {
+        let pubkey_bytes = Pubkey::from_str(validator_id)
+            .map(|pubkey| pubkey.to_bytes())
+            .unwrap_or_default();
+        (pubkey_bytes, validator_id.clone())
+    });
+    validator_ids.dedup();
+    validator_ids
 }
 
 // get the number of slots by subtracting the timestamp from the block time and dividing it by the time per slot
@@ -138,6 +203,7 @@ mod tests {
     async fn test_get_rewards_between_timestamps() {
         // Set up test variables and mock data.
         let validator_id = "6WgdYhhGE53WrZ7ywJA15hBVkw7CRbQ8yDBBTwmBtAHN";
+        let vote_pubkey = "6WgdYhhGE53WrZ7ywJA15hBVkw7CRbQ8yDBBTwmBtBBN";
         let validator_ids: &[String] = &[String::from(validator_id)];
         let epoch = 824;
         let block_reward: u64 = 40000;
@@ -214,7 +280,7 @@ mod tests {
                 Ok(JitoRewards {
                     total_count: 1000,
                     rewards: vec![JitoReward {
-                        vote_account: validator_id.to_string(),
+                        vote_account: vote_pubkey.to_string(),
                         mev_revenue: jito_reward,
                     }],
                 })
@@ -233,7 +299,7 @@ mod tests {
 
         let mock_rpc_vote_account_status = RpcVoteAccountStatus {
             current: vec![RpcVoteAccountInfo {
-                vote_pubkey: "6WgdYhhGE53WrZ7ywJA15hBVkw7CRbQ8yDBBTwmBtBBN".to_string(),
+                vote_pubkey: vote_pubkey.to_string(),
                 node_pubkey: validator_id.to_string(),
                 activated_stake: 4_200_000_000_000,
                 epoch_vote_account: true,
@@ -278,6 +344,7 @@ mod tests {
             start_timestamp,
             end_timestamp,
             validator_ids,
+            InflationCommission::PostCommission,
         )
         .await
         .unwrap();
@@ -299,6 +366,7 @@ mod tests {
     async fn test_get_total_rewards() {
         // Set up test variables and mock data.
         let validator_id = "6WgdYhhGE53WrZ7ywJA15hBVkw7CRbQ8yDBBTwmBtAHN";
+        let vote_pubkey = "6WgdYhhGE53WrZ7ywJA15hBVkw7CRbQ8yDBBTwmBtABB";
         let validator_ids: &[String] = &[String::from(validator_id)];
         let epoch = 823;
         let block_reward: u64 = 40000;
@@ -311,7 +379,7 @@ mod tests {
         // These mocks simulate the behavior of external dependencies.
         let mock_rpc_vote_account_status = RpcVoteAccountStatus {
             current: vec![RpcVoteAccountInfo {
-                vote_pubkey: "6WgdYhhGE53WrZ7ywJA15hBVkw7CRbQ8yDBBTwmBtABB".to_string(),
+                vote_pubkey: vote_pubkey.to_string(),
                 node_pubkey: validator_id.to_string(),
                 activated_stake: 4_200_000_000_000,
                 epoch_vote_account: true,
@@ -397,16 +465,21 @@ mod tests {
                 Ok(JitoRewards {
                     total_count: 1000,
                     rewards: vec![JitoReward {
-                        vote_account: validator_id.to_string(),
+                        vote_account: vote_pubkey.to_string(),
                         mev_revenue: jito_reward,
                     }],
                 })
             });
 
         // Call the function under test with the prepared data and mocks.
-        let rewards = get_total_rewards(&mock_solana_debt_calculator, validator_ids, epoch)
-            .await
-            .unwrap();
+        let rewards = get_total_rewards(
+            &mock_solana_debt_calculator,
+            validator_ids,
+            epoch,
+            InflationCommission::PostCommission,
+        )
+        .await
+        .unwrap();
 
         // Verify that the function produced the correct results.
         let reward = rewards
@@ -18,6 +18,9 @@ use url::Url;
 
 use crate::solana_debt_calculator::SolanaDebtCalculator;
 
+/// `getMultipleAccounts` accepts at most this many keys per request.
+pub const MAX_ACCOUNTS_PER_REQUEST: usize = 100;
+
 #[derive(Debug, Args)]
 pub struct SolanaValidatorDebtConnectionOptions {
     /// URL for DoubleZero Ledger's JSON RPC. Required.
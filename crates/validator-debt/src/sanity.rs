@@ -0,0 +1,242 @@
+use std::{collections::HashMap, fmt};
+
+use solana_client::rpc_response::RpcVoteAccountStatus;
+
+/// Default factor a validator's debt may deviate from its stake-weighted expectation
+pub const DEFAULT_SANITY_TOLERANCE: f64 = 10.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SanityCheckConfig {
+    /// Debts more than `tolerance` times above or below the expectation are flagged
+    pub tolerance: f64,
+    /// Refuse to submit the distribution when any validator is flagged
+    pub block_submission: bool,
+}
+
+impl Default for SanityCheckConfig {
+    fn default() -> Self {
+        Self {
+            tolerance: DEFAULT_SANITY_TOLERANCE,
+            block_submission: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SanityFlag {
+    WithinBand,
+    AboveBand,
+    BelowBand,
+    MissingVoteAccount,
+}
+
+impl SanityFlag {
+    pub fn is_flagged(&self) -> bool {
+        *self != Self::WithinBand
+    }
+}
+
+impl fmt::Display for SanityFlag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WithinBand => write!(f, "ok"),
+            Self::AboveBand => write!(f, "above band"),
+            Self::BelowBand => write!(f, "below band"),
+            Self::MissingVoteAccount => write!(f, "no vote account"),
+        }
+    }
+}
+
+/// Outcome of comparing computed debts against stake-weighted expectations
+#[derive(Debug, Default)]
+pub struct SanityReport {
+    /// Debt per unit of weight everything is compared against
+    pub debt_per_weight: f64,
+    pub flags: HashMap<String, SanityFlag>,
+}
+
+impl SanityReport {
+    pub fn flag(&self, validator_id: &str) -> SanityFlag {
+        self.flags
+            .get(validator_id)
+            .copied()
+            .unwrap_or(SanityFlag::WithinBand)
+    }
+
+    pub fn flagged(&self) -> Vec<(&String, SanityFlag)> {
+        let mut flagged = self
+            .flags
+            .iter()
+            .filter(|(_, flag)| flag.is_flagged())
+            .map(|(validator_id, flag)| (validator_id, *flag))
+            .collect::<Vec<_>>();
+        flagged.sort();
+        flagged
+    }
+}
+
+/// Stake weight of each validator (keyed by node id) for a Solana epoch
+///
+/// The weight is activated stake scaled by the vote credits earned in the epoch relative
+/// to the best performing validator, so inactive voters carry less weight.
+pub fn validator_weights(
+    vote_accounts: &RpcVoteAccountStatus,
+    solana_epoch: u64,
+) -> HashMap<String, f64> {
+    let accounts = vote_accounts
+        .current
+        .iter()
+        .chain(&vote_accounts.delinquent)
+        .map(|account| {
+            let credits = account
+                .epoch_credits
+                .iter()
+                .find(|(epoch, _, _)| *epoch == solana_epoch)
+                .map(|(_, credits, previous_credits)| credits.saturating_sub(*previous_credits))
+                .unwrap_or_default();
+            (account, credits)
+        })
+        .collect::<Vec<_>>();
+
+    let max_credits = accounts
+        .iter()
+        .map(|(_, credits)| *credits)
+        .max()
+        .unwrap_or_default();
+
+    accounts
+        .into_iter()
+        .map(|(account, credits)| {
+            let performance = if max_credits == 0 {
+                1.0
+            } else {
+                credits as f64 / max_credits as f64
+            };
+            (
+                account.node_pubkey.clone(),
+                account.activated_stake as f64 * performance,
+            )
+        })
+        .collect()
+}
+
+/// Compare each validator's stake-dependent debt against its stake-weighted expectation
+///
+/// The expectation is the validator's weight times the median debt per unit of weight, so
+/// the check needs no knowledge of the epoch's total rewards. Fixed per-validator charges
+/// should be excluded from `debts` beforehand.
+pub fn check_debts(
+    debts: &[(String, u64)],
+    weights: &HashMap<String, f64>,
+    tolerance: f64,
+) -> SanityReport {
+    let mut ratios = debts
+        .iter()
+        .filter_map(|(validator_id, debt)| {
+            let weight = weights.get(validator_id).copied()?;
+            (weight > 0.0 && *debt > 0).then(|| *debt as f64 / weight)
+        })
+        .collect::<Vec<_>>();
+    ratios.sort_by(f64::total_cmp);
+
+    let Some(debt_per_weight) = median(&ratios) else {
+        return SanityReport::default();
+    };
+
+    let flags = debts
+        .iter()
+        .map(|(validator_id, debt)| {
+            let flag = match weights.get(validator_id) {
+                None if *debt > 0 => SanityFlag::MissingVoteAccount,
+                None => SanityFlag::WithinBand,
+                Some(weight) => {
+                    let expected = weight * debt_per_weight;
+                    let debt = *debt as f64;
+                    if debt > expected * tolerance {
+                        SanityFlag::AboveBand
+                    } else if debt < expected / tolerance {
+                        SanityFlag::BelowBand
+                    } else {
+                        SanityFlag::WithinBand
+                    }
+                }
+            };
+            (validator_id.clone(), flag)
+        })
+        .collect();
+
+    SanityReport {
+        debt_per_weight,
+        flags,
+    }
+}
+
+fn median(sorted: &[f64]) -> Option<f64> {
+    match sorted.len() {
+        0 => None,
+        len if len % 2 == 0 => Some((sorted[len / 2 - 1] + sorted[len / 2]) / 2.0),
+        len => Some(sorted[len / 2]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_client::rpc_response::RpcVoteAccountInfo;
+
+    fn vote_account(node: &str, activated_stake: u64, credits: u64) -> RpcVoteAccountInfo {
+        RpcVoteAccountInfo {
+            vote_pubkey: format!("{node}-vote"),
+            node_pubkey: node.to_string(),
+            activated_stake,
+            epoch_vote_account: true,
+            epoch_credits: vec![(812, 1_000 + credits, 1_000), (811, 1_000, 500)],
+            commission: 10,
+            last_vote: 123456789,
+            root_slot: 123456700,
+        }
+    }
+
+    #[test]
+    fn test_validator_weights() {
+        let status = RpcVoteAccountStatus {
+            current: vec![vote_account("a", 1_000, 400), vote_account("b", 1_000, 200)],
+            delinquent: vec![vote_account("c", 500, 0)],
+        };
+
+        let weights = validator_weights(&status, 812);
+        assert_eq!(weights["a"], 1_000.0);
+        assert_eq!(weights["b"], 500.0);
+        assert_eq!(weights["c"], 0.0);
+    }
+
+    #[test]
+    fn test_check_debts() {
+        let weights = HashMap::from([
+            ("a".to_string(), 1_000.0),
+            ("b".to_string(), 2_000.0),
+            ("c".to_string(), 3_000.0),
+            ("d".to_string(), 1_000.0),
+            ("e".to_string(), 1_000.0),
+        ]);
+        let debts = vec![
+            ("a".to_string(), 100),
+            ("b".to_string(), 200),
+            ("c".to_string(), 300),
+            // 50x its expectation
+            ("d".to_string(), 5_000),
+            // A fraction of its expectation
+            ("e".to_string(), 1),
+            ("f".to_string(), 100),
+        ];
+
+        let report = check_debts(&debts, &weights, DEFAULT_SANITY_TOLERANCE);
+        assert_eq!(report.debt_per_weight, 0.1);
+        assert_eq!(report.flag("a"), SanityFlag::WithinBand);
+        assert_eq!(report.flag("c"), SanityFlag::WithinBand);
+        assert_eq!(report.flag("d"), SanityFlag::AboveBand);
+        assert_eq!(report.flag("e"), SanityFlag::BelowBand);
+        assert_eq!(report.flag("f"), SanityFlag::MissingVoteAccount);
+        assert_eq!(report.flagged().len(), 3);
+    }
+}
@@ -177,12 +177,25 @@ impl Transaction {
         solana_rpc_client: &RpcClient,
         debt: ComputedSolanaValidatorDebts,
         dz_epoch: u64,
+    ) -> Result<Vec<VersionedTransaction>> {
+        let node_ids: Vec<Pubkey> = debt.debts.iter().map(|d| d.node_id).collect();
+        self.pay_solana_validator_debt_for_node_ids(solana_rpc_client, &debt, &node_ids, dz_epoch)
+            .await
+    }
+
+    /// Build payment transactions for only the given node IDs, in the given order
+    pub async fn pay_solana_validator_debt_for_node_ids(
+        &self,
+        solana_rpc_client: &RpcClient,
+        debt: &ComputedSolanaValidatorDebts,
+        node_ids: &[Pubkey],
+        dz_epoch: u64,
     ) -> Result<Vec<VersionedTransaction>> {
         let mut transactions: Vec<VersionedTransaction> = Vec::new();
-        let debt_clone = debt.clone();
-        for d in debt.debts {
-            let debt_proof = debt_clone.find_debt_proof(&d.node_id).unwrap();
-            let (_, proof) = debt_proof;
+        for node_id in node_ids {
+            let (d, proof) = debt
+                .find_debt_proof(node_id)
+                .ok_or_else(|| anyhow!("No debt for {node_id} in DZ epoch {dz_epoch}"))?;
             let instruction = try_build_instruction(
                 &ID,
                 PaySolanaValidatorDebtAccounts::new(DoubleZeroEpoch::new(dz_epoch), &d.node_id),
@@ -211,17 +224,22 @@ impl Transaction {
         dz_epoch: u64,
         rpc_client: &RpcClient,
     ) -> Result<Distribution> {
-        let (distribution_key, _bump) = Distribution::find_address(DoubleZeroEpoch::new(dz_epoch));
-        let distribution_account = rpc_client.get_account(&distribution_key).await?;
+        fetch_distribution(rpc_client, dz_epoch).await
+    }
+}
 
-        let distribution_state = zero_copy::checked_from_bytes_with_discriminator::<Distribution>(
-            &distribution_account.data,
-        )
-        .expect("Failed to deserialize Distribution account data.")
-        .0;
+/// Fetch the distribution for `dz_epoch`; needs no signer
+pub async fn fetch_distribution(rpc_client: &RpcClient, dz_epoch: u64) -> Result<Distribution> {
+    let (distribution_key, _bump) = Distribution::find_address(DoubleZeroEpoch::new(dz_epoch));
+    let distribution_account = rpc_client.get_account(&distribution_key).await?;
 
-        Ok(*distribution_state)
-    }
+    let distribution_state = zero_copy::checked_from_bytes_with_discriminator::<Distribution>(
+        &distribution_account.data,
+    )
+    .expect("Failed to deserialize Distribution account data.")
+    .0;
+
+    Ok(*distribution_state)
 }
 
 #[cfg(test)]
@@ -307,6 +325,9 @@ mod tests {
                 node_id: Pubkey::from_str("va1i6T6vTcijrCz6G8r89H6igKjwkLfF6g5fnpvZu1b").unwrap(),
                 amount: 707,
             }],
+            fee_parameters: None,
+            inflation_commission: None,
+            debt_waiver: None,
         };
         let debt_proof = record.find_debt_proof(
             &Pubkey::from_str("va1i6T6vTcijrCz6G8r89H6igKjwkLfF6g5fnpvZu1b").unwrap(),
@@ -1,13 +1,73 @@
+use std::io::{self, Read};
+
 use borsh::{BorshDeserialize, BorshSerialize};
+use doublezero_revenue_distribution::state::Distribution;
 use solana_sdk::{hash::Hash, pubkey::Pubkey};
 use svm_hash::merkle::{MerkleProof, merkle_root_from_indexed_byte_ref_leaves};
 
-#[derive(Debug, Default, BorshDeserialize, BorshSerialize, Clone, PartialEq, Eq)]
+use crate::{
+    fee_history::DebtFeeParameters, inflation::InflationCommission, rewards::Reward,
+    waiver::DebtWaiver,
+};
+
+#[derive(Debug, Default, BorshSerialize, Clone, PartialEq, Eq)]
 pub struct ComputedSolanaValidatorDebts {
     pub blockhash: Hash,
     pub first_solana_epoch: u64,
     pub last_solana_epoch: u64,
     pub debts: Vec<ComputedSolanaValidatorDebt>,
+    /// Fee parameters the debts were computed with. Records written before
+    /// these were stored end after `debts` and deserialize as `None`.
+    pub fee_parameters: Option<DebtFeeParameters>,
+    /// Whether inflation rewards were charged before or after the validator's
+    /// commission. Follows `fee_parameters` and is `None` in older records.
+    pub inflation_commission: Option<InflationCommission>,
+    /// Debts waived for falling below the configured thresholds. Follows
+    /// `inflation_commission` and is `None` in older records.
+    pub debt_waiver: Option<DebtWaiver>,
+}
+
+impl BorshDeserialize for ComputedSolanaValidatorDebts {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let blockhash = Hash::deserialize_reader(reader)?;
+        let first_solana_epoch = u64::deserialize_reader(reader)?;
+        let last_solana_epoch = u64::deserialize_reader(reader)?;
+        let debts = Vec::<ComputedSolanaValidatorDebt>::deserialize_reader(reader)?;
+        let fee_parameters = deserialize_trailing_option(reader, "fee parameters")?;
+        let inflation_commission = deserialize_trailing_option(reader, "inflation commission")?;
+        let debt_waiver = deserialize_trailing_option(reader, "debt waiver")?;
+
+        Ok(Self {
+            blockhash,
+            first_solana_epoch,
+            last_solana_epoch,
+            debts,
+            fee_parameters,
+            inflation_commission,
+            debt_waiver,
+        })
+    }
+}
+
+/// Read an optional field appended to the record after it was first
+/// published. Records that end before the field deserialize it as `None`.
+fn deserialize_trailing_option<T: BorshDeserialize, R: Read>(
+    reader: &mut R,
+    field: &str,
+) -> io::Result<Option<T>> {
+    let mut tag = [0u8; 1];
+    if reader.read(&mut tag)? == 0 {
+        return Ok(None);
+    }
+
+    match tag[0] {
+        0 => Ok(None),
+        1 => Ok(Some(T::deserialize_reader(reader)?)),
+        tag => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid {field} tag {tag}"),
+        )),
+    }
 }
 
 impl ComputedSolanaValidatorDebts {
@@ -55,6 +115,25 @@ pub struct ComputedSolanaValidatorDebt {
 impl ComputedSolanaValidatorDebt {
     pub const LEAF_PREFIX: &'static [u8] = b"solana_validator_debt";
 
+    /// Debt owed on `reward` under the distribution's validator fee parameters
+    pub fn from_reward(node_id: Pubkey, reward: &Reward, distribution: &Distribution) -> Self {
+        let fee_parameters = &distribution.solana_validator_fee_parameters;
+        Self {
+            node_id,
+            amount: fee_parameters
+                .base_block_rewards_pct
+                .mul_scalar(reward.block_base)
+                + fee_parameters
+                    .priority_block_rewards_pct
+                    .mul_scalar(reward.block_priority)
+                + fee_parameters.jito_tips_pct.mul_scalar(reward.jito)
+                + fee_parameters
+                    .inflation_rewards_pct
+                    .mul_scalar(reward.inflation)
+                + fee_parameters.fixed_sol_amount as u64,
+        }
+    }
+
     pub fn merkle_root(&self, proof: MerkleProof) -> svm_hash::sha2::Hash {
         let mut leaf = [0; 40];
 
@@ -86,6 +165,9 @@ mod tests {
                     amount: 234234324,
                 },
             ],
+            fee_parameters: None,
+            inflation_commission: None,
+            debt_waiver: None,
         };
 
         let leaf_prefix = Some(ComputedSolanaValidatorDebt::LEAF_PREFIX);
@@ -130,4 +212,78 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_fee_parameters_are_optional_in_records() -> Result<()> {
+        let mut debts = ComputedSolanaValidatorDebts {
+            blockhash: Hash::new_unique(),
+            first_solana_epoch: 822,
+            last_solana_epoch: 822,
+            debts: vec![ComputedSolanaValidatorDebt {
+                node_id: Pubkey::new_unique(),
+                amount: 707,
+            }],
+            fee_parameters: Some(DebtFeeParameters {
+                base_block_rewards_bps: 500,
+                priority_block_rewards_bps: 500,
+                inflation_rewards_bps: 0,
+                jito_tips_bps: 250,
+                fixed_sol_amount: 1_000,
+            }),
+            inflation_commission: Some(InflationCommission::PreCommission),
+            debt_waiver: Some(DebtWaiver {
+                min_validator_debt: 1_000,
+                min_total_debt: 0,
+                distribution_waived: false,
+                waived: vec![ComputedSolanaValidatorDebt {
+                    node_id: Pubkey::new_unique(),
+                    amount: 5,
+                }],
+            }),
+        };
+        let decoded: ComputedSolanaValidatorDebts = borsh::from_slice(&borsh::to_vec(&debts)?)?;
+        assert_eq!(decoded, debts);
+
+        // Records written before debt waivers were stored end after the
+        // inflation commission.
+        let without_waiver = borsh::to_vec(&(
+            debts.blockhash,
+            debts.first_solana_epoch,
+            debts.last_solana_epoch,
+            &debts.debts,
+            debts.fee_parameters,
+            debts.inflation_commission,
+        ))?;
+        let decoded: ComputedSolanaValidatorDebts = borsh::from_slice(&without_waiver)?;
+        assert_eq!(decoded.inflation_commission, debts.inflation_commission);
+        assert_eq!(decoded.debt_waiver, None);
+
+        // Records written before the inflation commission was stored end after
+        // the fee parameters.
+        let without_commission = borsh::to_vec(&(
+            debts.blockhash,
+            debts.first_solana_epoch,
+            debts.last_solana_epoch,
+            &debts.debts,
+            debts.fee_parameters,
+        ))?;
+        let decoded: ComputedSolanaValidatorDebts = borsh::from_slice(&without_commission)?;
+        assert_eq!(decoded.fee_parameters, debts.fee_parameters);
+        assert_eq!(decoded.inflation_commission, None);
+
+        // Records written before fee parameters were stored end after the debts.
+        let legacy = borsh::to_vec(&(
+            debts.blockhash,
+            debts.first_solana_epoch,
+            debts.last_solana_epoch,
+            &debts.debts,
+        ))?;
+        let decoded: ComputedSolanaValidatorDebts = borsh::from_slice(&legacy)?;
+        debts.fee_parameters = None;
+        debts.inflation_commission = None;
+        debts.debt_waiver = None;
+        assert_eq!(decoded, debts);
+
+        Ok(())
+    }
 }
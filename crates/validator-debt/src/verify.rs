@@ -0,0 +1,303 @@
+//! Independent verification of a validator's debt from public data
+//!
+//! Reads the debt record the debt accountant posted to DoubleZero Ledger and the on-chain
+//! distribution, recomputes the validator's debt from its public rewards, and checks that
+//! the recorded entry is part of the merkle root the distribution holds. No keypair is
+//! needed: the merkle check is a simulation with signature verification disabled.
+
+use std::fmt;
+
+use anyhow::{Context, Result, anyhow};
+use doublezero_program_tools::{instruction::try_build_instruction, zero_copy};
+use doublezero_revenue_distribution::{
+    ID,
+    instruction::{
+        DistributionMerkleRootKind, RevenueDistributionInstructionData,
+        account::VerifyDistributionMerkleRootAccounts,
+    },
+    state::ProgramConfig,
+    types::{DoubleZeroEpoch, SolanaValidatorDebt},
+};
+use solana_client::{nonblocking::rpc_client::RpcClient, rpc_config::RpcSimulateTransactionConfig};
+use solana_sdk::{
+    message::{VersionedMessage, v0::Message},
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::VersionedTransaction,
+};
+use svm_hash::merkle::MerkleProof;
+
+use crate::{
+    ledger, rewards,
+    solana_debt_calculator::ValidatorRewards,
+    transaction,
+    validator_debt::{ComputedSolanaValidatorDebt, ComputedSolanaValidatorDebts},
+    worker::SOLANA_SEED_PREFIX,
+};
+
+/// What the public record says a validator owes, next to what its rewards imply
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebtVerification {
+    pub dz_epoch: u64,
+    pub node_id: Pubkey,
+    pub solana_epoch: u64,
+    /// Amount in the ledger record, if the validator was charged
+    pub recorded_amount: Option<u64>,
+    pub recomputed_amount: u64,
+    /// Amount the debt was computed at before it was waived, if it was
+    pub waived_amount: Option<u64>,
+    /// Whether the distribution's merkle root accepts the recorded entry
+    pub proof_accepted: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Matched,
+    NotCharged,
+    Waived,
+    Mismatched(String),
+}
+
+impl DebtVerification {
+    pub fn verdict(&self) -> Verdict {
+        let Some(recorded_amount) = self.recorded_amount else {
+            return Verdict::NotCharged;
+        };
+        if self.proof_accepted == Some(false) {
+            return Verdict::Mismatched(
+                "recorded entry is not in the distribution's merkle root".to_string(),
+            );
+        }
+        if recorded_amount == 0 && self.waived_amount == Some(self.recomputed_amount) {
+            return Verdict::Waived;
+        }
+        if recorded_amount != self.recomputed_amount {
+            return Verdict::Mismatched(format!(
+                "recorded {recorded_amount} lamports, recomputed {} lamports",
+                self.recomputed_amount
+            ));
+        }
+        Verdict::Matched
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Matched => write!(f, "MATCHED"),
+            Verdict::NotCharged => write!(f, "NOT CHARGED"),
+            Verdict::Waived => write!(f, "WAIVED"),
+            Verdict::Mismatched(reason) => write!(f, "MISMATCHED ({reason})"),
+        }
+    }
+}
+
+impl fmt::Display for DebtVerification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let recorded = self
+            .recorded_amount
+            .map(|amount| format!("{amount} lamports"))
+            .unwrap_or_else(|| "none".to_string());
+        let proof = match self.proof_accepted {
+            Some(true) => "accepted",
+            Some(false) => "rejected",
+            None => "not checked",
+        };
+        writeln!(f, "DZ epoch:          {}", self.dz_epoch)?;
+        writeln!(f, "Validator:         {}", self.node_id)?;
+        writeln!(f, "Solana epoch:      {}", self.solana_epoch)?;
+        writeln!(f, "Recorded debt:     {recorded}")?;
+        writeln!(f, "Recomputed debt:   {} lamports", self.recomputed_amount)?;
+        if let Some(waived_amount) = self.waived_amount {
+            writeln!(f, "Waived debt:       {waived_amount} lamports")?;
+        }
+        writeln!(f, "Merkle proof:      {proof}")?;
+        write!(f, "Verdict:           {}", self.verdict())
+    }
+}
+
+/// Verify the debt charged to `node_id` for `dz_epoch` against public data
+pub async fn verify_validator_debt<T: ValidatorRewards>(
+    solana_debt_calculator: &T,
+    dz_epoch: u64,
+    node_id: Pubkey,
+) -> Result<DebtVerification> {
+    let solana_rpc_client = solana_debt_calculator.solana_rpc_client();
+
+    let program_config_account = solana_rpc_client
+        .get_account(&ProgramConfig::find_address().0)
+        .await
+        .context("Failed to fetch the revenue distribution program config")?;
+    let debt_accountant_key = zero_copy::checked_from_bytes_with_discriminator::<ProgramConfig>(
+        &program_config_account.data,
+    )
+    .expect("Failed to deserialize ProgramConfig account data.")
+    .0
+    .debt_accountant_key;
+
+    let distribution = transaction::fetch_distribution(solana_rpc_client, dz_epoch).await?;
+
+    let dz_epoch_bytes = dz_epoch.to_le_bytes();
+    let seed: &[&[u8]] = &[SOLANA_SEED_PREFIX, &dz_epoch_bytes];
+    let (_, record_body) = ledger::read_from_ledger_for_payer(
+        solana_debt_calculator.ledger_rpc_client(),
+        &debt_accountant_key,
+        seed,
+        solana_debt_calculator.ledger_commitment_config(),
+    )
+    .await
+    .with_context(|| format!("No validator debt record posted for DZ epoch {dz_epoch}"))?;
+    let record: ComputedSolanaValidatorDebts = borsh::from_slice(&record_body)
+        .map_err(|e| anyhow!("failed to deserialize ledger record: {e}"))?;
+
+    // The calculation charges rewards from the last Solana epoch the DZ epoch overlaps
+    let solana_epoch = record.last_solana_epoch;
+    // Records written before the commission basis was recorded were all post-commission
+    let validator_rewards = rewards::get_total_rewards(
+        solana_debt_calculator,
+        &[node_id.to_string()],
+        solana_epoch,
+        record.inflation_commission.unwrap_or_default(),
+    )
+    .await?;
+    let recomputed_amount = validator_rewards
+        .rewards
+        .first()
+        .map(|reward| ComputedSolanaValidatorDebt::from_reward(node_id, reward, &distribution))
+        .map_or(0, |debt| debt.amount);
+
+    let (recorded_amount, proof_accepted) = match record.find_debt_proof(&node_id) {
+        Some((debt, proof)) => {
+            let accepted = simulate_merkle_root_check(
+                solana_rpc_client,
+                &debt_accountant_key,
+                dz_epoch,
+                proof,
+                SolanaValidatorDebt {
+                    node_id,
+                    amount: debt.amount,
+                },
+            )
+            .await?;
+            (Some(debt.amount), Some(accepted))
+        }
+        None => (None, None),
+    };
+    let waived_amount = record.debt_waiver.as_ref().and_then(|waiver| {
+        waiver
+            .waived
+            .iter()
+            .find(|debt| debt.node_id == node_id)
+            .map(|debt| debt.amount)
+    });
+
+    Ok(DebtVerification {
+        dz_epoch,
+        node_id,
+        solana_epoch,
+        recorded_amount,
+        recomputed_amount,
+        waived_amount,
+        proof_accepted,
+    })
+}
+
+/// Simulate the program's merkle root check for `leaf` without a signer
+///
+/// The debt accountant is used as fee payer since it is known to exist; signatures are not
+/// verified during the simulation.
+async fn simulate_merkle_root_check(
+    solana_rpc_client: &RpcClient,
+    fee_payer_key: &Pubkey,
+    dz_epoch: u64,
+    proof: MerkleProof,
+    leaf: SolanaValidatorDebt,
+) -> Result<bool> {
+    let instruction = try_build_instruction(
+        &ID,
+        VerifyDistributionMerkleRootAccounts::new(DoubleZeroEpoch::new(dz_epoch)),
+        &RevenueDistributionInstructionData::VerifyDistributionMerkleRoot {
+            kind: DistributionMerkleRootKind::SolanaValidatorDebt(leaf),
+            proof,
+        },
+    )?;
+
+    let recent_blockhash = solana_rpc_client.get_latest_blockhash().await?;
+    let message = Message::try_compile(fee_payer_key, &[instruction], &[], recent_blockhash)?;
+    let transaction = VersionedTransaction {
+        signatures: vec![Signature::default()],
+        message: VersionedMessage::V0(message),
+    };
+
+    let simulation = solana_rpc_client
+        .simulate_transaction_with_config(
+            &transaction,
+            RpcSimulateTransactionConfig {
+                sig_verify: false,
+                replace_recent_blockhash: true,
+                ..Default::default()
+            },
+        )
+        .await?;
+
+    Ok(simulation.value.err.is_none())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn verification(
+        recorded_amount: Option<u64>,
+        recomputed_amount: u64,
+        proof_accepted: Option<bool>,
+    ) -> DebtVerification {
+        DebtVerification {
+            dz_epoch: 90,
+            node_id: Pubkey::new_unique(),
+            solana_epoch: 840,
+            recorded_amount,
+            recomputed_amount,
+            waived_amount: None,
+            proof_accepted,
+        }
+    }
+
+    #[test]
+    fn test_verdict_matched() {
+        assert_eq!(
+            verification(Some(707), 707, Some(true)).verdict(),
+            Verdict::Matched
+        );
+    }
+
+    #[test]
+    fn test_verdict_amount_mismatch() {
+        assert!(matches!(
+            verification(Some(707), 700, Some(true)).verdict(),
+            Verdict::Mismatched(_)
+        ));
+    }
+
+    #[test]
+    fn test_verdict_rejected_proof() {
+        assert!(matches!(
+            verification(Some(707), 707, Some(false)).verdict(),
+            Verdict::Mismatched(_)
+        ));
+    }
+
+    #[test]
+    fn test_verdict_waived() {
+        let mut waived = verification(Some(0), 707, Some(true));
+        assert!(matches!(waived.verdict(), Verdict::Mismatched(_)));
+
+        waived.waived_amount = Some(707);
+        assert_eq!(waived.verdict(), Verdict::Waived);
+    }
+
+    #[test]
+    fn test_verdict_not_charged() {
+        assert_eq!(verification(None, 707, None).verdict(), Verdict::NotCharged);
+    }
+}
@@ -0,0 +1,161 @@
+//! Mapping between vote accounts and the validator identities (node ids) operating them
+//!
+//! Debts are keyed by node identity while some reward sources, such as Jito, report by vote
+//! account. A validator may operate several vote accounts, or have rotated to a new one, so
+//! rewards are attributed through this mapping rather than by comparing keys directly.
+
+use crate::solana_debt_calculator::ValidatorRewards;
+use anyhow::{Result, anyhow};
+use backon::{ExponentialBuilder, Retryable};
+use solana_client::rpc_response::RpcVoteAccountStatus;
+use std::{collections::BTreeMap, time::Duration};
+use tabled::Tabled;
+use tracing::info;
+
+/// Vote account to identity mapping for a Solana epoch
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VoteAccountMap {
+    pub epoch: u64,
+    identities: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Tabled)]
+pub struct VoteAccountMapping {
+    pub vote_account: String,
+    pub validator_id: String,
+}
+
+impl VoteAccountMap {
+    /// Build the mapping from current and delinquent vote accounts
+    ///
+    /// Delinquent accounts are included so that a vote account a validator rotated away from
+    /// during `epoch` still attributes the rewards it earned to that validator. Vote accounts are
+    /// fetched right after the epoch boundary, when their identities match those of `epoch`.
+    pub fn new(vote_accounts: &RpcVoteAccountStatus, epoch: u64) -> Self {
+        let identities = vote_accounts
+            .current
+            .iter()
+            .chain(&vote_accounts.delinquent)
+            .map(|vote_account| {
+                (
+                    vote_account.vote_pubkey.clone(),
+                    vote_account.node_pubkey.clone(),
+                )
+            })
+            .collect();
+
+        Self { epoch, identities }
+    }
+
+    /// Identity operating `vote_account`
+    pub fn identity(&self, vote_account: &str) -> Option<&str> {
+        self.identities.get(vote_account).map(String::as_str)
+    }
+
+    /// Vote accounts operated by `validator_id`
+    pub fn vote_accounts<'a>(&'a self, validator_id: &'a str) -> impl Iterator<Item = &'a str> {
+        self.identities
+            .iter()
+            .filter(move |(_, identity)| identity.as_str() == validator_id)
+            .map(|(vote_account, _)| vote_account.as_str())
+    }
+
+    /// Mapping rows for `validator_ids`, sorted by identity then vote account
+    pub fn mappings(&self, validator_ids: &[String]) -> Vec<VoteAccountMapping> {
+        let mut mappings = validator_ids
+            .iter()
+            .flat_map(|validator_id| {
+                self.vote_accounts(validator_id)
+                    .map(|vote_account| VoteAccountMapping {
+                        vote_account: vote_account.to_string(),
+                        validator_id: validator_id.clone(),
+                    })
+            })
+            .collect::<Vec<_>>();
+        mappings.sort_by(|a, b| {
+            (&a.validator_id, &a.vote_account).cmp(&(&b.validator_id, &b.vote_account))
+        });
+        mappings.dedup();
+        mappings
+    }
+
+    pub fn len(&self) -> usize {
+        self.identities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.identities.is_empty()
+    }
+}
+
+pub async fn fetch_vote_accounts<T: ValidatorRewards + ?Sized>(
+    solana_debt_calculator: &T,
+    epoch: u64,
+) -> Result<RpcVoteAccountStatus> {
+    (|| async { solana_debt_calculator.get_vote_accounts_with_config().await })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_max_times(5)
+                .with_min_delay(Duration::from_millis(100))
+                .with_max_delay(Duration::from_secs(10))
+                .with_jitter(),
+        )
+        .notify(|err, dur: Duration| {
+            info!("get_vote_accounts_with_config call failed, retrying in {dur:?}: {err}");
+        })
+        .await
+        .map_err(|e| {
+            anyhow!(
+                "Failed to fetch get_vote_accounts_with_config for epoch {epoch} after retries: {e:#?}"
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_client::rpc_response::RpcVoteAccountInfo;
+
+    fn vote_account(vote_pubkey: &str, node_pubkey: &str) -> RpcVoteAccountInfo {
+        RpcVoteAccountInfo {
+            vote_pubkey: vote_pubkey.to_string(),
+            node_pubkey: node_pubkey.to_string(),
+            activated_stake: 4_200_000_000_000,
+            epoch_vote_account: true,
+            epoch_credits: vec![(812, 256, 128)],
+            commission: 10,
+            last_vote: 123456789,
+            root_slot: 123456700,
+        }
+    }
+
+    #[test]
+    fn test_vote_account_map() {
+        let status = RpcVoteAccountStatus {
+            current: vec![
+                vote_account("vote-a1", "validator-a"),
+                vote_account("vote-b", "validator-b"),
+            ],
+            // rotated away from during the epoch
+            delinquent: vec![vote_account("vote-a2", "validator-a")],
+        };
+
+        let map = VoteAccountMap::new(&status, 812);
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.identity("vote-a2"), Some("validator-a"));
+        assert_eq!(map.identity("vote-c"), None);
+        assert_eq!(
+            map.vote_accounts("validator-a").collect::<Vec<_>>(),
+            vec!["vote-a1", "vote-a2"]
+        );
+
+        let mappings = map.mappings(&["validator-b".to_string(), "validator-a".to_string()]);
+        assert_eq!(
+            mappings
+                .iter()
+                .map(|m| m.vote_account.as_str())
+                .collect::<Vec<_>>(),
+            vec!["vote-a1", "vote-a2", "vote-b"]
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::validator_debt::ComputedSolanaValidatorDebt;
+
+/// Debt amounts (in lamports) below which collecting costs more in
+/// transaction fees than it recovers. Zero disables a threshold.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DebtThresholds {
+    /// Validator debts below this are waived
+    pub min_validator_debt: u64,
+    /// When the debts left after per-validator waivers total less than this,
+    /// all of them are waived and the distribution is finalized without
+    /// payments
+    pub min_total_debt: u64,
+}
+
+/// Debts waived under [`DebtThresholds`], stored in the debt record
+#[derive(Debug, Clone, Default, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct DebtWaiver {
+    pub min_validator_debt: u64,
+    pub min_total_debt: u64,
+    /// Whether every debt was waived because the total fell below
+    /// `min_total_debt`
+    pub distribution_waived: bool,
+    /// Waived debts with the amounts they were computed at
+    pub waived: Vec<ComputedSolanaValidatorDebt>,
+}
+
+impl DebtWaiver {
+    pub fn total_waived(&self) -> u64 {
+        self.waived.iter().map(|debt| debt.amount).sum()
+    }
+}
+
+impl DebtThresholds {
+    pub fn is_enabled(&self) -> bool {
+        self.min_validator_debt > 0 || self.min_total_debt > 0
+    }
+
+    /// Zero out the debts falling below the thresholds, returning what was
+    /// waived, or `None` when no threshold is set
+    ///
+    /// Waived debts stay in `debts` with a zero amount so every validator
+    /// keeps its leaf in the merkle tree.
+    pub fn apply(&self, debts: &mut [ComputedSolanaValidatorDebt]) -> Option<DebtWaiver> {
+        if !self.is_enabled() {
+            return None;
+        }
+
+        let mut waiver = DebtWaiver {
+            min_validator_debt: self.min_validator_debt,
+            min_total_debt: self.min_total_debt,
+            ..Default::default()
+        };
+
+        for debt in debts.iter_mut() {
+            if debt.amount > 0 && debt.amount < self.min_validator_debt {
+                waiver.waived.push(*debt);
+                debt.amount = 0;
+            }
+        }
+
+        let remaining: u64 = debts.iter().map(|debt| debt.amount).sum();
+        if remaining > 0 && remaining < self.min_total_debt {
+            waiver.distribution_waived = true;
+            for debt in debts.iter_mut().filter(|debt| debt.amount > 0) {
+                waiver.waived.push(*debt);
+                debt.amount = 0;
+            }
+        }
+
+        Some(waiver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::pubkey::Pubkey;
+
+    fn debts(amounts: &[u64]) -> Vec<ComputedSolanaValidatorDebt> {
+        amounts
+            .iter()
+            .map(|amount| ComputedSolanaValidatorDebt {
+                node_id: Pubkey::new_unique(),
+                amount: *amount,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_apply_thresholds() {
+        let mut computed = debts(&[0, 5, 10_000, 2_000_000]);
+        assert_eq!(DebtThresholds::default().apply(&mut computed), None);
+
+        let tiny_debt = computed[1];
+        let waiver = DebtThresholds {
+            min_validator_debt: 10_000,
+            min_total_debt: 1_000_000,
+        }
+        .apply(&mut computed)
+        .unwrap();
+        assert!(!waiver.distribution_waived);
+        assert_eq!(waiver.waived, vec![tiny_debt]);
+        assert_eq!(
+            computed.iter().map(|debt| debt.amount).collect::<Vec<_>>(),
+            vec![0, 0, 10_000, 2_000_000]
+        );
+    }
+
+    #[test]
+    fn test_apply_total_threshold() {
+        let mut computed = debts(&[5, 10_000, 20_000]);
+        let waiver = DebtThresholds {
+            min_validator_debt: 10,
+            min_total_debt: 1_000_000,
+        }
+        .apply(&mut computed)
+        .unwrap();
+
+        assert!(waiver.distribution_waived);
+        assert_eq!(waiver.total_waived(), 30_005);
+        assert!(computed.iter().all(|debt| debt.amount == 0));
+    }
+}
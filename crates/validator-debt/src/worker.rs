@@ -10,12 +10,17 @@ use doublezero_solana_client_tools::{log_info, log_warn};
 use leaky_bucket::RateLimiter;
 
 use crate::{
+    fee_history::DebtFeeParameters,
+    inflation::InflationCommission,
     ledger,
+    notify::{Milestone, Notifier},
     rewards::{self, EpochRewards},
     rpc::JoinedSolanaEpochs,
+    sanity::{self, SanityCheckConfig, SanityReport},
     solana_debt_calculator::ValidatorRewards,
     transaction::Transaction,
     validator_debt::{ComputedSolanaValidatorDebt, ComputedSolanaValidatorDebts},
+    waiver::DebtThresholds,
 };
 use anyhow::{Result, bail};
 use doublezero_revenue_distribution::instruction::RevenueDistributionInstructionData::ConfigureDistributionDebt;
@@ -27,7 +32,11 @@ use solana_sdk::{clock::Clock, pubkey::Pubkey, sysvar::clock};
 use std::{collections::HashMap, env, str::FromStr};
 use tabled::{Table, Tabled, settings::Style};
 
-const SOLANA_SEED_PREFIX: &[u8; 21] = b"solana_validator_debt";
+pub(crate) const SOLANA_SEED_PREFIX: &[u8; 21] = b"solana_validator_debt";
+
+/// Seed prefix for debt recomputed after its distribution was finalized, kept
+/// apart from the canonical record
+pub(crate) const SOLANA_AUDIT_SEED_PREFIX: &[u8; 27] = b"solana_validator_debt_audit";
 
 #[derive(Debug, Default, Tabled)]
 pub struct WriteSummary {
@@ -38,6 +47,7 @@ pub struct WriteSummary {
     pub block_priority_rewards: u64,
     pub inflation_rewards: u64,
     pub jito_rewards: u64,
+    pub sanity: String,
 }
 
 fn serviceability_pubkey() -> Result<Pubkey> {
@@ -64,6 +74,13 @@ pub async fn finalize_distribution<T: ValidatorRewards>(
 
     if let Some(finalized_sig) = transaction_signature {
         println!("finalized distribution tx: {finalized_sig:?}");
+
+        Notifier::from_env()
+            .notify(Milestone::DistributionFinalized {
+                dz_epoch,
+                signature: finalized_sig.to_string(),
+            })
+            .await;
     }
     Ok(())
 }
@@ -73,6 +90,10 @@ pub async fn calculate_validator_debt<T: ValidatorRewards>(
     transaction: Transaction,
     dz_epoch: u64,
     post_to_ledger_only: bool,
+    sanity_check: SanityCheckConfig,
+    inflation_commission: InflationCommission,
+    debt_thresholds: DebtThresholds,
+    post_finalization_audit: bool,
 ) -> Result<()> {
     let fetched_dz_epoch_info = solana_debt_calculator
         .ledger_rpc_client()
@@ -91,6 +112,20 @@ pub async fn calculate_validator_debt<T: ValidatorRewards>(
         .read_distribution(dz_epoch, solana_debt_calculator.solana_rpc_client())
         .await?;
 
+    // a finalized distribution has already been paid against its canonical
+    // record, so recomputing may only write to the audit seed
+    let is_finalized = distribution.is_debt_calculation_finalized();
+    if is_finalized && !post_finalization_audit {
+        bail!(
+            "Debt calculation for DZ epoch {dz_epoch} is already finalized on Solana; rerun with --post-finalization-audit to record the recomputation separately"
+        );
+    }
+    if !is_finalized && post_finalization_audit {
+        bail!(
+            "Debt calculation for DZ epoch {dz_epoch} is not finalized; --post-finalization-audit only applies to finalized distributions"
+        );
+    }
+
     // get solana current timestamp
     let clock_account = solana_debt_calculator
         .solana_rpc_client()
@@ -159,7 +194,12 @@ pub async fn calculate_validator_debt<T: ValidatorRewards>(
 
     // create the seed
     let dz_epoch_bytes = dz_epoch.to_le_bytes();
-    let seed: &[&[u8]] = &[SOLANA_SEED_PREFIX, &dz_epoch_bytes];
+    let seed_prefix: &[u8] = if post_finalization_audit {
+        SOLANA_AUDIT_SEED_PREFIX
+    } else {
+        SOLANA_SEED_PREFIX
+    };
+    let seed: &[&[u8]] = &[seed_prefix, &dz_epoch_bytes];
 
     // this means the previous dz epoch traversed more than one solana epoch
     // if the current dz_epoch_record's solana epoch is also in the previous record's epoch
@@ -182,9 +222,11 @@ pub async fn calculate_validator_debt<T: ValidatorRewards>(
         )
         .await?;
 
-        transaction
-            .finalize_distribution(solana_debt_calculator.solana_rpc_client(), dz_epoch)
-            .await?;
+        if !post_finalization_audit {
+            transaction
+                .finalize_distribution(solana_debt_calculator.solana_rpc_client(), dz_epoch)
+                .await?;
+        }
 
         bail!("No debt to pay for dz epoch {dz_epoch}")
     };
@@ -198,41 +240,84 @@ pub async fn calculate_validator_debt<T: ValidatorRewards>(
     );
 
     // fetch rewards for validators
-    let validator_rewards = rewards::get_total_rewards(
+    let (validator_rewards, vote_account_map) = rewards::get_total_rewards_with_vote_accounts(
         solana_debt_calculator,
         validator_pubkeys.as_slice(),
         solana_epoch,
+        inflation_commission,
     )
     .await?;
 
+    if transaction.dry_run {
+        let mappings = vote_account_map.mappings(&validator_pubkeys);
+        println!(
+            "Jito tips for solana epoch {solana_epoch} attributed through {} vote accounts:\n{}",
+            mappings.len(),
+            Table::new(mappings).with(Style::psql().remove_horizontals())
+        );
+    }
+
     // gather rewards into debts for all validators
     println!("Computing solana validator debt");
-    let computed_solana_validator_debt_vec: Vec<ComputedSolanaValidatorDebt> = validator_rewards
-        .rewards
-        .iter()
-        .map(|reward| ComputedSolanaValidatorDebt {
-            node_id: Pubkey::from_str(&reward.validator_id).unwrap(),
-            amount: distribution
-                .solana_validator_fee_parameters
-                .base_block_rewards_pct
-                .mul_scalar(reward.block_base)
-                + distribution
-                    .solana_validator_fee_parameters
-                    .priority_block_rewards_pct
-                    .mul_scalar(reward.block_priority)
-                + distribution
-                    .solana_validator_fee_parameters
-                    .jito_tips_pct
-                    .mul_scalar(reward.jito)
-                + distribution
-                    .solana_validator_fee_parameters
-                    .inflation_rewards_pct
-                    .mul_scalar(reward.inflation)
-                + distribution
-                    .solana_validator_fee_parameters
-                    .fixed_sol_amount as u64,
-        })
-        .collect();
+    let mut computed_solana_validator_debt_vec: Vec<ComputedSolanaValidatorDebt> =
+        validator_rewards
+            .rewards
+            .iter()
+            .map(|reward| {
+                ComputedSolanaValidatorDebt::from_reward(
+                    Pubkey::from_str(&reward.validator_id).unwrap(),
+                    reward,
+                    &distribution,
+                )
+            })
+            .collect();
+
+    // compare each debt against its stake-weighted expectation before anything is posted
+    let fixed_sol_amount = distribution
+        .solana_validator_fee_parameters
+        .fixed_sol_amount as u64;
+    let sanity_report = check_debt_sanity(
+        solana_debt_calculator,
+        &computed_solana_validator_debt_vec,
+        fixed_sol_amount,
+        solana_epoch,
+        sanity_check.tolerance,
+    )
+    .await?;
+
+    let flagged = sanity_report.flagged();
+    if !flagged.is_empty() {
+        for (validator_id, flag) in &flagged {
+            log_warn!(
+                "Validator {validator_id} debt is outside the stake-weighted sanity band: {flag}"
+            );
+        }
+        if sanity_check.block_submission {
+            bail!(
+                "{} validators failed the stake-weighted sanity check; rerun with --force to submit anyway",
+                flagged.len()
+            );
+        }
+    }
+
+    // waive debts too small to be worth collecting, after the sanity check saw them
+    let debt_waiver = debt_thresholds.apply(&mut computed_solana_validator_debt_vec);
+    if let Some(waiver) = &debt_waiver {
+        log_info!(
+            "Waived {} debts totaling {} lamports below the debt thresholds",
+            waiver.waived.len(),
+            waiver.total_waived()
+        );
+        if waiver.distribution_waived {
+            log_info!(
+                "Total debt is below {} lamports; the distribution will be finalized without payments",
+                waiver.min_total_debt
+            );
+        }
+    }
+    let distribution_waived = debt_waiver
+        .as_ref()
+        .is_some_and(|waiver| waiver.distribution_waived);
 
     let recent_blockhash = solana_debt_calculator
         .ledger_rpc_client()
@@ -244,8 +329,28 @@ pub async fn calculate_validator_debt<T: ValidatorRewards>(
         first_solana_epoch: solana_epoch_from_first_dz_epoch_block,
         last_solana_epoch: solana_epoch_from_last_dz_epoch_block,
         debts: computed_solana_validator_debt_vec.clone(),
+        fee_parameters: Some(DebtFeeParameters::from_distribution(&distribution)),
+        inflation_commission: Some(inflation_commission),
+        debt_waiver,
     };
 
+    if post_finalization_audit {
+        // the audit record is a fresh recomputation, so it replaces any earlier
+        // audit outright (recreated if its size changed) and nothing is
+        // submitted to Solana
+        ledger::create_record_on_ledger(
+            solana_debt_calculator.ledger_rpc_client(),
+            recent_blockhash,
+            &transaction.signer,
+            &computed_solana_validator_debts,
+            solana_debt_calculator.ledger_commitment_config(),
+            seed,
+        )
+        .await?;
+        log_info!("Recorded post-finalization audit of DZ epoch {dz_epoch} debt");
+        return Ok(());
+    }
+
     // read record
     create_or_validate_ledger_record(
         solana_debt_calculator,
@@ -260,14 +365,18 @@ pub async fn calculate_validator_debt<T: ValidatorRewards>(
         bail!("Debt posted only to DoubleZero Ledger and process exited")
     }
 
-    write_transaction(
-        solana_debt_calculator.solana_rpc_client(),
-        &computed_solana_validator_debts,
-        &transaction,
-        &validator_rewards,
-        dz_epoch,
-    )
-    .await?;
+    if distribution_waived {
+        finalize_distribution(solana_debt_calculator, transaction, dz_epoch).await?;
+    } else {
+        write_transaction(
+            solana_debt_calculator.solana_rpc_client(),
+            &computed_solana_validator_debts,
+            &transaction,
+            &validator_rewards,
+            dz_epoch,
+        )
+        .await?;
+    }
 
     let debt_map: HashMap<String, u64> = computed_solana_validator_debts
         .debts
@@ -286,6 +395,7 @@ pub async fn calculate_validator_debt<T: ValidatorRewards>(
             inflation_rewards: vr.inflation,
             total_rewards: vr.total,
             total_debt: debt_map[&vr.validator_id], // this should panic if not found
+            sanity: sanity_report.flag(&vr.validator_id).to_string(),
         })
         .collect();
 
@@ -298,6 +408,41 @@ pub async fn calculate_validator_debt<T: ValidatorRewards>(
     Ok(())
 }
 
+async fn check_debt_sanity<T: ValidatorRewards>(
+    solana_debt_calculator: &T,
+    debts: &[ComputedSolanaValidatorDebt],
+    fixed_sol_amount: u64,
+    solana_epoch: u64,
+    tolerance: f64,
+) -> Result<SanityReport> {
+    // vote accounts only report the current activated stake, so the weights
+    // reflect stake now rather than during the epochs the debt covers
+    let vote_accounts = solana_debt_calculator
+        .get_vote_accounts_with_config()
+        .await?;
+    let weights = sanity::validator_weights(&vote_accounts, solana_epoch);
+
+    // the fixed charge does not scale with stake, so only the remainder is compared
+    let variable_debts: Vec<(String, u64)> = debts
+        .iter()
+        .map(|debt| {
+            (
+                debt.node_id.to_string(),
+                debt.amount.saturating_sub(fixed_sol_amount),
+            )
+        })
+        .collect();
+
+    let report = sanity::check_debts(&variable_debts, &weights, tolerance);
+    log_info!(
+        "Stake-weighted sanity check: {} of {} validators outside {tolerance}x band",
+        report.flagged().len(),
+        debts.len()
+    );
+
+    Ok(report)
+}
+
 async fn write_transaction(
     solana_rpc_client: &RpcClient,
     computed_solana_validator_debts: &ComputedSolanaValidatorDebts,
@@ -318,10 +463,11 @@ async fn write_transaction(
 
     println!("Writing total debt {total_debt} to solana for {total_validators} validators");
 
+    let merkle_root = merkle_root.unwrap();
     let debt = ConfigureDistributionDebt {
         total_validators,
         total_debt,
-        merkle_root: merkle_root.unwrap(),
+        merkle_root,
     };
 
     let submitted_distribution = transaction
@@ -334,6 +480,16 @@ async fn write_transaction(
 
     if let Some(tx) = tx_submitted_sig {
         println!("submitted distribution tx: {tx:?}");
+
+        Notifier::from_env()
+            .notify(Milestone::DebtConfigured {
+                dz_epoch,
+                total_debt,
+                total_validators,
+                merkle_root: merkle_root.to_string(),
+                signature: tx.to_string(),
+            })
+            .await;
     }
 
     Ok(())
@@ -367,6 +523,15 @@ async fn create_or_validate_ledger_record<T: ValidatorRewards>(
                 );
             }
 
+            if let Some(recorded) = deserialized_record.inflation_commission
+                && Some(recorded) != computed_solana_validator_debts.inflation_commission
+                && !transaction.force
+            {
+                bail!(
+                    "DZ Ledger record charged inflation rewards {recorded:?}; rerun with the same --inflation-commission or --force to overwrite it"
+                );
+            }
+
             if transaction.force {
                 println!(
                     "Warning: DZ Ledger record does not match the new computer solana validator debt and has been overwritten"
@@ -410,7 +575,7 @@ async fn create_or_validate_ledger_record<T: ValidatorRewards>(
     }
 }
 
-async fn fetch_validator_pubkeys(ledger_rpc_client: &RpcClient) -> Result<Vec<String>> {
+pub async fn fetch_validator_pubkeys(ledger_rpc_client: &RpcClient) -> Result<Vec<String>> {
     let account_type = AccountType::AccessPass as u8;
     let filters = vec![solana_client::rpc_filter::RpcFilterType::Memcmp(
         solana_client::rpc_filter::Memcmp::new(
@@ -528,7 +693,17 @@ mod tests {
 
         let dz_epoch = 84;
         let transaction = Transaction::new(keypair, true, false);
-        calculate_validator_debt(&fpc, transaction, dz_epoch, false).await?;
+        calculate_validator_debt(
+            &fpc,
+            transaction,
+            dz_epoch,
+            false,
+            SanityCheckConfig::default(),
+            InflationCommission::PostCommission,
+            DebtThresholds::default(),
+            false,
+        )
+        .await?;
 
         let signer = try_load_keypair(None).unwrap();
 
@@ -680,7 +855,17 @@ mod tests {
         let signer = try_load_keypair(None).unwrap();
         let transaction = Transaction::new(signer, true, false);
 
-        calculate_validator_debt(&mock_solana_debt_calculator, transaction, 45, false).await?;
+        calculate_validator_debt(
+            &mock_solana_debt_calculator,
+            transaction,
+            45,
+            false,
+            SanityCheckConfig::default(),
+            InflationCommission::PostCommission,
+            DebtThresholds::default(),
+            false,
+        )
+        .await?;
 
         Ok(())
     }